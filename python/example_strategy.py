@@ -0,0 +1,47 @@
+#!/usr/bin/env python3
+# ===============================
+# python/example_strategy.py
+# ===============================
+#
+# Contoh strategi Python untuk py_bridge (STRATEGIES=python).
+# Protokol: satu objek JSON per baris.
+#   stdin : {"seq": 1, "tick": {"symbol": "BTCUSDT", "best_bid": 10010, "best_ask": 10012, ...},
+#            "book": {"microprice": 10011.2, "imbalance": 0.3, "imbalance_k": 0.1, "levels": 5}}
+#   stdout: {"seq": 1, "signal": {"side": "buy", "px": 10012, "qty": 10}}  atau  {"seq": 1, "signal": null}
+# Selalu balas setiap tick (dengan seq yang sama) dan flush stdout. Log ke stderr.
+#
+# Mean-reversion sederhana: beli di bawah rata-rata N tick - edge, jual di atasnya + edge.
+import json
+import sys
+from collections import defaultdict, deque
+
+WINDOW = 50
+EDGE = 5  # tick
+
+mids = defaultdict(lambda: deque(maxlen=WINDOW))
+
+
+def on_tick(t):
+    w = mids[t["symbol"]]
+    w.append((t["best_bid"] + t["best_ask"]) // 2)
+    if len(w) < WINDOW:
+        return None
+    fair = sum(w) // len(w)
+    if t["best_ask"] < fair - EDGE:
+        return {"side": "buy", "px": t["best_ask"], "qty": 10}
+    if t["best_bid"] > fair + EDGE:
+        return {"side": "sell", "px": t["best_bid"], "qty": 10}
+    return None
+
+
+def main():
+    print("example_strategy started", file=sys.stderr, flush=True)
+    for line in sys.stdin:
+        req = json.loads(line)
+        sig = on_tick(req["tick"])
+        sys.stdout.write(json.dumps({"seq": req["seq"], "signal": sig}) + "\n")
+        sys.stdout.flush()
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,129 @@
+// ===============================
+// src/account.rs
+// ===============================
+//
+// Snapshot saldo akun Binance:
+// - Poll GET /api/v3/account tiap `ACCOUNT_POLL_SECS`
+// - Publish `account_balance{asset,state=free|locked}` (unit aset asli, bukan tick)
+// - Dipakai risk untuk menolak Signal yang saldonya tidak cukup (hindari reject -2010):
+//     Buy  : butuh quote asset free >= px * qty
+//     Sell : butuh base asset free  >= qty
+//
+// Di venue Binance, `outboundAccountPosition` dari user data stream memperbarui saldo di antara poll.
+//
+// Pasangan base/quote diturunkan dari suffix symbol (USDT, FDUSD, BTC, ...).
+// Sebelum snapshot pertama tiba, check tidak memblokir apa pun.
+//
+use ahash::AHashMap as HashMap;
+use std::sync::{Arc, RwLock};
+#[cfg(feature = "binance")]
+use tokio::time::{interval, Duration, MissedTickBehavior};
+#[cfg(feature = "binance")]
+use tracing::{info, warn};
+
+use crate::domain::Side;
+#[cfg(feature = "binance")]
+use crate::exchange_http::{endpoints, ExchangeHttp};
+#[cfg(feature = "binance")]
+use crate::metrics::ACCOUNT_BALANCE;
+
+/// Suffix quote asset yang dikenali (urutan: yang lebih panjang dulu)
+const QUOTE_ASSETS: &[&str] = &["FDUSD", "USDT", "USDC", "BUSD", "TUSD", "TRY", "EUR", "BTC", "ETH", "BNB"];
+
+/// Pisah symbol Binance menjadi (base, quote)
+pub fn split_symbol(symbol: &str) -> Option<(&str, &str)> {
+    QUOTE_ASSETS
+        .iter()
+        .find(|q| symbol.len() > q.len() && symbol.ends_with(*q))
+        .map(|q| symbol.split_at(symbol.len() - q.len()))
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Balance {
+    pub free: f64,
+    #[cfg(feature = "binance")]
+    pub locked: f64,
+}
+
+/// Handle saldo (diisi task polling, dibaca risk)
+#[derive(Clone, Default)]
+pub struct Balances {
+    inner: Arc<RwLock<Option<HashMap<String, Balance>>>>,
+}
+
+impl Balances {
+    #[cfg(feature = "binance")]
+    fn replace(&self, snap: HashMap<String, Balance>) {
+        *self.inner.write().unwrap() = Some(snap);
+    }
+
+    #[cfg(feature = "binance")]
+    /// Update saldo sebagian dari user data stream (`outboundAccountPosition`: hanya aset yang berubah).
+    /// Sebelum snapshot polling pertama hanya gauge yang diisi (saldo parsial tidak boleh memblokir cek).
+    pub fn apply_updates(&self, updates: &[(String, Balance)]) {
+        for (asset, bal) in updates {
+            ACCOUNT_BALANCE.with_label_values(&[asset, "free"]).set(bal.free);
+            ACCOUNT_BALANCE.with_label_values(&[asset, "locked"]).set(bal.locked);
+        }
+        if let Some(snap) = self.inner.write().unwrap().as_mut() {
+            snap.extend(updates.iter().cloned());
+        }
+    }
+
+    /// Cek saldo untuk order (`qty` dalam lot); Err berisi (asset, butuh, tersedia)
+    pub fn check(&self, symbol: &str, side: Side, px: i64, qty: i64) -> Result<(), (String, f64, f64)> {
+        let g = self.inner.read().unwrap();
+        let Some(bal) = g.as_ref() else { return Ok(()) };
+        let Some((base, quote)) = split_symbol(symbol) else { return Ok(()) };
+        let base_qty = crate::sizing::base_qty(symbol, qty);
+        let (asset, need) = match side {
+            // skala harga domain 2 desimal (PoC, sama dengan gateway_binance)
+            Side::Buy => (quote, px as f64 / 100.0 * base_qty),
+            Side::Sell => (base, base_qty),
+        };
+        let free = bal.get(asset).map(|b| b.free).unwrap_or(0.0);
+        if free + 1e-12 < need {
+            return Err((asset.to_string(), need, free));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "binance")]
+fn str_f64(v: &serde_json::Value, key: &str) -> f64 {
+    v.get(key).and_then(|x| x.as_str()).and_then(|s| s.parse::<f64>().ok()).unwrap_or(0.0)
+}
+
+/// Task polling saldo
+#[cfg(feature = "binance")]
+pub async fn run(http: ExchangeHttp, interval_secs: u64, balances: Balances) {
+    info!(interval_secs, "account polling: started");
+    let mut tick = interval(Duration::from_secs(interval_secs.max(1)));
+    tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        tick.tick().await;
+        let v = match http.send(&endpoints::account(), &[("omitZeroBalances", "true".to_string())]).await {
+            Ok(v) => v,
+            Err(e) => {
+                warn!(err = %e, "account polling: GET /api/v3/account failed");
+                continue;
+            }
+        };
+        let mut snap: HashMap<String, Balance> = HashMap::new();
+        for b in v.get("balances").and_then(|x| x.as_array()).into_iter().flatten() {
+            let Some(asset) = b.get("asset").and_then(|x| x.as_str()) else { continue };
+            let bal = Balance { free: str_f64(b, "free"), locked: str_f64(b, "locked") };
+            ACCOUNT_BALANCE.with_label_values(&[asset, "free"]).set(bal.free);
+            ACCOUNT_BALANCE.with_label_values(&[asset, "locked"]).set(bal.locked);
+            snap.insert(asset.to_string(), bal);
+        }
+        // aset yang hilang dari snapshot (saldo jadi nol) -> gauge 0
+        if let Some(prev) = balances.inner.read().unwrap().as_ref() {
+            for asset in prev.keys().filter(|a| !snap.contains_key(*a)) {
+                ACCOUNT_BALANCE.with_label_values(&[asset, "free"]).set(0.0);
+                ACCOUNT_BALANCE.with_label_values(&[asset, "locked"]).set(0.0);
+            }
+        }
+        balances.replace(snap);
+    }
+}
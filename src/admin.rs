@@ -0,0 +1,168 @@
+// ===============================
+// src/admin.rs
+// ===============================
+//
+// Admin API (di-serve oleh server metrics yang sama, path `/admin/...`).
+// Handler bersifat sinkron karena server metrics jalan di OS thread sendiri.
+//
+// Endpoint:
+// - GET /admin/blotter?symbol=BTCUSDT&strategy=<name>&since=<ts_ns>&format=json|csv
+// - GET /admin/symbols   (cache exchangeInfo: status & filter per symbol)
+// - POST /admin/cancel_all              (panic button: batalkan semua order terbuka di semua venue)
+// - GET /admin/kill | POST /admin/kill  (status / engage kill switch: blok order baru + cancel-all)
+// - POST /admin/kill/release            (buka lagi trading)
+// - GET /admin/portfolio_stop           (equity, puncak sesi, drawdown, ambang, status stop)
+// - POST /admin/portfolio_stop/rearm    (buka kunci trading setelah portfolio stop; puncak baru)
+// - GET /admin/strategies               (scoreboard performa per strategi)
+// - POST /admin/strategies/enable?name=<strategy>  (aktifkan lagi strategi yang di-auto-disable)
+// - GET /admin/multileg                 (paket multi-leg terbuka + selesai: fill per leg, imbalance)
+// - POST /admin/multileg                (body JSON MultiLegOrder -> risk; px ×100, qty dalam lot)
+// - GET /admin/config                   (konfigurasi efektif saat start, rahasia disamarkan; lihat effective_config.rs)
+//
+use ahash::AHashMap as HashMap;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+use crate::blotter::{Blotter, Fill};
+use crate::dashboard::DashState;
+use crate::domain::MultiLegOrder;
+use crate::kill_switch::KillSwitch;
+use crate::multileg::LegBook;
+use crate::portfolio_stop::PortfolioStop;
+use crate::scoreboard::Scoreboard;
+use crate::symbol_registry::SymbolRegistry;
+use crate::webhook::Webhook;
+
+/// State yang dibagikan ke admin API (+ dashboard)
+#[derive(Clone)]
+pub struct AdminState {
+    pub blotter: Blotter,
+    pub dashboard: DashState,
+    pub symbols: SymbolRegistry,
+    pub kill: KillSwitch,
+    pub portfolio_stop: PortfolioStop,
+    pub scoreboard: Scoreboard,
+    pub webhook: Option<Webhook>, // POST /webhook/tradingview (WEBHOOK_SECRET)
+    pub legs: LegBook,
+    pub mleg_tx: mpsc::Sender<MultiLegOrder>, // POST /admin/multileg -> risk
+    pub config: Arc<String>,                  // GET /admin/config (JSON, dibuat sekali saat start)
+}
+
+/// Respon sederhana: (status line, content-type, body)
+pub struct Response {
+    pub status: &'static str,
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn json(body: String) -> Self {
+        Self { status: "200 OK", content_type: "application/json", body: body.into_bytes() }
+    }
+    pub fn text(status: &'static str, body: &str) -> Self {
+        Self { status, content_type: "text/plain; charset=utf-8", body: body.as_bytes().to_vec() }
+    }
+}
+
+/// Parse query string `a=1&b=2` (URL-decoded)
+pub fn parse_query(q: &str) -> HashMap<String, String> {
+    q.split('&')
+        .filter(|kv| !kv.is_empty())
+        .map(|kv| {
+            let (k, v) = kv.split_once('=').unwrap_or((kv, ""));
+            let dec = |s: &str| urlencoding::decode(s).map(|c| c.into_owned()).unwrap_or_else(|_| s.to_string());
+            (dec(k), dec(v))
+        })
+        .collect()
+}
+
+pub fn handle(state: &AdminState, method: &str, path: &str, query: &str, body: &[u8]) -> Response {
+    let params = parse_query(query);
+    match (method, path) {
+        ("GET", "/admin/blotter") => blotter(state, &params),
+        ("GET", "/admin/symbols") => match serde_json::to_string(&state.symbols.snapshot()) {
+            Ok(body) => Response::json(body),
+            Err(e) => Response::text("500 Internal Server Error", &format!("{e}\n")),
+        },
+        ("POST", "/admin/cancel_all") => {
+            let venues = state.kill.cancel_all("admin:cancel_all");
+            Response::json(format!("{{\"venues\":{venues}}}"))
+        }
+        ("GET", "/admin/kill") => Response::json(format!("{{\"engaged\":{}}}", state.kill.engaged())),
+        ("POST", "/admin/kill") => {
+            let venues = state.kill.engage("kill:admin");
+            Response::json(format!("{{\"engaged\":true,\"venues\":{venues}}}"))
+        }
+        ("POST", "/admin/kill/release") => {
+            state.kill.release();
+            Response::json("{\"engaged\":false}".to_string())
+        }
+        ("GET", "/admin/portfolio_stop") => match serde_json::to_string(&state.portfolio_stop.status()) {
+            Ok(body) => Response::json(body),
+            Err(e) => Response::text("500 Internal Server Error", &format!("{e}\n")),
+        },
+        ("POST", "/admin/portfolio_stop/rearm") => match serde_json::to_string(&state.portfolio_stop.rearm()) {
+            Ok(body) => Response::json(body),
+            Err(e) => Response::text("500 Internal Server Error", &format!("{e}\n")),
+        },
+        ("GET", "/admin/multileg") => match serde_json::to_string(&state.legs.snapshot()) {
+            Ok(body) => Response::json(body),
+            Err(e) => Response::text("500 Internal Server Error", &format!("{e}\n")),
+        },
+        ("POST", "/admin/multileg") => submit_multileg(state, body),
+        ("GET", "/admin/config") => Response::json(state.config.to_string()),
+        ("GET", "/admin/strategies") => match serde_json::to_string(&state.scoreboard.snapshot()) {
+            Ok(body) => Response::json(body),
+            Err(e) => Response::text("500 Internal Server Error", &format!("{e}\n")),
+        },
+        ("POST", "/admin/strategies/enable") => match params.get("name") {
+            Some(name) if state.scoreboard.enable(name) => Response::json(format!("{{\"strategy\":\"{name}\",\"disabled\":false}}")),
+            Some(_) => Response::text("404 Not Found", "unknown strategy\n"),
+            None => Response::text("400 Bad Request", "missing name\n"),
+        },
+        _ => Response::text("404 Not Found", "not found\n"),
+    }
+}
+
+/// POST /admin/multileg: paket dikirim ke risk (hasil cek risk lihat GET /admin/multileg / log)
+fn submit_multileg(state: &AdminState, body: &[u8]) -> Response {
+    let mut m: MultiLegOrder = match serde_json::from_slice(body) {
+        Ok(m) => m,
+        Err(e) => return Response::text("400 Bad Request", &format!("invalid multi-leg order: {e}\n")),
+    };
+    if m.id.is_empty() {
+        m.id = crate::order_ids::next("M", crate::clock::now_ns());
+    }
+    let id = m.id.clone();
+    match state.mleg_tx.try_send(m) {
+        Ok(()) => Response::json(format!("{{\"queued\":true,\"id\":{}}}", serde_json::Value::String(id))),
+        Err(_) => Response::text("503 Service Unavailable", "multi-leg queue full\n"),
+    }
+}
+
+fn blotter(state: &AdminState, params: &HashMap<String, String>) -> Response {
+    let symbol = params.get("symbol").map(|s| s.as_str()).filter(|s| !s.is_empty());
+    let since = match params.get("since").filter(|s| !s.is_empty()) {
+        Some(s) => match s.parse::<i128>() {
+            Ok(v) => Some(v),
+            Err(_) => return Response::text("400 Bad Request", "since must be ts_ns (integer)\n"),
+        },
+        None => None,
+    };
+    let strategy = params.get("strategy").map(|s| s.as_str()).filter(|s| !s.is_empty());
+    let fills = state.blotter.query(symbol, strategy, since);
+
+    if params.get("format").map(|s| s.as_str()) == Some("csv") {
+        let mut out = String::from(Fill::CSV_HEADER);
+        out.push('\n');
+        for f in &fills {
+            out.push_str(&f.to_csv_row());
+            out.push('\n');
+        }
+        return Response { status: "200 OK", content_type: "text/csv; charset=utf-8", body: out.into_bytes() };
+    }
+    match serde_json::to_string(&fills) {
+        Ok(s) => Response::json(s),
+        Err(e) => Response::text("500 Internal Server Error", &format!("{e}\n")),
+    }
+}
@@ -0,0 +1,392 @@
+// ===============================
+// src/alerts.rs
+// ===============================
+//
+// Notifikasi event kritis frekuensi rendah, untuk user yang tidak memakai integrasi chat.
+// Channel: email SMTP (STARTTLS / TLS implisit / plain untuk relay lokal).
+//
+// Event (kunci template / ALERT_EMAIL_KINDS):
+//   kill_switch   : kill switch di-engage (admin / shutdown)           vars: reason, venues
+//   recon_break   : break rekonsiliasi baru vs exchange               vars: symbol, cl_id, kind
+//   daily_report  : ringkasan harian (DAILY_REPORT_HOUR_UTC)          vars: date, fills, volume, fees, strategies
+//   overtrading   : strategi di-mute karena burst order searah         vars: strategy, symbol, side, orders, window_ms, mute_secs
+//   position_drift: diff-positions menemukan drift di atas ambang       vars: breaches, details
+//   portfolio_stop: drawdown portofolio melewati ambang, flatten       vars: equity, high, drawdown, limit, venues
+//   metric_alarm  : laju ticks/signals/rejects menyimpang dari baseline vars: metric, direction, rate, baseline, deviation_pct, window_secs
+// Variabel umum di semua template: {kind}, {host}, {time}.
+//
+// Template: default bawaan, bisa di-override per event lewat ALERT_TEMPLATES_FILE (JSON)
+//   {"kill_switch": {"subject": "[dma] KILL {reason}", "body": "Kill switch engaged: {reason}"}}
+//
+// Butuh fitur cargo `alerts` (lettre + rustls, default aktif); tanpa fitur ini handle selalu no-op.
+//
+// Rate limit: event sejenis paling sering sekali per ALERT_MIN_INTERVAL_SECS (daily_report tidak dibatasi);
+// event yang tertahan dihitung dan disebut di email berikutnya.
+//
+// ENV:
+//   SMTP_HOST=smtp.example.com   (kosong = email nonaktif)
+//   SMTP_PORT=587
+//   SMTP_TLS=starttls|tls|none   (default starttls; tls = implicit TLS, biasanya port 465)
+//   SMTP_USER= / SMTP_PASS=
+//   SMTP_FROM=bot@example.com
+//   SMTP_TO=ops@example.com,me@example.com
+//   ALERT_EMAIL_KINDS=kill_switch,recon_break,daily_report,overtrading,position_drift,portfolio_stop,metric_alarm
+//   ALERT_MIN_INTERVAL_SECS=300
+//   ALERT_TEMPLATES_FILE=alert_templates.json
+//   DAILY_REPORT_HOUR_UTC=0      (-1 = tanpa laporan harian)
+//
+use ahash::AHashMap as HashMap;
+use chrono::{Duration as ChronoDuration, Timelike, Utc};
+#[cfg(feature = "alerts")]
+use lettre::message::{header::ContentType, Mailbox};
+#[cfg(feature = "alerts")]
+use lettre::transport::smtp::authentication::Credentials;
+#[cfg(feature = "alerts")]
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+#[cfg(feature = "alerts")]
+use serde::Deserialize;
+#[cfg(feature = "alerts")]
+use std::time::Instant;
+use std::time::Duration;
+use tokio::sync::mpsc;
+#[cfg(feature = "alerts")]
+use tracing::{error, info};
+use tracing::warn;
+
+use crate::blotter::Blotter;
+#[cfg(feature = "alerts")]
+use crate::metrics::ALERTS_SENT;
+use crate::scoreboard::Scoreboard;
+use crate::sizing;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    KillSwitch,
+    ReconBreak,
+    DailyReport,
+    Overtrading,
+    PositionDrift,
+    PortfolioStop,
+    MetricAlarm,
+}
+
+impl AlertKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertKind::KillSwitch => "kill_switch",
+            AlertKind::ReconBreak => "recon_break",
+            AlertKind::DailyReport => "daily_report",
+            AlertKind::Overtrading => "overtrading",
+            AlertKind::PositionDrift => "position_drift",
+            AlertKind::PortfolioStop => "portfolio_stop",
+            AlertKind::MetricAlarm => "metric_alarm",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s.trim() {
+            "kill_switch" => Some(AlertKind::KillSwitch),
+            "recon_break" => Some(AlertKind::ReconBreak),
+            "daily_report" => Some(AlertKind::DailyReport),
+            "overtrading" => Some(AlertKind::Overtrading),
+            "position_drift" => Some(AlertKind::PositionDrift),
+            "portfolio_stop" => Some(AlertKind::PortfolioStop),
+            "metric_alarm" => Some(AlertKind::MetricAlarm),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "alerts")]
+    fn default_template(&self) -> Template {
+        let (subject, body) = match self {
+            AlertKind::KillSwitch => (
+                "[dma_bot] KILL SWITCH engaged ({reason})",
+                "Kill switch engaged on {host} at {time}.\nReason: {reason}\nCancel-all sent to {venues} venue(s).\n\nRelease with POST /admin/kill/release.",
+            ),
+            AlertKind::ReconBreak => (
+                "[dma_bot] reconciliation break {kind} {symbol}",
+                "Reconciliation break on {host} at {time}.\nSymbol: {symbol}\nOrder: {cl_id}\nKind: {kind}",
+            ),
+            AlertKind::DailyReport => (
+                "[dma_bot] daily report {date}",
+                "Daily report for {date} ({host}).\n\nFills: {fills}\nVolume (quote): {volume}\nFees (quote): {fees}\n\nStrategies:\n{strategies}",
+            ),
+            AlertKind::Overtrading => (
+                "[dma_bot] overtrading: {strategy} muted on {symbol}",
+                "Overtrading guard on {host} at {time}.\nStrategy {strategy} sent {orders} {side} orders for {symbol} within {window_ms} ms.\nIts signals for {symbol} are rejected for {mute_secs} s.",
+            ),
+            AlertKind::PositionDrift => (
+                "[dma_bot] position drift: {breaches} breach(es)",
+                "Position diff on {host} at {time} found {breaches} drift(s) above threshold (lots):\n\n{details}",
+            ),
+            AlertKind::PortfolioStop => (
+                "[dma_bot] PORTFOLIO STOP: drawdown {drawdown} USD",
+                "Portfolio stop on {host} at {time}.\nEquity {equity} USD, session high {high} USD, drawdown {drawdown} USD (limit {limit}).\nCancel-all sent to {venues} venue(s); all positions are being flattened.\n\nTrading stays locked until POST /admin/portfolio_stop/rearm.",
+            ),
+            AlertKind::MetricAlarm => (
+                "[dma_bot] metric alarm: {metric} {direction} ({deviation_pct}%)",
+                "Metric alarm on {host} at {time}.\n{metric} rate {rate}/s vs rolling baseline {baseline}/s ({deviation_pct}%), sustained for {window_secs} s.\n\nCheck the feed / strategies before it shows up in PnL.",
+            ),
+        };
+        Template { subject: subject.to_string(), body: body.to_string() }
+    }
+}
+
+#[cfg(feature = "alerts")]
+#[derive(Debug, Clone, Deserialize)]
+struct Template {
+    subject: String,
+    body: String,
+}
+
+#[cfg(feature = "alerts")]
+impl Template {
+    fn render(&self, vars: &HashMap<&str, String>) -> (String, String) {
+        let fill = |s: &str| vars.iter().fold(s.to_string(), |acc, (k, v)| acc.replace(&format!("{{{k}}}"), v));
+        (fill(&self.subject), fill(&self.body))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpTls {
+    StartTls,
+    Tls,
+    None,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AlertCfg {
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub smtp_tls: SmtpTls,
+    pub smtp_user: String,
+    #[serde(serialize_with = "crate::effective_config::secret")]
+    pub smtp_pass: String,
+    pub from: String,
+    pub to: Vec<String>,
+    pub kinds: Vec<AlertKind>,
+    pub min_interval: Duration,
+    pub templates_file: Option<String>,
+    pub daily_report_hour: Option<u32>,
+}
+
+impl AlertCfg {
+    pub fn from_env() -> Self {
+        let var = |k: &str| std::env::var(k).unwrap_or_default();
+        let smtp_tls = match var("SMTP_TLS").to_lowercase().as_str() {
+            "tls" | "ssl" => SmtpTls::Tls,
+            "none" | "plain" => SmtpTls::None,
+            _ => SmtpTls::StartTls,
+        };
+        let kinds = std::env::var("ALERT_EMAIL_KINDS")
+            .map(|s| s.split(',').filter_map(AlertKind::parse).collect())
+            .unwrap_or_else(|_| {
+                vec![AlertKind::KillSwitch, AlertKind::ReconBreak, AlertKind::DailyReport, AlertKind::Overtrading, AlertKind::PositionDrift, AlertKind::PortfolioStop, AlertKind::MetricAlarm]
+            });
+        let hour: i64 = var("DAILY_REPORT_HOUR_UTC").parse().unwrap_or(0);
+        Self {
+            smtp_host: var("SMTP_HOST"),
+            smtp_port: var("SMTP_PORT").parse().unwrap_or(if smtp_tls == SmtpTls::Tls { 465 } else { 587 }),
+            smtp_tls,
+            smtp_user: var("SMTP_USER"),
+            smtp_pass: var("SMTP_PASS"),
+            from: var("SMTP_FROM"),
+            to: var("SMTP_TO").split(',').map(str::trim).filter(|s| !s.is_empty()).map(String::from).collect(),
+            kinds,
+            min_interval: Duration::from_secs(var("ALERT_MIN_INTERVAL_SECS").parse().unwrap_or(300)),
+            templates_file: std::env::var("ALERT_TEMPLATES_FILE").ok().filter(|s| !s.is_empty()),
+            daily_report_hour: (0..24).contains(&hour).then_some(hour as u32),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        !self.smtp_host.is_empty() && !self.from.is_empty() && !self.to.is_empty()
+    }
+}
+
+#[cfg_attr(not(feature = "alerts"), allow(dead_code))]
+struct Alert {
+    kind: AlertKind,
+    vars: HashMap<&'static str, String>,
+}
+
+/// Handle notifikasi (clone murah; no-op jika email nonaktif). Aman dipanggil dari thread non-async.
+#[derive(Clone, Default)]
+pub struct Alerts {
+    tx: Option<mpsc::Sender<Alert>>,
+}
+
+impl Alerts {
+    pub fn notify(&self, kind: AlertKind, vars: &[(&'static str, String)]) {
+        let Some(tx) = &self.tx else { return };
+        let alert = Alert { kind, vars: vars.iter().cloned().collect() };
+        if tx.try_send(alert).is_err() {
+            warn!(kind = kind.as_str(), "alert queue full, dropped");
+        }
+    }
+}
+
+#[cfg(feature = "alerts")]
+fn transport(cfg: &AlertCfg) -> Result<AsyncSmtpTransport<Tokio1Executor>, lettre::transport::smtp::Error> {
+    let builder = match cfg.smtp_tls {
+        SmtpTls::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&cfg.smtp_host)?,
+        SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&cfg.smtp_host)?,
+        SmtpTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&cfg.smtp_host),
+    };
+    let builder = builder.port(cfg.smtp_port).timeout(Some(Duration::from_secs(20)));
+    let builder = if cfg.smtp_user.is_empty() {
+        builder
+    } else {
+        builder.credentials(Credentials::new(cfg.smtp_user.clone(), cfg.smtp_pass.clone()))
+    };
+    Ok(builder.build())
+}
+
+#[cfg(feature = "alerts")]
+fn load_templates(cfg: &AlertCfg) -> HashMap<AlertKind, Template> {
+    let mut out: HashMap<AlertKind, Template> = [AlertKind::KillSwitch, AlertKind::ReconBreak, AlertKind::DailyReport, AlertKind::Overtrading, AlertKind::PositionDrift, AlertKind::PortfolioStop, AlertKind::MetricAlarm]
+        .into_iter()
+        .map(|k| (k, k.default_template()))
+        .collect();
+    let Some(path) = &cfg.templates_file else { return out };
+    let parsed = std::fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|s| serde_json::from_str::<std::collections::HashMap<String, Template>>(&s).map_err(|e| e.to_string()));
+    match parsed {
+        Ok(map) => {
+            for (k, t) in map {
+                match AlertKind::parse(&k) {
+                    Some(kind) => {
+                        out.insert(kind, t);
+                    }
+                    None => warn!(%path, key = %k, "alert templates: unknown event"),
+                }
+            }
+        }
+        Err(e) => error!(%path, %e, "alert templates: load failed, using defaults"),
+    }
+    out
+}
+
+/// Buat handle + task pengirim; task None jika email nonaktif
+#[cfg(feature = "alerts")]
+pub fn start(cfg: AlertCfg) -> (Alerts, Option<impl std::future::Future<Output = ()>>) {
+    if !cfg.enabled() {
+        return (Alerts::default(), None);
+    }
+    let (tx, rx) = mpsc::channel(256);
+    (Alerts { tx: Some(tx) }, Some(run(cfg, rx)))
+}
+
+/// Build tanpa fitur `alerts` (tanpa lettre/SMTP): handle selalu no-op
+#[cfg(not(feature = "alerts"))]
+pub fn start(cfg: AlertCfg) -> (Alerts, Option<std::future::Ready<()>>) {
+    if cfg.enabled() {
+        warn!("SMTP_* set but binary built without `--features alerts`; email disabled");
+    }
+    (Alerts::default(), None)
+}
+
+#[cfg(feature = "alerts")]
+async fn run(cfg: AlertCfg, mut rx: mpsc::Receiver<Alert>) {
+    let mailer = match transport(&cfg) {
+        Ok(t) => t,
+        Err(e) => {
+            error!(host = %cfg.smtp_host, ?e, "alerts: SMTP transport setup failed, email disabled");
+            return;
+        }
+    };
+    let templates = load_templates(&cfg);
+    let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "dma_bot_rust".to_string());
+    info!(host = %cfg.smtp_host, port = cfg.smtp_port, tls = ?cfg.smtp_tls, to = ?cfg.to, "alerts: email enabled");
+
+    let mut last_sent: HashMap<AlertKind, Instant> = HashMap::new();
+    let mut suppressed: HashMap<AlertKind, u64> = HashMap::new();
+    while let Some(mut alert) = rx.recv().await {
+        if !cfg.kinds.contains(&alert.kind) {
+            continue;
+        }
+        if alert.kind != AlertKind::DailyReport {
+            if last_sent.get(&alert.kind).is_some_and(|t| t.elapsed() < cfg.min_interval) {
+                *suppressed.entry(alert.kind).or_default() += 1;
+                ALERTS_SENT.with_label_values(&[alert.kind.as_str(), "suppressed"]).inc();
+                continue;
+            }
+            last_sent.insert(alert.kind, Instant::now());
+        }
+        alert.vars.insert("kind", alert.kind.as_str().to_string());
+        alert.vars.insert("host", host.clone());
+        alert.vars.insert("time", Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string());
+        let (subject, mut body) = templates[&alert.kind].render(&alert.vars);
+        if let Some(n) = suppressed.remove(&alert.kind).filter(|n| *n > 0) {
+            body.push_str(&format!("\n\n({n} similar alert(s) suppressed since the previous email)"));
+        }
+        let result = match build_message(&cfg, &subject, body) {
+            Ok(msg) => mailer.send(msg).await.map(|_| ()).map_err(|e| e.to_string()),
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => {
+                ALERTS_SENT.with_label_values(&[alert.kind.as_str(), "sent"]).inc();
+                info!(kind = alert.kind.as_str(), %subject, "alert email sent");
+            }
+            Err(e) => {
+                ALERTS_SENT.with_label_values(&[alert.kind.as_str(), "failed"]).inc();
+                error!(kind = alert.kind.as_str(), %e, "alert email failed");
+            }
+        }
+    }
+}
+
+#[cfg(feature = "alerts")]
+fn build_message(cfg: &AlertCfg, subject: &str, body: String) -> Result<Message, String> {
+    let from: Mailbox = cfg.from.parse().map_err(|e| format!("SMTP_FROM: {e}"))?;
+    let mut builder = Message::builder().from(from).subject(subject).header(ContentType::TEXT_PLAIN);
+    for to in &cfg.to {
+        builder = builder.to(to.parse().map_err(|e| format!("SMTP_TO {to}: {e}"))?);
+    }
+    builder.body(body).map_err(|e| e.to_string())
+}
+
+/// Task laporan harian: tiap DAILY_REPORT_HOUR_UTC, ringkas fill 24 jam terakhir + scoreboard strategi
+pub async fn run_daily_report(alerts: Alerts, hour: u32, blotter: Blotter, board: Scoreboard) {
+    loop {
+        let now = Utc::now();
+        let mut next = now.date_naive().and_hms_opt(hour, 0, 0).unwrap_or_default().and_utc();
+        if next <= now {
+            next += ChronoDuration::days(1);
+        }
+        let wait = (next - now).to_std().unwrap_or(Duration::from_secs(3600));
+        tokio::time::sleep(wait).await;
+
+        let since = (Utc::now() - ChronoDuration::days(1)).timestamp_nanos_opt().unwrap_or(0) as i128;
+        let fills = blotter.query(None, None, Some(since));
+        let volume: f64 = fills.iter().map(|f| sizing::base_qty(&f.symbol, f.qty) * f.px as f64 / 100.0).sum();
+        let fees: f64 = fills.iter().map(|f| f.fee as f64 / 100.0 * sizing::lot_size(&f.symbol)).sum();
+        let strategies: String = board
+            .snapshot()
+            .iter()
+            .map(|(name, s)| {
+                let state = if s.disabled { " DISABLED" } else { "" };
+                format!("  {name}: equity {:.2}, window pnl {:.2}, hit rate {:.0}%, drawdown {:.2}{state}\n", s.equity, s.window_pnl, s.hit_rate * 100.0, s.drawdown)
+            })
+            .collect();
+        let date = (Utc::now() - ChronoDuration::hours(1)).format("%Y-%m-%d").to_string();
+        alerts.notify(
+            AlertKind::DailyReport,
+            &[
+                ("date", date),
+                ("fills", fills.len().to_string()),
+                ("volume", format!("{volume:.2}")),
+                ("fees", format!("{fees:.2}")),
+                ("strategies", if strategies.is_empty() { "  (none)\n".to_string() } else { strategies }),
+            ],
+        );
+        // hindari kirim dua kali di jam yang sama
+        while Utc::now().hour() == hour {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+        }
+    }
+}
@@ -0,0 +1,128 @@
+// ===============================
+// src/allocator.rs
+// ===============================
+//
+// Capital allocator: bagi total notional (quote) ke strategi aktif, lalu skalakan ukuran
+// order tiap strategi lewat sizing (`sizing::strategy_scale`, dipakai risk saat sizing Signal).
+//
+// Bobot:
+// - static      : ALLOC_WEIGHTS (strategi tanpa entri = 1)
+// - performance : risk-adjusted dari scoreboard = PnL window / (drawdown + 1), negatif = 0,
+//                 dikalikan ALLOC_WEIGHTS; tiap strategi aktif dapat minimal ALLOC_MIN_SHARE
+//                 (belum ada performa positif sama sekali -> jatuh ke bobot static)
+// Strategi yang di-disable scoreboard tidak dapat alokasi (scale 0).
+//
+// Alokasi_s = ALLOC_TOTAL_NOTIONAL × bobot_s / Σbobot
+// Scale_s   = alokasi_s / (ALLOC_TOTAL_NOTIONAL / N aktif)   (1.0 = bagian rata)
+// Ukuran per trade (STRATEGY_QUOTE_NOTIONAL / ORDER_QUOTE_NOTIONAL / qty strategi) dikali scale_s.
+//
+// ENV:
+//   ALLOC_TOTAL_NOTIONAL=0        (quote; 0 = allocator nonaktif, scale selalu 1)
+//   ALLOC_MODE=static|performance (default static)
+//   ALLOC_WEIGHTS=mean_reversion:2,ma_crossover:1
+//   ALLOC_MIN_SHARE=0.05          (porsi minimum per strategi aktif, mode performance)
+//   ALLOC_REBALANCE_SECS=60
+//
+use ahash::AHashMap as HashMap;
+use std::time::Duration;
+use tracing::info;
+
+use crate::metrics::{STRATEGY_ALLOCATION, STRATEGY_SIZE_SCALE};
+use crate::scoreboard::Scoreboard;
+use crate::sizing;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AllocMode {
+    Static,
+    Performance,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AllocCfg {
+    pub total_notional: f64,
+    pub mode: AllocMode,
+    pub weights: HashMap<String, f64>,
+    pub min_share: f64,
+    pub rebalance_secs: u64,
+}
+
+impl AllocCfg {
+    pub fn from_env() -> Self {
+        let num = |k: &str, d: f64| std::env::var(k).ok().and_then(|s| s.parse().ok()).unwrap_or(d);
+        let mode = match std::env::var("ALLOC_MODE").unwrap_or_default().to_lowercase().as_str() {
+            "performance" | "perf" => AllocMode::Performance,
+            _ => AllocMode::Static,
+        };
+        Self {
+            total_notional: num("ALLOC_TOTAL_NOTIONAL", 0.0),
+            mode,
+            weights: sizing::parse_map(&std::env::var("ALLOC_WEIGHTS").unwrap_or_default()),
+            min_share: num("ALLOC_MIN_SHARE", 0.05).clamp(0.0, 1.0),
+            rebalance_secs: num("ALLOC_REBALANCE_SECS", 60.0).max(1.0) as u64,
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.total_notional > 0.0
+    }
+
+    fn static_weight(&self, strategy: &str) -> f64 {
+        self.weights.get(strategy).copied().unwrap_or(1.0)
+    }
+}
+
+/// Bobot ternormalisasi (Σ = 1) untuk strategi aktif
+fn weights(cfg: &AllocCfg, active: &[String], board: &Scoreboard) -> HashMap<String, f64> {
+    let statics: Vec<f64> = active.iter().map(|s| cfg.static_weight(s)).collect();
+    let mut raw = statics.clone();
+    if cfg.mode == AllocMode::Performance {
+        let scores = board.snapshot();
+        let perf: Vec<f64> = active
+            .iter()
+            .zip(&statics)
+            .map(|(s, w)| scores.get(s).map_or(0.0, |sc| (sc.window_pnl / (sc.drawdown + 1.0)).max(0.0)) * w)
+            .collect();
+        let sum: f64 = perf.iter().sum();
+        if sum > 0.0 {
+            // lantai per strategi, sisanya dibagi proporsional performa
+            let floor = cfg.min_share.min(1.0 / active.len() as f64);
+            let rest = 1.0 - floor * active.len() as f64;
+            raw = perf.iter().map(|p| floor + rest * p / sum).collect();
+        }
+    }
+    let sum: f64 = raw.iter().sum();
+    active
+        .iter()
+        .zip(raw)
+        .map(|(s, w)| (s.clone(), if sum > 0.0 { w / sum } else { 1.0 / active.len() as f64 }))
+        .collect()
+}
+
+/// Hitung ulang alokasi + scale, publish ke sizing & metrics
+fn rebalance(cfg: &AllocCfg, strategies: &[String], board: &Scoreboard) {
+    let active: Vec<String> = strategies.iter().filter(|s| !board.is_disabled(s)).cloned().collect();
+    let w = weights(cfg, &active, board);
+    let mut scales = HashMap::new();
+    for s in strategies {
+        let share = w.get(s).copied().unwrap_or(0.0);
+        let scale = share * active.len() as f64;
+        if crate::metrics::strategy_admitted(s) {
+            STRATEGY_ALLOCATION.with_label_values(&[s]).set(cfg.total_notional * share);
+            STRATEGY_SIZE_SCALE.with_label_values(&[s]).set(scale);
+        }
+        scales.insert(s.clone(), scale);
+    }
+    tracing::debug!(?scales, "capital allocation");
+    sizing::set_strategy_scales(scales);
+}
+
+/// Task allocator: rebalance tiap ALLOC_REBALANCE_SECS
+pub async fn run(cfg: AllocCfg, strategies: Vec<String>, board: Scoreboard) {
+    info!(total = cfg.total_notional, mode = ?cfg.mode, ?strategies, "capital allocator");
+    let mut tick = tokio::time::interval(Duration::from_secs(cfg.rebalance_secs));
+    loop {
+        tick.tick().await;
+        rebalance(&cfg, &strategies, &board);
+    }
+}
@@ -0,0 +1,119 @@
+// ===============================
+// src/bench_fanout.rs
+// ===============================
+//
+// Benchmark fan-out ExecReport (subcommand bench-exec-fanout): clone ExecReport per consumer
+// (perilaku lama) vs satu Arc<ExecReport> yang dibagi semua consumer.
+//
+// Jumlah alokasi heap hanya dihitung jika dibangun dengan `--features alloc-count` (global
+// allocator penghitung); tanpa feature hanya waktu yang dilaporkan.
+//
+//   cargo run --release --features alloc-count -- bench-exec-fanout --reports 200000 --consumers 7
+//
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::mpsc;
+
+use crate::domain::{ExecReport, ExecStatus, Side};
+
+#[cfg(feature = "alloc-count")]
+mod counting {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    pub static ALLOCS: AtomicU64 = AtomicU64::new(0);
+    pub static BYTES: AtomicU64 = AtomicU64::new(0);
+
+    pub struct Counting;
+
+    // SAFETY: meneruskan semua panggilan ke System, hanya menambah counter
+    unsafe impl GlobalAlloc for Counting {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            ALLOCS.fetch_add(1, Ordering::Relaxed);
+            BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+            unsafe { System.alloc(layout) }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            unsafe { System.dealloc(ptr, layout) }
+        }
+    }
+
+    #[global_allocator]
+    static GLOBAL: Counting = Counting;
+}
+
+/// (alokasi, byte) sejak start; None tanpa feature alloc-count
+fn alloc_snapshot() -> Option<(u64, u64)> {
+    #[cfg(feature = "alloc-count")]
+    {
+        use std::sync::atomic::Ordering;
+        Some((counting::ALLOCS.load(Ordering::Relaxed), counting::BYTES.load(Ordering::Relaxed)))
+    }
+    #[cfg(not(feature = "alloc-count"))]
+    None
+}
+
+fn sample(i: u64) -> ExecReport {
+    ExecReport {
+        cl_id: format!("CL-{i}-1234567890-A"),
+        symbol: "BTCUSDT".to_string(),
+        status: ExecStatus::PartialFill,
+        filled_qty: 5,
+        avg_px: 10_001,
+        ts_ns: i as i128,
+        venue: "A".to_string(),
+        side: Some(Side::Buy),
+        order_qty: 10,
+        strategy: "mean_reversion".to_string(),
+        tags: vec!["desk=crypto".to_string()],
+        book: "alpha".to_string(),
+        exch_ts_ns: 0,
+    }
+}
+
+/// Kirim `reports` report ke `consumers` channel; `wrap` = bentuk pesan per consumer
+async fn run<T: Send + 'static>(reports: u64, consumers: usize, mut wrap: impl FnMut(&Arc<ExecReport>) -> T) -> f64 {
+    let mut txs = Vec::new();
+    let mut handles = Vec::new();
+    for _ in 0..consumers {
+        let (tx, mut rx) = mpsc::channel::<T>(4096);
+        txs.push(tx);
+        handles.push(tokio::spawn(async move { while rx.recv().await.is_some() {} }));
+    }
+    let start = Instant::now();
+    for i in 0..reports {
+        // report dari gateway dibungkus Arc sekali di fan-out pusat
+        let er = Arc::new(sample(i));
+        for tx in &txs {
+            let _ = tx.send(wrap(&er)).await;
+        }
+    }
+    drop(txs);
+    for h in handles {
+        let _ = h.await;
+    }
+    start.elapsed().as_secs_f64()
+}
+
+fn line(name: &str, reports: u64, secs: f64, before: Option<(u64, u64)>) {
+    let allocs = match (before, alloc_snapshot()) {
+        (Some(b), Some(a)) => format!(
+            "allocs/report={:>6.2} bytes/report={:>8.1}",
+            (a.0 - b.0) as f64 / reports as f64,
+            (a.1 - b.1) as f64 / reports as f64
+        ),
+        _ => "allocs: build with --features alloc-count".to_string(),
+    };
+    println!("{name:<7} {:>9.0} reports/s  {allocs}", reports as f64 / secs);
+}
+
+pub async fn bench(reports: u64, consumers: usize) {
+    println!("exec fan-out bench: reports={reports} consumers={consumers}");
+    let before = alloc_snapshot();
+    let secs = run(reports, consumers, |er| (**er).clone()).await;
+    line("clone", reports, secs, before);
+    let before = alloc_snapshot();
+    let secs = run(reports, consumers, |er| er.clone()).await;
+    line("arc", reports, secs, before);
+}
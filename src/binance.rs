@@ -4,13 +4,32 @@
 use hmac::{Hmac, Mac};
 use serde::Deserialize;
 use sha2::Sha256;
+use std::fmt;
+use std::sync::atomic::{AtomicI64, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
 
-pub fn timestamp_ms() -> u64 {
+use crate::metrics::BINANCE_ERRORS;
+
+/// Selisih jam server Binance - jam lokal (ms), diisi `sync_server_time` setelah -1021
+static SERVER_OFFSET_MS: AtomicI64 = AtomicI64::new(0);
+
+fn local_ms() -> i64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap()
-        .as_millis() as u64
+        .as_millis() as i64
+}
+
+/// Timestamp request bertanda tangan (jam lokal dikoreksi offset server)
+pub fn timestamp_ms() -> u64 {
+    (local_ms() + SERVER_OFFSET_MS.load(Ordering::Relaxed)).max(0) as u64
+}
+
+/// Catat `serverTime` dari GET /api/v3/time; kembalikan offset baru (ms)
+pub fn sync_server_time(server_ms: u64) -> i64 {
+    let offset = server_ms as i64 - local_ms();
+    SERVER_OFFSET_MS.store(offset, Ordering::Relaxed);
+    offset
 }
 
 pub fn sign_query(secret: &str, query: &str) -> String {
@@ -20,17 +39,164 @@ pub fn sign_query(secret: &str, query: &str) -> String {
     hex::encode(sig)
 }
 
+// ---- Error code REST / WS API ----
+
+/// Cara menangani error Binance
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// request pasti tidak diproses; aman dikirim ulang setelah jeda
+    Retryable,
+    /// ditolak karena isi request / akun; mengirim ulang tidak akan membantu
+    Fatal,
+    /// state lokal perlu disinkronkan dulu (jam server, status order tidak diketahui)
+    NeedsResync,
+}
+
+impl ErrorClass {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorClass::Retryable => "retryable",
+            ErrorClass::Fatal => "fatal",
+            ErrorClass::NeedsResync => "resync",
+        }
+    }
+}
+
+/// Error API Binance (`{"code":..,"msg":..}`) yang dibedakan engine
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BinanceError {
+    /// -1001 DISCONNECTED: error internal, coba lagi
+    Disconnected,
+    /// -1003 TOO_MANY_REQUESTS: weight limit / IP ban (429/418)
+    TooManyRequests,
+    /// -1007 TIMEOUT: backend timeout, status order tidak diketahui
+    Timeout,
+    /// -1013 filter symbol (LOT_SIZE, PRICE_FILTER, NOTIONAL, ...)
+    FilterFailure(String),
+    /// -1015 TOO_MANY_ORDERS: order rate limit
+    TooManyOrders,
+    /// -1021 INVALID_TIMESTAMP: di luar recvWindow (clock skew)
+    InvalidTimestamp,
+    /// -1022 INVALID_SIGNATURE
+    InvalidSignature,
+    /// -1100..=-1199: parameter request tidak valid
+    BadRequest(i64, String),
+    /// -2010 NEW_ORDER_REJECTED (saldo kurang, duplikat, market tutup, ...)
+    NewOrderRejected(String),
+    /// -2011 CANCEL_REJECTED (order sudah final / tidak dikenal)
+    CancelRejected,
+    /// -2013 NO_SUCH_ORDER
+    NoSuchOrder,
+    /// -2014 / -2015: API key salah format / ditolak (IP, permission)
+    Unauthorized(i64),
+    Other(i64, String),
+}
+
+impl BinanceError {
+    pub fn from_code(code: i64, msg: &str) -> Self {
+        match code {
+            -1001 => BinanceError::Disconnected,
+            -1003 => BinanceError::TooManyRequests,
+            -1007 => BinanceError::Timeout,
+            -1013 => BinanceError::FilterFailure(msg.to_string()),
+            -1015 => BinanceError::TooManyOrders,
+            -1021 => BinanceError::InvalidTimestamp,
+            -1022 => BinanceError::InvalidSignature,
+            -1199..=-1100 => BinanceError::BadRequest(code, msg.to_string()),
+            -2010 => BinanceError::NewOrderRejected(msg.to_string()),
+            -2011 => BinanceError::CancelRejected,
+            -2013 => BinanceError::NoSuchOrder,
+            -2015 | -2014 => BinanceError::Unauthorized(code),
+            _ => BinanceError::Other(code, msg.to_string()),
+        }
+    }
+
+    /// Dari body error JSON (`{"code":-2010,"msg":"..."}`, juga objek `error` WS API)
+    pub fn from_body(body: &str) -> Option<Self> {
+        let v: serde_json::Value = serde_json::from_str(body).ok()?;
+        let code = v.get("code")?.as_i64()?;
+        Some(Self::from_code(code, v.get("msg").and_then(|m| m.as_str()).unwrap_or("")))
+    }
+
+    pub fn code(&self) -> i64 {
+        match self {
+            BinanceError::Disconnected => -1001,
+            BinanceError::TooManyRequests => -1003,
+            BinanceError::Timeout => -1007,
+            BinanceError::FilterFailure(_) => -1013,
+            BinanceError::TooManyOrders => -1015,
+            BinanceError::InvalidTimestamp => -1021,
+            BinanceError::InvalidSignature => -1022,
+            BinanceError::NewOrderRejected(_) => -2010,
+            BinanceError::CancelRejected => -2011,
+            BinanceError::NoSuchOrder => -2013,
+            BinanceError::BadRequest(code, _) | BinanceError::Unauthorized(code) | BinanceError::Other(code, _) => *code,
+        }
+    }
+
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            BinanceError::Disconnected | BinanceError::TooManyRequests | BinanceError::TooManyOrders => ErrorClass::Retryable,
+            BinanceError::Timeout | BinanceError::InvalidTimestamp => ErrorClass::NeedsResync,
+            _ => ErrorClass::Fatal,
+        }
+    }
+
+    /// Hitung di `binance_errors_total{code,class}`
+    pub fn record(&self) {
+        BINANCE_ERRORS.with_label_values(&[&self.code().to_string(), self.class().as_str()]).inc();
+    }
+}
+
+/// Alasan reject ringkas untuk ExecStatus::Rejected, mis. "-2010 NEW_ORDER_REJECTED: Account has insufficient balance"
+impl fmt::Display for BinanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let code = self.code();
+        match self {
+            BinanceError::Disconnected => write!(f, "{code} DISCONNECTED"),
+            BinanceError::TooManyRequests => write!(f, "{code} TOO_MANY_REQUESTS"),
+            BinanceError::Timeout => write!(f, "{code} TIMEOUT"),
+            BinanceError::FilterFailure(m) => write!(f, "{code} FILTER_FAILURE: {m}"),
+            BinanceError::TooManyOrders => write!(f, "{code} TOO_MANY_ORDERS"),
+            BinanceError::InvalidTimestamp => write!(f, "{code} INVALID_TIMESTAMP"),
+            BinanceError::InvalidSignature => write!(f, "{code} INVALID_SIGNATURE"),
+            BinanceError::BadRequest(_, m) => write!(f, "{code} BAD_REQUEST: {m}"),
+            BinanceError::NewOrderRejected(m) => write!(f, "{code} NEW_ORDER_REJECTED: {m}"),
+            BinanceError::CancelRejected => write!(f, "{code} CANCEL_REJECTED"),
+            BinanceError::NoSuchOrder => write!(f, "{code} NO_SUCH_ORDER"),
+            BinanceError::Unauthorized(_) => write!(f, "{code} UNAUTHORIZED"),
+            BinanceError::Other(_, m) => write!(f, "{code} {m}"),
+        }
+    }
+}
+
 // ---- Minimal user-data stream models ----
+// Spot mengirim `executionReport` + `outboundAccountPosition` (field datar);
+// futures mengirim `ORDER_TRADE_UPDATE` dengan detail order di `o`.
 #[derive(Debug, Deserialize)]
-pub struct WsEnvelope {
-    #[serde(default)]
-    pub e: Option<String>,
+#[serde(tag = "e")]
+pub enum UserEvent {
+    #[serde(rename = "executionReport")]
+    ExecutionReport(ExecutionReport),
+    #[serde(rename = "outboundAccountPosition")]
+    AccountPosition(AccountPosition),
+    #[serde(rename = "ORDER_TRADE_UPDATE")]
+    OrderTradeUpdate(FuturesOrderEvent),
+    /// balanceUpdate, listStatus, listenKeyExpired, ...
+    #[serde(other)]
+    Other,
+}
+
+#[allow(non_snake_case, dead_code)]
+#[derive(Debug, Deserialize)]
+pub struct FuturesOrderEvent {
     #[serde(rename = "E", default)]
     pub E: Option<u64>,
-    #[serde(rename = "o", default)]
-    pub o: Option<OrderTradeUpdate>,
+    #[serde(rename = "o")]
+    pub o: OrderTradeUpdate,
 }
 
+#[allow(non_snake_case, dead_code)]
 #[derive(Debug, Deserialize)]
 pub struct OrderTradeUpdate {
     #[serde(rename = "s")]
@@ -50,3 +216,126 @@ pub struct OrderTradeUpdate {
     #[serde(rename = "ap", default)]
     pub ap: Option<String>, // avg price
 }
+
+/// Spot `executionReport`
+#[allow(non_snake_case, dead_code)]
+#[derive(Debug, Deserialize)]
+pub struct ExecutionReport {
+    #[serde(rename = "E", default)]
+    pub E: Option<u64>, // event time
+    #[serde(rename = "s")]
+    pub s: String, // symbol
+    #[serde(rename = "c")]
+    pub c: String, // clientOrderId (untuk cancel: clientOrderId request cancel)
+    #[serde(rename = "C", default)]
+    pub C: String, // original clientOrderId (hanya terisi saat cancel)
+    #[serde(rename = "S", default)]
+    pub S: String, // side
+    #[serde(rename = "X")]
+    pub X: String, // order status
+    #[serde(rename = "x")]
+    pub x: String, // execution type: NEW, TRADE, CANCELED, REPLACED, REJECTED, EXPIRED
+    #[serde(rename = "r", default)]
+    pub r: String, // reject reason
+    #[serde(rename = "i", default)]
+    pub i: Option<u64>, // orderId
+    #[serde(rename = "l", default)]
+    pub l: Option<String>, // last filled qty
+    #[serde(rename = "L", default)]
+    pub L: Option<String>, // last filled price
+    #[serde(rename = "z", default)]
+    pub z: Option<String>, // cum filled qty
+    #[serde(rename = "Z", default)]
+    pub Z: Option<String>, // cum quote qty
+    #[serde(rename = "T", default)]
+    pub T: Option<u64>, // transaction time
+}
+
+impl ExecutionReport {
+    /// clientOrderId order yang dimaksud (cancel membawa cl_id asal di `C`)
+    pub fn cl_id(&self) -> &str {
+        if self.C.is_empty() {
+            &self.c
+        } else {
+            &self.C
+        }
+    }
+
+    /// Harga rata-rata = cum quote / cum qty
+    pub fn avg_px(&self) -> Option<f64> {
+        let qty = self.z.as_deref()?.parse::<f64>().ok()?;
+        let quote = self.Z.as_deref()?.parse::<f64>().ok()?;
+        (qty > 0.0).then(|| quote / qty)
+    }
+}
+
+/// Spot `outboundAccountPosition`: saldo aset yang berubah
+#[allow(non_snake_case, dead_code)]
+#[derive(Debug, Deserialize)]
+pub struct AccountPosition {
+    #[serde(rename = "E", default)]
+    pub E: Option<u64>,
+    #[serde(rename = "B", default)]
+    pub B: Vec<AssetBalance>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AssetBalance {
+    #[serde(rename = "a")]
+    pub asset: String,
+    #[serde(rename = "f")]
+    pub free: String,
+    #[serde(rename = "l")]
+    pub locked: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_error_codes() {
+        let e = BinanceError::from_body(r#"{"code":-2010,"msg":"Account has insufficient balance for requested action."}"#).unwrap();
+        assert_eq!(e.class(), ErrorClass::Fatal);
+        assert_eq!(e.to_string(), "-2010 NEW_ORDER_REJECTED: Account has insufficient balance for requested action.");
+        assert_eq!(BinanceError::from_code(-1013, "Filter failure: LOT_SIZE").class(), ErrorClass::Fatal);
+        assert_eq!(BinanceError::from_code(-1003, "Too much request weight used").class(), ErrorClass::Retryable);
+        assert_eq!(BinanceError::from_code(-1021, "Timestamp outside recvWindow").class(), ErrorClass::NeedsResync);
+        assert_eq!(BinanceError::from_code(-1102, "Mandatory parameter 'price' was not sent").code(), -1102);
+        assert_eq!(BinanceError::from_body("<html>502</html>"), None);
+    }
+
+    #[test]
+    fn parses_spot_execution_report_fill() {
+        let raw = r#"{"e":"executionReport","E":1499405658658,"s":"ETHBTC","c":"mUvoqJxFIILMdfAW5iGSOW","S":"BUY","o":"LIMIT","f":"GTC","q":"1.00000000","p":"0.10264410","P":"0.00000000","F":"0.00000000","g":-1,"C":"","x":"TRADE","X":"PARTIALLY_FILLED","r":"NONE","i":4293153,"l":"0.40000000","z":"0.40000000","L":"0.10264410","n":"0.00000040","N":"ETH","T":1499405658657,"t":1234,"v":3,"I":8641984,"w":false,"m":false,"M":false,"O":1499405658657,"Z":"0.04105764","Y":"0.04105764","Q":"0.00000000","W":1499405658657,"V":"NONE"}"#;
+        let UserEvent::ExecutionReport(er) = serde_json::from_str(raw).unwrap() else { panic!("not an executionReport") };
+        assert_eq!(er.cl_id(), "mUvoqJxFIILMdfAW5iGSOW");
+        assert_eq!(er.X, "PARTIALLY_FILLED");
+        assert_eq!(er.i, Some(4293153));
+        assert_eq!(er.z.as_deref(), Some("0.40000000"));
+        assert!((er.avg_px().unwrap() - 0.1026441).abs() < 1e-9);
+    }
+
+    #[test]
+    fn spot_cancel_reports_original_client_order_id() {
+        let raw = r#"{"e":"executionReport","E":1499405658700,"s":"ETHBTC","c":"web_cancel_42","S":"BUY","o":"LIMIT","f":"GTC","q":"1.00000000","p":"0.10264410","C":"C0mK4Hq2Tz5-binance","x":"CANCELED","X":"CANCELED","r":"NONE","i":4293153,"l":"0.00000000","z":"0.40000000","L":"0.00000000","T":1499405658699,"Z":"0.04105764"}"#;
+        let UserEvent::ExecutionReport(er) = serde_json::from_str(raw).unwrap() else { panic!("not an executionReport") };
+        assert_eq!(er.cl_id(), "C0mK4Hq2Tz5-binance");
+        assert_eq!(er.X, "CANCELED");
+    }
+
+    #[test]
+    fn parses_account_position_and_futures_update() {
+        let raw = r#"{"e":"outboundAccountPosition","E":1564034571105,"u":1564034571073,"B":[{"a":"ETH","f":"10000.000000","l":"0.000000"},{"a":"USDT","f":"95.5","l":"4.5"}]}"#;
+        let UserEvent::AccountPosition(ap) = serde_json::from_str(raw).unwrap() else { panic!("not an outboundAccountPosition") };
+        assert_eq!(ap.B.len(), 2);
+        assert_eq!((ap.B[1].asset.as_str(), ap.B[1].free.as_str(), ap.B[1].locked.as_str()), ("USDT", "95.5", "4.5"));
+
+        let raw = r#"{"e":"ORDER_TRADE_UPDATE","E":1568879465651,"T":1568879465650,"o":{"s":"BTCUSDT","c":"TEST","S":"SELL","o":"TRAILING_STOP_MARKET","f":"GTC","q":"0.001","p":"0","ap":"0","sp":"7103.04","x":"NEW","X":"NEW","i":8886774,"l":"0","z":"0","L":"0","T":1568879465650}}"#;
+        let UserEvent::OrderTradeUpdate(ev) = serde_json::from_str(raw).unwrap() else { panic!("not an ORDER_TRADE_UPDATE") };
+        assert_eq!((ev.E, ev.o.c.as_str(), ev.o.X.as_str()), (Some(1568879465651), "TEST", "NEW"));
+
+        let raw = r#"{"e":"balanceUpdate","E":1573200697110,"a":"BTC","d":"100.00000000","T":1573200697068}"#;
+        assert!(matches!(serde_json::from_str::<UserEvent>(raw).unwrap(), UserEvent::Other));
+    }
+}
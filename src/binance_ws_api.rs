@@ -0,0 +1,146 @@
+// ===============================
+// src/binance_ws_api.rs
+// ===============================
+//
+// Klien Binance WebSocket API (order entry via WS, latensi lebih rendah dari REST).
+//
+// - Endpoint: wss://ws-api.binance.com:443/ws-api/v3 (testnet: wss://testnet.binance.vision/ws-api/v3)
+// - Request : {"id": "...", "method": "order.place", "params": {..., apiKey, timestamp, signature}}
+// - Signature: HMAC-SHA256 atas params (tanpa signature) diurutkan alfabetis, format query.
+// - Respon dicocokkan via `id`; frame lain (ping dll) diabaikan.
+//
+// Koneksi dibuka lazily dan dibuka ulang otomatis pada request berikutnya jika putus.
+//
+use futures_util::{SinkExt, StreamExt};
+use tokio::time::{timeout, Duration};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::binance::{sign_query, timestamp_ms};
+use crate::net::{connect_ws, WsStream};
+
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Error WS API: `status` mengikuti HTTP-like status di respon (None = transport)
+#[derive(Debug)]
+pub struct WsApiError {
+    pub status: Option<u16>,
+    pub body: String,
+}
+
+pub struct WsApiClient {
+    url: String,
+    api_key: String,
+    api_sec: String,
+    recv_window: u64,
+    ws: Option<WsStream>,
+    next_id: u64,
+}
+
+/// URL default WS API sesuai REST base (testnet vs mainnet)
+pub fn default_url(rest_base: &str) -> &'static str {
+    if rest_base.contains("testnet") {
+        "wss://testnet.binance.vision/ws-api/v3"
+    } else {
+        "wss://ws-api.binance.com:443/ws-api/v3"
+    }
+}
+
+impl WsApiClient {
+    pub fn new(url: String, api_key: String, api_sec: String, recv_window: u64) -> Self {
+        Self { url, api_key, api_sec, recv_window, ws: None, next_id: 0 }
+    }
+
+    fn transport_err(e: impl std::fmt::Display) -> WsApiError {
+        WsApiError { status: None, body: e.to_string() }
+    }
+
+    async fn connection(&mut self) -> Result<&mut WsStream, WsApiError> {
+        if self.ws.is_none() {
+            tracing::info!(url = %self.url, "connecting binance ws-api");
+            let (ws, _) = connect_ws(&self.url).await.map_err(Self::transport_err)?;
+            self.ws = Some(ws);
+        }
+        Ok(self.ws.as_mut().unwrap())
+    }
+
+    /// Kirim request bertanda tangan; params berupa string (kecuali field numerik waktu).
+    pub async fn signed_request(
+        &mut self,
+        method: &str,
+        params: Vec<(String, String)>,
+    ) -> Result<serde_json::Value, WsApiError> {
+        let mut params = params;
+        params.push(("apiKey".to_string(), self.api_key.clone()));
+        params.push(("timestamp".to_string(), timestamp_ms().to_string()));
+        params.push(("recvWindow".to_string(), self.recv_window.to_string()));
+        params.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let payload = params.iter().map(|(k, v)| format!("{k}={v}")).collect::<Vec<_>>().join("&");
+        let signature = sign_query(&self.api_sec, &payload);
+
+        let mut obj = serde_json::Map::new();
+        for (k, v) in params {
+            let val = match k.as_str() {
+                "timestamp" | "recvWindow" => serde_json::json!(v.parse::<u64>().unwrap_or(0)),
+                _ => serde_json::Value::String(v),
+            };
+            obj.insert(k, val);
+        }
+        obj.insert("signature".to_string(), serde_json::Value::String(signature));
+
+        self.next_id += 1;
+        let id = format!("dma-{}", self.next_id);
+        let req = serde_json::json!({ "id": id, "method": method, "params": obj });
+
+        let started = std::time::Instant::now();
+        let res = self.roundtrip(&id, req.to_string()).await;
+        if crate::exchange_log::enabled() {
+            let (status, response) = match &res {
+                Ok(v) => (Some(200), v.to_string()),
+                Err(e) => (e.status, e.body.clone()),
+            };
+            crate::exchange_log::record(crate::exchange_log::Entry {
+                ts_ns: crate::clock::now_ns(),
+                transport: "ws",
+                method,
+                path: &self.url,
+                request: payload,
+                status,
+                latency_us: started.elapsed().as_micros() as u64,
+                response,
+            });
+        }
+        if matches!(res, Err(WsApiError { status: None, .. })) {
+            // transport error: buang koneksi, reconnect pada request berikutnya
+            self.ws = None;
+        }
+        res
+    }
+
+    async fn roundtrip(&mut self, id: &str, text: String) -> Result<serde_json::Value, WsApiError> {
+        let ws = self.connection().await?;
+        ws.send(Message::Text(text)).await.map_err(Self::transport_err)?;
+
+        let wait = async {
+            while let Some(frame) = ws.next().await {
+                let m = frame.map_err(Self::transport_err)?;
+                if !m.is_text() {
+                    continue;
+                }
+                let txt = m.into_text().unwrap_or_default();
+                let Ok(v) = serde_json::from_str::<serde_json::Value>(&txt) else { continue };
+                if v.get("id").and_then(|x| x.as_str()) != Some(id) {
+                    continue;
+                }
+                let status = v.get("status").and_then(|x| x.as_u64()).unwrap_or(0) as u16;
+                if status == 200 {
+                    return Ok(v.get("result").cloned().unwrap_or(serde_json::Value::Null));
+                }
+                let body = v.get("error").map(|e| e.to_string()).unwrap_or(txt);
+                return Err(WsApiError { status: Some(status), body });
+            }
+            Err(Self::transport_err("ws-api closed"))
+        };
+        timeout(RESPONSE_TIMEOUT, wait).await.map_err(|_| Self::transport_err("ws-api response timeout"))?
+    }
+}
@@ -0,0 +1,219 @@
+// ===============================
+// src/blotter.rs
+// ===============================
+//
+// Trade blotter: daftar semua fill (bukan ACK) dalam memori, opsional
+// dipersist ke JSONL agar bisa direkonstruksi setelah restart.
+//
+// - ExecReport.filled_qty bersifat kumulatif per cl_id, jadi blotter
+//   menyimpan cum terakhir dan mencatat selisihnya sebagai satu fill.
+// - Harga per fill diturunkan dari perubahan avg_px kumulatif.
+// - Fee = estimasi dari fee_bps venue (RouterCfg) atas notional fill.
+//
+// ENV: `BLOTTER_FILE=/path/to/fills.jsonl` (opsional), `BLOTTER_MAX_FILLS`.
+// Query via admin API: `GET /admin/blotter?symbol=&strategy=&since=&format=json|csv`.
+//
+use ahash::AHashMap as HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Write};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
+
+use crate::domain::{ExecReport, ExecStatus, Side};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fill {
+    pub ts_ns: i128,
+    pub cl_id: String,
+    pub symbol: String,
+    pub venue: String,
+    pub strategy: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub px: i64,
+    pub qty: i64,
+    pub fee: i64,
+    /// None = fill lama (sebelum side dicatat)
+    #[serde(default)]
+    pub side: Option<Side>,
+}
+
+impl Fill {
+    #[cfg(feature = "admin-api")]
+    pub const CSV_HEADER: &'static str = "ts_ns,cl_id,symbol,venue,strategy,tags,px,qty,fee,side";
+
+    #[cfg(feature = "admin-api")]
+    pub fn to_csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{},{},{}",
+            self.ts_ns,
+            self.cl_id,
+            self.symbol,
+            self.venue,
+            self.strategy.as_deref().unwrap_or(""),
+            self.tags.join(";"),
+            self.px,
+            self.qty,
+            self.fee,
+            self.side.as_ref().map_or("", Side::as_str)
+        )
+    }
+}
+
+/// Progres kumulatif per cl_id (untuk menghitung delta fill)
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+pub struct CumState {
+    pub qty: i64,
+    pub avg_px: i64,
+}
+
+struct Inner {
+    fills: VecDeque<Fill>,
+    max_fills: usize,
+    cum: HashMap<String, CumState>,
+    fee_bps: HashMap<String, i32>,
+    persist: Option<std::fs::File>,
+}
+
+/// Handle blotter yang bisa di-clone (dipakai task exec & admin API)
+#[derive(Clone)]
+pub struct Blotter {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl Blotter {
+    pub fn new(fee_bps: HashMap<String, i32>, max_fills: usize, path: Option<String>) -> Self {
+        let mut fills = VecDeque::new();
+        let mut persist = None;
+        if let Some(path) = path {
+            fills = load_fills(&path, max_fills);
+            info!(%path, loaded = fills.len(), "blotter: persistence enabled");
+            match std::fs::OpenOptions::new().create(true).append(true).open(&path) {
+                Ok(f) => persist = Some(f),
+                Err(e) => error!(?e, %path, "blotter: open persist file failed, memory only"),
+            }
+        }
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                fills,
+                max_fills: max_fills.max(1),
+                cum: HashMap::new(),
+                fee_bps,
+                persist,
+            })),
+        }
+    }
+
+    /// Catat ExecReport; kembalikan Fill baru jika report menambah qty terisi.
+    pub fn on_exec(&self, er: &ExecReport) -> Option<Fill> {
+        if matches!(er.status, ExecStatus::Expired | ExecStatus::Rejected(_)) {
+            // order selesai tanpa fill tambahan: lepas progres working
+            self.inner.lock().unwrap().cum.remove(&er.cl_id);
+            return None;
+        }
+        if !matches!(er.status, ExecStatus::PartialFill | ExecStatus::Filled) {
+            return None;
+        }
+        let mut g = self.inner.lock().unwrap();
+        let prev = g.cum.get(&er.cl_id).copied().unwrap_or_default();
+        let qty = er.filled_qty - prev.qty;
+        if qty <= 0 {
+            return None;
+        }
+        // harga fill = (notional kumulatif baru - lama) / qty delta
+        let px = (er.avg_px * er.filled_qty - prev.avg_px * prev.qty) / qty;
+        if matches!(er.status, ExecStatus::Filled) {
+            g.cum.remove(&er.cl_id);
+        } else {
+            g.cum.insert(er.cl_id.clone(), CumState { qty: er.filled_qty, avg_px: er.avg_px });
+        }
+
+        let venue = if er.venue.is_empty() { "?".to_string() } else { er.venue.clone() };
+        let fee_bps = g.fee_bps.get(&venue).copied().unwrap_or(0) as i64;
+        let fill = Fill {
+            ts_ns: er.ts_ns,
+            cl_id: er.cl_id.clone(),
+            symbol: er.symbol.clone(),
+            venue,
+            strategy: Some(er.strategy.clone()).filter(|s| !s.is_empty()),
+            tags: er.tags.clone(),
+            px,
+            qty,
+            fee: px * qty * fee_bps / 10_000,
+            side: er.side,
+        };
+
+        if let Some(f) = g.persist.as_mut() {
+            if let Ok(line) = serde_json::to_string(&fill) {
+                if let Err(e) = writeln!(f, "{line}") {
+                    warn!(?e, "blotter: persist write failed");
+                }
+            }
+        }
+        if g.fills.len() >= g.max_fills {
+            g.fills.pop_front();
+        }
+        g.fills.push_back(fill.clone());
+        Some(fill)
+    }
+
+    /// Order yang sudah terisi sebagian (untuk snapshot)
+    pub fn working_orders(&self) -> std::collections::BTreeMap<String, CumState> {
+        let g = self.inner.lock().unwrap();
+        g.cum.iter().map(|(k, v)| (k.clone(), *v)).collect()
+    }
+
+    /// Pulihkan progres order working dari snapshot (warm start)
+    pub fn restore_working_orders(&self, orders: std::collections::BTreeMap<String, CumState>) {
+        if orders.is_empty() {
+            return;
+        }
+        info!(n = orders.len(), "blotter: working orders restored");
+        self.inner.lock().unwrap().cum.extend(orders);
+    }
+
+    /// `n` fill terakhir (urut waktu)
+    #[cfg(feature = "admin-api")]
+    pub fn recent(&self, n: usize) -> Vec<Fill> {
+        let g = self.inner.lock().unwrap();
+        g.fills.iter().skip(g.fills.len().saturating_sub(n)).cloned().collect()
+    }
+
+    /// Ambil fill dengan filter opsional symbol, strategi & ts_ns minimum (inklusif)
+    pub fn query(&self, symbol: Option<&str>, strategy: Option<&str>, since_ns: Option<i128>) -> Vec<Fill> {
+        let g = self.inner.lock().unwrap();
+        g.fills
+            .iter()
+            .filter(|f| symbol.is_none_or(|s| f.symbol.eq_ignore_ascii_case(s)))
+            .filter(|f| strategy.is_none_or(|s| f.strategy.as_deref() == Some(s)))
+            .filter(|f| since_ns.is_none_or(|t| f.ts_ns >= t))
+            .cloned()
+            .collect()
+    }
+}
+
+fn load_fills(path: &str, max_fills: usize) -> VecDeque<Fill> {
+    let mut out = VecDeque::new();
+    let Ok(file) = std::fs::File::open(path) else { return out; };
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        match serde_json::from_str::<Fill>(&line) {
+            Ok(f) => {
+                if out.len() >= max_fills.max(1) {
+                    out.pop_front();
+                }
+                out.push_back(f);
+            }
+            Err(e) => warn!(?e, "blotter: skip bad line"),
+        }
+    }
+    out
+}
+
+/// Task blotter: konsumsi ExecReport dari fan-out
+pub async fn run(mut exec_rx: mpsc::Receiver<Arc<ExecReport>>, blotter: Blotter) {
+    while let Some(er) = exec_rx.recv().await {
+        blotter.on_exec(&er);
+    }
+}
@@ -0,0 +1,144 @@
+// ===============================
+// src/book_analytics.rs
+// ===============================
+//
+// Analitik order book terpusat: microprice + imbalance top-1 / top-k dihitung sekali per tick
+// lalu dibroadcast sebagai `EnrichedTick`, jadi strategi tidak membangun ulang book sendiri.
+//
+// - microprice  : (bid * ask_qty + ask * bid_qty) / (bid_qty + ask_qty), x100 (pecahan); mid jika qty tidak ada
+// - imbalance   : (bid_qty - ask_qty) / (bid_qty + ask_qty) di top of book, -1..1 (positif = bid lebih tebal)
+// - imbalance_k : sama, dijumlah atas k level teratas BookUpdate terakhir (fallback top-1 tanpa depth)
+//
+// Task `run` hanya berjalan jika ada depth (MOCK_BOOK_LEVELS > 0). Tanpa depth, `TickRx::Raw`
+// menghitung statistik top-of-book langsung dari MdTick di worker strategi. Dengan MD_BUS=ring
+// statistik dihitung task feed saat menulis ke ring (md_ring.rs), task `run` tidak dipakai.
+//
+// ENV:
+//   BOOK_IMBALANCE_LEVELS=5   (k level untuk imbalance_k)
+//
+use ahash::AHashMap as HashMap;
+use std::sync::Arc;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use crate::domain::{BookStats, BookUpdate, EnrichedTick, Levels, MdTick};
+use crate::md_ring::{MdRing, RingReader};
+use crate::metrics::{symbol_admitted, BOOK_IMBALANCE};
+use crate::supervisor::Relay;
+
+pub fn levels_from_env() -> usize {
+    std::env::var("BOOK_IMBALANCE_LEVELS").ok().and_then(|s| s.parse().ok()).unwrap_or(5).max(1)
+}
+
+fn ratio(bid_qty: i64, ask_qty: i64) -> f64 {
+    let total = bid_qty + ask_qty;
+    if total > 0 { (bid_qty - ask_qty) as f64 / total as f64 } else { 0.0 }
+}
+
+fn top_k(levels: &Levels, k: usize) -> i64 {
+    levels.iter().take(k).map(|l| l.1).sum()
+}
+
+/// Statistik book untuk `md`; `book` = BookUpdate terakhir symbol yang sama (jika ada)
+pub fn stats(md: &MdTick, book: Option<&BookUpdate>, k: usize) -> BookStats {
+    // qty top of book: dari tick, fallback level 1 book
+    let level1 = |l: Option<&Levels>| l.and_then(|l| l.first()).map_or(0, |l| l.1);
+    let bid_qty = if md.bid_qty > 0 { md.bid_qty } else { level1(book.map(|b| &b.bids)) };
+    let ask_qty = if md.ask_qty > 0 { md.ask_qty } else { level1(book.map(|b| &b.asks)) };
+    let (bid, ask) = (md.best_bid as f64, md.best_ask as f64);
+    let microprice = if bid_qty + ask_qty > 0 {
+        (bid * ask_qty as f64 + ask * bid_qty as f64) / (bid_qty + ask_qty) as f64
+    } else {
+        (bid + ask) / 2.0
+    };
+    let imbalance = ratio(bid_qty, ask_qty);
+    let (imbalance_k, levels) = match book {
+        Some(b) => (ratio(top_k(&b.bids, k), top_k(&b.asks, k)), b.bids.len().min(b.asks.len()).min(k)),
+        None => (imbalance, 0),
+    };
+    BookStats { microprice, imbalance, imbalance_k, levels }
+}
+
+/// `stats` + gauge imbalance per symbol (task `run` dan publisher ring MD)
+pub fn enrich(md: &MdTick, book: Option<&BookUpdate>, k: usize) -> BookStats {
+    let stats = stats(md, book, k);
+    if symbol_admitted(&md.symbol) {
+        BOOK_IMBALANCE.with_label_values(&[&md.symbol]).set(stats.imbalance_k);
+    }
+    stats
+}
+
+/// MdTick + BookUpdate -> EnrichedTick
+pub async fn run(
+    mut md_rx: broadcast::Receiver<MdTick>,
+    mut book_rx: broadcast::Receiver<BookUpdate>,
+    out_tx: broadcast::Sender<EnrichedTick>,
+    k: usize,
+) {
+    let mut books: HashMap<String, BookUpdate> = HashMap::new();
+    loop {
+        tokio::select! {
+            // feed mock mengirim BookUpdate sebelum MdTick; utamakan book agar tick memakai depth terbaru
+            biased;
+            b = book_rx.recv() => match b {
+                Ok(b) => {
+                    books.insert(b.symbol.clone(), b);
+                }
+                Err(RecvError::Lagged(n)) => warn!(n, "book analytics lagged (book)"),
+                Err(RecvError::Closed) => break,
+            },
+            md = md_rx.recv() => match md {
+                Ok(md) => {
+                    let stats = enrich(&md, books.get(&md.symbol), k);
+                    let _ = out_tx.send(EnrichedTick { tick: md, stats });
+                }
+                Err(RecvError::Lagged(n)) => warn!(n, "book analytics lagged (md)"),
+                Err(RecvError::Closed) => break,
+            },
+        }
+    }
+}
+
+/// Bus tick untuk worker strategi (dipilih di main)
+#[derive(Clone)]
+pub enum TickSrc {
+    Raw(broadcast::Sender<MdTick>),
+    Enriched(broadcast::Sender<EnrichedTick>),
+    Ring(MdRing),                     // MD_BUS=ring, lihat md_ring.rs
+    Symbol(Arc<Relay<EnrichedTick>>), // PIPELINE=per_symbol, lihat pipeline.rs
+}
+
+impl TickSrc {
+    pub fn subscribe(&self) -> TickRx {
+        match self {
+            TickSrc::Raw(tx) => TickRx::Raw(tx.subscribe()),
+            TickSrc::Enriched(tx) => TickRx::Enriched(tx.subscribe()),
+            TickSrc::Ring(ring) => TickRx::Ring(ring.subscribe()),
+            TickSrc::Symbol(relay) => TickRx::Symbol(relay.attach()),
+        }
+    }
+}
+
+/// Sumber tick untuk worker strategi: EnrichedTick dari task analytics, atau MdTick mentah
+/// (statistik top-of-book dihitung lokal)
+pub enum TickRx {
+    Raw(broadcast::Receiver<MdTick>),
+    Enriched(broadcast::Receiver<EnrichedTick>),
+    Ring(RingReader),
+    Symbol(mpsc::Receiver<EnrichedTick>),
+}
+
+impl TickRx {
+    pub async fn recv(&mut self) -> Result<EnrichedTick, RecvError> {
+        match self {
+            TickRx::Raw(rx) => rx.recv().await.map(|md| {
+                let stats = stats(&md, None, 1);
+                EnrichedTick { tick: md, stats }
+            }),
+            TickRx::Enriched(rx) => rx.recv().await,
+            TickRx::Ring(rx) => rx.recv().await,
+            TickRx::Symbol(rx) => rx.recv().await.ok_or(RecvError::Closed),
+        }
+    }
+}
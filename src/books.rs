@@ -0,0 +1,61 @@
+// ===============================
+// src/books.rs
+// ===============================
+//
+// Multi-tenant "book": grup strategi logis dalam satu proses, masing-masing dengan
+// budget risk & PnL sendiri. Book dibawa Signal -> Order -> ExecReport (field `book`).
+//
+// ENV:
+//   BOOKS=alpha:mean_reversion+ma_crossover,beta:vol_breakout   (strategi lain -> book "default")
+//   BOOK_BUDGETS=alpha:50000,beta:20000   gross notional maks per book (quote, termasuk order pending)
+//   BOOK_QPS=alpha:30,beta:10             kuota burst throttle per book (default MAX_QPS)
+//
+// Allocator (di risk): setiap book punya bucket throttle & budget eksposur sendiri, sehingga
+// burst / posisi satu book tidak menghabiskan limit book lain.
+//
+use ahash::AHashMap as HashMap;
+
+pub const DEFAULT_BOOK: &str = "default";
+
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct BookCfg {
+    pub strategy_book: HashMap<String, String>, // strategi -> book
+    pub budgets: HashMap<String, f64>,          // book -> gross notional maks (quote)
+    pub qps: HashMap<String, u32>,              // book -> kuota throttle
+}
+
+impl BookCfg {
+    pub fn from_env() -> Self {
+        let var = |k: &str| std::env::var(k).unwrap_or_default();
+        let mut strategy_book = HashMap::new();
+        for (book, strategies) in var("BOOKS").split(',').filter_map(|kv| kv.split_once(':')) {
+            for s in strategies.split('+').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+                strategy_book.insert(s.to_string(), book.trim().to_string());
+            }
+        }
+        let qps = var("BOOK_QPS")
+            .split(',')
+            .filter_map(|kv| kv.split_once(':'))
+            .filter_map(|(k, v)| Some((k.trim().to_string(), v.trim().parse().ok()?)))
+            .collect();
+        Self { strategy_book, budgets: crate::sizing::parse_map(&var("BOOK_BUDGETS")), qps }
+    }
+
+    /// Book untuk strategi (default jika tidak dipetakan)
+    pub fn book_for(&self, strategy: &str) -> String {
+        self.strategy_book.get(strategy).cloned().unwrap_or_else(|| DEFAULT_BOOK.to_string())
+    }
+
+    pub fn budget(&self, book: &str) -> Option<f64> {
+        self.budgets.get(book).copied()
+    }
+
+    pub fn qps(&self, book: &str, default_qps: u32) -> u32 {
+        self.qps.get(book).copied().unwrap_or(default_qps)
+    }
+}
+
+/// Nama book efektif (Signal/ExecReport lama tanpa book -> default)
+pub fn name(book: &str) -> &str {
+    if book.is_empty() { DEFAULT_BOOK } else { book }
+}
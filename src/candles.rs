@@ -0,0 +1,107 @@
+// ===============================
+// src/candles.rs
+// ===============================
+//
+// Agregator candle (OHLC dari mid) per symbol, bucket waktu tetap berdasarkan `ts_ns` tick.
+//
+// Dipakai untuk evaluasi strategi saat candle close (STRATEGY_BAR_SECS): worker hanya memanggil
+// strategi sekali per candle dengan tick terakhir candle itu, seperti logika "on bar close" TradingView.
+// Candle dianggap close saat tick pertama dari bucket berikutnya datang (tidak ada timer; symbol
+// tanpa tick tidak menghasilkan candle kosong).
+//
+use ahash::AHashMap as HashMap;
+
+use crate::domain::EnrichedTick;
+
+/// Candle OHLC (harga mid, tick ×100)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candle {
+    pub symbol: String,
+    pub start_ns: i128,
+    pub open: i64,
+    pub high: i64,
+    pub low: i64,
+    pub close: i64,
+    pub ticks: u64,
+}
+
+impl Candle {
+    fn new(symbol: &str, start_ns: i128, mid: i64) -> Self {
+        Self { symbol: symbol.to_string(), start_ns, open: mid, high: mid, low: mid, close: mid, ticks: 1 }
+    }
+
+    fn update(&mut self, mid: i64) {
+        self.high = self.high.max(mid);
+        self.low = self.low.min(mid);
+        self.close = mid;
+        self.ticks += 1;
+    }
+}
+
+pub struct Aggregator {
+    interval_ns: i128,
+    /// candle berjalan + tick terakhirnya per symbol
+    open: HashMap<String, (Candle, EnrichedTick)>,
+}
+
+impl Aggregator {
+    pub fn new(interval_secs: u64) -> Self {
+        Self { interval_ns: interval_secs.max(1) as i128 * 1_000_000_000, open: HashMap::new() }
+    }
+
+    /// Masukkan tick; Some((candle yang baru close, tick terakhirnya)) saat tick membuka bucket baru
+    pub fn on_tick(&mut self, t: &EnrichedTick) -> Option<(Candle, EnrichedTick)> {
+        let md = &t.tick;
+        let mid = (md.best_bid + md.best_ask) / 2;
+        let start = md.ts_ns - md.ts_ns.rem_euclid(self.interval_ns);
+        match self.open.get_mut(&md.symbol) {
+            Some((c, last)) if c.start_ns == start => {
+                c.update(mid);
+                *last = t.clone();
+                None
+            }
+            // tick terlambat (bucket lebih tua): masuk ke candle berjalan
+            Some((c, _)) if start < c.start_ns => {
+                c.update(mid);
+                None
+            }
+            Some(_) => self.open.insert(md.symbol.clone(), (Candle::new(&md.symbol, start, mid), t.clone())),
+            None => {
+                self.open.insert(md.symbol.clone(), (Candle::new(&md.symbol, start, mid), t.clone()));
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::MdTick;
+
+    fn tick(ts_s: f64, mid: i64) -> EnrichedTick {
+        let md = MdTick {
+            ts_ns: (ts_s * 1e9) as i128,
+            symbol: "BTCUSDT".into(),
+            best_bid: mid - 1,
+            best_ask: mid + 1,
+            bid_qty: 0,
+            ask_qty: 0,
+            seq: 0,
+            exch_ts_ns: 0,
+        };
+        EnrichedTick { tick: md, stats: Default::default() }
+    }
+
+    #[test]
+    fn closes_candle_on_first_tick_of_next_bucket() {
+        let mut agg = Aggregator::new(60);
+        assert!(agg.on_tick(&tick(0.0, 100)).is_none());
+        assert!(agg.on_tick(&tick(30.0, 110)).is_none());
+        assert!(agg.on_tick(&tick(59.0, 95)).is_none());
+        let (c, last) = agg.on_tick(&tick(61.0, 120)).expect("candle closed");
+        assert_eq!((c.open, c.high, c.low, c.close, c.ticks), (100, 110, 95, 95, 3));
+        assert_eq!(last.tick.ts_ns, 59_000_000_000);
+        assert!(agg.on_tick(&tick(119.0, 121)).is_none());
+    }
+}
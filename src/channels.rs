@@ -0,0 +1,101 @@
+// ===============================
+// src/channels.rs
+// ===============================
+//
+// Kapasitas channel internal (sebelumnya hard-coded di main.rs) + gauge kedalaman antrean
+// per channel, supaya kapasitas bisa di-tune dari saturasi yang teramati:
+//   channel_depth{name}    : pesan yang sedang antre (sampel tiap detik; mpsc + relay supervisor dijumlah)
+//   channel_capacity{name} : kapasitas terpasang
+// Depth mendekati capacity = producer mulai tertahan (mpsc) / consumer mulai lagged (broadcast).
+//
+// Nama channel (default kapasitas):
+//   md 4096, book 4096, enriched 4096 (broadcast)
+//   signals 2048, orders 2048, multileg 256, legs 256, recorder 8192,
+//   exec 4096 (gateway -> fan-out pusat), exec_fanout 4096 (fan-out -> tiap consumer),
+//   positions 2048 (per symbol), venue_orders 1024 / venue_cmds 16 (per venue)
+//
+// ENV:
+//   CHANNEL_CAPS=md:8192,exec_fanout:2048
+//
+use ahash::AHashMap as HashMap;
+use once_cell::sync::Lazy;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::{interval, Duration};
+
+use crate::metrics::{CHANNEL_CAPACITY, CHANNEL_DEPTH};
+use crate::supervisor::Relay;
+
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct ChannelCaps {
+    caps: HashMap<String, usize>,
+}
+
+impl ChannelCaps {
+    pub fn from_env() -> Self {
+        let caps = std::env::var("CHANNEL_CAPS")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|kv| kv.split_once(':'))
+            .filter_map(|(k, v)| Some((k.trim().to_string(), v.trim().parse::<usize>().ok()?)))
+            .filter(|(_, v)| *v > 0)
+            .collect();
+        Self { caps }
+    }
+
+    /// Kapasitas channel `name` (override CHANNEL_CAPS, atau default)
+    pub fn get(&self, name: &str, default: usize) -> usize {
+        self.caps.get(name).copied().unwrap_or(default)
+    }
+}
+
+type Probe = Box<dyn Fn() -> Option<usize> + Send>;
+
+/// name -> probe kedalaman (beberapa probe dengan nama sama dijumlah)
+static PROBES: Lazy<Mutex<Vec<(String, Probe)>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+fn register(name: &str, capacity: usize, probe: Probe) {
+    CHANNEL_CAPACITY.with_label_values(&[name]).set(capacity as i64);
+    PROBES.lock().unwrap().push((name.to_string(), probe));
+}
+
+/// Pantau kedalaman mpsc (sender weak: tidak menahan channel tetap terbuka)
+pub fn watch_mpsc<T: Send + 'static>(name: &str, tx: &mpsc::Sender<T>) {
+    let weak = tx.downgrade();
+    let cap = tx.max_capacity();
+    register(name, cap, Box::new(move || weak.upgrade().map(|tx| tx.max_capacity() - tx.capacity())));
+}
+
+/// Pantau antrean internal Relay supervisor (ditambahkan ke channel asal dengan nama sama)
+pub fn watch_relay<T: Send + 'static>(name: &str, relay: &Arc<Relay<T>>) {
+    let weak = Arc::downgrade(relay);
+    PROBES.lock().unwrap().push((name.to_string(), Box::new(move || weak.upgrade().map(|r| r.depth()))));
+}
+
+/// Pantau broadcast: tick yang belum dibaca receiver paling lambat
+pub fn watch_broadcast<T: Send + 'static>(name: &str, tx: &broadcast::Sender<T>, capacity: usize) {
+    let weak = tx.downgrade();
+    register(name, capacity, Box::new(move || weak.upgrade().map(|tx| tx.len())));
+}
+
+/// Kedalaman semua channel yang dipantau saat ini (juga dipakai heartbeat / liveness)
+pub fn depths() -> BTreeMap<String, usize> {
+    let mut depth = BTreeMap::new();
+    for (name, probe) in PROBES.lock().unwrap().iter() {
+        // channel yang sudah tertutup dihitung 0
+        *depth.entry(name.clone()).or_insert(0) += probe().unwrap_or(0);
+    }
+    depth
+}
+
+/// Sampler gauge channel_depth tiap detik
+pub async fn run() {
+    let mut tick = interval(Duration::from_secs(1));
+    loop {
+        tick.tick().await;
+        for (name, d) in depths() {
+            CHANNEL_DEPTH.with_label_values(&[&name]).set(d as i64);
+        }
+    }
+}
@@ -0,0 +1,142 @@
+// ===============================
+// src/chaos.rs
+// ===============================
+//
+// Chaos testing: injeksi kegagalan dunia nyata ke pipeline untuk memvalidasi bahwa
+// risk / blotter / positions tetap benar. Hanya aktif jika di-build dengan
+// `--features chaos`; tanpa feature semua hook adalah no-op (biaya nol di build produksi).
+//
+// Titik injeksi (Point):
+//   md     : tick market data dari feed (drop / delay)
+//   signal : Signal masuk ke risk (drop / delay)
+//   exec   : ExecReport dari gateway sebelum fan-out (drop / delay / duplikat)
+//   ws     : loop WS Binance (market data & user stream) diputus paksa
+//
+// ENV (probabilitas 0.0–1.0 per pesan):
+//   CHAOS_DELAY_PROB=0.05  CHAOS_DELAY_MAX_MS=200
+//   CHAOS_DROP_PROB=0.01
+//   CHAOS_DUP_PROB=0.02          (hanya exec)
+//   CHAOS_WS_DISCONNECT_PROB=0.0005 (per frame WS)
+//   CHAOS_POINTS=md,signal,exec,ws   (default: semua)
+//
+// Setiap injeksi dihitung di `chaos_injections_total{point,kind}`.
+//
+// Tanpa feature, sebagian tipe di bawah hanya dipakai oleh implementasi chaos
+#![cfg_attr(not(feature = "chaos"), allow(dead_code))]
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Point {
+    Md,
+    Signal,
+    Exec,
+    Ws,
+}
+
+impl Point {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Point::Md => "md",
+            Point::Signal => "signal",
+            Point::Exec => "exec",
+            Point::Ws => "ws",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct ChaosCfg {
+    pub delay_prob: f64,
+    pub delay_max_ms: u64,
+    pub drop_prob: f64,
+    pub dup_prob: f64,
+    pub ws_disconnect_prob: f64,
+    pub points: Vec<String>, // kosong = semua
+}
+
+impl ChaosCfg {
+    pub fn enabled(&self) -> bool {
+        self.delay_prob > 0.0 || self.drop_prob > 0.0 || self.dup_prob > 0.0 || self.ws_disconnect_prob > 0.0
+    }
+}
+
+#[cfg(feature = "chaos")]
+mod imp {
+    use super::{ChaosCfg, Point};
+    use crate::metrics::CHAOS_INJECTIONS;
+    use once_cell::sync::OnceCell;
+    use rand::Rng;
+    use std::time::Duration;
+
+    static CHAOS: OnceCell<ChaosCfg> = OnceCell::new();
+
+    pub fn init(cfg: ChaosCfg) {
+        if cfg.enabled() {
+            tracing::warn!(?cfg, "CHAOS MODE: failure injection active");
+        }
+        let _ = CHAOS.set(cfg);
+    }
+
+    /// Lempar dadu untuk `point`; catat metric jika kena
+    fn roll(point: Point, prob: f64, kind: &str) -> bool {
+        let Some(cfg) = CHAOS.get() else { return false };
+        if prob <= 0.0 || !(cfg.points.is_empty() || cfg.points.iter().any(|p| p == point.as_str())) {
+            return false;
+        }
+        let hit = rand::thread_rng().gen_bool(prob.min(1.0));
+        if hit {
+            CHAOS_INJECTIONS.with_label_values(&[point.as_str(), kind]).inc();
+        }
+        hit
+    }
+
+    pub async fn delay(point: Point) {
+        let Some(cfg) = CHAOS.get() else { return };
+        if roll(point, cfg.delay_prob, "delay") {
+            let ms = rand::thread_rng().gen_range(0..=cfg.delay_max_ms);
+            tokio::time::sleep(Duration::from_millis(ms)).await;
+        }
+    }
+
+    pub fn drop(point: Point) -> bool {
+        CHAOS.get().is_some_and(|c| roll(point, c.drop_prob, "drop"))
+    }
+
+    pub fn duplicate(point: Point) -> bool {
+        CHAOS.get().is_some_and(|c| roll(point, c.dup_prob, "duplicate"))
+    }
+
+    pub fn ws_disconnect() -> bool {
+        CHAOS.get().is_some_and(|c| roll(Point::Ws, c.ws_disconnect_prob, "disconnect"))
+    }
+}
+
+#[cfg(not(feature = "chaos"))]
+mod imp {
+    use super::{ChaosCfg, Point};
+
+    pub fn init(cfg: ChaosCfg) {
+        if cfg.enabled() {
+            tracing::warn!("CHAOS_* set but binary built without `--features chaos`; ignored");
+        }
+    }
+    #[inline(always)]
+    pub async fn delay(_point: Point) {}
+    #[inline(always)]
+    pub fn drop(_point: Point) -> bool { false }
+    #[inline(always)]
+    pub fn duplicate(_point: Point) -> bool { false }
+    #[inline(always)]
+    pub fn ws_disconnect() -> bool { false }
+}
+
+/// Set konfigurasi chaos global (dipanggil sekali di startup)
+pub use imp::init;
+/// Tunda pesan secara acak (latency injection)
+pub use imp::delay;
+/// true = buang pesan ini
+pub use imp::drop;
+/// true = kirim pesan ini dua kali
+pub use imp::duplicate;
+/// true = putus paksa koneksi WS sekarang
+#[cfg(feature = "binance")]
+pub use imp::ws_disconnect;
@@ -0,0 +1,183 @@
+// ===============================
+// src/check_venue.rs
+// ===============================
+//
+// Subcommand `check-venue`: cek kesiapan venue Binance sebelum engine dijalankan.
+//
+//   1) API key/secret ada di env
+//   2) konektivitas + selisih jam ke server (GET /api/v3/time) dibanding recvWindow
+//   3) key diterima & boleh trading (GET /api/v3/account: canTrade, permissions SPOT/MARGIN)
+//   4) tiap symbol SYMBOLS terdaftar & TRADING (exchangeInfo)
+//   5) saldo quote asset cukup untuk order minimum (minNotional / --min-quote)
+//
+// Setiap cek dicetak [ OK ] / [WARN] / [FAIL] + saran perbaikan; exit code 1 jika ada FAIL.
+//
+use crate::account::split_symbol;
+use crate::binance::{sync_server_time, BinanceError};
+use crate::config::{Args, Limits, TradingMode};
+use crate::exchange_http::{endpoints, ExchangeHttp, HttpError};
+use crate::symbol_registry::SymbolInfo;
+
+#[derive(Default)]
+struct Report {
+    failed: usize,
+    warned: usize,
+}
+
+impl Report {
+    fn ok(&self, what: &str, detail: impl std::fmt::Display) {
+        println!("[ OK ] {what}: {detail}");
+    }
+
+    fn warn(&mut self, what: &str, detail: impl std::fmt::Display, hint: &str) {
+        self.warned += 1;
+        println!("[WARN] {what}: {detail}\n       -> {hint}");
+    }
+
+    fn fail(&mut self, what: &str, detail: impl std::fmt::Display, hint: &str) {
+        self.failed += 1;
+        println!("[FAIL] {what}: {detail}\n       -> {hint}");
+    }
+}
+
+/// Saran untuk error API yang umum saat setup
+fn hint(e: &HttpError) -> &'static str {
+    match e.binance() {
+        Some(BinanceError::Unauthorized(_)) => {
+            "key rejected: check BINANCE_API_KEY, the key's IP whitelist and that it belongs to this venue (testnet keys only work on testnet)"
+        }
+        Some(BinanceError::InvalidSignature) => "BINANCE_API_SECRET does not match BINANCE_API_KEY",
+        Some(BinanceError::InvalidTimestamp) => "local clock is off: sync it (chrony / ntpd) or raise BINANCE_RECV_WINDOW",
+        _ if e.status().is_none() => "venue unreachable: check BINANCE_REST_URL, EXCHANGE_PROXY and network access",
+        _ => "see the error above",
+    }
+}
+
+fn str_f64(v: &serde_json::Value, key: &str) -> f64 {
+    v.get(key).and_then(|x| x.as_str()).and_then(|s| s.parse::<f64>().ok()).unwrap_or(0.0)
+}
+
+/// Jalankan semua cek; true jika tidak ada FAIL
+pub async fn run(args: &Args, limits: &Limits, min_quote: f64) -> bool {
+    let mut r = Report::default();
+    let base = args.binance_rest_url.clone();
+    println!("check-venue: {base} (symbols: {})", args.symbols.join(","));
+
+    // 1) kredensial
+    let key = std::env::var("BINANCE_API_KEY").unwrap_or_default();
+    let secret = std::env::var("BINANCE_API_SECRET").unwrap_or_default();
+    let have_keys = !key.is_empty() && !secret.is_empty();
+    if have_keys {
+        r.ok("api key", format!("{}…", key.chars().take(6).collect::<String>()));
+    } else {
+        r.fail(
+            "api key",
+            "BINANCE_API_KEY / BINANCE_API_SECRET not set",
+            "set both in .env (testnet keys: https://testnet.binance.vision)",
+        );
+    }
+    let recv_window: u64 = std::env::var("BINANCE_RECV_WINDOW").ok().and_then(|s| s.parse().ok()).unwrap_or(5000);
+    let http = ExchangeHttp::new(base, key, secret, recv_window);
+
+    // 2) waktu server
+    let started = std::time::Instant::now();
+    match http.send(&endpoints::server_time(), &[]).await {
+        Ok(v) => {
+            let rtt_ms = started.elapsed().as_millis() as i64;
+            let server_ms = v.get("serverTime").and_then(|x| x.as_u64()).unwrap_or(0);
+            // selisih dikoreksi setengah RTT; offset dipakai request bertanda tangan berikutnya
+            let skew = sync_server_time(server_ms) - rtt_ms / 2;
+            let detail = format!("skew {skew} ms (rtt {rtt_ms} ms, recvWindow {recv_window} ms)");
+            if skew.unsigned_abs() >= recv_window {
+                r.fail("server time", detail, "sync the local clock (chrony / ntpd); signed requests would get -1021");
+            } else if skew.unsigned_abs() >= 1000 {
+                r.warn("server time", detail, "clock drift is large; sync the local clock (chrony / ntpd)");
+            } else {
+                r.ok("server time", detail);
+            }
+        }
+        Err(e) => r.fail("server time", &e, hint(&e)),
+    }
+
+    // 3) akun & izin
+    let mut balances: Option<serde_json::Value> = None;
+    if have_keys {
+        match http.send(&endpoints::account(), &[("omitZeroBalances", "true".to_string())]).await {
+            Ok(v) => {
+                let need = if limits.trading_mode == TradingMode::Margin { "MARGIN" } else { "SPOT" };
+                let perms: Vec<&str> = v
+                    .get("permissions")
+                    .and_then(|x| x.as_array())
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|p| p.as_str())
+                    .collect();
+                if v.get("canTrade").and_then(|x| x.as_bool()) == Some(false) {
+                    r.fail("permissions", "account canTrade=false", "enable trading on the API key (Spot & Margin Trading)");
+                } else if !perms.is_empty() && !perms.contains(&need) {
+                    r.fail("permissions", format!("{perms:?} lacks {need}"), "enable the matching trading permission on the account / API key");
+                } else {
+                    r.ok("permissions", format!("canTrade, {need}"));
+                }
+                balances = v.get("balances").cloned();
+            }
+            Err(e) => r.fail("account", &e, hint(&e)),
+        }
+    }
+
+    // 4) symbol
+    let list = serde_json::to_string(&args.symbols).unwrap_or_default();
+    let infos: Vec<(String, SymbolInfo)> = match http.send(&endpoints::exchange_info(), &[("symbols", list)]).await {
+        Ok(v) => v
+            .get("symbols")
+            .and_then(|x| x.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|s| Some((s.get("symbol")?.as_str()?.to_string(), SymbolInfo::from_json(s))))
+            .collect(),
+        Err(e) => {
+            // -1121: salah satu symbol tidak dikenal -> semua dianggap hilang, dilaporkan per symbol
+            if e.binance().map(|b| b.code()) != Some(-1121) {
+                r.fail("exchangeInfo", &e, hint(&e));
+            }
+            Vec::new()
+        }
+    };
+    for sym in &args.symbols {
+        let what = format!("symbol {sym}");
+        match infos.iter().find(|(s, _)| s == sym).map(|(_, i)| i) {
+            None => r.fail(&what, "not listed on this venue", "fix SYMBOLS (testnet lists far fewer pairs than mainnet)"),
+            Some(i) if !i.tradable() => {
+                r.fail(&what, format!("status {} (spot allowed: {})", i.status, i.spot_allowed), "remove it from SYMBOLS until it trades again")
+            }
+            Some(i) => r.ok(&what, format!("TRADING, tick {} step {} minNotional {}", i.tick_size, i.step_size, i.min_notional)),
+        }
+    }
+
+    // 5) saldo minimum
+    if let Some(bal) = balances {
+        let free = |asset: &str| {
+            bal.as_array()
+                .into_iter()
+                .flatten()
+                .find(|b| b.get("asset").and_then(|x| x.as_str()) == Some(asset))
+                .map(|b| str_f64(b, "free"))
+                .unwrap_or(0.0)
+        };
+        for (sym, info) in &infos {
+            let Some((base_asset, quote)) = split_symbol(sym) else { continue };
+            let need = info.min_notional.max(min_quote);
+            let have = free(quote);
+            let what = format!("balance {quote} ({sym})");
+            let detail = format!("free {have} {quote}, need >= {need}; {base_asset} free {}", free(base_asset));
+            if have + 1e-12 < need {
+                r.fail(&what, detail, "fund the account (testnet balances are reset periodically; re-create the key to get fresh funds)");
+            } else {
+                r.ok(&what, detail);
+            }
+        }
+    }
+
+    println!("check-venue: {} failed, {} warning(s)", r.failed, r.warned);
+    r.failed == 0
+}
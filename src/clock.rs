@@ -0,0 +1,85 @@
+// ===============================
+// src/clock.rs
+// ===============================
+//
+// Clock engine: satu referensi waktu untuk semua ts_ns yang dibuat engine (MdTick, Order,
+// ExecReport, GTT). Wall clock dibaca sekali saat start, selanjutnya maju dengan
+// `Instant` (monotonic), jadi selisih antar event tidak pernah negatif walau jam sistem
+// di-adjust NTP.
+//
+// Waktu event dari exchange (field `E` di WS) disimpan terpisah di `exch_ts_ns`, sehingga
+// latensi bisa dipisah: jaringan (ts_ns - exch_ts_ns) vs proses internal (antar ts_ns engine).
+//
+// Catatan: untuk perbandingan lintas proses / dengan REST exchange (snapshot, reconcile)
+// tetap gunakan wall clock.
+//
+// Komponen yang logikanya bergantung waktu (risk: throttle, GTT; gateway mock & Binance: GTT, ts_ns
+// report, latensi sig->ack) menerima `SharedClock`
+// agar bisa diuji dengan `MockClock` yang dimajukan manual.
+//
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+use std::time::Instant;
+
+static ANCHOR: Lazy<(i128, Instant)> =
+    Lazy::new(|| (Utc::now().timestamp_nanos_opt().unwrap_or(0) as i128, Instant::now()));
+
+/// Waktu engine (ns epoch, monotonic)
+pub fn now_ns() -> i128 {
+    let (wall, at) = *ANCHOR;
+    wall + at.elapsed().as_nanos() as i128
+}
+
+/// Waktu event exchange (ms epoch) -> ns; 0 = tidak ada
+#[cfg(feature = "binance")]
+pub fn exch_ms_to_ns(ms: Option<u64>) -> i128 {
+    ms.map(|m| m as i128 * 1_000_000).unwrap_or(0)
+}
+
+/// Sumber waktu yang bisa diinjeksi (ns epoch)
+pub trait Clock: Send + Sync {
+    fn now_ns(&self) -> i128;
+}
+
+pub type SharedClock = Arc<dyn Clock>;
+
+/// Clock engine (`now_ns`)
+pub struct EngineClock;
+
+impl Clock for EngineClock {
+    fn now_ns(&self) -> i128 {
+        now_ns()
+    }
+}
+
+pub fn engine() -> SharedClock {
+    Arc::new(EngineClock)
+}
+
+/// Clock manual untuk test: waktu hanya maju lewat `advance_ms` / `set_ns`
+#[cfg(test)]
+#[derive(Default)]
+pub struct MockClock(std::sync::Mutex<i128>);
+
+#[cfg(test)]
+impl MockClock {
+    pub fn at_ns(ns: i128) -> Arc<Self> {
+        Arc::new(Self(std::sync::Mutex::new(ns)))
+    }
+
+    pub fn set_ns(&self, ns: i128) {
+        *self.0.lock().unwrap() = ns;
+    }
+
+    pub fn advance_ms(&self, ms: i64) {
+        *self.0.lock().unwrap() += ms as i128 * 1_000_000;
+    }
+}
+
+#[cfg(test)]
+impl Clock for MockClock {
+    fn now_ns(&self) -> i128 {
+        *self.0.lock().unwrap()
+    }
+}
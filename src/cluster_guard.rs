@@ -0,0 +1,144 @@
+// ===============================
+// src/cluster_guard.rs
+// ===============================
+//
+// Guard anti-overtrading: deteksi burst order searah dari satu strategi untuk satu symbol
+// (strategi yang "lepas kendali", mis. feedback loop fill -> signal) sebelum biaya fee menumpuk.
+//
+// - Risk mencatat setiap Signal yang lolos semua cek lain per (strategi, symbol).
+// - Order berlawanan arah memutus rangkaian (market making / rebalancing normal tidak terhitung).
+// - Lebih dari CLUSTER_MAX_ORDERS order searah dalam CLUSTER_WINDOW_MS -> strategi di-mute untuk
+//   symbol itu selama CLUSTER_MUTE_SECS: risk menolak Signal-nya (RiskError::Overtrading), event dilog,
+//   dihitung di `overtrading_mutes_total{strategy}` dan dikirim sebagai alert `overtrading`.
+// - Mute berakhir sendiri; strategi & symbol lain tidak terpengaruh.
+//
+// ENV:
+//   CLUSTER_MAX_ORDERS=0      (0 = nonaktif)
+//   CLUSTER_WINDOW_MS=2000
+//   CLUSTER_MUTE_SECS=60
+//
+use ahash::AHashMap as HashMap;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+use crate::alerts::{AlertKind, Alerts};
+use crate::domain::Side;
+use crate::metrics::OVERTRADING_MUTES;
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ClusterCfg {
+    pub max_orders: usize,
+    pub window_ms: u64,
+    pub mute_secs: u64,
+}
+
+impl ClusterCfg {
+    pub fn from_env() -> Self {
+        let num = |k: &str, d: u64| std::env::var(k).ok().and_then(|s| s.parse().ok()).unwrap_or(d);
+        Self {
+            max_orders: num("CLUSTER_MAX_ORDERS", 0) as usize,
+            window_ms: num("CLUSTER_WINDOW_MS", 2_000),
+            mute_secs: num("CLUSTER_MUTE_SECS", 60),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.max_orders > 0
+    }
+}
+
+impl Default for ClusterCfg {
+    fn default() -> Self {
+        Self { max_orders: 0, window_ms: 2_000, mute_secs: 60 }
+    }
+}
+
+/// Rangkaian order searah per (strategi, symbol)
+#[derive(Default)]
+struct Burst {
+    side_buy: bool,
+    times: VecDeque<i128>,
+    muted_until: i128,
+}
+
+/// Handle guard (clone murah, dipakai task risk)
+#[derive(Clone, Default)]
+pub struct ClusterGuard {
+    cfg: ClusterCfg,
+    alerts: Alerts,
+    bursts: Arc<Mutex<HashMap<(String, String), Burst>>>,
+}
+
+impl ClusterGuard {
+    pub fn new(cfg: ClusterCfg, alerts: Alerts) -> Self {
+        Self { cfg, alerts, bursts: Arc::default() }
+    }
+
+    /// Catat order baru; Err(ns mute berakhir) jika strategi sedang / baru saja di-mute untuk symbol ini
+    pub fn admit(&self, strategy: &str, symbol: &str, side: Side, now: i128) -> Result<(), i128> {
+        if !self.cfg.enabled() {
+            return Ok(());
+        }
+        let mut g = self.bursts.lock().unwrap();
+        let b = g.entry((strategy.to_string(), symbol.to_string())).or_default();
+        if now < b.muted_until {
+            return Err(b.muted_until);
+        }
+        let buy = matches!(side, Side::Buy);
+        if buy != b.side_buy {
+            b.side_buy = buy;
+            b.times.clear();
+        }
+        let window = self.cfg.window_ms as i128 * 1_000_000;
+        while b.times.front().is_some_and(|t| now - t > window) {
+            b.times.pop_front();
+        }
+        if b.times.len() >= self.cfg.max_orders {
+            b.muted_until = now + self.cfg.mute_secs as i128 * 1_000_000_000;
+            let orders = b.times.len() + 1;
+            b.times.clear();
+            warn!(%strategy, %symbol, ?side, orders, window_ms = self.cfg.window_ms, mute_secs = self.cfg.mute_secs,
+                "overtrading: same-direction order burst, strategy muted for symbol");
+            OVERTRADING_MUTES.with_label_values(&[strategy]).inc();
+            self.alerts.notify(
+                AlertKind::Overtrading,
+                &[
+                    ("strategy", strategy.to_string()),
+                    ("symbol", symbol.to_string()),
+                    ("side", format!("{side:?}")),
+                    ("orders", orders.to_string()),
+                    ("window_ms", self.cfg.window_ms.to_string()),
+                    ("mute_secs", self.cfg.mute_secs.to_string()),
+                ],
+            );
+            return Err(b.muted_until);
+        }
+        b.times.push_back(now);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_side_burst_mutes_strategy_for_symbol() {
+        let g = ClusterGuard::new(ClusterCfg { max_orders: 3, window_ms: 1_000, mute_secs: 10 }, Alerts::default());
+        let ms = 1_000_000i128;
+        for i in 0..3 {
+            assert!(g.admit("mm", "BTCUSDT", Side::Buy, i * 100 * ms).is_ok());
+        }
+        // arah berlawanan memutus rangkaian
+        assert!(g.admit("mm", "BTCUSDT", Side::Sell, 300 * ms).is_ok());
+        for i in 4..6 {
+            assert!(g.admit("mm", "BTCUSDT", Side::Sell, i * 100 * ms).is_ok());
+        }
+        assert!(g.admit("mm", "BTCUSDT", Side::Sell, 600 * ms).is_err());
+        // strategi / symbol lain tidak terpengaruh; mute berakhir setelah CLUSTER_MUTE_SECS
+        assert!(g.admit("mm", "ETHUSDT", Side::Sell, 800 * ms).is_ok());
+        assert!(g.admit("mm", "BTCUSDT", Side::Buy, 5_000 * ms).is_err());
+        assert!(g.admit("mm", "BTCUSDT", Side::Buy, 10_600 * ms).is_ok());
+    }
+}
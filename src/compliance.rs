@@ -0,0 +1,82 @@
+// ===============================
+// src/compliance.rs
+// ===============================
+//
+// Pre-trade compliance: aturan organisasi dari file JSON, dievaluasi risk sebelum limit lain.
+//
+// COMPLIANCE_FILE=compliance.json
+// {
+//   "deny_symbols": ["LUNAUSDT"],
+//   "max_order_qty": { "BTCUSDT": 0.5, "*": 1000 },          // unit base per order
+//   "max_order_notional": { "*": 5000 }                     // quote per order
+// }
+// Kunci "*" berlaku untuk semua symbol yang tidak disebut eksplisit.
+//
+// Setiap penolakan berisi alasan terstruktur (rule, batas, nilai) dan dicatat ke audit log
+// (recorder: `Event::Compliance`) + `compliance_rejects_total{rule}`.
+// File yang diset tapi tidak valid = engine menolak start (fail closed).
+//
+use std::collections::{HashMap, HashSet};
+use serde::Deserialize;
+
+use crate::domain::{ComplianceReject, Side};
+use crate::sizing;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ComplianceRules {
+    #[serde(default)]
+    pub deny_symbols: HashSet<String>,
+    #[serde(default)]
+    pub max_order_qty: HashMap<String, f64>,
+    #[serde(default)]
+    pub max_order_notional: HashMap<String, f64>,
+}
+
+impl ComplianceRules {
+    /// Muat dari file; panic jika file diset tapi tidak bisa dibaca / di-parse
+    pub fn load(path: &str) -> Self {
+        let raw = std::fs::read_to_string(path).unwrap_or_else(|e| panic!("compliance: read {} failed: {}", path, e));
+        let mut rules: Self = serde_json::from_str(&raw).unwrap_or_else(|e| panic!("compliance: parse {} failed: {}", path, e));
+        rules.deny_symbols = rules.deny_symbols.iter().map(|s| s.to_ascii_uppercase()).collect();
+        tracing::info!(
+            %path,
+            deny = rules.deny_symbols.len(),
+            qty_caps = rules.max_order_qty.len(),
+            notional_caps = rules.max_order_notional.len(),
+            "compliance rules loaded"
+        );
+        rules
+    }
+
+    fn cap(map: &HashMap<String, f64>, symbol: &str) -> Option<f64> {
+        map.get(symbol).or_else(|| map.get("*")).copied()
+    }
+
+    /// Cek order (`qty` dalam lot); Err berisi alasan terstruktur
+    pub fn check(&self, ts_ns: i128, symbol: &str, strategy: &str, side: Side, px: i64, qty: i64) -> Result<(), Box<ComplianceReject>> {
+        let reject = |rule: &str, limit: f64, actual: f64| Box::new(ComplianceReject {
+            ts_ns,
+            rule: rule.to_string(),
+            symbol: symbol.to_string(),
+            strategy: strategy.to_string(),
+            side,
+            px,
+            qty,
+            limit,
+            actual,
+        });
+        if self.deny_symbols.contains(symbol) {
+            return Err(reject("deny_symbol", 0.0, 0.0));
+        }
+        let base = sizing::base_qty(symbol, qty);
+        if let Some(cap) = Self::cap(&self.max_order_qty, symbol).filter(|cap| base > *cap + 1e-12) {
+            return Err(reject("max_order_qty", cap, base));
+        }
+        let notional = base * px as f64 / 100.0;
+        if let Some(cap) = Self::cap(&self.max_order_notional, symbol).filter(|cap| notional > *cap + 1e-9) {
+            return Err(reject("max_order_notional", cap, notional));
+        }
+        Ok(())
+    }
+}
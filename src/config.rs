@@ -21,10 +21,146 @@ Summary : Streams multi-symbol market data (mock/Binance), runs pluggable
 =============================================================================
 */
 use std::env;
+use clap::{Parser, Subcommand};
 use dotenvy::dotenv;
 
+/// Argumen command line (konfigurasi lain tetap via ENV)
+#[derive(Parser, Debug, Clone)]
+#[command(version, about = "dma_bot_rust — low-latency async crypto trading engine")]
+pub struct Cli {
+    /// Pulihkan state (window strategi, posisi, order working) dari SNAPSHOT_FILE
+    #[arg(long)]
+    pub warm_start: bool,
+
+    /// Cetak konfigurasi efektif (JSON, rahasia disamarkan) lalu keluar tanpa menjalankan engine
+    #[arg(long)]
+    pub print_config: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Subcommand utilitas (tanpa subcommand = jalankan engine)
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Cetak dashboard Grafana (JSON siap import) ke stdout
+    GrafanaDashboard,
+    /// Baca rekaman JSONL: ringkasan per tipe/symbol, atau cetak event terfilter (--print)
+    ReadRecording {
+        /// file rekaman (RECORD_FILE; .gz / .zst / direktori segmen), beberapa = digabung urut waktu
+        #[arg(num_args = 1.., required = true)]
+        files: Vec<String>,
+        /// tipe event, comma separated: md,sig,ord,exec,note,compliance
+        #[arg(long, value_delimiter = ',')]
+        kind: Vec<String>,
+        #[arg(long)]
+        symbol: Option<String>,
+        /// ts_ns minimum (inklusif)
+        #[arg(long)]
+        since: Option<i128>,
+        /// ts_ns maksimum (eksklusif)
+        #[arg(long)]
+        until: Option<i128>,
+        /// cetak event (JSONL) alih-alih ringkasan
+        #[arg(long)]
+        print: bool,
+    },
+    /// Export fill ke CSV untuk pajak / akuntansi (timestamp, symbol, side, qty, price, fee, venue)
+    ExportFills {
+        /// sumber fill
+        #[arg(long, value_enum, default_value = "blotter")]
+        source: crate::tax_export::Source,
+        /// file sumber (default: BLOTTER_FILE / RECORD_FILE)
+        #[arg(long)]
+        file: Option<String>,
+        /// file output (default stdout)
+        #[arg(long)]
+        out: Option<String>,
+        #[arg(long, value_enum, default_value = "normalized")]
+        format: crate::tax_export::Format,
+        /// ts_ns minimum (inklusif)
+        #[arg(long)]
+        since: Option<i128>,
+        /// ts_ns maksimum (eksklusif)
+        #[arg(long)]
+        until: Option<i128>,
+    },
+    /// Bangun ulang posisi & order terbuka dari rekaman event (replay reducer deterministik), cetak JSON
+    RebuildState {
+        /// file rekaman (default RECORD_FILE; .gz / .zst / direktori segmen)
+        #[arg(long)]
+        from: Option<String>,
+        /// ts_ns maksimum (eksklusif)
+        #[arg(long)]
+        until: Option<i128>,
+        /// file output (default stdout)
+        #[arg(long)]
+        out: Option<String>,
+        /// file checkpoint: ditulis berkala, run berikutnya melanjutkan dari sini; dihapus setelah selesai
+        #[arg(long)]
+        checkpoint: Option<String>,
+        /// interval checkpoint (baris rekaman)
+        #[arg(long, default_value_t = 1_000_000)]
+        checkpoint_every: u64,
+        /// worker paralel: proses subset symbol `i/n`, gabungkan hasilnya dengan merge-state
+        #[arg(long)]
+        shard: Option<crate::rebuild::Shard>,
+    },
+    /// Gabungkan output rebuild-state per shard menjadi satu state
+    MergeState {
+        /// output rebuild-state --shard i/n
+        #[arg(required = true, num_args = 1..)]
+        files: Vec<String>,
+        /// file output (default stdout)
+        #[arg(long)]
+        out: Option<String>,
+    },
+    /// Bandingkan posisi antar sumber (snapshot live / exchange JSON / rebuild-state), laporkan drift per symbol & venue
+    DiffPositions {
+        /// file posisi; yang pertama = referensi
+        #[arg(num_args = 2.., required = true)]
+        files: Vec<String>,
+        /// ambang drift lot per symbol, `*` = default (override POS_DRIFT_MAX)
+        #[arg(long)]
+        max_drift: Option<String>,
+    },
+    /// Cek venue Binance sebelum start: API key & izin, selisih jam server, symbol TRADING, saldo minimum
+    #[cfg(feature = "binance")]
+    CheckVenue {
+        /// saldo quote minimum per symbol (selain minNotional exchange)
+        #[arg(long, default_value_t = 0.0)]
+        min_quote: f64,
+    },
+    /// Benchmark transport MD: tokio broadcast vs ring buffer SPMC per symbol (latensi & drop)
+    BenchMdBus {
+        /// jumlah tick yang dipublish (dibagi rata ke semua symbol)
+        #[arg(long, default_value_t = 1_000_000)]
+        ticks: u64,
+        /// jumlah reader
+        #[arg(long, default_value_t = 4)]
+        consumers: usize,
+        /// jumlah symbol (satu producer per symbol, seperti task feed)
+        #[arg(long, default_value_t = 2)]
+        symbols: usize,
+        #[arg(long, default_value_t = 4096)]
+        capacity: usize,
+        /// tick/s per symbol (0 = secepatnya, memperlihatkan perilaku drop)
+        #[arg(long, default_value_t = 0)]
+        rate: u64,
+    },
+    /// Benchmark fan-out ExecReport: clone per consumer vs Arc bersama (alokasi: --features alloc-count)
+    BenchExecFanout {
+        #[arg(long, default_value_t = 200_000)]
+        reports: u64,
+        /// jumlah consumer (fan-out engine: 7)
+        #[arg(long, default_value_t = 7)]
+        consumers: usize,
+    },
+}
+
 /// Mode sumber market data / venue trading
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum MarketMode {
     Mock,
     BinanceSandbox,
@@ -59,20 +195,90 @@ impl MarketMode {
     }
 }
 
+/// Mode trading: spot (long-only, jual sebatas kepemilikan) vs margin (short diizinkan)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TradingMode {
+    Spot,
+    Margin,
+}
+
+impl TradingMode {
+    pub fn from_env(key: &str, default_mode: TradingMode) -> TradingMode {
+        match env::var(key).unwrap_or_default().to_ascii_lowercase().as_str() {
+            "spot" => TradingMode::Spot,
+            "margin" | "futures" => TradingMode::Margin,
+            _ => default_mode,
+        }
+    }
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TradingMode::Spot => "spot",
+            TradingMode::Margin => "margin",
+        }
+    }
+}
+
+/// Peran proses (mode multi-proses via IPC)
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessRole {
+    All,  // semua komponen dalam satu proses
+    Feed, // feed + strategi
+    Exec, // risk + router + gateway + positions
+}
+
+impl ProcessRole {
+    pub fn from_env(key: &str) -> ProcessRole {
+        match env::var(key).unwrap_or_default().to_ascii_lowercase().as_str() {
+            "feed" => ProcessRole::Feed,
+            "exec" => ProcessRole::Exec,
+            _ => ProcessRole::All,
+        }
+    }
+    pub fn runs_feed(&self) -> bool { matches!(self, ProcessRole::All | ProcessRole::Feed) }
+    pub fn runs_exec(&self) -> bool { matches!(self, ProcessRole::All | ProcessRole::Exec) }
+}
+
 // ===== Strategi =====
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum StrategyMode {
     MeanReversion,
+    #[serde(rename = "ma_crossover")]
     MACrossover,
     VolBreakout,
+    Bollinger,
+    Donchian,
+    Python, // script eksternal lewat py_bridge (PY_STRATEGY_CMD)
 }
 
 impl StrategyMode {
+    /// Nama kanonik (label metrics, atribusi Signal, kunci snapshot)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StrategyMode::MeanReversion => "mean_reversion",
+            StrategyMode::MACrossover => "ma_crossover",
+            StrategyMode::VolBreakout => "vol_breakout",
+            StrategyMode::Bollinger => "bollinger",
+            StrategyMode::Donchian => "donchian",
+            StrategyMode::Python => "python",
+        }
+    }
+
+    /// Strategi yang exit-nya butuh umpan balik posisi (`on_position`); posisi hanya ada di proses exec
+    pub fn needs_positions(&self) -> bool {
+        matches!(self, StrategyMode::Donchian)
+    }
+
     pub fn parse_one(s: &str) -> Option<Self> {
         match s.trim().to_ascii_lowercase().as_str() {
             "mean_reversion" | "meanreversion" | "mr" => Some(StrategyMode::MeanReversion),
             "ma_crossover"  | "macrossover"  | "ma"  => Some(StrategyMode::MACrossover),
             "vol_breakout"  | "volbreakout"  | "vb"  => Some(StrategyMode::VolBreakout),
+            "bollinger"     | "bb"                   => Some(StrategyMode::Bollinger),
+            "donchian"      | "dc"                   => Some(StrategyMode::Donchian),
+            "python"        | "py"                   => Some(StrategyMode::Python),
             _ => None,
         }
     }
@@ -83,7 +289,7 @@ impl StrategyMode {
         if let Ok(val) = env::var(env_key_list) {
             let mut out: Vec<Self> = val
                 .split(',')
-                .filter_map(|t| Self::parse_one(t))
+                .filter_map(Self::parse_one)
                 .collect();
             out.dedup();
             if !out.is_empty() {
@@ -100,37 +306,176 @@ impl StrategyMode {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize)]
 pub struct Args {
     // symbol
+    #[allow(dead_code)]
     pub data_source: String, // legacy; tidak wajib digunakan
     pub symbol: String,      // primary symbol (untuk snapshot router)
     pub symbols: Vec<String>, // multi-symbol feed/positions
 
     // files/metrics
     pub record_file: Option<String>,
-    pub metrics_port: u16,
+    pub record: crate::recorder::RecordCfg, // filter tipe event + sampling MD recorder
+    pub heartbeat_secs: u64,            // interval log / note recorder / file liveness heartbeat
+    pub liveness_file: Option<String>,  // file JSON heartbeat untuk watchdog eksternal (mtime)
+    pub metrics_server: crate::metrics_server::MetricsServerCfg, // bind / auth / TLS server metrics
+    pub blotter_file: Option<String>, // persist fill blotter (JSONL), opsional
+    pub blotter_max_fills: usize,     // kapasitas blotter di memori
+    pub recon_interval_secs: u64,     // rekonsiliasi vs exchange (0 = nonaktif)
+    pub recon_lookback_secs: u64,
+    pub account_poll_secs: u64,       // poll saldo Binance + cek saldo di risk (0 = nonaktif)
+    pub symbol_refresh_secs: u64,     // refresh exchangeInfo (status/filter symbol) (0 = nonaktif)
+    pub feed_stale_ms: u64,           // router tolak order jika MD symbol lebih tua dari ini (0 = nonaktif)
+    pub compliance_file: Option<String>, // aturan compliance JSON (deny-list / hard cap)
+    pub risk_script_file: Option<String>, // filter pre-trade rhai (hot reload)
+    pub risk_script_reload_secs: u64,
+    pub venue_health: crate::venue_health::HealthCfg, // ambang safe mode venue
+    pub chaos: crate::chaos::ChaosCfg, // injeksi kegagalan (butuh --features chaos)
+    pub reconnect: crate::reconnect::Policy, // backoff reconnect loop jaringan (feed, user stream, ipc)
+    pub snapshot_file: Option<String>, // snapshot state (bincode), kosong = nonaktif
+    pub snapshot_interval_secs: u64,
+    pub snapshot_max_age_secs: u64,   // batas umur window strategi saat warm start
+    pub warm_start: bool,             // CLI --warm-start
 
     // market mode
     pub feed_mode: MarketMode,
+    pub mock_scenario: crate::mock_scenario::Scenario, // skenario harga feed mock
+    pub mock_book_levels: usize, // depth BookUpdate feed mock (0 = tanpa depth)
+    pub mock_resting: bool,      // venue mock: order limit resting sampai MD menembus harganya
+    pub md_filter: crate::md_filter::FilterCfg, // buang quote crossed / nol / bad print di feed
+    pub fx: crate::fx::FxCfg,                   // kurs silang quote asset -> USD
     pub venue_mode: MarketMode,
+    pub venue_symbols: ahash::AHashMap<String, ahash::AHashSet<String>>, // whitelist symbol per venue (router)
     pub binance_ws_url: String,
     pub binance_rest_url: String,
+    #[serde(serialize_with = "crate::effective_config::url_opt")]
+    pub exchange_proxy: Option<String>,     // http(s)/socks5 proxy untuk REST & WS
+    pub exchange_ca_bundle: Option<String>, // PEM CA tambahan (TLS corporate)
+    pub exchange_log_file: Option<String>,  // log request/response exchange (rahasia disamarkan)
+    pub dead_letter_file: Option<String>,   // event yang tidak terkirim + alasannya (JSONL)
+
+    // multi-proses
+    pub process_role: ProcessRole,
+    pub ipc_socket: String,
 
     // strategy selection
     pub strategy_modes: Vec<StrategyMode>, // bisa lebih dari satu
     pub strategy_workers: u32,             // worker per strategi
+    pub strategy_tags: Vec<String>,        // tag bebas yang ditempel ke setiap Signal
+    pub strategy_quote_notional: ahash::AHashMap<String, f64>, // ukuran per trade (quote) per strategi
+    pub strategy_max_tps: ahash::AHashMap<String, f64>,        // konflasi MD: tick/s maks per symbol per strategi
+    pub strategy_bar_secs: ahash::AHashMap<String, u64>,       // evaluasi bar-close: durasi candle (detik) per strategi
+    pub quote_throttle: crate::quote_throttle::ThrottleCfg, // limit churn order per (venue, symbol)
+    pub peg: crate::router::PegCfg,                         // pegging harga child ke quote terkini
+    pub oms: crate::oms::OmsCfg,                            // timeout partial fill + re-route sisa
+    pub scoreboard: crate::scoreboard::ScoreCfg,            // window evaluasi + auto-disable strategi
+    pub alloc: crate::allocator::AllocCfg,                  // bagi total notional ke strategi aktif
+    pub vol_target: crate::vol_target::VolTargetCfg,        // skala ukuran global dari target vol portofolio
+    pub bollinger: crate::strategy::BollingerCfg,           // window / k / cooldown strategi Bollinger
+    pub donchian: crate::strategy::DonchianCfg,             // channel + trailing stop ATR strategi Donchian
+    pub py_bridge: crate::py_bridge::PyBridgeCfg,           // script strategi Python (STRATEGIES=python)
+    pub md_anomaly: crate::md_anomaly::AnomalyCfg,          // deteksi flicker / osilasi spread per symbol
+    pub holding: crate::holding::HoldingCfg,                // batas lama memegang posisi per strategi + force-exit
+    pub portfolio_stop: crate::portfolio_stop::PortfolioStopCfg, // drawdown portofolio -> cancel-all + flatten + kunci
+    pub trading_day: crate::trading_day::TradingDayCfg,      // batas hari trading (UTC / lokal bursa) + rekap harian
+    pub metric_alarms: crate::metric_alarms::AlarmCfg,       // alarm laju ticks / signals / rejects vs baseline bergulir
+    pub dca: crate::dca::DcaCfg,                            // pembelian notional tetap terjadwal (cron)
+    #[cfg(feature = "admin-api")]
+    pub webhook: crate::webhook::WebhookCfg,                // signal manual dari alert TradingView
+    pub alerts: crate::alerts::AlertCfg,                    // notifikasi email (SMTP)
+    pub cluster: crate::cluster_guard::ClusterCfg,          // guard burst order searah per (strategi, symbol)
+    #[cfg(feature = "pushgateway")]
+    pub push: crate::pushgateway::PushCfg,                  // push metrics ke Pushgateway (run pendek / batch)
+    pub metric_labels: crate::metrics::LabelCfg,            // batas kardinalitas label + family nonaktif
+    pub sim_latency: crate::sim_latency::SimLatencyCfg,     // latensi order/fill + skew clock venue mock
+    pub slippage: crate::slippage::SlippageCfg,              // model slippage per symbol di venue mock
+    pub md_bus: crate::md_ring::MdBusCfg,                    // transport MD ke strategi (broadcast / ring)
+    pub pipeline: crate::pipeline::PipelineCfg,              // bus bersama / pipeline per symbol
+    pub channel_caps: crate::channels::ChannelCaps,          // kapasitas channel internal (CHANNEL_CAPS)
+    pub mleg_hedge: crate::multileg::HedgeCfg,              // remediasi legging order multi-leg
+    pub books: crate::books::BookCfg,      // book (tenant) per strategi + budget risk per book
+    pub sizing: crate::sizing::SizingCfg,  // lot size per symbol
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize)]
 pub struct Limits {
     pub max_notional: i64,
     pub px_min: i64,
     pub px_max: i64,
     pub max_qps: u32,
+    pub order_ttl_ms: u64, // good-till-time untuk setiap order (0 = GTC)
+    pub order_quote_notional: f64, // ukuran default dalam quote (0 = pakai qty strategi)
+    pub trading_mode: TradingMode,
+    pub max_leverage: f64,  // margin: notional short maks = max_leverage * margin_equity
+    pub margin_equity: f64, // ekuitas margin dalam quote (0 = tanpa batas leverage)
+    pub maint_margin_rate: f64,    // rasio maintenance margin (estimasi harga likuidasi)
+    pub max_margin_usage_pct: f64, // blok order jika usage initial margin > ini (0 = off)
+    pub venue_max_position: ahash::AHashMap<String, i64>, // |posisi| maks per (venue, symbol) dalam lot
+    pub correlation_groups: Vec<CorrelationGroup>, // cap eksposur searah gabungan per grup symbol berkorelasi
+    pub inv_bands: ahash::AHashMap<String, InvBand>, // target inventory + band soft/hard per symbol (lot)
+}
+
+/// Grup symbol berkorelasi (mis. BTC/ETH) dengan cap notional searah gabungan (quote)
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct CorrelationGroup {
+    pub name: String,
+    pub symbols: Vec<String>,
+    pub max_notional: f64,
 }
 
-pub fn load() -> (Args, Limits) {
+impl CorrelationGroup {
+    /// Parse "majors:BTCUSDT+ETHUSDT:50000,alts:SOLUSDT+BNBUSDT:20000"
+    pub fn parse_many(s: &str) -> Vec<Self> {
+        s.split(',')
+            .filter_map(|g| {
+                let mut it = g.trim().splitn(3, ':');
+                let (name, syms, cap) = (it.next()?.trim(), it.next()?, it.next()?.trim().parse::<f64>().ok()?);
+                let symbols: Vec<String> =
+                    syms.split('+').map(|x| x.trim().to_ascii_uppercase()).filter(|x| !x.is_empty()).collect();
+                (!name.is_empty() && !symbols.is_empty() && cap > 0.0)
+                    .then(|| Self { name: name.to_string(), symbols, max_notional: cap })
+            })
+            .collect()
+    }
+}
+
+/// Band inventory per symbol (lot): di luar `soft` router makin condong ke venue yang mengurangi
+/// eksposur; di luar `hard` risk menolak order yang menambah eksposur
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize)]
+pub struct InvBand {
+    pub target: i64,
+    pub soft: i64,
+    pub hard: i64,
+}
+
+impl InvBand {
+    /// Parse "BTCUSDT:0:50:100,ETHUSDT:10:20:40" (symbol:target:soft:hard)
+    pub fn parse_many(s: &str) -> ahash::AHashMap<String, Self> {
+        s.split(',')
+            .filter_map(|b| {
+                let f: Vec<&str> = b.trim().split(':').map(str::trim).collect();
+                let [sym, target, soft, hard] = f.as_slice() else { return None };
+                let (target, soft, hard) = (target.parse().ok()?, soft.parse::<i64>().ok()?, hard.parse::<i64>().ok()?);
+                (!sym.is_empty() && soft >= 0 && hard >= soft).then(|| (sym.to_ascii_uppercase(), Self { target, soft, hard }))
+            })
+            .collect()
+    }
+
+    /// Pengali bias router: 1 di dalam band soft, naik linear ke 3 di tepi band hard (dan terus naik di luarnya)
+    pub fn pressure(&self, qty: i64) -> f64 {
+        let excess = ((qty - self.target).abs() - self.soft).max(0);
+        1.0 + 2.0 * excess as f64 / (self.hard - self.soft).max(1) as f64
+    }
+
+    /// Posisi `before` -> `after` menjauh dari target dan berakhir di luar band hard
+    pub fn blocks(&self, before: i64, after: i64) -> bool {
+        let (b, a) = ((before - self.target).abs(), (after - self.target).abs());
+        a > b && a > self.hard
+    }
+}
+
+pub fn load(cli: &Cli) -> (Args, Limits) {
     // Pastikan .env dibaca (agar RECORD_FILE, SYMBOLS, dll ter-load)
     let _ = dotenv();
 
@@ -152,20 +497,124 @@ pub fn load() -> (Args, Limits) {
         .unwrap_or_else(|| vec![symbol.clone()]);
 
     let record_file  = env::var("RECORD_FILE").ok();
-    let metrics_port = env::var("METRICS_PORT")
+    let heartbeat_secs = env::var("HEARTBEAT_SECS").ok().and_then(|s| s.parse::<u64>().ok()).unwrap_or(1).max(1);
+    let liveness_file = env::var("LIVENESS_FILE").ok().filter(|s| !s.is_empty());
+    let blotter_file = env::var("BLOTTER_FILE").ok().filter(|s| !s.is_empty());
+    let blotter_max_fills = env::var("BLOTTER_MAX_FILLS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(100_000);
+    let recon_interval_secs = env::var("RECON_INTERVAL_SECS")
         .ok()
         .and_then(|s| s.parse().ok())
-        .unwrap_or(9898);
+        .unwrap_or(60);
+    let recon_lookback_secs = env::var("RECON_LOOKBACK_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(3600);
+
+    let env_num = |k: &str, d: u64| env::var(k).ok().and_then(|s| s.parse().ok()).unwrap_or(d);
+    let account_poll_secs = env_num("ACCOUNT_POLL_SECS", 10);
+    let symbol_refresh_secs = env_num("SYMBOL_REFRESH_SECS", 300);
+    let feed_stale_ms = env_num("FEED_STALE_MS", 10_000);
+    let compliance_file = env::var("COMPLIANCE_FILE").ok().filter(|s| !s.is_empty());
+    let risk_script_file = env::var("RISK_SCRIPT_FILE").ok().filter(|s| !s.is_empty());
+    let risk_script_reload_secs = env_num("RISK_SCRIPT_RELOAD_SECS", 2);
+    let venue_health = crate::venue_health::HealthCfg {
+        window_secs: env_num("VENUE_HEALTH_WINDOW_SECS", 30),
+        max_rejects: env_num("VENUE_MAX_REJECTS", 20) as usize,
+        max_http_5xx: env_num("VENUE_MAX_5XX", 3) as usize,
+        max_clock_errors: env_num("VENUE_MAX_CLOCK_ERRORS", 3) as usize,
+        ws_silence_secs: env_num("VENUE_WS_SILENCE_SECS", 300),
+        resume_secs: env_num("VENUE_RESUME_SECS", 60),
+    };
+
+    // ===== Chaos testing =====
+    let env_prob = |k: &str| env::var(k).ok().and_then(|s| s.parse::<f64>().ok()).unwrap_or(0.0);
+    let chaos = crate::chaos::ChaosCfg {
+        delay_prob: env_prob("CHAOS_DELAY_PROB"),
+        delay_max_ms: env_num("CHAOS_DELAY_MAX_MS", 200),
+        drop_prob: env_prob("CHAOS_DROP_PROB"),
+        dup_prob: env_prob("CHAOS_DUP_PROB"),
+        ws_disconnect_prob: env_prob("CHAOS_WS_DISCONNECT_PROB"),
+        points: env::var("CHAOS_POINTS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|s| s.trim().to_ascii_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect(),
+    };
+
+    // ===== Reconnect =====
+    //   RECONNECT_BASE_MS=500 RECONNECT_MAX_MS=32000 RECONNECT_MAX_ATTEMPTS=0 (0 = tanpa batas)
+    //   RECONNECT_RESET_SECS=30 RECONNECT_JITTER=none|full|equal|fixed:250
+    let reconnect_default = crate::reconnect::Policy::default();
+    let reconnect = crate::reconnect::Policy {
+        base_ms: env_num("RECONNECT_BASE_MS", reconnect_default.base_ms),
+        max_ms: env_num("RECONNECT_MAX_MS", reconnect_default.max_ms),
+        max_attempts: env_num("RECONNECT_MAX_ATTEMPTS", 0) as u32,
+        reset_after: std::time::Duration::from_secs(env_num("RECONNECT_RESET_SECS", 30)),
+        jitter: env::var("RECONNECT_JITTER")
+            .ok()
+            .and_then(|s| crate::reconnect::Jitter::parse(&s))
+            .unwrap_or(reconnect_default.jitter),
+    };
+
+    // ===== Snapshot / warm restart =====
+    let snapshot_file = env::var("SNAPSHOT_FILE").ok().filter(|s| !s.is_empty());
+    let snapshot_interval_secs = env_num("SNAPSHOT_INTERVAL_SECS", 10);
+    let snapshot_max_age_secs = env_num("SNAPSHOT_MAX_AGE_SECS", 300);
 
     // ===== Mode =====
     let feed_mode  = MarketMode::from_env("FEED_MODE",  MarketMode::Mock);
     let venue_mode = MarketMode::from_env("VENUE_MODE", MarketMode::Mock);
+    let mock_scenario = crate::mock_scenario::Scenario::from_env("MOCK_SCENARIO");
+    let mock_book_levels = env_num("MOCK_BOOK_LEVELS", 0) as usize;
+    // MOCK_RESTING=0 -> venue mock mengisi order limit instan di harga limit (perilaku lama)
+    let mock_resting = env::var("MOCK_RESTING").map(|v| v != "0" && !v.eq_ignore_ascii_case("false")).unwrap_or(true);
+
+    // MD_FILTER=1 MD_MAX_JUMP_PCT=5 MD_SMOOTH_ALPHA=0.2 MD_JUMP_RESYNC=5
+    let env_f64 = |k: &str, d: f64| env::var(k).ok().and_then(|s| s.parse().ok()).unwrap_or(d);
+    let md_filter_default = crate::md_filter::FilterCfg::default();
+    let md_filter = crate::md_filter::FilterCfg {
+        enabled: env::var("MD_FILTER").map(|v| v != "0" && !v.eq_ignore_ascii_case("false")).unwrap_or(true),
+        max_jump_pct: env_f64("MD_MAX_JUMP_PCT", md_filter_default.max_jump_pct),
+        smooth_alpha: env_f64("MD_SMOOTH_ALPHA", md_filter_default.smooth_alpha),
+        jump_resync: env_num("MD_JUMP_RESYNC", md_filter_default.jump_resync as u64) as u32,
+    };
+
+    // FX_USD_ASSETS=USDT,USDC,FDUSD,BUSD,TUSD,USD FX_RATES=EUR:1.08,TRY:0.03
+    let fx_default = crate::fx::FxCfg::default();
+    let fx = crate::fx::FxCfg {
+        usd_assets: env::var("FX_USD_ASSETS")
+            .map(|v| v.split(',').map(|s| s.trim().to_uppercase()).filter(|s| !s.is_empty()).collect())
+            .unwrap_or(fx_default.usd_assets),
+        static_rates: env::var("FX_RATES").map(|v| crate::sizing::parse_map(&v)).unwrap_or_default(),
+    };
 
     let binance_ws_url = env::var("BINANCE_WS_URL")
         .unwrap_or_else(|_| feed_mode.default_ws_url().to_string());
     let binance_rest_url = env::var("BINANCE_REST_URL")
         .unwrap_or_else(|_| venue_mode.default_rest_url().to_string());
 
+    let exchange_proxy = env::var("EXCHANGE_PROXY").ok().filter(|s| !s.is_empty());
+    let exchange_ca_bundle = env::var("EXCHANGE_CA_BUNDLE").ok().filter(|s| !s.is_empty());
+    let exchange_log_file = env::var("EXCHANGE_LOG_FILE").ok().filter(|s| !s.is_empty());
+    let dead_letter_file = env::var("DEAD_LETTER_FILE").ok().filter(|s| !s.is_empty());
+    // VENUE_SYMBOLS=A:BTCUSDT+ETHUSDT,binance:BTCUSDT (venue tidak disebut = semua symbol)
+    let venue_symbols = env::var("VENUE_SYMBOLS")
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|kv| kv.split_once(':'))
+        .map(|(v, syms)| (v.trim().to_string(), syms.split('+').map(|s| s.trim().to_uppercase()).filter(|s| !s.is_empty()).collect()))
+        .filter(|(v, _): &(String, ahash::AHashSet<String>)| !v.is_empty())
+        .collect();
+
+    // ===== Multi-proses =====
+    //   PROCESS_ROLE=all|feed|exec, IPC_SOCKET=/tmp/dma_bot.sock
+    let process_role = ProcessRole::from_env("PROCESS_ROLE");
+    let ipc_socket = env::var("IPC_SOCKET").unwrap_or_else(|_| "/tmp/dma_bot.sock".to_string());
+
     // ===== Strategy selection =====
     // Contoh:
     //   STRATEGY=ma_crossover
@@ -181,18 +630,107 @@ pub fn load() -> (Args, Limits) {
         .and_then(|s| s.parse().ok())
         .unwrap_or(2);
 
+    // STRATEGY_TAGS=book=alpha,desk=crypto
+    let strategy_tags: Vec<String> = env::var("STRATEGY_TAGS")
+        .unwrap_or_default()
+        .split(',')
+        .map(|x| x.trim().to_string())
+        .filter(|x| !x.is_empty())
+        .collect();
+
+    // STRATEGY_QUOTE_NOTIONAL=mean_reversion:50 ; LOT_SIZES=BTCUSDT:0.00001 ; TICK_SIZES=BTCUSDT:0.01
+    let strategy_quote_notional = crate::sizing::parse_map(&env::var("STRATEGY_QUOTE_NOTIONAL").unwrap_or_default());
+    // STRATEGY_MAX_TPS=ma_crossover:10 (konflasi, lihat conflate.rs)
+    let strategy_max_tps = crate::sizing::parse_map(&env::var("STRATEGY_MAX_TPS").unwrap_or_default());
+    // STRATEGY_BAR_SECS=ma_crossover:60 (strategi dievaluasi saat candle close, lihat candles.rs)
+    let strategy_bar_secs = crate::sizing::parse_map(&env::var("STRATEGY_BAR_SECS").unwrap_or_default())
+        .into_iter()
+        .map(|(k, v)| (k, v as u64))
+        .filter(|(_, v)| *v > 0)
+        .collect();
+    let sizing = crate::sizing::SizingCfg {
+        lot_sizes: crate::sizing::parse_map(&env::var("LOT_SIZES").unwrap_or_default()),
+        tick_sizes: crate::sizing::parse_map(&env::var("TICK_SIZES").unwrap_or_default()),
+    };
+
     let args = Args {
         data_source,
         symbol,
         symbols,
         record_file,
-        metrics_port,
+        record: crate::recorder::RecordCfg::from_env(),
+        heartbeat_secs,
+        liveness_file,
+        metrics_server: crate::metrics_server::MetricsServerCfg::from_env(),
+        blotter_file,
+        blotter_max_fills,
+        recon_interval_secs,
+        recon_lookback_secs,
+        account_poll_secs,
+        symbol_refresh_secs,
+        feed_stale_ms,
+        compliance_file,
+        risk_script_file,
+        risk_script_reload_secs,
+        venue_health,
+        chaos,
+        reconnect,
+        snapshot_file,
+        snapshot_interval_secs,
+        snapshot_max_age_secs,
+        warm_start: cli.warm_start,
         feed_mode,
+        mock_scenario,
+        mock_book_levels,
+        mock_resting,
+        md_filter,
+        fx,
         venue_mode,
+        venue_symbols,
         binance_ws_url,
         binance_rest_url,
+        exchange_proxy,
+        exchange_ca_bundle,
+        exchange_log_file,
+        dead_letter_file,
+        process_role,
+        ipc_socket,
         strategy_modes,
         strategy_workers,
+        strategy_tags,
+        strategy_quote_notional,
+        strategy_max_tps,
+        strategy_bar_secs,
+        quote_throttle: crate::quote_throttle::ThrottleCfg::from_env(),
+        oms: crate::oms::OmsCfg::from_env(),
+        scoreboard: crate::scoreboard::ScoreCfg::from_env(),
+        alloc: crate::allocator::AllocCfg::from_env(),
+        vol_target: crate::vol_target::VolTargetCfg::from_env(),
+        bollinger: crate::strategy::BollingerCfg::from_env(),
+        donchian: crate::strategy::DonchianCfg::from_env(),
+        py_bridge: crate::py_bridge::PyBridgeCfg::from_env(),
+        dca: crate::dca::DcaCfg::from_env(),
+        md_anomaly: crate::md_anomaly::AnomalyCfg::from_env(),
+        holding: crate::holding::HoldingCfg::from_env(),
+        portfolio_stop: crate::portfolio_stop::PortfolioStopCfg::from_env(),
+        trading_day: crate::trading_day::TradingDayCfg::from_env(),
+        metric_alarms: crate::metric_alarms::AlarmCfg::from_env(),
+        #[cfg(feature = "admin-api")]
+        webhook: crate::webhook::WebhookCfg::from_env(),
+        alerts: crate::alerts::AlertCfg::from_env(),
+        cluster: crate::cluster_guard::ClusterCfg::from_env(),
+        #[cfg(feature = "pushgateway")]
+        push: crate::pushgateway::PushCfg::from_env(),
+        metric_labels: crate::metrics::LabelCfg::from_env(),
+        sim_latency: crate::sim_latency::SimLatencyCfg::from_env(),
+        slippage: crate::slippage::SlippageCfg::from_env(),
+        md_bus: crate::md_ring::MdBusCfg::from_env(),
+        pipeline: crate::pipeline::PipelineCfg::from_env(),
+        channel_caps: crate::channels::ChannelCaps::from_env(),
+        mleg_hedge: crate::multileg::HedgeCfg::from_env(),
+        peg: crate::router::PegCfg::from_env(),
+        books: crate::books::BookCfg::from_env(),
+        sizing,
     };
 
     // ===== Limits =====
@@ -203,7 +741,41 @@ pub fn load() -> (Args, Limits) {
     let px_min  = env::var("PX_MIN").ok().and_then(|x| x.parse().ok()).unwrap_or(1_000);
     let px_max  = env::var("PX_MAX").ok().and_then(|x| x.parse().ok()).unwrap_or(200_000);
     let max_qps = env::var("MAX_QPS").ok().and_then(|x| x.parse().ok()).unwrap_or(50);
+    let order_ttl_ms = env::var("ORDER_TTL_MS").ok().and_then(|x| x.parse().ok()).unwrap_or(0);
 
-    let limits = Limits { max_notional, px_min, px_max, max_qps };
+    let order_quote_notional = env::var("ORDER_QUOTE_NOTIONAL").ok().and_then(|s| s.parse().ok()).unwrap_or(0.0);
+    // Spot/margin: default spot untuk venue Binance (akun Spot), margin untuk mock (demo short)
+    let default_trading = if matches!(args.venue_mode, MarketMode::Mock) { TradingMode::Margin } else { TradingMode::Spot };
+    let trading_mode = TradingMode::from_env("TRADING_MODE", default_trading);
+    let max_leverage = env::var("MAX_LEVERAGE").ok().and_then(|s| s.parse().ok()).unwrap_or(1.0);
+    let margin_equity = env::var("MARGIN_EQUITY").ok().and_then(|s| s.parse().ok()).unwrap_or(0.0);
+    let maint_margin_rate = env::var("MAINT_MARGIN_RATE").ok().and_then(|s| s.parse().ok()).unwrap_or(0.005);
+    let max_margin_usage_pct = env::var("MAX_MARGIN_USAGE_PCT").ok().and_then(|s| s.parse().ok()).unwrap_or(0.0);
+    // VENUE_MAX_POSITION=A:100,binance:20 (lot per symbol)
+    let venue_max_position = crate::sizing::parse_map(&env::var("VENUE_MAX_POSITION").unwrap_or_default())
+        .into_iter()
+        .map(|(k, v)| (k, v as i64))
+        .collect();
+    // CORRELATION_GROUPS=majors:BTCUSDT+ETHUSDT:50000 (cap notional searah gabungan, quote)
+    let correlation_groups = CorrelationGroup::parse_many(&env::var("CORRELATION_GROUPS").unwrap_or_default());
+    // INV_BANDS=BTCUSDT:0:50:100 (target:soft:hard, lot)
+    let inv_bands = InvBand::parse_many(&env::var("INV_BANDS").unwrap_or_default());
+
+    let limits = Limits {
+        max_notional,
+        px_min,
+        px_max,
+        max_qps,
+        order_ttl_ms,
+        order_quote_notional,
+        trading_mode,
+        max_leverage,
+        margin_equity,
+        maint_margin_rate,
+        max_margin_usage_pct,
+        venue_max_position,
+        correlation_groups,
+        inv_bands,
+    };
     (args, limits)
 }
@@ -0,0 +1,272 @@
+// ===============================
+// src/config_check.rs
+// ===============================
+//
+// Validasi konfigurasi saat startup, sebelum task apa pun dijalankan.
+//
+// Semua masalah dikumpulkan dulu lalu dicetak sekaligus (bukan panic di tengah jalan / gagal diam-diam):
+//   error   -> engine tidak start (exit code 2)
+//   warning -> dilog, engine tetap jalan
+//
+// Yang dicek:
+//   - nilai mode tak dikenal (FEED_MODE, VENUE_MODE, TRADING_MODE, PROCESS_ROLE, MD_ANOMALY) yang dulu diam-diam jatuh ke default
+//   - FEED_MODE / VENUE_MODE selain mock pada build tanpa fitur `binance` atau dengan `sim-only`
+//   - SMTP_HOST / RISK_SCRIPT_FILE pada build tanpa fitur `alerts` / `risk-script`
+//   - venue Binance (VENUE_MODE != mock) tanpa BINANCE_API_KEY / BINANCE_API_SECRET
+//   - format SYMBOLS (huruf besar + angka) dan quote asset yang dikenal
+//   - file output bisa ditulis (RECORD_FILE, BLOTTER_FILE, SNAPSHOT_FILE, EXCHANGE_LOG_FILE, LIVENESS_FILE)
+//   - file input ada (COMPLIANCE_FILE, RISK_SCRIPT_FILE, EXCHANGE_CA_BUNDLE, ALERT_TEMPLATES_FILE)
+//   - strategi yang butuh umpan balik posisi (Donchian) pada PROCESS_ROLE=feed
+//   - DCA_SCHEDULE bisa di-parse dan symbol DCA_NOTIONAL ada di SYMBOLS
+//   - nama metrik METRIC_ALARMS dikenal
+//   - limit yang saling bertentangan (PX_MIN > PX_MAX, margin tanpa leverage, ...) dan SMTP setengah jadi
+//
+use std::env;
+use std::fs::OpenOptions;
+use std::path::Path;
+
+use crate::account::split_symbol;
+use crate::config::{Args, Limits, MarketMode, ProcessRole, TradingMode};
+
+#[derive(Debug, Default)]
+pub struct Report {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl Report {
+    fn error(&mut self, msg: impl Into<String>) {
+        self.errors.push(msg.into());
+    }
+
+    fn warn(&mut self, msg: impl Into<String>) {
+        self.warnings.push(msg.into());
+    }
+}
+
+/// Nilai env `key` (jika di-set dan tidak kosong) yang tidak ada di `allowed`
+fn unknown_value(r: &mut Report, key: &str, allowed: &[&str]) {
+    if let Ok(v) = env::var(key) {
+        if !v.is_empty() && !allowed.contains(&v.to_ascii_lowercase().as_str()) {
+            r.error(format!("{key}={v} is not a known value (expected one of: {})", allowed.join(", ")));
+        }
+    }
+}
+
+/// Direktori tujuan bisa dibuat & ditulisi (file probe dibuat lalu dihapus)
+fn check_writable(r: &mut Report, key: &str, path: &str) {
+    let dir = Path::new(path).parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    if let Err(e) = std::fs::create_dir_all(dir) {
+        r.error(format!("{key}={path}: cannot create directory {}: {e}", dir.display()));
+        return;
+    }
+    let probe = dir.join(format!(".dma_bot_write_probe.{}", std::process::id()));
+    match OpenOptions::new().create(true).write(true).truncate(true).open(&probe) {
+        Ok(_) => {
+            let _ = std::fs::remove_file(&probe);
+        }
+        Err(e) => r.error(format!("{key}={path}: directory {} is not writable: {e}", dir.display())),
+    }
+}
+
+fn check_exists(r: &mut Report, key: &str, path: &str) {
+    if !Path::new(path).is_file() {
+        r.error(format!("{key}={path}: file not found"));
+    }
+}
+
+/// Cross-check konfigurasi; tanpa efek samping selain file probe di direktori output
+pub fn validate(args: &Args, limits: &Limits) -> Report {
+    let mut r = Report::default();
+
+    // 1) nilai mode
+    let modes = ["mock", "binance_sandbox", "binance_mainnet"];
+    unknown_value(&mut r, "FEED_MODE", &modes);
+    unknown_value(&mut r, "VENUE_MODE", &modes);
+    unknown_value(&mut r, "TRADING_MODE", &["spot", "margin", "futures"]);
+    unknown_value(&mut r, "PROCESS_ROLE", &["all", "feed", "exec"]);
+    unknown_value(&mut r, "MD_ANOMALY", &["off", "pause", "widen"]);
+
+    // 2) build simulasi: hanya mode mock
+    if cfg!(feature = "sim-only") || !cfg!(feature = "binance") {
+        for (key, mode) in [("FEED_MODE", &args.feed_mode), ("VENUE_MODE", &args.venue_mode)] {
+            if !matches!(mode, MarketMode::Mock) {
+                let why = if cfg!(feature = "sim-only") { "built with the sim-only feature" } else { "built without the binance feature" };
+                r.error(format!("{key}: only mock is available ({why})"));
+            }
+        }
+    }
+
+    // 2a) fitur opsional yang dikonfigurasi tapi tidak ikut terkompilasi
+    if !cfg!(feature = "alerts") && !args.alerts.smtp_host.is_empty() {
+        r.error("SMTP_HOST is set but the binary was built without the alerts feature (no email support)");
+    }
+    if !cfg!(feature = "risk-script") && args.risk_script_file.is_some() {
+        r.error("RISK_SCRIPT_FILE is set but the binary was built without the risk-script feature (every signal would be rejected)");
+    }
+
+    // 2b) kredensial venue
+    if cfg!(feature = "binance") && !matches!(args.venue_mode, MarketMode::Mock) {
+        let missing: Vec<&str> = ["BINANCE_API_KEY", "BINANCE_API_SECRET"]
+            .into_iter()
+            .filter(|k| env::var(k).map(|v| v.is_empty()).unwrap_or(true))
+            .collect();
+        if !missing.is_empty() {
+            r.error(format!(
+                "VENUE_MODE={} needs {} (testnet keys: https://testnet.binance.vision)",
+                if matches!(args.venue_mode, MarketMode::BinanceMainnet) { "binance_mainnet" } else { "binance_sandbox" },
+                missing.join(" and ")
+            ));
+        }
+    }
+
+    // 3) symbol
+    for s in &args.symbols {
+        if !s.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()) {
+            r.error(format!("SYMBOLS: {s:?} is not a valid symbol (expected e.g. BTCUSDT)"));
+        } else if split_symbol(s).is_none() {
+            r.warn(format!("SYMBOLS: {s} has no known quote asset suffix; treated as USD-quoted for risk notionals"));
+        }
+    }
+    if let Some(dup) = args.symbols.iter().enumerate().find_map(|(i, s)| args.symbols[..i].contains(s).then_some(s)) {
+        r.warn(format!("SYMBOLS: {dup} listed more than once"));
+    }
+
+    // 4) file output & input
+    let outputs = [
+        ("RECORD_FILE", &args.record_file),
+        ("BLOTTER_FILE", &args.blotter_file),
+        ("SNAPSHOT_FILE", &args.snapshot_file),
+        ("EXCHANGE_LOG_FILE", &args.exchange_log_file),
+        ("DEAD_LETTER_FILE", &args.dead_letter_file),
+        ("TRADING_DAY_FILE", &args.trading_day.file),
+        ("LIVENESS_FILE", &args.liveness_file),
+    ];
+    for (key, path) in outputs {
+        if let Some(p) = path.as_deref().filter(|p| !p.is_empty()) {
+            check_writable(&mut r, key, p);
+        }
+    }
+    for kind in args.record.events.iter().filter(|k| !crate::recorder::KINDS.contains(&k.as_str())) {
+        r.error(format!("RECORD_EVENTS: unknown event type {kind} (expected {})", crate::recorder::KINDS.join(",")));
+    }
+    if args.record_file.is_none() && (!args.record.events.is_empty() || args.record.md_sample > 1) {
+        r.warn("RECORD_EVENTS / RECORD_MD_SAMPLE set without RECORD_FILE; recorder is off");
+    }
+    let inputs = [
+        ("COMPLIANCE_FILE", &args.compliance_file),
+        ("RISK_SCRIPT_FILE", &args.risk_script_file),
+        ("EXCHANGE_CA_BUNDLE", &args.exchange_ca_bundle),
+        ("ALERT_TEMPLATES_FILE", &args.alerts.templates_file),
+        ("METRICS_TLS_CERT", &args.metrics_server.tls_cert),
+        ("METRICS_TLS_KEY", &args.metrics_server.tls_key),
+    ];
+    for (key, path) in inputs {
+        if let Some(p) = path.as_deref() {
+            check_exists(&mut r, key, p);
+        }
+    }
+
+    // 5) limit
+    if limits.px_min > limits.px_max {
+        r.error(format!("PX_MIN ({}) is above PX_MAX ({}): every order would be rejected", limits.px_min, limits.px_max));
+    }
+    if limits.max_notional <= 0 {
+        r.error("MAX_NOTIONAL must be > 0: every order would be rejected");
+    }
+    if limits.trading_mode == TradingMode::Margin && limits.margin_equity > 0.0 && limits.max_leverage <= 0.0 {
+        r.error("TRADING_MODE=margin with MARGIN_EQUITY set needs MAX_LEVERAGE > 0");
+    }
+    if limits.trading_mode == TradingMode::Spot && limits.max_margin_usage_pct > 0.0 {
+        r.warn("MAX_MARGIN_USAGE_PCT is ignored in TRADING_MODE=spot");
+    }
+
+    // 5a) PROCESS_ROLE=feed: IPC hanya membawa MdTick + Signal ke exec, posisi tidak pernah kembali ke feed
+    if matches!(args.process_role, ProcessRole::Feed) {
+        for m in args.strategy_modes.iter().filter(|m| m.needs_positions()) {
+            r.error(format!(
+                "STRATEGIES: {} needs position feedback for its exits, which PROCESS_ROLE=feed does not receive (use PROCESS_ROLE=all)",
+                m.as_str()
+            ));
+        }
+    }
+
+    // 5b) tag strategi: tag internal engine melewati gate risk (force-exit / flatten portfolio stop)
+    for t in args.strategy_tags.iter().filter(|t| crate::holding::is_reserved_tag(t)) {
+        r.error(format!("STRATEGY_TAGS: {t} is reserved for engine exits (it bypasses the portfolio stop and sizing in risk)"));
+    }
+
+    // 6) DCA
+    if !args.dca.schedule.is_empty() {
+        if let Err(e) = crate::dca::Cron::parse(&args.dca.schedule) {
+            r.error(format!("DCA_SCHEDULE={:?}: {e}", args.dca.schedule));
+        }
+        if args.dca.notional.is_empty() {
+            r.warn("DCA_SCHEDULE is set but DCA_NOTIONAL is empty: DCA disabled");
+        }
+        for s in args.dca.notional.keys().filter(|s| !args.symbols.contains(s)) {
+            r.error(format!("DCA_NOTIONAL: {s} is not in SYMBOLS (no market data to price the buy)"));
+        }
+    }
+
+    // 7) alert email setengah dikonfigurasi
+    if !args.alerts.smtp_host.is_empty() && !args.alerts.enabled() {
+        r.error("SMTP_HOST is set but SMTP_FROM / SMTP_TO are missing: alert emails would be silently disabled");
+    }
+
+    // 8) server metrics
+    let m = &args.metrics_server;
+    if m.tls_cert.is_some() != m.tls_key.is_some() {
+        r.error("METRICS_TLS_CERT and METRICS_TLS_KEY must be set together");
+    }
+    if m.basic_auth.as_deref().is_some_and(|up| !up.contains(':')) {
+        r.error("METRICS_BASIC_AUTH must be user:pass");
+    }
+    if m.bind.parse::<std::net::IpAddr>().is_ok_and(|ip| !ip.is_loopback()) && m.basic_auth.is_none() && m.bearer_token.is_none() {
+        r.warn(format!(
+            "metrics/admin server on {} has no auth: set METRICS_BIND=127.0.0.1 or METRICS_BASIC_AUTH / METRICS_BEARER_TOKEN",
+            m.bind
+        ));
+    }
+
+    // 9) hari trading & portfolio stop
+    if env::var("TRADING_DAY_TZ").is_ok_and(|v| !v.trim().is_empty() && crate::trading_day::parse_offset(&v).is_none()) {
+        r.error("TRADING_DAY_TZ must be UTC or an offset like +07:00 / -05:00");
+    }
+    if env::var("TRADING_DAY_START").is_ok_and(|v| !v.trim().is_empty() && chrono::NaiveTime::parse_from_str(v.trim(), "%H:%M").is_err()) {
+        r.error("TRADING_DAY_START must be HH:MM (local time of TRADING_DAY_TZ)");
+    }
+    let ps = &args.portfolio_stop;
+    if ps.dd_pct > 0.0 && ps.equity_usd <= 0.0 {
+        r.warn("PORTFOLIO_STOP_DD_PCT without PORTFOLIO_EQUITY_USD: equity is PnL only, the % limit applies from the first profit");
+    }
+    if ps.dd_pct >= 100.0 {
+        r.error("PORTFOLIO_STOP_DD_PCT must be < 100");
+    }
+
+    // 10) alarm laju metrik
+    for m in args.metric_alarms.metrics.iter().filter(|m| !crate::metric_alarms::METRICS.contains(&m.as_str())) {
+        r.error(format!("METRIC_ALARMS: unknown metric {m} (expected {})", crate::metric_alarms::METRICS.join(",")));
+    }
+    if args.metric_alarms.enabled() && args.metric_alarms.z <= 0.0 && args.metric_alarms.min_dev_pct <= 0.0 {
+        r.warn("METRIC_ALARM_Z and METRIC_ALARM_MIN_DEV_PCT are both <= 0: every sample deviates, alarms fire continuously");
+    }
+
+    r
+}
+
+/// Jalankan validasi: warning dilog, error dicetak sekaligus lalu proses keluar (exit 2)
+pub fn enforce(args: &Args, limits: &Limits) {
+    let r = validate(args, limits);
+    for w in &r.warnings {
+        tracing::warn!("config: {w}");
+    }
+    if r.errors.is_empty() {
+        return;
+    }
+    eprintln!("configuration invalid ({} error(s)):", r.errors.len());
+    for e in &r.errors {
+        eprintln!("  - {e}");
+    }
+    std::process::exit(2);
+}
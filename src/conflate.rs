@@ -0,0 +1,84 @@
+// ===============================
+// src/conflate.rs
+// ===============================
+//
+// Konflasi market data per consumer: strategi yang tidak butuh setiap update bookTicker
+// (mis. cukup 10 tick/s) menerima paling banyak satu tick per interval per symbol.
+// - tick pertama setelah interval lewat langsung diteruskan (tanpa tambahan latensi)
+// - tick yang datang di dalam interval menimpa tick pending symbol itu (hanya quote terbaru
+//   yang disimpan) dan dikirim saat interval berakhir
+// - deteksi gap seq tetap jalan atas semua tick mentah, sebelum konflasi
+// Tick yang ditimpa dihitung di md_conflated_total{strategy}.
+//
+// ENV:
+//   STRATEGY_MAX_TPS=ma_crossover:10,python:5   (tick/s per symbol per worker; kosong = semua tick)
+//
+use ahash::AHashMap as HashMap;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::{sleep_until, Duration, Instant};
+
+use crate::book_analytics::TickRx;
+use crate::domain::EnrichedTick;
+use crate::md_seq::GapDetector;
+use crate::metrics::{strategy_label, MD_CONFLATED};
+
+pub struct Conflated {
+    rx: TickRx,
+    gaps: GapDetector,
+    interval: Option<Duration>,
+    strategy: String,
+    last_emit: HashMap<String, Instant>,
+    pending: HashMap<String, EnrichedTick>,
+}
+
+impl Conflated {
+    /// `max_tps` = None / <= 0 -> pass-through
+    pub fn new(rx: TickRx, gaps: GapDetector, strategy: &str, max_tps: Option<f64>) -> Self {
+        Self {
+            rx,
+            gaps,
+            interval: max_tps.filter(|t| *t > 0.0).map(|t| Duration::from_secs_f64(1.0 / t)),
+            strategy: strategy.to_string(),
+            last_emit: HashMap::new(),
+            pending: HashMap::new(),
+        }
+    }
+
+    pub async fn recv(&mut self) -> Result<EnrichedTick, RecvError> {
+        let Some(interval) = self.interval else {
+            let t = self.rx.recv().await?;
+            self.gaps.observe(&t.tick);
+            return Ok(t);
+        };
+        loop {
+            // pending paling awal yang jatuh tempo
+            let next = self
+                .pending
+                .keys()
+                .filter_map(|s| Some((*self.last_emit.get(s)? + interval, s.clone())))
+                .min();
+            tokio::select! {
+                t = self.rx.recv() => {
+                    let t = t?;
+                    self.gaps.observe(&t.tick);
+                    let now = Instant::now();
+                    let due = self.last_emit.get(&t.tick.symbol).is_none_or(|last| now >= *last + interval);
+                    if due && !self.pending.contains_key(&t.tick.symbol) {
+                        self.last_emit.insert(t.tick.symbol.clone(), now);
+                        return Ok(t);
+                    }
+                    if self.pending.insert(t.tick.symbol.clone(), t).is_some() {
+                        MD_CONFLATED.with_label_values(&[strategy_label(&self.strategy)]).inc();
+                    }
+                }
+                _ = sleep_until(next.as_ref().map_or_else(Instant::now, |n| n.0)), if next.is_some() => {
+                    let Some((_, symbol)) = next else { continue };
+                    if let Some(t) = self.pending.remove(&symbol) {
+                        self.last_emit.insert(symbol, Instant::now());
+                        return Ok(t);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,195 @@
+// ===============================
+// src/dashboard.rs
+// ===============================
+//
+// Dashboard web minimal yang di-serve bot sendiri (tanpa Grafana):
+// - GET /dashboard         -> satu halaman HTML (src/dashboard.html, di-embed saat compile)
+// - GET /dashboard/events  -> Server-Sent Events, snapshot JSON tiap 1 detik
+//
+// Isi snapshot: harga terakhir per symbol, posisi & PnL, open order, fill terakhir (blotter).
+// Server metrics jalan di satu OS thread, jadi tiap koneksi SSE dilayani thread sendiri
+// (dibatasi MAX_SSE_CLIENTS).
+//
+use ahash::AHashMap as HashMap;
+use serde::Serialize;
+use std::collections::BTreeMap;
+#[cfg(feature = "admin-api")]
+use std::io::Write;
+#[cfg(feature = "admin-api")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "admin-api")]
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, watch};
+
+use crate::blotter::Blotter;
+#[cfg(feature = "admin-api")]
+use crate::blotter::Fill;
+use crate::domain::{ExecReport, ExecStatus, InvSnapshot, MdTick, Side};
+use crate::md_seq::GapDetector;
+
+#[cfg(feature = "admin-api")]
+pub const PAGE: &str = include_str!("dashboard.html");
+
+#[cfg(feature = "admin-api")]
+const MAX_SSE_CLIENTS: usize = 16;
+#[cfg(feature = "admin-api")]
+const RECENT_FILLS: usize = 20;
+#[cfg(feature = "admin-api")]
+static SSE_CLIENTS: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, Clone, Serialize)]
+struct Price {
+    bid: i64,
+    ask: i64,
+    ts_ns: i128,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Position {
+    qty: i64,
+    realized_pnl: i64,
+    unrealized_pnl: i64,
+    last_mid: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct OpenOrder {
+    cl_id: String,
+    symbol: String,
+    side: Option<Side>,
+    venue: String,
+    strategy: String,
+    filled_qty: i64,
+    ts_ns: i128,
+}
+
+#[derive(Default)]
+struct Inner {
+    prices: BTreeMap<String, Price>,
+    positions: BTreeMap<String, Position>,
+    open_orders: HashMap<String, OpenOrder>,
+}
+
+#[cfg(feature = "admin-api")]
+#[derive(Serialize)]
+struct View<'a> {
+    prices: &'a BTreeMap<String, Price>,
+    positions: &'a BTreeMap<String, Position>,
+    open_orders: Vec<&'a OpenOrder>,
+    fills: Vec<Fill>,
+}
+
+/// State dashboard yang bisa di-clone (diisi task tokio, dibaca thread SSE)
+#[derive(Clone)]
+pub struct DashState {
+    inner: Arc<Mutex<Inner>>,
+    #[cfg(feature = "admin-api")]
+    blotter: Blotter,
+}
+
+impl DashState {
+    pub fn new(blotter: Blotter) -> Self {
+        // fill terakhir hanya dibaca view dashboard (server admin)
+        #[cfg(not(feature = "admin-api"))]
+        let _ = blotter;
+        Self {
+            inner: Arc::default(),
+            #[cfg(feature = "admin-api")]
+            blotter,
+        }
+    }
+
+    fn on_md(&self, md: &MdTick) {
+        let p = Price { bid: md.best_bid, ask: md.best_ask, ts_ns: md.ts_ns };
+        self.inner.lock().unwrap().prices.insert(md.symbol.clone(), p);
+    }
+
+    fn on_exec(&self, er: &ExecReport) {
+        let mut g = self.inner.lock().unwrap();
+        match er.status {
+            ExecStatus::Ack | ExecStatus::PartialFill => {
+                let o = g.open_orders.entry(er.cl_id.clone()).or_insert_with(|| OpenOrder {
+                    cl_id: er.cl_id.clone(),
+                    symbol: er.symbol.clone(),
+                    side: er.side,
+                    venue: er.venue.clone(),
+                    strategy: er.strategy.clone(),
+                    filled_qty: 0,
+                    ts_ns: er.ts_ns,
+                });
+                o.filled_qty = er.filled_qty;
+            }
+            _ => {
+                g.open_orders.remove(&er.cl_id);
+            }
+        }
+    }
+
+    fn on_position(&self, snap: &InvSnapshot) {
+        let p = Position {
+            qty: snap.state.total_qty,
+            realized_pnl: snap.state.realized_pnl,
+            unrealized_pnl: snap.state.unrealized_pnl,
+            last_mid: snap.state.last_mid,
+        };
+        self.inner.lock().unwrap().positions.insert(snap.symbol.clone(), p);
+    }
+
+    /// Snapshot JSON untuk dikirim ke browser
+    #[cfg(feature = "admin-api")]
+    pub fn to_json(&self) -> String {
+        let fills = self.blotter.recent(RECENT_FILLS);
+        let g = self.inner.lock().unwrap();
+        let mut open_orders: Vec<&OpenOrder> = g.open_orders.values().collect();
+        open_orders.sort_by_key(|o| o.ts_ns);
+        let view = View { prices: &g.prices, positions: &g.positions, open_orders, fills };
+        serde_json::to_string(&view).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+/// Task: ikuti MD & ExecReport
+pub async fn run(state: DashState, mut md_rx: broadcast::Receiver<MdTick>, mut exec_rx: mpsc::Receiver<Arc<ExecReport>>) {
+    let mut gaps = GapDetector::new("dashboard");
+    loop {
+        tokio::select! {
+            md = md_rx.recv() => match md {
+                Ok(md) => {
+                    gaps.observe(&md);
+                    state.on_md(&md)
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            Some(er) = exec_rx.recv() => state.on_exec(&er),
+        }
+    }
+}
+
+/// Task: ikuti snapshot posisi satu symbol
+pub async fn watch_positions(state: DashState, mut rx: watch::Receiver<InvSnapshot>) {
+    while rx.changed().await.is_ok() {
+        let snap = rx.borrow().clone();
+        state.on_position(&snap);
+    }
+}
+
+/// Layani satu koneksi SSE (blocking, di thread sendiri) sampai client putus
+#[cfg(feature = "admin-api")]
+pub fn serve_events<S: Write + Send + 'static>(mut stream: S, state: DashState) {
+    if SSE_CLIENTS.fetch_add(1, Ordering::SeqCst) >= MAX_SSE_CLIENTS {
+        SSE_CLIENTS.fetch_sub(1, Ordering::SeqCst);
+        let _ = stream.write_all(b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+        return;
+    }
+    std::thread::spawn(move || {
+        let header = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n";
+        let mut ok = stream.write_all(header.as_bytes()).is_ok();
+        while ok {
+            let frame = format!("data: {}\n\n", state.to_json());
+            ok = stream.write_all(frame.as_bytes()).and_then(|_| stream.flush()).is_ok();
+            std::thread::sleep(Duration::from_secs(1));
+        }
+        SSE_CLIENTS.fetch_sub(1, Ordering::SeqCst);
+    });
+}
@@ -0,0 +1,229 @@
+// ===============================
+// src/dca.rs
+// ===============================
+//
+// Strategi DCA (dollar-cost averaging): beli notional tetap untuk symbol yang dikonfigurasi
+// sesuai jadwal cron (UTC), bukan berdasarkan tick.
+//
+// - Jadwal: ekspresi cron 5 field "menit jam tanggal bulan hari" (hari 0-6, 0/7 = Minggu),
+//   mendukung `*`, angka, list `a,b`, range `a-b` dan step `*/n` / `a-b/n`.
+//   Jika tanggal & hari sama-sama dibatasi, cukup salah satu yang cocok (semantik cron standar).
+// - Saat jadwal jatuh tempo, satu Signal Buy per symbol dikirim ke bus Signal yang sama dengan
+//   strategi lain (quote_notional = DCA_NOTIONAL, harga = best ask terakhir), jadi tetap lewat
+//   risk (limit, sizing lot, compliance, status symbol) + router seperti biasa.
+// - Symbol tanpa quote segar (lebih tua dari DCA_MAX_QUOTE_AGE_SECS) dilewati untuk jadwal itu
+//   (`dca_signals_total{result="stale_quote"}`), tidak disusulkan.
+//
+// ENV:
+//   DCA_SCHEDULE="0 9 * * 1"          (kosong = nonaktif; contoh: tiap Senin 09:00 UTC)
+//   DCA_NOTIONAL=BTCUSDT:50,ETHUSDT:25 (quote per eksekusi per symbol)
+//   DCA_STRATEGY=dca                  (nama strategi untuk atribusi / book / scoreboard)
+//   DCA_MAX_QUOTE_AGE_SECS=60
+//
+use ahash::AHashMap as HashMap;
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, Timelike, Utc};
+use tokio::sync::{broadcast, mpsc};
+use tracing::{info, warn};
+
+use crate::clock;
+use crate::domain::{MdTick, Side, Signal};
+use crate::metrics::DCA_SIGNALS;
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DcaCfg {
+    pub schedule: String,
+    pub notional: HashMap<String, f64>,
+    pub strategy: String,
+    pub max_quote_age_secs: u64,
+}
+
+impl DcaCfg {
+    pub fn from_env() -> Self {
+        Self {
+            schedule: std::env::var("DCA_SCHEDULE").unwrap_or_default().trim().to_string(),
+            notional: crate::sizing::parse_map(&std::env::var("DCA_NOTIONAL").unwrap_or_default()),
+            strategy: std::env::var("DCA_STRATEGY").unwrap_or_else(|_| "dca".to_string()),
+            max_quote_age_secs: std::env::var("DCA_MAX_QUOTE_AGE_SECS").ok().and_then(|s| s.parse().ok()).unwrap_or(60),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        !self.schedule.is_empty() && !self.notional.is_empty()
+    }
+}
+
+/// Jadwal cron 5 field (bitmask per field)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cron {
+    minute: u64,
+    hour: u64,
+    dom: u64,
+    month: u64,
+    dow: u64,
+    dom_any: bool,
+    dow_any: bool,
+}
+
+/// Satu field cron -> bitmask nilai yang cocok
+fn parse_field(s: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for part in s.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((r, st)) => (r, st.parse::<u32>().map_err(|_| format!("bad step {st:?}"))?),
+            None => (part, 1),
+        };
+        if step == 0 {
+            return Err("step must be > 0".into());
+        }
+        let (lo, hi) = match range {
+            "*" => (min, max),
+            r => match r.split_once('-') {
+                Some((a, b)) => (
+                    a.parse().map_err(|_| format!("bad value {a:?}"))?,
+                    b.parse().map_err(|_| format!("bad value {b:?}"))?,
+                ),
+                None => {
+                    let v: u32 = r.parse().map_err(|_| format!("bad value {r:?}"))?;
+                    // "5/15" = mulai 5, step 15 sampai max
+                    (v, if part.contains('/') { max } else { v })
+                }
+            },
+        };
+        if lo < min || hi > max || lo > hi {
+            return Err(format!("{range:?} out of range {min}-{max}"));
+        }
+        for v in (lo..=hi).step_by(step as usize) {
+            mask |= 1 << v;
+        }
+    }
+    Ok(mask)
+}
+
+impl Cron {
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let f: Vec<&str> = expr.split_whitespace().collect();
+        let [m, h, dom, mon, dow] = f[..] else {
+            return Err(format!("expected 5 fields (minute hour day month weekday), got {}", f.len()));
+        };
+        let mut dow_mask = parse_field(dow, 0, 7)?;
+        // 7 = Minggu
+        if dow_mask & (1 << 7) != 0 {
+            dow_mask = (dow_mask | 1) & !(1 << 7);
+        }
+        Ok(Self {
+            minute: parse_field(m, 0, 59)?,
+            hour: parse_field(h, 0, 23)?,
+            dom: parse_field(dom, 1, 31)?,
+            month: parse_field(mon, 1, 12)?,
+            dow: dow_mask,
+            dom_any: dom == "*",
+            dow_any: dow == "*",
+        })
+    }
+
+    fn matches(&self, t: &DateTime<Utc>) -> bool {
+        let bit = |mask: u64, v: u32| mask & (1 << v) != 0;
+        let dom = bit(self.dom, t.day());
+        let dow = bit(self.dow, t.weekday().num_days_from_sunday());
+        let day = match (self.dom_any, self.dow_any) {
+            (false, false) => dom || dow,
+            _ => dom && dow,
+        };
+        day && bit(self.minute, t.minute()) && bit(self.hour, t.hour()) && bit(self.month, t.month())
+    }
+
+    /// Menit terjadwal berikutnya setelah `after` (maks. ~4 tahun ke depan)
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut t = after.with_second(0)?.with_nanosecond(0)? + ChronoDuration::minutes(1);
+        for _ in 0..(4 * 366 * 24 * 60) {
+            if self.matches(&t) {
+                return Some(t);
+            }
+            t += ChronoDuration::minutes(1);
+        }
+        None
+    }
+}
+
+/// Task: tunggu jadwal, lalu kirim Signal Buy per symbol ke bus Signal
+pub async fn run(cfg: DcaCfg, book: String, mut md_rx: broadcast::Receiver<MdTick>, sig_tx: mpsc::Sender<Signal>) {
+    let cron = match Cron::parse(&cfg.schedule) {
+        Ok(c) => c,
+        Err(e) => {
+            warn!(schedule = %cfg.schedule, %e, "dca: invalid DCA_SCHEDULE, disabled");
+            return;
+        }
+    };
+    let mut quotes: HashMap<String, MdTick> = HashMap::new();
+    loop {
+        let Some(next) = cron.next_after(Utc::now()) else {
+            warn!(schedule = %cfg.schedule, "dca: schedule never fires, disabled");
+            return;
+        };
+        info!(next = %next.to_rfc3339(), symbols = cfg.notional.len(), "dca: next run");
+        let sleep = tokio::time::sleep((next - Utc::now()).to_std().unwrap_or_default());
+        tokio::pin!(sleep);
+        // simpan quote terakhir per symbol sambil menunggu
+        loop {
+            tokio::select! {
+                _ = &mut sleep => break,
+                md = md_rx.recv() => match md {
+                    Ok(md) if cfg.notional.contains_key(&md.symbol) => {
+                        quotes.insert(md.symbol.clone(), md);
+                    }
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => return,
+                },
+            }
+        }
+
+        let now = clock::now_ns();
+        let max_age = cfg.max_quote_age_secs as i128 * 1_000_000_000;
+        for (symbol, notional) in &cfg.notional {
+            let Some(md) = quotes.get(symbol).filter(|q| now - q.ts_ns <= max_age && q.best_ask > 0) else {
+                warn!(%symbol, "dca: no fresh quote, skipping this run");
+                DCA_SIGNALS.with_label_values(&[crate::metrics::symbol_label(symbol), "stale_quote"]).inc();
+                continue;
+            };
+            let sig = Signal {
+                ts_ns: now,
+                symbol: symbol.clone(),
+                side: Side::Buy,
+                px: md.best_ask,
+                qty: 0,
+                strategy: cfg.strategy.clone(),
+                tags: vec!["dca".to_string()],
+                book: book.clone(),
+                quote_notional: Some(*notional),
+            };
+            let result = match sig_tx.try_send(sig) {
+                Ok(()) => "sent",
+                Err(_) => "queue_full",
+            };
+            info!(%symbol, notional, px = md.best_ask, result, "dca: buy");
+            DCA_SIGNALS.with_label_values(&[crate::metrics::symbol_label(symbol), result]).inc();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn cron_finds_next_scheduled_minute() {
+        // tiap Senin 09:00
+        let c = Cron::parse("0 9 * * 1").unwrap();
+        let sat = Utc.with_ymd_and_hms(2024, 6, 1, 12, 30, 0).unwrap();
+        assert_eq!(c.next_after(sat), Some(Utc.with_ymd_and_hms(2024, 6, 3, 9, 0, 0).unwrap()));
+
+        // tiap 15 menit jam kerja; tepat di jadwal -> jadwal berikutnya
+        let c = Cron::parse("*/15 8-17 * * *").unwrap();
+        let t = Utc.with_ymd_and_hms(2024, 6, 1, 17, 45, 0).unwrap();
+        assert_eq!(c.next_after(t), Some(Utc.with_ymd_and_hms(2024, 6, 2, 8, 0, 0).unwrap()));
+
+        assert!(Cron::parse("0 25 * * *").is_err());
+        assert!(Cron::parse("0 9 * *").is_err());
+    }
+}
@@ -0,0 +1,135 @@
+// ===============================
+// src/dead_letter.rs
+// ===============================
+//
+// Dead-letter queue: event yang tidak bisa dikirim ke tujuannya (channel penuh / tertutup, tidak ada
+// consumer, order yang tidak bisa dirutekan) ditulis ke JSONL beserta alasannya, supaya tidak ada
+// yang hilang diam-diam dari audit trail trading:
+//   {"ts_ns":..,"kind":"exec","consumer":"positions","reason":"no_consumer","event":{...ExecReport}}
+//
+// Sumber:
+// - fan-out ExecReport: consumer tertutup, recorder penuh, symbol tanpa task positions
+// - risk: RiskReject / Compliance yang tidak muat di antrean recorder
+// - router: order tanpa venue yang bisa dirutekan / sisa qty yang tidak terkirim
+// - gateway Binance: lane submit symbol tertutup
+//
+// Setiap dead letter dihitung di `dead_letters_total{kind,reason}` (juga saat file nonaktif).
+// Antrean penulis penuh -> entri hanya dicatat di log error.
+//
+// ENV:
+//   DEAD_LETTER_FILE=/var/log/dma/dead_letters.jsonl   (kosong = nonaktif)
+//
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+use tokio::{
+    fs::{self, OpenOptions},
+    io::{AsyncWriteExt, BufWriter},
+    sync::mpsc,
+    time::{interval, Duration, MissedTickBehavior},
+};
+use tracing::{error, info};
+
+use crate::metrics::DEAD_LETTERS;
+
+const QUEUE_CAP: usize = 4096;
+
+static SINK: OnceCell<mpsc::Sender<String>> = OnceCell::new();
+
+#[derive(Debug, Serialize)]
+pub struct Entry<'a> {
+    pub ts_ns: i128,
+    /// jenis event (kind recorder: exec, ord, riskreject, compliance, ...)
+    pub kind: &'a str,
+    /// tujuan yang gagal menerima (positions, recorder, router, ...)
+    pub consumer: &'a str,
+    /// no_consumer | full | closed | no_route | unrouted
+    pub reason: &'a str,
+    pub event: Value,
+}
+
+/// Aktifkan file dead-letter; kembalikan receiver untuk task `run`
+pub fn init() -> mpsc::Receiver<String> {
+    let (tx, rx) = mpsc::channel(QUEUE_CAP);
+    let _ = SINK.set(tx);
+    rx
+}
+
+/// Catat satu event yang tidak terkirim
+pub fn record<T: Serialize>(kind: &str, consumer: &str, reason: &str, event: &T) {
+    DEAD_LETTERS.with_label_values(&[kind, reason]).inc();
+    let Some(tx) = SINK.get() else { return };
+    let Ok(line) = serde_json::to_string(&entry(kind, consumer, reason, event)) else { return };
+    if let Err(e) = tx.try_send(line) {
+        error!(line = %e.into_inner(), "dead letter queue full, entry not written");
+    }
+}
+
+fn entry<'a, T: Serialize>(kind: &'a str, consumer: &'a str, reason: &'a str, event: &T) -> Entry<'a> {
+    Entry { ts_ns: crate::clock::now_ns(), kind, consumer, reason, event: serde_json::to_value(event).unwrap_or_default() }
+}
+
+async fn open_writer(path: &str) -> Option<BufWriter<tokio::fs::File>> {
+    if let Some(parent) = Path::new(path).parent() {
+        if !parent.as_os_str().is_empty() {
+            let _ = fs::create_dir_all(parent).await;
+        }
+    }
+    match OpenOptions::new().create(true).append(true).open(path).await {
+        Ok(f) => Some(BufWriter::new(f)),
+        Err(e) => {
+            error!(?e, %path, "dead letter: open failed");
+            None
+        }
+    }
+}
+
+/// Task penulis file (flush tiap 1s)
+pub async fn run(mut rx: mpsc::Receiver<String>, path: String) {
+    info!(%path, "dead letter: started");
+    let Some(mut writer) = open_writer(&path).await else { return };
+    let mut tick = interval(Duration::from_secs(1));
+    tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            maybe = rx.recv() => {
+                let Some(line) = maybe else { break };
+                if let Err(e) = writer.write_all(format!("{line}\n").as_bytes()).await {
+                    error!(?e, "dead letter: write failed, reopening");
+                    match open_writer(&path).await {
+                        Some(w) => writer = w,
+                        None => return,
+                    }
+                }
+            }
+            _ = tick.tick() => {
+                let _ = writer.flush().await;
+            }
+        }
+    }
+    let _ = writer.flush().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ExecReport, ExecStatus, Side};
+
+    #[test]
+    fn entry_carries_reason_and_full_event() {
+        let er = ExecReport {
+            cl_id: "c1".into(), symbol: "XRPUSDT".into(), status: ExecStatus::Filled, filled_qty: 3, avg_px: 50,
+            ts_ns: 1, venue: "A".into(), side: Some(Side::Buy), order_qty: 3, strategy: "mr".into(), tags: vec![],
+            book: String::new(), exch_ts_ns: 0,
+        };
+        let v = serde_json::to_value(entry("exec", "positions", "no_consumer", &er)).unwrap();
+        assert_eq!((v["kind"].as_str(), v["consumer"].as_str(), v["reason"].as_str()), (Some("exec"), Some("positions"), Some("no_consumer")));
+        assert_eq!(v["event"]["cl_id"], "c1");
+        assert_eq!(v["event"]["filled_qty"], 3);
+
+        // tanpa file: tetap dihitung
+        record("exec", "positions", "no_consumer", &er);
+        assert!(DEAD_LETTERS.with_label_values(&["exec", "no_consumer"]).get() >= 1);
+    }
+}
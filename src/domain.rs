@@ -5,22 +5,244 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum Side { Buy, Sell }
-impl Side { pub fn sign(&self) -> i64 { match self { Side::Buy => 1, Side::Sell => -1 } } }
+impl Side {
+    pub fn sign(&self) -> i64 { match self { Side::Buy => 1, Side::Sell => -1 } }
+    pub fn as_str(&self) -> &'static str { match self { Side::Buy => "buy", Side::Sell => "sell" } }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MdTick { pub ts_ns: i128, pub symbol: String, pub best_bid: i64, pub best_ask: i64 }
+pub struct MdTick {
+    pub ts_ns: i128, pub symbol: String, pub best_bid: i64, pub best_ask: i64,
+    /// ukuran di best bid/ask (0 = tidak diketahui, mis. rekaman lama)
+    #[serde(default)] pub bid_qty: i64,
+    #[serde(default)] pub ask_qty: i64,
+    /// nomor urut per symbol dari feed (0 = tidak diketahui), lihat md_seq.rs
+    #[serde(default)] pub seq: u64,
+    /// waktu event di exchange (ns; 0 = tidak ada, mis. mock / bookTicker Spot). `ts_ns` = clock engine saat diterima
+    #[serde(default)] pub exch_ts_ns: i128,
+}
+/// Level depth (px, qty), level terbaik lebih dulu
+pub type Levels = Vec<(i64, i64)>;
+/// Snapshot depth L2, saat ini dari feed mock (`MOCK_BOOK_LEVELS`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookUpdate { pub ts_ns: i128, pub symbol: String, pub bids: Levels, pub asks: Levels }
+/// Analitik book per tick (book_analytics.rs): microprice x100 (pecahan), imbalance -1..1 (positif = bid lebih tebal)
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct BookStats { pub microprice: f64, pub imbalance: f64, pub imbalance_k: f64, pub levels: usize }
+/// MdTick + BookStats untuk strategi
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Signal { pub ts_ns: i128, pub symbol: String, pub side: Side, pub px: i64, pub qty: i64 }
+pub struct EnrichedTick { pub tick: MdTick, pub stats: BookStats }
+// Atribusi: `strategy` (nama strategi asal) + `tags` bebas, dibawa Signal -> Order -> ExecReport.
+// `#[serde(default)]` agar rekaman JSONL lama tetap bisa dibaca.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Order { pub cl_id: String, pub ts_ns: i128, pub symbol: String, pub side: Side, pub px: i64, pub qty: i64 }
+pub struct Signal {
+    pub ts_ns: i128, pub symbol: String, pub side: Side, pub px: i64, pub qty: i64,
+    #[serde(default)] pub strategy: String,
+    #[serde(default)] pub tags: Vec<String>,
+    /// book (tenant) pemilik Signal; kosong = book default
+    #[serde(default)] pub book: String,
+    /// ukuran dalam notional quote (mis. 50.0 USDT); jika ada, risk menghitung ulang `qty` (lot)
+    #[serde(default)] pub quote_notional: Option<f64>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Order {
+    pub cl_id: String, pub ts_ns: i128, pub symbol: String, pub side: Side, pub px: i64, pub qty: i64,
+    #[serde(default)] pub strategy: String,
+    #[serde(default)] pub tags: Vec<String>,
+    #[serde(default)] pub book: String,
+    /// good-till-time: order dibatalkan gateway pada waktu ini (None = GTC)
+    #[serde(default)] pub expire_at_ns: Option<i128>,
+    /// LIMIT biasa atau stop-limit native exchange (lihat OrderType)
+    #[serde(default)] pub order_type: OrderType,
+}
+/// Tipe order di venue. Stop-limit: order limit di `Order.px` baru aktif saat harga menyentuh
+/// `stop_px` (tick ×100), dikelola exchange (tetap hidup walau bot mati).
+/// Dari Signal lewat tag `stop_loss_limit=<stop_px>` / `take_profit_limit=<stop_px>`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrderType {
+    #[default]
+    Limit,
+    /// sell: terpicu saat harga <= stop_px; buy: saat harga >= stop_px
+    StopLossLimit { stop_px: i64 },
+    /// sell: terpicu saat harga >= stop_px; buy: saat harga <= stop_px
+    TakeProfitLimit { stop_px: i64 },
+}
+impl OrderType {
+    /// Tipe order dari tag Signal/Order (default Limit)
+    pub fn from_tags(tags: &[String]) -> Self {
+        let tag = |k: &str| tags.iter().find_map(|t| t.strip_prefix(k)?.parse::<i64>().ok()).filter(|px| *px > 0);
+        match (tag("stop_loss_limit="), tag("take_profit_limit=")) {
+            (Some(stop_px), _) => OrderType::StopLossLimit { stop_px },
+            (_, Some(stop_px)) => OrderType::TakeProfitLimit { stop_px },
+            _ => OrderType::Limit,
+        }
+    }
+    /// Nilai `type` Binance
+    #[cfg(feature = "binance")]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderType::Limit => "LIMIT",
+            OrderType::StopLossLimit { .. } => "STOP_LOSS_LIMIT",
+            OrderType::TakeProfitLimit { .. } => "TAKE_PROFIT_LIMIT",
+        }
+    }
+    #[cfg(feature = "binance")]
+    pub fn stop_px(&self) -> Option<i64> {
+        match self {
+            OrderType::Limit => None,
+            OrderType::StopLossLimit { stop_px } | OrderType::TakeProfitLimit { stop_px } => Some(*stop_px),
+        }
+    }
+    /// Order `side` sudah aktif pada harga pasar `px` (Limit selalu aktif)
+    pub fn triggered(&self, side: Side, px: i64) -> bool {
+        match (self, side) {
+            (OrderType::Limit, _) => true,
+            (OrderType::StopLossLimit { stop_px }, Side::Sell) | (OrderType::TakeProfitLimit { stop_px }, Side::Buy) => px <= *stop_px,
+            (OrderType::StopLossLimit { stop_px }, Side::Buy) | (OrderType::TakeProfitLimit { stop_px }, Side::Sell) => px >= *stop_px,
+        }
+    }
+}
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VenueOrder { pub venue: String, pub order: Order }
+/// Satu leg order multi-leg. `ratio` = lot per unit paket (imbalance dihitung dalam unit: filled / ratio);
+/// 0 = `qty` (paket satu unit)
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ExecReport { pub cl_id: String, pub symbol: String, pub status: ExecStatus, pub filled_qty: i64, pub avg_px: i64, pub ts_ns: i128 }
+pub struct Leg {
+    pub symbol: String, pub side: Side, pub px: i64, pub qty: i64,
+    #[serde(default)] pub ratio: i64,
+}
+impl Leg {
+    pub fn ratio(&self) -> i64 { if self.ratio > 0 { self.ratio } else { self.qty.max(1) } }
+}
+/// Paket order multi-leg (arbitrase / pairs): semua leg lolos risk atau semuanya ditolak,
+/// lalu router mengirim leg berurutan tanpa jeda setelah pre-flight semua leg. Lihat multileg.rs
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum ExecStatus { Ack, PartialFill, Filled, Rejected(String) }
+pub struct MultiLegOrder {
+    /// id paket (kosong = dibuat risk)
+    #[serde(default)] pub id: String,
+    #[serde(default)] pub ts_ns: i128,
+    pub legs: Vec<Leg>,
+    #[serde(default)] pub strategy: String,
+    #[serde(default)] pub tags: Vec<String>,
+    #[serde(default)] pub book: String,
+}
+impl MultiLegOrder {
+    /// Signal untuk leg ke-`i` (dicek risk seperti Signal biasa); tag `mleg=<id>` + `leg=<i>` ikut sampai ExecReport
+    pub fn leg_signal(&self, i: usize) -> Signal {
+        let leg = &self.legs[i];
+        let mut tags = self.tags.clone();
+        tags.push(format!("mleg={}", self.id));
+        tags.push(format!("leg={i}"));
+        Signal {
+            ts_ns: self.ts_ns, symbol: leg.symbol.clone(), side: leg.side, px: leg.px, qty: leg.qty,
+            strategy: self.strategy.clone(), tags, book: self.book.clone(), quote_notional: None,
+        }
+    }
+}
+/// (id paket, index leg) dari tag order / ExecReport
+pub fn leg_of(tags: &[String]) -> Option<(&str, usize)> {
+    let id = tags.iter().find_map(|t| t.strip_prefix("mleg="))?;
+    let leg = tags.iter().find_map(|t| t.strip_prefix("leg="))?.parse().ok()?;
+    Some((id, leg))
+}
+/// Order leg yang sudah lolos risk (risk -> router), urut sesuai leg
+#[derive(Debug, Clone)]
+pub struct LegGroup { pub id: String, pub orders: Vec<Order> }
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum Event { Md(MdTick), Sig(Signal), Ord(Order), Exec(ExecReport), Note(String) }
+pub struct ExecReport {
+    pub cl_id: String, pub symbol: String, pub status: ExecStatus, pub filled_qty: i64, pub avg_px: i64, pub ts_ns: i128,
+    /// venue yang mengeksekusi (diisi gateway; jangan diturunkan dari cl_id)
+    #[serde(default)] pub venue: String,
+    /// sisi order asal (None untuk rekaman lama)
+    #[serde(default)] pub side: Option<Side>,
+    /// qty order asal (lot; 0 = tidak diketahui)
+    #[serde(default)] pub order_qty: i64,
+    #[serde(default)] pub strategy: String,
+    #[serde(default)] pub tags: Vec<String>,
+    #[serde(default)] pub book: String,
+    /// waktu event di exchange (ns; 0 = laporan lokal / mock), lihat clock.rs
+    #[serde(default)] pub exch_ts_ns: i128,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ExecStatus { Ack, PartialFill, Filled, Rejected(String), Expired }
+
+/// Ubah ExecReport kumulatif (filled_qty/avg_px per cl_id) menjadi fill inkremental
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FillDeltas { cum: std::collections::HashMap<String, (i64, i64)> }
+impl FillDeltas {
+    /// (qty, px) fill baru dari report ini; None jika tidak menambah qty terisi
+    pub fn on_report(&mut self, er: &ExecReport) -> Option<(i64, i64)> {
+        let terminal = !matches!(er.status, ExecStatus::Ack | ExecStatus::PartialFill);
+        let (prev_qty, prev_px) = if terminal {
+            self.cum.remove(&er.cl_id).unwrap_or_default()
+        } else {
+            self.cum.get(&er.cl_id).copied().unwrap_or_default()
+        };
+        if !matches!(er.status, ExecStatus::PartialFill | ExecStatus::Filled) {
+            return None;
+        }
+        let qty = er.filled_qty - prev_qty;
+        if qty <= 0 {
+            return None;
+        }
+        if !terminal {
+            self.cum.insert(er.cl_id.clone(), (er.filled_qty, er.avg_px));
+        }
+        // harga fill = (notional kumulatif baru - lama) / qty delta
+        Some((qty, (er.avg_px * er.filled_qty - prev_px * prev_qty) / qty))
+    }
+}
+/// Perintah kontrol ke gateway venue (di luar alur order biasa)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum VenueCmd {
+    CancelAll { reason: String },
+    Cancel { cl_id: String, symbol: String, reason: String },
+    /// cancel/replace: `cl_id` lama dibatalkan (Rejected "REPLACED"), sisa qty dikirim sebagai `order` (cl_id baru)
+    Replace { cl_id: String, order: Order },
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Event { Md(MdTick), Sig(Signal), Ord(Order), Exec(ExecReport), Note(String), Compliance(ComplianceReject), RiskReject(RiskReject) }
+impl Event {
+    /// Nama tipe (tag JSON dalam lower-case): md | sig | ord | exec | note | compliance | riskreject
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Event::Md(_) => "md", Event::Sig(_) => "sig", Event::Ord(_) => "ord",
+            Event::Exec(_) => "exec", Event::Note(_) => "note", Event::Compliance(_) => "compliance",
+            Event::RiskReject(_) => "riskreject",
+        }
+    }
+    pub fn symbol(&self) -> Option<&str> {
+        match self {
+            Event::Md(e) => Some(&e.symbol), Event::Sig(e) => Some(&e.symbol), Event::Ord(e) => Some(&e.symbol),
+            Event::Exec(e) => Some(&e.symbol), Event::Compliance(e) => Some(&e.symbol), Event::RiskReject(e) => Some(&e.symbol),
+            Event::Note(_) => None,
+        }
+    }
+    pub fn ts_ns(&self) -> Option<i128> {
+        match self {
+            Event::Md(e) => Some(e.ts_ns), Event::Sig(e) => Some(e.ts_ns), Event::Ord(e) => Some(e.ts_ns),
+            Event::Exec(e) => Some(e.ts_ns), Event::Compliance(e) => Some(e.ts_ns), Event::RiskReject(e) => Some(e.ts_ns),
+            Event::Note(_) => None,
+        }
+    }
+}
+
+/// Penolakan compliance (dicatat ke audit log / recorder); rule: deny_symbol | max_order_qty | max_order_notional
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplianceReject {
+    pub ts_ns: i128, pub rule: String, pub symbol: String, pub strategy: String, pub side: Side, pub px: i64, pub qty: i64,
+    /// batas yang dilanggar vs nilai order (unit base / quote; 0 untuk deny_symbol)
+    pub limit: f64, pub actual: f64,
+}
+
+/// Signal yang ditolak risk (selain compliance, yang punya event sendiri); reason = label `risk_rejects_total`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskReject {
+    pub ts_ns: i128, pub reason: String, pub symbol: String, pub strategy: String, pub book: String,
+    pub side: Side, pub px: i64, pub qty: i64,
+    /// pesan error lengkap (RiskError Display)
+    pub detail: String,
+}
 
 // Inventory structures
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -32,6 +254,10 @@ pub struct SymbolState {
     pub realized_pnl: i64,
     pub unrealized_pnl: i64,
     pub by_venue: std::collections::HashMap<String, VenuePosition>,
+    #[serde(default)]
+    pub by_strategy: std::collections::HashMap<String, VenuePosition>,
+    #[serde(default)]
+    pub by_book: std::collections::HashMap<String, VenuePosition>,
 }
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct InvSnapshot { pub ts_ns: i128, pub symbol: String, pub state: SymbolState }
@@ -0,0 +1,137 @@
+// ===============================
+// src/exch_format.rs
+// ===============================
+//
+// Format string harga & qty yang dikirim ke exchange, satu tempat untuk semua gateway.
+//
+// Dulu harga dikirim sebagai `format!("{price}")` dari f64 (bisa "0.30000000000000004" / desimal
+// berlebih) dan qty dari f64 × stepSize. Sekarang:
+// - angka dihitung sebagai integer kelipatan step (tanpa aritmetika float pada nilai akhir),
+//   lalu ditulis desimal biasa (tidak pernah notasi eksponen), nol di belakang dibuang
+// - qty = lot × LOT_SIZES, dibulatkan KE BAWAH ke stepSize exchange
+// - harga (tick ×100 internal) dibulatkan ke tickSize exchange: Buy ke bawah, Sell ke atas
+//   (tidak pernah membayar lebih / menjual lebih murah dari harga Signal)
+//
+// Filter per symbol: tickSize / stepSize dari exchangeInfo (symbol_registry.rs, saat refresh);
+// sebelum itu / tanpa registry: TICK_SIZES (default 0.01 = skala harga internal) dan LOT_SIZES.
+//
+// ENV:
+//   TICK_SIZES=BTCUSDT:0.01,DOGEUSDT:0.00001
+//
+use ahash::AHashMap as HashMap;
+use once_cell::sync::Lazy;
+use std::sync::RwLock;
+
+use crate::domain::Side;
+use crate::sizing;
+
+/// Skala harga internal: px = harga × 100
+const PX_DECIMALS: u32 = 2;
+/// Presisi maksimum (Binance memakai 8 desimal)
+const MAX_DECIMALS: u32 = 12;
+
+/// (tickSize, stepSize) dari exchangeInfo per symbol
+static FILTERS: Lazy<RwLock<HashMap<String, (f64, f64)>>> = Lazy::new(Default::default);
+
+/// Simpan filter exchange untuk symbol (0 = tidak diketahui)
+pub fn set_filters(symbol: &str, tick_size: f64, step_size: f64) {
+    FILTERS.write().unwrap().insert(symbol.to_string(), (tick_size, step_size));
+}
+
+fn tick_size(symbol: &str) -> f64 {
+    match FILTERS.read().unwrap().get(symbol) {
+        Some((t, _)) if *t > 0.0 => *t,
+        _ => sizing::tick_size(symbol),
+    }
+}
+
+fn step_size(symbol: &str) -> f64 {
+    match FILTERS.read().unwrap().get(symbol) {
+        Some((_, s)) if *s > 0.0 => *s,
+        _ => sizing::lot_size(symbol),
+    }
+}
+
+/// Jumlah desimal step (0.00001 -> 5, 0.5 -> 1, 10 -> 0)
+fn decimals(step: f64) -> u32 {
+    let s = format!("{:.*}", MAX_DECIMALS as usize, step);
+    let s = s.trim_end_matches('0');
+    s.split_once('.').map(|(_, d)| d.len() as u32).unwrap_or(0)
+}
+
+/// Step sebagai integer pada skala 10^-d
+fn step_units(step: f64, d: u32) -> i128 {
+    ((step * 10f64.powi(d as i32)).round() as i128).max(1)
+}
+
+/// Integer `units` × 10^-d -> "123.45" (tanpa nol di belakang)
+fn fmt_units(units: i128, d: u32) -> String {
+    let scale = 10i128.pow(d);
+    let sign = if units < 0 { "-" } else { "" };
+    let (int, frac) = (units.abs() / scale, units.abs() % scale);
+    if d == 0 || frac == 0 {
+        return format!("{sign}{int}");
+    }
+    let frac = format!("{:0width$}", frac, width = d as usize);
+    format!("{sign}{int}.{}", frac.trim_end_matches('0'))
+}
+
+/// Qty base untuk `lots` (lot × LOT_SIZES), dibulatkan ke bawah ke stepSize exchange
+pub fn qty(symbol: &str, lots: i64) -> String {
+    fmt_qty(lots, sizing::lot_size(symbol), step_size(symbol))
+}
+
+/// Harga untuk `px` (tick ×100), dibulatkan ke tickSize: Buy ke bawah, Sell ke atas
+pub fn price(symbol: &str, px: i64, side: Side) -> String {
+    fmt_price(px, tick_size(symbol), side)
+}
+
+fn fmt_qty(lots: i64, lot: f64, step: f64) -> String {
+    let d = decimals(lot).max(decimals(step));
+    let raw = lots as i128 * step_units(lot, d);
+    let step = step_units(step, d);
+    fmt_units(raw - raw.rem_euclid(step), d)
+}
+
+fn fmt_price(px: i64, tick: f64, side: Side) -> String {
+    let d = decimals(tick).max(PX_DECIMALS);
+    let raw = px as i128 * 10i128.pow(d - PX_DECIMALS);
+    let tick = step_units(tick, d);
+    let down = raw - raw.rem_euclid(tick);
+    let rounded = match side {
+        Side::Sell if down != raw => down + tick,
+        _ => down,
+    };
+    fmt_units(rounded, d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_without_exponent_and_rounds_to_filters() {
+        // qty kecil 1e-7: tidak boleh "1e-7"; lot lebih halus dari step -> floor
+        assert_eq!(fmt_qty(1, 0.0000001, 0.0000001), "0.0000001");
+        assert_eq!(fmt_qty(123, 0.0000001, 0.00001), "0.00001");
+        assert_eq!(fmt_qty(3, 0.1, 0.1), "0.3");
+        assert_eq!(fmt_qty(2_100_000_000_000, 0.00001, 0.00001), "21000000");
+        assert_eq!(fmt_qty(7, 1.0, 1.0), "7");
+
+        // default tick 0.01 = skala internal
+        assert_eq!(fmt_price(6_000_012, 0.01, Side::Buy), "60000.12");
+        assert_eq!(fmt_price(30, 0.01, Side::Sell), "0.3");
+        // tick 0.5: Buy turun, Sell naik, harga tepat di tick tidak berubah
+        assert_eq!(fmt_price(10_030, 0.5, Side::Buy), "100");
+        assert_eq!(fmt_price(10_030, 0.5, Side::Sell), "100.5");
+        assert_eq!(fmt_price(10_050, 0.5, Side::Sell), "100.5");
+        // tick lebih halus dari skala internal / lebih kasar dari 1
+        assert_eq!(fmt_price(12, 0.00001, Side::Buy), "0.12");
+        assert_eq!(fmt_price(12_345, 10.0, Side::Buy), "120");
+
+        // filter exchangeInfo menimpa fallback TICK_SIZES / LOT_SIZES
+        set_filters("XUSDT", 0.5, 0.0);
+        assert_eq!(price("XUSDT", 10_030, Side::Buy), "100");
+        assert_eq!(qty("XUSDT", 3), "3");
+    }
+}
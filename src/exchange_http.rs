@@ -0,0 +1,241 @@
+// ===============================
+// src/exchange_http.rs
+// ===============================
+//
+// Klien REST exchange (Binance-style) yang reusable:
+// - Endpoint bertipe (method, path, tingkat keamanan, boleh retry atau tidak)
+// - Injeksi otomatis timestamp/recvWindow/signature untuk endpoint SIGNED
+//   dan header X-MBX-APIKEY untuk endpoint API_KEY/SIGNED
+// - Error bertipe: transport / error API ({code,msg}) / HTTP lain / decode
+// - Retry dengan backoff untuk error yang aman diulang (transport, 5xx, 429, kode Retryable, -1021)
+//
+use reqwest::Method;
+use serde_json::Value;
+use std::fmt;
+use tokio::time::{sleep, Duration};
+
+use crate::exchange_log;
+use crate::binance::{sign_query, sync_server_time, timestamp_ms, BinanceError, ErrorClass};
+
+/// Tingkat keamanan endpoint (mengikuti istilah Binance)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Security {
+    Public,
+    ApiKey,
+    Signed,
+}
+
+#[derive(Debug, Clone)]
+pub struct Endpoint {
+    pub method: Method,
+    pub path: &'static str,
+    pub security: Security,
+    /// aman diulang (idempotent); order baru TIDAK boleh diulang otomatis
+    pub retryable: bool,
+}
+
+/// Endpoint Binance Spot yang dipakai engine
+pub mod endpoints {
+    use super::{Endpoint, Security};
+    use reqwest::Method;
+
+    pub fn new_order() -> Endpoint {
+        Endpoint { method: Method::POST, path: "/api/v3/order", security: Security::Signed, retryable: false }
+    }
+    pub fn query_order() -> Endpoint {
+        Endpoint { method: Method::GET, path: "/api/v3/order", security: Security::Signed, retryable: true }
+    }
+    pub fn cancel_order() -> Endpoint {
+        Endpoint { method: Method::DELETE, path: "/api/v3/order", security: Security::Signed, retryable: true }
+    }
+    pub fn cancel_replace() -> Endpoint {
+        Endpoint { method: Method::POST, path: "/api/v3/order/cancelReplace", security: Security::Signed, retryable: false }
+    }
+    pub fn cancel_open_orders() -> Endpoint {
+        Endpoint { method: Method::DELETE, path: "/api/v3/openOrders", security: Security::Signed, retryable: true }
+    }
+    pub fn all_orders() -> Endpoint {
+        Endpoint { method: Method::GET, path: "/api/v3/allOrders", security: Security::Signed, retryable: true }
+    }
+    pub fn my_trades() -> Endpoint {
+        Endpoint { method: Method::GET, path: "/api/v3/myTrades", security: Security::Signed, retryable: true }
+    }
+    pub fn account() -> Endpoint {
+        Endpoint { method: Method::GET, path: "/api/v3/account", security: Security::Signed, retryable: true }
+    }
+    pub fn server_time() -> Endpoint {
+        Endpoint { method: Method::GET, path: "/api/v3/time", security: Security::Public, retryable: true }
+    }
+    pub fn exchange_info() -> Endpoint {
+        Endpoint { method: Method::GET, path: "/api/v3/exchangeInfo", security: Security::Public, retryable: true }
+    }
+    pub fn create_listen_key() -> Endpoint {
+        Endpoint { method: Method::POST, path: "/api/v3/userDataStream", security: Security::ApiKey, retryable: true }
+    }
+}
+
+#[derive(Debug)]
+pub enum HttpError {
+    /// gagal kirim / koneksi (tanpa respon)
+    Transport(String),
+    /// respon error dengan body JSON {"code":..,"msg":..}
+    Api { status: u16, code: i64, msg: String },
+    /// respon non-2xx tanpa body API yang bisa di-parse
+    Http { status: u16, body: String },
+    /// respon 2xx tapi body bukan JSON
+    Decode(String),
+}
+
+impl HttpError {
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            HttpError::Api { status, .. } | HttpError::Http { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    /// Error API bertipe (None untuk transport / HTTP tanpa body API / decode)
+    pub fn binance(&self) -> Option<BinanceError> {
+        match self {
+            HttpError::Api { code, msg, .. } => Some(BinanceError::from_code(*code, msg)),
+            _ => None,
+        }
+    }
+
+    /// Error yang aman dicoba ulang (untuk endpoint retryable)
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            HttpError::Transport(_) => true,
+            HttpError::Api { status, .. } if *status >= 500 || *status == 429 => true,
+            HttpError::Api { .. } => {
+                self.binance().is_some_and(|e| e.class() == ErrorClass::Retryable || e == BinanceError::InvalidTimestamp)
+            }
+            HttpError::Http { status, .. } => *status >= 500 || *status == 429,
+            HttpError::Decode(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HttpError::Transport(e) => write!(f, "transport: {e}"),
+            HttpError::Api { status, code, msg } => write!(f, "{status} {{\"code\":{code},\"msg\":\"{msg}\"}}"),
+            HttpError::Http { status, body } => write!(f, "{status} {body}"),
+            HttpError::Decode(e) => write!(f, "decode: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for HttpError {}
+
+#[derive(Clone)]
+pub struct ExchangeHttp {
+    http: reqwest::Client,
+    base: String,
+    api_key: String,
+    api_sec: String,
+    recv_window: u64,
+    max_retries: u32,
+    retry_backoff_ms: u64,
+}
+
+impl ExchangeHttp {
+    pub fn new(base: String, api_key: String, api_sec: String, recv_window: u64) -> Self {
+        Self {
+            http: crate::net::http_client(),
+            base,
+            api_key,
+            api_sec,
+            recv_window,
+            max_retries: 3,
+            retry_backoff_ms: 200,
+        }
+    }
+
+    /// Kirim request ke endpoint; param query diberi timestamp/signature sesuai `security`.
+    pub async fn send(&self, ep: &Endpoint, params: &[(&str, String)]) -> Result<Value, HttpError> {
+        let mut attempt: u32 = 0;
+        loop {
+            match self.send_once(ep, params).await {
+                Err(e) if ep.retryable && e.is_retryable() && attempt < self.max_retries => {
+                    attempt += 1;
+                    let wait = self.retry_backoff_ms << (attempt - 1);
+                    tracing::warn!(path = ep.path, attempt, wait_ms = wait, err = %e, "exchange http retry");
+                    sleep(Duration::from_millis(wait)).await;
+                }
+                other => return other,
+            }
+        }
+    }
+
+    /// Sinkronkan offset jam ke server (GET /api/v3/time); offset baru dalam ms
+    pub async fn sync_time(&self) -> Result<i64, HttpError> {
+        let v = self.send(&endpoints::server_time(), &[]).await?;
+        let server_ms = v.get("serverTime").and_then(|x| x.as_u64()).ok_or_else(|| HttpError::Decode(v.to_string()))?;
+        Ok(sync_server_time(server_ms))
+    }
+
+    async fn send_once(&self, ep: &Endpoint, params: &[(&str, String)]) -> Result<Value, HttpError> {
+        let mut pairs: Vec<String> = params
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, urlencoding::encode(v)))
+            .collect();
+        if ep.security == Security::Signed {
+            // timestamp baru tiap attempt (retry -1021 butuh timestamp segar)
+            pairs.push(format!("timestamp={}", timestamp_ms()));
+            pairs.push(format!("recvWindow={}", self.recv_window));
+        }
+        let mut query = pairs.join("&");
+        if ep.security == Security::Signed {
+            let sig = sign_query(&self.api_sec, &query);
+            query = if query.is_empty() { format!("signature={sig}") } else { format!("{query}&signature={sig}") };
+        }
+        let url = if query.is_empty() {
+            format!("{}{}", self.base, ep.path)
+        } else {
+            format!("{}{}?{}", self.base, ep.path, query)
+        };
+
+        let mut req = self.http.request(ep.method.clone(), url);
+        if ep.security != Security::Public {
+            req = req.header("X-MBX-APIKEY", &self.api_key);
+        }
+        let started = std::time::Instant::now();
+        let rsp = async {
+            let rsp = req.send().await?;
+            let status = rsp.status().as_u16();
+            Ok::<_, reqwest::Error>((status, rsp.text().await?))
+        }
+        .await;
+        if exchange_log::enabled() {
+            let (status, response) = match &rsp {
+                Ok((status, body)) => (Some(*status), body.clone()),
+                Err(e) => (None, e.to_string()),
+            };
+            exchange_log::record(exchange_log::Entry {
+                ts_ns: crate::clock::now_ns(),
+                transport: "rest",
+                method: ep.method.as_str(),
+                path: ep.path,
+                request: query.clone(),
+                status,
+                latency_us: started.elapsed().as_micros() as u64,
+                response,
+            });
+        }
+        let (status, body) = rsp.map_err(|e| HttpError::Transport(e.to_string()))?;
+
+        if (200..300).contains(&status) {
+            return serde_json::from_str(&body).map_err(|e| HttpError::Decode(format!("{e}: {body}")));
+        }
+        match serde_json::from_str::<Value>(&body) {
+            Ok(v) if v.get("code").and_then(|c| c.as_i64()).is_some() => Err(HttpError::Api {
+                status,
+                code: v["code"].as_i64().unwrap_or(0),
+                msg: v.get("msg").and_then(|m| m.as_str()).unwrap_or("").to_string(),
+            }),
+            _ => Err(HttpError::Http { status, body }),
+        }
+    }
+}
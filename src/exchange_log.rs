@@ -0,0 +1,183 @@
+// ===============================
+// src/exchange_log.rs
+// ===============================
+//
+// Log request/response exchange (debug post-mortem reject order), JSONL terpisah dari recorder.
+//
+// - Semua request REST (ExchangeHttp) & WS API (WsApiClient) + respon / error-nya, satu baris per call:
+//   {"ts_ns":..,"transport":"rest","method":"POST","path":"/api/v3/order","request":"symbol=..&signature=<redacted>",
+//    "status":400,"latency_us":..,"response":"{\"code\":-2010,..}"}
+// - Rahasia disamarkan sebelum ditulis: query/param `signature`, `apiKey`, `listenKey` (juga di body respon).
+//   Header X-MBX-APIKEY tidak pernah dicatat.
+// - Body respon dipotong ke MAX_BODY byte; antrean penuh -> entri dibuang (exchange_log_dropped_total).
+//
+// ENV:
+//   EXCHANGE_LOG_FILE=/var/log/dma/exchange.jsonl   (kosong = nonaktif)
+//
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+use tokio::{
+    fs::{self, OpenOptions},
+    io::{AsyncWriteExt, BufWriter},
+    sync::mpsc,
+    time::{interval, Duration, MissedTickBehavior},
+};
+use tracing::{error, info};
+
+use crate::metrics::EXCHANGE_LOG_DROPPED;
+
+/// Kunci yang nilainya tidak boleh keluar dari proses
+const SECRET_KEYS: &[&str] = &["signature", "apiKey", "listenKey"];
+const REDACTED: &str = "<redacted>";
+const MAX_BODY: usize = 4096;
+const QUEUE_CAP: usize = 4096;
+
+static SINK: OnceCell<mpsc::Sender<String>> = OnceCell::new();
+
+#[derive(Debug, Serialize)]
+pub struct Entry<'a> {
+    pub ts_ns: i128,
+    /// rest | ws
+    pub transport: &'a str,
+    /// metode HTTP, atau metode WS API (`order.place`, ...)
+    pub method: &'a str,
+    pub path: &'a str,
+    pub request: String,
+    /// status HTTP (WS API: status di respon); None = gagal transport
+    pub status: Option<u16>,
+    pub latency_us: u64,
+    pub response: String,
+}
+
+/// Aktifkan log; kembalikan receiver untuk task `run`
+pub fn init() -> mpsc::Receiver<String> {
+    let (tx, rx) = mpsc::channel(QUEUE_CAP);
+    let _ = SINK.set(tx);
+    rx
+}
+
+pub fn enabled() -> bool {
+    SINK.get().is_some()
+}
+
+/// Catat satu call (no-op jika log tidak aktif); request & response disamarkan di sini
+pub fn record(mut e: Entry<'_>) {
+    let Some(tx) = SINK.get() else { return };
+    e.request = redact_query(&e.request);
+    e.response = truncate(redact_body(&e.response));
+    let Ok(line) = serde_json::to_string(&e) else { return };
+    if tx.try_send(line).is_err() {
+        EXCHANGE_LOG_DROPPED.inc();
+    }
+}
+
+/// `k=v&k2=v2` -> nilai kunci rahasia diganti
+pub fn redact_query(q: &str) -> String {
+    q.split('&')
+        .map(|kv| match kv.split_once('=') {
+            Some((k, _)) if SECRET_KEYS.contains(&k) => format!("{k}={REDACTED}"),
+            _ => kv.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Body JSON: kunci rahasia (di level mana pun) diganti; body non-JSON dikembalikan apa adanya
+pub fn redact_body(body: &str) -> String {
+    match serde_json::from_str::<Value>(body) {
+        Ok(mut v) => {
+            redact_value(&mut v);
+            v.to_string()
+        }
+        Err(_) => body.to_string(),
+    }
+}
+
+fn redact_value(v: &mut Value) {
+    match v {
+        Value::Object(m) => {
+            for (k, x) in m.iter_mut() {
+                if SECRET_KEYS.contains(&k.as_str()) {
+                    *x = Value::String(REDACTED.to_string());
+                } else {
+                    redact_value(x);
+                }
+            }
+        }
+        Value::Array(a) => a.iter_mut().for_each(redact_value),
+        _ => {}
+    }
+}
+
+fn truncate(mut s: String) -> String {
+    if s.len() > MAX_BODY {
+        let mut end = MAX_BODY;
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        s.truncate(end);
+        s.push_str("...");
+    }
+    s
+}
+
+async fn open_writer(path: &str) -> Option<BufWriter<tokio::fs::File>> {
+    if let Some(parent) = Path::new(path).parent() {
+        if !parent.as_os_str().is_empty() {
+            let _ = fs::create_dir_all(parent).await;
+        }
+    }
+    match OpenOptions::new().create(true).append(true).open(path).await {
+        Ok(f) => Some(BufWriter::new(f)),
+        Err(e) => {
+            error!(?e, %path, "exchange log: open failed");
+            None
+        }
+    }
+}
+
+/// Task penulis file (flush tiap 1s)
+pub async fn run(mut rx: mpsc::Receiver<String>, path: String) {
+    info!(%path, "exchange log: started (secrets redacted)");
+    let Some(mut writer) = open_writer(&path).await else { return };
+    let mut tick = interval(Duration::from_secs(1));
+    tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            maybe = rx.recv() => {
+                let Some(line) = maybe else { break };
+                if let Err(e) = writer.write_all(format!("{line}\n").as_bytes()).await {
+                    error!(?e, "exchange log: write failed, reopening");
+                    match open_writer(&path).await {
+                        Some(w) => writer = w,
+                        None => return,
+                    }
+                }
+            }
+            _ = tick.tick() => {
+                let _ = writer.flush().await;
+            }
+        }
+    }
+    let _ = writer.flush().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secrets_are_redacted() {
+        let q = "symbol=BTCUSDT&newClientOrderId=C0mK4Hq2Tz5&timestamp=1&signature=abcdef0123";
+        assert_eq!(redact_query(q), "symbol=BTCUSDT&newClientOrderId=C0mK4Hq2Tz5&timestamp=1&signature=<redacted>");
+
+        let ws = r#"{"id":"dma-1","method":"order.place","params":{"apiKey":"KEY","signature":"SIG","symbol":"BTCUSDT"}}"#;
+        let out = redact_body(ws);
+        assert!(!out.contains("KEY") && !out.contains("SIG") && out.contains("BTCUSDT"));
+
+        assert_eq!(redact_body(r#"{"listenKey":"pqia91ma19a5s61cv6a81va65sdf19v8a65a1a5s61cv6a81va65sdf19v8a65a1"}"#), r#"{"listenKey":"<redacted>"}"#);
+        assert_eq!(redact_body("<html>502 Bad Gateway</html>"), "<html>502 Bad Gateway</html>");
+    }
+}
@@ -0,0 +1,172 @@
+// ===============================
+// src/exec_guard.rs
+// ===============================
+//
+// Proteksi ExecReport duplikat / out-of-order di fan-out exec (sebelum
+// posttrade, positions, blotter).
+//
+// Event user data Binance bisa terkirim ulang setelah reconnect. Aturan per cl_id:
+// - filled_qty kumulatif harus monoton naik; report dengan cum lebih kecil = out_of_order
+// - report dengan status & cum sama seperti sebelumnya = duplicate
+// - ACK setelah order sudah terisi sebagian = out_of_order
+// - report apa pun setelah status final (Filled/Rejected/Expired) = after_terminal
+//
+// Report yang ditolak dibuang dan dihitung di `exec_reports_dropped_total{reason}`.
+// cl_id final diingat (dibatasi `max_terminal`) agar replay lama tetap terdeteksi.
+// cl_id yang belum final (order resting lama, report final yang hilang) dibatasi `max_open`: saat lewat batas,
+// seperempat entry yang paling lama tidak mendapat report dilupakan. Report berikutnya untuk cl_id itu
+// diperlakukan seperti report pertama (diterima), bukan dibuang.
+//
+use ahash::{AHashMap as HashMap, AHashSet as HashSet};
+use std::collections::VecDeque;
+use tracing::debug;
+
+use crate::domain::{ExecReport, ExecStatus};
+use crate::metrics::EXEC_DROPPED;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Ack,
+    Fill,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Seen {
+    kind: Kind,
+    cum_qty: i64,
+    /// urutan report terakhir (untuk eviction entry paling lama diam)
+    seq: u64,
+}
+
+pub struct ExecGuard {
+    open: HashMap<String, Seen>,
+    terminal: HashSet<String>,
+    terminal_order: VecDeque<String>,
+    max_terminal: usize,
+    max_open: usize,
+    seq: u64,
+}
+
+impl ExecGuard {
+    pub fn new(max_terminal: usize, max_open: usize) -> Self {
+        Self {
+            open: HashMap::new(),
+            terminal: HashSet::new(),
+            terminal_order: VecDeque::new(),
+            max_terminal: max_terminal.max(1),
+            max_open: max_open.max(4),
+            seq: 0,
+        }
+    }
+
+    /// true = report diteruskan; false = dibuang (duplikat / out-of-order)
+    pub fn accept(&mut self, er: &ExecReport) -> bool {
+        match self.check(er) {
+            Ok(()) => true,
+            Err(reason) => {
+                EXEC_DROPPED.with_label_values(&[reason]).inc();
+                debug!(cl_id = %er.cl_id, status = ?er.status, filled = er.filled_qty, %reason, "exec report dropped");
+                false
+            }
+        }
+    }
+
+    fn check(&mut self, er: &ExecReport) -> Result<(), &'static str> {
+        if self.terminal.contains(&er.cl_id) {
+            return Err("after_terminal");
+        }
+        let prev = self.open.get(&er.cl_id).copied();
+        let kind = match er.status {
+            ExecStatus::Ack => Kind::Ack,
+            ExecStatus::PartialFill => Kind::Fill,
+            ExecStatus::Filled | ExecStatus::Rejected(_) | ExecStatus::Expired => {
+                if let Some(p) = prev {
+                    if er.filled_qty < p.cum_qty {
+                        return Err("out_of_order");
+                    }
+                }
+                self.open.remove(&er.cl_id);
+                self.mark_terminal(&er.cl_id);
+                return Ok(());
+            }
+        };
+        if let Some(p) = prev {
+            if er.filled_qty < p.cum_qty || (kind == Kind::Ack && p.kind == Kind::Fill) {
+                return Err("out_of_order");
+            }
+            if kind == p.kind && er.filled_qty == p.cum_qty {
+                return Err("duplicate");
+            }
+        }
+        self.seq += 1;
+        self.open.insert(er.cl_id.clone(), Seen { kind, cum_qty: er.filled_qty, seq: self.seq });
+        if self.open.len() > self.max_open {
+            self.evict_idle();
+        }
+        Ok(())
+    }
+
+    /// Lupakan seperempat cl_id non-final yang paling lama diam (amortized: tidak tiap report)
+    fn evict_idle(&mut self) {
+        let n = self.max_open / 4;
+        let mut seqs: Vec<u64> = self.open.values().map(|s| s.seq).collect();
+        let (_, &mut cutoff, _) = seqs.select_nth_unstable(n);
+        self.open.retain(|_, s| s.seq >= cutoff);
+        debug!(evicted = n, open = self.open.len(), "exec guard: idle open cl_ids evicted");
+    }
+
+    fn mark_terminal(&mut self, cl_id: &str) {
+        if self.terminal.insert(cl_id.to_string()) {
+            self.terminal_order.push_back(cl_id.to_string());
+            while self.terminal_order.len() > self.max_terminal {
+                if let Some(old) = self.terminal_order.pop_front() {
+                    self.terminal.remove(&old);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn er(cl_id: &str, status: ExecStatus, filled_qty: i64) -> ExecReport {
+        ExecReport {
+            cl_id: cl_id.into(), symbol: "BTCUSDT".into(), status, filled_qty, avg_px: 100, ts_ns: 0, venue: "A".into(),
+            side: None, order_qty: 5, strategy: String::new(), tags: vec![], book: String::new(), exch_ts_ns: 0,
+        }
+    }
+
+    #[test]
+    fn duplicate_out_of_order_and_after_terminal_are_dropped() {
+        let mut g = ExecGuard::new(10, 10);
+        assert_eq!(g.check(&er("c1", ExecStatus::Ack, 0)), Ok(()));
+        assert_eq!(g.check(&er("c1", ExecStatus::Ack, 0)), Err("duplicate"));
+        assert_eq!(g.check(&er("c1", ExecStatus::PartialFill, 2)), Ok(()));
+        assert_eq!(g.check(&er("c1", ExecStatus::PartialFill, 2)), Err("duplicate"));
+        assert_eq!(g.check(&er("c1", ExecStatus::PartialFill, 1)), Err("out_of_order"));
+        assert_eq!(g.check(&er("c1", ExecStatus::Ack, 2)), Err("out_of_order"));
+        assert_eq!(g.check(&er("c1", ExecStatus::Filled, 1)), Err("out_of_order"));
+        assert_eq!(g.check(&er("c1", ExecStatus::Filled, 5)), Ok(()));
+        assert_eq!(g.check(&er("c1", ExecStatus::PartialFill, 5)), Err("after_terminal"));
+        assert_eq!(g.check(&er("c1", ExecStatus::Filled, 5)), Err("after_terminal"));
+        assert!(g.open.is_empty());
+    }
+
+    #[test]
+    fn idle_open_cl_ids_are_evicted_past_the_bound() {
+        let mut g = ExecGuard::new(10, 8);
+        for i in 0..8 {
+            assert_eq!(g.check(&er(&format!("o{i}"), ExecStatus::Ack, 0)), Ok(()));
+        }
+        // o0 tetap aktif: report baru menggeser urutan diamnya
+        assert_eq!(g.check(&er("o0", ExecStatus::PartialFill, 1)), Ok(()));
+        assert_eq!(g.check(&er("o8", ExecStatus::Ack, 0)), Ok(()));
+        assert!(g.open.len() <= 8);
+        assert!(g.open.contains_key("o0") && g.open.contains_key("o8"));
+        assert!(!g.open.contains_key("o1") && !g.open.contains_key("o2"));
+        // yang dilupakan diperlakukan seperti report pertama
+        assert_eq!(g.check(&er("o1", ExecStatus::Ack, 0)), Ok(()));
+    }
+}
@@ -3,42 +3,89 @@
 // ===============================
 //
 // Market Data adapters:
-// - run_mock      : random-walk generator (~200 ticks/s)
+// - run_mock      : generator mock (~200 ticks/s), skenario via MOCK_SCENARIO (lihat mock_scenario.rs),
+//                   depth L2 opsional via MOCK_BOOK_LEVELS
 // - run_binance   : Binance WS bookTicker (works for Sandbox & Mainnet)
-//                    pass the WS base URL from config (no hardcoded ENV)
+//                    pass the WS base URL from config (no hardcoded ENV); hanya dengan fitur `binance`
 //
 // Notes:
 // - Domain price scale: we use 2 decimals (px * 100) for PoC consistency.
 //   For production, derive tickSize/stepSize from exchangeInfo and scale properly.
 //
 
-use chrono::Utc;
-use futures_util::StreamExt; // for .next()
-use rand::Rng;
 use std::time::Duration;
 use tokio::time::sleep;
-use tokio_tungstenite::connect_async;
-use tracing::{error, info, warn};
-use url::Url;
 
-use crate::domain::MdTick;
+use crate::chaos::{self, Point};
+use crate::clock;
+use crate::domain::{BookUpdate, MdTick};
+use crate::md_ring::MdRing;
+use crate::md_filter::TickFilter;
+use crate::md_seq;
 use crate::metrics::TICKS;
+use crate::mock_scenario::{MockGen, Scenario};
 
-/// Generator market data mock (random walk) ~200 ticks/s
-pub async fn run_mock(md_tx: tokio::sync::broadcast::Sender<MdTick>, symbol: String) {
-    let mut px_bid: i64 = 100_00; // 100.00 (2 desimal)
+#[cfg(feature = "binance")]
+use {
+    crate::metrics::MD_NET_DELAY,
+    crate::reconnect::Backoff,
+    futures_util::StreamExt, // for .next()
+    tracing::{error, info, warn},
+    url::Url,
+};
+
+/// Generator market data mock ~200 ticks/s (harga awal 100.00, 2 desimal)
+/// + BookUpdate `book_levels` level per tick jika > 0.
+///
+/// `ring` (MD_BUS=ring): tick juga ditulis langsung ke ring symbol ini untuk worker strategi
+pub async fn run_mock(
+    md_tx: tokio::sync::broadcast::Sender<MdTick>,
+    book_tx: tokio::sync::broadcast::Sender<BookUpdate>,
+    ring: Option<MdRing>,
+    symbol: String,
+    scenario: Scenario,
+    book_levels: usize,
+) {
+    let mut gen = MockGen::new(scenario);
+    let mut filter = TickFilter::default();
+    let mut ring = ring.and_then(|r| r.publisher(&symbol, crate::book_analytics::levels_from_env()));
+    let mut last_book = None;
     loop {
         // jangan simpan ThreadRng melewati .await
-        let step = rand::thread_rng().gen_range(-3..=3);
-        px_bid = (px_bid + step).max(50_00);
-        let tick = MdTick {
-            ts_ns: Utc::now().timestamp_nanos_opt().unwrap_or(0) as i128,
+        let q = gen.next(&mut rand::thread_rng());
+        if book_levels > 0 {
+            let (bids, asks) = gen.book(&q, book_levels, &mut rand::thread_rng());
+            let ts_ns = clock::now_ns();
+            let book = BookUpdate { ts_ns, symbol: symbol.clone(), bids, asks };
+            // statistik depth untuk ring dihitung di sini (tanpa task analytics)
+            if ring.is_some() {
+                last_book = Some(book.clone());
+            }
+            let _ = book_tx.send(book);
+        }
+        let mut tick = MdTick {
+            ts_ns: clock::now_ns(),
             symbol: symbol.clone(),
-            best_bid: px_bid,
-            best_ask: px_bid + 1,
+            best_bid: q.bid,
+            best_ask: q.ask,
+            bid_qty: q.bid_qty,
+            ask_qty: q.ask_qty,
+            seq: 0,
+            exch_ts_ns: 0,
         };
-        let _ = md_tx.send(tick);
-        TICKS.inc();
+        // quote rusak dibuang sebelum diberi seq (bukan gap)
+        if filter.accept(&tick) {
+            // seq diberi sebelum chaos drop: tick yang dibuang terlihat sebagai gap
+            md_seq::stamp(&mut tick);
+            chaos::delay(Point::Md).await;
+            if !chaos::drop(Point::Md) {
+                if let Some(r) = &mut ring {
+                    r.publish(&tick, last_book.as_ref());
+                }
+                let _ = md_tx.send(tick);
+                TICKS.inc();
+            }
+        }
         sleep(Duration::from_millis(5)).await; // ~200 ticks/s
     }
 }
@@ -50,15 +97,19 @@ pub async fn run_mock(md_tx: tokio::sync::broadcast::Sender<MdTick>, symbol: Str
 ///     * Mainnet: wss://stream.binance.com:9443/ws
 /// - `symbol` adalah domain symbol (mis. "BTCUSDT") — kita lower-case saat susun topic.
 /// - Skala harga: 2 desimal (PoC). Untuk produksi, gunakan tickSize dari `exchangeInfo`.
+#[cfg(feature = "binance")]
 pub async fn run_binance(
     md_tx: tokio::sync::broadcast::Sender<MdTick>,
+    ring: Option<MdRing>,
     symbol: String,
     ws_base: String,
 ) {
+    let mut ring = ring.and_then(|r| r.publisher(&symbol, 1));
     let topic = format!("{}@bookTicker", symbol.to_lowercase());
     let ws_url = format!("{}/{}", ws_base.trim_end_matches('/'), topic);
 
-    let mut attempt: u32 = 0;
+    let mut backoff = Backoff::new(format!("bookTicker:{symbol}"));
+    let mut filter = TickFilter::default();
     loop {
         let url = match Url::parse(&ws_url) {
             Ok(u) => u,
@@ -69,12 +120,16 @@ pub async fn run_binance(
         };
 
         info!(%ws_url, "connecting binance bookTicker");
-        match connect_async(url).await {
+        match crate::net::connect_ws(url.as_str()).await {
             Ok((mut ws, _resp)) => {
                 info!("connected to bookTicker for {}", symbol);
-                attempt = 0; // reset backoff
+                backoff.connected();
 
                 while let Some(frame) = ws.next().await {
+                    if chaos::ws_disconnect() {
+                        warn!("chaos: forcing bookTicker disconnect");
+                        break;
+                    }
                     match frame {
                         Ok(m) if m.is_text() => {
                             // Contoh payload:
@@ -93,15 +148,34 @@ pub async fn run_binance(
                                     // NOTE: PoC scale 2 decimals
                                     let bid = (b.parse::<f64>().unwrap_or(0.0) * 100.0).round() as i64;
                                     let ask = (a.parse::<f64>().unwrap_or(0.0) * 100.0).round() as i64;
-                                    if bid > 0 && ask > 0 {
-                                        let tick = MdTick {
-                                            ts_ns: Utc::now().timestamp_nanos_opt().unwrap_or(0) as i128,
-                                            symbol: symbol.clone(),
-                                            best_bid: bid,
-                                            best_ask: ask,
-                                        };
-                                        let _ = md_tx.send(tick);
-                                        TICKS.inc();
+                                    // ukuran B/A dibulatkan ke unit qty domain
+                                    let size = |k: &str| {
+                                        v.get(k).and_then(|x| x.as_str()).and_then(|x| x.parse::<f64>().ok()).unwrap_or(0.0).round() as i64
+                                    };
+                                    let mut tick = MdTick {
+                                        ts_ns: clock::now_ns(),
+                                        symbol: symbol.clone(),
+                                        best_bid: bid,
+                                        best_ask: ask,
+                                        bid_qty: size("B"),
+                                        ask_qty: size("A"),
+                                        seq: 0,
+                                        // bookTicker Spot tanpa `E`; stream lain (mis. futures) mengisinya
+                                        exch_ts_ns: clock::exch_ms_to_ns(v.get("E").and_then(|x| x.as_u64())),
+                                    };
+                                    // zero / crossed / bad print dibuang di sini
+                                    if filter.accept(&tick) {
+                                        if tick.exch_ts_ns > 0 {
+                                            MD_NET_DELAY.observe((tick.ts_ns - tick.exch_ts_ns) as f64 / 1e6);
+                                        }
+                                        md_seq::stamp(&mut tick);
+                                        if !chaos::drop(Point::Md) {
+                                            if let Some(r) = &mut ring {
+                                                r.publish(&tick, None);
+                                            }
+                                            let _ = md_tx.send(tick);
+                                            TICKS.inc();
+                                        }
                                     }
                                 }
                             }
@@ -122,12 +196,9 @@ pub async fn run_binance(
             }
         }
 
-        // Exponential backoff + jitter
-        attempt = attempt.saturating_add(1);
-        let shift = attempt.min(6) as u32;           // 0..=6
-        let factor = 1u64 << shift;                  // 1,2,4,...,64
-        let base_ms = 500u64.saturating_mul(factor); // 0.5s..32s
-        let jitter = rand::thread_rng().gen_range(0..=250);
-        sleep(Duration::from_millis(base_ms + jitter)).await;
+        // Exponential backoff + jitter (reconnect::Policy)
+        if !backoff.wait().await {
+            return;
+        }
     }
 }
@@ -0,0 +1,92 @@
+// ===============================
+// src/feed_health.rs
+// ===============================
+//
+// Registry kesehatan feed per symbol: waktu + best bid/ask tick terakhir dari bus MD (feed lokal, IPC, mock).
+// Router memeriksanya sebelum merutekan; order untuk symbol tanpa MD (feed mati / tidak
+// pernah dikonfigurasi) ditolak dengan ExecReport Rejected("NO_MARKET_DATA") alih-alih
+// dirutekan buta. Quote terakhir juga dipakai router untuk pegging harga child.
+//
+// ENV: FEED_STALE_MS=10000   (tick terakhir lebih tua dari ini = tidak ada MD; 0 = cek nonaktif)
+//
+use ahash::AHashMap as HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use tracing::warn;
+
+use crate::domain::MdTick;
+use crate::metrics::MD_LIVE;
+
+#[derive(Clone, Copy)]
+struct LastTick {
+    at: Instant,
+    bid: i64,
+    ask: i64,
+}
+
+/// Handle registry (diisi task `run`, dibaca router)
+#[derive(Clone)]
+pub struct FeedHealth {
+    stale_after: Option<Duration>,
+    last_tick: Arc<RwLock<HashMap<String, LastTick>>>,
+}
+
+impl FeedHealth {
+    pub fn new(stale_ms: u64) -> Self {
+        Self { stale_after: (stale_ms > 0).then(|| Duration::from_millis(stale_ms)), last_tick: Arc::default() }
+    }
+
+    /// Alasan tidak ada MD untuk symbol (None = MD segar / cek nonaktif)
+    pub fn check(&self, symbol: &str) -> Option<&'static str> {
+        let stale_after = self.stale_after?;
+        match self.last_tick.read().unwrap().get(symbol) {
+            None => Some("no ticks"),
+            Some(t) if t.at.elapsed() > stale_after => Some("stale"),
+            Some(_) => None,
+        }
+    }
+
+    /// Best (bid, ask) terakhir
+    pub fn quote(&self, symbol: &str) -> Option<(i64, i64)> {
+        self.last_tick.read().unwrap().get(symbol).map(|t| (t.bid, t.ask))
+    }
+
+    fn touch(&self, md: &MdTick) {
+        let mut g = self.last_tick.write().unwrap();
+        let v = LastTick { at: Instant::now(), bid: md.best_bid, ask: md.best_ask };
+        match g.get_mut(&md.symbol) {
+            Some(t) => *t = v,
+            None => {
+                g.insert(md.symbol.clone(), v);
+            }
+        }
+    }
+}
+
+/// Task: catat tick terakhir per symbol + publish `md_live{symbol}` tiap detik
+pub async fn run(mut md_rx: broadcast::Receiver<MdTick>, health: FeedHealth) {
+    let mut tick = tokio::time::interval(Duration::from_secs(1));
+    let mut live: HashMap<String, bool> = HashMap::new();
+    loop {
+        tokio::select! {
+            r = md_rx.recv() => match r {
+                Ok(md) => health.touch(&md),
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            _ = tick.tick() => {
+                let symbols: Vec<String> = health.last_tick.read().unwrap().keys().cloned().collect();
+                for sym in symbols {
+                    let ok = health.check(&sym).is_none();
+                    if live.insert(sym.clone(), ok) == Some(true) && !ok {
+                        warn!(symbol = %sym, "market data stale, router will reject orders");
+                    }
+                    if crate::metrics::symbol_admitted(&sym) {
+                        MD_LIVE.with_label_values(&[&sym]).set(ok as i64);
+                    }
+                }
+            }
+        }
+    }
+}
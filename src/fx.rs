@@ -0,0 +1,183 @@
+// ===============================
+// src/fx.rs
+// ===============================
+//
+// Kurs silang (cross rate) aset -> USD untuk symbol dengan quote berbeda (BTCUSDT, ETHBTC, BTCEUR, ...).
+//
+// - Stablecoin di FX_USD_ASSETS bernilai 1 USD.
+// - Kurs aset lain diturunkan dari mid MD: BTCUSDT -> BTC = mid × kurs(USDT); ETHBTC -> ETH = mid × kurs(BTC).
+//   Juga arah sebaliknya (USDTTRY -> TRY = kurs(USDT) / mid).
+// - FX_RATES memberi kurs statis (fiat / aset yang tidak ada di SYMBOLS); kurs dari MD menimpanya.
+//
+// Notional risk (leverage, budget book, grup korelasi) dan PnL portofolio (`pnl_portfolio_usd`)
+// dihitung dalam USD-ekuivalen. Symbol yang quote-nya belum punya kurs ditolak risk (`NoCrossRate`);
+// symbol dengan suffix quote tak dikenal dianggap ber-quote USD (perilaku lama).
+//
+// ENV:
+//   FX_USD_ASSETS=USDT,USDC,FDUSD,BUSD,TUSD,USD
+//   FX_RATES=EUR:1.08,TRY:0.03
+//
+use ahash::{AHashMap as HashMap, AHashSet as HashSet};
+use once_cell::sync::Lazy;
+use std::sync::RwLock;
+use tokio::sync::broadcast;
+
+use crate::account::split_symbol;
+use crate::domain::MdTick;
+use crate::metrics::{FX_RATE_USD, PNL_PORTFOLIO_USD};
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FxCfg {
+    pub usd_assets: Vec<String>,
+    pub static_rates: HashMap<String, f64>,
+}
+
+impl Default for FxCfg {
+    fn default() -> Self {
+        Self {
+            usd_assets: ["USDT", "USDC", "FDUSD", "BUSD", "TUSD", "USD"].iter().map(|s| s.to_string()).collect(),
+            static_rates: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct State {
+    /// aset bernilai tetap 1 USD
+    usd: HashSet<String>,
+    /// aset -> USD
+    rates: HashMap<String, f64>,
+    /// symbol -> (realized, unrealized) PnL dalam quote
+    pnl: HashMap<String, (f64, f64)>,
+}
+
+static FX: Lazy<RwLock<State>> = Lazy::new(|| RwLock::new(State::from_cfg(&FxCfg::default())));
+
+impl State {
+    fn from_cfg(cfg: &FxCfg) -> Self {
+        let mut rates = cfg.static_rates.clone();
+        for a in &cfg.usd_assets {
+            rates.insert(a.clone(), 1.0);
+        }
+        Self { usd: cfg.usd_assets.iter().cloned().collect(), rates, pnl: HashMap::new() }
+    }
+
+    /// Update kurs dari mid `px` (unit quote per base)
+    fn on_mid(&mut self, symbol: &str, px: f64) {
+        let Some((base, quote)) = split_symbol(symbol) else { return };
+        if self.usd.contains(base) {
+            if !self.usd.contains(quote) {
+                self.set(quote, 1.0 / px);
+            }
+        } else if let Some(q) = self.rates.get(quote).copied() {
+            self.set(base, px * q);
+        } else if let Some(b) = self.rates.get(base).copied() {
+            self.set(quote, b / px);
+        }
+    }
+
+    fn set(&mut self, asset: &str, rate: f64) {
+        if rate.is_finite() && rate > 0.0 {
+            self.rates.insert(asset.to_string(), rate);
+            FX_RATE_USD.with_label_values(&[asset]).set(rate);
+        }
+    }
+
+    /// Kurs quote symbol; None jika quote dikenal tapi belum ada kurs
+    fn quote_rate(&self, symbol: &str) -> Option<f64> {
+        match split_symbol(symbol) {
+            Some((_, quote)) => self.rates.get(quote).copied(),
+            None => Some(1.0),
+        }
+    }
+}
+
+/// Set konfigurasi kurs (dipanggil sekali di startup)
+pub fn init(cfg: FxCfg) {
+    if !cfg.static_rates.is_empty() {
+        tracing::info!(rates = ?cfg.static_rates, usd = ?cfg.usd_assets, "fx: static cross rates");
+    }
+    let mut st = State::from_cfg(&cfg);
+    for (a, r) in st.rates.clone() {
+        st.set(&a, r);
+    }
+    *FX.write().unwrap() = st;
+}
+
+/// Update kurs dari mid (tick ×100) symbol
+pub fn on_mid(symbol: &str, mid: i64) {
+    if mid > 0 {
+        FX.write().unwrap().on_mid(symbol, mid as f64 / 100.0);
+    }
+}
+
+/// Kurs quote asset `symbol` -> USD (1.0 untuk symbol tanpa suffix quote dikenal)
+pub fn quote_rate(symbol: &str) -> Option<f64> {
+    FX.read().unwrap().quote_rate(symbol)
+}
+
+/// Quote asset yang belum punya kurs (alasan tolak di risk)
+pub fn missing_quote(symbol: &str) -> Option<String> {
+    let st = FX.read().unwrap();
+    match split_symbol(symbol) {
+        Some((_, quote)) if st.quote_rate(symbol).is_none() => Some(quote.to_string()),
+        _ => None,
+    }
+}
+
+/// Nilai quote `amount` dalam USD; kurs tak dikenal -> apa adanya
+pub fn to_usd(symbol: &str, amount: f64) -> f64 {
+    amount * quote_rate(symbol).unwrap_or(1.0)
+}
+
+/// Catat PnL symbol (quote) dan publish total portofolio dalam USD
+pub fn set_pnl(symbol: &str, realized: f64, unrealized: f64) {
+    FX.write().unwrap().pnl.insert(symbol.to_string(), (realized, unrealized));
+    let (r, u) = portfolio_pnl();
+    PNL_PORTFOLIO_USD.with_label_values(&["realized"]).set(r);
+    PNL_PORTFOLIO_USD.with_label_values(&["unrealized"]).set(u);
+}
+
+/// (realized, unrealized) semua symbol dalam USD, dengan kurs terkini
+pub fn portfolio_pnl() -> (f64, f64) {
+    let st = FX.read().unwrap();
+    st.pnl.iter().fold((0.0, 0.0), |(r, u), (s, (pr, pu))| {
+        let rate = st.quote_rate(s).unwrap_or(1.0);
+        (r + pr * rate, u + pu * rate)
+    })
+}
+
+/// Task: turunkan kurs dari MD
+pub async fn run(mut md_rx: broadcast::Receiver<MdTick>) {
+    loop {
+        match md_rx.recv().await {
+            Ok(md) => on_mid(&md.symbol, (md.best_bid + md.best_ask) / 2),
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_cross_rates_through_quote_chain() {
+        let mut st = State::from_cfg(&FxCfg { static_rates: [("EUR".to_string(), 1.1)].into_iter().collect(), ..Default::default() });
+        assert_eq!(st.quote_rate("ETHBTC"), None);
+        assert_eq!(st.quote_rate("BTCEUR"), Some(1.1));
+        assert_eq!(st.quote_rate("FOOBAR"), Some(1.0));
+
+        // BTCUSDT 60_000 -> BTC = 60_000 USD; ETHBTC quote sekarang punya kurs
+        st.on_mid("BTCUSDT", 60_000.0);
+        assert_eq!(st.quote_rate("ETHBTC"), Some(60_000.0));
+        st.on_mid("ETHBTC", 0.05);
+        assert!((st.rates["ETH"] - 3_000.0).abs() < 1e-9);
+
+        // USDTTRY 40 -> TRY = 0.025 USD; stablecoin tetap 1
+        st.on_mid("USDTTRY", 40.0);
+        assert_eq!(st.quote_rate("BTCTRY"), Some(0.025));
+        assert_eq!(st.rates["USDT"], 1.0);
+    }
+}
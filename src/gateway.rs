@@ -1,42 +1,360 @@
 // ===============================
 // src/gateway.rs (per-venue)
 // ===============================
-use chrono::Utc;
-use tokio::{sync::mpsc, time::{sleep, Duration}};
-use crate::domain::{ExecReport, ExecStatus, VenueOrder};
-use crate::metrics::EXECS;
+//
+// Gateway mock per venue. Tiga mode fill:
+// - tanpa harga sama sekali (belum ada BookUpdate / MdTick untuk symbol, atau MOCK_RESTING=0):
+//   ACK -> Filled penuh di harga limit setelah latensi fill (perilaku lama)
+// - top-of-book (MdTick, tanpa depth): sama seperti depth-aware dengan satu level per sisi
+//   (best bid/ask + ukurannya; ukuran 0 = tak terbatas). Limit buy di bawah ask / sell di atas bid
+//   resting sampai feed menembus harganya, jadi strategi pasif tidak lagi terisi instan di harga apa pun.
+// - depth-aware (ada BookUpdate): setelah latensi fill, order mengambil likuiditas dari level
+//   lawan yang harganya <= limit (buy) / >= limit (sell), sebatas ukuran yang ditampilkan.
+//   Sisa order resting dan hanya terisi saat pasar menembus harganya di update berikutnya
+//   (PartialFill kumulatif -> Filled). Order resting diproses FIFO dan berbagi ukuran level
+//   dalam satu update, jadi ada efek antrean.
+//
+// Latensi (lihat sim_latency.rs): tiap order disampel sendiri: terima -> ACK (latensi order),
+// ACK -> match (latensi fill). Order in-flight antre berdasarkan waktu jatuh tempo, jadi venue lambat
+// tidak menahan order lain dan order bisa saling menyalip.
+//
+// Slippage (lihat slippage.rs): harga tiap fill digeser merugikan sesuai model per symbol.
+//
+// GTT: order yang lewat `expire_at_ns` sebelum terisi penuh -> Expired. Waktu GTT, ts_ns report dan
+// latensi sig->ack dibaca dari `SharedClock` yang diinjeksi (clock engine di produksi, MockClock di test).
+// Stop-limit (STOP_LOSS_LIMIT / TAKE_PROFIT_LIMIT): resting tanpa mengambil likuiditas sampai mid
+// top-of-book menyentuh stop_px, lalu match seperti limit biasa. Tanpa harga: terisi seperti limit.
+// CancelAll / Cancel (per cl_id): order resting / sudah ACK tapi belum di-match -> Rejected("CANCELED").
+// Replace: order resting lama -> Rejected("REPLACED"), order baru (sisa qty, harga baru) ACK + match ke book.
+//
+use ahash::{AHashMap as HashMap, AHashSet as HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::{sync::{broadcast, mpsc}, time::{interval, sleep_until, Duration, Instant}};
+use tracing::info;
+use crate::clock::{Clock, SharedClock};
+use crate::domain::{BookUpdate, ExecReport, ExecStatus, MdTick, Order, OrderType, Side, VenueCmd, VenueOrder};
+use crate::metrics::{EXECS, EXEC_NET_DELAY, LAT_SIG_ACK, ORDERS_EXPIRED};
+use crate::sim_latency::{self, VenueLatency};
+use crate::slippage::SlippageCfg;
 
+/// Order yang sedang menunggu latensi venue
+struct InFlight {
+    due: Instant,
+    acked: bool, // false: menunggu ACK, true: menunggu match
+    order: Order,
+}
+
+/// Order resting (depth-aware)
+struct Resting {
+    order: Order,
+    filled: i64,
+    notional: i64, // sum(px * qty) fill, untuk avg_px
+    triggered: bool, // stop-limit: false sampai harga menyentuh stop_px (Limit selalu true)
+}
+
+impl Resting {
+    fn new(order: Order) -> Self {
+        let triggered = order.order_type == OrderType::Limit;
+        Self { order, filled: 0, notional: 0, triggered }
+    }
+}
+
+/// Report ACK; orderId venue mock (unik lintas venue mock) dicatat di registry ID
+fn ack_report(o: &Order, venue: &str, clock: &dyn Clock) -> ExecReport {
+    static NEXT_ORDER_ID: AtomicU64 = AtomicU64::new(1);
+    crate::order_ids::bind_exchange(&o.cl_id, NEXT_ORDER_ID.fetch_add(1, Ordering::Relaxed));
+    report(o, venue, ExecStatus::Ack, 0, 0, clock)
+}
+
+/// Latensi internal tick -> ACK gateway (Order.ts_ns = ts_ns MdTick pemicu; `now` dari clock gateway)
+pub fn observe_sig_to_ack(o: &Order, now: i128) {
+    if o.ts_ns > 0 {
+        LAT_SIG_ACK.observe((now - o.ts_ns) as f64 / 1e6);
+    }
+}
+
+fn report(o: &Order, venue: &str, status: ExecStatus, filled_qty: i64, avg_px: i64, clock: &dyn Clock) -> ExecReport {
+    ExecReport {
+        cl_id: o.cl_id.clone(),
+        symbol: o.symbol.clone(),
+        status,
+        filled_qty,
+        avg_px,
+        ts_ns: clock.now_ns(),
+        venue: venue.to_string(),
+        side: Some(o.side),
+        order_qty: o.qty,
+        strategy: o.strategy.clone(),
+        tags: o.tags.clone(),
+        book: o.book.clone(),
+        exch_ts_ns: sim_latency::venue_ts(venue),
+    }
+}
+
+async fn send(exec_tx: &mpsc::Sender<ExecReport>, er: ExecReport, label: &str) {
+    EXECS.with_label_values(&[label, &er.venue]).inc();
+    if er.exch_ts_ns > 0 {
+        EXEC_NET_DELAY.with_label_values(&[&er.venue]).observe((er.ts_ns - er.exch_ts_ns) as f64 / 1e6);
+    }
+    let _ = exec_tx.send(er).await;
+}
+
+/// Ambil likuiditas dari sisi lawan `book` untuk order `r`; `used` = qty yang sudah
+/// terpakai per level di update ini. Return qty yang terisi (harga fill sudah termasuk slippage).
+fn take(r: &mut Resting, book: &BookUpdate, used: &mut HashMap<(bool, i64), i64>, slip: &SlippageCfg) -> i64 {
+    if !r.triggered {
+        // stop-limit: picu di mid top-of-book; sebelum itu tidak mengambil likuiditas
+        let (Some(&(bid, _)), Some(&(ask, _))) = (book.bids.first(), book.asks.first()) else { return 0 };
+        if !r.order.order_type.triggered(r.order.side, (bid + ask) / 2) {
+            return 0;
+        }
+        r.triggered = true;
+    }
+    let (levels, is_ask) = match r.order.side {
+        Side::Buy => (&book.asks, true),
+        Side::Sell => (&book.bids, false),
+    };
+    let mut got = 0;
+    for &(px, qty) in levels {
+        let through = match r.order.side {
+            Side::Buy => px <= r.order.px,
+            Side::Sell => px >= r.order.px,
+        };
+        if !through {
+            break;
+        }
+        let u = used.entry((is_ask, px)).or_insert(0);
+        let n = (qty - *u).min(r.order.qty - r.filled).max(0);
+        if n > 0 {
+            *u += n;
+            r.filled += n;
+            r.notional += n * slip.apply(&r.order.symbol, r.order.side, r.order.qty, px, Some(book));
+            got += n;
+        }
+        if r.filled >= r.order.qty {
+            break;
+        }
+    }
+    got
+}
+
+/// Laporkan hasil match; true jika order selesai (Filled)
+async fn report_fill(r: &Resting, venue: &str, exec_tx: &mpsc::Sender<ExecReport>, clock: &dyn Clock) -> bool {
+    let avg = r.notional / r.filled.max(1);
+    if r.filled >= r.order.qty {
+        send(exec_tx, report(&r.order, venue, ExecStatus::Filled, r.filled, avg, clock), "filled").await;
+        true
+    } else {
+        send(exec_tx, report(&r.order, venue, ExecStatus::PartialFill, r.filled, avg, clock), "partial").await;
+        false
+    }
+}
+
+/// Match order resting FIFO ke update `b`; ukuran level dibagi dalam satu update
+async fn cross_resting(resting: &mut Vec<Resting>, b: &BookUpdate, venue: &str, exec_tx: &mpsc::Sender<ExecReport>, slip: &SlippageCfg, clock: &dyn Clock) {
+    let mut used = HashMap::new();
+    let mut i = 0;
+    while i < resting.len() {
+        if resting[i].order.symbol == b.symbol && take(&mut resting[i], b, &mut used, slip) > 0
+            && report_fill(&resting[i], venue, exec_tx, clock).await
+        {
+            resting.remove(i);
+            continue;
+        }
+        i += 1;
+    }
+}
+
+/// Book satu level dari top-of-book MdTick (ukuran 0 = tidak diketahui -> tak terbatas)
+fn top_of_book(t: &MdTick) -> BookUpdate {
+    let level = |px: i64, qty: i64| if px > 0 { vec![(px, if qty > 0 { qty } else { i64::MAX / 4 })] } else { Vec::new() };
+    BookUpdate { ts_ns: t.ts_ns, symbol: t.symbol.clone(), bids: level(t.best_bid, t.bid_qty), asks: level(t.best_ask, t.ask_qty) }
+}
+
+/// Keluarkan order resting yang sudah lewat GTT menurut `clock` (urutan antrean dipertahankan)
+fn take_expired(resting: &mut Vec<Resting>, clock: &dyn Clock) -> Vec<Resting> {
+    let now = clock.now_ns();
+    let (expired, live): (Vec<Resting>, Vec<Resting>) =
+        std::mem::take(resting).into_iter().partition(|r| r.order.expire_at_ns.is_some_and(|t| now >= t));
+    *resting = live;
+    expired
+}
+
+async fn expire(o: &Order, filled: i64, avg: i64, venue: &str, exec_tx: &mpsc::Sender<ExecReport>, clock: &dyn Clock) {
+    send(exec_tx, report(o, venue, ExecStatus::Expired, filled, avg, clock), "expired").await;
+    ORDERS_EXPIRED.with_label_values(&[venue]).inc();
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn run_venue(
     mut rx: mpsc::Receiver<VenueOrder>,
+    mut cmd_rx: mpsc::Receiver<VenueCmd>,
     exec_tx: mpsc::Sender<ExecReport>,
     venue: String,
-    fill_ms: u64,
+    mut lat: VenueLatency,
+    slip: SlippageCfg,
+    mut book_rx: Option<broadcast::Receiver<BookUpdate>>,
+    mut md_rx: Option<broadcast::Receiver<MdTick>>,
+    clock: SharedClock,
 ) {
-    while let Some(vord) = rx.recv().await {
-        let o = vord.order;
-
-        let ack = ExecReport {
-            cl_id: o.cl_id.clone(),
-            symbol: o.symbol.clone(),
-            status: ExecStatus::Ack,
-            filled_qty: 0,
-            avg_px: 0,
-            ts_ns: Utc::now().timestamp_nanos_opt().unwrap_or(0) as i128,
+    let clock = &*clock;
+    let mut books: HashMap<String, BookUpdate> = HashMap::new();
+    // symbol dengan depth L2: MdTick untuk symbol ini diabaikan
+    let mut depth: HashSet<String> = HashSet::new();
+    let mut resting: Vec<Resting> = Vec::new();
+    let mut inflight: Vec<InFlight> = Vec::new();
+    let mut expiry_tick = interval(Duration::from_millis(100));
+
+    loop {
+        let vord = tokio::select! {
+            Some(cmd) = cmd_rx.recv() => {
+                match cmd {
+                    VenueCmd::CancelAll { reason } => {
+                        info!(%venue, %reason, resting = resting.len(), "mock cancel-all");
+                        let acked = inflight.iter().filter(|f| f.acked).map(|f| Resting::new(f.order.clone()));
+                        let cancelled: Vec<Resting> = acked.collect();
+                        inflight.retain(|f| !f.acked);
+                        for r in resting.drain(..).chain(cancelled) {
+                            let avg = r.notional / r.filled.max(1);
+                            let er = report(&r.order, &venue, ExecStatus::Rejected("CANCELED".to_string()), r.filled, avg, clock);
+                            send(&exec_tx, er, "rejected").await;
+                        }
+                    }
+                    VenueCmd::Replace { cl_id, order } => {
+                        // hanya order resting yang bisa diganti (yang sudah final diabaikan)
+                        let Some(i) = resting.iter().position(|r| r.order.cl_id == cl_id) else { continue };
+                        let old = resting.remove(i);
+                        let avg = old.notional / old.filled.max(1);
+                        let er = report(&old.order, &venue, ExecStatus::Rejected("REPLACED".to_string()), old.filled, avg, clock);
+                        send(&exec_tx, er, "rejected").await;
+                        send(&exec_tx, ack_report(&order, &venue, clock), "ack").await;
+                        let mut r = Resting::new(order);
+                        let done = match books.get(&r.order.symbol) {
+                            Some(book) => take(&mut r, book, &mut HashMap::new(), &slip) > 0 && report_fill(&r, &venue, &exec_tx, clock).await,
+                            None => false,
+                        };
+                        if !done {
+                            resting.push(r);
+                        }
+                    }
+                    VenueCmd::Cancel { cl_id, reason, .. } => {
+                        if let Some(i) = inflight.iter().position(|f| f.acked && f.order.cl_id == cl_id) {
+                            let f = inflight.remove(i);
+                            resting.push(Resting::new(f.order));
+                        }
+                        if let Some(i) = resting.iter().position(|r| r.order.cl_id == cl_id) {
+                            let r = resting.remove(i);
+                            info!(%venue, %cl_id, %reason, filled = r.filled, "mock cancel");
+                            let avg = r.notional / r.filled.max(1);
+                            let er = report(&r.order, &venue, ExecStatus::Rejected("CANCELED".to_string()), r.filled, avg, clock);
+                            send(&exec_tx, er, "rejected").await;
+                        }
+                    }
+                }
+                continue;
+            }
+            book = async { book_rx.as_mut().unwrap().recv().await }, if book_rx.is_some() => {
+                match book {
+                    Ok(b) => {
+                        cross_resting(&mut resting, &b, &venue, &exec_tx, &slip, clock).await;
+                        depth.insert(b.symbol.clone());
+                        books.insert(b.symbol.clone(), b);
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => book_rx = None,
+                }
+                continue;
+            }
+            md = async { md_rx.as_mut().unwrap().recv().await }, if md_rx.is_some() => {
+                match md {
+                    Ok(t) if !depth.contains(&t.symbol) => {
+                        let b = top_of_book(&t);
+                        cross_resting(&mut resting, &b, &venue, &exec_tx, &slip, clock).await;
+                        books.insert(b.symbol.clone(), b);
+                    }
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => md_rx = None,
+                }
+                continue;
+            }
+            _ = expiry_tick.tick(), if !resting.is_empty() => {
+                for r in take_expired(&mut resting, clock) {
+                    expire(&r.order, r.filled, r.notional / r.filled.max(1), &venue, &exec_tx, clock).await;
+                }
+                continue;
+            }
+            _ = sleep_until(inflight.iter().map(|f| f.due).min().unwrap_or_else(Instant::now)), if !inflight.is_empty() => {
+                let now = Instant::now();
+                let (due, rest): (Vec<InFlight>, Vec<InFlight>) = inflight.drain(..).partition(|f| f.due <= now);
+                inflight = rest;
+                for f in due {
+                    if !f.acked {
+                        send(&exec_tx, ack_report(&f.order, &venue, clock), "ack").await;
+                        observe_sig_to_ack(&f.order, clock.now_ns());
+                        inflight.push(InFlight { due: Instant::now() + lat.fill(), acked: true, order: f.order });
+                        continue;
+                    }
+                    let o = f.order;
+                    // GTT: order yang kedaluwarsa sebelum terisi dibatalkan lokal
+                    if o.expire_at_ns.is_some_and(|t| clock.now_ns() >= t) {
+                        expire(&o, 0, 0, &venue, &exec_tx, clock).await;
+                        continue;
+                    }
+                    // Depth-aware: match ke book terakhir, sisa jadi resting
+                    if let Some(book) = books.get(&o.symbol) {
+                        let mut r = Resting::new(o);
+                        let done = take(&mut r, book, &mut HashMap::new(), &slip) > 0 && report_fill(&r, &venue, &exec_tx, clock).await;
+                        if !done {
+                            resting.push(r);
+                        }
+                        continue;
+                    }
+                    let (qty, px) = (o.qty, slip.apply(&o.symbol, o.side, o.qty, o.px, None));
+                    send(&exec_tx, report(&o, &venue, ExecStatus::Filled, qty, px, clock), "filled").await;
+                }
+                continue;
+            }
+            maybe = rx.recv() => match maybe { Some(v) => v, None => break },
+        };
+        inflight.push(InFlight { due: Instant::now() + lat.order(), acked: false, order: vord.order });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stop_loss_limit_rests_until_mid_touches_stop() {
+        let tags = vec!["stop_loss_limit=9500".to_string()];
+        let order = Order {
+            cl_id: "sl1".into(), ts_ns: 0, symbol: "BTCUSDT".into(), side: Side::Sell, px: 9400, qty: 2,
+            strategy: String::new(), tags: tags.clone(), book: String::new(), expire_at_ns: None,
+            order_type: OrderType::from_tags(&tags),
         };
-        let _ = exec_tx.send(ack).await;
-        EXECS.with_label_values(&["ack", &venue]).inc();
-
-        sleep(Duration::from_millis(fill_ms)).await;
-
-        let fill = ExecReport {
-            cl_id: o.cl_id.clone(),
-            symbol: o.symbol.clone(),
-            status: ExecStatus::Filled,
-            filled_qty: o.qty,
-            avg_px: o.px,
-            ts_ns: Utc::now().timestamp_nanos_opt().unwrap_or(0) as i128,
+        let book = |bid: i64, ask: i64| BookUpdate { ts_ns: 0, symbol: "BTCUSDT".into(), bids: vec![(bid, 10)], asks: vec![(ask, 10)] };
+        let slip = SlippageCfg::default();
+        let mut r = Resting::new(order);
+        // bid 9600 >= limit 9400, tapi stop belum tersentuh
+        assert_eq!(take(&mut r, &book(9600, 9602), &mut HashMap::new(), &slip), 0);
+        assert_eq!(take(&mut r, &book(9450, 9452), &mut HashMap::new(), &slip), 2);
+        assert!(r.triggered);
+    }
+
+    #[test]
+    fn resting_gtt_follows_injected_clock() {
+        let order = |cl_id: &str, expire_at_ns| Order {
+            cl_id: cl_id.into(), ts_ns: 0, symbol: "BTCUSDT".into(), side: Side::Buy, px: 100, qty: 1,
+            strategy: String::new(), tags: vec![], book: String::new(), expire_at_ns, order_type: OrderType::Limit,
         };
-        let _ = exec_tx.send(fill).await;
-        EXECS.with_label_values(&["filled", &venue]).inc();
+        let clock = crate::clock::MockClock::at_ns(1_000_000_000);
+        let mut resting = vec![Resting::new(order("a", Some(1_500_000_000))), Resting::new(order("b", None))];
+        assert!(take_expired(&mut resting, &*clock).is_empty());
+        clock.advance_ms(500);
+        let expired = take_expired(&mut resting, &*clock);
+        assert_eq!(expired.iter().map(|r| r.order.cl_id.as_str()).collect::<Vec<_>>(), ["a"]);
+        assert_eq!(resting.len(), 1);
+        let er = report(&expired[0].order, "mock", ExecStatus::Expired, 0, 0, &*clock);
+        assert_eq!(er.ts_ns, 1_500_000_000);
     }
 }
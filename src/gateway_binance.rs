@@ -1,25 +1,109 @@
 // ===============================
 // src/gateway_binance.rs
 // ===============================
+use ahash::{AHashMap as HashMap, AHashSet as HashSet};
 use chrono::Utc;
 use futures_util::StreamExt;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
 use tokio::{
-    sync::mpsc,
-    time::{sleep, Duration},
+    sync::{mpsc, Mutex as AsyncMutex, Semaphore},
+    time::{interval, Duration, MissedTickBehavior},
 };
-use tokio_tungstenite::connect_async;
 use url::Url;
 
-use crate::binance::{sign_query, timestamp_ms, WsEnvelope};
-use crate::domain::{ExecReport, ExecStatus, Side, VenueOrder};
-use crate::metrics::EXECS;
+use crate::account::{Balance, Balances};
+use crate::binance::{BinanceError, ErrorClass, UserEvent};
+use crate::clock::{self, SharedClock};
+use crate::binance_ws_api::{WsApiClient, WsApiError};
+use crate::exchange_http::{endpoints, ExchangeHttp, HttpError};
+use crate::domain::{ExecReport, ExecStatus, Order, Side, VenueCmd, VenueOrder};
+use crate::metrics::{BIN_ORDER_POLLS, BIN_WS_CONNECTED, BIN_WS_LAST_EVENT_TS, BIN_WS_RECONNECTS, EXECS, EXEC_NET_DELAY, ORDERS_EXPIRED, ORDERS_INFLIGHT};
+use crate::venue_health::VenueHealth;
+use crate::quote_throttle::ChurnLimiter;
+use crate::reconnect::Backoff;
+use crate::supervisor::AbortOnDrop;
+
+/// Order yang masih hidup di venue, dicatat saat dikirim.
+/// Event user data stream hanya membawa cl_id, jadi atribusi & GTT disimpan di sini.
+#[derive(Debug, Clone, Default)]
+struct LiveOrder {
+    symbol: String,
+    side: Option<Side>,
+    qty: i64,
+    strategy: String,
+    tags: Vec<String>,
+    book: String,
+    expire_at_ns: Option<i128>,
+    /// cancel karena GTT sudah dikirim -> CANCELED dilaporkan sebagai Expired
+    expiring: bool,
+    /// cancel/replace sudah dikirim -> CANCELED dilaporkan sebagai Rejected("REPLACED")
+    replaced: bool,
+    /// cancel diminta (VenueCmd::Cancel / CancelAll); order yang masih antre di lane tidak dikirim
+    cancel_pending: bool,
+    /// orderId exchange dari respon placement (None = belum dijawab venue)
+    order_id: Option<u64>,
+    /// status & cum filled (lot) terakhir yang dilaporkan; poll hanya melapor perubahan
+    last_status: String,
+    last_cum: i64,
+}
+
+impl LiveOrder {
+    fn from_order(o: &Order) -> Self {
+        Self {
+            symbol: o.symbol.to_ascii_uppercase(),
+            side: Some(o.side),
+            qty: o.qty,
+            strategy: o.strategy.clone(),
+            tags: o.tags.clone(),
+            book: o.book.clone(),
+            expire_at_ns: o.expire_at_ns,
+            expiring: false,
+            replaced: false,
+            cancel_pending: false,
+            order_id: None,
+            last_status: "NEW".to_string(),
+            last_cum: 0,
+        }
+    }
+}
+
+/// ExecReport status tanpa fill untuk order yang dicatat gateway (ACK / reject lokal)
+fn local_report(o: &Order, venue: &str, status: ExecStatus, ts_ns: i128) -> ExecReport {
+    ExecReport {
+        cl_id: o.cl_id.clone(),
+        symbol: o.symbol.clone(),
+        status,
+        filled_qty: 0,
+        avg_px: 0,
+        ts_ns,
+        venue: venue.to_string(),
+        side: Some(o.side),
+        order_qty: o.qty,
+        strategy: o.strategy.clone(),
+        tags: o.tags.clone(),
+        book: o.book.clone(),
+        exch_ts_ns: 0,
+    }
+}
+
+type LiveOrders = Arc<Mutex<HashMap<String, LiveOrder>>>;
 
-/// Binance gateway (REST + User Data Stream).
+/// Binance gateway (REST atau WS API untuk order + User Data Stream).
 /// PoC: submit LIMIT GTC orders only; fills/updates come from userDataStream WS.
+#[allow(clippy::too_many_arguments)]
 pub async fn run_venue_binance(
     mut rx: mpsc::Receiver<VenueOrder>,
+    mut cmd_rx: mpsc::Receiver<VenueCmd>,
     exec_tx: mpsc::Sender<ExecReport>,
     venue: String,
+    health: VenueHealth,
+    symbols: Vec<String>,
+    limiter: ChurnLimiter,
+    balances: Option<Balances>,
+    clock: SharedClock,
 ) {
     // ENV
     let rest_base =
@@ -33,10 +117,44 @@ pub async fn run_venue_binance(
         .and_then(|s| s.parse::<u64>().ok())
         .unwrap_or(5000);
 
-    let http = reqwest::Client::new();
+    // Maks order in-flight (submit paralel antar symbol)
+    let max_inflight = std::env::var("BINANCE_MAX_INFLIGHT")
+        .ok()
+        .and_then(|s| s.parse::<usize>().ok())
+        .unwrap_or(8)
+        .max(1);
+
+    // Kirim ulang order baru untuk error rate limit / -1001 / -1021 (clientOrderId sama -> tidak dobel)
+    let retries = std::env::var("BINANCE_ORDER_RETRIES")
+        .ok()
+        .and_then(|s| s.parse::<u32>().ok())
+        .unwrap_or(2);
+
+    // Poll status order (GET /api/v3/order) selama user data stream putus; 0 = nonaktif
+    let poll_ms = std::env::var("BINANCE_ORDER_POLL_MS")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(2000);
+
+    let rest = ExchangeHttp::new(rest_base.clone(), api_key.clone(), api_sec.clone(), recv_window);
+
+    // Transport order: BINANCE_ORDER_TRANSPORT=rest|ws (default rest)
+    let transport = match std::env::var("BINANCE_ORDER_TRANSPORT")
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+        .as_str()
+    {
+        "ws" => {
+            let url = std::env::var("BINANCE_WS_API_URL")
+                .unwrap_or_else(|_| crate::binance_ws_api::default_url(&rest_base).to_string());
+            tracing::info!(%url, "binance order transport: ws-api");
+            OrderTransport::Ws(Arc::new(AsyncMutex::new(WsApiClient::new(url, api_key.clone(), api_sec.clone(), recv_window))))
+        }
+        _ => OrderTransport::Rest,
+    };
 
     // 1) Get listenKey
-    let listen_key: String = match create_listen_key(&http, &rest_base, &api_key).await {
+    let listen_key: String = match create_listen_key(&rest).await {
         Ok(k) => k,
         Err(e) => {
             tracing::error!(?e, "create listenKey failed");
@@ -45,109 +163,538 @@ pub async fn run_venue_binance(
     };
 
     // 2) Spawn WS user data stream
+    let live: LiveOrders = Arc::default();
     let exec_tx_ws = exec_tx.clone();
     let venue_ws = venue.clone();
-    tokio::spawn(async move { user_stream_ws_loop(&ws_base, &listen_key, exec_tx_ws, venue_ws).await });
+    let health_ws = health.clone();
+    let live_ws = live.clone();
+    let stream_up = Arc::new(AtomicBool::new(false));
+    let ws_url = format!("{}/{}", ws_base.trim_end_matches('/'), listen_key);
+    let stream_up_ws = stream_up.clone();
+    let clock_ws = clock.clone();
+    // ikut dibatalkan jika gateway ini mati (supervisor membuat instance baru)
+    let _user_stream = AbortOnDrop(tokio::spawn(async move {
+        user_stream_ws_loop(ws_url, exec_tx_ws, venue_ws, health_ws, live_ws, stream_up_ws, balances, clock_ws).await
+    }));
+
+    // Fallback: fill tetap tertangkap saat user data stream putus
+    let _poller = (poll_ms > 0).then(|| {
+        let poller = OrderPoller {
+            rest: rest.clone(),
+            live: live.clone(),
+            stream_up,
+            exec_tx: exec_tx.clone(),
+            venue: venue.clone(),
+            health: health.clone(),
+            clock: clock.clone(),
+        };
+        AbortOnDrop(tokio::spawn(poller.run(Duration::from_millis(poll_ms))))
+    });
+
+    // Target cancel-all: symbol config + yang pernah diorder (order dari instance sebelumnya ikut)
+    let mut traded_symbols: HashSet<String> = symbols.iter().map(|s| s.to_ascii_uppercase()).collect();
+
+    // Submit order: satu lane per symbol, paralel dibatasi semaphore
+    let submitter = Submitter {
+        rest: rest.clone(),
+        transport: transport.clone(),
+        inflight: Arc::new(Semaphore::new(max_inflight)),
+        exec_tx: exec_tx.clone(),
+        venue: venue.clone(),
+        health: health.clone(),
+        live: live.clone(),
+        retries,
+        clock: clock.clone(),
+    };
+    let mut lanes: HashMap<String, mpsc::Sender<LaneCmd>> = HashMap::new();
 
-    // 3) Consume orders from router
-    while let Some(vord) = rx.recv().await {
+    // Pemeriksa GTT: order yang lewat expire_at_ns dibatalkan lewat lane symbol-nya
+    let mut expiry_tick = interval(Duration::from_millis(200));
+    expiry_tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    // 3) Consume orders from router (+ perintah kontrol venue)
+    loop {
+        let vord = tokio::select! {
+            _ = expiry_tick.tick() => {
+                for (cl_id, sym) in mark_expired(&live, clock.now_ns()) {
+                    tracing::info!(%venue, %cl_id, "GTT expired, cancelling");
+                    limiter.note(&venue, &sym);
+                    // lewat lane symbol: cancel tidak mendahului placement yang masih antre
+                    let lane = lanes.entry(sym.clone()).or_insert_with(|| submitter.spawn_lane());
+                    let _ = lane.send(LaneCmd::Cancel { cl_id, symbol: sym, gtt: true }).await;
+                }
+                continue;
+            }
+            Some(cmd) = cmd_rx.recv() => {
+                match cmd {
+                    VenueCmd::CancelAll { reason } => {
+                        tracing::warn!(%venue, %reason, "cancel-all open orders");
+                        // order yang masih antre di lane dibuang lokal saat gilirannya
+                        live.lock().unwrap().values_mut().for_each(|lo| lo.cancel_pending = true);
+                        for sym in &traded_symbols {
+                            if let Some(lane) = lanes.get(sym) {
+                                let _ = lane.send(LaneCmd::CancelAll { symbol: sym.clone() }).await;
+                            } else if let Err(e) = transport.cancel_open_orders(&rest, sym).await {
+                                tracing::error!(status = ?e.status, body = %e.body, %sym, "cancel openOrders failed");
+                            }
+                        }
+                    }
+                    VenueCmd::Replace { cl_id, order } => {
+                        // order lama sudah final -> tidak ada yang diganti
+                        let Some(old) = live.lock().unwrap().get_mut(&cl_id).map(|lo| { lo.replaced = true; lo.symbol.clone() }) else { continue };
+                        tracing::info!(%venue, %cl_id, new_cl_id = %order.cl_id, px = order.px, qty = order.qty, "cancel/replace");
+                        limiter.note(&venue, &old);
+                        live.lock().unwrap().insert(order.cl_id.clone(), LiveOrder::from_order(&order));
+                        let _ = exec_tx.send(local_report(&order, &venue, ExecStatus::Ack, clock.now_ns())).await;
+                        EXECS.with_label_values(&["ack", &venue]).inc();
+                        let lane = lanes.entry(old).or_insert_with(|| submitter.spawn_lane());
+                        let _ = lane.send(LaneCmd::Replace { cl_id, order }).await;
+                    }
+                    VenueCmd::Cancel { cl_id, symbol, reason } => {
+                        tracing::info!(%venue, %cl_id, %reason, "cancel order");
+                        limiter.note(&venue, &symbol);
+                        if let Some(lo) = live.lock().unwrap().get_mut(&cl_id) {
+                            lo.cancel_pending = true;
+                        }
+                        let symbol = symbol.to_ascii_uppercase();
+                        let lane = lanes.entry(symbol.clone()).or_insert_with(|| submitter.spawn_lane());
+                        let _ = lane.send(LaneCmd::Cancel { cl_id, symbol, gtt: false }).await;
+                    }
+                }
+                continue;
+            }
+            maybe = rx.recv() => match maybe { Some(v) => v, None => break },
+        };
         let o = vord.order;
+        let symbol_up = o.symbol.to_ascii_uppercase();
+        traded_symbols.insert(symbol_up.clone());
+        live.lock().unwrap().insert(o.cl_id.clone(), LiveOrder::from_order(&o));
 
         // Immediate ACK (gateway received)
-        let ack = ExecReport {
-            cl_id: o.cl_id.clone(),
-            symbol: o.symbol.clone(),
-            status: ExecStatus::Ack,
-            filled_qty: 0,
-            avg_px: 0,
-            ts_ns: Utc::now().timestamp_nanos_opt().unwrap_or(0) as i128,
-        };
-        let _ = exec_tx.send(ack).await;
+        let now = clock.now_ns();
+        let _ = exec_tx.send(local_report(&o, &venue, ExecStatus::Ack, now)).await;
         EXECS.with_label_values(&["ack", &venue]).inc();
+        crate::gateway::observe_sig_to_ack(&o, now);
 
-        // Build LIMIT GTC params
-        let ts = timestamp_ms();
-        let symbol_up = o.symbol.to_ascii_uppercase();
-        let price = (o.px as f64) / 100.0;
-        let qty = o.qty as f64;
+        // Serahkan ke lane symbol-nya (dibuat saat order pertama untuk symbol tsb)
+        let lane = lanes.entry(symbol_up).or_insert_with(|| submitter.spawn_lane());
+        if let Err(mpsc::error::SendError(LaneCmd::Place(o))) = lane.send(LaneCmd::Place(o)).await {
+            tracing::error!(cl_id = %o.cl_id, "submit lane closed, order dropped");
+            crate::dead_letter::record("ord", "gateway_binance", "closed", &o);
+        }
+    }
+}
 
-        let side = match o.side {
-            Side::Buy => "BUY",
-            Side::Sell => "SELL",
-        };
+/// Tandai order live yang lewat GTT pada `now` (sekali saja: `expiring`); return (cl_id, symbol) untuk di-cancel
+fn mark_expired(live: &LiveOrders, now: i128) -> Vec<(String, String)> {
+    live.lock()
+        .unwrap()
+        .iter_mut()
+        .filter(|(_, lo)| !lo.expiring && lo.expire_at_ns.is_some_and(|t| now >= t))
+        .map(|(cl_id, lo)| {
+            lo.expiring = true;
+            (cl_id.clone(), lo.symbol.clone())
+        })
+        .collect()
+}
 
-        let params = vec![
-            ("symbol".to_string(), symbol_up.clone()),
-            ("side".to_string(), side.to_string()),
-            ("type".to_string(), "LIMIT".to_string()),
-            ("timeInForce".to_string(), "GTC".to_string()),
-            ("quantity".to_string(), format!("{qty}")),
-            ("price".to_string(), format!("{price}")),
-            ("timestamp".to_string(), ts.to_string()),
-            ("recvWindow".to_string(), recv_window.to_string()),
-            ("newClientOrderId".to_string(), o.cl_id.clone()),
-        ];
-
-        let query = params
-            .iter()
-            .map(|(k, v)| format!("{}={}", k, urlencoding::encode(v)))
-            .collect::<Vec<_>>()
-            .join("&");
+/// Perintah lane symbol. Satu lane mengeksekusi berurutan, jadi cancel / replace / GTT
+/// tidak pernah sampai ke venue sebelum placement order yang dituju.
+enum LaneCmd {
+    Place(Order),
+    /// `gtt` = cancel karena GTT (gagal -> dicoba lagi 1 detik kemudian)
+    Cancel { cl_id: String, symbol: String, gtt: bool },
+    Replace { cl_id: String, order: Order },
+    CancelAll { symbol: String },
+}
+
+/// Hasil pengecekan order antre sebelum dikirim lane
+#[derive(Debug)]
+enum Claim {
+    Send,
+    /// entry live sudah tidak ada (final)
+    Gone,
+    /// cancel / GTT datang selagi antre: dibuang lokal dengan status ini, tidak pernah sampai venue
+    Drop(ExecStatus),
+}
+
+/// Putuskan nasib order antre tepat sebelum dikirim (di bawah lock live, sekali per order)
+fn claim(live: &LiveOrders, cl_id: &str, now: i128) -> Claim {
+    let mut g = live.lock().unwrap();
+    let Some(lo) = g.get(cl_id) else { return Claim::Gone };
+    let status = if lo.expiring || lo.expire_at_ns.is_some_and(|t| now >= t) {
+        ExecStatus::Expired
+    } else if lo.cancel_pending {
+        ExecStatus::Rejected("CANCELED".to_string())
+    } else {
+        return Claim::Send;
+    };
+    g.remove(cl_id);
+    Claim::Drop(status)
+}
 
-        let sig = sign_query(&api_sec, &query);
-        let url = format!("{}/api/v3/order?{}&signature={}", rest_base, query, sig);
+/// Konteks submit order, dibagi ke semua lane symbol
+#[derive(Clone)]
+struct Submitter {
+    rest: ExchangeHttp,
+    transport: OrderTransport,
+    inflight: Arc<Semaphore>,
+    exec_tx: mpsc::Sender<ExecReport>,
+    venue: String,
+    health: VenueHealth,
+    live: LiveOrders,
+    /// maks kirim ulang order untuk error Retryable / -1021
+    retries: u32,
+    /// waktu GTT & ts_ns report
+    clock: SharedClock,
+}
 
-        // Send order
-        let resp = http.post(url).header("X-MBX-APIKEY", &api_key).send().await;
+impl Submitter {
+    /// Lane per symbol: order & cancel/replace satu symbol dikirim berurutan (urutan terjaga),
+    /// antar symbol paralel dibatasi semaphore `inflight`. Lane selesai saat gateway berhenti.
+    fn spawn_lane(&self) -> mpsc::Sender<LaneCmd> {
+        let (tx, mut rx) = mpsc::channel::<LaneCmd>(256);
+        let this = self.clone();
+        tokio::spawn(async move {
+            while let Some(cmd) = rx.recv().await {
+                // satu permit per perintah: lane lain tidak menunggu antrean panjang symbol ini
+                let Ok(_permit) = this.inflight.acquire().await else { break };
+                match cmd {
+                    LaneCmd::Place(o) => this.place(o).await,
+                    LaneCmd::Cancel { cl_id, symbol, gtt } => this.cancel(&symbol, &cl_id, gtt).await,
+                    LaneCmd::Replace { cl_id, order } => this.replace(&cl_id, order).await,
+                    LaneCmd::CancelAll { symbol } => {
+                        if let Err(e) = this.transport.cancel_open_orders(&this.rest, &symbol).await {
+                            tracing::error!(status = ?e.status, body = %e.body, %symbol, "cancel openOrders failed");
+                        }
+                    }
+                }
+            }
+        });
+        tx
+    }
 
-        match resp {
-            Ok(rsp) if rsp.status().is_success() => {
-                tracing::info!("order sent OK: cl_id={}", o.cl_id);
-                // Fills/partial fills will arrive via WS ORDER_TRADE_UPDATE
+    /// Kirim satu order antre (kecuali sudah dibatalkan / kedaluwarsa selagi antre)
+    async fn place(&self, o: Order) {
+        match claim(&self.live, &o.cl_id, self.clock.now_ns()) {
+            Claim::Send => {
+                let res = self.place_with_retry(&o).await;
+                self.on_result(o, res).await;
             }
-            Ok(rsp) => {
-                let code = rsp.status();
-                let body = rsp.text().await.unwrap_or_default();
-                tracing::error!(%code, %body, "order send failed");
-                let rej = ExecReport {
-                    cl_id: o.cl_id.clone(),
-                    symbol: o.symbol.clone(),
-                    status: ExecStatus::Rejected(body),
-                    filled_qty: 0,
-                    avg_px: 0,
-                    ts_ns: Utc::now().timestamp_nanos_opt().unwrap_or(0) as i128,
-                };
-                let _ = exec_tx.send(rej).await;
-                EXECS.with_label_values(&["rejected", &venue]).inc();
+            Claim::Gone => {}
+            Claim::Drop(status) => self.drop_queued(o, status).await,
+        }
+    }
+
+    /// Order yang dibatalkan / kedaluwarsa selagi antre: laporkan final tanpa menyentuh venue
+    async fn drop_queued(&self, o: Order, status: ExecStatus) {
+        let venue = &self.venue;
+        tracing::info!(%venue, cl_id = %o.cl_id, ?status, "order dropped before placement");
+        let label = if matches!(status, ExecStatus::Expired) {
+            ORDERS_EXPIRED.with_label_values(&[venue]).inc();
+            "expired"
+        } else {
+            "rejected"
+        };
+        let _ = self.exec_tx.send(local_report(&o, venue, status, self.clock.now_ns())).await;
+        EXECS.with_label_values(&[label, venue]).inc();
+    }
+
+    /// Batalkan order yang sudah dikirim lane ini; order yang sudah final / dibuang lokal dilewati
+    async fn cancel(&self, symbol: &str, cl_id: &str, gtt: bool) {
+        if !self.live.lock().unwrap().contains_key(cl_id) {
+            return;
+        }
+        let Err(e) = self.transport.cancel_order(&self.rest, symbol, cl_id).await else { return };
+        if gtt {
+            tracing::error!(status = ?e.status, body = %e.body, %cl_id, "GTT cancel failed, retry in 1s");
+            if let Some(lo) = self.live.lock().unwrap().get_mut(cl_id) {
+                lo.expiring = false;
+                lo.expire_at_ns = Some(self.clock.now_ns() + 1_000_000_000);
+            }
+        } else {
+            tracing::error!(status = ?e.status, body = %e.body, %cl_id, "cancel failed");
+        }
+    }
+
+    /// Cancel/replace `cl_id` -> `order` (order baru sudah dicatat & di-ACK oleh loop gateway)
+    async fn replace(&self, cl_id: &str, order: Order) {
+        let venue = &self.venue;
+        // order lama final selagi antre (fill / reject / cancel): tidak ada yang diganti
+        let res = if self.live.lock().unwrap().contains_key(cl_id) {
+            self.transport.cancel_replace(&self.rest, cl_id, order_params(&order)).await
+        } else {
+            Err(SendError { status: None, body: "original order already final".to_string(), err: None })
+        };
+        match res {
+            Ok(v) => capture_order_id(&self.live, &order.cl_id, v.get("newOrderResponse").unwrap_or(&v)),
+            Err(e) => {
+                tracing::error!(status = ?e.status, body = %e.body, %cl_id, "cancel/replace failed");
+                // STOP_ON_FAILURE: order lama tetap hidup, order baru tidak pernah ada
+                {
+                    let mut g = self.live.lock().unwrap();
+                    g.remove(&order.cl_id);
+                    if let Some(lo) = g.get_mut(cl_id) {
+                        lo.replaced = false;
+                    }
+                }
+                let _ = self.exec_tx.send(local_report(&order, venue, ExecStatus::Rejected("REPLACE_FAILED".to_string()), self.clock.now_ns())).await;
+                EXECS.with_label_values(&["rejected", venue]).inc();
+            }
+        }
+    }
+
+    /// Kirim order; Retryable -> kirim ulang dengan backoff, -1021 -> sinkron jam server lalu kirim ulang
+    async fn place_with_retry(&self, o: &Order) -> Result<serde_json::Value, SendError> {
+        let venue = &self.venue;
+        let mut attempt: u32 = 0;
+        loop {
+            ORDERS_INFLIGHT.with_label_values(&[venue]).inc();
+            let res = self.transport.place_order(&self.rest, order_params(o)).await;
+            ORDERS_INFLIGHT.with_label_values(&[venue]).dec();
+            let Err(SendError { err: Some(e), .. }) = &res else { return res };
+            if attempt >= self.retries {
+                return res;
+            }
+            attempt += 1;
+            match e.class() {
+                ErrorClass::Retryable => {
+                    let wait = 200u64 << (attempt - 1);
+                    tracing::warn!(%venue, cl_id = %o.cl_id, err = %e, attempt, wait_ms = wait, "order retry");
+                    tokio::time::sleep(Duration::from_millis(wait)).await;
+                }
+                ErrorClass::NeedsResync if *e == BinanceError::InvalidTimestamp => {
+                    self.health.report_clock_error(venue);
+                    match self.rest.sync_time().await {
+                        Ok(offset_ms) => tracing::warn!(%venue, cl_id = %o.cl_id, offset_ms, "clock resynced to server time, retrying order"),
+                        Err(err) => {
+                            tracing::error!(%venue, %err, "server time sync failed");
+                            return res;
+                        }
+                    }
+                }
+                _ => return res,
+            }
+        }
+    }
+
+    /// Status order tidak diketahui (transport / 5xx / -1007): cek ke venue sebelum menganggapnya ditolak
+    async fn resync_order(&self, o: &Order) -> Option<ExecReport> {
+        let p = [("symbol", o.symbol.to_ascii_uppercase()), ("origClientOrderId", o.cl_id.clone())];
+        match self.rest.send(&endpoints::query_order(), &p).await {
+            Ok(v) => {
+                capture_order_id(&self.live, &o.cl_id, &v);
+                let u = OrderUpdate::from_query(&v, &o.cl_id, &o.symbol.to_ascii_uppercase())?;
+                tracing::warn!(venue = %self.venue, cl_id = %o.cl_id, status = %u.status, "order found at venue after failed submit");
+                Some(apply_update(&self.live, &self.venue, &self.health, u, self.clock.now_ns()))
             }
             Err(e) => {
-                tracing::error!(?e, "order send err");
+                if let Some(be) = e.binance() {
+                    be.record();
+                }
+                None
+            }
+        }
+    }
+
+    async fn on_result(&self, o: Order, res: Result<serde_json::Value, SendError>) {
+        let venue = &self.venue;
+        match res {
+            Ok(v) => {
+                capture_order_id(&self.live, &o.cl_id, &v);
+                tracing::info!(cl_id = %o.cl_id, order_id = ?v.get("orderId"), "order sent OK");
+                // Fills/partial fills arrive via the user data stream (executionReport / ORDER_TRADE_UPDATE) or the poller
+            }
+            Err(SendError { status, body, err }) => {
+                tracing::error!(?status, %body, "order send failed");
+                match (status, &err) {
+                    // gagal transport dihitung sama dengan 5xx (venue tidak terjangkau)
+                    (None, _) => self.health.report_http_5xx(venue),
+                    (Some(code), _) if code >= 500 => self.health.report_http_5xx(venue),
+                    // timestamp di luar recvWindow (clock skew)
+                    (_, Some(BinanceError::InvalidTimestamp)) => self.health.report_clock_error(venue),
+                    _ => {}
+                }
+                let unknown = status.is_none_or(|c| c >= 500) || err == Some(BinanceError::Timeout);
+                if unknown {
+                    if let Some(er) = self.resync_order(&o).await {
+                        let _ = self.exec_tx.send(er).await;
+                        return;
+                    }
+                }
+                self.health.report_reject(venue);
+                self.live.lock().unwrap().remove(&o.cl_id);
                 let rej = ExecReport {
-                    cl_id: o.cl_id.clone(),
-                    symbol: o.symbol.clone(),
-                    status: ExecStatus::Rejected(format!("{e}")),
+                    cl_id: o.cl_id,
+                    symbol: o.symbol,
+                    // reason ringkas dari kode Binance; body mentah hanya jika tidak dikenali
+                    status: ExecStatus::Rejected(err.map_or(body, |e| e.to_string())),
                     filled_qty: 0,
                     avg_px: 0,
-                    ts_ns: Utc::now().timestamp_nanos_opt().unwrap_or(0) as i128,
+                    ts_ns: self.clock.now_ns(),
+                    venue: venue.clone(),
+                    side: Some(o.side),
+                    order_qty: o.qty,
+                    strategy: o.strategy,
+                    tags: o.tags,
+                    book: o.book,
+                    exch_ts_ns: 0,
                 };
-                let _ = exec_tx.send(rej).await;
-                EXECS.with_label_values(&["rejected", &venue]).inc();
+                let _ = self.exec_tx.send(rej).await;
+                EXECS.with_label_values(&["rejected", venue]).inc();
             }
         }
+    }
+}
 
-        // small pacing to avoid rate limits in PoC
-        sleep(Duration::from_millis(50)).await;
+/// Catat orderId dari respon placement (REST / WS API) ke live order + registry ID
+fn capture_order_id(live: &LiveOrders, cl_id: &str, rsp: &serde_json::Value) {
+    let Some(id) = rsp.get("orderId").and_then(|x| x.as_u64()) else { return };
+    if let Some(lo) = live.lock().unwrap().get_mut(cl_id) {
+        lo.order_id = Some(id);
     }
+    crate::order_ids::bind_exchange(cl_id, id);
 }
 
-async fn create_listen_key(
-    http: &reqwest::Client,
-    rest_base: &str,
-    api_key: &str,
-) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-    let url = format!("{}/api/v3/userDataStream", rest_base);
-    let rsp = http.post(url).header("X-MBX-APIKEY", api_key).send().await?;
-    let v = rsp.json::<serde_json::Value>().await?;
+/// Referensi order untuk cancel/query: orderId jika sudah diketahui registry, selain itu origClientOrderId
+fn order_ref(cl_id: &str) -> (&'static str, String) {
+    match crate::order_ids::exchange_id(cl_id) {
+        Some(id) => ("orderId", id.to_string()),
+        None => ("origClientOrderId", cl_id.to_string()),
+    }
+}
+
+/// Param LIMIT / STOP_LOSS_LIMIT / TAKE_PROFIT_LIMIT GTC (timestamp/recvWindow/signature ditambah oleh transport)
+fn order_params(o: &Order) -> Vec<(String, String)> {
+    let symbol_up = o.symbol.to_ascii_uppercase();
+    let price = crate::exch_format::price(&symbol_up, o.px, o.side);
+    let qty = crate::exch_format::qty(&symbol_up, o.qty);
+
+    let side = match o.side {
+        Side::Buy => "BUY",
+        Side::Sell => "SELL",
+    };
+
+    let mut params = vec![
+        ("symbol".to_string(), symbol_up.clone()),
+        ("side".to_string(), side.to_string()),
+        ("type".to_string(), o.order_type.as_str().to_string()),
+        ("timeInForce".to_string(), "GTC".to_string()),
+        ("quantity".to_string(), qty),
+        ("price".to_string(), price),
+        ("newClientOrderId".to_string(), o.cl_id.clone()),
+    ];
+    if let Some(stop_px) = o.order_type.stop_px() {
+        // STOP_LOSS_LIMIT / TAKE_PROFIT_LIMIT: stopPrice dibulatkan ke tick seperti harga limit
+        params.push(("stopPrice".to_string(), crate::exch_format::price(&symbol_up, stop_px, o.side)));
+    }
+    params
+}
+
+/// Gagal kirim (REST atau WS API); `status` None = error transport, `err` = kode Binance jika ada
+struct SendError {
+    status: Option<u16>,
+    body: String,
+    err: Option<BinanceError>,
+}
+
+impl SendError {
+    fn new(status: Option<u16>, body: String, err: Option<BinanceError>) -> Self {
+        if let Some(e) = &err {
+            e.record();
+        }
+        Self { status, body, err }
+    }
+}
+
+impl From<WsApiError> for SendError {
+    fn from(e: WsApiError) -> Self {
+        let err = BinanceError::from_body(&e.body);
+        Self::new(e.status, e.body, err)
+    }
+}
+
+impl From<HttpError> for SendError {
+    fn from(e: HttpError) -> Self {
+        Self::new(e.status(), e.to_string(), e.binance())
+    }
+}
+
+/// Jalur order entry: REST (default) atau Binance WebSocket API.
+/// Klien WS API menunggu respon per request, jadi dipakai bergantian lewat mutex.
+#[derive(Clone)]
+enum OrderTransport {
+    Rest,
+    Ws(Arc<AsyncMutex<WsApiClient>>),
+}
+
+impl OrderTransport {
+    /// Kirim order baru; respon (ACK berisi `orderId`) dikembalikan apa adanya
+    async fn place_order(&self, rest: &ExchangeHttp, params: Vec<(String, String)>) -> Result<serde_json::Value, SendError> {
+        match self {
+            OrderTransport::Rest => {
+                let p: Vec<(&str, String)> = params.iter().map(|(k, v)| (k.as_str(), v.clone())).collect();
+                rest.send(&endpoints::new_order(), &p).await.map_err(Into::into)
+            }
+            OrderTransport::Ws(c) => c.lock().await.signed_request("order.place", params).await.map_err(Into::into),
+        }
+    }
+
+    /// Batalkan satu order via origClientOrderId; -2011 (sudah final/tidak dikenal) bukan error
+    async fn cancel_order(&self, rest: &ExchangeHttp, symbol: &str, cl_id: &str) -> Result<(), SendError> {
+        match self {
+            OrderTransport::Rest => {
+                let p = [("symbol", symbol.to_string()), order_ref(cl_id)];
+                match rest.send(&endpoints::cancel_order(), &p).await {
+                    Err(e) if e.binance() != Some(BinanceError::CancelRejected) => Err(e.into()),
+                    _ => Ok(()),
+                }
+            }
+            OrderTransport::Ws(c) => {
+                let (k, v) = order_ref(cl_id);
+                let p = vec![("symbol".to_string(), symbol.to_string()), (k.to_string(), v)];
+                match c.lock().await.signed_request("order.cancel", p).await {
+                    Err(e) if BinanceError::from_body(&e.body) != Some(BinanceError::CancelRejected) => Err(e.into()),
+                    _ => Ok(()),
+                }
+            }
+        }
+    }
+
+    /// Cancel/replace atomik (STOP_ON_FAILURE): batalkan `cl_id`, kirim order baru `params`
+    async fn cancel_replace(&self, rest: &ExchangeHttp, cl_id: &str, mut params: Vec<(String, String)>) -> Result<serde_json::Value, SendError> {
+        params.push(("cancelReplaceMode".to_string(), "STOP_ON_FAILURE".to_string()));
+        params.push(("cancelOrigClientOrderId".to_string(), cl_id.to_string()));
+        match self {
+            OrderTransport::Rest => {
+                let p: Vec<(&str, String)> = params.iter().map(|(k, v)| (k.as_str(), v.clone())).collect();
+                rest.send(&endpoints::cancel_replace(), &p).await.map_err(Into::into)
+            }
+            OrderTransport::Ws(c) => c.lock().await.signed_request("order.cancelReplace", params).await.map_err(Into::into),
+        }
+    }
+
+    async fn cancel_open_orders(&self, rest: &ExchangeHttp, symbol: &str) -> Result<(), SendError> {
+        // -2011 = tidak ada order terbuka, bukan error
+        match self {
+            OrderTransport::Rest => {
+                match rest.send(&endpoints::cancel_open_orders(), &[("symbol", symbol.to_string())]).await {
+                    Err(e) if e.binance() != Some(BinanceError::CancelRejected) => Err(e.into()),
+                    _ => Ok(()),
+                }
+            }
+            OrderTransport::Ws(c) => {
+                match c.lock().await.signed_request("openOrders.cancelAll", vec![("symbol".to_string(), symbol.to_string())]).await {
+                    Err(e) if BinanceError::from_body(&e.body) != Some(BinanceError::CancelRejected) => Err(e.into()),
+                    _ => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+async fn create_listen_key(rest: &ExchangeHttp) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let v = rest.send(&endpoints::create_listen_key(), &[]).await?;
     let lk = v
         .get("listenKey")
         .and_then(|x| x.as_str())
@@ -155,71 +702,48 @@ async fn create_listen_key(
     Ok(lk.to_string())
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn user_stream_ws_loop(
-    ws_base: &str,
-    listen_key: &str,
+    ws_url: String,
     exec_tx: mpsc::Sender<crate::domain::ExecReport>,
     venue: String,
+    health: VenueHealth,
+    live: LiveOrders,
+    stream_up: Arc<AtomicBool>,
+    balances: Option<Balances>,
+    clock: SharedClock,
 ) {
-    let ws_url = format!("{}/{}", ws_base.trim_end_matches('/'), listen_key);
+    let mut backoff = Backoff::new(format!("user_stream:{venue}"));
     loop {
         match Url::parse(&ws_url) {
             Ok(u) => {
                 tracing::info!(%ws_url, "connecting userDataStream");
-                match connect_async(u).await {
+                match crate::net::connect_ws(u.as_str()).await {
                     Ok((mut ws, _)) => {
+                        BIN_WS_CONNECTED.with_label_values(&[&venue]).set(1);
+                        stream_up.store(true, Ordering::Relaxed);
+                        backoff.connected();
+                        health.report_ws_alive(&venue);
                         while let Some(msg) = ws.next().await {
+                            if crate::chaos::ws_disconnect() {
+                                tracing::warn!(%venue, "chaos: forcing userDataStream disconnect");
+                                break;
+                            }
+                            if msg.is_ok() {
+                                // event maupun ping dihitung sebagai tanda hidup
+                                health.report_ws_alive(&venue);
+                                BIN_WS_LAST_EVENT_TS.with_label_values(&[&venue]).set(Utc::now().timestamp());
+                            }
                             match msg {
                                 Ok(m) if m.is_text() => {
-                                    if let Ok(env) =
-                                        serde_json::from_str::<WsEnvelope>(&m.into_text().unwrap_or_default())
-                                    {
-                                        if env.e.as_deref() == Some("ORDER_TRADE_UPDATE") {
-                                            if let Some(ord) = env.o {
-                                                // Map -> ExecReport
-                                                let status = match ord.X.as_str() {
-                                                    "NEW" => ExecStatus::Ack,
-                                                    "PARTIALLY_FILLED" => ExecStatus::PartialFill,
-                                                    "FILLED" => ExecStatus::Filled,
-                                                    "CANCELED" | "EXPIRED" => ExecStatus::Rejected(ord.X.clone()),
-                                                    "REJECTED" => ExecStatus::Rejected("REJECTED".to_string()),
-                                                    _ => ExecStatus::Ack,
-                                                };
-
-                                                let cum_filled: i64 = ord
-                                                    .z
-                                                    .as_deref()
-                                                    .and_then(|s| s.parse::<f64>().ok())
-                                                    .unwrap_or(0.0) as i64;
-
-                                                let avg_px: i64 = ord
-                                                    .ap
-                                                    .as_deref()
-                                                    .and_then(|s| s.parse::<f64>().ok())
-                                                    .map(|p| (p * 100.0).round() as i64)
-                                                    .unwrap_or(0);
-
-                                                // Derive metric label without moving `status`
-                                                let label: &str = match &status {
-                                                    ExecStatus::Ack => "ack",
-                                                    ExecStatus::PartialFill => "partial",
-                                                    ExecStatus::Filled => "filled",
-                                                    ExecStatus::Rejected(_) => "rejected",
-                                                };
-                                                EXECS.with_label_values(&[label, &venue]).inc();
-
-                                                // Now move status into the report
-                                                let er = ExecReport {
-                                                    cl_id: ord.c,
-                                                    symbol: ord.s,
-                                                    status,
-                                                    filled_qty: cum_filled,
-                                                    avg_px,
-                                                    ts_ns: Utc::now().timestamp_nanos_opt().unwrap_or(0) as i128,
-                                                };
-                                                let _ = exec_tx.send(er).await;
-                                            }
+                                    let Ok(ev) = serde_json::from_str::<UserEvent>(&m.into_text().unwrap_or_default()) else { continue };
+                                    if let Some(er) = on_user_event(ev, &live, &venue, &health, balances.as_ref(), clock.now_ns()) {
+                                        if er.exch_ts_ns > 0 {
+                                            EXEC_NET_DELAY
+                                                .with_label_values(&[&venue])
+                                                .observe((er.ts_ns - er.exch_ts_ns) as f64 / 1e6);
                                         }
+                                        let _ = exec_tx.send(er).await;
                                     }
                                 }
                                 Ok(_) => {}
@@ -229,6 +753,9 @@ async fn user_stream_ws_loop(
                                 }
                             }
                         }
+                        BIN_WS_CONNECTED.with_label_values(&[&venue]).set(0);
+                        stream_up.store(false, Ordering::Relaxed);
+                        BIN_WS_RECONNECTS.with_label_values(&[&venue]).inc();
                         tracing::warn!("userDataStream disconnected, reconnecting …");
                     }
                     Err(e) => {
@@ -241,6 +768,297 @@ async fn user_stream_ws_loop(
                 return;
             }
         }
-        sleep(Duration::from_secs(2)).await;
+        if !backoff.wait().await {
+            return;
+        }
+    }
+}
+
+/// Update status order dari venue (event user data stream atau hasil poll GET /api/v3/order)
+struct OrderUpdate {
+    cl_id: String,
+    symbol: String,
+    /// status Binance: NEW, PARTIALLY_FILLED, FILLED, CANCELED, REJECTED, EXPIRED
+    status: String,
+    /// cum filled (base asset)
+    cum_qty: Option<f64>,
+    avg_px: Option<f64>,
+    exch_ms: Option<u64>,
+    order_id: Option<u64>,
+}
+
+impl OrderUpdate {
+    /// Dari respon GET /api/v3/order; avg = cummulativeQuoteQty / executedQty
+    fn from_query(v: &serde_json::Value, cl_id: &str, symbol: &str) -> Option<Self> {
+        let num = |k: &str| v.get(k).and_then(|x| x.as_str()).and_then(|x| x.parse::<f64>().ok());
+        let cum_qty = num("executedQty");
+        let avg_px = match (cum_qty, num("cummulativeQuoteQty")) {
+            (Some(q), Some(quote)) if q > 0.0 => Some(quote / q),
+            _ => None,
+        };
+        Some(Self {
+            cl_id: v.get("clientOrderId").and_then(|x| x.as_str()).unwrap_or(cl_id).to_string(),
+            symbol: symbol.to_string(),
+            status: v.get("status")?.as_str()?.to_string(),
+            cum_qty,
+            avg_px,
+            exch_ms: v.get("updateTime").and_then(|x| x.as_u64()),
+            order_id: v.get("orderId").and_then(|x| x.as_u64()),
+        })
+    }
+
+    fn cum_lots(&self) -> i64 {
+        self.cum_qty.map(|z| crate::sizing::lots_from_base(&self.symbol, z)).unwrap_or(0)
+    }
+}
+
+/// Event user data stream: update order (spot `executionReport` / futures `ORDER_TRADE_UPDATE`)
+/// -> ExecReport; saldo spot (`outboundAccountPosition`) -> Balances
+fn on_user_event(
+    ev: UserEvent,
+    live: &LiveOrders,
+    venue: &str,
+    health: &VenueHealth,
+    balances: Option<&Balances>,
+    ts_ns: i128,
+) -> Option<ExecReport> {
+    let num = |x: Option<String>| x.as_deref().and_then(|v| v.parse::<f64>().ok());
+    let u = match ev {
+        UserEvent::ExecutionReport(er) => OrderUpdate {
+            cl_id: er.cl_id().to_string(),
+            avg_px: er.avg_px(),
+            cum_qty: num(er.z),
+            symbol: er.s,
+            status: er.X,
+            exch_ms: er.E,
+            order_id: er.i,
+        },
+        UserEvent::OrderTradeUpdate(ev) => OrderUpdate {
+            cl_id: ev.o.c,
+            symbol: ev.o.s,
+            status: ev.o.X,
+            cum_qty: num(ev.o.z),
+            avg_px: num(ev.o.ap),
+            exch_ms: ev.E,
+            order_id: None,
+        },
+        UserEvent::AccountPosition(ap) => {
+            if let Some(b) = balances {
+                let parse = |v: &str| v.parse::<f64>().unwrap_or(0.0);
+                let updates: Vec<(String, Balance)> = ap
+                    .B
+                    .into_iter()
+                    .map(|x| (x.asset, Balance { free: parse(&x.free), locked: parse(&x.locked) }))
+                    .collect();
+                b.apply_updates(&updates);
+            }
+            return None;
+        }
+        UserEvent::Other => return None,
+    };
+    Some(apply_update(live, venue, health, u, ts_ns))
+}
+
+/// Map update venue -> ExecReport (atribusi dari live order; dilepas saat status final)
+fn apply_update(live: &LiveOrders, venue: &str, health: &VenueHealth, u: OrderUpdate, ts_ns: i128) -> ExecReport {
+    let cum_filled = u.cum_lots();
+    let (expiring, replaced) = match live.lock().unwrap().get_mut(&u.cl_id) {
+        Some(lo) => {
+            if lo.order_id.is_none() && u.order_id.is_some() {
+                lo.order_id = u.order_id;
+                crate::order_ids::bind_exchange(&u.cl_id, u.order_id.unwrap_or_default());
+            }
+            lo.last_status = u.status.clone();
+            lo.last_cum = cum_filled;
+            (lo.expiring, lo.replaced)
+        }
+        None => (false, false),
+    };
+
+    let status = match u.status.as_str() {
+        "NEW" => ExecStatus::Ack,
+        "PARTIALLY_FILLED" => ExecStatus::PartialFill,
+        "FILLED" => ExecStatus::Filled,
+        // cancel yang kita kirim karena GTT
+        "CANCELED" | "EXPIRED" if expiring => {
+            ORDERS_EXPIRED.with_label_values(&[venue]).inc();
+            ExecStatus::Expired
+        }
+        // order lama dari cancel/replace (OMS)
+        "CANCELED" if replaced => ExecStatus::Rejected("REPLACED".to_string()),
+        "CANCELED" | "EXPIRED" => ExecStatus::Rejected(u.status.clone()),
+        "REJECTED" => {
+            health.report_reject(venue);
+            ExecStatus::Rejected("REJECTED".to_string())
+        }
+        _ => ExecStatus::Ack,
+    };
+
+    let avg_px: i64 = u.avg_px.map(|p| (p * 100.0).round() as i64).unwrap_or(0);
+
+    let label: &str = match &status {
+        ExecStatus::Ack => "ack",
+        ExecStatus::PartialFill => "partial",
+        ExecStatus::Filled => "filled",
+        ExecStatus::Rejected(_) => "rejected",
+        ExecStatus::Expired => "expired",
+    };
+    EXECS.with_label_values(&[label, venue]).inc();
+
+    let (side, order_qty, strategy, tags, book) = {
+        let mut g = live.lock().unwrap();
+        let terminal = matches!(status, ExecStatus::Filled | ExecStatus::Rejected(_) | ExecStatus::Expired);
+        let entry = if terminal { g.remove(&u.cl_id) } else { g.get(&u.cl_id).cloned() };
+        entry.map(|lo| (lo.side, lo.qty, lo.strategy, lo.tags, lo.book)).unwrap_or_default()
+    };
+
+    ExecReport {
+        cl_id: u.cl_id,
+        symbol: u.symbol,
+        status,
+        filled_qty: cum_filled,
+        avg_px,
+        ts_ns,
+        venue: venue.to_string(),
+        side,
+        order_qty,
+        strategy,
+        tags,
+        book,
+        exch_ts_ns: clock::exch_ms_to_ns(u.exch_ms),
+    }
+}
+
+/// Poll status order via REST selama user data stream putus (+ satu sapuan setelah tersambung
+/// lagi, karena event selama putus tidak dikirim ulang). Hanya perubahan yang dilaporkan.
+struct OrderPoller {
+    rest: ExchangeHttp,
+    live: LiveOrders,
+    stream_up: Arc<AtomicBool>,
+    exec_tx: mpsc::Sender<ExecReport>,
+    venue: String,
+    health: VenueHealth,
+    clock: SharedClock,
+}
+
+impl OrderPoller {
+    async fn run(self, every: Duration) {
+        let mut tick = interval(every);
+        tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut was_down = false;
+        loop {
+            tick.tick().await;
+            let up = self.stream_up.load(Ordering::Relaxed);
+            if up && !was_down {
+                continue;
+            }
+            was_down = !up;
+            self.sweep().await;
+        }
+    }
+
+    async fn sweep(&self) {
+        let venue = &self.venue;
+        let targets: Vec<(String, String, Option<u64>)> = self
+            .live
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(cl_id, lo)| (cl_id.clone(), lo.symbol.clone(), lo.order_id))
+            .collect();
+        for (cl_id, symbol, order_id) in targets {
+            let by = match order_id {
+                Some(id) => ("orderId", id.to_string()),
+                None => ("origClientOrderId", cl_id.clone()),
+            };
+            let p = [("symbol", symbol.clone()), by];
+            let v = match self.rest.send(&endpoints::query_order(), &p).await {
+                Ok(v) => v,
+                Err(e) => {
+                    // -2013: order belum sampai di venue (submit masih in-flight)
+                    tracing::debug!(%venue, %cl_id, err = %e, "order status poll failed");
+                    BIN_ORDER_POLLS.with_label_values(&[venue, "error"]).inc();
+                    continue;
+                }
+            };
+            let Some(u) = OrderUpdate::from_query(&v, &cl_id, &symbol) else {
+                BIN_ORDER_POLLS.with_label_values(&[venue, "error"]).inc();
+                continue;
+            };
+            let changed = self
+                .live
+                .lock()
+                .unwrap()
+                .get(&u.cl_id)
+                .is_some_and(|lo| lo.last_status != u.status || lo.last_cum != u.cum_lots());
+            if !changed {
+                BIN_ORDER_POLLS.with_label_values(&[venue, "unchanged"]).inc();
+                continue;
+            }
+            BIN_ORDER_POLLS.with_label_values(&[venue, "updated"]).inc();
+            tracing::info!(%venue, cl_id = %u.cl_id, status = %u.status, "order update recovered by poll");
+            let _ = self.exec_tx.send(apply_update(&self.live, venue, &self.health, u, self.clock.now_ns())).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::OrderType;
+
+    fn queued(live: &LiveOrders, cl_id: &str, expire_at_ns: Option<i128>) {
+        let o = Order {
+            cl_id: cl_id.into(),
+            ts_ns: 0,
+            symbol: "btcusdt".into(),
+            side: Side::Buy,
+            px: 10_000,
+            qty: 1,
+            strategy: "mr".into(),
+            tags: vec![],
+            book: String::new(),
+            expire_at_ns,
+            order_type: OrderType::Limit,
+        };
+        live.lock().unwrap().insert(o.cl_id.clone(), LiveOrder::from_order(&o));
+    }
+
+    #[test]
+    fn order_cancelled_or_expired_while_queued_never_reaches_venue() {
+        let live: LiveOrders = Arc::default();
+        queued(&live, "gtt", Some(1_000));
+        queued(&live, "late", Some(1_000));
+        queued(&live, "cxl", None);
+        queued(&live, "ok", Some(5_000));
+
+        // GTT jatuh tempo selagi order antre di lane: loop gateway menandai expiring + antre Cancel
+        live.lock().unwrap().get_mut("gtt").unwrap().expiring = true;
+        assert!(matches!(claim(&live, "gtt", 500), Claim::Drop(ExecStatus::Expired)));
+        // Cancel di belakang placement: entry sudah dibuang -> tidak ada cancel ke venue
+        assert!(matches!(claim(&live, "gtt", 500), Claim::Gone));
+
+        // lewat GTT sebelum pemeriksa sempat jalan
+        assert!(matches!(claim(&live, "late", 2_000), Claim::Drop(ExecStatus::Expired)));
+
+        live.lock().unwrap().get_mut("cxl").unwrap().cancel_pending = true;
+        assert!(matches!(claim(&live, "cxl", 2_000), Claim::Drop(ExecStatus::Rejected(r)) if r == "CANCELED"));
+
+        assert!(matches!(claim(&live, "ok", 2_000), Claim::Send));
+        assert_eq!(live.lock().unwrap().keys().collect::<Vec<_>>(), ["ok"]);
+    }
+
+    #[test]
+    fn gtt_scan_follows_injected_clock() {
+        use crate::clock::{Clock, MockClock};
+        let live: LiveOrders = Arc::default();
+        let clock = MockClock::at_ns(1_000);
+        queued(&live, "a", Some(1_500));
+        queued(&live, "b", None);
+        assert!(mark_expired(&live, clock.now_ns()).is_empty());
+        clock.set_ns(1_500);
+        assert_eq!(mark_expired(&live, clock.now_ns()), [("a".to_string(), "BTCUSDT".to_string())]);
+        // sudah ditandai expiring: cancel GTT tidak diantre dua kali
+        assert!(mark_expired(&live, clock.now_ns()).is_empty());
     }
 }
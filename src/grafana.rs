@@ -0,0 +1,252 @@
+// ===============================
+// src/grafana.rs
+// ===============================
+//
+// Generator dashboard Grafana (`dma_bot_rust grafana-dashboard > dashboard.json`).
+//
+// Nama metric diambil langsung dari collector di metrics.rs (`desc().fq_name`), jadi
+// dashboard selalu cocok dengan binary yang menghasilkannya. Label di ekspresi PromQL
+// harus mengikuti label yang didaftarkan di metrics.rs.
+//
+// Output siap di-import: datasource lewat input `${DS_PROMETHEUS}`, variabel
+// job / venue / symbol / rate_window.
+//
+use prometheus::core::Collector;
+use serde_json::{json, Value};
+
+use crate::metrics::*;
+
+const DS: &str = "${DS_PROMETHEUS}";
+const WIDTH: u32 = 24;
+
+/// Nama metric dari collector (untuk histogram: nama dasar, tanpa _bucket)
+fn name<C: Collector>(c: &C) -> String {
+    c.desc().first().map(|d| d.fq_name.clone()).unwrap_or_default()
+}
+
+/// Penempatan panel otomatis: kiri -> kanan, pindah baris jika lebar habis
+struct Layout {
+    panels: Vec<Value>,
+    x: u32,
+    y: u32,
+    row_h: u32,
+}
+
+impl Layout {
+    fn new() -> Self {
+        Self { panels: Vec::new(), x: 0, y: 0, row_h: 0 }
+    }
+
+    fn add(&mut self, kind: &str, title: &str, w: u32, h: u32, unit: &str, exprs: &[(String, &str)]) {
+        if self.x + w > WIDTH {
+            self.x = 0;
+            self.y += self.row_h;
+            self.row_h = 0;
+        }
+        let targets: Vec<Value> = exprs
+            .iter()
+            .enumerate()
+            .map(|(i, (expr, legend))| {
+                json!({
+                    "expr": expr,
+                    "legendFormat": legend,
+                    "refId": ((b'A' + i as u8) as char).to_string(),
+                })
+            })
+            .collect();
+        let mut panel = json!({
+            "type": kind,
+            "title": title,
+            "id": self.panels.len() + 1,
+            "gridPos": { "h": h, "w": w, "x": self.x, "y": self.y },
+            "datasource": { "type": "prometheus", "uid": DS },
+            "targets": targets,
+            "fieldConfig": { "defaults": { "unit": unit } },
+        });
+        if kind == "stat" {
+            panel["options"] = json!({
+                "reduceOptions": { "calcs": ["lastNotNull"] },
+                "colorMode": "value",
+                "graphMode": "none",
+                "justifyMode": "auto",
+            });
+        }
+        self.panels.push(panel);
+        self.x += w;
+        self.row_h = self.row_h.max(h);
+    }
+}
+
+fn query_var(name: &str, query: String) -> Value {
+    json!({
+        "name": name,
+        "type": "query",
+        "datasource": { "type": "prometheus", "uid": DS },
+        "query": query,
+        "refresh": 1,
+        "includeAll": true,
+        "multi": true,
+        "current": { "text": "All", "value": "$__all" },
+    })
+}
+
+/// Dashboard lengkap sebagai JSON
+pub fn dashboard() -> Value {
+    let execs = name(&*EXECS);
+    let lat = name(&*LAT_SIG_ACK);
+    let inv = name(&*INV_QTY);
+    let inv_total = name(&*INV_TOTAL_QTY);
+    let pnl_r = name(&*PNL_REALIZED);
+    let pnl_u = name(&*PNL_UNREALIZED);
+    let pnl_strat = name(&*PNL_REALIZED_BY_STRATEGY);
+    let markout = name(&*MARKOUT_TICKS);
+    let markout_venue = name(&*MARKOUT_AVG_BY_VENUE);
+    let ticks = name(&*TICKS_BY_SYMBOL);
+    let signals = name(&*SIGNALS);
+    let orders = name(&*ORDERS);
+    let safe = name(&*VENUE_SAFE_MODE);
+    let safe_entries = name(&*VENUE_SAFE_MODE_ENTRIES);
+    let expired = name(&*ORDERS_EXPIRED);
+    let dropped = name(&*EXEC_DROPPED);
+    let recon = name(&*RECON_BREAKS);
+    let ws_conn = name(&*BIN_WS_CONNECTED);
+    let ws_age = name(&*BIN_WS_LAST_EVENT_AGE);
+    let ipc = name(&*IPC_CONNECTED);
+    let ipc_dropped = name(&*IPC_DROPPED);
+
+    let j = r#"job="$job""#;
+    let sel = r#"job="$job", symbol=~"$symbol", venue=~"$venue""#;
+    let rw = "[$rate_window]";
+
+    let mut l = Layout::new();
+
+    // ---- Ringkasan ----
+    l.add("stat", "Exec Reports /s (total)", 6, 5, "ops", &[(format!("sum(rate({execs}{{{j}}}{rw}))"), "all")]);
+    l.add("stat", "Fills /s", 6, 5, "ops", &[(format!(r#"sum(rate({execs}{{{j}, status="filled"}}{rw}))"#), "filled")]);
+    l.add("stat", "Realized PnL (ticks)", 6, 5, "none", &[(format!("sum({pnl_r}{{{j}}})"), "realized")]);
+    l.add("stat", "Unrealized PnL (ticks)", 6, 5, "none", &[(format!("sum({pnl_u}{{{j}}})"), "unrealized")]);
+
+    // ---- Alur order ----
+    l.add("timeseries", "Pipeline /s — ticks → signals → orders", 12, 8, "ops", &[
+        (format!("sum(rate({ticks}{{{j}, symbol=~\"$symbol\"}}{rw}))"), "ticks"),
+        (format!("sum(rate({signals}{{{j}}}{rw}))"), "signals"),
+        (format!("sum(rate({orders}{{{j}}}{rw}))"), "orders"),
+    ]);
+    l.add("timeseries", "Exec Reports /s by Status × Venue", 12, 8, "ops", &[(
+        format!("sum by (status, venue) (rate({execs}{{{j}, venue=~\"$venue\"}}{rw}))"),
+        "{{status}} @ {{venue}}",
+    )]);
+    let quantiles: Vec<(String, &str)> = [("0.50", "p50"), ("0.90", "p90"), ("0.95", "p95"), ("0.99", "p99")]
+        .iter()
+        .map(|(q, legend)| (format!("histogram_quantile({q}, sum by (le) (rate({lat}_bucket{{{j}}}{rw})))"), *legend))
+        .collect();
+    l.add("timeseries", "Latency signal→ack (ms)", 12, 8, "ms", &quantiles);
+    l.add("timeseries", "Orders Expired /s (GTT) & Exec Reports Dropped /s", 12, 8, "ops", &[
+        (format!("sum by (venue) (rate({expired}{{{j}}}{rw}))"), "expired @ {{venue}}"),
+        (format!("sum by (reason) (rate({dropped}{{{j}}}{rw}))"), "dropped: {{reason}}"),
+    ]);
+
+    // ---- Inventory & PnL ----
+    l.add("stat", "Inventory Total Qty", 6, 8, "none", &[(format!("{inv_total}{{{j}}}"), "total")]);
+    l.add("timeseries", "Inventory per Venue", 9, 8, "none", &[(
+        format!("sum by (venue) ({inv}{{{sel}}})"),
+        "{{venue}}",
+    )]);
+    l.add("table", "Inventory Snapshot (symbol × venue)", 9, 8, "none", &[(format!("{inv}{{{sel}}}"), "")]);
+    l.add("timeseries", "Realized / Unrealized PnL (ticks)", 12, 8, "none", &[
+        (format!("sum({pnl_r}{{{j}}})"), "realized"),
+        (format!("sum({pnl_u}{{{j}}})"), "unrealized"),
+    ]);
+    l.add("timeseries", "Realized PnL by Strategy (ticks)", 12, 8, "none", &[(
+        format!("sum by (strategy) ({pnl_strat}{{{j}, symbol=~\"$symbol\"}})"),
+        "{{strategy}}",
+    )]);
+
+    // ---- Kualitas eksekusi ----
+    l.add("timeseries", "Markout by Horizon (avg ticks per fill)", 12, 8, "none", &[(
+        format!(
+            "sum by (horizon) (rate({markout}_sum{{{j}}}{rw})) / sum by (horizon) (rate({markout}_count{{{j}}}{rw}))"
+        ),
+        "{{horizon}}",
+    )]);
+    l.add("timeseries", "Markout by Venue (qty-weighted ticks)", 12, 8, "none", &[(
+        format!("{markout_venue}{{{j}, venue=~\"$venue\"}}"),
+        "{{horizon}} @ {{venue}}",
+    )]);
+
+    // ---- Kesehatan venue & link ----
+    l.add("timeseries", "Venue Safe Mode", 8, 7, "none", &[
+        (format!("{safe}{{{j}, venue=~\"$venue\"}}"), "{{venue}}"),
+        (format!("sum by (venue, reason) (increase({safe_entries}{{{j}}}{rw}))"), "entries {{venue}}: {{reason}}"),
+    ]);
+    l.add("timeseries", "Binance User Stream", 8, 7, "none", &[
+        (format!("{ws_conn}{{{j}}}"), "connected {{venue}}"),
+        (format!("{ws_age}{{{j}}}"), "last event age (s) {{venue}}"),
+    ]);
+    l.add("timeseries", "Reconciliation Breaks & IPC", 8, 7, "none", &[
+        (format!("sum by (kind) (increase({recon}{{{j}}}{rw}))"), "break: {{kind}}"),
+        (format!("{ipc}{{{j}}}"), "ipc connected"),
+        (format!("sum by (kind) (rate({ipc_dropped}{{{j}}}{rw}))"), "ipc dropped: {{kind}}"),
+    ]);
+
+    // ---- Scrape & konfigurasi ----
+    l.add("stat", "Target Health (UP ratio)", 4, 6, "percentunit", &[(format!("avg(up{{{j}}})"), "up")]);
+    l.add("stat", "Scrape Duration (avg)", 4, 6, "s", &[(format!("avg(scrape_duration_seconds{{{j}}})"), "scrape")]);
+    l.add("stat", "Samples / scrape", 4, 6, "none", &[(
+        format!("avg(scrape_samples_post_metric_relabeling{{{j}}})"),
+        "samples",
+    )]);
+    l.add("table", "Config Overview", 12, 6, "none", &[
+        (format!("{}{{{j}}}", name(&*CONFIG_FEED_MODE)), "feed"),
+        (format!("{}{{{j}}}", name(&*CONFIG_VENUE_MODE)), "venue"),
+        (format!("{}{{{j}}}", name(&*CONFIG_STRATEGY_ACTIVE)), "strategy"),
+        (format!("{}{{{j}}}", name(&*CONFIG_SYMBOL)), "symbol"),
+    ]);
+
+    let windows: Vec<Value> = ["30s", "1m", "5m", "15m"].iter().map(|w| json!({ "text": w, "value": w })).collect();
+
+    json!({
+        "__inputs": [{
+            "name": "DS_PROMETHEUS",
+            "label": "Prometheus",
+            "type": "datasource",
+            "pluginId": "prometheus",
+            "pluginName": "Prometheus",
+        }],
+        "overwrite": true,
+        "schemaVersion": 39,
+        "title": "DMA Bot — Trading & PnL (Rust)",
+        "uid": "dma-bot-trading-pnl",
+        "version": 1,
+        "description": format!("generated by dma_bot_rust {} (grafana-dashboard)", env!("CARGO_PKG_VERSION")),
+        "tags": ["rust", "prometheus", "dma", "trading"],
+        "time": { "from": "now-6h", "to": "now" },
+        "refresh": "10s",
+        "templating": { "list": [
+            {
+                "name": "job",
+                "type": "query",
+                "datasource": { "type": "prometheus", "uid": DS },
+                "query": "label_values(up, job)",
+                "refresh": 1,
+                "current": { "text": "dma_bot_rust", "value": "dma_bot_rust" },
+            },
+            query_var("venue", format!("label_values({inv}{{{j}}}, venue)")),
+            query_var("symbol", format!("label_values({inv}{{{j}}}, symbol)")),
+            {
+                "name": "rate_window",
+                "type": "custom",
+                "query": "30s,1m,5m,15m",
+                "current": { "text": "1m", "value": "1m" },
+                "options": windows,
+            },
+        ]},
+        "panels": l.panels,
+    })
+}
+
+/// Subcommand `grafana-dashboard`: cetak JSON ke stdout
+pub fn print() {
+    let out = serde_json::to_string_pretty(&dashboard()).unwrap_or_default();
+    println!("{out}");
+}
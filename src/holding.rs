@@ -0,0 +1,207 @@
+// ===============================
+// src/holding.rs
+// ===============================
+//
+// Batas lama memegang posisi per strategi (mandat intraday, strategi scalping yang "nyangkut").
+//
+// - Umur posisi per (strategi, symbol) dihitung dari snapshot posisi (PositionFeed, by_strategy):
+//   dimulai saat posisi strategi berubah dari flat (atau berbalik arah), reset saat kembali flat.
+// - Risk menolak Signal yang MENAMBAH posisi yang sudah melewati HOLD_MAX_SECS (RiskError::HoldingPeriod);
+//   order yang mengurangi posisi tetap boleh.
+// - Task `run` mengirim Signal force-exit (sisi berlawanan, qty = posisi, harga = best bid/ask) untuk
+//   posisi yang kedaluwarsa, diulang tiap HOLD_RETRY_SECS selama posisi masih terbuka.
+//   Signal bertag `force_exit`: risk memakai qty apa adanya (tanpa skala alokasi / notional) dan
+//   tidak memblokirnya karena strategi di-disable scoreboard atau guard overtrading.
+//   Dihitung di `holding_force_exits_total{strategy}`.
+//
+// ENV:
+//   HOLD_MAX_SECS=mean_reversion:3600,ma_crossover:28800   (kosong = nonaktif)
+//   HOLD_CHECK_SECS=5
+//   HOLD_RETRY_SECS=30
+//
+use ahash::AHashMap as HashMap;
+use std::sync::{Arc, RwLock};
+use tokio::sync::mpsc;
+use tokio::time::{interval, Duration, MissedTickBehavior};
+use tracing::warn;
+
+use crate::books::BookCfg;
+use crate::clock;
+use crate::domain::{InvSnapshot, Side, Signal};
+use crate::feed_health::FeedHealth;
+use crate::metrics::{strategy_label, HOLDING_FORCE_EXITS};
+use crate::positions::PositionFeed;
+
+/// Tag Signal force-exit (lihat risk::check)
+pub const FORCE_EXIT_TAG: &str = "force_exit";
+
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct HoldingCfg {
+    pub max_secs: HashMap<String, f64>,
+    pub check_secs: u64,
+    pub retry_secs: u64,
+}
+
+impl HoldingCfg {
+    pub fn from_env() -> Self {
+        let num = |k: &str, d: u64| std::env::var(k).ok().and_then(|s| s.parse().ok()).unwrap_or(d);
+        Self {
+            max_secs: crate::sizing::parse_map(&std::env::var("HOLD_MAX_SECS").unwrap_or_default()),
+            check_secs: num("HOLD_CHECK_SECS", 5),
+            retry_secs: num("HOLD_RETRY_SECS", 30),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        !self.max_secs.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Held {
+    qty: i64,
+    opened_ns: i128,
+    last_exit_ns: i128,
+}
+
+/// Handle umur posisi (diisi task `run`, dibaca risk)
+#[derive(Clone, Default)]
+pub struct HoldingGuard {
+    cfg: Arc<HoldingCfg>,
+    held: Arc<RwLock<HashMap<(String, String), Held>>>,
+}
+
+impl HoldingGuard {
+    pub fn new(cfg: HoldingCfg) -> Self {
+        Self { cfg: Arc::new(cfg), held: Arc::default() }
+    }
+
+    /// Update umur posisi strategi dari snapshot symbol (ts_ns = waktu fill terakhir)
+    pub fn observe(&self, snap: &InvSnapshot) {
+        let mut g = self.held.write().unwrap();
+        for (strategy, pos) in &snap.state.by_strategy {
+            let key = (strategy.clone(), snap.symbol.clone());
+            if pos.qty == 0 {
+                g.remove(&key);
+                continue;
+            }
+            match g.get_mut(&key) {
+                Some(h) if h.qty.signum() == pos.qty.signum() => h.qty = pos.qty,
+                _ => {
+                    g.insert(key, Held { qty: pos.qty, opened_ns: snap.ts_ns, last_exit_ns: 0 });
+                }
+            }
+        }
+    }
+
+    /// (detik dipegang, batas) jika posisi strategi di symbol sudah melewati batasnya
+    pub fn overdue(&self, strategy: &str, symbol: &str, now: i128) -> Option<(f64, f64)> {
+        let max = *self.cfg.max_secs.get(strategy)?;
+        let h = *self.held.read().unwrap().get(&(strategy.to_string(), symbol.to_string()))?;
+        let held = (now - h.opened_ns) as f64 / 1e9;
+        (held > max).then_some((held, max))
+    }
+
+    /// Some(detik dipegang, batas) jika Signal menambah posisi yang sudah kedaluwarsa
+    pub fn blocks(&self, strategy: &str, symbol: &str, side: Side, now: i128) -> Option<(f64, f64)> {
+        let qty = self.held.read().unwrap().get(&(strategy.to_string(), symbol.to_string()))?.qty;
+        if qty.signum() != side.sign() {
+            return None;
+        }
+        self.overdue(strategy, symbol, now)
+    }
+
+    /// Posisi kedaluwarsa yang perlu (di-)force-exit sekarang: (strategi, symbol, qty, detik dipegang)
+    fn due_exits(&self, now: i128) -> Vec<(String, String, i64, f64)> {
+        let retry = self.cfg.retry_secs as i128 * 1_000_000_000;
+        let keys: Vec<_> = self.held.read().unwrap().keys().cloned().collect();
+        let mut out = Vec::new();
+        for (strategy, symbol) in keys {
+            let Some((held, _)) = self.overdue(&strategy, &symbol, now) else { continue };
+            let mut g = self.held.write().unwrap();
+            if let Some(h) = g.get_mut(&(strategy.clone(), symbol.clone())).filter(|h| now - h.last_exit_ns >= retry) {
+                h.last_exit_ns = now;
+                out.push((strategy, symbol, h.qty, held));
+            }
+        }
+        out
+    }
+}
+
+pub fn is_force_exit(sig: &Signal) -> bool {
+    sig.tags.iter().any(|t| t == FORCE_EXIT_TAG)
+}
+
+/// Tag yang hanya boleh dipasang engine (force-exit / flatten portfolio stop). Signal bertag ini melewati
+/// gate risk, jadi tag dari luar (webhook, STRATEGY_TAGS) ditolak
+pub fn is_reserved_tag(tag: &str) -> bool {
+    [FORCE_EXIT_TAG, crate::portfolio_stop::PORTFOLIO_STOP_TAG].iter().any(|r| tag.trim().eq_ignore_ascii_case(r))
+}
+
+/// Task: baca posisi terbaru, kirim Signal force-exit untuk posisi yang melewati HOLD_MAX_SECS
+pub async fn run(guard: HoldingGuard, positions: PositionFeed, feed: FeedHealth, books: BookCfg, sig_tx: mpsc::Sender<Signal>) {
+    let mut tick = interval(Duration::from_secs(guard.cfg.check_secs.max(1)));
+    tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        tick.tick().await;
+        for snap in positions.all() {
+            guard.observe(&snap);
+        }
+        let now = clock::now_ns();
+        for (strategy, symbol, qty, held) in guard.due_exits(now) {
+            let side = if qty > 0 { Side::Sell } else { Side::Buy };
+            let Some((bid, ask)) = feed.quote(&symbol) else {
+                warn!(%strategy, %symbol, "holding limit: no quote for force exit, retrying later");
+                continue;
+            };
+            warn!(%strategy, %symbol, qty, held_secs = held as u64, "holding limit exceeded: force exit");
+            HOLDING_FORCE_EXITS.with_label_values(&[strategy_label(&strategy)]).inc();
+            let sig = Signal {
+                ts_ns: now,
+                symbol,
+                side,
+                px: if qty > 0 { bid } else { ask },
+                qty: qty.abs(),
+                book: books.book_for(&strategy),
+                strategy,
+                tags: vec![FORCE_EXIT_TAG.to_string(), "hold_limit".to_string()],
+                quote_notional: None,
+            };
+            if sig_tx.send(sig).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{SymbolState, VenuePosition};
+
+    fn snap(ts_s: i128, qty: i64) -> InvSnapshot {
+        let mut state = SymbolState::default();
+        state.by_strategy.insert("mr".into(), VenuePosition { qty, avg_cost_px: 100, realized_pnl: 0 });
+        InvSnapshot { ts_ns: ts_s * 1_000_000_000, symbol: "BTCUSDT".into(), state }
+    }
+
+    #[test]
+    fn position_age_starts_at_open_and_blocks_adding_after_limit() {
+        let cfg = HoldingCfg { max_secs: [("mr".to_string(), 60.0)].into_iter().collect(), check_secs: 1, retry_secs: 30 };
+        let g = HoldingGuard::new(cfg);
+        let s = 1_000_000_000i128;
+        g.observe(&snap(0, 5));
+        // tambah posisi searah tidak mereset umur
+        g.observe(&snap(50, 8));
+        assert!(g.overdue("mr", "BTCUSDT", 59 * s).is_none());
+        assert!(g.blocks("mr", "BTCUSDT", Side::Buy, 61 * s).is_some());
+        assert!(g.blocks("mr", "BTCUSDT", Side::Sell, 61 * s).is_none());
+        assert_eq!(g.due_exits(61 * s).len(), 1);
+        // retry baru setelah HOLD_RETRY_SECS
+        assert!(g.due_exits(70 * s).is_empty());
+        assert_eq!(g.due_exits(91 * s)[0].2, 8);
+        // flat -> umur reset
+        g.observe(&snap(95, 0));
+        assert!(g.overdue("mr", "BTCUSDT", 200 * s).is_none());
+    }
+}
@@ -0,0 +1,147 @@
+// ===============================
+// src/indicators.rs
+// ===============================
+//
+// Indikator teknikal bersama untuk strategi (state bisa di-snapshot, harga dalam tick ×100).
+//
+// - RollingStats: mean & standar deviasi rolling N nilai terakhir (Bollinger band).
+//   Jumlah & jumlah kuadrat disimpan sebagai i128 agar update O(1) tanpa drift floating point.
+// - Channel: high/low rolling N nilai terakhir (Donchian channel).
+// - Atr: Average True Range dengan smoothing Wilder (trailing stop).
+//
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RollingStats {
+    cap: usize,
+    win: VecDeque<i64>,
+    sum: i128,
+    sum_sq: i128,
+}
+
+impl RollingStats {
+    pub fn new(cap: usize) -> Self {
+        let cap = cap.max(1);
+        Self { cap, win: VecDeque::with_capacity(cap), sum: 0, sum_sq: 0 }
+    }
+
+    pub fn push(&mut self, v: i64) {
+        if self.win.len() == self.cap {
+            if let Some(x) = self.win.pop_front() {
+                self.sum -= x as i128;
+                self.sum_sq -= (x as i128) * (x as i128);
+            }
+        }
+        self.win.push_back(v);
+        self.sum += v as i128;
+        self.sum_sq += (v as i128) * (v as i128);
+    }
+
+    /// Window sudah terisi penuh
+    pub fn full(&self) -> bool {
+        self.win.len() == self.cap
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        (!self.win.is_empty()).then(|| self.sum as f64 / self.win.len() as f64)
+    }
+
+    /// Standar deviasi populasi
+    pub fn std(&self) -> Option<f64> {
+        let n = self.win.len() as i128;
+        if n == 0 {
+            return None;
+        }
+        // n² × varians = n·Σx² − (Σx)², dihitung eksak di i128
+        let var_n2 = n * self.sum_sq - self.sum * self.sum;
+        Some((var_n2.max(0) as f64).sqrt() / n as f64)
+    }
+}
+
+/// High/low rolling N nilai terakhir
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Channel {
+    cap: usize,
+    win: VecDeque<i64>,
+}
+
+impl Channel {
+    pub fn new(cap: usize) -> Self {
+        let cap = cap.max(1);
+        Self { cap, win: VecDeque::with_capacity(cap) }
+    }
+
+    pub fn push(&mut self, v: i64) {
+        if self.win.len() == self.cap {
+            self.win.pop_front();
+        }
+        self.win.push_back(v);
+    }
+
+    pub fn full(&self) -> bool {
+        self.win.len() == self.cap
+    }
+
+    /// (high, low) window; None jika kosong
+    pub fn bounds(&self) -> Option<(i64, i64)> {
+        let hi = self.win.iter().max()?;
+        let lo = self.win.iter().min()?;
+        Some((*hi, *lo))
+    }
+}
+
+/// Average True Range (Wilder): ATR = (ATR_prev × (n-1) + TR) / n, seed = rata-rata n TR pertama
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Atr {
+    n: usize,
+    prev_close: Option<i64>,
+    seed: Vec<f64>,
+    value: Option<f64>,
+}
+
+impl Atr {
+    pub fn new(n: usize) -> Self {
+        Self { n: n.max(1), prev_close: None, seed: Vec::new(), value: None }
+    }
+
+    /// Masukkan satu bar (untuk data tick: high = low = close = mid)
+    pub fn push(&mut self, high: i64, low: i64, close: i64) {
+        let tr = match self.prev_close {
+            Some(pc) => (high - low).max((high - pc).abs()).max((low - pc).abs()),
+            None => high - low,
+        } as f64;
+        self.prev_close = Some(close);
+        self.value = match self.value {
+            Some(atr) => Some((atr * (self.n - 1) as f64 + tr) / self.n as f64),
+            None => {
+                self.seed.push(tr);
+                (self.seed.len() == self.n).then(|| std::mem::take(&mut self.seed).iter().sum::<f64>() / self.n as f64)
+            }
+        };
+    }
+
+    pub fn value(&self) -> Option<f64> {
+        self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rolling_mean_and_std_drop_old_values() {
+        let mut s = RollingStats::new(4);
+        for v in [2, 4, 4, 4] {
+            s.push(v);
+        }
+        assert!(s.full());
+        assert_eq!(s.mean(), Some(3.5));
+        assert!((s.std().unwrap() - 0.75f64.sqrt()).abs() < 1e-12);
+        // 2 keluar dari window: [4, 4, 4, 8]
+        s.push(8);
+        assert_eq!(s.mean(), Some(5.0));
+        assert!((s.std().unwrap() - 3.0f64.sqrt()).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,156 @@
+// ===============================
+// src/ipc.rs
+// ===============================
+//
+// IPC untuk mode multi-proses (PROCESS_ROLE=feed|exec):
+//
+//   [feed process]  feed + strategi  ──Unix socket──>  [exec process] risk + router + gateway + positions
+//
+// - Frame: panjang u32 little-endian + payload bincode (`IpcMsg`).
+// - Proses feed menjadi server (berumur panjang, memegang state rolling window strategi);
+//   proses exec menjadi client dan reconnect otomatis, jadi exec bisa di-restart
+//   tanpa kehilangan state market data.
+// - Saat exec tidak tersambung, Signal dibuang (sinyal basi tidak boleh dieksekusi
+//   belakangan) dan dihitung di `ipc_dropped_total{kind}`.
+//
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{UnixListener, UnixStream},
+    sync::{broadcast, mpsc},
+};
+use tracing::{error, info, warn};
+
+use crate::domain::{MdTick, Signal};
+use crate::md_seq::GapDetector;
+use crate::metrics::{IPC_CONNECTED, IPC_DROPPED};
+use crate::reconnect::{self, Backoff, Policy};
+
+/// Batas ukuran frame (pengaman terhadap data korup)
+const MAX_FRAME: usize = 1 << 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum IpcMsg {
+    Md(MdTick),
+    Sig(Signal),
+}
+
+async fn write_frame(s: &mut UnixStream, msg: &IpcMsg) -> std::io::Result<()> {
+    let buf = bincode::serialize(msg).map_err(std::io::Error::other)?;
+    s.write_all(&(buf.len() as u32).to_le_bytes()).await?;
+    s.write_all(&buf).await
+}
+
+async fn read_frame(s: &mut UnixStream) -> std::io::Result<IpcMsg> {
+    let mut len = [0u8; 4];
+    s.read_exact(&mut len).await?;
+    let len = u32::from_le_bytes(len) as usize;
+    if len > MAX_FRAME {
+        return Err(std::io::Error::other(format!("ipc frame too large: {len}")));
+    }
+    let mut buf = vec![0u8; len];
+    s.read_exact(&mut buf).await?;
+    bincode::deserialize(&buf).map_err(std::io::Error::other)
+}
+
+/// Sisi feed: listen di `path`, teruskan MdTick & Signal ke proses exec yang tersambung
+pub async fn serve_feed(
+    path: String,
+    mut md_rx: broadcast::Receiver<MdTick>,
+    mut sig_rx: mpsc::Receiver<Signal>,
+) {
+    let _ = std::fs::remove_file(&path); // socket basi dari run sebelumnya
+    let listener = match UnixListener::bind(&path) {
+        Ok(l) => l,
+        Err(e) => {
+            error!(?e, %path, "ipc: bind failed");
+            return;
+        }
+    };
+    info!(%path, "ipc: feed server listening");
+    let mut gaps = GapDetector::new("ipc");
+
+    let mut client: Option<UnixStream> = None;
+    loop {
+        let msg = tokio::select! {
+            acc = listener.accept() => {
+                match acc {
+                    Ok((s, _)) => {
+                        info!("ipc: exec process connected");
+                        IPC_CONNECTED.set(1);
+                        client = Some(s);
+                    }
+                    Err(e) => warn!(?e, "ipc: accept failed"),
+                }
+                continue;
+            }
+            md = md_rx.recv() => match md {
+                Ok(md) => {
+                    gaps.observe(&md);
+                    IpcMsg::Md(md)
+                }
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    IPC_DROPPED.with_label_values(&["md"]).inc_by(n);
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            sig = sig_rx.recv() => match sig {
+                Some(sig) => IpcMsg::Sig(sig),
+                None => break,
+            },
+        };
+
+        let kind = if matches!(msg, IpcMsg::Md(_)) { "md" } else { "sig" };
+        match client.as_mut() {
+            Some(s) => {
+                if let Err(e) = write_frame(s, &msg).await {
+                    warn!(?e, "ipc: exec process disconnected");
+                    IPC_CONNECTED.set(0);
+                    IPC_DROPPED.with_label_values(&[kind]).inc();
+                    client = None;
+                }
+            }
+            None => IPC_DROPPED.with_label_values(&[kind]).inc(),
+        }
+    }
+}
+
+/// Sisi exec: sambung ke proses feed, injeksikan MdTick & Signal ke bus lokal
+pub async fn run_exec_client(
+    path: String,
+    md_tx: broadcast::Sender<MdTick>,
+    sig_tx: mpsc::Sender<Signal>,
+) {
+    // socket lokal: tetap coba ulang cepat (maks 1s) walau policy global lebih longgar
+    let policy = Policy { max_ms: reconnect::policy().max_ms.min(1_000), ..reconnect::policy().clone() };
+    let mut backoff = Backoff::with_policy("ipc:feed", policy);
+    loop {
+        match UnixStream::connect(&path).await {
+            Ok(mut s) => {
+                info!(%path, "ipc: connected to feed process");
+                IPC_CONNECTED.set(1);
+                backoff.connected();
+                loop {
+                    match read_frame(&mut s).await {
+                        Ok(IpcMsg::Md(md)) => { let _ = md_tx.send(md); }
+                        Ok(IpcMsg::Sig(sig)) => {
+                            if sig_tx.send(sig).await.is_err() {
+                                return;
+                            }
+                        }
+                        Err(e) => {
+                            warn!(?e, "ipc: feed connection lost, reconnecting …");
+                            break;
+                        }
+                    }
+                }
+                IPC_CONNECTED.set(0);
+            }
+            Err(e) => warn!(?e, %path, "ipc: connect to feed process failed"),
+        }
+        if !backoff.wait().await {
+            return;
+        }
+    }
+}
@@ -0,0 +1,95 @@
+// ===============================
+// src/kill_switch.rs
+// ===============================
+//
+// Panic button level venue:
+// - `cancel_all(reason)` : kirim VenueCmd::CancelAll ke semua gateway
+//     * Binance: DELETE /api/v3/openOrders per symbol (symbol config + yang pernah diorder)
+//     * mock   : order resting di book simulasi dibatalkan (Rejected "CANCELED")
+// - `engage(reason)`     : kill switch = risk menolak semua Signal baru + cancel_all
+// - `release()`          : trading dibuka lagi (order yang sudah dibatalkan tidak dikirim ulang)
+//
+// Dipanggil dari admin API (POST /admin/cancel_all, /admin/kill), graceful shutdown (Ctrl-C),
+// dan portfolio stop (portfolio_stop.rs, cancel_all saja).
+// Aman dipanggil dari thread non-async (admin) karena memakai try_send.
+//
+use ahash::AHashMap as HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tracing::{error, warn};
+
+use crate::alerts::{AlertKind, Alerts};
+use crate::domain::VenueCmd;
+use crate::metrics::{CANCEL_ALL, KILL_SWITCH};
+
+#[derive(Clone, Default)]
+pub struct KillSwitch {
+    engaged: Arc<AtomicBool>,
+    venues: Arc<Mutex<HashMap<String, mpsc::Sender<VenueCmd>>>>,
+    alerts: Alerts,
+}
+
+impl KillSwitch {
+    /// Kill switch yang mengirim notifikasi (email) saat di-engage
+    pub fn with_alerts(alerts: Alerts) -> Self {
+        Self { alerts, ..Default::default() }
+    }
+
+    /// Daftarkan channel kontrol gateway (dipanggil saat wiring venue)
+    pub fn register_venue(&self, venue: &str, tx: mpsc::Sender<VenueCmd>) {
+        self.venues.lock().unwrap().insert(venue.to_string(), tx);
+    }
+
+    pub fn engaged(&self) -> bool {
+        self.engaged.load(Ordering::Relaxed)
+    }
+
+    /// Batalkan semua order terbuka di semua venue; kembalikan jumlah venue yang menerima perintah
+    pub fn cancel_all(&self, reason: &str) -> usize {
+        let venues = self.venues.lock().unwrap();
+        let mut sent = 0;
+        for (venue, tx) in venues.iter() {
+            match tx.try_send(VenueCmd::CancelAll { reason: reason.to_string() }) {
+                Ok(()) => sent += 1,
+                Err(e) => error!(%venue, %reason, ?e, "cancel-all: venue command channel unavailable"),
+            }
+        }
+        CANCEL_ALL.with_label_values(&[reason_label(reason)]).inc();
+        warn!(%reason, venues = sent, "cancel-all sent");
+        sent
+    }
+
+    /// Hentikan trading baru + batalkan semua order terbuka
+    pub fn engage(&self, reason: &str) -> usize {
+        let first = !self.engaged.swap(true, Ordering::Relaxed);
+        if first {
+            warn!(%reason, "KILL SWITCH engaged");
+        }
+        KILL_SWITCH.set(1);
+        let venues = self.cancel_all(reason);
+        if first {
+            self.alerts.notify(AlertKind::KillSwitch, &[("reason", reason.to_string()), ("venues", venues.to_string())]);
+        }
+        venues
+    }
+
+    #[cfg(feature = "admin-api")]
+    pub fn release(&self) {
+        if self.engaged.swap(false, Ordering::Relaxed) {
+            warn!("kill switch released");
+        }
+        KILL_SWITCH.set(0);
+    }
+}
+
+/// Label metrik dibatasi ke sumber perintah (alasan bebas hanya di log)
+fn reason_label(reason: &str) -> &'static str {
+    match reason.split(':').next().unwrap_or("") {
+        "admin" => "admin",
+        "kill" => "kill",
+        "shutdown" => "shutdown",
+        "portfolio_stop" => "portfolio_stop",
+        _ => "other",
+    }
+}
@@ -0,0 +1,100 @@
+// ===============================
+// src/liveness.rs
+// ===============================
+//
+// Heartbeat engine (sebelumnya di main.rs): forward MD ke recorder + ringkasan periodik.
+//
+// Tiap HEARTBEAT_SECS:
+// - log `heartbeat` (tick per detik)
+// - Event::Note heartbeat ke recorder (hanya jika RECORD_FILE aktif), jadi post-mortem bisa melihat
+//   kapan engine macet tanpa menebak dari jeda MD:
+//     {"heartbeat":{"ts_ns":..,"uptime_secs":..,"ticks":..,"tick_rate":..,"by_symbol":{"BTCUSDT":..},
+//                   "channels":{"orders":0,"recorder":12,..}}}
+// - LIVENESS_FILE ditulis ulang (atomic: tmp + rename) dengan JSON yang sama; watchdog eksternal cukup
+//   mengecek mtime file (mis. systemd / k8s exec probe: umur file < 3 × HEARTBEAT_SECS).
+//
+// ENV:
+//   HEARTBEAT_SECS=1
+//   LIVENESS_FILE=/run/dma_bot/alive.json   (kosong = nonaktif)
+//
+use ahash::AHashMap as HashMap;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tokio::select;
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::{interval, Duration, Instant, MissedTickBehavior};
+use tracing::{info, warn};
+
+use crate::channels;
+use crate::clock;
+use crate::domain::{Event, MdTick};
+use crate::md_seq;
+
+#[derive(Debug, Clone)]
+pub struct HeartbeatCfg {
+    pub interval_secs: u64,
+    pub record_notes: bool,
+    pub liveness_file: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Beat {
+    ts_ns: i128,
+    uptime_secs: u64,
+    ticks: u64,
+    tick_rate: f64,
+    by_symbol: BTreeMap<String, u64>,
+    channels: BTreeMap<String, usize>,
+}
+
+/// Tulis file liveness secara atomic (pembaca tidak pernah melihat file setengah jadi)
+async fn touch(path: &str, body: &str) -> std::io::Result<()> {
+    let tmp = format!("{path}.tmp");
+    tokio::fs::write(&tmp, body).await?;
+    tokio::fs::rename(&tmp, path).await
+}
+
+/// Loop heartbeat + record MD (berjalan sampai proses selesai)
+pub async fn run(md_tx: broadcast::Sender<MdTick>, rec_tx: mpsc::Sender<Event>, cfg: HeartbeatCfg) {
+    let mut md_rx = md_tx.subscribe();
+    let mut gaps = md_seq::GapDetector::new("recorder");
+    let mut by_symbol: HashMap<String, u64> = HashMap::new();
+    let started = Instant::now();
+    let mut last = Instant::now();
+    let mut tick = interval(Duration::from_secs(cfg.interval_secs.max(1)));
+    tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    tick.tick().await;
+
+    loop {
+        select! {
+            Ok(md) = md_rx.recv() => {
+                *by_symbol.entry(md.symbol.clone()).or_default() += 1;
+                gaps.observe(&md);
+                let _ = rec_tx.try_send(Event::Md(md));
+            },
+            _ = tick.tick() => {
+                let ticks: u64 = by_symbol.values().sum();
+                let tick_rate = ticks as f64 / last.elapsed().as_secs_f64().max(1e-3);
+                last = Instant::now();
+                info!(ticks, tick_rate = format!("{tick_rate:.1}"), "heartbeat");
+                let beat = Beat {
+                    ts_ns: clock::now_ns(),
+                    uptime_secs: started.elapsed().as_secs(),
+                    ticks,
+                    tick_rate,
+                    by_symbol: by_symbol.drain().collect(),
+                    channels: channels::depths(),
+                };
+                let json = serde_json::json!({ "heartbeat": beat }).to_string();
+                if let Some(path) = &cfg.liveness_file {
+                    if let Err(e) = touch(path, &format!("{json}\n")).await {
+                        warn!(%path, ?e, "liveness file write failed");
+                    }
+                }
+                if cfg.record_notes {
+                    let _ = rec_tx.try_send(Event::Note(json));
+                }
+            }
+        }
+    }
+}
@@ -34,38 +34,352 @@ Summary : Streams multi-symbol market data (mock/Binance), runs pluggable
 mod domain;
 mod config;
 mod metrics;
+mod metrics_server;   // server HTTP metrics/admin/dashboard (bind, basic auth / bearer, TLS)
 mod recorder;
 mod feed;
+mod mock_scenario;    // skenario harga feed mock (trend, crash, gap, ...)
 mod strategy;
 mod risk;
+mod books;            // multi-tenant book (strategi, budget risk & PnL per book)
+mod compliance;       // aturan compliance dari file (deny-list symbol, hard cap ukuran order)
+mod sizing;           // lot per symbol (LOT_SIZE) + konversi notional quote -> qty
+#[cfg(feature = "binance")]
+mod exch_format;      // format string harga/qty ke exchange (tickSize/stepSize, tanpa notasi eksponen)
+mod margin;           // estimasi margin / harga likuidasi (TRADING_MODE=margin)
 mod router;
-mod gateway;          // mock gateway (ACK -> Filled after delay)
+mod gateway;          // mock gateway (ACK -> Filled setelah latensi simulasi)
 mod posttrade;
 mod positions;
+#[cfg(feature = "binance")]
 mod binance;          // helper (signer/types) for Binance
+#[cfg(feature = "binance")]
 mod gateway_binance;  // real Binance Spot (REST + User Data Stream)
+#[cfg(feature = "binance")]
+mod binance_ws_api;   // Binance WebSocket API (order entry via WS)
+#[cfg(feature = "binance")]
+mod exchange_http;    // klien REST exchange (signing, error bertipe, retry)
+#[cfg(feature = "binance")]
+mod net;              // proxy & CA custom untuk REST/WS exchange
+mod ipc;              // mode multi-proses (feed <-> exec via Unix socket)
+mod blotter;          // fill blotter (in-memory + JSONL opsional)
+#[cfg(feature = "admin-api")]
+mod admin;            // admin API (/admin/...) di server metrics
+#[cfg(feature = "binance")]
+mod reconcile;        // rekonsiliasi blotter vs Binance myTrades
+mod account;          // polling saldo Binance + cek saldo di risk
+mod symbol_registry;  // cache exchangeInfo (status/filter) + stop trading saat BREAK/HALT
+mod venue_health;     // deteksi gangguan venue + safe mode
+mod snapshot;         // snapshot state (bincode) + warm restart
+mod exec_guard;       // buang ExecReport duplikat / out-of-order
+mod dashboard;        // dashboard HTML + SSE (/dashboard)
+mod chaos;            // chaos testing (delay/drop/duplikat/putus WS), aktif dgn --features chaos
+mod grafana;          // generator dashboard Grafana (subcommand grafana-dashboard)
+mod supervisor;       // watchdog task: restart dengan backoff + task_restarts_total
+mod clock;            // clock engine monotonic (ts_ns) + waktu event exchange
+mod kill_switch;      // panic button: cancel-all semua venue + kill switch (admin, shutdown)
+mod holding;          // batas lama memegang posisi per strategi + force-exit
+mod portfolio_stop;   // drawdown portofolio dari puncak sesi -> cancel-all + flatten + kunci trading sampai re-arm
+mod trading_day;      // pergantian hari trading (UTC / lokal bursa): rekap PnL harian + reset counter harian
+mod metric_alarms;    // alarm laju ticks / signals / rejects vs baseline EWMA -> alert metric_alarm
+mod md_anomaly;       // deteksi pola quote mencurigakan (flicker / osilasi spread) -> pause / widen
+mod md_filter;        // filter kualitas MD di feed (zero / crossed / bad print)
+mod md_seq;           // seq per symbol di MdTick + deteksi gap di consumer (md_gaps_total)
+mod quote_throttle;   // limiter churn order per (venue, symbol) + coalescing quote
+mod reconnect;        // policy backoff reconnect bersama (feed, user stream, ipc)
+mod tax_export;       // export fill ke CSV pajak/akuntansi (subcommand export-fills)
+mod rebuild;          // event sourcing: replay rekaman -> posisi & order terbuka (subcommand rebuild-state)
+mod pos_diff;         // diff posisi live / exchange / replay + alert drift (subcommand diff-positions)
+mod oms;              // timeout partial fill: cancel sisa + re-route opsional
+mod feed_health;      // registry MD terakhir per symbol; router tolak order tanpa MD
+mod venue_limits;     // batas posisi per venue (VENUE_MAX_POSITION) untuk risk & router
+mod scoreboard;       // performa per strategi (PnL window, hit rate, drawdown) + auto-disable
+mod allocator;        // capital allocator: total notional -> scale ukuran order per strategi
+mod vol_target;       // volatility targeting: skala ukuran semua strategi dari vol portofolio
+mod py_bridge;        // strategi Python via subprocess (JSON per baris, timeout per tick)
+mod script_filter;    // filter pre-trade rhai di risk (RISK_SCRIPT_FILE, hot reload)
+mod dca;              // strategi DCA: beli notional tetap sesuai jadwal cron
+#[cfg(feature = "admin-api")]
+mod webhook;          // signal manual dari webhook (alert TradingView) -> risk/router
+mod alerts;           // notifikasi event kritis via email SMTP (kill switch, recon break, laporan harian)
+#[cfg(feature = "pushgateway")]
+mod pushgateway;      // push metrics ke Prometheus Pushgateway (periodik + final saat shutdown)
+mod multileg;         // order multi-leg: view fill per leg + imbalance, remediasi legging (chase / unwind)
+mod sim_latency;      // model latensi order/fill + skew clock per venue untuk gateway mock
+mod slippage;         // model slippage per symbol (tick tetap, proporsional spread, impact akar kuadrat) di gateway mock
+mod book_analytics;   // microprice + imbalance top-k terpusat -> EnrichedTick untuk strategi
+mod conflate;         // konflasi MD per strategi (quote terbaru per interval per symbol)
+mod md_ring;          // ring buffer SPMC lock-free per symbol sebagai transport MD ke strategi (MD_BUS=ring) + benchmark
+mod bench_fanout;     // benchmark fan-out ExecReport clone vs Arc (subcommand bench-exec-fanout)
+mod channels;         // kapasitas channel (CHANNEL_CAPS) + gauge channel_depth / channel_capacity
+#[cfg(feature = "binance")]
+mod check_venue;      // subcommand check-venue: validasi key, izin, jam server, symbol & saldo sebelum start
+#[cfg(feature = "binance")]
+mod exchange_log;     // log request/response exchange ke file JSONL (EXCHANGE_LOG_FILE), rahasia disamarkan
+mod dead_letter;      // event yang tidak terkirim (channel penuh / tanpa consumer) -> JSONL (DEAD_LETTER_FILE)
+mod order_ids;        // generator cl_id ringkas (base62 snowflake) + registry cl_id <-> orderId exchange
+mod indicators;       // indikator bersama strategi (rolling mean/stdev)
+mod candles;          // agregator candle OHLC per symbol (evaluasi strategi saat bar close)
+mod effective_config; // dump konfigurasi efektif JSON (rahasia disamarkan): --print-config, GET /admin/config
+mod config_check;     // validasi konfigurasi saat startup (semua error dikumpulkan, exit 2)
+mod liveness;         // heartbeat: MD -> recorder, Note heartbeat periodik + file liveness (LIVENESS_FILE)
+mod cluster_guard;    // guard anti-overtrading: mute strategi per symbol saat burst order searah
+mod fx;               // kurs silang quote asset -> USD (notional risk & PnL portofolio USD-ekuivalen)
+mod pipeline;         // pipeline per symbol: dispatcher -> strategi & positions per symbol (PIPELINE=per_symbol)
 
 use ahash::AHashMap as HashMap;
+use clap::Parser;
+use std::sync::Arc;
 use tokio::{
     select,
     sync::{broadcast, mpsc, watch},
     time::Duration,
 };
-use tracing::info;
+use tracing::{info, warn};
 
-use crate::domain::{Event, InvSnapshot, VenueOrder};
+use crate::domain::{Event, InvSnapshot, VenueCmd, VenueOrder};
 
 #[tokio::main]
 async fn main() {
+    // ---- Subcommand utilitas (tanpa engine) ----
+    let cli = config::Cli::parse();
+    match &cli.command {
+        Some(config::Command::GrafanaDashboard) => {
+            grafana::print();
+            return;
+        }
+        Some(config::Command::ReadRecording { files, kind, symbol, since, until, print }) => {
+            let filter = recorder::reader::Filter {
+                kinds: kind.clone(),
+                symbol: symbol.clone(),
+                since_ns: *since,
+                until_ns: *until,
+            };
+            recorder::read_recording(files, filter, *print);
+            return;
+        }
+        Some(config::Command::ExportFills { source, file, out, format, since, until }) => {
+            // LOT_SIZES & file default dari env/.env
+            let (args, _) = config::load(&cli);
+            sizing::init(args.sizing.clone());
+            let default = match source {
+                tax_export::Source::Blotter => args.blotter_file.clone(),
+                tax_export::Source::Recording => args.record_file.clone(),
+            };
+            let Some(path) = file.clone().or(default) else {
+                eprintln!("export-fills: no input file (use --file, BLOTTER_FILE or RECORD_FILE)");
+                std::process::exit(2);
+            };
+            let fee_bps = router::RouterCfg::default().venues.iter().map(|(k, v)| (k.clone(), v.fee_bps)).collect();
+            tax_export::run(*source, &path, out.as_deref(), *format, (*since, *until), fee_bps);
+            return;
+        }
+        Some(config::Command::RebuildState { from, until, out, checkpoint, checkpoint_every, shard }) => {
+            let (args, _) = config::load(&cli);
+            let Some(path) = from.clone().or(args.record_file.clone()) else {
+                eprintln!("rebuild-state: no input file (use --from or RECORD_FILE)");
+                std::process::exit(2);
+            };
+            let opts = rebuild::RunOpts { shard: *shard, checkpoint: checkpoint.clone(), checkpoint_every: *checkpoint_every };
+            rebuild::run(&path, *until, out.as_deref(), &opts);
+            return;
+        }
+        Some(config::Command::MergeState { files, out }) => {
+            rebuild::run_merge(files, out.as_deref());
+            return;
+        }
+        Some(config::Command::DiffPositions { files, max_drift }) => {
+            // LOT_SIZES (konversi qty exchange) & SMTP dari env/.env
+            let (args, _) = config::load(&cli);
+            sizing::init(args.sizing.clone());
+            let max = sizing::parse_map(&max_drift.clone().or_else(|| std::env::var("POS_DRIFT_MAX").ok()).unwrap_or_default());
+            let (alerts, task) = alerts::start(args.alerts.clone());
+            let task = task.map(tokio::spawn);
+            let res = pos_diff::run(files, &max, alerts).await;
+            // tunggu email alert terkirim sebelum keluar
+            if let Some(t) = task {
+                let _ = t.await;
+            }
+            match res {
+                Ok(breach) => std::process::exit(breach as i32),
+                Err(e) => {
+                    eprintln!("diff-positions: {e}");
+                    std::process::exit(2);
+                }
+            }
+        }
+        #[cfg(feature = "binance")]
+        Some(config::Command::CheckVenue { min_quote }) => {
+            let (args, limits) = config::load(&cli);
+            net::init(net::NetCfg {
+                proxy: args.exchange_proxy.clone(),
+                ca_bundle: args.exchange_ca_bundle.clone(),
+            });
+            let ok = check_venue::run(&args, &limits, *min_quote).await;
+            std::process::exit(if ok { 0 } else { 1 });
+        }
+        Some(config::Command::BenchMdBus { ticks, consumers, symbols, capacity, rate }) => {
+            md_ring::bench(*ticks, *consumers, *symbols, *capacity, *rate).await;
+            return;
+        }
+        Some(config::Command::BenchExecFanout { reports, consumers }) => {
+            bench_fanout::bench(*reports, *consumers).await;
+            return;
+        }
+        None => {}
+    }
+
     // ---- Logging ----
     tracing_subscriber::fmt().with_env_filter("info").init();
 
     // ---- Load config & limits ----
-    let (args, limits) = config::load();
+    let (args, limits) = config::load(&cli);
+    if cli.print_config {
+        println!("{}", effective_config::render(&args, &limits));
+        return;
+    }
+    config_check::enforce(&args, &limits);
+    #[cfg(feature = "binance")]
+    net::init(net::NetCfg {
+        proxy: args.exchange_proxy.clone(),
+        ca_bundle: args.exchange_ca_bundle.clone(),
+    });
+    #[cfg(feature = "binance")]
+    if let Some(path) = args.exchange_log_file.clone() {
+        let rx = supervisor::Relay::new(exchange_log::init(), 4096);
+        supervisor::spawn("exchange_log", move || exchange_log::run(rx.attach(), path.clone()));
+    }
+    if let Some(path) = args.dead_letter_file.clone() {
+        let rx = supervisor::Relay::new(dead_letter::init(), 4096);
+        supervisor::spawn("dead_letter", move || dead_letter::run(rx.attach(), path.clone()));
+    }
+    chaos::init(args.chaos.clone());
+    reconnect::init(args.reconnect.clone());
+    md_filter::init(args.md_filter.clone());
+    fx::init(args.fx.clone());
+    sizing::init(args.sizing.clone());
+    margin::init(margin::MarginCfg {
+        enabled: limits.trading_mode == config::TradingMode::Margin,
+        leverage: limits.max_leverage,
+        maint_rate: limits.maint_margin_rate,
+        equity: limits.margin_equity,
+    });
 
-    // ---- Metrics ----
+    // ---- SOR config (dipakai router & estimasi fee blotter) ----
+    let mut cfg = router::RouterCfg::default();
+    cfg.set_symbols(&args.venue_symbols);
+    cfg.inv_bands = limits.inv_bands.clone();
+    // validasi startup: venue Binance hanya dirutekan symbol yang terdaftar & TRADING di exchangeInfo-nya
+    #[cfg(feature = "binance")]
+    if !matches!(args.venue_mode, config::MarketMode::Mock) {
+        let http = exchange_http::ExchangeHttp::new(args.binance_rest_url.clone(), String::new(), String::new(), 5000);
+        let binance: Vec<String> = cfg.venues.keys().filter(|v| is_binance_venue(v)).cloned().collect();
+        if !binance.is_empty() {
+            match symbol_registry::tradable_symbols(&http, &args.symbols).await {
+                Ok(listed) => binance.iter().for_each(|v| cfg.restrict_to_listed(v, &listed)),
+                Err(e) => tracing::warn!(err = %e, "venue symbol validation: exchangeInfo failed, whitelists unchanged"),
+            }
+        }
+    }
+
+    // ---- Blotter ----
+    let blotter = blotter::Blotter::new(
+        cfg.venues.iter().map(|(k, v)| (k.clone(), v.fee_bps)).collect(),
+        args.blotter_max_fills,
+        args.blotter_file.clone(),
+    );
+
+    // ---- Snapshot / warm restart ----
+    let snaps = snapshot::Snapshots::new(
+        args.snapshot_file.as_deref(),
+        args.warm_start,
+        args.snapshot_max_age_secs,
+    );
+    blotter.restore_working_orders(snaps.take_working_orders());
+
+    // ---- Batas posisi per venue (risk + router), dibangun dari fill di fan-out ----
+    let venue_pos = venue_limits::VenuePositions::new(limits.venue_max_position.clone(), cfg.venues.keys().cloned().collect());
+    for sym in &args.symbols {
+        for (venue, qty) in snaps.peek_venue_positions(sym) {
+            venue_pos.seed(&venue, sym, qty);
+        }
+    }
+    // order child terbuka (gauge open_orders / working_notional), diisi router/OMS + fan-out
+    let open_orders = oms::WorkingOrders::default();
+    if let Some(path) = args.snapshot_file.clone() {
+        let (snaps, blotter, secs) = (snaps.clone(), blotter.clone(), args.snapshot_interval_secs);
+        supervisor::spawn("snapshot_writer", move || snapshot::run_writer(snaps.clone(), blotter.clone(), path.clone(), secs));
+    }
+
+    // ---- Symbol registry (exchangeInfo publik; hanya jika feed/venue Binance) ----
+    let symbols = symbol_registry::SymbolRegistry::default();
+    // posisi terbaru per symbol untuk umpan balik ke strategi (diisi task positions)
+    let position_feed = positions::PositionFeed::default();
+    // anomali pola quote per symbol (MD_ANOMALY): dibaca risk (pause) & worker strategi (widen)
+    let md_anomaly = md_anomaly::MdAnomaly::new(args.md_anomaly.clone());
+    // umur posisi per (strategi, symbol) untuk HOLD_MAX_SECS (risk + task force-exit)
+    let holding = holding::HoldingGuard::new(args.holding.clone());
+    // stop drawdown portofolio (risk: kunci trading; admin: status / re-arm)
+    let portfolio_stop = portfolio_stop::PortfolioStop::new(args.portfolio_stop.clone());
+    #[cfg(feature = "binance")]
+    if (!matches!(args.feed_mode, config::MarketMode::Mock) || !matches!(args.venue_mode, config::MarketMode::Mock)) && args.symbol_refresh_secs > 0 {
+        let http = exchange_http::ExchangeHttp::new(args.binance_rest_url.clone(), String::new(), String::new(), 5000);
+        let (syms, secs, registry) = (args.symbols.clone(), args.symbol_refresh_secs, symbols.clone());
+        supervisor::spawn("symbol_registry", move || symbol_registry::run(http.clone(), syms.clone(), secs, registry.clone()));
+    }
+
+    // ---- Metrics (+ admin API & dashboard) ----
+    metrics::configure_labels(args.metric_labels.clone(), &args.symbols);
+    sim_latency::init(&args.sim_latency);
     metrics::init();
-    tokio::spawn(metrics::serve_metrics(args.metrics_port));
+    let dash = dashboard::DashState::new(blotter.clone());
+    let (alerts, alert_task) = alerts::start(args.alerts.clone());
+    if let Some(task) = alert_task {
+        tokio::spawn(task);
+    }
+    let kill = kill_switch::KillSwitch::with_alerts(alerts.clone());
+    let board = scoreboard::Scoreboard::new(args.scoreboard.clone());
+    if let (true, Some(hour)) = (args.alerts.enabled(), args.alerts.daily_report_hour) {
+        let (a, b, sb) = (alerts.clone(), blotter.clone(), board.clone());
+        supervisor::spawn("daily_report", move || alerts::run_daily_report(a.clone(), hour, b.clone(), sb.clone()));
+    }
+    if args.metric_alarms.enabled() {
+        let (cfg, a) = (args.metric_alarms.clone(), alerts.clone());
+        supervisor::spawn("metric_alarms", move || metric_alarms::run(cfg.clone(), a.clone()));
+    }
+    // kapasitas channel (CHANNEL_CAPS) + gauge kedalaman antrean
+    let caps = args.channel_caps.clone();
+    supervisor::spawn("channel_depth", channels::run);
+    // bus Signal dibuat di sini agar webhook (server admin) bisa menyuntik Signal
+    let (sig_tx, sig_rx) = mpsc::channel::<domain::Signal>(caps.get("signals", 2048));
+    // order multi-leg: admin/strategi -> risk -> router (LegGroup)
+    let (mleg_tx, mleg_rx) = mpsc::channel::<domain::MultiLegOrder>(caps.get("multileg", 256));
+    let (leg_tx, leg_rx) = mpsc::channel::<domain::LegGroup>(caps.get("legs", 256));
+    channels::watch_mpsc("signals", &sig_tx);
+    channels::watch_mpsc("multileg", &mleg_tx);
+    channels::watch_mpsc("legs", &leg_tx);
+    let legs = multileg::LegBook::new(args.mleg_hedge.clone());
+    #[cfg(feature = "admin-api")]
+    {
+        let webhook = args.webhook.enabled().then(|| webhook::Webhook::new(args.webhook.clone(), sig_tx.clone()));
+        let admin_state = admin::AdminState {
+            blotter: blotter.clone(),
+            dashboard: dash.clone(),
+            symbols: symbols.clone(),
+            kill: kill.clone(),
+            portfolio_stop: portfolio_stop.clone(),
+            scoreboard: board.clone(),
+            webhook,
+            legs: legs.clone(),
+            mleg_tx,
+            config: Arc::new(effective_config::render(&args, &limits)),
+        };
+        tokio::spawn(metrics_server::serve_metrics(args.metrics_server.clone(), admin_state));
+    }
+    #[cfg(feature = "pushgateway")]
+    if args.push.enabled() {
+        let cfg = args.push.clone();
+        supervisor::spawn("pushgateway", move || pushgateway::run(cfg.clone()));
+    }
 
     // ---- Human-friendly startup info + export config to metrics ----
     let feed_mode_str = match args.feed_mode {
@@ -78,15 +392,7 @@ async fn main() {
         config::MarketMode::BinanceSandbox => "binance_sandbox",
         config::MarketMode::BinanceMainnet => "binance_mainnet",
     };
-    let strategy_names: Vec<&'static str> = args
-        .strategy_modes
-        .iter()
-        .map(|m| match m {
-            config::StrategyMode::MeanReversion => "mean_reversion",
-            config::StrategyMode::MACrossover => "ma_crossover",
-            config::StrategyMode::VolBreakout => "vol_breakout",
-        })
-        .collect();
+    let strategy_names: Vec<&'static str> = args.strategy_modes.iter().map(|m| m.as_str()).collect();
 
     info!(
         feed_mode = %feed_mode_str,
@@ -109,90 +415,356 @@ async fn main() {
         crate::metrics::CONFIG_SYMBOL.with_label_values(&[s]).set(1);
     }
     for m in &args.strategy_modes {
-        let label = match m {
-            config::StrategyMode::MeanReversion => "mean_reversion",
-            config::StrategyMode::MACrossover => "ma_crossover",
-            config::StrategyMode::VolBreakout => "vol_breakout",
-        };
         crate::metrics::CONFIG_STRATEGY_ACTIVE
-            .with_label_values(&[label])
+            .with_label_values(&[m.as_str()])
             .set(args.strategy_workers as i64);
     }
 
     // ---- Buses ----
-    let (md_tx, _md_rx) = broadcast::channel::<domain::MdTick>(4096);
-    let (sig_tx, sig_rx) = mpsc::channel::<domain::Signal>(2048);
-    let (ord_tx, ord_rx) = mpsc::channel::<domain::Order>(2048);
-
-    // Fan-out ExecReport: gateway -> central -> (posttrade, positions dispatcher)
-    let (exec_central_tx, exec_central_rx) = mpsc::channel::<domain::ExecReport>(4096);
-    let (exec_to_post_tx, exec_to_post_rx) = mpsc::channel::<domain::ExecReport>(4096);
-    let (exec_to_pos_tx, exec_to_pos_rx) = mpsc::channel::<domain::ExecReport>(4096);
+    let (md_tx, _md_rx) = broadcast::channel::<domain::MdTick>(caps.get("md", 4096));
+    let (book_tx, _book_rx) = broadcast::channel::<domain::BookUpdate>(caps.get("book", 4096));
+    let (ord_tx, ord_rx) = mpsc::channel::<domain::Order>(caps.get("orders", 2048));
+    channels::watch_broadcast("md", &md_tx, caps.get("md", 4096));
+    channels::watch_broadcast("book", &book_tx, caps.get("book", 4096));
+    channels::watch_mpsc("orders", &ord_tx);
+    // detektor anomali quote (feed & exec: strategi butuh pengali widen, risk butuh status pause)
+    if args.md_anomaly.enabled() {
+        let (md, a) = (md_tx.clone(), md_anomaly.clone());
+        supervisor::spawn("md_anomaly", move || md_anomaly::run(md.subscribe(), a.clone()));
+    }
+
+    // ---- Recorder (optional) ----
+    let (rec_tx, rec_rx) = mpsc::channel::<Event>(caps.get("recorder", 8192));
+    let heartbeat_cfg = liveness::HeartbeatCfg {
+        interval_secs: args.heartbeat_secs,
+        record_notes: args.record_file.is_some(),
+        liveness_file: args.liveness_file.clone(),
+    };
+    channels::watch_mpsc("recorder", &rec_tx);
+    if let Some(path) = args.record_file.clone() {
+        let rx = supervisor::Relay::new(rec_rx, caps.get("recorder", 8192));
+        channels::watch_relay("recorder", &rx);
+        let cfg = args.record.clone();
+        supervisor::spawn("recorder", move || recorder::run(rx.attach(), path.clone(), cfg.clone()));
+    }
+
+    // Fan-out ExecReport: gateway -> central (guard duplikat/out-of-order) -> (posttrade, positions dispatcher, blotter, dashboard, risk, recorder)
+    let (exec_central_tx, exec_central_rx) = mpsc::channel::<domain::ExecReport>(caps.get("exec", 4096));
+    channels::watch_mpsc("exec", &exec_central_tx);
+    let fanout_cap = caps.get("exec_fanout", 4096);
+    let (exec_to_post_tx, exec_to_post_rx) = mpsc::channel::<Arc<domain::ExecReport>>(fanout_cap);
+    channels::watch_mpsc("exec_fanout:post", &exec_to_post_tx);
+    let (exec_to_pos_tx, exec_to_pos_rx) = mpsc::channel::<Arc<domain::ExecReport>>(fanout_cap);
+    channels::watch_mpsc("exec_fanout:pos", &exec_to_pos_tx);
+    let (exec_to_blotter_tx, exec_to_blotter_rx) = mpsc::channel::<Arc<domain::ExecReport>>(fanout_cap);
+    channels::watch_mpsc("exec_fanout:blotter", &exec_to_blotter_tx);
+    let (exec_to_dash_tx, exec_to_dash_rx) = mpsc::channel::<Arc<domain::ExecReport>>(fanout_cap);
+    channels::watch_mpsc("exec_fanout:dash", &exec_to_dash_tx);
+    let (exec_to_risk_tx, exec_to_risk_rx) = mpsc::channel::<Arc<domain::ExecReport>>(fanout_cap);
+    channels::watch_mpsc("exec_fanout:risk", &exec_to_risk_tx);
+    let exec_rec_tx = args.record_file.is_some().then(|| rec_tx.clone());
+    let exec_venue_pos = venue_pos.clone();
+    let exec_open = open_orders.clone();
+    let exec_legs = legs.clone();
+    let (exec_to_oms_tx, exec_to_oms_rx) = mpsc::channel::<Arc<domain::ExecReport>>(fanout_cap);
+    channels::watch_mpsc("exec_fanout:oms", &exec_to_oms_tx);
+    let exec_oms_tx = args.oms.enabled().then_some(exec_to_oms_tx);
+    let (exec_to_board_tx, exec_to_board_rx) = mpsc::channel::<Arc<domain::ExecReport>>(fanout_cap);
+    channels::watch_mpsc("exec_fanout:board", &exec_to_board_tx);
     tokio::spawn(async move {
         let mut rx = exec_central_rx;
+        let mut guard = exec_guard::ExecGuard::new(100_000, 100_000);
         while let Some(er) = rx.recv().await {
-            let _ = exec_to_post_tx.send(er.clone()).await;
-            let _ = exec_to_pos_tx.send(er).await;
+            // chaos (no-op tanpa --features chaos): delay / drop / duplikat sebelum guard
+            chaos::delay(chaos::Point::Exec).await;
+            if chaos::drop(chaos::Point::Exec) {
+                continue;
+            }
+            let copies = if chaos::duplicate(chaos::Point::Exec) { 2 } else { 1 };
+            // satu alokasi per report; consumer berbagi Arc (bukan clone String per consumer)
+            let er = Arc::new(er);
+            for _ in 0..copies {
+                if !guard.accept(&er) {
+                    continue;
+                }
+                exec_venue_pos.on_report(&er);
+                exec_open.on_report(&er);
+                exec_legs.on_report(&er);
+                // consumer yang memegang state (posisi, risk, blotter, scoreboard, OMS): send blocking, jadi
+                // consumer lambat menahan fan-out (dan consumer lain) sampai antreannya longgar lagi
+                let critical = [
+                    ("blotter", &exec_to_blotter_tx),
+                    ("positions", &exec_to_pos_tx),
+                    ("risk", &exec_to_risk_tx),
+                    ("scoreboard", &exec_to_board_tx),
+                ];
+                for (name, tx) in critical.into_iter().chain(exec_oms_tx.as_ref().map(|tx| ("oms", tx))) {
+                    if tx.send(er.clone()).await.is_err() {
+                        dead_letter::record("exec", name, "closed", &*er);
+                    }
+                }
+                // tampilan turunan (TCA, dashboard): try_send, antrean penuh -> report dilewati & dihitung
+                for (name, tx) in [("posttrade", &exec_to_post_tx), ("dashboard", &exec_to_dash_tx)] {
+                    match tx.try_send(er.clone()) {
+                        Ok(()) => {}
+                        Err(mpsc::error::TrySendError::Full(_)) => metrics::EXEC_FANOUT_DROPPED.with_label_values(&[name]).inc(),
+                        Err(mpsc::error::TrySendError::Closed(_)) => dead_letter::record("exec", name, "closed", &*er),
+                    }
+                }
+                if let Some(tx) = &exec_rec_tx {
+                    if let Err(e) = tx.try_send(Event::Exec((*er).clone())) {
+                        let reason = if matches!(e, mpsc::error::TrySendError::Full(_)) { "full" } else { "closed" };
+                        dead_letter::record("exec", "recorder", reason, &*er);
+                    }
+                }
+            }
         }
     });
+    {
+        let (rx, b) = (supervisor::Relay::new(exec_to_blotter_rx, fanout_cap), blotter.clone());
+        channels::watch_relay("exec_fanout:blotter", &rx);
+        supervisor::spawn("blotter", move || blotter::run(rx.attach(), b.clone()));
+        let (rx, d, md) = (supervisor::Relay::new(exec_to_dash_rx, fanout_cap), dash.clone(), md_tx.clone());
+        channels::watch_relay("exec_fanout:dash", &rx);
+        supervisor::spawn("dashboard", move || dashboard::run(d.clone(), md.subscribe(), rx.attach()));
+        let (rx, b, md) = (supervisor::Relay::new(exec_to_board_rx, fanout_cap), board.clone(), md_tx.clone());
+        channels::watch_relay("exec_fanout:board", &rx);
+        supervisor::spawn("scoreboard", move || scoreboard::run(rx.attach(), md.subscribe(), b.clone()));
+    }
+    if args.alloc.enabled() {
+        let strategies: Vec<String> = args.strategy_modes.iter().map(|m| m.as_str().to_string()).collect();
+        let (cfg, b) = (args.alloc.clone(), board.clone());
+        supervisor::spawn("allocator", move || allocator::run(cfg.clone(), strategies.clone(), b.clone()));
+    }
+    if args.vol_target.enabled() {
+        let (cfg, md, p) = (args.vol_target.clone(), md_tx.clone(), position_feed.clone());
+        supervisor::spawn("vol_target", move || vol_target::run(cfg.clone(), md.subscribe(), p.clone()));
+    }
 
-    // ---- Recorder (optional) ----
-    let (rec_tx, rec_rx) = mpsc::channel::<Event>(8192);
-    if let Some(path) = args.record_file.clone() {
-        tokio::spawn(recorder::run(rec_rx, path));
+    // ---- Mode proses ----
+    //   PROCESS_ROLE=all  : semua komponen dalam satu proses (default)
+    //   PROCESS_ROLE=feed : feed + strategi, Signal/MdTick dikirim via IPC
+    //   PROCESS_ROLE=exec : risk + router + gateway + positions, input dari IPC
+    let role = args.process_role.clone();
+    info!(role = ?role, ipc_socket = %args.ipc_socket, "process role");
+    if !role.runs_feed() {
+        let (sock, md, sig) = (args.ipc_socket.clone(), md_tx.clone(), sig_tx.clone());
+        supervisor::spawn("ipc_exec_client", move || ipc::run_exec_client(sock.clone(), md.clone(), sig.clone()));
     }
 
     // ---- FEED (Market Data) ----
+    // MD_BUS=ring: ring SPMC per symbol untuk worker strategi, ditulis langsung oleh task feed
+    let md_ring = (role.runs_feed() && !args.strategy_modes.is_empty() && args.md_bus.kind == md_ring::BusKind::Ring)
+        .then(|| md_ring::MdRing::new(&args.symbols, args.md_bus.capacity));
     // Multi-symbol feed: args.symbols (fallback ke args.symbol jika SYMBOLS kosong)
-    match args.feed_mode {
-        config::MarketMode::Mock => {
-            for sym in args.symbols.iter().cloned() {
-                let tx = md_tx.clone();
-                tokio::spawn(async move {
-                    feed::run_mock(tx, sym).await;
-                });
+    if role.runs_feed() {
+        match args.feed_mode {
+            config::MarketMode::Mock => {
+                info!(scenario = args.mock_scenario.as_str(), book_levels = args.mock_book_levels, "mock feed");
+                for sym in &args.symbols {
+                    let sym = sym.clone();
+                    let tx = md_tx.clone();
+                    let book_tx = book_tx.clone();
+                    let ring = md_ring.clone();
+                    let scenario = args.mock_scenario;
+                    let levels = args.mock_book_levels;
+                    supervisor::spawn(format!("feed:{sym}"), move || {
+                        feed::run_mock(tx.clone(), book_tx.clone(), ring.clone(), sym.clone(), scenario, levels)
+                    });
+                }
             }
-        }
-        config::MarketMode::BinanceSandbox | config::MarketMode::BinanceMainnet => {
-            for sym in args.symbols.iter().cloned() {
-                let tx = md_tx.clone();
-                let base = args.binance_ws_url.clone();
-                tokio::spawn(async move {
-                    feed::run_binance(tx, sym, base).await;
-                });
+            // tanpa fitur `binance` mode ini sudah ditolak config_check
+            config::MarketMode::BinanceSandbox | config::MarketMode::BinanceMainnet => {
+                #[cfg(feature = "binance")]
+                for sym in &args.symbols {
+                    let sym = sym.clone();
+                    let tx = md_tx.clone();
+                    let (base, ring) = (args.binance_ws_url.clone(), md_ring.clone());
+                    supervisor::spawn(format!("feed:{sym}"), move || {
+                        feed::run_binance(tx.clone(), ring.clone(), sym.clone(), base.clone())
+                    });
+                }
             }
-        }
-    };
+        };
+    }
 
     // ---- Strategy workers ----
     // Pilih via ENV:
-    //   STRATEGY=mean_reversion|ma_crossover|vol_breakout  (single)
+    //   STRATEGY=mean_reversion|ma_crossover|vol_breakout|bollinger|donchian|python  (single)
     //   atau STRATEGIES=mean_reversion,ma_crossover        (multi)
     //   STRATEGY_WORKERS=N                                 (default 2)
-    for mode in &args.strategy_modes {
-        for _ in 0..args.strategy_workers {
-            let rx = md_tx.subscribe();
+    // (proses exec-only tidak menjalankan strategi)
+    let strategy_modes: &[config::StrategyMode] = if role.runs_feed() { &args.strategy_modes } else { &[] };
+    // analitik book (microprice / imbalance top-k) terpusat jika feed membawa depth
+    let ticks = if let Some(ring) = &md_ring {
+        info!(capacity = args.md_bus.capacity, "md bus: ring");
+        book_analytics::TickSrc::Ring(ring.clone())
+    } else if !strategy_modes.is_empty() && args.mock_book_levels > 0 && matches!(args.feed_mode, config::MarketMode::Mock) {
+        let (tx, _) = broadcast::channel::<domain::EnrichedTick>(caps.get("enriched", 4096));
+        channels::watch_broadcast("enriched", &tx, caps.get("enriched", 4096));
+        let (md, book, out, k) = (md_tx.clone(), book_tx.clone(), tx.clone(), book_analytics::levels_from_env());
+        supervisor::spawn("book_analytics", move || book_analytics::run(md.subscribe(), book.subscribe(), out.clone(), k));
+        book_analytics::TickSrc::Enriched(tx)
+    } else {
+        book_analytics::TickSrc::Raw(md_tx.clone())
+    };
+    // PIPELINE=per_symbol: satu pipeline (dan worker strategi sendiri) per symbol
+    let mut pipes = args.pipeline.per_symbol.then(|| pipeline::Pipelines::new(&args.symbols, args.pipeline.capacity));
+    let worker_symbols: Vec<Option<String>> = match &pipes {
+        Some(_) => args.symbols.iter().cloned().map(Some).collect(),
+        None => vec![None],
+    };
+    for (mode, symbol) in strategy_modes.iter().flat_map(|m| worker_symbols.iter().map(move |s| (m, s))) {
+        for worker in 0..args.strategy_workers {
+            let md = match (&mut pipes, symbol) {
+                (Some(p), Some(sym)) => p.strategy_src(sym),
+                _ => ticks.clone(),
+            };
             let sig = sig_tx.clone();
+            let ctx = strategy::WorkerCtx {
+                strategy: mode.as_str(),
+                worker,
+                tags: args.strategy_tags.clone(),
+                book: args.books.book_for(mode.as_str()),
+                quote_notional: args.strategy_quote_notional.get(mode.as_str()).copied(),
+                max_tps: args.strategy_max_tps.get(mode.as_str()).copied(),
+                bar_secs: args.strategy_bar_secs.get(mode.as_str()).copied(),
+                symbol: symbol.clone(),
+                snaps: snaps.clone(),
+                symbols: symbols.clone(),
+                positions: position_feed.clone(),
+                anomaly: md_anomaly.clone(),
+            };
+            let name = format!("strategy:{}", ctx.key());
             match mode {
                 config::StrategyMode::MeanReversion => {
-                    tokio::spawn(strategy::run(rx, sig));
+                    supervisor::spawn(name, move || strategy::run(md.subscribe(), sig.clone(), ctx.clone()));
                 }
                 config::StrategyMode::MACrossover => {
-                    tokio::spawn(strategy::run_ma_crossover(rx, sig));
+                    supervisor::spawn(name, move || strategy::run_ma_crossover(md.subscribe(), sig.clone(), ctx.clone()));
                 }
                 config::StrategyMode::VolBreakout => {
-                    tokio::spawn(strategy::run_vol_breakout(rx, sig));
+                    supervisor::spawn(name, move || strategy::run_vol_breakout(md.subscribe(), sig.clone(), ctx.clone()));
+                }
+                config::StrategyMode::Bollinger => {
+                    let cfg = args.bollinger.clone();
+                    supervisor::spawn(name, move || strategy::run_bollinger(md.subscribe(), sig.clone(), ctx.clone(), cfg.clone()));
+                }
+                config::StrategyMode::Donchian => {
+                    let cfg = args.donchian.clone();
+                    supervisor::spawn(name, move || strategy::run_donchian(md.subscribe(), sig.clone(), ctx.clone(), cfg.clone()));
+                }
+                config::StrategyMode::Python => {
+                    let cfg = args.py_bridge.clone();
+                    supervisor::spawn(name, move || py_bridge::run(md.subscribe(), sig.clone(), ctx.clone(), cfg.clone()));
                 }
             }
         }
     }
 
+    // DCA terjadwal (berbasis waktu, bukan tick) -> bus Signal yang sama
+    if role.runs_feed() && args.dca.enabled() {
+        let (cfg, book, md, sig) = (args.dca.clone(), args.books.book_for(&args.dca.strategy), md_tx.clone(), sig_tx.clone());
+        supervisor::spawn("dca", move || dca::run(cfg.clone(), book.clone(), md.subscribe(), sig.clone()));
+    }
+
+    if let Some(p) = &pipes {
+        info!(symbols = args.symbols.len(), capacity = args.pipeline.capacity, "per-symbol pipelines");
+        p.spawn(ticks.clone());
+    }
+
+    // Proses feed-only: serahkan Signal & MdTick ke proses exec, lalu heartbeat saja
+    if !role.runs_exec() {
+        let (sock, md, rx) = (args.ipc_socket.clone(), md_tx.clone(), supervisor::Relay::new(sig_rx, caps.get("signals", 2048)));
+        supervisor::spawn("ipc_serve_feed", move || ipc::serve_feed(sock.clone(), md.subscribe(), rx.attach()));
+        liveness::run(md_tx, rec_tx, heartbeat_cfg).await;
+        return;
+    }
+
     // ---- Risk ----
-    tokio::spawn(risk::run(sig_rx, ord_tx.clone(), limits));
+    // ---- Klien REST Binance bersama (rekonsiliasi, saldo akun) ----
+    #[cfg(feature = "binance")]
+    let binance_http = if matches!(args.venue_mode, config::MarketMode::Mock) {
+        None
+    } else {
+        match (std::env::var("BINANCE_API_KEY"), std::env::var("BINANCE_API_SECRET")) {
+            (Ok(api_key), Ok(api_secret)) => {
+                let recv_window = std::env::var("BINANCE_RECV_WINDOW")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(5000);
+                Some(exchange_http::ExchangeHttp::new(
+                    args.binance_rest_url.clone(),
+                    api_key,
+                    api_secret,
+                    recv_window,
+                ))
+            }
+            _ => {
+                tracing::warn!("reconciliation & account polling disabled: BINANCE_API_KEY/SECRET missing");
+                None
+            }
+        }
+    };
 
-    // ---- SOR Multi-Venue ----
-    let cfg = router::RouterCfg::default();
+    // ---- Saldo akun: polling + cek saldo di risk ----
+    #[cfg(feature = "binance")]
+    let balances = (binance_http.is_some() && args.account_poll_secs > 0).then(account::Balances::default);
+    #[cfg(not(feature = "binance"))]
+    let balances: Option<account::Balances> = None;
+    #[cfg(feature = "binance")]
+    if let (Some(http), Some(b)) = (&binance_http, &balances) {
+        let (http, secs, b) = (http.clone(), args.account_poll_secs, b.clone());
+        supervisor::spawn("account", move || account::run(http.clone(), secs, b.clone()));
+    }
+
+    // posisi awal (warm start) untuk cek spot/margin; dibaca sebelum positions mengambil snapshot
+    let initial_net: HashMap<String, i64> = args
+        .symbols
+        .iter()
+        .filter_map(|s| snaps.peek_position_qty(s).map(|q| (s.clone(), q)))
+        .collect();
+    let risk_ctx = risk::RiskCtx {
+        limits,
+        balances: balances.clone(),
+        initial_net,
+        symbols: symbols.clone(),
+        compliance: args.compliance_file.as_deref().map(compliance::ComplianceRules::load),
+        audit_tx: args.record_file.is_some().then(|| rec_tx.clone()),
+        books: args.books.clone(),
+        kill: kill.clone(),
+        portfolio_stop: portfolio_stop.clone(),
+        venue_pos: venue_pos.clone(),
+        scoreboard: board.clone(),
+        cluster: cluster_guard::ClusterGuard::new(args.cluster.clone(), alerts.clone()),
+        md_anomaly: md_anomaly.clone(),
+        holding: holding.clone(),
+        script: args.risk_script_file.clone().map(script_filter::ScriptFilter::new),
+        legs: legs.clone(),
+        leg_tx,
+        clock: clock::engine(),
+    };
+    if let Some(script) = risk_ctx.script.clone() {
+        let secs = args.risk_script_reload_secs;
+        supervisor::spawn("risk_script", move || script_filter::run_reloader(script.clone(), secs));
+    }
+    {
+        let (sig_rx, exec_rx) = (supervisor::Relay::new(sig_rx, caps.get("signals", 2048)), supervisor::Relay::new(exec_to_risk_rx, fanout_cap));
+        let mleg_rx = supervisor::Relay::new(mleg_rx, caps.get("multileg", 256));
+        channels::watch_relay("signals", &sig_rx);
+        channels::watch_relay("exec_fanout:risk", &exec_rx);
+        channels::watch_relay("multileg", &mleg_rx);
+        let ord_tx = ord_tx.clone();
+        let mut ctx = risk_ctx;
+        supervisor::spawn("risk", move || {
+            let run_ctx = ctx.clone();
+            // posisi warm start hanya untuk start pertama (setelah restart sudah basi)
+            ctx.initial_net.clear();
+            risk::run(sig_rx.attach(), mleg_rx.attach(), exec_rx.attach(), ord_tx.clone(), run_ctx)
+        });
+    }
 
+    // ---- SOR Multi-Venue ----
     // Salin parameter venue agar 'static
     let venue_params: Vec<(String, u32)> = cfg
         .venues
@@ -200,59 +772,118 @@ async fn main() {
         .map(|(name, vcfg)| (name.clone(), vcfg.est_latency_ms))
         .collect();
 
-    // Buat gateway per-venue
+    // Registry kesehatan venue (safe mode)
+    let venue_health = venue_health::VenueHealth::default();
+
+    // Buat gateway per-venue (+ channel perintah kontrol)
     let mut gw_txs: HashMap<String, mpsc::Sender<VenueOrder>> = HashMap::new();
+    let mut venue_cmd_txs: HashMap<String, mpsc::Sender<VenueCmd>> = HashMap::new();
+    let limiter = quote_throttle::ChurnLimiter::new(args.quote_throttle.clone());
     for (venue_name, est_latency_ms) in venue_params {
-        let (tx, rx) = mpsc::channel::<VenueOrder>(1024);
-        gw_txs.insert(venue_name.clone(), tx);
+        let (ord_cap, cmd_cap) = (caps.get("venue_orders", 1024), caps.get("venue_cmds", 16));
+        let (tx, rx) = mpsc::channel::<VenueOrder>(ord_cap);
+        let (cmd_tx, cmd_rx) = mpsc::channel::<VenueCmd>(cmd_cap);
+        let (ord_name, cmd_name) = (format!("venue_orders:{venue_name}"), format!("venue_cmds:{venue_name}"));
+        channels::watch_mpsc(&ord_name, &tx);
+        channels::watch_mpsc(&cmd_name, &cmd_tx);
+        if limiter.enabled(&venue_name) {
+            // router -> throttle -> gateway
+            let (thr_tx, thr_rx) = mpsc::channel::<VenueOrder>(ord_cap);
+            channels::watch_mpsc(&ord_name, &thr_tx);
+            gw_txs.insert(venue_name.clone(), thr_tx);
+            let (thr_rx, venue, exec_tx, limiter) =
+                (supervisor::Relay::new(thr_rx, ord_cap), venue_name.clone(), exec_central_tx.clone(), limiter.clone());
+            channels::watch_relay(&ord_name, &thr_rx);
+            supervisor::spawn(format!("quote_throttle:{venue_name}"), move || {
+                quote_throttle::run(venue.clone(), thr_rx.attach(), tx.clone(), exec_tx.clone(), limiter.clone())
+            });
+        } else {
+            gw_txs.insert(venue_name.clone(), tx);
+        }
+        kill.register_venue(&venue_name, cmd_tx.clone());
+        venue_cmd_txs.insert(venue_name.clone(), cmd_tx);
         let exec_tx = exec_central_tx.clone();
+        let health = venue_health.clone();
+        // depth hanya ada dari feed mock di proses yang sama
+        let book_src = (args.mock_book_levels > 0 && role.runs_feed() && matches!(args.feed_mode, config::MarketMode::Mock))
+            .then(|| book_tx.clone());
+        // top-of-book MD: order limit resting sampai harga feed menembusnya
+        let md_src = args.mock_resting.then(|| md_tx.clone());
+        let (rx, cmd_rx) = (supervisor::Relay::new(rx, ord_cap), supervisor::Relay::new(cmd_rx, cmd_cap));
+        channels::watch_relay(&ord_name, &rx);
+        channels::watch_relay(&cmd_name, &cmd_rx);
 
-        let venue_mode = args.venue_mode.clone();
-        let rest_base = args.binance_rest_url.clone();
+        // Sandbox/Mainnet: venue "binance"/"binance_testnet" pakai gateway_binance, lainnya mock
+        let real_binance = !matches!(args.venue_mode, config::MarketMode::Mock) && is_binance_venue(&venue_name);
+        if real_binance {
+            // pass REST base ke gateway_binance via ENV (dipakai internal)
+            std::env::set_var("BINANCE_REST_URL", args.binance_rest_url.clone());
+        }
 
-        tokio::spawn({
-            let venue_name_spawn = venue_name.clone();
+        let (venue, symbols, limiter) = (venue_name.clone(), args.symbols.clone(), limiter.clone());
+        let balances = balances.clone();
+        let (sim_latency, slippage) = (args.sim_latency.clone(), args.slippage.clone());
+        supervisor::spawn(format!("gateway:{venue_name}"), move || {
+            let (rx, cmd_rx, exec_tx, venue) = (rx.attach(), cmd_rx.attach(), exec_tx.clone(), venue.clone());
+            let (symbols, limiter) = (symbols.clone(), limiter.clone());
+            let book_rx = book_src.as_ref().map(|tx| tx.subscribe());
+            let md_rx = md_src.as_ref().map(|tx| tx.subscribe());
+            let (health, balances) = (health.clone(), balances.clone());
+            let (lat, slippage) = (sim_latency.venue(&venue, est_latency_ms as u64), slippage.clone());
             async move {
-                match venue_mode {
-                    // Semua venue mock
-                    config::MarketMode::Mock => {
-                        crate::gateway::run_venue(
-                            rx,
-                            exec_tx,
-                            venue_name_spawn,
-                            est_latency_ms as u64,
-                        )
-                        .await;
-                    }
-                    // Sandbox/Mainnet: venue "binance"/"binance_testnet" pakai gateway_binance, lainnya mock
-                    config::MarketMode::BinanceSandbox | config::MarketMode::BinanceMainnet => {
-                        match venue_name_spawn.to_ascii_lowercase().as_str() {
-                            "binance" | "binance_testnet" => {
-                                // pass REST base ke gateway_binance via ENV (dipakai internal)
-                                std::env::set_var("BINANCE_REST_URL", rest_base.clone());
-                                crate::gateway_binance::run_venue_binance(
-                                    rx,
-                                    exec_tx,
-                                    venue_name_spawn,
-                                )
-                                .await;
-                            }
-                            _ => {
-                                crate::gateway::run_venue(
-                                    rx,
-                                    exec_tx,
-                                    venue_name_spawn,
-                                    est_latency_ms as u64,
-                                )
-                                .await;
-                            }
-                        }
-                    }
+                if real_binance {
+                    #[cfg(feature = "binance")]
+                    crate::gateway_binance::run_venue_binance(rx, cmd_rx, exec_tx, venue, health, symbols, limiter, balances, clock::engine()).await;
+                    // tidak tercapai tanpa fitur `binance` (VENUE_MODE selain mock ditolak config_check)
+                    #[cfg(not(feature = "binance"))]
+                    let _ = (health, symbols, limiter, balances);
+                } else {
+                    crate::gateway::run_venue(rx, cmd_rx, exec_tx, venue, lat, slippage, book_rx, md_rx, clock::engine()).await;
                 }
             }
         });
     }
 
+    // ---- OMS: timeout partial fill (cancel sisa + re-route opsional) ----
+    if args.oms.enabled() {
+        let rx = supervisor::Relay::new(exec_to_oms_rx, fanout_cap);
+        channels::watch_relay("exec_fanout:oms", &rx);
+        let (md, cmds, ord_tx, kill, cfg) = (md_tx.clone(), venue_cmd_txs.clone(), ord_tx.clone(), kill.clone(), args.oms.clone());
+        let (open, stop) = (open_orders.clone(), portfolio_stop.clone());
+        supervisor::spawn("oms", move || {
+            oms::run(rx.attach(), md.subscribe(), cmds.clone(), ord_tx.clone(), kill.clone(), stop.clone(), cfg.clone(), open.clone())
+        });
+    }
+
+    // ---- Remediasi legging multi-leg (chase / unwind) ----
+    if args.mleg_hedge.enabled() {
+        let (l, md, cmds, ord_tx, kill) = (legs.clone(), md_tx.clone(), venue_cmd_txs.clone(), ord_tx.clone(), kill.clone());
+        let stop = portfolio_stop.clone();
+        supervisor::spawn("multileg_hedger", move || {
+            multileg::run_hedger(l.clone(), md.subscribe(), cmds.clone(), ord_tx.clone(), kill.clone(), stop.clone())
+        });
+    }
+
+    {
+        let (health, cfg) = (venue_health.clone(), args.venue_health.clone());
+        supervisor::spawn("venue_health", move || venue_health::run(health.clone(), cfg.clone(), venue_cmd_txs.clone()));
+    }
+
+    // ---- Reconciliation (hanya jika venue Binance aktif) ----
+    #[cfg(feature = "binance")]
+    if let Some(http) = binance_http.clone().filter(|_| args.recon_interval_secs > 0) {
+        let recon_cfg = reconcile::ReconCfg {
+            http,
+            symbols: args.symbols.clone(),
+            venues: vec!["binance".into(), "binance_testnet".into()],
+            interval_secs: args.recon_interval_secs,
+            lookback_secs: args.recon_lookback_secs,
+            alerts: alerts.clone(),
+        };
+        let b = blotter.clone();
+        supervisor::spawn("reconcile", move || reconcile::run(recon_cfg.clone(), b.clone()));
+    }
+
     // ---- Positions / PnL watcher (multi-symbol dengan dispatcher) ----
     // Snapshot utama untuk symbol "primary" (dipakai router)
     let (snap_tx_primary, snap_rx) = watch::channel::<InvSnapshot>(InvSnapshot {
@@ -262,65 +893,141 @@ async fn main() {
     });
 
     // Channel positions per symbol
-    let mut pos_txs: HashMap<String, mpsc::Sender<crate::domain::ExecReport>> = HashMap::new();
+    let mut pos_txs: HashMap<String, mpsc::Sender<Arc<crate::domain::ExecReport>>> = HashMap::new();
 
     for sym in args.symbols.iter().cloned() {
-        let (pos_tx, pos_rx) = mpsc::channel::<crate::domain::ExecReport>(2048);
+        let (pos_tx, pos_rx) = mpsc::channel::<Arc<crate::domain::ExecReport>>(caps.get("positions", 2048));
+        let pos_name = format!("positions:{}", metrics::symbol_label(&sym));
+        channels::watch_mpsc(&pos_name, &pos_tx);
         pos_txs.insert(sym.clone(), pos_tx);
 
-        let md_rx_pos = md_tx.subscribe();
-        if sym == args.symbol {
+        let snap_tx = if sym == args.symbol {
             // symbol utama -> gunakan snap_tx_primary (agar router tetap dapat snapshot)
-            let snap_tx = snap_tx_primary.clone();
-            tokio::spawn(positions::run(sym.clone(), md_rx_pos, pos_rx, snap_tx));
+            snap_tx_primary.clone()
         } else {
             // symbol lain -> snapshot sendiri (tidak dipakai router saat ini)
-            let (snap_tx_other, _snap_rx_unused) = watch::channel::<InvSnapshot>(InvSnapshot {
+            watch::channel::<InvSnapshot>(InvSnapshot {
                 ts_ns: 0,
                 symbol: sym.clone(),
                 state: Default::default(),
-            });
-            tokio::spawn(positions::run(sym.clone(), md_rx_pos, pos_rx, snap_tx_other));
-        }
+            })
+            .0
+        };
+        tokio::spawn(dashboard::watch_positions(dash.clone(), snap_tx.subscribe()));
+        let md = pipes
+            .as_ref()
+            .and_then(|p| p.positions_src(&sym))
+            .unwrap_or_else(|| pipeline::PosMdSrc::Shared(md_tx.clone()));
+        let (rx, snaps, feed) = (supervisor::Relay::new(pos_rx, caps.get("positions", 2048)), snaps.clone(), position_feed.clone());
+        channels::watch_relay(&pos_name, &rx);
+        supervisor::spawn(format!("positions:{sym}"), move || {
+            positions::run(sym.clone(), md.subscribe(), rx.attach(), snap_tx.clone(), snaps.clone(), feed.clone())
+        });
     }
 
     // Dispatcher: fanout ExecReport ke positions per symbol
     tokio::spawn({
-        let mut pos_map = pos_txs;
+        let pos_map = pos_txs;
         let mut rx = exec_to_pos_rx;
         async move {
             while let Some(er) = rx.recv().await {
                 if let Some(tx) = pos_map.get(&er.symbol) {
-                    let _ = tx.send(er).await;
+                    if let Err(mpsc::error::SendError(er)) = tx.send(er).await {
+                        dead_letter::record("exec", "positions", "closed", &*er);
+                    }
                 } else {
                     // Tak ada channel untuk symbol tsb (belum dikonfigurasi)
                     tracing::debug!(symbol = %er.symbol, "no positions channel for symbol");
+                    dead_letter::record("exec", "positions", "no_consumer", &*er);
                 }
             }
         }
     });
 
-    // ---- Router ----
-    tokio::spawn(router::run(ord_rx, gw_txs, cfg, snap_rx));
+    // ---- Router (+ registry MD per symbol) ----
+    {
+        let feed = feed_health::FeedHealth::new(args.feed_stale_ms);
+        let (md, f) = (md_tx.clone(), feed.clone());
+        supervisor::spawn("feed_health", move || feed_health::run(md.subscribe(), f.clone()));
+        let md = md_tx.clone();
+        supervisor::spawn("fx", move || fx::run(md.subscribe()));
+        if args.holding.enabled() {
+            let (g, p, f, b, s) = (holding.clone(), position_feed.clone(), feed.clone(), args.books.clone(), sig_tx.clone());
+            supervisor::spawn("holding", move || holding::run(g.clone(), p.clone(), f.clone(), b.clone(), s.clone()));
+        }
+        if args.portfolio_stop.enabled() {
+            let (st, k, p, f) = (portfolio_stop.clone(), kill.clone(), position_feed.clone(), feed.clone());
+            let (b, s, a) = (args.books.clone(), sig_tx.clone(), alerts.clone());
+            supervisor::spawn("portfolio_stop", move || {
+                portfolio_stop::run(st.clone(), k.clone(), p.clone(), f.clone(), b.clone(), s.clone(), a.clone())
+            });
+        }
+        {
+            let (cfg, bl, p, st) = (args.trading_day.clone(), blotter.clone(), position_feed.clone(), portfolio_stop.clone());
+            supervisor::spawn("trading_day", move || trading_day::run(cfg.clone(), bl.clone(), p.clone(), st.clone()));
+        }
+        let ctx = router::RouterCtx {
+            health: venue_health.clone(),
+            venue_pos: venue_pos.clone(),
+            open: open_orders.clone(),
+            feed,
+            exec_tx: exec_central_tx.clone(),
+            peg: args.peg.clone(),
+        };
+        let (rx, leg_rx) = (supervisor::Relay::new(ord_rx, caps.get("orders", 2048)), supervisor::Relay::new(leg_rx, caps.get("legs", 256)));
+        channels::watch_relay("orders", &rx);
+        channels::watch_relay("legs", &leg_rx);
+        supervisor::spawn("router", move || {
+            router::run(rx.attach(), leg_rx.attach(), gw_txs.clone(), cfg.clone(), snap_rx.clone(), ctx.clone())
+        });
+    }
 
     // ---- Post-Trade ----
-    tokio::spawn(posttrade::run(exec_to_post_rx));
-
-    // ---- Heartbeat + record MD ----
-    let mut md_rx_metrics = md_tx.subscribe();
-    let rec_tx2 = rec_tx.clone();
-    let mut tick_count: u64 = 0;
-
-    loop {
-        select! {
-            Ok(md) = md_rx_metrics.recv() => {
-                tick_count += 1;
-                let _ = rec_tx2.try_send(Event::Md(md));
+    {
+        let (rx, md) = (supervisor::Relay::new(exec_to_post_rx, fanout_cap), md_tx.clone());
+        channels::watch_relay("exec_fanout:post", &rx);
+        supervisor::spawn("posttrade", move || posttrade::run(rx.attach(), md.subscribe()));
+    }
+
+    // ---- Heartbeat + record MD (sampai Ctrl-C / SIGTERM) ----
+    select! {
+        _ = liveness::run(md_tx, rec_tx, heartbeat_cfg) => {}
+        _ = shutdown_signal() => {
+            // Graceful shutdown: blok order baru + batalkan semua order terbuka, beri waktu gateway
+            warn!("shutdown requested, cancelling all open orders");
+            kill.engage("shutdown");
+            tokio::time::sleep(Duration::from_secs(2)).await;
+            // metrik final untuk run pendek / batch
+            #[cfg(feature = "pushgateway")]
+            if args.push.enabled() {
+                pushgateway::push(&args.push).await;
+            }
+        }
+    }
+}
+
+/// Venue "binance"/"binance_testnet" memakai gateway_binance (di luar VENUE_MODE=mock)
+fn is_binance_venue(name: &str) -> bool {
+    matches!(name.to_ascii_lowercase().as_str(), "binance" | "binance_testnet")
+}
+
+/// Ctrl-C, atau SIGTERM (timeout / orchestrator) di unix
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut term) => select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = term.recv() => {}
             },
-            _ = tokio::time::sleep(Duration::from_secs(1)) => {
-                info!(ticks=tick_count, "heartbeat");
-                tick_count = 0;
+            Err(_) => {
+                let _ = tokio::signal::ctrl_c().await;
             }
         }
     }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
 }
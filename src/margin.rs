@@ -0,0 +1,97 @@
+// ===============================
+// src/margin.rs
+// ===============================
+//
+// Pemakaian margin & leverage (TRADING_MODE=margin).
+//
+// Belum ada gateway futures: angka di sini adalah estimasi model isolated margin dari
+// posisi internal (positions), bukan angka dari exchange.
+//   initial margin     = |qty| * px / MAX_LEVERAGE
+//   maintenance margin = |qty| * px * MAINT_MARGIN_RATE
+//   liquidation long   = entry * (1 - 1/L + mmr)
+//   liquidation short  = entry * (1 + 1/L - mmr)
+// Usage (%) = total margin / MARGIN_EQUITY. Risk memblokir order yang membuat usage
+// initial margin > MAX_MARGIN_USAGE_PCT.
+//
+use ahash::AHashMap as HashMap;
+use once_cell::sync::{Lazy, OnceCell};
+use std::sync::Mutex;
+
+use crate::metrics::{LIQUIDATION_PRICE, MARGIN_POSITION, MARGIN_USAGE_PCT};
+use crate::sizing;
+
+#[derive(Debug, Clone, Default)]
+pub struct MarginCfg {
+    pub enabled: bool,
+    pub leverage: f64,
+    pub maint_rate: f64,
+    pub equity: f64,
+}
+
+static MARGIN: OnceCell<MarginCfg> = OnceCell::new();
+
+/// symbol -> (initial, maintenance) margin terakhir (untuk agregat usage)
+static USED: Lazy<Mutex<HashMap<String, (f64, f64)>>> = Lazy::new(Default::default);
+
+/// Set konfigurasi margin global (dipanggil sekali di startup)
+pub fn init(cfg: MarginCfg) {
+    if cfg.enabled {
+        tracing::info!(?cfg, "margin tracking");
+    }
+    let _ = MARGIN.set(cfg);
+}
+
+fn cfg() -> &'static MarginCfg {
+    MARGIN.get_or_init(MarginCfg::default)
+}
+
+/// Notional posisi dalam USD-ekuivalen (`qty` lot, `px` tick ×100, quote dikonversi lewat fx)
+pub fn notional(symbol: &str, qty: i64, px: i64) -> f64 {
+    crate::fx::to_usd(symbol, sizing::base_qty(symbol, qty.abs()) * px as f64 / 100.0)
+}
+
+pub fn initial_margin(notional: f64) -> f64 {
+    notional / cfg().leverage.max(1e-9)
+}
+
+pub fn maintenance_margin(notional: f64) -> f64 {
+    notional * cfg().maint_rate
+}
+
+/// Harga likuidasi (tick) posisi isolated dengan harga masuk `entry_px`; 0 jika flat
+pub fn liquidation_price(qty: i64, entry_px: i64) -> i64 {
+    let c = cfg();
+    let im = 1.0 / c.leverage.max(1e-9);
+    let factor = match qty.signum() {
+        1 => 1.0 - im + c.maint_rate,
+        -1 => 1.0 + im - c.maint_rate,
+        _ => return 0,
+    };
+    (entry_px as f64 * factor).max(0.0).round() as i64
+}
+
+/// % dari MARGIN_EQUITY (0 jika equity tidak diset)
+pub fn usage_pct(margin: f64) -> f64 {
+    let equity = cfg().equity;
+    if equity > 0.0 { margin / equity * 100.0 } else { 0.0 }
+}
+
+/// Publish margin posisi `symbol` (dipanggil positions saat fill / mark-to-market)
+pub fn publish(symbol: &str, qty: i64, entry_px: i64, mark_px: i64) {
+    if !cfg().enabled {
+        return;
+    }
+    let n = notional(symbol, qty, mark_px);
+    let (im, mm) = (initial_margin(n), maintenance_margin(n));
+    if crate::metrics::symbol_admitted(symbol) {
+        MARGIN_POSITION.with_label_values(&[symbol, "initial"]).set(im);
+        MARGIN_POSITION.with_label_values(&[symbol, "maintenance"]).set(mm);
+        LIQUIDATION_PRICE.with_label_values(&[symbol]).set(liquidation_price(qty, entry_px) as f64 / 100.0);
+    }
+
+    let mut used = USED.lock().unwrap();
+    used.insert(symbol.to_string(), (im, mm));
+    let (ti, tm) = used.values().fold((0.0, 0.0), |(a, b), (i, m)| (a + i, b + m));
+    MARGIN_USAGE_PCT.with_label_values(&["initial"]).set(usage_pct(ti));
+    MARGIN_USAGE_PCT.with_label_values(&["maintenance"]).set(usage_pct(tm));
+}
@@ -0,0 +1,235 @@
+// ===============================
+// src/md_anomaly.rs
+// ===============================
+//
+// Deteksi anomali pola quote (spoofing / momentum ignition / flash) per symbol dari bus MD.
+// Berbeda dengan md_filter.rs (membuang tick rusak satu per satu), detektor ini melihat pola
+// dalam window waktu pendek; tick tetap diteruskan apa adanya.
+//
+// Jenis anomali (`md_anomalies_total{symbol,kind}`):
+// - flicker           : best bid/ask bolak-balik A -> B -> A lebih dari MD_FLICKER_MAX kali dalam window
+//                       (quote dipasang & ditarik cepat untuk memancing strategi)
+// - spread_oscillation: spread max >= MD_SPREAD_OSC_RATIO × spread min dalam window dan spread
+//                       berganti arah (melebar/menyempit) minimal 4 kali
+//
+// Symbol yang terdeteksi ditandai selama MD_ANOMALY_HOLD_SECS (deteksi ulang memperpanjang),
+// `md_anomaly_active{symbol}` = 1. Aksi selama tanda aktif:
+// - pause : risk menolak semua Signal symbol itu (RiskError::MdAnomaly)
+// - widen : threshold strategi bawaan dikali MD_ANOMALY_WIDEN (edge mean-reversion / MA crossover /
+//           breakout, k Bollinger) lewat `TickStrategy::set_threshold_mult`
+//
+// ENV:
+//   MD_ANOMALY=off             (off | pause | widen)
+//   MD_ANOMALY_WINDOW_MS=1000
+//   MD_FLICKER_MAX=10
+//   MD_SPREAD_OSC_RATIO=5.0
+//   MD_ANOMALY_HOLD_SECS=30
+//   MD_ANOMALY_WIDEN=2.0
+//
+use ahash::AHashMap as HashMap;
+use std::collections::VecDeque;
+use std::sync::{Arc, RwLock};
+use tokio::sync::broadcast;
+use tracing::warn;
+
+use crate::domain::MdTick;
+use crate::metrics::{symbol_label, MD_ANOMALIES, MD_ANOMALY_ACTIVE};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyAction {
+    Off,
+    Pause,
+    Widen,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AnomalyCfg {
+    pub action: AnomalyAction,
+    pub window_ms: u64,
+    pub flicker_max: usize,
+    pub spread_osc_ratio: f64,
+    pub hold_secs: u64,
+    pub widen: f64,
+}
+
+impl AnomalyCfg {
+    pub fn from_env() -> Self {
+        let var = |k: &str| std::env::var(k).ok();
+        let action = match var("MD_ANOMALY").unwrap_or_default().to_ascii_lowercase().as_str() {
+            "pause" => AnomalyAction::Pause,
+            "widen" => AnomalyAction::Widen,
+            _ => AnomalyAction::Off,
+        };
+        Self {
+            action,
+            window_ms: var("MD_ANOMALY_WINDOW_MS").and_then(|s| s.parse().ok()).unwrap_or(1_000),
+            flicker_max: var("MD_FLICKER_MAX").and_then(|s| s.parse().ok()).unwrap_or(10),
+            spread_osc_ratio: var("MD_SPREAD_OSC_RATIO").and_then(|s| s.parse().ok()).unwrap_or(5.0),
+            hold_secs: var("MD_ANOMALY_HOLD_SECS").and_then(|s| s.parse().ok()).unwrap_or(30),
+            widen: var("MD_ANOMALY_WIDEN").and_then(|s| s.parse().ok()).unwrap_or(2.0),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.action != AnomalyAction::Off
+    }
+}
+
+impl Default for AnomalyCfg {
+    fn default() -> Self {
+        Self { action: AnomalyAction::Off, window_ms: 1_000, flicker_max: 10, spread_osc_ratio: 5.0, hold_secs: 30, widen: 2.0 }
+    }
+}
+
+/// Pola quote dalam window untuk satu symbol
+#[derive(Default)]
+struct Window {
+    ticks: VecDeque<(i128, i64, i64)>, // (ts_ns, bid, ask)
+}
+
+impl Window {
+    fn push(&mut self, md: &MdTick, window_ns: i128) {
+        self.ticks.push_back((md.ts_ns, md.best_bid, md.best_ask));
+        while self.ticks.front().is_some_and(|(t, _, _)| md.ts_ns - t > window_ns) {
+            self.ticks.pop_front();
+        }
+    }
+
+    /// Jumlah pola A -> B -> A pada best bid + best ask
+    fn flickers(&self) -> usize {
+        let t: Vec<_> = self.ticks.iter().collect();
+        t.windows(3)
+            .map(|w| {
+                let (a, b, c) = (w[0], w[1], w[2]);
+                (a.1 != b.1 && a.1 == c.1) as usize + (a.2 != b.2 && a.2 == c.2) as usize
+            })
+            .sum()
+    }
+
+    /// (max/min spread, jumlah pergantian arah spread)
+    fn spread_osc(&self) -> (f64, usize) {
+        let spreads: Vec<i64> = self.ticks.iter().map(|(_, b, a)| a - b).collect();
+        let (min, max) = (spreads.iter().copied().min().unwrap_or(0), spreads.iter().copied().max().unwrap_or(0));
+        let ratio = if min > 0 { max as f64 / min as f64 } else { 0.0 };
+        let dirs: Vec<i64> = spreads.windows(2).map(|w| (w[1] - w[0]).signum()).filter(|d| *d != 0).collect();
+        (ratio, dirs.windows(2).filter(|w| w[0] != w[1]).count())
+    }
+}
+
+/// Handle status anomali per symbol (diisi task `run`, dibaca risk & worker strategi)
+#[derive(Clone, Default)]
+pub struct MdAnomaly {
+    cfg: Arc<AnomalyCfg>,
+    flagged: Arc<RwLock<HashMap<String, (&'static str, i128)>>>, // symbol -> (jenis, aktif sampai ns)
+}
+
+impl MdAnomaly {
+    pub fn new(cfg: AnomalyCfg) -> Self {
+        Self { cfg: Arc::new(cfg), flagged: Arc::default() }
+    }
+
+    /// Jenis anomali yang sedang aktif untuk symbol
+    pub fn active(&self, symbol: &str, now: i128) -> Option<&'static str> {
+        self.flagged.read().unwrap().get(symbol).filter(|(_, until)| now < *until).map(|(kind, _)| *kind)
+    }
+
+    /// pause: jenis anomali aktif (risk menolak Signal)
+    pub fn paused(&self, symbol: &str, now: i128) -> Option<&'static str> {
+        (self.cfg.action == AnomalyAction::Pause).then(|| self.active(symbol, now)).flatten()
+    }
+
+    /// widen: pengali threshold strategi (1.0 jika tidak ada anomali)
+    pub fn threshold_mult(&self, symbol: &str, now: i128) -> f64 {
+        match self.cfg.action {
+            AnomalyAction::Widen if self.active(symbol, now).is_some() => self.cfg.widen.max(1.0),
+            _ => 1.0,
+        }
+    }
+
+    /// Evaluasi window; Some(jenis) jika pola anomali terdeteksi
+    fn detect(&self, w: &Window) -> Option<&'static str> {
+        if w.flickers() > self.cfg.flicker_max {
+            return Some("flicker");
+        }
+        let (ratio, turns) = w.spread_osc();
+        (ratio >= self.cfg.spread_osc_ratio && turns >= 4).then_some("spread_oscillation")
+    }
+
+    fn flag(&self, symbol: &str, kind: &'static str, now: i128) {
+        let until = now + self.cfg.hold_secs as i128 * 1_000_000_000;
+        let prev = self.flagged.write().unwrap().insert(symbol.to_string(), (kind, until));
+        if prev.is_none_or(|(_, u)| now >= u) {
+            warn!(%symbol, kind, hold_secs = self.cfg.hold_secs, action = ?self.cfg.action, "md anomaly: suspicious quote pattern");
+            MD_ANOMALIES.with_label_values(&[symbol_label(symbol), kind]).inc();
+        }
+    }
+}
+
+/// Task: evaluasi pola quote per symbol + publish `md_anomaly_active{symbol}` tiap detik
+pub async fn run(mut md_rx: broadcast::Receiver<MdTick>, anomaly: MdAnomaly) {
+    let window_ns = anomaly.cfg.window_ms as i128 * 1_000_000;
+    let mut windows: HashMap<String, Window> = HashMap::new();
+    let mut tick = tokio::time::interval(std::time::Duration::from_secs(1));
+    loop {
+        tokio::select! {
+            r = md_rx.recv() => match r {
+                Ok(md) => {
+                    let w = windows.entry(md.symbol.clone()).or_default();
+                    w.push(&md, window_ns);
+                    if let Some(kind) = anomaly.detect(w) {
+                        anomaly.flag(&md.symbol, kind, md.ts_ns);
+                        w.ticks.clear();
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            _ = tick.tick() => {
+                let now = crate::clock::now_ns();
+                for sym in windows.keys().filter(|s| crate::metrics::symbol_admitted(s)) {
+                    MD_ANOMALY_ACTIVE.with_label_values(&[sym]).set(anomaly.active(sym, now).is_some() as i64);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn md(ms: i128, bid: i64, ask: i64) -> MdTick {
+        MdTick { ts_ns: ms * 1_000_000, symbol: "BTCUSDT".into(), best_bid: bid, best_ask: ask, bid_qty: 0, ask_qty: 0, seq: 0, exch_ts_ns: 0 }
+    }
+
+    #[test]
+    fn flags_quote_flicker_and_spread_oscillation() {
+        let a = MdAnomaly::new(AnomalyCfg { action: AnomalyAction::Pause, flicker_max: 4, ..Default::default() });
+        let mut w = Window::default();
+        // quote stabil naik pelan: tidak ada anomali
+        for i in 0..20 {
+            w.push(&md(i * 10, 100 + i as i64, 102 + i as i64), 1_000_000_000);
+        }
+        assert_eq!(a.detect(&w), None);
+
+        // bid 100 <-> 101 bolak-balik
+        let mut w = Window::default();
+        for i in 0..8 {
+            w.push(&md(i * 10, 100 + (i % 2) as i64, 110), 1_000_000_000);
+        }
+        assert_eq!(a.detect(&w), Some("flicker"));
+
+        // spread 1 <-> 10 berganti-ganti (bid & ask bergeser agar tidak terhitung flicker)
+        let mut w = Window::default();
+        for i in 0..6i64 {
+            let spread = if i % 2 == 0 { 1 } else { 10 };
+            w.push(&md(i as i128 * 10, 100 + i * 20, 100 + i * 20 + spread), 1_000_000_000);
+        }
+        assert_eq!(a.detect(&w), Some("spread_oscillation"));
+
+        a.flag("BTCUSDT", "flicker", 0);
+        assert_eq!(a.paused("BTCUSDT", 1), Some("flicker"));
+        assert_eq!(a.paused("BTCUSDT", 31_000_000_000), None);
+    }
+}
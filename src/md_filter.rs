@@ -0,0 +1,172 @@
+// ===============================
+// src/md_filter.rs
+// ===============================
+//
+// Filter kualitas data MD di batas feed (sebelum seq & publish ke strategi):
+// - zero   : bid/ask <= 0
+// - crossed: ask < bid (locked ask == bid tetap lolos)
+// - jump   : mid melompat > MD_MAX_JUMP_PCT dari mid referensi (bad print)
+//
+// Mid referensi = EMA mid tick yang lolos (MD_SMOOTH_ALPHA), jadi satu print buruk tidak
+// menggeser acuan. Jika pasar benar-benar gap, setelah MD_JUMP_RESYNC penolakan jump
+// berturut-turut tick diterima dan referensi di-anchor ulang.
+//
+// ENV:
+//   MD_FILTER=1            0 = nonaktif
+//   MD_MAX_JUMP_PCT=5.0    0 = tanpa cek jump
+//   MD_SMOOTH_ALPHA=0.2
+//   MD_JUMP_RESYNC=5
+//
+// Tick yang dibuang dihitung di `md_filtered_total{symbol,reason}`.
+//
+use once_cell::sync::OnceCell;
+use tracing::warn;
+
+use crate::domain::MdTick;
+use crate::metrics::MD_FILTERED;
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FilterCfg {
+    pub enabled: bool,
+    pub max_jump_pct: f64,
+    pub smooth_alpha: f64,
+    pub jump_resync: u32,
+}
+
+impl Default for FilterCfg {
+    fn default() -> Self {
+        Self { enabled: true, max_jump_pct: 5.0, smooth_alpha: 0.2, jump_resync: 5 }
+    }
+}
+
+static CFG: OnceCell<FilterCfg> = OnceCell::new();
+
+/// Set konfigurasi global (dipanggil sekali di startup)
+pub fn init(cfg: FilterCfg) {
+    let _ = CFG.set(cfg);
+}
+
+fn cfg() -> &'static FilterCfg {
+    CFG.get_or_init(FilterCfg::default)
+}
+
+/// State filter per symbol (satu per task feed)
+#[derive(Default)]
+pub struct TickFilter {
+    ref_mid: Option<f64>,
+    jump_streak: u32,
+}
+
+impl TickFilter {
+    /// true = tick bersih dan boleh dipublish
+    pub fn accept(&mut self, md: &MdTick) -> bool {
+        let cfg = cfg();
+        if !cfg.enabled {
+            return true;
+        }
+        match self.check(md, cfg) {
+            Ok(()) => true,
+            Err(reason) => {
+                MD_FILTERED.with_label_values(&[crate::metrics::symbol_label(&md.symbol), reason]).inc();
+                false
+            }
+        }
+    }
+
+    fn check(&mut self, md: &MdTick, cfg: &FilterCfg) -> Result<(), &'static str> {
+        if md.best_bid <= 0 || md.best_ask <= 0 {
+            return Err("zero");
+        }
+        if md.best_ask < md.best_bid {
+            return Err("crossed");
+        }
+        let mid = (md.best_bid + md.best_ask) as f64 / 2.0;
+        if let Some(r) = self.ref_mid.filter(|_| cfg.max_jump_pct > 0.0) {
+            let jump_pct = (mid - r).abs() / r * 100.0;
+            if jump_pct > cfg.max_jump_pct {
+                self.jump_streak += 1;
+                if self.jump_streak < cfg.jump_resync {
+                    return Err("jump");
+                }
+                // lompatan bertahan: anggap level harga baru
+                warn!(symbol = %md.symbol, jump_pct, "md filter: persistent jump, re-anchoring reference mid");
+                self.ref_mid = None;
+            }
+        }
+        self.jump_streak = 0;
+        let a = cfg.smooth_alpha.clamp(0.0, 1.0);
+        self.ref_mid = Some(match self.ref_mid {
+            Some(r) => r + a * (mid - r),
+            None => mid,
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn md(bid: i64, ask: i64) -> MdTick {
+        MdTick { ts_ns: 0, symbol: "BTCUSDT".into(), best_bid: bid, best_ask: ask, bid_qty: 0, ask_qty: 0, seq: 0, exch_ts_ns: 0 }
+    }
+
+    fn cfg(resync: u32) -> FilterCfg {
+        FilterCfg { enabled: true, max_jump_pct: 5.0, smooth_alpha: 0.5, jump_resync: resync }
+    }
+
+    #[test]
+    fn zero_prices_rejected() {
+        let (mut f, c) = (TickFilter::default(), cfg(5));
+        assert_eq!(f.check(&md(0, 10_000), &c), Err("zero"));
+        assert_eq!(f.check(&md(10_000, 0), &c), Err("zero"));
+        assert_eq!(f.check(&md(-1, 10_000), &c), Err("zero"));
+        assert_eq!(f.ref_mid, None);
+    }
+
+    #[test]
+    fn crossed_rejected_locked_accepted() {
+        let (mut f, c) = (TickFilter::default(), cfg(5));
+        assert_eq!(f.check(&md(10_002, 10_000), &c), Err("crossed"));
+        assert_eq!(f.ref_mid, None);
+        // locked (ask == bid) bukan crossed
+        assert_eq!(f.check(&md(10_000, 10_000), &c), Ok(()));
+        assert_eq!(f.ref_mid, Some(10_000.0));
+    }
+
+    #[test]
+    fn jump_rejected_without_moving_reference() {
+        let (mut f, c) = (TickFilter::default(), cfg(5));
+        assert_eq!(f.check(&md(10_000, 10_000), &c), Ok(()));
+        // +10% dari referensi: bad print
+        assert_eq!(f.check(&md(11_000, 11_000), &c), Err("jump"));
+        assert_eq!(f.ref_mid, Some(10_000.0));
+        assert_eq!(f.jump_streak, 1);
+        // +4% masih di bawah MD_MAX_JUMP_PCT: lolos, EMA bergerak setengah jalan, streak direset
+        assert_eq!(f.check(&md(10_400, 10_400), &c), Ok(()));
+        assert_eq!(f.ref_mid, Some(10_200.0));
+        assert_eq!(f.jump_streak, 0);
+    }
+
+    #[test]
+    fn persistent_jump_resyncs_after_streak() {
+        let (mut f, c) = (TickFilter::default(), cfg(3));
+        assert_eq!(f.check(&md(10_000, 10_000), &c), Ok(()));
+        assert_eq!(f.check(&md(12_000, 12_000), &c), Err("jump"));
+        assert_eq!(f.check(&md(12_000, 12_000), &c), Err("jump"));
+        assert_eq!(f.ref_mid, Some(10_000.0));
+        // penolakan ke-MD_JUMP_RESYNC: diterima dan referensi di-anchor ke level baru (bukan EMA)
+        assert_eq!(f.check(&md(12_000, 12_000), &c), Ok(()));
+        assert_eq!(f.ref_mid, Some(12_000.0));
+        assert_eq!(f.jump_streak, 0);
+        assert_eq!(f.check(&md(12_100, 12_100), &c), Ok(()));
+    }
+
+    #[test]
+    fn jump_check_disabled_with_zero_pct() {
+        let mut f = TickFilter::default();
+        let c = FilterCfg { max_jump_pct: 0.0, ..cfg(5) };
+        assert_eq!(f.check(&md(10_000, 10_000), &c), Ok(()));
+        assert_eq!(f.check(&md(20_000, 20_000), &c), Ok(()));
+    }
+}
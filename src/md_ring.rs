@@ -0,0 +1,584 @@
+// ===============================
+// src/md_ring.rs
+// ===============================
+//
+// Transport MD alternatif untuk worker strategi (MD_BUS=ring): ring buffer SPMC lock-free per symbol
+// sebagai pengganti bus broadcast.
+// - satu ring per symbol; task feed symbol itu satu-satunya publisher (klaim eksklusif, dilepas saat task
+//   feed berhenti / di-restart supervisor). Tick ditulis langsung dari task feed, tanpa task perantara
+// - slot = seqlock: stamp + payload berupa word atomik (load/store Relaxed), jadi reader yang membaca
+//   bersamaan dengan penulisan tidak pernah data race; salinan robek terdeteksi dari stamp dan dibuang
+// - publish tidak pernah menunggu reader; reader (satu per worker) membaca semua ring bergiliran dan
+//   parkir di `Notify` saat kosong (publisher hanya membangunkan jika ada reader yang parkir)
+// - reader yang tertinggal lebih dari kapasitas lompat ke tick tertua yang masih ada dan menerima
+//   RecvError::Lagged(n), sama seperti broadcast, jadi loop strategi tidak berubah
+// - bus tertutup saat semua handle `MdRing` dan publisher di-drop: reader menghabiskan sisa tick
+//   lalu menerima RecvError::Closed
+//
+// Perbandingan latensi & drop vs broadcast: subcommand `bench-md-bus`.
+//
+// ENV:
+//   MD_BUS=broadcast|ring   (default broadcast)
+//   MD_RING_CAPACITY=4096   (per symbol, dibulatkan ke pangkat 2)
+//
+use std::sync::atomic::{fence, AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use tokio::sync::broadcast::{self, error::{RecvError, TryRecvError}};
+use tokio::sync::Notify;
+use tracing::warn;
+
+use crate::book_analytics;
+use crate::clock;
+use crate::domain::{BookStats, BookUpdate, EnrichedTick, MdTick};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BusKind {
+    Broadcast,
+    Ring,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MdBusCfg {
+    pub kind: BusKind,
+    pub capacity: usize,
+}
+
+impl MdBusCfg {
+    pub fn from_env() -> Self {
+        let kind = match std::env::var("MD_BUS").unwrap_or_default().to_ascii_lowercase().as_str() {
+            "ring" => BusKind::Ring,
+            _ => BusKind::Broadcast,
+        };
+        let capacity = std::env::var("MD_RING_CAPACITY").ok().and_then(|s| s.parse().ok()).unwrap_or(4096);
+        Self { kind, capacity }
+    }
+}
+
+/// Jumlah word payload satu tick
+const WORDS: usize = 13;
+
+fn encode(md: &MdTick, s: &BookStats) -> [u64; WORDS] {
+    [
+        md.ts_ns as u64,
+        (md.ts_ns >> 64) as u64,
+        md.exch_ts_ns as u64,
+        (md.exch_ts_ns >> 64) as u64,
+        md.best_bid as u64,
+        md.best_ask as u64,
+        md.bid_qty as u64,
+        md.ask_qty as u64,
+        md.seq,
+        s.microprice.to_bits(),
+        s.imbalance.to_bits(),
+        s.imbalance_k.to_bits(),
+        s.levels as u64,
+    ]
+}
+
+fn decode(symbol: &str, w: &[u64; WORDS]) -> EnrichedTick {
+    let wide = |lo: u64, hi: u64| ((hi as i128) << 64) | lo as i128;
+    EnrichedTick {
+        tick: MdTick {
+            ts_ns: wide(w[0], w[1]),
+            symbol: symbol.to_string(),
+            best_bid: w[4] as i64,
+            best_ask: w[5] as i64,
+            bid_qty: w[6] as i64,
+            ask_qty: w[7] as i64,
+            seq: w[8],
+            exch_ts_ns: wide(w[2], w[3]),
+        },
+        stats: BookStats {
+            microprice: f64::from_bits(w[9]),
+            imbalance: f64::from_bits(w[10]),
+            imbalance_k: f64::from_bits(w[11]),
+            levels: w[12] as usize,
+        },
+    }
+}
+
+struct Slot {
+    // 0 = kosong, 2n+1 = sedang ditulis (posisi n), 2n+2 = berisi posisi n
+    stamp: AtomicU64,
+    words: [AtomicU64; WORDS],
+}
+
+/// Ring satu symbol
+struct Lane {
+    symbol: String,
+    slots: Box<[Slot]>,
+    mask: u64,
+    head: AtomicU64,     // posisi tulis berikutnya
+    claimed: AtomicBool, // ada publisher aktif
+}
+
+struct Bus {
+    lanes: Box<[Lane]>,
+    notify: Notify,
+    parked: AtomicUsize,  // reader yang menunggu di `notify`
+    senders: AtomicUsize, // handle MdRing + publisher yang hidup; 0 = tertutup
+    closed: AtomicBool,
+}
+
+impl Bus {
+    fn release(&self) {
+        if self.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.closed.store(true, Ordering::Release);
+            self.notify.notify_waiters();
+        }
+    }
+}
+
+/// Handle bus ring: membuat publisher (per symbol) dan reader. Clone = satu sender lagi
+pub struct MdRing {
+    bus: Arc<Bus>,
+}
+
+impl Clone for MdRing {
+    fn clone(&self) -> Self {
+        self.bus.senders.fetch_add(1, Ordering::Relaxed);
+        Self { bus: self.bus.clone() }
+    }
+}
+
+impl Drop for MdRing {
+    fn drop(&mut self) {
+        self.bus.release();
+    }
+}
+
+impl MdRing {
+    /// Satu ring `capacity` slot per symbol
+    pub fn new(symbols: &[String], capacity: usize) -> Self {
+        let cap = capacity.max(2).next_power_of_two();
+        let lanes = symbols
+            .iter()
+            .map(|s| Lane {
+                symbol: s.clone(),
+                slots: (0..cap).map(|_| Slot { stamp: AtomicU64::new(0), words: Default::default() }).collect(),
+                mask: cap as u64 - 1,
+                head: AtomicU64::new(0),
+                claimed: AtomicBool::new(false),
+            })
+            .collect();
+        let bus = Bus {
+            lanes,
+            notify: Notify::new(),
+            parked: AtomicUsize::new(0),
+            senders: AtomicUsize::new(1),
+            closed: AtomicBool::new(false),
+        };
+        Self { bus: Arc::new(bus) }
+    }
+
+    /// Klaim ujung tulis ring `symbol`; None jika symbol tidak dikenal atau publisher lain masih aktif.
+    /// `k` = level untuk imbalance_k (BOOK_IMBALANCE_LEVELS)
+    pub fn publisher(&self, symbol: &str, k: usize) -> Option<RingPublisher> {
+        let Some(lane) = self.bus.lanes.iter().position(|l| l.symbol == symbol) else {
+            warn!(%symbol, "md ring: unknown symbol, ticks not published to the ring");
+            return None;
+        };
+        if self.bus.lanes[lane].claimed.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            warn!(%symbol, "md ring: publisher already active");
+            return None;
+        }
+        self.bus.senders.fetch_add(1, Ordering::Relaxed);
+        Some(RingPublisher { bus: self.bus.clone(), lane, k })
+    }
+
+    /// Reader baru mulai dari tick berikutnya (seperti broadcast::subscribe)
+    pub fn subscribe(&self) -> RingReader {
+        let next = self.bus.lanes.iter().map(|l| l.head.load(Ordering::Acquire)).collect();
+        RingReader { bus: self.bus.clone(), next, cursor: 0 }
+    }
+}
+
+/// Ujung tulis satu ring (tepat satu per symbol, tidak Clone)
+pub struct RingPublisher {
+    bus: Arc<Bus>,
+    lane: usize,
+    k: usize,
+}
+
+impl Drop for RingPublisher {
+    fn drop(&mut self) {
+        self.bus.lanes[self.lane].claimed.store(false, Ordering::Release);
+        self.bus.release();
+    }
+}
+
+impl RingPublisher {
+    /// Tulis tick + statistik book (`book` = BookUpdate terakhir symbol ini, jika feed membawa depth)
+    pub fn publish(&mut self, md: &MdTick, book: Option<&BookUpdate>) {
+        let stats = match book {
+            Some(b) => book_analytics::enrich(md, Some(b), self.k),
+            None => book_analytics::stats(md, None, 1),
+        };
+        let words = encode(md, &stats);
+        let lane = &self.bus.lanes[self.lane];
+        let pos = lane.head.load(Ordering::Relaxed);
+        let slot = &lane.slots[(pos & lane.mask) as usize];
+        slot.stamp.store(pos * 2 + 1, Ordering::Relaxed);
+        fence(Ordering::Release);
+        for (a, v) in slot.words.iter().zip(words) {
+            a.store(v, Ordering::Relaxed);
+        }
+        slot.stamp.store(pos * 2 + 2, Ordering::Release);
+        lane.head.store(pos + 1, Ordering::Release);
+        // pasangan fence di RingReader::recv: reader yang parkir melihat head baru atau dibangunkan
+        fence(Ordering::SeqCst);
+        if self.bus.parked.load(Ordering::SeqCst) > 0 {
+            self.bus.notify.notify_waiters();
+        }
+    }
+}
+
+/// Slot berikutnya satu ring: None = kosong, Err(n) = n tick terlewat
+fn read_lane(lane: &Lane, next: &mut u64) -> Option<Result<[u64; WORDS], u64>> {
+    let cap = lane.mask + 1;
+    let head = lane.head.load(Ordering::Acquire);
+    if *next >= head {
+        return None;
+    }
+    if head - *next > cap {
+        let missed = head - cap - *next;
+        *next = head - cap;
+        return Some(Err(missed));
+    }
+    let slot = &lane.slots[(*next & lane.mask) as usize];
+    let want = *next * 2 + 2;
+    if slot.stamp.load(Ordering::Acquire) == want {
+        let mut words = [0u64; WORDS];
+        for (v, a) in words.iter_mut().zip(&slot.words) {
+            *v = a.load(Ordering::Relaxed);
+        }
+        fence(Ordering::Acquire);
+        if slot.stamp.load(Ordering::Relaxed) == want {
+            *next += 1;
+            return Some(Ok(words));
+        }
+    }
+    // slot sudah ditimpa putaran berikutnya saat dibaca
+    let head = lane.head.load(Ordering::Acquire);
+    let missed = (head.saturating_sub(cap) + 1).saturating_sub(*next).max(1);
+    *next += missed;
+    Some(Err(missed))
+}
+
+/// Kurangi hitungan reader parkir juga saat future recv di-drop (select!)
+struct Parked<'a>(&'a AtomicUsize);
+
+impl Drop for Parked<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+pub struct RingReader {
+    bus: Arc<Bus>,
+    next: Vec<u64>, // posisi baca per ring
+    cursor: usize,  // ring yang dicek pertama (bergiliran)
+}
+
+impl RingReader {
+    pub fn try_recv(&mut self) -> Result<EnrichedTick, TryRecvError> {
+        let bus = &*self.bus;
+        // dibaca sebelum scan: semua tick yang ditulis sebelum bus ditutup ikut terlihat di scan
+        let closed = bus.closed.load(Ordering::Acquire);
+        let n = bus.lanes.len();
+        for i in 0..n {
+            let idx = (self.cursor + i) % n;
+            let lane = &bus.lanes[idx];
+            if let Some(r) = read_lane(lane, &mut self.next[idx]) {
+                self.cursor = (idx + 1) % n;
+                return r.map(|w| decode(&lane.symbol, &w)).map_err(TryRecvError::Lagged);
+            }
+        }
+        Err(if closed { TryRecvError::Closed } else { TryRecvError::Empty })
+    }
+
+    fn ready(&self) -> bool {
+        self.bus.closed.load(Ordering::Acquire)
+            || self.bus.lanes.iter().zip(&self.next).any(|(l, n)| l.head.load(Ordering::Acquire) > *n)
+    }
+
+    pub async fn recv(&mut self) -> Result<EnrichedTick, RecvError> {
+        loop {
+            match self.try_recv() {
+                Ok(t) => return Ok(t),
+                Err(TryRecvError::Lagged(n)) => return Err(RecvError::Lagged(n)),
+                Err(TryRecvError::Closed) => return Err(RecvError::Closed),
+                Err(TryRecvError::Empty) => {}
+            }
+            let bus = self.bus.clone();
+            // daftar di Notify, umumkan parkir, cek ulang, baru tunggu (tidak ada publish yang terlewat)
+            let notified = bus.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            bus.parked.fetch_add(1, Ordering::SeqCst);
+            let _parked = Parked(&bus.parked);
+            fence(Ordering::SeqCst);
+            if !self.ready() {
+                notified.await;
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Benchmark (subcommand bench-md-bus)
+// ---------------------------------------------------------------------------
+
+#[derive(Default)]
+struct BenchResult {
+    lat_ns: Vec<i64>,
+    lagged: u64,
+}
+
+impl BenchResult {
+    fn on_tick(&mut self, ts_ns: i128) {
+        self.lat_ns.push((clock::now_ns() - ts_ns) as i64);
+    }
+}
+
+fn bench_symbols(n: usize) -> Vec<String> {
+    (0..n.max(1)).map(|i| format!("SYM{i}USDT")).collect()
+}
+
+fn bench_tick(symbol: &str, i: u64) -> MdTick {
+    MdTick {
+        ts_ns: clock::now_ns(),
+        symbol: symbol.to_string(),
+        best_bid: 10_000,
+        best_ask: 10_002,
+        bid_qty: 5,
+        ask_qty: 5,
+        seq: i + 1,
+        exch_ts_ns: 0,
+    }
+}
+
+/// Satu producer (= satu task feed): `ticks` tick, `rate` tick/s (0 = secepatnya)
+async fn produce(symbol: String, ticks: u64, rate: u64, mut send: impl FnMut(MdTick)) {
+    let start = Instant::now();
+    for i in 0..ticks {
+        send(bench_tick(&symbol, i));
+        if rate > 0 {
+            let due = start + std::time::Duration::from_secs_f64((i + 1) as f64 / rate as f64);
+            if due > Instant::now() {
+                tokio::time::sleep_until(due.into()).await;
+            }
+        } else if i % 256 == 255 {
+            tokio::task::yield_now().await;
+        }
+    }
+}
+
+fn summary(name: &str, total: u64, results: Vec<BenchResult>, elapsed: f64) {
+    let consumers = results.len() as u64;
+    let lagged: u64 = results.iter().map(|r| r.lagged).sum();
+    let mut lat: Vec<i64> = results.into_iter().flat_map(|r| r.lat_ns).collect();
+    lat.sort_unstable();
+    let pct = |p: f64| lat.get(((lat.len() as f64 * p) as usize).min(lat.len().saturating_sub(1))).map_or(0.0, |v| *v as f64 / 1e3);
+    println!(
+        "{name:<10} delivered={:>10} dropped={:>9} ({:>5.1}%)  p50={:>8.1}us p99={:>9.1}us max={:>10.1}us  {:>6.2}s",
+        lat.len(),
+        lagged,
+        lagged as f64 * 100.0 / (total * consumers).max(1) as f64,
+        pct(0.50),
+        pct(0.99),
+        pct(1.0),
+        elapsed,
+    );
+}
+
+/// Bandingkan broadcast vs ring: satu producer per symbol (seperti task feed), latensi publish -> terima
+/// dan jumlah tick yang di-drop. Reader berhenti di Closed setelah semua producer selesai
+pub async fn bench(ticks: u64, consumers: usize, symbols: usize, capacity: usize, rate: u64) {
+    let symbols = bench_symbols(symbols);
+    let per_symbol = ticks / symbols.len() as u64;
+    let total = per_symbol * symbols.len() as u64;
+    let rate_s = if rate == 0 { "max".to_string() } else { format!("{rate}/s per symbol") };
+    println!("md bus bench: ticks={total} symbols={} consumers={consumers} capacity={capacity} rate={rate_s}", symbols.len());
+
+    // tokio broadcast (bus `md` bersama, semua feed satu Sender)
+    {
+        let (tx, _) = broadcast::channel::<MdTick>(capacity);
+        let handles: Vec<_> = (0..consumers)
+            .map(|_| {
+                let mut rx = tx.subscribe();
+                tokio::spawn(async move {
+                    let mut r = BenchResult::default();
+                    loop {
+                        match rx.recv().await {
+                            Ok(md) => r.on_tick(md.ts_ns),
+                            Err(RecvError::Lagged(n)) => r.lagged += n,
+                            Err(RecvError::Closed) => break,
+                        }
+                    }
+                    r
+                })
+            })
+            .collect();
+        let start = Instant::now();
+        let producers: Vec<_> = symbols
+            .iter()
+            .map(|s| {
+                let (tx, s) = (tx.clone(), s.clone());
+                tokio::spawn(produce(s, per_symbol, rate, move |t| {
+                    let _ = tx.send(t);
+                }))
+            })
+            .collect();
+        drop(tx);
+        for p in producers {
+            let _ = p.await;
+        }
+        let mut results = Vec::new();
+        for h in handles {
+            results.push(h.await.unwrap_or_default());
+        }
+        summary("broadcast", total, results, start.elapsed().as_secs_f64());
+    }
+
+    // ring SPMC per symbol
+    {
+        let ring = MdRing::new(&symbols, capacity);
+        let handles: Vec<_> = (0..consumers)
+            .map(|_| {
+                let mut rx = ring.subscribe();
+                tokio::spawn(async move {
+                    let mut r = BenchResult::default();
+                    loop {
+                        match rx.recv().await {
+                            Ok(t) => r.on_tick(t.tick.ts_ns),
+                            Err(RecvError::Lagged(n)) => r.lagged += n,
+                            Err(RecvError::Closed) => break,
+                        }
+                    }
+                    r
+                })
+            })
+            .collect();
+        let start = Instant::now();
+        let producers: Vec<_> = symbols
+            .iter()
+            .filter_map(|s| ring.publisher(s, 1).map(|p| (s.clone(), p)))
+            .map(|(s, mut p)| tokio::spawn(produce(s, per_symbol, rate, move |t| p.publish(&t, None))))
+            .collect();
+        drop(ring);
+        for p in producers {
+            let _ = p.await;
+        }
+        let mut results = Vec::new();
+        for h in handles {
+            results.push(h.await.unwrap_or_default());
+        }
+        summary("ring", total, results, start.elapsed().as_secs_f64());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tick(symbol: &str, i: u64) -> MdTick {
+        MdTick {
+            ts_ns: -(i as i128) * 1_000_000_007,
+            symbol: symbol.into(),
+            best_bid: i as i64,
+            best_ask: i as i64 + 1,
+            bid_qty: 3 * i as i64,
+            ask_qty: 5,
+            seq: i,
+            exch_ts_ns: i as i128 * 1_000_000_000_000,
+        }
+    }
+
+    fn seq(r: Result<EnrichedTick, TryRecvError>) -> Result<(String, u64), TryRecvError> {
+        r.map(|t| (t.tick.symbol, t.tick.seq))
+    }
+
+    #[test]
+    fn ring_delivers_lags_and_closes() {
+        let syms = ["BTCUSDT".to_string(), "ETHUSDT".to_string()];
+        let ring = MdRing::new(&syms, 4);
+        let (mut btc, mut eth) = (ring.publisher("BTCUSDT", 1).unwrap(), ring.publisher("ETHUSDT", 1).unwrap());
+        // satu publisher per symbol
+        assert!(ring.publisher("BTCUSDT", 1).is_none());
+        let mut rx = ring.subscribe();
+        assert!(matches!(rx.try_recv(), Err(TryRecvError::Empty)));
+
+        for i in 1..=3 {
+            btc.publish(&tick("BTCUSDT", i), None);
+        }
+        eth.publish(&tick("ETHUSDT", 1), None);
+        // ring dibaca bergiliran, urutan per symbol terjaga
+        let got: Vec<_> = (0..4).map(|_| seq(rx.try_recv()).unwrap()).collect();
+        let want = [("BTCUSDT", 1), ("ETHUSDT", 1), ("BTCUSDT", 2), ("BTCUSDT", 3)];
+        assert_eq!(got, want.map(|(s, n)| (s.to_string(), n)));
+        let t = tick("BTCUSDT", 3);
+        btc.publish(&t, None);
+        let back = rx.try_recv().unwrap().tick;
+        assert_eq!((back.ts_ns, back.exch_ts_ns, back.bid_qty), (t.ts_ns, t.exch_ts_ns, t.bid_qty));
+
+        // tertinggal > kapasitas: lompat ke tick tertua yang masih ada
+        for i in 10..16 {
+            btc.publish(&tick("BTCUSDT", i), None);
+        }
+        assert!(matches!(rx.try_recv(), Err(TryRecvError::Lagged(2))));
+        assert_eq!(seq(rx.try_recv()).unwrap(), ("BTCUSDT".to_string(), 12));
+
+        // publisher di-drop tapi handle bus masih ada: belum tertutup
+        drop((btc, eth));
+        let mut btc = ring.publisher("BTCUSDT", 1).unwrap();
+        btc.publish(&tick("BTCUSDT", 20), None);
+        drop((btc, ring));
+        // sisa tick tetap terbaca sebelum Closed
+        let rest: Vec<u64> = std::iter::from_fn(|| rx.try_recv().ok()).map(|t| t.tick.seq).collect();
+        assert_eq!(rest, [13, 14, 15, 20]);
+        assert!(matches!(rx.try_recv(), Err(TryRecvError::Closed)));
+    }
+
+    #[test]
+    fn concurrent_readers_never_see_torn_ticks() {
+        const N: u64 = 200_000;
+        let ring = MdRing::new(&["BTCUSDT".to_string()], 64);
+        let readers: Vec<_> = (0..3)
+            .map(|_| {
+                let mut rx = ring.subscribe();
+                std::thread::spawn(move || {
+                    let (mut last, mut got) = (0u64, 0u64);
+                    loop {
+                        match rx.try_recv() {
+                            Ok(t) => {
+                                let want = tick("BTCUSDT", t.tick.seq);
+                                let md = &t.tick;
+                                assert_eq!((md.ts_ns, md.best_bid, md.best_ask, md.bid_qty), (want.ts_ns, want.best_bid, want.best_ask, want.bid_qty));
+                                assert_eq!(md.exch_ts_ns, want.exch_ts_ns);
+                                assert!(md.seq > last);
+                                (last, got) = (md.seq, got + 1);
+                            }
+                            Err(TryRecvError::Lagged(_)) | Err(TryRecvError::Empty) => std::hint::spin_loop(),
+                            Err(TryRecvError::Closed) => return (last, got),
+                        }
+                    }
+                })
+            })
+            .collect();
+        let mut publisher = ring.publisher("BTCUSDT", 1).unwrap();
+        drop(ring);
+        for i in 1..=N {
+            publisher.publish(&tick("BTCUSDT", i), None);
+        }
+        drop(publisher);
+        for r in readers {
+            let (last, got) = r.join().unwrap();
+            assert_eq!(last, N);
+            assert!(got > 0);
+        }
+    }
+}
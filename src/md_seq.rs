@@ -0,0 +1,57 @@
+// ===============================
+// src/md_seq.rs
+// ===============================
+//
+// Sequencing bus MD:
+// - feed memberi `MdTick.seq` monotonic per symbol tepat sebelum publish (`stamp`).
+//   Counter global, jadi tetap naik walau task feed di-restart supervisor.
+// - consumer memasang `GapDetector`; lompatan seq (broadcast Lagged, drop chaos, link IPC putus)
+//   dihitung di `md_gaps_total{symbol,consumer}` — kehilangan data tidak lagi diam-diam.
+//
+// seq 0 = tidak diketahui (rekaman lama) -> tidak dicek.
+//
+use ahash::AHashMap as HashMap;
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+
+use crate::domain::MdTick;
+use crate::metrics::MD_GAPS;
+
+static SEQ: Lazy<Mutex<HashMap<String, u64>>> = Lazy::new(Default::default);
+
+/// Beri nomor urut berikutnya untuk symbol tick ini
+pub fn stamp(tick: &mut MdTick) {
+    let mut g = SEQ.lock().unwrap();
+    let seq = g.entry(tick.symbol.clone()).or_insert(0);
+    *seq += 1;
+    tick.seq = *seq;
+}
+
+/// Detektor gap sisi consumer (satu per task penerima)
+pub struct GapDetector {
+    consumer: String,
+    last: HashMap<String, u64>,
+}
+
+impl GapDetector {
+    pub fn new(consumer: impl Into<String>) -> Self {
+        Self { consumer: consumer.into(), last: HashMap::new() }
+    }
+
+    /// Catat tick; kembalikan jumlah tick yang hilang sebelum tick ini
+    pub fn observe(&mut self, md: &MdTick) -> u64 {
+        if md.seq == 0 {
+            return 0;
+        }
+        let prev = self.last.insert(md.symbol.clone(), md.seq).unwrap_or(0);
+        // seq mundur = sumber mulai ulang (mis. proses feed restart): anggap baseline baru
+        if prev == 0 || md.seq <= prev {
+            return 0;
+        }
+        let gap = md.seq - prev - 1;
+        if gap > 0 {
+            MD_GAPS.with_label_values(&[crate::metrics::symbol_label(&md.symbol), &self.consumer]).inc_by(gap);
+        }
+        gap
+    }
+}
@@ -0,0 +1,266 @@
+// ===============================
+// src/metric_alarms.rs
+// ===============================
+//
+// Alarm internal dari laju (rate-of-change) metrik kunci — bukan sekadar export ke Prometheus:
+// degradasi feed yang "diam" (tick melambat, strategi berhenti mengirim signal, reject naik) ketahuan
+// sebelum terlihat di PnL, juga saat tidak ada Alertmanager.
+//
+// Metrik yang diawasi (laju per detik, disampel tiap METRIC_ALARM_SAMPLE_SECS dari counter):
+//   ticks   : ticks_total                                   (alarm turun & naik)
+//   signals : signals_total                                 (alarm turun & naik)
+//   rejects : risk_rejects_total + exec_reports_total{status=rejected}   (alarm naik saja)
+//
+// Baseline bergulir per metrik = EWMA mean & varians laju, alpha = SAMPLE / BASELINE_SECS.
+// Alarm jika |laju - mean| > Z * stdev DAN deviasi relatif > MIN_DEV_PCT, berturut-turut CONSECUTIVE sampel
+// (setelah WARMUP sampel). Selama alarm baseline dibekukan (level buruk tidak "dipelajari");
+// alarm yang bertahan lebih dari BASELINE_SECS dianggap level baru -> baseline dipelajari ulang.
+// Saat alarm: log warn + alert `metric_alarm` (alerts.rs) + gauge metric_alarm_active{metric}.
+//
+// ENV:
+//   METRIC_ALARMS=ticks,signals,rejects   (kosong = nonaktif)
+//   METRIC_ALARM_SAMPLE_SECS=10
+//   METRIC_ALARM_BASELINE_SECS=900
+//   METRIC_ALARM_Z=4
+//   METRIC_ALARM_MIN_DEV_PCT=50
+//   METRIC_ALARM_CONSECUTIVE=3
+//   METRIC_ALARM_WARMUP=30                (sampel sebelum alarm boleh aktif)
+//
+use prometheus::core::Collector;
+use serde::Serialize;
+use tokio::time::{interval, Duration, MissedTickBehavior};
+use tracing::{info, warn};
+
+use crate::alerts::{AlertKind, Alerts};
+use crate::metrics::{EXECS, METRIC_ALARMS, METRIC_ALARM_ACTIVE, RISK_REJECTS, SIGNALS, TICKS};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AlarmCfg {
+    pub metrics: Vec<String>,
+    pub sample_secs: u64,
+    pub baseline_secs: u64,
+    pub z: f64,
+    pub min_dev_pct: f64,
+    pub consecutive: u32,
+    pub warmup: u32,
+}
+
+impl Default for AlarmCfg {
+    fn default() -> Self {
+        Self { metrics: Vec::new(), sample_secs: 10, baseline_secs: 900, z: 4.0, min_dev_pct: 50.0, consecutive: 3, warmup: 30 }
+    }
+}
+
+impl AlarmCfg {
+    pub fn from_env() -> Self {
+        let d = Self::default();
+        let var = |k: &str| std::env::var(k).unwrap_or_default();
+        Self {
+            metrics: var("METRIC_ALARMS").split(',').map(|s| s.trim().to_ascii_lowercase()).filter(|s| !s.is_empty()).collect(),
+            sample_secs: var("METRIC_ALARM_SAMPLE_SECS").parse().ok().filter(|&s| s > 0).unwrap_or(d.sample_secs),
+            baseline_secs: var("METRIC_ALARM_BASELINE_SECS").parse().ok().filter(|&s| s > 0).unwrap_or(d.baseline_secs),
+            z: var("METRIC_ALARM_Z").parse().unwrap_or(d.z),
+            min_dev_pct: var("METRIC_ALARM_MIN_DEV_PCT").parse().unwrap_or(d.min_dev_pct),
+            consecutive: var("METRIC_ALARM_CONSECUTIVE").parse().ok().filter(|&n| n > 0).unwrap_or(d.consecutive),
+            warmup: var("METRIC_ALARM_WARMUP").parse().unwrap_or(d.warmup),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        !self.metrics.is_empty()
+    }
+}
+
+/// Nama metrik yang dikenal (METRIC_ALARMS)
+pub const METRICS: &[&str] = &["ticks", "signals", "rejects"];
+
+/// Nilai counter kumulatif metrik
+fn read(metric: &str) -> f64 {
+    match metric {
+        "ticks" => TICKS.get() as f64,
+        "signals" => SIGNALS.get() as f64,
+        "rejects" => {
+            let risk: f64 = RISK_REJECTS.collect().iter().flat_map(|f| f.get_metric()).map(|m| m.get_counter().get_value()).sum();
+            let exec: f64 = EXECS
+                .collect()
+                .iter()
+                .flat_map(|f| f.get_metric())
+                .filter(|m| m.get_label().iter().any(|l| l.get_name() == "status" && l.get_value() == "rejected"))
+                .map(|m| m.get_counter().get_value())
+                .sum();
+            risk + exec
+        }
+        _ => 0.0,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Drop,
+    Spike,
+}
+
+impl Direction {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Drop => "drop",
+            Direction::Spike => "spike",
+        }
+    }
+}
+
+/// Transisi status alarm satu sampel
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Transition {
+    Fired { dir: Direction, rate: f64, mean: f64 },
+    Cleared { rate: f64 },
+}
+
+/// Baseline EWMA + status alarm satu metrik
+#[derive(Debug, Clone)]
+struct Detector {
+    alpha: f64,
+    mean: f64,
+    var: f64,
+    samples: u32,
+    streak: u32,
+    active: Option<Direction>,
+    active_samples: u32,
+    spikes_only: bool,
+}
+
+impl Detector {
+    fn new(cfg: &AlarmCfg, spikes_only: bool) -> Self {
+        let alpha = (cfg.sample_secs as f64 / cfg.baseline_secs as f64).clamp(0.001, 1.0);
+        Self { alpha, mean: 0.0, var: 0.0, samples: 0, streak: 0, active: None, active_samples: 0, spikes_only }
+    }
+
+    fn learn(&mut self, rate: f64) {
+        if self.samples == 0 {
+            self.mean = rate;
+        } else {
+            let d = rate - self.mean;
+            self.mean += self.alpha * d;
+            self.var = (1.0 - self.alpha) * (self.var + self.alpha * d * d);
+        }
+        self.samples += 1;
+    }
+
+    /// Arah deviasi jika `rate` di luar band baseline
+    fn deviation(&self, cfg: &AlarmCfg, rate: f64) -> Option<Direction> {
+        let d = rate - self.mean;
+        let rel = if self.mean > 0.0 { d.abs() / self.mean * 100.0 } else if d.abs() > 0.0 { f64::INFINITY } else { 0.0 };
+        let dir = if d < 0.0 { Direction::Drop } else { Direction::Spike };
+        (d.abs() > cfg.z * self.var.sqrt() && rel > cfg.min_dev_pct && !(self.spikes_only && dir == Direction::Drop)).then_some(dir)
+    }
+
+    fn observe(&mut self, cfg: &AlarmCfg, rate: f64) -> Option<Transition> {
+        if self.samples < cfg.warmup.max(1) {
+            self.learn(rate);
+            return None;
+        }
+        let dev = self.deviation(cfg, rate);
+        match (self.active, dev) {
+            (Some(_), Some(_)) => {
+                self.active_samples += 1;
+                // alarm bertahan > BASELINE_SECS: level baru, pelajari ulang
+                if self.active_samples as u64 * cfg.sample_secs > cfg.baseline_secs {
+                    *self = Self::new(cfg, self.spikes_only);
+                    self.learn(rate);
+                    return Some(Transition::Cleared { rate });
+                }
+                None
+            }
+            (Some(_), None) => {
+                (self.active, self.active_samples, self.streak) = (None, 0, 0);
+                self.learn(rate);
+                Some(Transition::Cleared { rate })
+            }
+            (None, Some(dir)) => {
+                self.streak += 1;
+                if self.streak < cfg.consecutive {
+                    return None;
+                }
+                (self.active, self.active_samples) = (Some(dir), 0);
+                Some(Transition::Fired { dir, rate, mean: self.mean })
+            }
+            (None, None) => {
+                self.streak = 0;
+                self.learn(rate);
+                None
+            }
+        }
+    }
+}
+
+/// Task: sampel laju metrik, bandingkan dengan baseline, kirim alert saat menyimpang
+pub async fn run(cfg: AlarmCfg, alerts: Alerts) {
+    let metrics: Vec<&'static str> = METRICS.iter().copied().filter(|m| cfg.metrics.iter().any(|c| c == m)).collect();
+    let mut state: Vec<(&str, Detector, f64)> = metrics.iter().map(|&m| (m, Detector::new(&cfg, m == "rejects"), read(m))).collect();
+    let mut tick = interval(Duration::from_secs(cfg.sample_secs));
+    tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    tick.tick().await;
+    info!(?metrics, sample_secs = cfg.sample_secs, baseline_secs = cfg.baseline_secs, "metric alarms: started");
+    loop {
+        tick.tick().await;
+        for (metric, det, last) in state.iter_mut() {
+            let now = read(metric);
+            let rate = (now - *last).max(0.0) / cfg.sample_secs as f64;
+            *last = now;
+            match det.observe(&cfg, rate) {
+                Some(Transition::Fired { dir, rate, mean }) => {
+                    let dev_pct = if mean > 0.0 { (rate - mean) / mean * 100.0 } else { 100.0 };
+                    warn!(metric = *metric, direction = dir.as_str(), rate, baseline = mean, dev_pct, "metric alarm");
+                    METRIC_ALARMS.with_label_values(&[metric, dir.as_str()]).inc();
+                    METRIC_ALARM_ACTIVE.with_label_values(&[metric]).set(1);
+                    alerts.notify(
+                        AlertKind::MetricAlarm,
+                        &[
+                            ("metric", metric.to_string()),
+                            ("direction", dir.as_str().to_string()),
+                            ("rate", format!("{rate:.3}")),
+                            ("baseline", format!("{mean:.3}")),
+                            ("deviation_pct", format!("{dev_pct:.0}")),
+                            ("window_secs", (cfg.sample_secs * cfg.consecutive as u64).to_string()),
+                        ],
+                    );
+                }
+                Some(Transition::Cleared { rate }) => {
+                    info!(metric = *metric, rate, "metric alarm cleared");
+                    METRIC_ALARM_ACTIVE.with_label_values(&[metric]).set(0);
+                }
+                None => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fires_on_sustained_drop_and_clears_on_recovery() {
+        let cfg = AlarmCfg { sample_secs: 10, baseline_secs: 100, warmup: 5, consecutive: 2, ..Default::default() };
+        let mut d = Detector::new(&cfg, false);
+        for i in 0..20 {
+            assert_eq!(d.observe(&cfg, 100.0 + (i % 3) as f64), None);
+        }
+        // satu sampel jelek belum cukup
+        assert_eq!(d.observe(&cfg, 10.0), None);
+        match d.observe(&cfg, 5.0) {
+            Some(Transition::Fired { dir: Direction::Drop, mean, .. }) => assert!((mean - 101.0).abs() < 2.0),
+            t => panic!("expected drop alarm, got {t:?}"),
+        }
+        assert_eq!(d.observe(&cfg, 5.0), None);
+        assert_eq!(d.observe(&cfg, 100.0), Some(Transition::Cleared { rate: 100.0 }));
+
+        // rejects: hanya kenaikan yang dialarm
+        let mut r = Detector::new(&cfg, true);
+        for _ in 0..10 {
+            r.observe(&cfg, 2.0);
+        }
+        assert_eq!((r.observe(&cfg, 0.0), r.observe(&cfg, 0.0)), (None, None));
+        assert!(matches!((r.observe(&cfg, 30.0), r.observe(&cfg, 30.0)), (None, Some(Transition::Fired { dir: Direction::Spike, .. }))));
+    }
+}
@@ -2,13 +2,15 @@
 // src/metrics.rs
 // ===============================
 use once_cell::sync::Lazy;
+use once_cell::sync::OnceCell;
+use prometheus::core::Collector;
 use prometheus::{
-    Encoder, Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts,
-    Registry, TextEncoder,
+    Gauge, GaugeVec, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts,
+    Registry,
 };
-use std::io::{Read, Write};
-use std::net::{TcpListener, TcpStream};
-use std::thread;
+#[cfg(any(feature = "admin-api", feature = "pushgateway"))]
+use prometheus::{Encoder, TextEncoder};
+use std::sync::Mutex;
 
 // Single custom registry (we register everything here)
 pub static REGISTRY: Lazy<Registry> = Lazy::new(Registry::new);
@@ -25,6 +27,38 @@ pub static TICKS_BY_SYMBOL: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+pub static MD_FILTERED: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("md_filtered_total", "MD ticks dropped by the data-quality filter (labels: symbol, reason = zero|crossed|jump)"),
+        &["symbol", "reason"],
+    )
+    .unwrap()
+});
+
+pub static MD_ANOMALIES: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("md_anomalies_total", "suspicious quote patterns detected (labels: symbol, kind = flicker|spread_oscillation)"),
+        &["symbol", "kind"],
+    )
+    .unwrap()
+});
+
+pub static MD_ANOMALY_ACTIVE: Lazy<IntGaugeVec> = Lazy::new(|| {
+    IntGaugeVec::new(
+        Opts::new("md_anomaly_active", "1 while the symbol is flagged by the MD anomaly detector (MD_ANOMALY_HOLD_SECS)"),
+        &["symbol"],
+    )
+    .unwrap()
+});
+
+pub static MD_GAPS: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("md_gaps_total", "MD ticks missed by a consumer, from seq gaps (labels: symbol, consumer)"),
+        &["symbol", "consumer"],
+    )
+    .unwrap()
+});
+
 pub static SIGNALS: Lazy<IntCounter> =
     Lazy::new(|| IntCounter::new("signals_total", "strategy signals").unwrap());
 
@@ -52,10 +86,66 @@ pub static EXECS: Lazy<IntCounterVec> = Lazy::new(|| {
 
 // Latency from signal -> ack (milliseconds)
 pub static LAT_SIG_ACK: Lazy<Histogram> = Lazy::new(|| {
-    Histogram::with_opts(HistogramOpts::new(
-        "latency_signal_to_ack_ms",
-        "Latency from signal to ack (ms)",
-    ))
+    Histogram::with_opts(
+        HistogramOpts::new("latency_signal_to_ack_ms", "Latency from signal to ack (ms)")
+            .buckets(vec![0.5, 1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0]),
+    )
+    .unwrap()
+});
+
+// Latensi jaringan: terima di engine - waktu event exchange (E), ms
+pub static MD_NET_DELAY: Lazy<Histogram> = Lazy::new(|| {
+    Histogram::with_opts(
+        HistogramOpts::new("md_network_delay_ms", "MD receive time minus exchange event time (ms)")
+            .buckets(vec![1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 250.0, 500.0, 1000.0]),
+    )
+    .unwrap()
+});
+
+pub static EXEC_NET_DELAY: Lazy<HistogramVec> = Lazy::new(|| {
+    HistogramVec::new(
+        HistogramOpts::new("exec_network_delay_ms", "exec report receive time minus exchange event time (ms)")
+            .buckets(vec![1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 250.0, 500.0, 1000.0]),
+        &["venue"],
+    )
+    .unwrap()
+});
+
+// Latensi simulasi gateway mock per venue (kind = order | fill)
+pub static SIM_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
+    HistogramVec::new(
+        HistogramOpts::new("sim_latency_ms", "sampled mock venue latency (order = recv->ack, fill = ack->match) (ms)")
+            .buckets(vec![0.5, 1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 250.0, 500.0]),
+        &["venue", "kind"],
+    )
+    .unwrap()
+});
+
+// Slippage simulasi gateway mock per fill (bps dari harga fill sebelum slippage)
+pub static SIM_SLIPPAGE_BPS: Lazy<HistogramVec> = Lazy::new(|| {
+    HistogramVec::new(
+        HistogramOpts::new("sim_slippage_bps", "slippage applied by the mock venue per fill (bps)")
+            .buckets(vec![0.0, 0.5, 1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0]),
+        &["symbol"],
+    )
+    .unwrap()
+});
+
+// Post-trade markout (ticks per unit, positif = fill menguntungkan)
+pub static MARKOUT_TICKS: Lazy<HistogramVec> = Lazy::new(|| {
+    HistogramVec::new(
+        HistogramOpts::new("markout_ticks", "per-fill markout vs mid after horizon (ticks)")
+            .buckets(vec![-50.0, -20.0, -10.0, -5.0, -2.0, -1.0, 0.0, 1.0, 2.0, 5.0, 10.0, 20.0, 50.0]),
+        &["horizon", "strategy"],
+    )
+    .unwrap()
+});
+
+pub static MARKOUT_AVG_BY_VENUE: Lazy<GaugeVec> = Lazy::new(|| {
+    GaugeVec::new(
+        Opts::new("markout_avg_ticks_by_venue", "qty-weighted average markout per venue (ticks)"),
+        &["horizon", "venue"],
+    )
     .unwrap()
 });
 
@@ -82,6 +172,14 @@ pub static PNL_REALIZED: Lazy<IntGauge> =
 pub static PNL_UNREALIZED: Lazy<IntGauge> =
     Lazy::new(|| IntGauge::new("pnl_unrealized", "unrealized PnL (ticks)").unwrap());
 
+pub static PNL_REALIZED_BY_STRATEGY: Lazy<IntGaugeVec> = Lazy::new(|| {
+    IntGaugeVec::new(
+        Opts::new("pnl_realized_by_strategy", "realized PnL per strategy (ticks)"),
+        &["symbol", "strategy"],
+    )
+    .unwrap()
+});
+
 // -------- Binance user-data stream health (optional, used by gateway_binance) --------
 pub static BIN_WS_CONNECTED: Lazy<IntGaugeVec> = Lazy::new(|| {
     IntGaugeVec::new(
@@ -105,6 +203,64 @@ pub static BIN_WS_RECONNECTS: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+pub static ORDERS_INFLIGHT: Lazy<IntGaugeVec> = Lazy::new(|| {
+    IntGaugeVec::new(
+        Opts::new("orders_inflight", "order submits awaiting venue response (label: venue)"),
+        &["venue"],
+    )
+    .unwrap()
+});
+
+pub static OPEN_ORDERS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    IntGaugeVec::new(
+        Opts::new("open_orders", "child orders sent to a venue and not yet terminal (oms.rs WorkingOrders)"),
+        &["symbol", "venue"],
+    )
+    .unwrap()
+});
+
+pub static WORKING_NOTIONAL: Lazy<GaugeVec> = Lazy::new(|| {
+    GaugeVec::new(
+        Opts::new("working_notional", "unfilled notional of open orders, USD (remaining qty x limit price)"),
+        &["symbol"],
+    )
+    .unwrap()
+});
+
+pub static BIN_ORDER_POLLS: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "binance_order_polls_total",
+            "GET /api/v3/order status polls while userDataStream is down (outcome: updated|unchanged|error)",
+        ),
+        &["venue", "outcome"],
+    )
+    .unwrap()
+});
+
+pub static BINANCE_ERRORS: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "binance_errors_total",
+            "Binance API errors by code (class: retryable|fatal|resync)",
+        ),
+        &["code", "class"],
+    )
+    .unwrap()
+});
+
+pub static DEAD_LETTERS: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("dead_letters_total", "undeliverable events sent to the dead-letter queue (labels: kind, reason)"),
+        &["kind", "reason"],
+    )
+    .unwrap()
+});
+
+pub static EXCHANGE_LOG_DROPPED: Lazy<IntCounter> = Lazy::new(|| {
+    IntCounter::new("exchange_log_dropped_total", "exchange request/response log entries dropped (queue full)").unwrap()
+});
+
 pub static BIN_WS_LAST_EVENT_TS: Lazy<IntGaugeVec> = Lazy::new(|| {
     IntGaugeVec::new(
         Opts::new(
@@ -149,6 +305,543 @@ pub static BIN_LISTEN_KEEPALIVE_ERR: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+// -------- Reconciliation (blotter vs exchange) --------
+pub static RECON_BREAKS: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "reconciliation_breaks_total",
+            "fills missing/divergent between blotter and exchange (labels: kind, symbol)",
+        ),
+        &["kind", "symbol"],
+    )
+    .unwrap()
+});
+
+// -------- Venue health / safe mode --------
+pub static VENUE_SAFE_MODE: Lazy<IntGaugeVec> = Lazy::new(|| {
+    IntGaugeVec::new(
+        Opts::new("venue_safe_mode", "1 if venue is in safe mode (not routable)"),
+        &["venue"],
+    )
+    .unwrap()
+});
+
+pub static VENUE_SAFE_MODE_ENTRIES: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "venue_safe_mode_entries_total",
+            "safe mode activations (labels: venue, reason)",
+        ),
+        &["venue", "reason"],
+    )
+    .unwrap()
+});
+
+// -------- Exec report guard (duplikat / out-of-order) --------
+pub static EXEC_DROPPED: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("exec_reports_dropped_total", "exec reports dropped by the fan-out guard"),
+        &["reason"],
+    )
+    .unwrap()
+});
+
+pub static EXEC_FANOUT_DROPPED: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("exec_fanout_dropped_total", "exec reports not delivered to a full non-critical fan-out consumer (label: consumer)"),
+        &["consumer"],
+    )
+    .unwrap()
+});
+
+// -------- Order expiry (good-till-time) --------
+pub static ORDERS_EXPIRED: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("orders_expired_total", "orders cancelled at their GTT expiry"),
+        &["venue"],
+    )
+    .unwrap()
+});
+
+// -------- Saldo akun (Binance /api/v3/account) --------
+pub static ACCOUNT_BALANCE: Lazy<GaugeVec> = Lazy::new(|| {
+    GaugeVec::new(
+        Opts::new("account_balance", "exchange account balance per asset (labels: asset, state=free|locked)"),
+        &["asset", "state"],
+    )
+    .unwrap()
+});
+
+// -------- Multi-tenant books --------
+pub static BOOK_ORDERS: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("book_orders_total", "signals evaluated by risk per book (labels: book, result=accepted|rejected)"),
+        &["book", "result"],
+    )
+    .unwrap()
+});
+
+pub static BOOK_EXPOSURE: Lazy<GaugeVec> = Lazy::new(|| {
+    GaugeVec::new(
+        Opts::new("book_exposure_notional", "gross notional per book incl. pending orders (quote)"),
+        &["book"],
+    )
+    .unwrap()
+});
+
+pub static PNL_REALIZED_BY_BOOK: Lazy<IntGaugeVec> = Lazy::new(|| {
+    IntGaugeVec::new(
+        Opts::new("pnl_realized_by_book", "realized PnL per book (ticks)"),
+        &["symbol", "book"],
+    )
+    .unwrap()
+});
+
+pub static INV_QTY_BY_BOOK: Lazy<IntGaugeVec> = Lazy::new(|| {
+    IntGaugeVec::new(
+        Opts::new("inventory_qty_by_book", "net position per book (lots)"),
+        &["symbol", "book"],
+    )
+    .unwrap()
+});
+
+// -------- Compliance --------
+// Penolakan risk per alasan (RiskError::reason); symbol / strategi di luar batas label -> "other"
+pub static RISK_REJECTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(Opts::new("risk_rejects_total", "signals rejected by pre-trade risk checks"), &["reason", "symbol", "strategy"]).unwrap()
+});
+
+pub static COMPLIANCE_REJECTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("compliance_rejects_total", "orders rejected by compliance rules (label: rule)"),
+        &["rule"],
+    )
+    .unwrap()
+});
+
+// -------- Symbol registry (exchangeInfo) --------
+pub static SYMBOL_TRADABLE: Lazy<IntGaugeVec> = Lazy::new(|| {
+    IntGaugeVec::new(
+        Opts::new("symbol_tradable", "1 if exchangeInfo reports the symbol as TRADING (label: symbol)"),
+        &["symbol"],
+    )
+    .unwrap()
+});
+
+// -------- Margin (TRADING_MODE=margin) --------
+pub static MARGIN_POSITION: Lazy<GaugeVec> = Lazy::new(|| {
+    GaugeVec::new(
+        Opts::new("margin_position", "margin per position in quote (labels: symbol, kind=initial|maintenance)"),
+        &["symbol", "kind"],
+    )
+    .unwrap()
+});
+
+pub static MARGIN_USAGE_PCT: Lazy<GaugeVec> = Lazy::new(|| {
+    GaugeVec::new(
+        Opts::new("margin_usage_pct", "margin used as % of MARGIN_EQUITY (label: kind=initial|maintenance)"),
+        &["kind"],
+    )
+    .unwrap()
+});
+
+pub static LIQUIDATION_PRICE: Lazy<GaugeVec> = Lazy::new(|| {
+    GaugeVec::new(
+        Opts::new("liquidation_price", "estimated liquidation price per position (0 = flat)"),
+        &["symbol"],
+    )
+    .unwrap()
+});
+
+// -------- IPC (mode multi-proses) --------
+pub static IPC_CONNECTED: Lazy<IntGauge> = Lazy::new(|| {
+    IntGauge::new("ipc_connected", "1 if feed<->exec IPC link is connected").unwrap()
+});
+
+pub static IPC_DROPPED: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("ipc_dropped_total", "messages dropped on the IPC link (label: kind=md|sig)"),
+        &["kind"],
+    )
+    .unwrap()
+});
+
+// -------- Chaos testing (hanya dengan --features chaos) --------
+#[cfg(feature = "chaos")]
+pub static CHAOS_INJECTIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("chaos_injections_total", "failures injected by the chaos module (labels: point, kind)"),
+        &["point", "kind"],
+    )
+    .unwrap()
+});
+
+// -------- Supervisor --------
+pub static TASK_RESTARTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("task_restarts_total", "supervised task restarts after exit/panic (label: task)"),
+        &["task"],
+    )
+    .unwrap()
+});
+
+// ---- Quote throttle (churn limiter router -> gateway) ----
+pub static QUOTES_QUEUED: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("quotes_throttled_total", "orders held back by the per-symbol churn limiter (label: venue)"),
+        &["venue"],
+    )
+    .unwrap()
+});
+
+pub static QUOTES_COALESCED: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("quotes_coalesced_total", "queued quotes superseded by a newer quote (label: venue)"),
+        &["venue"],
+    )
+    .unwrap()
+});
+
+// ---- Kill switch / cancel-all ----
+pub static KILL_SWITCH: Lazy<IntGauge> =
+    Lazy::new(|| IntGauge::new("kill_switch_engaged", "1 if kill switch engaged (new orders blocked)").unwrap());
+
+pub static PORTFOLIO_STOP: Lazy<IntGauge> =
+    Lazy::new(|| IntGauge::new("portfolio_stop_tripped", "1 if the portfolio drawdown stop tripped (trading locked until re-arm)").unwrap());
+
+pub static PORTFOLIO_DRAWDOWN_USD: Lazy<Gauge> =
+    Lazy::new(|| Gauge::new("portfolio_drawdown_usd", "portfolio equity drawdown from the session high (USD)").unwrap());
+
+// ---- Hari trading ----
+pub static DAILY_PNL_USD: Lazy<Gauge> =
+    Lazy::new(|| Gauge::new("daily_pnl_usd", "portfolio PnL since the current trading day opened (USD)").unwrap());
+
+pub static DAILY_FILLS: Lazy<IntGauge> =
+    Lazy::new(|| IntGauge::new("daily_fills", "fills in the current trading day").unwrap());
+
+pub static TRADING_DAY_ROLLOVERS: Lazy<IntCounter> =
+    Lazy::new(|| IntCounter::new("trading_day_rollovers_total", "trading day rollovers (daily record written)").unwrap());
+
+// ---- Alarm laju metrik ----
+pub static METRIC_ALARMS: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("metric_alarms_total", "rate-of-change alarms fired vs the rolling baseline (labels: metric = ticks|signals|rejects, direction = drop|spike)"),
+        &["metric", "direction"],
+    )
+    .unwrap()
+});
+
+pub static METRIC_ALARM_ACTIVE: Lazy<IntGaugeVec> = Lazy::new(|| {
+    IntGaugeVec::new(Opts::new("metric_alarm_active", "1 while the metric's rate is outside its rolling baseline band"), &["metric"]).unwrap()
+});
+
+pub static CANCEL_ALL: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("cancel_all_total", "venue-wide cancel-all commands (label: source = admin|kill|shutdown|other)"),
+        &["source"],
+    )
+    .unwrap()
+});
+
+// ---- OMS: partial-fill timeout ----
+pub static PARTIAL_TIMEOUTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("partial_fill_timeouts_total", "partially filled orders cancelled after PARTIAL_FILL_TIMEOUT_MS (label: venue)"),
+        &["venue"],
+    )
+    .unwrap()
+});
+
+pub static ORDER_REPRICES: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("order_reprices_total", "cancel/replace reprices by the adaptive aggression tactic (label: urgency)"),
+        &["urgency"],
+    )
+    .unwrap()
+});
+
+pub static PARTIAL_REROUTES: Lazy<IntCounter> =
+    Lazy::new(|| IntCounter::new("partial_reroutes_total", "partial-fill remainders re-routed at an updated price").unwrap());
+
+// ---- Router / feed health ----
+pub static ROUTER_REJECTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("router_rejects_total", "orders rejected by the router before routing (label: reason)"),
+        &["reason"],
+    )
+    .unwrap()
+});
+
+pub static ORDERS_PEGGED: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("orders_pegged_total", "orders re-priced to the live quote by the router vs signal price (label: result = improved|unchanged|worse)"),
+        &["result"],
+    )
+    .unwrap()
+});
+
+// Imbalance top-k order book per symbol (book_analytics)
+pub static BOOK_IMBALANCE: Lazy<GaugeVec> = Lazy::new(|| {
+    GaugeVec::new(
+        Opts::new("book_imbalance", "top-k order book imbalance, -1..1 (positive = more bid size)"),
+        &["symbol"],
+    )
+    .unwrap()
+});
+
+pub static MD_LIVE: Lazy<IntGaugeVec> = Lazy::new(|| {
+    IntGaugeVec::new(
+        Opts::new("md_live", "1 if the symbol received market data within FEED_STALE_MS"),
+        &["symbol"],
+    )
+    .unwrap()
+});
+
+// ---- Batas posisi per venue ----
+pub static VENUE_POSITION_CAPPED: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("venue_position_capped_total", "child orders skipped or clipped by VENUE_MAX_POSITION (label: venue)"),
+        &["venue"],
+    )
+    .unwrap()
+});
+
+// ---- Backpressure antrean order venue (router) ----
+pub static VENUE_BACKPRESSURE: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("venue_backpressure_total", "child orders diverted because the venue order queue was full (label: venue)"),
+        &["venue"],
+    )
+    .unwrap()
+});
+
+// ---- Scoreboard performa per strategi ----
+pub static STRATEGY_PNL_WINDOW: Lazy<GaugeVec> = Lazy::new(|| {
+    GaugeVec::new(
+        Opts::new("strategy_pnl_window", "strategy PnL over STRATEGY_EVAL_WINDOW_SECS (quote, incl. unrealized)"),
+        &["strategy"],
+    )
+    .unwrap()
+});
+
+pub static STRATEGY_HIT_RATE: Lazy<GaugeVec> = Lazy::new(|| {
+    GaugeVec::new(
+        Opts::new("strategy_hit_rate", "share of profitable closing trades in the evaluation window"),
+        &["strategy"],
+    )
+    .unwrap()
+});
+
+pub static STRATEGY_DRAWDOWN: Lazy<GaugeVec> = Lazy::new(|| {
+    GaugeVec::new(
+        Opts::new("strategy_drawdown", "max peak-to-trough equity drop in the evaluation window (quote)"),
+        &["strategy"],
+    )
+    .unwrap()
+});
+
+pub static STRATEGY_DISABLED: Lazy<IntGaugeVec> = Lazy::new(|| {
+    IntGaugeVec::new(
+        Opts::new("strategy_disabled", "1 if the strategy was auto-disabled by the scoreboard"),
+        &["strategy"],
+    )
+    .unwrap()
+});
+
+// ---- Bridge strategi Python ----
+pub static PY_STRATEGY_TIMEOUTS: Lazy<IntCounter> = Lazy::new(|| {
+    IntCounter::new("py_strategy_timeouts_total", "python strategy replies not received within PY_STRATEGY_TIMEOUT_MS").unwrap()
+});
+
+pub static PY_STRATEGY_DROPPED: Lazy<IntCounter> = Lazy::new(|| {
+    IntCounter::new("py_strategy_dropped_total", "ticks not sent to the python strategy because a reply was pending").unwrap()
+});
+
+// Pipeline per symbol: tick dibuang karena channel penuh (stage = dispatch | strategy)
+pub static PIPELINE_DROPPED: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("pipeline_dropped_total", "ticks dropped by the per-symbol pipeline because a channel was full"),
+        &["symbol", "stage"],
+    )
+    .unwrap()
+});
+
+// Kurs silang aset -> USD (fx.rs) & PnL portofolio USD-ekuivalen
+pub static FX_RATE_USD: Lazy<GaugeVec> = Lazy::new(|| {
+    GaugeVec::new(Opts::new("fx_rate_usd", "cross rate asset -> USD (stablecoins = 1)"), &["asset"]).unwrap()
+});
+pub static PNL_PORTFOLIO_USD: Lazy<GaugeVec> = Lazy::new(|| {
+    GaugeVec::new(Opts::new("pnl_portfolio_usd", "portfolio PnL across all symbols in USD equivalent (kind: realized|unrealized)"), &["kind"]).unwrap()
+});
+
+// Guard anti-overtrading: strategi di-mute per symbol karena burst order searah (cluster_guard.rs)
+pub static OVERTRADING_MUTES: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(Opts::new("overtrading_mutes_total", "strategy muted for a symbol after a same-direction order burst"), &["strategy"]).unwrap()
+});
+
+// Registry ID order: cl_id hasil generator yang sudah terpakai (dilewati)
+pub static ORDER_ID_COLLISIONS: Lazy<IntCounter> = Lazy::new(|| {
+    IntCounter::new("order_id_collisions_total", "generated cl_ids skipped because they were already registered").unwrap()
+});
+
+// Kedalaman antrean channel internal (channels.rs)
+pub static CHANNEL_DEPTH: Lazy<IntGaugeVec> = Lazy::new(|| {
+    IntGaugeVec::new(Opts::new("channel_depth", "messages queued in an internal channel (sampled every second)"), &["name"]).unwrap()
+});
+
+pub static CHANNEL_CAPACITY: Lazy<IntGaugeVec> = Lazy::new(|| {
+    IntGaugeVec::new(Opts::new("channel_capacity", "configured capacity of an internal channel (CHANNEL_CAPS)"), &["name"]).unwrap()
+});
+
+// Konflasi MD per strategi: tick yang ditimpa quote lebih baru sebelum dikirim
+pub static MD_CONFLATED: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("md_conflated_total", "ticks superseded by a newer quote before delivery (STRATEGY_MAX_TPS)"),
+        &["strategy"],
+    )
+    .unwrap()
+});
+
+// ---- Filter script risk (rhai) ----
+pub static SCRIPT_FILTER_REJECTS: Lazy<IntCounter> = Lazy::new(|| {
+    IntCounter::new("script_filter_rejects_total", "signals rejected by the risk script (RISK_SCRIPT_FILE)").unwrap()
+});
+
+pub static SCRIPT_FILTER_ERRORS: Lazy<IntCounter> = Lazy::new(|| {
+    IntCounter::new("script_filter_errors_total", "risk script compile / runtime errors").unwrap()
+});
+
+// ---- Batas lama memegang posisi ----
+pub static HOLDING_FORCE_EXITS: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("holding_force_exits_total", "force-exit signals for positions held longer than HOLD_MAX_SECS"),
+        &["strategy"],
+    )
+    .unwrap()
+});
+
+// ---- DCA terjadwal ----
+pub static DCA_SIGNALS: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("dca_signals_total", "scheduled DCA buys by symbol and result (sent, stale_quote, queue_full)"),
+        &["symbol", "result"],
+    )
+    .unwrap()
+});
+
+// ---- Webhook signal manual ----
+pub static WEBHOOK_SIGNALS: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("webhook_signals_total", "webhook alerts by result (accepted, unauthorized, invalid, queue_full)"),
+        &["result"],
+    )
+    .unwrap()
+});
+
+// ---- Notifikasi (email) ----
+pub static ALERTS_SENT: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("alerts_total", "alert notifications by kind and result (sent, failed, suppressed)"),
+        &["kind", "result"],
+    )
+    .unwrap()
+});
+
+// ---- Pushgateway ----
+pub static PUSHGATEWAY_FAILURES: Lazy<IntCounter> = Lazy::new(|| {
+    IntCounter::new("pushgateway_push_failures_total", "failed pushes to PUSHGATEWAY_URL").unwrap()
+});
+
+// ---- Order multi-leg ----
+pub static MULTILEG_ORDERS: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "multileg_orders_total",
+            "multi-leg orders (label: result = accepted|rejected|preflight_rejected|filled|broken|unfilled)",
+        ),
+        &["result"],
+    )
+    .unwrap()
+});
+
+pub static MULTILEG_OPEN: Lazy<IntGauge> =
+    Lazy::new(|| IntGauge::new("multileg_open", "multi-leg orders with legs still working").unwrap());
+
+pub static MULTILEG_IMBALANCE: Lazy<Gauge> = Lazy::new(|| {
+    Gauge::new("multileg_imbalance_units", "sum over open multi-leg orders of leg fill imbalance (package units)").unwrap()
+});
+
+pub static MULTILEG_HEDGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("multileg_hedge_orders_total", "legging remediation orders (label: action = chase|unwind)"),
+        &["action"],
+    )
+    .unwrap()
+});
+
+pub static MULTILEG_LEGGING_SECS: Lazy<Histogram> = Lazy::new(|| {
+    Histogram::with_opts(
+        HistogramOpts::new("multileg_legging_seconds", "time a multi-leg order spent with imbalanced legs")
+            .buckets(vec![0.1, 0.25, 0.5, 1.0, 2.0, 5.0, 10.0, 30.0, 60.0, 300.0]),
+    )
+    .unwrap()
+});
+
+pub static MULTILEG_UNWIND_PNL: Lazy<Gauge> = Lazy::new(|| {
+    Gauge::new("multileg_unwind_pnl_quote", "cumulative PnL of unwinding over-filled legs (quote; negative = legging cost)").unwrap()
+});
+
+// ---- Capital allocator ----
+pub static STRATEGY_ALLOCATION: Lazy<GaugeVec> = Lazy::new(|| {
+    GaugeVec::new(
+        Opts::new("strategy_allocation", "share of ALLOC_TOTAL_NOTIONAL allocated to the strategy (quote)"),
+        &["strategy"],
+    )
+    .unwrap()
+});
+
+pub static STRATEGY_SIZE_SCALE: Lazy<GaugeVec> = Lazy::new(|| {
+    GaugeVec::new(
+        Opts::new("strategy_size_scale", "order size multiplier from the capital allocator (1 = equal share)"),
+        &["strategy"],
+    )
+    .unwrap()
+});
+
+// ---- Volatility targeting ----
+pub static PORTFOLIO_VOL: Lazy<Gauge> = Lazy::new(|| {
+    Gauge::new("portfolio_vol_annualized", "estimated annualized portfolio volatility (fraction of VOL_TARGET_EQUITY)").unwrap()
+});
+
+pub static VOL_TARGET_SCALE: Lazy<Gauge> = Lazy::new(|| {
+    Gauge::new("vol_target_scale", "order size multiplier applied to all strategies by volatility targeting").unwrap()
+});
+
+// ---- Reconnect policy (per koneksi jaringan) ----
+pub static CONN_UP: Lazy<IntGaugeVec> = Lazy::new(|| {
+    IntGaugeVec::new(
+        Opts::new("connection_up", "1 if network connection is up (label: conn)"),
+        &["conn"],
+    )
+    .unwrap()
+});
+
+pub static RECONNECT_ATTEMPTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("reconnect_attempts_total", "reconnect attempts per connection (label: conn)"),
+        &["conn"],
+    )
+    .unwrap()
+});
+
+pub static RECONNECT_BACKOFF_MS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    IntGaugeVec::new(
+        Opts::new("reconnect_backoff_ms", "last reconnect backoff delay in ms (label: conn)"),
+        &["conn"],
+    )
+    .unwrap()
+});
+
 // ---- Config visibility (feed / venue / strategies / symbols) ----
 pub static CONFIG_FEED_MODE: Lazy<IntGaugeVec> = Lazy::new(|| {
     IntGaugeVec::new(
@@ -185,40 +878,260 @@ pub static CONFIG_SYMBOL: Lazy<IntGaugeVec> = Lazy::new(|| {
     .unwrap()
 });
 
+// ---- Kardinalitas label ----
+// Label symbol/strategy dibatasi N nilai pertama yang aktif (SYMBOLS dari config masuk lebih dulu);
+// sisanya: counter/histogram digabung ke label "other", gauge tidak diekspor (nilai gauge tidak bisa dijumlah).
+// Family di METRICS_DISABLE tidak didaftarkan ke registry (nama tanpa suffix, mis. "inv_qty,markout_ticks").
+//
+// ENV:
+//   METRICS_MAX_SYMBOLS=0      (0 = tanpa batas)
+//   METRICS_MAX_STRATEGIES=0
+//   METRICS_DISABLE=
+pub const OTHER_LABEL: &str = "other";
+
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct LabelCfg {
+    pub max_symbols: usize,
+    pub max_strategies: usize,
+    pub disabled: Vec<String>,
+}
+
+impl LabelCfg {
+    pub fn from_env() -> Self {
+        let num = |k: &str| std::env::var(k).ok().and_then(|s| s.parse().ok()).unwrap_or(0);
+        Self {
+            max_symbols: num("METRICS_MAX_SYMBOLS"),
+            max_strategies: num("METRICS_MAX_STRATEGIES"),
+            disabled: std::env::var("METRICS_DISABLE")
+                .unwrap_or_default()
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+        }
+    }
+}
+
+static LABEL_CFG: OnceCell<LabelCfg> = OnceCell::new();
+
+#[derive(Default)]
+struct Admitted {
+    symbols: ahash::AHashSet<String>,
+    strategies: ahash::AHashSet<String>,
+}
+
+static ADMITTED: Lazy<Mutex<Admitted>> = Lazy::new(Mutex::default);
+
+fn label_cfg() -> &'static LabelCfg {
+    LABEL_CFG.get_or_init(LabelCfg::default)
+}
+
+/// Set batas label (sekali, sebelum `init`); `seed` = symbol config yang selalu punya series sendiri
+pub fn configure_labels(cfg: LabelCfg, seed: &[String]) {
+    let mut adm = ADMITTED.lock().unwrap();
+    for s in seed.iter().take(if cfg.max_symbols == 0 { usize::MAX } else { cfg.max_symbols }) {
+        adm.symbols.insert(s.clone());
+    }
+    let _ = LABEL_CFG.set(cfg);
+}
+
+fn admit(set: &mut ahash::AHashSet<String>, max: usize, v: &str) -> bool {
+    if max == 0 || set.contains(v) {
+        return true;
+    }
+    if set.len() < max {
+        set.insert(v.to_string());
+        return true;
+    }
+    false
+}
+
+/// true = symbol boleh punya series sendiri (dipakai gauge: false -> jangan set)
+pub fn symbol_admitted(symbol: &str) -> bool {
+    label_cfg().max_symbols == 0 || admit(&mut ADMITTED.lock().unwrap().symbols, label_cfg().max_symbols, symbol)
+}
+
+/// true = strategi boleh punya series sendiri
+pub fn strategy_admitted(strategy: &str) -> bool {
+    label_cfg().max_strategies == 0 || admit(&mut ADMITTED.lock().unwrap().strategies, label_cfg().max_strategies, strategy)
+}
+
+/// Label symbol untuk counter/histogram (di luar batas -> "other")
+pub fn symbol_label(symbol: &str) -> &str {
+    if symbol_admitted(symbol) { symbol } else { OTHER_LABEL }
+}
+
+/// Label strategi untuk counter/histogram (di luar batas -> "other")
+pub fn strategy_label(strategy: &str) -> &str {
+    if strategy_admitted(strategy) { strategy } else { OTHER_LABEL }
+}
+
 pub fn init() {
-    // Register all metrics to the custom registry
-    for m in [
-        REGISTRY.register(Box::new(TICKS.clone())),
-        REGISTRY.register(Box::new(TICKS_BY_SYMBOL.clone())),
-        REGISTRY.register(Box::new(SIGNALS.clone())),
-        REGISTRY.register(Box::new(SIGNALS_BY.clone())),
-        REGISTRY.register(Box::new(ORDERS.clone())),
-        REGISTRY.register(Box::new(EXECS.clone())),
-        REGISTRY.register(Box::new(LAT_SIG_ACK.clone())),
-        REGISTRY.register(Box::new(VENUE_SCORE.clone())),
-        REGISTRY.register(Box::new(INV_QTY.clone())),
-        REGISTRY.register(Box::new(INV_TOTAL_QTY.clone())),
-        REGISTRY.register(Box::new(PNL_REALIZED.clone())),
-        REGISTRY.register(Box::new(PNL_UNREALIZED.clone())),
+    // Register all metrics to the custom registry (kecuali family di METRICS_DISABLE)
+    let collectors: Vec<Box<dyn Collector>> = vec![
+        Box::new(TICKS.clone()),
+        Box::new(TICKS_BY_SYMBOL.clone()),
+        Box::new(MD_GAPS.clone()),
+        Box::new(MD_FILTERED.clone()),
+        Box::new(MD_ANOMALIES.clone()),
+        Box::new(MD_ANOMALY_ACTIVE.clone()),
+        Box::new(SIGNALS.clone()),
+        Box::new(SIGNALS_BY.clone()),
+        Box::new(ORDERS.clone()),
+        Box::new(EXECS.clone()),
+        Box::new(LAT_SIG_ACK.clone()),
+        Box::new(MD_NET_DELAY.clone()),
+        Box::new(EXEC_NET_DELAY.clone()),
+        Box::new(SIM_LATENCY.clone()),
+        Box::new(SIM_SLIPPAGE_BPS.clone()),
+        Box::new(VENUE_SCORE.clone()),
+        Box::new(INV_QTY.clone()),
+        Box::new(INV_TOTAL_QTY.clone()),
+        Box::new(PNL_REALIZED.clone()),
+        Box::new(PNL_UNREALIZED.clone()),
+        Box::new(PNL_REALIZED_BY_STRATEGY.clone()),
+        // Markout
+        Box::new(MARKOUT_TICKS.clone()),
+        Box::new(MARKOUT_AVG_BY_VENUE.clone()),
         // Binance WS health
-        REGISTRY.register(Box::new(BIN_WS_CONNECTED.clone())),
-        REGISTRY.register(Box::new(BIN_WS_RECONNECTS.clone())),
-        REGISTRY.register(Box::new(BIN_WS_LAST_EVENT_TS.clone())),
-        REGISTRY.register(Box::new(BIN_WS_LAST_EVENT_AGE.clone())),
-        REGISTRY.register(Box::new(BIN_LISTEN_KEEPALIVE_OK.clone())),
-        REGISTRY.register(Box::new(BIN_LISTEN_KEEPALIVE_ERR.clone())),
+        Box::new(BIN_WS_CONNECTED.clone()),
+        Box::new(BIN_WS_RECONNECTS.clone()),
+        Box::new(BIN_WS_LAST_EVENT_TS.clone()),
+        Box::new(ORDERS_INFLIGHT.clone()),
+        Box::new(OPEN_ORDERS.clone()),
+        Box::new(WORKING_NOTIONAL.clone()),
+        Box::new(BIN_ORDER_POLLS.clone()),
+        Box::new(BINANCE_ERRORS.clone()),
+        Box::new(EXCHANGE_LOG_DROPPED.clone()),
+        Box::new(DEAD_LETTERS.clone()),
+        Box::new(BIN_WS_LAST_EVENT_AGE.clone()),
+        Box::new(BIN_LISTEN_KEEPALIVE_OK.clone()),
+        Box::new(BIN_LISTEN_KEEPALIVE_ERR.clone()),
+        // Reconciliation
+        Box::new(RECON_BREAKS.clone()),
+        // Venue health
+        Box::new(VENUE_SAFE_MODE.clone()),
+        Box::new(VENUE_SAFE_MODE_ENTRIES.clone()),
+        // Exec guard
+        Box::new(EXEC_DROPPED.clone()),
+        Box::new(EXEC_FANOUT_DROPPED.clone()),
+        // Order expiry
+        Box::new(ORDERS_EXPIRED.clone()),
+        // Account
+        Box::new(ACCOUNT_BALANCE.clone()),
+        // Supervisor
+        Box::new(TASK_RESTARTS.clone()),
+        // Quote throttle
+        Box::new(QUOTES_QUEUED.clone()),
+        Box::new(QUOTES_COALESCED.clone()),
+        // Kill switch
+        Box::new(KILL_SWITCH.clone()),
+        Box::new(CANCEL_ALL.clone()),
+        Box::new(PORTFOLIO_STOP.clone()),
+        Box::new(PORTFOLIO_DRAWDOWN_USD.clone()),
+        Box::new(DAILY_PNL_USD.clone()),
+        Box::new(DAILY_FILLS.clone()),
+        Box::new(TRADING_DAY_ROLLOVERS.clone()),
+        Box::new(METRIC_ALARMS.clone()),
+        Box::new(METRIC_ALARM_ACTIVE.clone()),
+        // OMS
+        Box::new(PARTIAL_TIMEOUTS.clone()),
+        Box::new(PARTIAL_REROUTES.clone()),
+        Box::new(ORDER_REPRICES.clone()),
+        // Router / feed health
+        Box::new(ROUTER_REJECTS.clone()),
+        Box::new(MD_LIVE.clone()),
+        Box::new(BOOK_IMBALANCE.clone()),
+        Box::new(ORDERS_PEGGED.clone()),
+        // Venue position caps
+        Box::new(VENUE_POSITION_CAPPED.clone()),
+        Box::new(VENUE_BACKPRESSURE.clone()),
+        // Strategy scoreboard
+        Box::new(STRATEGY_PNL_WINDOW.clone()),
+        Box::new(STRATEGY_HIT_RATE.clone()),
+        Box::new(STRATEGY_DRAWDOWN.clone()),
+        Box::new(STRATEGY_DISABLED.clone()),
+        Box::new(OVERTRADING_MUTES.clone()),
+        // Python strategy bridge
+        Box::new(PY_STRATEGY_TIMEOUTS.clone()),
+        Box::new(PY_STRATEGY_DROPPED.clone()),
+        // MD conflation
+        Box::new(MD_CONFLATED.clone()),
+        Box::new(PIPELINE_DROPPED.clone()),
+        Box::new(ORDER_ID_COLLISIONS.clone()),
+        Box::new(FX_RATE_USD.clone()),
+        Box::new(PNL_PORTFOLIO_USD.clone()),
+        Box::new(PORTFOLIO_VOL.clone()),
+        Box::new(VOL_TARGET_SCALE.clone()),
+        // Channel internal
+        Box::new(CHANNEL_DEPTH.clone()),
+        Box::new(CHANNEL_CAPACITY.clone()),
+        // Risk script
+        Box::new(SCRIPT_FILTER_REJECTS.clone()),
+        Box::new(SCRIPT_FILTER_ERRORS.clone()),
+        // Webhook
+        Box::new(WEBHOOK_SIGNALS.clone()),
+        // Holding limit
+        Box::new(HOLDING_FORCE_EXITS.clone()),
+        // DCA
+        Box::new(DCA_SIGNALS.clone()),
+        // Alerts
+        Box::new(ALERTS_SENT.clone()),
+        // Pushgateway
+        Box::new(PUSHGATEWAY_FAILURES.clone()),
+        // Multi-leg
+        Box::new(MULTILEG_ORDERS.clone()),
+        Box::new(MULTILEG_OPEN.clone()),
+        Box::new(MULTILEG_IMBALANCE.clone()),
+        Box::new(MULTILEG_HEDGES.clone()),
+        Box::new(MULTILEG_LEGGING_SECS.clone()),
+        Box::new(MULTILEG_UNWIND_PNL.clone()),
+        // Capital allocator
+        Box::new(STRATEGY_ALLOCATION.clone()),
+        Box::new(STRATEGY_SIZE_SCALE.clone()),
+        // Reconnect
+        Box::new(CONN_UP.clone()),
+        Box::new(RECONNECT_ATTEMPTS.clone()),
+        Box::new(RECONNECT_BACKOFF_MS.clone()),
+        // Books
+        Box::new(BOOK_ORDERS.clone()),
+        Box::new(BOOK_EXPOSURE.clone()),
+        Box::new(PNL_REALIZED_BY_BOOK.clone()),
+        Box::new(INV_QTY_BY_BOOK.clone()),
+        // Compliance
+        Box::new(COMPLIANCE_REJECTS.clone()),
+        Box::new(RISK_REJECTS.clone()),
+        // Symbol registry
+        Box::new(SYMBOL_TRADABLE.clone()),
+        // Margin
+        Box::new(MARGIN_POSITION.clone()),
+        Box::new(MARGIN_USAGE_PCT.clone()),
+        Box::new(LIQUIDATION_PRICE.clone()),
+        // IPC
+        Box::new(IPC_CONNECTED.clone()),
+        Box::new(IPC_DROPPED.clone()),
         // Config visibility
-        REGISTRY.register(Box::new(CONFIG_FEED_MODE.clone())),
-        REGISTRY.register(Box::new(CONFIG_VENUE_MODE.clone())),
-        REGISTRY.register(Box::new(CONFIG_STRATEGY_ACTIVE.clone())),
-        REGISTRY.register(Box::new(CONFIG_SYMBOL.clone())),
-    ] {
-        let _ = m;
+        Box::new(CONFIG_FEED_MODE.clone()),
+        Box::new(CONFIG_VENUE_MODE.clone()),
+        Box::new(CONFIG_STRATEGY_ACTIVE.clone()),
+        Box::new(CONFIG_SYMBOL.clone()),
+    ];
+    let disabled = &label_cfg().disabled;
+    for c in collectors {
+        if c.desc().iter().any(|d| disabled.contains(&d.fq_name)) {
+            continue;
+        }
+        let _ = REGISTRY.register(c);
+    }
+    #[cfg(feature = "chaos")]
+    if !disabled.iter().any(|d| d == "chaos_injections_total") {
+        let _ = REGISTRY.register(Box::new(CHAOS_INJECTIONS.clone()));
     }
 }
 
 // Encode all metrics in Prometheus text format
-fn encode_metrics() -> Vec<u8> {
+#[cfg(any(feature = "admin-api", feature = "pushgateway"))]
+pub fn encode_metrics() -> Vec<u8> {
     let encoder = TextEncoder::new();
     let families = REGISTRY.gather();
     let mut buf = Vec::new();
@@ -227,37 +1140,3 @@ fn encode_metrics() -> Vec<u8> {
     }
     buf
 }
-
-// Serve one HTTP request (GET / or /metrics) — tiny HTTP 1.1 responder
-fn handle_client(mut stream: TcpStream) {
-    // Read a bit to consume headers (no full parse)
-    let mut _req_buf = [0u8; 1024];
-    let _ = stream.read(&mut _req_buf);
-
-    let body = encode_metrics();
-    let header = format!(
-        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
-        body.len()
-    );
-
-    let _ = stream.write_all(header.as_bytes());
-    let _ = stream.write_all(&body);
-    let _ = stream.flush();
-}
-
-// Run the metrics server in a dedicated OS thread (keeps Tokio runtime clean)
-pub async fn serve_metrics(port: u16) {
-    thread::spawn(move || {
-        let addr = format!("0.0.0.0:{port}");
-        let listener = TcpListener::bind(&addr)
-            .unwrap_or_else(|e| panic!("metrics bind {} failed: {}", addr, e));
-        eprintln!("metrics listening on http://{addr}/ (and /metrics)");
-
-        for conn in listener.incoming() {
-            match conn {
-                Ok(stream) => handle_client(stream),
-                Err(e) => eprintln!("metrics accept error: {}", e),
-            }
-        }
-    });
-}
@@ -0,0 +1,251 @@
+// ===============================
+// src/metrics_server.rs
+// ===============================
+//
+// Server HTTP metrics (dipisah dari metrics.rs): /metrics, /admin/..., /dashboard, webhook.
+// Server kecil HTTP/1.1 di thread OS sendiri (runtime Tokio tetap bersih), satu request per koneksi.
+//
+// Keamanan (telemetri trading + admin API tidak boleh terbuka untuk seluruh jaringan):
+// - METRICS_BIND: alamat bind (mis. 127.0.0.1 agar hanya lokal)
+// - METRICS_BASIC_AUTH=user:pass dan/atau METRICS_BEARER_TOKEN: semua path wajib header
+//   `Authorization` yang cocok (salah satu cukup), selain itu 401.
+//   Pengecualian: /webhook/tradingview (TradingView tidak bisa mengirim header; dilindungi secret sendiri).
+// - METRICS_TLS_CERT + METRICS_TLS_KEY (PEM): layani HTTPS (rustls)
+// - server hanya ada dengan fitur `admin-api` (default); tanpa itu metrics tetap bisa di-push (pushgateway)
+//
+// ENV:
+//   METRICS_BIND=0.0.0.0
+//   METRICS_PORT=9898
+//   METRICS_BASIC_AUTH=           (user:pass)
+//   METRICS_BEARER_TOKEN=
+//   METRICS_TLS_CERT=             (path PEM chain)
+//   METRICS_TLS_KEY=              (path PEM private key)
+//
+#[cfg(feature = "admin-api")]
+use {
+    base64::Engine,
+    rustls_pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer},
+    std::io::{Read, Write},
+    std::net::{TcpListener, TcpStream},
+    std::sync::Arc,
+    std::thread,
+};
+
+#[cfg(feature = "admin-api")]
+use crate::admin::AdminState;
+#[cfg(feature = "admin-api")]
+use crate::metrics::encode_metrics;
+
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct MetricsServerCfg {
+    pub bind: String,
+    pub port: u16,
+    #[serde(serialize_with = "crate::effective_config::secret_opt")]
+    pub basic_auth: Option<String>,
+    #[serde(serialize_with = "crate::effective_config::secret_opt")]
+    pub bearer_token: Option<String>,
+    pub tls_cert: Option<String>,
+    pub tls_key: Option<String>,
+}
+
+impl MetricsServerCfg {
+    pub fn from_env() -> Self {
+        let var = |k: &str| std::env::var(k).ok().map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+        Self {
+            bind: var("METRICS_BIND").unwrap_or_else(|| "0.0.0.0".to_string()),
+            port: var("METRICS_PORT").and_then(|s| s.parse().ok()).unwrap_or(9898),
+            basic_auth: var("METRICS_BASIC_AUTH"),
+            bearer_token: var("METRICS_BEARER_TOKEN"),
+            tls_cert: var("METRICS_TLS_CERT"),
+            tls_key: var("METRICS_TLS_KEY"),
+        }
+    }
+
+    #[cfg(feature = "admin-api")]
+    pub fn tls(&self) -> bool {
+        self.tls_cert.is_some() && self.tls_key.is_some()
+    }
+
+    /// Nilai header Authorization yang diterima (kosong = tanpa auth)
+    #[cfg(feature = "admin-api")]
+    fn accepted_auth(&self) -> Vec<String> {
+        let basic = self.basic_auth.iter().map(|up| format!("Basic {}", base64::engine::general_purpose::STANDARD.encode(up)));
+        let bearer = self.bearer_token.iter().map(|t| format!("Bearer {t}"));
+        basic.chain(bearer).collect()
+    }
+}
+
+/// Perbandingan tanpa early-exit (hindari timing leak pada token)
+#[cfg(feature = "admin-api")]
+fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Header Authorization request cocok dengan salah satu kredensial (atau auth nonaktif)
+#[cfg(feature = "admin-api")]
+fn authorized(head: &str, accepted: &[String]) -> bool {
+    if accepted.is_empty() {
+        return true;
+    }
+    let Some(got) = head
+        .lines()
+        .filter_map(|l| l.split_once(':'))
+        .find(|(k, _)| k.trim().eq_ignore_ascii_case("authorization"))
+        .map(|(_, v)| v.trim())
+    else {
+        return false;
+    };
+    accepted.iter().any(|a| ct_eq(a.as_bytes(), got.as_bytes()))
+}
+
+/// Batas body request (webhook)
+#[cfg(feature = "admin-api")]
+const MAX_BODY: usize = 64 * 1024;
+
+/// Baca header + body (sesuai Content-Length, maks MAX_BODY)
+#[cfg(feature = "admin-api")]
+fn read_request(stream: &mut impl Read) -> (String, Vec<u8>) {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    let head_end = loop {
+        let n = stream.read(&mut chunk).unwrap_or(0);
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(i) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break i + 4;
+        }
+        if n == 0 || buf.len() > MAX_BODY {
+            break buf.len();
+        }
+    };
+    let head = String::from_utf8_lossy(&buf[..head_end]).into_owned();
+    let len = head
+        .lines()
+        .filter_map(|l| l.split_once(':'))
+        .find(|(k, _)| k.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, v)| v.trim().parse::<usize>().ok())
+        .unwrap_or(0)
+        .min(MAX_BODY);
+    let mut body = buf[head_end..].to_vec();
+    while body.len() < len {
+        let n = stream.read(&mut chunk).unwrap_or(0);
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+    body.truncate(len);
+    (head, body)
+}
+
+// Serve one HTTP request — tiny HTTP 1.1 responder
+// - /admin/...          -> admin API (lihat admin.rs)
+// - lainnya (/, /metrics) -> Prometheus text format
+#[cfg(feature = "admin-api")]
+fn handle_client<S: Read + Write + Send + 'static>(mut stream: S, admin: &AdminState, accepted: &[String]) {
+    let (req, body) = read_request(&mut stream);
+    let mut parts = req.lines().next().unwrap_or("").split_whitespace();
+    let method = parts.next().unwrap_or("GET");
+    let target = parts.next().unwrap_or("/");
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    if path != "/webhook/tradingview" && !authorized(&req, accepted) {
+        let challenge = if accepted.iter().any(|a| a.starts_with("Basic ")) { "Basic realm=\"dma_bot\"" } else { "Bearer" };
+        let rsp = format!("HTTP/1.1 401 Unauthorized\r\nWWW-Authenticate: {challenge}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+        let _ = stream.write_all(rsp.as_bytes());
+        let _ = stream.flush();
+        return;
+    }
+
+    if path == "/dashboard/events" {
+        // SSE: koneksi panjang, dilayani thread sendiri
+        crate::dashboard::serve_events(stream, admin.dashboard.clone());
+        return;
+    }
+
+    let (status, content_type, body) = if path == "/dashboard" {
+        ("200 OK", "text/html; charset=utf-8", crate::dashboard::PAGE.as_bytes().to_vec())
+    } else if path.starts_with("/admin/") {
+        let rsp = crate::admin::handle(admin, method, path, query, &body);
+        (rsp.status, rsp.content_type, rsp.body)
+    } else if let (Some(webhook), "/webhook/tradingview") = (&admin.webhook, path) {
+        let rsp = webhook.handle(method, query, &body);
+        (rsp.status, rsp.content_type, rsp.body)
+    } else {
+        ("200 OK", "text/plain; version=0.0.4; charset=utf-8", encode_metrics())
+    };
+    let header = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+
+    let _ = stream.write_all(header.as_bytes());
+    let _ = stream.write_all(&body);
+    let _ = stream.flush();
+}
+
+/// Konfigurasi TLS server dari file PEM
+#[cfg(feature = "admin-api")]
+fn tls_config(cert: &str, key: &str) -> Result<Arc<rustls::ServerConfig>, String> {
+    let certs = CertificateDer::pem_file_iter(cert)
+        .and_then(|it| it.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("{cert}: {e}"))?;
+    let key = PrivateKeyDer::from_pem_file(key).map_err(|e| format!("{key}: {e}"))?;
+    let cfg = rustls::ServerConfig::builder().with_no_client_auth().with_single_cert(certs, key).map_err(|e| e.to_string())?;
+    Ok(Arc::new(cfg))
+}
+
+#[cfg(feature = "admin-api")]
+fn serve_tls(stream: TcpStream, tls: &Arc<rustls::ServerConfig>, admin: &AdminState, accepted: &[String]) {
+    match rustls::ServerConnection::new(tls.clone()) {
+        Ok(conn) => handle_client(rustls::StreamOwned::new(conn, stream), admin, accepted),
+        Err(e) => eprintln!("metrics tls error: {e}"),
+    }
+}
+
+// Run the metrics server in a dedicated OS thread (keeps Tokio runtime clean)
+#[cfg(feature = "admin-api")]
+pub async fn serve_metrics(cfg: MetricsServerCfg, admin: AdminState) {
+    let tls = cfg.tls().then(|| {
+        let (cert, key) = (cfg.tls_cert.as_deref().unwrap_or_default(), cfg.tls_key.as_deref().unwrap_or_default());
+        tls_config(cert, key).unwrap_or_else(|e| panic!("metrics tls config failed: {e}"))
+    });
+    thread::spawn(move || {
+        let addr = format!("{}:{}", cfg.bind, cfg.port);
+        let listener = TcpListener::bind(&addr)
+            .unwrap_or_else(|e| panic!("metrics bind {} failed: {}", addr, e));
+        let accepted = cfg.accepted_auth();
+        let scheme = if tls.is_some() { "https" } else { "http" };
+        eprintln!(
+            "metrics listening on {scheme}://{addr}/ (and /metrics, /admin/..., /dashboard){}",
+            if accepted.is_empty() { "" } else { " [auth required]" }
+        );
+
+        for conn in listener.incoming() {
+            match conn {
+                Ok(stream) => match &tls {
+                    Some(tls) => serve_tls(stream, tls, &admin, &accepted),
+                    None => handle_client(stream, &admin, &accepted),
+                },
+                Err(e) => eprintln!("metrics accept error: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(all(test, feature = "admin-api"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn authorization_header_must_match_basic_or_bearer() {
+        let cfg = MetricsServerCfg { basic_auth: Some("prom:s3cret".into()), bearer_token: Some("tok".into()), ..Default::default() };
+        let accepted = cfg.accepted_auth();
+        let req = |auth: &str| format!("GET /metrics HTTP/1.1\r\nHost: x\r\n{auth}\r\n\r\n");
+        assert!(authorized(&req("Authorization: Basic cHJvbTpzM2NyZXQ="), &accepted));
+        assert!(authorized(&req("authorization: Bearer tok"), &accepted));
+        assert!(!authorized(&req("Authorization: Bearer nope"), &accepted));
+        assert!(!authorized(&req("X-Other: 1"), &accepted));
+        // tanpa kredensial terkonfigurasi: terbuka
+        assert!(authorized(&req("X-Other: 1"), &MetricsServerCfg::default().accepted_auth()));
+    }
+}
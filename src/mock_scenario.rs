@@ -0,0 +1,241 @@
+// ===============================
+// src/mock_scenario.rs
+// ===============================
+//
+// Skenario harga untuk feed mock (`MOCK_SCENARIO`), agar strategi & risk bisa diuji
+// di rezim pasar yang buruk, bukan cuma random walk datar:
+//
+// - random_walk  : default lama (langkah -3..=3 tick)
+// - trend        : random walk + drift searah yang berbalik arah sesekali
+// - vol_cluster  : volatilitas berkelompok ala GARCH(1,1) (tenang lama, lalu bergejolak)
+// - gap          : random walk + lompatan harga mendadak 1–3%
+// - flash_crash  : jatuh 5–10% dalam ~50 tick, lalu pulih sebagian pelan-pelan
+// - wide_spread  : mid random walk, spread sesekali melebar ke 20–50 tick
+//
+// Di atas skenario, setiap quote:
+// - spread dinamis: 1 tick + kelipatan volatilitas terkini (EWMA |langkah mid|)
+// - ukuran bid/ask acak, menipis saat volatil
+// - sesekali pasar locked (ask == bid) atau crossed (ask < bid), untuk menguji
+//   penanganan quote degenerate di downstream
+//
+// Depth L2 opsional (`MOCK_BOOK_LEVELS`): level ke-i berjarak i tick dari best,
+// makin dalam makin tebal.
+//
+// Generator murni (tanpa I/O); RNG dipasok pemanggil supaya ThreadRng tidak
+// tersimpan melewati .await.
+//
+use rand::Rng;
+
+use crate::domain::Levels;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scenario {
+    RandomWalk,
+    Trend,
+    VolCluster,
+    Gap,
+    FlashCrash,
+    WideSpread,
+}
+
+impl Scenario {
+    pub fn from_env(key: &str) -> Scenario {
+        match std::env::var(key).unwrap_or_default().trim().to_ascii_lowercase().as_str() {
+            "trend" => Scenario::Trend,
+            "vol_cluster" | "garch" => Scenario::VolCluster,
+            "gap" | "gaps" => Scenario::Gap,
+            "flash_crash" | "crash" => Scenario::FlashCrash,
+            "wide_spread" | "spread" => Scenario::WideSpread,
+            _ => Scenario::RandomWalk,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scenario::RandomWalk => "random_walk",
+            Scenario::Trend => "trend",
+            Scenario::VolCluster => "vol_cluster",
+            Scenario::Gap => "gap",
+            Scenario::FlashCrash => "flash_crash",
+            Scenario::WideSpread => "wide_spread",
+        }
+    }
+}
+
+// Parameter skenario (satuan: tick harga ×100, per tick feed ~5ms)
+const START_MID: f64 = 10_000.0;
+const MIN_MID: f64 = 100.0;
+const TREND_DRIFT: f64 = 0.3;          // tick per langkah
+const TREND_FLIP_PROB: f64 = 1.0 / 3_000.0;
+const GARCH_OMEGA: f64 = 0.05;
+const GARCH_ALPHA: f64 = 0.08;
+const GARCH_BETA: f64 = 0.90;
+const GAP_PROB: f64 = 1.0 / 2_000.0;
+const CRASH_PROB: f64 = 1.0 / 6_000.0;
+const CRASH_DOWN_STEPS: u32 = 50;
+const CRASH_RECOVER_STEPS: u32 = 600;
+const WIDE_PROB: f64 = 1.0 / 1_000.0;
+const VOL_EWMA: f64 = 0.05;            // bobot langkah terbaru di estimasi volatilitas
+const SPREAD_PER_VOL: f64 = 0.8;       // tick spread per tick volatilitas
+const BASE_SIZE: f64 = 200.0;          // ukuran rata-rata di best level (qty)
+const DEGENERATE_PROB: f64 = 1.0 / 5_000.0;
+
+/// Satu quote top-of-book
+#[derive(Debug, Clone, Copy)]
+pub struct Quote {
+    pub bid: i64,
+    pub ask: i64,
+    pub bid_qty: i64,
+    pub ask_qty: i64,
+}
+
+/// Fase flash crash yang sedang berjalan
+#[derive(Debug, Clone, Copy)]
+struct Crash {
+    down_left: u32,
+    recover_left: u32,
+    down_step: f64,
+    recover_step: f64,
+}
+
+/// Generator bid/ask satu symbol
+#[derive(Debug, Clone)]
+pub struct MockGen {
+    scenario: Scenario,
+    mid: f64,
+    drift: f64,
+    var: f64,
+    last_ret: f64,
+    crash: Option<Crash>,
+    wide_left: u32,
+    wide_ticks: i64,
+    vol: f64,
+}
+
+/// Normal standar (aproksimasi Irwin–Hall, cukup untuk simulasi)
+fn normal<R: Rng>(rng: &mut R) -> f64 {
+    (0..12).map(|_| rng.gen::<f64>()).sum::<f64>() - 6.0
+}
+
+impl MockGen {
+    pub fn new(scenario: Scenario) -> Self {
+        Self {
+            scenario,
+            mid: START_MID,
+            drift: TREND_DRIFT,
+            var: GARCH_OMEGA / (1.0 - GARCH_ALPHA - GARCH_BETA),
+            last_ret: 0.0,
+            crash: None,
+            wide_left: 0,
+            wide_ticks: 1,
+            vol: 1.5,
+        }
+    }
+
+    /// Langkah berikutnya
+    pub fn next<R: Rng>(&mut self, rng: &mut R) -> Quote {
+        let walk = rng.gen_range(-3..=3) as f64;
+        let step = match self.scenario {
+            Scenario::RandomWalk | Scenario::WideSpread => walk,
+            Scenario::Trend => {
+                if rng.gen_bool(TREND_FLIP_PROB) {
+                    self.drift = -self.drift;
+                }
+                walk + self.drift
+            }
+            Scenario::VolCluster => {
+                self.var = GARCH_OMEGA + GARCH_ALPHA * self.last_ret * self.last_ret + GARCH_BETA * self.var;
+                self.last_ret = normal(rng) * self.var.sqrt();
+                self.last_ret
+            }
+            Scenario::Gap => {
+                if rng.gen_bool(GAP_PROB) {
+                    let pct = rng.gen_range(0.01..0.03);
+                    let sign = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+                    walk + sign * pct * self.mid
+                } else {
+                    walk
+                }
+            }
+            Scenario::FlashCrash => walk + self.crash_step(rng),
+        };
+        let prev = self.mid;
+        self.mid = (self.mid + step).max(MIN_MID);
+        self.vol += VOL_EWMA * ((self.mid - prev).abs() - self.vol);
+
+        let mut spread = 1 + (self.vol * SPREAD_PER_VOL).round() as i64;
+        if self.scenario == Scenario::WideSpread {
+            spread = spread.max(self.wide_spread(rng));
+        }
+        let bid = (self.mid - spread as f64 / 2.0).round() as i64;
+        let mut ask = bid + spread;
+        if rng.gen_bool(DEGENERATE_PROB) {
+            // locked (ask == bid) atau crossed (ask < bid)
+            ask = if rng.gen_bool(0.5) { bid } else { bid - rng.gen_range(1..=3) };
+        }
+
+        // ukuran menipis saat volatil (likuiditas ditarik)
+        let depth = BASE_SIZE / (1.0 + self.vol / 2.0);
+        let mut size = || (depth * rng.gen_range(0.2..2.0)).round().max(1.0) as i64;
+        Quote { bid, ask, bid_qty: size(), ask_qty: size() }
+    }
+
+    /// Depth `levels` level per sisi, konsisten dengan quote terakhir
+    pub fn book<R: Rng>(&self, q: &Quote, levels: usize, rng: &mut R) -> (Levels, Levels) {
+        let depth = BASE_SIZE / (1.0 + self.vol / 2.0);
+        let mut side = |px0: i64, qty0: i64, dir: i64| -> Levels {
+            (0..levels as i64)
+                .map(|i| {
+                    let qty = if i == 0 { qty0 } else { (depth * (1 + i) as f64 * rng.gen_range(0.5..1.5)).round() as i64 };
+                    (px0 + dir * i, qty.max(1))
+                })
+                .collect()
+        };
+        let bids = side(q.bid, q.bid_qty, -1);
+        let asks = side(q.ask, q.ask_qty, 1);
+        (bids, asks)
+    }
+
+    fn crash_step<R: Rng>(&mut self, rng: &mut R) -> f64 {
+        match self.crash.as_mut() {
+            None => {
+                if rng.gen_bool(CRASH_PROB) {
+                    let depth = self.mid * rng.gen_range(0.05..0.10);
+                    self.crash = Some(Crash {
+                        down_left: CRASH_DOWN_STEPS,
+                        recover_left: CRASH_RECOVER_STEPS,
+                        down_step: depth / CRASH_DOWN_STEPS as f64,
+                        recover_step: depth * 0.7 / CRASH_RECOVER_STEPS as f64,
+                    });
+                }
+                0.0
+            }
+            Some(c) if c.down_left > 0 => {
+                c.down_left -= 1;
+                -c.down_step
+            }
+            Some(c) => {
+                let s = c.recover_step;
+                c.recover_left = c.recover_left.saturating_sub(1);
+                if c.recover_left == 0 {
+                    self.crash = None;
+                }
+                s
+            }
+        }
+    }
+
+    fn wide_spread<R: Rng>(&mut self, rng: &mut R) -> i64 {
+        if self.wide_left > 0 {
+            self.wide_left -= 1;
+            return self.wide_ticks;
+        }
+        if rng.gen_bool(WIDE_PROB) {
+            self.wide_left = rng.gen_range(100..400);
+            self.wide_ticks = rng.gen_range(20..=50);
+            return self.wide_ticks;
+        }
+        0
+    }
+}
@@ -0,0 +1,634 @@
+// ===============================
+// src/multileg.rs
+// ===============================
+//
+// Order multi-leg (arbitrase / pairs): view OMS per paket + remediasi legging.
+//
+// Alur: MultiLegOrder (POST /admin/multileg atau strategi) -> risk (semua leg dicek; satu gagal = paket ditolak)
+//   -> router (pre-flight MD + venue untuk semua leg dulu, lalu leg dikirim berurutan) -> gateway.
+// Leg dikenali dari tag `mleg=<id>` / `leg=<i>` yang dibawa Order -> ExecReport (termasuk child venue,
+// re-route dan reprice OMS).
+//
+// LegBook melacak fill bersih per leg dalam unit paket (filled / ratio):
+//   imbalance = unit leg terbanyak - unit leg tersedikit (0 = kedua sisi seimbang)
+// Paket selesai saat semua leg final (terisi penuh / ditolak / dibatalkan) dan tidak ada remediasi tersisa;
+// selesai dengan imbalance > 0 berarti legging risk tertinggal (`multileg_orders_total{result="broken"}`).
+//   GET /admin/multileg   (paket terbuka + paket terakhir yang selesai)
+//
+// Remediasi (task `run_hedger`): jika imbalance bertahan >= MLEG_LAG_TIMEOUT_MS, satu ronde:
+//   1) child yang masih working di leg yang akan disesuaikan dibatalkan dulu (VenueCmd::Cancel)
+//   2) chase : leg tertinggal dikirim ulang untuk sisa qty ke unit leg terbanyak, harga menembus touch
+//      unwind: kelebihan fill leg terdepan ditutup (sisi berlawanan) ke unit leg tersedikit
+//   Mode chase: ronde terakhir selalu unwind (leg yang tetap tidak terisi tidak boleh meninggalkan posisi
+//   telanjang). Order remediasi membawa tag leg yang sama + `hedge=chase|unwind`, tidak lewat risk lagi
+//   (qty sudah lolos di paket asal) dan tidak dikirim saat kill switch aktif atau portfolio stop terpicu
+//   (posisi leg ditutup flatten portfolio stop; unwind di sini akan menutup posisi yang sama dua kali).
+//
+// ENV:
+//   MLEG_HEDGE=off|chase|unwind   (default off)
+//   MLEG_LAG_TIMEOUT_MS=2000
+//   MLEG_HEDGE_ROUNDS=3           ronde remediasi per paket
+//   MLEG_HEDGE_TICKS=5            offset harga menembus touch (unit ×100)
+//
+use ahash::AHashMap as HashMap;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::{interval, Duration, Instant, MissedTickBehavior};
+use tracing::{info, warn};
+
+use crate::domain::{leg_of, ExecReport, ExecStatus, FillDeltas, MdTick, MultiLegOrder, Order, OrderType, Side, VenueCmd};
+use crate::kill_switch::KillSwitch;
+use crate::metrics::{MULTILEG_HEDGES, MULTILEG_IMBALANCE, MULTILEG_LEGGING_SECS, MULTILEG_OPEN, MULTILEG_ORDERS, MULTILEG_UNWIND_PNL};
+use crate::portfolio_stop::PortfolioStop;
+use crate::sizing;
+
+/// Jumlah paket selesai yang disimpan untuk admin
+const DONE_KEEP: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HedgeMode { #[default] Off, Chase, Unwind }
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HedgeCfg {
+    pub mode: HedgeMode,
+    pub lag_timeout_ms: u64,
+    pub rounds: u32,
+    pub ticks: i64,
+}
+
+impl HedgeCfg {
+    pub fn from_env() -> Self {
+        let mode = match std::env::var("MLEG_HEDGE").unwrap_or_default().to_ascii_lowercase().as_str() {
+            "chase" => HedgeMode::Chase,
+            "unwind" => HedgeMode::Unwind,
+            _ => HedgeMode::Off,
+        };
+        let num = |k: &str, d: u64| std::env::var(k).ok().and_then(|s| s.parse().ok()).unwrap_or(d);
+        Self {
+            mode,
+            lag_timeout_ms: num("MLEG_LAG_TIMEOUT_MS", 2000),
+            rounds: num("MLEG_HEDGE_ROUNDS", 3) as u32,
+            ticks: num("MLEG_HEDGE_TICKS", 5) as i64,
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.mode != HedgeMode::Off && self.rounds > 0
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LegState {
+    pub symbol: String,
+    pub side: Side,
+    pub qty: i64,
+    pub ratio: i64,
+    /// fill bersih searah leg (fill unwind mengurangi)
+    pub filled: i64,
+    pub avg_px: i64,
+    pub closed: bool,
+    pub reject_reason: Option<String>,
+    #[serde(skip)]
+    open: HashMap<String, (String, bool)>, // cl_id child yang belum final -> (venue, cancel terkirim)
+    #[serde(skip)]
+    settled: i64,                          // qty order child yang sudah final (leg terpecah ke beberapa venue)
+}
+
+impl LegState {
+    /// Fill dalam unit paket
+    pub fn units(&self) -> f64 {
+        self.filled as f64 / self.ratio as f64
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Package {
+    pub id: String,
+    pub strategy: String,
+    pub created_ns: i128,
+    pub legs: Vec<LegState>,
+    pub imbalance: f64,
+    pub hedge_rounds: u32,
+    pub done: bool,
+    #[serde(skip)]
+    imbalanced_since: Option<Instant>, // timer remediasi (di-reset tiap ronde)
+    #[serde(skip)]
+    legging_since: Option<Instant>,    // awal episode legging (histogram durasi)
+    #[serde(skip)]
+    tags: Vec<String>,
+    #[serde(skip)]
+    book: String,
+}
+
+impl Package {
+    /// (unit leg tersedikit, unit leg terbanyak)
+    fn units_range(&self) -> (f64, f64) {
+        self.legs.iter().map(LegState::units).fold((f64::MAX, f64::MIN), |(lo, hi), u| (lo.min(u), hi.max(u)))
+    }
+
+    fn update_imbalance(&mut self) {
+        let (lo, hi) = self.units_range();
+        self.imbalance = if self.legs.is_empty() { 0.0 } else { hi - lo };
+        if self.imbalance > 0.0 {
+            let now = Instant::now();
+            self.imbalanced_since.get_or_insert(now);
+            self.legging_since.get_or_insert(now);
+        } else {
+            self.imbalanced_since = None;
+            if let Some(t) = self.legging_since.take() {
+                MULTILEG_LEGGING_SECS.observe(t.elapsed().as_secs_f64());
+            }
+        }
+    }
+}
+
+/// Aksi remediasi yang dijalankan task hedger
+enum HedgeAction {
+    Cancel { venue: String, cl_id: String, symbol: String },
+    Send(Order),
+}
+
+#[cfg(feature = "admin-api")]
+#[derive(Debug, Serialize)]
+pub struct LegSnapshot {
+    pub open: Vec<Package>,
+    pub done: Vec<Package>,
+}
+
+#[derive(Default)]
+struct Inner {
+    open: HashMap<String, Package>,
+    done: VecDeque<Package>,
+    deltas: FillDeltas,
+}
+
+/// Handle view multi-leg (clone murah; diisi fan-out ExecReport, paket didaftarkan risk)
+#[derive(Clone, Default)]
+pub struct LegBook {
+    cfg: Arc<HedgeCfg>,
+    inner: Arc<Mutex<Inner>>,
+}
+
+/// Harga menembus touch untuk order remediasi
+fn cross_px(side: Side, bid: i64, ask: i64, ticks: i64) -> i64 {
+    match side {
+        Side::Buy => ask + ticks,
+        Side::Sell => (bid - ticks).max(1),
+    }
+}
+
+fn opposite(side: Side) -> Side {
+    match side {
+        Side::Buy => Side::Sell,
+        Side::Sell => Side::Buy,
+    }
+}
+
+impl LegBook {
+    pub fn new(cfg: HedgeCfg) -> Self {
+        Self { cfg: Arc::new(cfg), inner: Arc::default() }
+    }
+
+    /// Daftarkan paket yang lolos risk
+    pub fn register(&self, m: &MultiLegOrder) {
+        let legs = m
+            .legs
+            .iter()
+            .map(|l| LegState {
+                symbol: l.symbol.clone(),
+                side: l.side,
+                qty: l.qty,
+                ratio: l.ratio(),
+                filled: 0,
+                avg_px: 0,
+                closed: false,
+                reject_reason: None,
+                open: HashMap::new(),
+                settled: 0,
+            })
+            .collect();
+        let pkg = Package {
+            id: m.id.clone(),
+            strategy: m.strategy.clone(),
+            created_ns: m.ts_ns,
+            legs,
+            imbalance: 0.0,
+            hedge_rounds: 0,
+            done: false,
+            imbalanced_since: None,
+            legging_since: None,
+            tags: m.tags.clone(),
+            book: m.book.clone(),
+        };
+        let mut g = self.inner.lock().unwrap();
+        g.open.insert(m.id.clone(), pkg);
+        MULTILEG_OPEN.set(g.open.len() as i64);
+    }
+
+    #[cfg(feature = "admin-api")]
+    pub fn snapshot(&self) -> LegSnapshot {
+        let g = self.inner.lock().unwrap();
+        LegSnapshot { open: g.open.values().cloned().collect(), done: g.done.iter().cloned().collect() }
+    }
+
+    /// true = paket masih menunggu remediasi (jangan ditutup walau semua leg final)
+    fn awaiting_hedge(&self, p: &Package) -> bool {
+        self.cfg.enabled() && p.imbalance > 0.0 && p.hedge_rounds < self.cfg.rounds
+    }
+
+    pub fn on_report(&self, er: &ExecReport) {
+        let Some((id, idx)) = leg_of(&er.tags) else { return };
+        let mut g = self.inner.lock().unwrap();
+        let fill = g.deltas.on_report(er);
+        let Some(pkg) = g.open.get_mut(id) else { return };
+        let Some(leg) = pkg.legs.get_mut(idx) else { return };
+        if let Some((qty, px)) = fill {
+            if er.side.is_none_or(|s| s.sign() == leg.side.sign()) {
+                leg.avg_px = (leg.avg_px * leg.filled + px * qty) / (leg.filled + qty);
+                leg.filled += qty;
+            } else {
+                // fill unwind: realize selisih terhadap harga masuk leg
+                let pnl = (px - leg.avg_px) as f64 / 100.0 * leg.side.sign() as f64 * sizing::base_qty(&leg.symbol, qty);
+                MULTILEG_UNWIND_PNL.add(pnl);
+                leg.filled -= qty;
+            }
+        }
+        match &er.status {
+            ExecStatus::Ack | ExecStatus::PartialFill => {
+                leg.open.entry(er.cl_id.clone()).or_insert_with(|| (er.venue.clone(), false));
+                leg.closed = false; // child baru (re-route / reprice / remediasi) membuka leg lagi
+            }
+            // REPLACED: child pengganti menyusul, leg belum final
+            ExecStatus::Rejected(r) if r == "REPLACED" => {
+                leg.open.remove(&er.cl_id);
+            }
+            status => {
+                leg.open.remove(&er.cl_id);
+                if let ExecStatus::Rejected(r) = status {
+                    leg.reject_reason = Some(r.clone());
+                }
+                leg.settled += er.order_qty;
+                leg.closed = leg.open.is_empty() && leg.settled >= leg.qty;
+            }
+        }
+        if leg.filled >= leg.qty && leg.open.is_empty() {
+            leg.closed = true;
+        }
+        pkg.update_imbalance();
+
+        if pkg.legs.iter().all(|l| l.closed) && !self.awaiting_hedge(pkg) {
+            let pkg = g.open.remove(id).unwrap();
+            finish(&mut g, pkg);
+        }
+        MULTILEG_OPEN.set(g.open.len() as i64);
+        MULTILEG_IMBALANCE.set(g.open.values().fold(0.0, |acc, p| acc + p.imbalance));
+    }
+
+    /// Paket yang imbalance-nya melewati timeout -> aksi remediasi (ronde langsung dicatat)
+    fn remediate(&self, quotes: &HashMap<String, (i64, i64)>) -> Vec<HedgeAction> {
+        let cfg = &self.cfg;
+        let timeout = Duration::from_millis(cfg.lag_timeout_ms);
+        let mut out = Vec::new();
+        let mut finished = Vec::new();
+        let mut g = self.inner.lock().unwrap();
+        for (id, p) in g.open.iter_mut() {
+            if p.imbalanced_since.is_none_or(|t| t.elapsed() < timeout) {
+                continue;
+            }
+            if p.hedge_rounds >= cfg.rounds {
+                // ronde habis: paket ditutup begitu semua leg final
+                if p.legs.iter().all(|l| l.closed) {
+                    finished.push(id.clone());
+                }
+                continue;
+            }
+            let unwind = cfg.mode == HedgeMode::Unwind || p.hedge_rounds + 1 == cfg.rounds;
+            let (lo, hi) = p.units_range();
+            // leg yang disesuaikan: tertinggal (chase) atau terdepan (unwind)
+            let targets: Vec<usize> = (0..p.legs.len())
+                .filter(|&i| if unwind { p.legs[i].units() > lo } else { p.legs[i].units() < hi })
+                .collect();
+
+            // 1) child yang masih working dibatalkan dulu; ronde jalan setelah semuanya final
+            //    (unwind: semua leg, agar leg tertinggal tidak terisi belakangan dan membuka legging baru)
+            let mut waiting = false;
+            for leg in p.legs.iter_mut().enumerate().filter(|(i, _)| unwind || targets.contains(i)).map(|(_, l)| l) {
+                for (cl_id, (venue, sent)) in leg.open.iter_mut() {
+                    waiting = true;
+                    if !*sent {
+                        *sent = true;
+                        out.push(HedgeAction::Cancel { venue: venue.clone(), cl_id: cl_id.clone(), symbol: leg.symbol.clone() });
+                    }
+                }
+            }
+            if waiting {
+                continue;
+            }
+
+            // 2) order remediasi
+            p.hedge_rounds += 1;
+            p.imbalanced_since = Some(Instant::now());
+            let action = if unwind { "unwind" } else { "chase" };
+            for &i in &targets {
+                let leg = &p.legs[i];
+                let Some(&(bid, ask)) = quotes.get(&leg.symbol) else {
+                    warn!(%id, leg = i, "multi-leg hedge skipped: no market data");
+                    continue;
+                };
+                let (side, qty) = if unwind {
+                    (opposite(leg.side), leg.filled - (lo * leg.ratio as f64).ceil() as i64)
+                } else {
+                    (leg.side, ((hi * leg.ratio as f64).ceil() as i64).min(leg.qty) - leg.filled)
+                };
+                if qty <= 0 {
+                    continue;
+                }
+                let mut tags = p.tags.clone();
+                tags.extend([format!("mleg={id}"), format!("leg={i}"), format!("hedge={action}")]);
+                let ord = Order {
+                    cl_id: crate::order_ids::derive(id, &format!("L{i}-H{}", p.hedge_rounds)),
+                    ts_ns: crate::clock::now_ns(),
+                    symbol: leg.symbol.clone(),
+                    side,
+                    px: cross_px(side, bid, ask, cfg.ticks),
+                    qty,
+                    strategy: p.strategy.clone(),
+                    tags,
+                    book: p.book.clone(),
+                    expire_at_ns: None,
+                    order_type: OrderType::Limit,
+                };
+                warn!(%id, leg = i, action, round = p.hedge_rounds, qty, px = ord.px, imbalance = p.imbalance, "multi-leg legging: hedging");
+                MULTILEG_HEDGES.with_label_values(&[action]).inc();
+                out.push(HedgeAction::Send(ord));
+            }
+        }
+        for id in finished {
+            if let Some(pkg) = g.open.remove(&id) {
+                finish(&mut g, pkg);
+            }
+        }
+        MULTILEG_OPEN.set(g.open.len() as i64);
+        out
+    }
+}
+
+/// Hasil paket selesai: filled (semua leg penuh) / broken (selesai dengan imbalance) / unfilled
+fn outcome(pkg: &Package) -> &'static str {
+    if pkg.legs.iter().all(|l| l.filled >= l.qty) {
+        "filled"
+    } else if pkg.imbalance > 0.0 {
+        "broken"
+    } else {
+        "unfilled"
+    }
+}
+
+/// Pindahkan paket ke daftar selesai + metrics hasil
+fn finish(g: &mut Inner, mut pkg: Package) {
+    pkg.done = true;
+    let result = outcome(&pkg);
+    if result == "filled" {
+        info!(id = %pkg.id, hedge_rounds = pkg.hedge_rounds, "multi-leg order filled");
+    } else {
+        warn!(id = %pkg.id, imbalance = pkg.imbalance, hedge_rounds = pkg.hedge_rounds, "multi-leg order finished incomplete");
+    }
+    if let Some(t) = pkg.legging_since.take() {
+        MULTILEG_LEGGING_SECS.observe(t.elapsed().as_secs_f64());
+    }
+    MULTILEG_ORDERS.with_label_values(&[result]).inc();
+    g.done.push_back(pkg);
+    if g.done.len() > DONE_KEEP {
+        g.done.pop_front();
+    }
+}
+
+/// Aksi remediasi satu tick; kosong selama kill switch aktif / portfolio stop terpicu (ronde tidak terpakai)
+fn hedge_tick(legs: &LegBook, quotes: &HashMap<String, (i64, i64)>, kill: &KillSwitch, stop: &PortfolioStop) -> Vec<HedgeAction> {
+    if kill.engaged() || stop.tripped() {
+        return Vec::new();
+    }
+    legs.remediate(quotes)
+}
+
+/// Task remediasi legging: cek tiap 100ms, cancel ke gateway venue, order remediasi ke router
+pub async fn run_hedger(
+    legs: LegBook,
+    mut md_rx: broadcast::Receiver<MdTick>,
+    venue_cmd_txs: HashMap<String, mpsc::Sender<VenueCmd>>,
+    ord_tx: mpsc::Sender<Order>,
+    kill: KillSwitch,
+    stop: PortfolioStop,
+) {
+    let cfg = legs.cfg.clone();
+    info!(mode = ?cfg.mode, lag_timeout_ms = cfg.lag_timeout_ms, rounds = cfg.rounds, "multi-leg hedger: started");
+    let mut quotes: HashMap<String, (i64, i64)> = HashMap::new();
+    let mut check = interval(Duration::from_millis(100));
+    check.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            md = md_rx.recv() => match md {
+                Ok(md) => { quotes.insert(md.symbol.clone(), (md.best_bid, md.best_ask)); }
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            _ = check.tick() => {
+                for action in hedge_tick(&legs, &quotes, &kill, &stop) {
+                    match action {
+                        HedgeAction::Cancel { venue, cl_id, symbol } => {
+                            if let Some(tx) = venue_cmd_txs.get(&venue) {
+                                let _ = tx.try_send(VenueCmd::Cancel { cl_id, symbol, reason: "mleg_hedge".to_string() });
+                            }
+                        }
+                        HedgeAction::Send(ord) => {
+                            let _ = ord_tx.send(ord).await;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Leg;
+
+    fn package(id: &str) -> MultiLegOrder {
+        let leg = |symbol: &str, side| Leg { symbol: symbol.into(), side, px: 10_000, qty: 2, ratio: 0 };
+        MultiLegOrder {
+            id: id.into(),
+            ts_ns: 0,
+            legs: vec![leg("BTCUSDT", Side::Buy), leg("ETHUSDT", Side::Sell)],
+            strategy: "arb".into(),
+            tags: vec![],
+            book: String::new(),
+        }
+    }
+
+    fn report(id: &str, leg: usize, cl_id: &str, status: ExecStatus, filled: i64) -> ExecReport {
+        let (symbol, side) = if leg == 0 { ("BTCUSDT", Side::Buy) } else { ("ETHUSDT", Side::Sell) };
+        ExecReport {
+            cl_id: cl_id.into(),
+            symbol: symbol.into(),
+            status,
+            filled_qty: filled,
+            avg_px: 10_000,
+            ts_ns: 0,
+            venue: "A".into(),
+            side: Some(side),
+            order_qty: 2,
+            strategy: "arb".into(),
+            tags: vec![format!("mleg={id}"), format!("leg={leg}")],
+            book: String::new(),
+            exch_ts_ns: 0,
+        }
+    }
+
+    fn quotes() -> HashMap<String, (i64, i64)> {
+        [("BTCUSDT".to_string(), (9_990, 10_010)), ("ETHUSDT".to_string(), (9_990, 10_010))].into_iter().collect()
+    }
+
+    fn open_imbalance(legs: &LegBook, id: &str) -> f64 {
+        legs.inner.lock().unwrap().open[id].imbalance
+    }
+
+    fn done_outcome(legs: &LegBook, id: &str) -> Option<&'static str> {
+        legs.inner.lock().unwrap().done.iter().find(|p| p.id == id).map(outcome)
+    }
+
+    #[test]
+    fn two_leg_imbalance_and_outcomes() {
+        let legs = LegBook::new(HedgeCfg::default());
+
+        // p1: kedua leg terisi bertahap -> imbalance naik turun, selesai "filled"
+        legs.register(&package("p1"));
+        legs.on_report(&report("p1", 0, "p1-0", ExecStatus::Ack, 0));
+        legs.on_report(&report("p1", 1, "p1-1", ExecStatus::Ack, 0));
+        legs.on_report(&report("p1", 0, "p1-0", ExecStatus::PartialFill, 1));
+        assert_eq!(open_imbalance(&legs, "p1"), 0.5);
+        legs.on_report(&report("p1", 1, "p1-1", ExecStatus::Filled, 2));
+        assert_eq!(open_imbalance(&legs, "p1"), 0.5);
+        legs.on_report(&report("p1", 0, "p1-0", ExecStatus::Filled, 2));
+        assert_eq!(done_outcome(&legs, "p1"), Some("filled"));
+
+        // p2: leg 0 terisi, leg 1 dibatalkan tanpa fill -> legging tertinggal, "broken"
+        legs.register(&package("p2"));
+        legs.on_report(&report("p2", 0, "p2-0", ExecStatus::Filled, 2));
+        assert_eq!(open_imbalance(&legs, "p2"), 1.0);
+        assert_eq!(done_outcome(&legs, "p2"), None);
+        legs.on_report(&report("p2", 1, "p2-1", ExecStatus::Rejected("CANCELED".into()), 0));
+        assert_eq!(done_outcome(&legs, "p2"), Some("broken"));
+
+        // p3: kedua leg ditolak tanpa fill -> seimbang tapi kosong, "unfilled"
+        legs.register(&package("p3"));
+        legs.on_report(&report("p3", 0, "p3-0", ExecStatus::Rejected("INSUFFICIENT_BALANCE".into()), 0));
+        assert_eq!(done_outcome(&legs, "p3"), None);
+        legs.on_report(&report("p3", 1, "p3-1", ExecStatus::Rejected("INSUFFICIENT_BALANCE".into()), 0));
+        assert_eq!(done_outcome(&legs, "p3"), Some("unfilled"));
+        assert!(legs.inner.lock().unwrap().open.is_empty());
+    }
+
+    #[test]
+    fn no_hedge_order_after_portfolio_stop() {
+        let legs = LegBook::new(HedgeCfg { mode: HedgeMode::Chase, lag_timeout_ms: 0, rounds: 3, ticks: 5 });
+        let (kill, stop) = (KillSwitch::default(), PortfolioStop::default());
+        legs.register(&package("p1"));
+        // leg 0 terisi, leg 1 dibatalkan tanpa fill -> legging, ronde chase siap dikirim
+        legs.on_report(&report("p1", 0, "p1-0", ExecStatus::Filled, 2));
+        legs.on_report(&report("p1", 1, "p1-1", ExecStatus::Rejected("CANCELED".into()), 0));
+
+        stop.trip();
+        assert!(hedge_tick(&legs, &quotes(), &kill, &stop).is_empty());
+        assert_eq!(legs.inner.lock().unwrap().open["p1"].hedge_rounds, 0);
+
+        stop.rearm();
+        let actions = hedge_tick(&legs, &quotes(), &kill, &stop);
+        assert!(matches!(&actions[..], [HedgeAction::Send(o)] if o.tags.contains(&"hedge=chase".to_string())));
+    }
+
+    fn hedger(mode: HedgeMode, rounds: u32) -> LegBook {
+        LegBook::new(HedgeCfg { mode, lag_timeout_ms: 0, rounds, ticks: 5 })
+    }
+
+    /// Semua order remediasi: (symbol, tanda side, qty, px, tag hedge)
+    fn sends(actions: &[HedgeAction]) -> Vec<(String, i64, i64, i64, String)> {
+        actions
+            .iter()
+            .filter_map(|a| match a {
+                HedgeAction::Send(o) => {
+                    let tag = o.tags.iter().find(|t| t.starts_with("hedge=")).cloned().unwrap_or_default();
+                    Some((o.symbol.clone(), o.side.sign(), o.qty, o.px, tag))
+                }
+                HedgeAction::Cancel { .. } => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn chase_resends_lagging_leg_unwind_closes_leading_leg() {
+        for (mode, want) in [
+            (HedgeMode::Chase, ("ETHUSDT".to_string(), -1, 2, 9_985, "hedge=chase".to_string())),
+            (HedgeMode::Unwind, ("BTCUSDT".to_string(), -1, 2, 9_985, "hedge=unwind".to_string())),
+        ] {
+            let legs = hedger(mode, 3);
+            legs.register(&package("p1"));
+            legs.on_report(&report("p1", 0, "p1-0", ExecStatus::Filled, 2));
+            legs.on_report(&report("p1", 1, "p1-1", ExecStatus::Rejected("CANCELED".into()), 0));
+            assert_eq!(sends(&legs.remediate(&quotes())), vec![want]);
+            assert_eq!(legs.inner.lock().unwrap().open["p1"].hedge_rounds, 1);
+        }
+    }
+
+    #[test]
+    fn working_child_cancelled_before_round() {
+        let legs = hedger(HedgeMode::Chase, 3);
+        legs.register(&package("p1"));
+        legs.on_report(&report("p1", 0, "p1-0", ExecStatus::Filled, 2));
+        legs.on_report(&report("p1", 1, "p1-1", ExecStatus::Ack, 0));
+
+        // child leg tertinggal masih working: cancel dulu, ronde belum terpakai
+        let actions = legs.remediate(&quotes());
+        assert!(matches!(&actions[..], [HedgeAction::Cancel { venue, cl_id, symbol }]
+            if venue == "A" && cl_id == "p1-1" && symbol == "ETHUSDT"));
+        assert_eq!(legs.inner.lock().unwrap().open["p1"].hedge_rounds, 0);
+        // cancel tidak dikirim dua kali selama menunggu report final
+        assert!(legs.remediate(&quotes()).is_empty());
+
+        legs.on_report(&report("p1", 1, "p1-1", ExecStatus::Rejected("CANCELED".into()), 0));
+        assert_eq!(sends(&legs.remediate(&quotes())).len(), 1);
+        assert_eq!(legs.inner.lock().unwrap().open["p1"].hedge_rounds, 1);
+    }
+
+    #[test]
+    fn final_chase_round_unwinds_and_round_qty_tracks_fills() {
+        let legs = hedger(HedgeMode::Chase, 2);
+        legs.register(&package("p1"));
+        legs.on_report(&report("p1", 0, "p1-0", ExecStatus::Filled, 2));
+        legs.on_report(&report("p1", 1, "p1-1", ExecStatus::PartialFill, 1));
+        legs.on_report(&report("p1", 1, "p1-1", ExecStatus::Rejected("CANCELED".into()), 1));
+        assert_eq!(open_imbalance(&legs, "p1"), 0.5);
+
+        // ronde 1 (chase): hanya sisa leg tertinggal, 2 - 1
+        let r1 = sends(&legs.remediate(&quotes()));
+        assert_eq!(r1, vec![("ETHUSDT".to_string(), -1, 1, 9_985, "hedge=chase".to_string())]);
+        let chase_id = crate::order_ids::derive("p1", "L1-H1");
+        legs.on_report(&report("p1", 1, &chase_id, ExecStatus::Rejected("CANCELED".into()), 0));
+
+        // ronde terakhir selalu unwind: kelebihan leg terdepan, 2 - 1
+        let r2 = sends(&legs.remediate(&quotes()));
+        assert_eq!(r2, vec![("BTCUSDT".to_string(), -1, 1, 9_985, "hedge=unwind".to_string())]);
+
+        // fill unwind menyeimbangkan paket; ronde habis -> paket selesai tanpa legging
+        let mut unwind = report("p1", 0, &crate::order_ids::derive("p1", "L0-H2"), ExecStatus::Filled, 1);
+        unwind.side = Some(Side::Sell);
+        unwind.order_qty = 1;
+        legs.on_report(&unwind);
+        assert_eq!(done_outcome(&legs, "p1"), Some("unfilled"));
+        assert!(legs.remediate(&quotes()).is_empty());
+    }
+}
@@ -0,0 +1,662 @@
+// ===============================
+// src/oms.rs
+// ===============================
+//
+// OMS: timeout partial fill.
+// - Order child yang sudah PartialFill lalu tidak mendapat fill lagi selama
+//   PARTIAL_FILL_TIMEOUT_MS -> sisa order dibatalkan (VenueCmd::Cancel ke gateway venue).
+// - Opsional (PARTIAL_REROUTE=1): setelah venue melaporkan order final (CANCELED), sisa qty
+//   dikirim ulang ke router sebagai order baru di harga pasar terbaru (best ask untuk buy,
+//   best bid untuk sell), cl_id `{child}-R`. Re-route berantai dibatasi PARTIAL_REROUTE_MAX.
+//
+// Order re-route tidak melewati risk lagi (qty sudah lolos risk di order asal), tapi tidak
+// dikirim saat kill switch aktif atau portfolio stop terpicu (portfolio_stop.rs).
+//
+// Adaptive aggression (AGGRESSION=1): child yang belum terisi di-reprice bertahap lewat
+// cancel/replace (VenueCmd::Replace) mengikuti jadwal kelas urgensi. Tiap langkah `ms:level`:
+// setelah `ms` sejak ACK, harga = near touch + level% spread (buy: bid + level% (ask-bid)),
+// jadi 0 = join, 50 = mid, 100 = at touch, >100 = menembus touch (minimal 1 tick).
+// Kelas order: tag `urgency=<kelas>`, lalu STRATEGY_URGENCY, default `normal`; `none` = tanpa taktik.
+// Order pengganti memakai cl_id `{child}-P{n}` dan hanya membawa sisa qty. Reprice berhenti saat kill switch
+// aktif atau portfolio stop terpicu (harga tidak dikejar lagi; flatten lewat jalur force-exit).
+//
+// Order terbuka (WorkingOrders, selalu aktif): child dicatat saat router/OMS mengirimnya ke venue
+// dan dilepas saat report final. Gauge `open_orders{symbol,venue}` (jumlah child) dan
+// `working_notional{symbol}` (sisa qty × harga limit, USD) menunjukkan eksposur yang belum terisi.
+//
+// ENV:
+//   PARTIAL_FILL_TIMEOUT_MS=0   0 = nonaktif
+//   PARTIAL_REROUTE=0
+//   PARTIAL_REROUTE_MAX=1
+//   AGGRESSION=0
+//   AGGRESSION_LOW=2000:0,5000:50,10000:100
+//   AGGRESSION_NORMAL=1000:50,3000:100,6000:125
+//   AGGRESSION_HIGH=300:100,1000:150,2000:200
+//   STRATEGY_URGENCY=ma_crossover:high,mean_reversion:low
+//
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use ahash::AHashMap as HashMap;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::{interval, Duration, Instant, MissedTickBehavior};
+use tracing::{info, warn};
+
+use crate::domain::{ExecReport, ExecStatus, MdTick, Order, OrderType, Side, VenueCmd};
+use crate::kill_switch::KillSwitch;
+use crate::metrics::{symbol_admitted, OPEN_ORDERS, ORDER_REPRICES, PARTIAL_REROUTES, PARTIAL_TIMEOUTS, WORKING_NOTIONAL};
+use crate::portfolio_stop::PortfolioStop;
+
+/// Jadwal reprice: (ms sejak ACK, level % spread dari near touch), urut naik
+pub type Schedule = Vec<(u64, i64)>;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OmsCfg {
+    pub partial_timeout_ms: u64,
+    pub reroute: bool,
+    pub reroute_max: usize,
+    pub aggression: bool,
+    pub schedules: HashMap<String, Schedule>, // kelas urgensi -> jadwal
+    pub strategy_urgency: HashMap<String, String>,
+}
+
+/// Parse "ms:level,ms:level" (urut berdasarkan ms)
+fn parse_schedule(s: &str) -> Schedule {
+    let mut out: Schedule = s
+        .split(',')
+        .filter_map(|kv| kv.split_once(':'))
+        .filter_map(|(ms, lvl)| Some((ms.trim().parse().ok()?, lvl.trim().parse().ok()?)))
+        .collect();
+    out.sort_by_key(|&(ms, _)| ms);
+    out
+}
+
+impl OmsCfg {
+    pub fn from_env() -> Self {
+        let num = |k: &str, d: u64| std::env::var(k).ok().and_then(|s| s.parse().ok()).unwrap_or(d);
+        let schedules = [
+            ("low", "AGGRESSION_LOW", "2000:0,5000:50,10000:100"),
+            ("normal", "AGGRESSION_NORMAL", "1000:50,3000:100,6000:125"),
+            ("high", "AGGRESSION_HIGH", "300:100,1000:150,2000:200"),
+        ]
+        .into_iter()
+        .map(|(class, key, default)| (class.to_string(), parse_schedule(&std::env::var(key).unwrap_or_else(|_| default.to_string()))))
+        .collect();
+        let strategy_urgency = std::env::var("STRATEGY_URGENCY")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|kv| kv.split_once(':'))
+            .map(|(k, v)| (k.trim().to_string(), v.trim().to_ascii_lowercase()))
+            .collect();
+        Self {
+            partial_timeout_ms: num("PARTIAL_FILL_TIMEOUT_MS", 0),
+            reroute: num("PARTIAL_REROUTE", 0) == 1,
+            reroute_max: num("PARTIAL_REROUTE_MAX", 1) as usize,
+            aggression: num("AGGRESSION", 0) == 1,
+            schedules,
+            strategy_urgency,
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.partial_timeout_ms > 0 || self.aggression
+    }
+
+    /// Kelas urgensi order: tag `urgency=...` > STRATEGY_URGENCY > normal
+    fn urgency(&self, er: &ExecReport) -> String {
+        er.tags
+            .iter()
+            .find_map(|t| t.strip_prefix("urgency="))
+            .map(|s| s.to_ascii_lowercase())
+            .or_else(|| self.strategy_urgency.get(&er.strategy).cloned())
+            .unwrap_or_else(|| "normal".to_string())
+    }
+}
+
+/// Harga untuk level agresi (% spread dari near touch)
+pub fn aggression_px(side: Side, bid: i64, ask: i64, level: i64) -> i64 {
+    let spread = (ask - bid).max(1);
+    let step = spread * level / 100;
+    match side {
+        Side::Buy if level > 100 => (bid + step).max(ask + 1),
+        Side::Buy => bid + step,
+        Side::Sell if level > 100 => (ask - step).min(bid - 1),
+        Side::Sell => ask - step,
+    }
+}
+
+/// Child yang masih terbuka di venue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenChild {
+    pub venue: String,
+    pub symbol: String,
+    pub px: i64,
+    pub qty: i64,
+    pub filled: i64,
+}
+
+/// Order child terbuka (clone murah; diisi router/OMS saat kirim + fan-out ExecReport)
+#[derive(Clone, Default)]
+pub struct WorkingOrders {
+    inner: Arc<Mutex<HashMap<String, OpenChild>>>,
+}
+
+impl WorkingOrders {
+    /// Catat child yang dikirim ke venue (working sampai report final)
+    pub fn reserve(&self, venue: &str, child: &Order) {
+        let o = OpenChild { venue: venue.to_string(), symbol: child.symbol.clone(), px: child.px, qty: child.qty, filled: 0 };
+        self.inner.lock().unwrap().insert(child.cl_id.clone(), o);
+        self.publish(venue, &child.symbol);
+    }
+
+    pub fn on_report(&self, er: &ExecReport) {
+        let mut g = self.inner.lock().unwrap();
+        match er.status {
+            ExecStatus::Ack | ExecStatus::PartialFill => {
+                // order yang tidak tercatat saat kirim: harga limit tidak diketahui
+                let o = g.entry(er.cl_id.clone()).or_insert_with(|| OpenChild {
+                    venue: er.venue.clone(),
+                    symbol: er.symbol.clone(),
+                    px: er.avg_px,
+                    qty: er.order_qty,
+                    filled: 0,
+                });
+                o.filled = er.filled_qty;
+                if er.order_qty > 0 {
+                    o.qty = er.order_qty;
+                }
+            }
+            _ => {
+                if g.remove(&er.cl_id).is_none() {
+                    return;
+                }
+            }
+        }
+        drop(g);
+        self.publish(&er.venue, &er.symbol);
+    }
+
+    /// Child terbuka per cl_id (urut; rebuild-state)
+    pub fn snapshot(&self) -> BTreeMap<String, OpenChild> {
+        self.inner.lock().unwrap().iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+
+    /// Pulihkan child terbuka (resume checkpoint rebuild-state)
+    pub fn restore(&self, orders: BTreeMap<String, OpenChild>) {
+        self.inner.lock().unwrap().extend(orders);
+    }
+
+    /// (jumlah child, sisa notional USD) untuk symbol di semua venue
+    pub fn exposure(&self, symbol: &str) -> (usize, f64) {
+        let g = self.inner.lock().unwrap();
+        g.values().filter(|o| o.symbol == symbol).fold((0, 0.0), |(n, notional), o| {
+            let quote = crate::sizing::base_qty(symbol, (o.qty - o.filled).max(0)) * o.px as f64 / 100.0;
+            (n + 1, notional + crate::fx::to_usd(symbol, quote))
+        })
+    }
+
+    fn publish(&self, venue: &str, symbol: &str) {
+        if !symbol_admitted(symbol) {
+            return;
+        }
+        let n = self.inner.lock().unwrap().values().filter(|o| o.venue == venue && o.symbol == symbol).count();
+        OPEN_ORDERS.with_label_values(&[symbol, venue]).set(n as i64);
+        WORKING_NOTIONAL.with_label_values(&[symbol]).set(self.exposure(symbol).1);
+    }
+}
+
+/// Child yang dikelola taktik agresi
+struct Working {
+    er: ExecReport,     // report terakhir (cum filled, atribusi)
+    class: String,
+    started: Instant,   // ACK order pertama (jadwal tidak di-reset oleh replace)
+    step: usize,        // langkah jadwal yang sudah dijalankan
+    px: Option<i64>,    // harga terakhir yang dipasang OMS
+    base: String,       // cl_id child asal
+    n: u32,             // jumlah replace
+    replacing: bool,
+}
+
+/// Order child yang sudah terisi sebagian
+struct Partial {
+    er: Arc<ExecReport>,   // report terakhir (cum filled, side, tag, ...)
+    last_fill: Instant,
+    cancel_sent: bool,
+}
+
+/// Jumlah re-route dalam rantai cl_id (`...-R-B-R-A` = 2; termasuk rantai yang ID-nya diganti registry)
+fn reroute_depth(cl_id: &str) -> usize {
+    crate::order_ids::lineage(cl_id).iter().skip(1).filter(|s| *s == "R").count()
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn run(
+    mut exec_rx: mpsc::Receiver<Arc<ExecReport>>,
+    mut md_rx: broadcast::Receiver<MdTick>,
+    venue_cmd_txs: HashMap<String, mpsc::Sender<VenueCmd>>,
+    ord_tx: mpsc::Sender<Order>,
+    kill: KillSwitch,
+    stop: PortfolioStop,
+    cfg: OmsCfg,
+    open: WorkingOrders,
+) {
+    let timeout = Duration::from_millis(cfg.partial_timeout_ms);
+    info!(timeout_ms = cfg.partial_timeout_ms, reroute = cfg.reroute, aggression = cfg.aggression, "oms: started");
+    let mut partials: HashMap<String, Partial> = HashMap::new();
+    let mut working: HashMap<String, Working> = HashMap::new();
+    let mut quotes: HashMap<String, (i64, i64)> = HashMap::new(); // symbol -> (bid, ask)
+    let mut check = interval(Duration::from_millis(100));
+    check.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            md = md_rx.recv() => match md {
+                Ok(md) => { quotes.insert(md.symbol.clone(), (md.best_bid, md.best_ask)); }
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            er = exec_rx.recv() => {
+                let Some(er) = er else { break };
+                if cfg.aggression {
+                    track(&mut working, &er, &cfg);
+                }
+                if cfg.partial_timeout_ms == 0 {
+                    continue;
+                }
+                let Some(remaining) = track_partial(&mut partials, &er, &cfg, Instant::now()) else { continue };
+                if let Some(ord) = reroute(&er, remaining, &quotes, &kill, &stop, cfg.reroute_max) {
+                    let _ = ord_tx.send(ord).await;
+                }
+            }
+            _ = check.tick(), if !working.is_empty() || !partials.is_empty() => {
+                if !(kill.engaged() || stop.tripped()) {
+                    let reprices = reprice(&mut working, &quotes, &cfg, Instant::now());
+                    send_reprices(&mut working, reprices, &venue_cmd_txs, &open);
+                }
+                for (cl_id, er) in timed_out(&partials, timeout, Instant::now()) {
+                    let Some(tx) = venue_cmd_txs.get(&er.venue) else { continue };
+                    warn!(%cl_id, venue = %er.venue, filled = er.filled_qty, qty = er.order_qty, "partial fill timed out, cancelling remainder");
+                    let cmd = VenueCmd::Cancel { cl_id: cl_id.clone(), symbol: er.symbol.clone(), reason: "partial_timeout".to_string() };
+                    if tx.try_send(cmd).is_ok() {
+                        if let Some(p) = partials.get_mut(&cl_id) {
+                            p.cancel_sent = true;
+                        }
+                        PARTIAL_TIMEOUTS.with_label_values(&[&er.venue]).inc();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Update state timeout partial dari ExecReport. Some(sisa qty) = order final yang sisanya dibatalkan
+/// oleh OMS (cancel timeout), jadi sisa qty boleh di-re-route; cancel dari venue / user tidak di-re-route
+fn track_partial(partials: &mut HashMap<String, Partial>, er: &Arc<ExecReport>, cfg: &OmsCfg, now: Instant) -> Option<i64> {
+    match er.status {
+        ExecStatus::PartialFill => {
+            let p = partials
+                .entry(er.cl_id.clone())
+                .or_insert_with(|| Partial { er: er.clone(), last_fill: now, cancel_sent: false });
+            if er.filled_qty > p.er.filled_qty {
+                p.last_fill = now;
+            }
+            p.er = er.clone();
+            None
+        }
+        ExecStatus::Ack => None,
+        _ => {
+            let p = partials.remove(&er.cl_id)?;
+            let remaining = er.order_qty - er.filled_qty;
+            (p.cancel_sent && cfg.reroute && remaining > 0 && matches!(er.status, ExecStatus::Rejected(_))).then_some(remaining)
+        }
+    }
+}
+
+/// Partial yang tidak mendapat fill lagi selama `timeout` dan belum dibatalkan -> (cl_id, report terakhir)
+fn timed_out(partials: &HashMap<String, Partial>, timeout: Duration, now: Instant) -> Vec<(String, Arc<ExecReport>)> {
+    partials
+        .iter()
+        .filter(|(_, p)| !p.cancel_sent && now.saturating_duration_since(p.last_fill) >= timeout)
+        .map(|(cl_id, p)| (cl_id.clone(), p.er.clone()))
+        .collect()
+}
+
+/// Update state taktik agresi dari ExecReport
+fn track(working: &mut HashMap<String, Working>, er: &ExecReport, cfg: &OmsCfg) {
+    match er.status {
+        ExecStatus::Ack => {
+            // stop-limit dikelola exchange: tidak di-reprice
+            if working.contains_key(&er.cl_id) || er.side.is_none() || OrderType::from_tags(&er.tags) != OrderType::Limit {
+                return;
+            }
+            let class = cfg.urgency(er);
+            if cfg.schedules.get(&class).is_none_or(|s| s.is_empty()) {
+                return;
+            }
+            working.insert(
+                er.cl_id.clone(),
+                Working {
+                    er: er.clone(),
+                    class,
+                    started: Instant::now(),
+                    step: 0,
+                    px: None,
+                    base: er.cl_id.clone(),
+                    n: 0,
+                    replacing: false,
+                },
+            );
+        }
+        ExecStatus::PartialFill => {
+            if let Some(w) = working.get_mut(&er.cl_id) {
+                w.er = er.clone();
+            }
+        }
+        _ => {
+            working.remove(&er.cl_id);
+        }
+    }
+}
+
+/// Langkah jadwal yang jatuh tempo -> (cl_id lama, order pengganti, langkah sebelumnya);
+/// state order baru langsung dicatat
+fn reprice(
+    working: &mut HashMap<String, Working>,
+    quotes: &HashMap<String, (i64, i64)>,
+    cfg: &OmsCfg,
+    now: Instant,
+) -> Vec<(String, Order, usize)> {
+    let mut out = Vec::new();
+    let mut added = Vec::new();
+    for (cl_id, w) in working.iter_mut().filter(|(_, w)| !w.replacing) {
+        let Some(schedule) = cfg.schedules.get(&w.class) else { continue };
+        let elapsed = now.saturating_duration_since(w.started).as_millis() as u64;
+        let due = schedule.iter().take_while(|(ms, _)| *ms <= elapsed).count();
+        if due <= w.step {
+            continue;
+        }
+        let prev_step = std::mem::replace(&mut w.step, due);
+        let (Some(side), Some(&(bid, ask))) = (w.er.side, quotes.get(&w.er.symbol)) else { continue };
+        let px = aggression_px(side, bid, ask, schedule[due - 1].1);
+        let qty = w.er.order_qty - w.er.filled_qty;
+        if px <= 0 || qty <= 0 || w.px == Some(px) {
+            continue;
+        }
+        let n = w.n + 1;
+        let order = Order {
+            cl_id: crate::order_ids::derive(&w.base, &format!("P{n}")),
+            ts_ns: crate::clock::now_ns(),
+            symbol: w.er.symbol.clone(),
+            side,
+            px,
+            qty,
+            strategy: w.er.strategy.clone(),
+            tags: w.er.tags.clone(),
+            book: w.er.book.clone(),
+            expire_at_ns: None,
+            order_type: OrderType::Limit,
+        };
+        info!(%cl_id, new_cl_id = %order.cl_id, class = %w.class, level = schedule[due - 1].1, px, qty, "aggression: reprice");
+        ORDER_REPRICES.with_label_values(&[&w.class]).inc();
+        w.replacing = true;
+        let er = ExecReport { cl_id: order.cl_id.clone(), filled_qty: 0, avg_px: 0, order_qty: qty, ..w.er.clone() };
+        let next = Working { er, class: w.class.clone(), started: w.started, step: due, px: Some(px), base: w.base.clone(), n, replacing: false };
+        added.push((order.cl_id.clone(), next));
+        out.push((cl_id.clone(), order, prev_step));
+    }
+    working.extend(added);
+    out
+}
+
+/// Kirim replace ke gateway venue; gagal (channel penuh / venue tak dikenal) -> order pengganti dibuang
+/// dan child lama kembali ke langkah sebelumnya, jadi langkah ini diulang di tick berikutnya
+fn send_reprices(
+    working: &mut HashMap<String, Working>,
+    reprices: Vec<(String, Order, usize)>,
+    venue_cmd_txs: &HashMap<String, mpsc::Sender<VenueCmd>>,
+    open: &WorkingOrders,
+) {
+    for (cl_id, order, prev_step) in reprices {
+        let venue = working[&order.cl_id].er.venue.clone();
+        let sent = venue_cmd_txs
+            .get(&venue)
+            .is_some_and(|tx| tx.try_send(VenueCmd::Replace { cl_id: cl_id.clone(), order: order.clone() }).is_ok());
+        if sent {
+            open.reserve(&venue, &order);
+        } else {
+            working.remove(&order.cl_id);
+            if let Some(w) = working.get_mut(&cl_id) {
+                w.replacing = false;
+                w.step = prev_step;
+            }
+        }
+    }
+}
+
+/// Order re-route untuk sisa qty (None = tidak dikirim: kill switch / portfolio stop / batas rantai / tanpa MD)
+fn reroute(
+    er: &ExecReport,
+    qty: i64,
+    quotes: &HashMap<String, (i64, i64)>,
+    kill: &KillSwitch,
+    stop: &PortfolioStop,
+    max: usize,
+) -> Option<Order> {
+    if kill.engaged() || stop.tripped() || reroute_depth(&er.cl_id) >= max {
+        return None;
+    }
+    let (Some(side), Some(&(bid, ask))) = (er.side, quotes.get(&er.symbol)) else {
+        warn!(cl_id = %er.cl_id, "re-route skipped: no side / market data");
+        return None;
+    };
+    let px = match side {
+        Side::Buy => ask,
+        Side::Sell => bid,
+    };
+    let now = crate::clock::now_ns();
+    let ord = Order {
+        cl_id: crate::order_ids::derive(&er.cl_id, "R"),
+        ts_ns: now,
+        symbol: er.symbol.clone(),
+        side,
+        px,
+        qty,
+        strategy: er.strategy.clone(),
+        tags: er.tags.clone(),
+        book: er.book.clone(),
+        expire_at_ns: None,
+        order_type: OrderType::Limit,
+    };
+    info!(cl_id = %ord.cl_id, qty, px, "re-routing partial-fill remainder");
+    PARTIAL_REROUTES.inc();
+    Some(ord)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn child(cl_id: &str, px: i64, qty: i64) -> Order {
+        Order {
+            cl_id: cl_id.into(),
+            ts_ns: 0,
+            symbol: "BTCUSDT".into(),
+            side: Side::Buy,
+            px,
+            qty,
+            strategy: "mr".into(),
+            tags: vec![],
+            book: String::new(),
+            expire_at_ns: None,
+            order_type: OrderType::Limit,
+        }
+    }
+
+    fn report(cl_id: &str, status: ExecStatus, filled: i64) -> ExecReport {
+        ExecReport {
+            cl_id: cl_id.into(),
+            symbol: "BTCUSDT".into(),
+            status,
+            filled_qty: filled,
+            avg_px: 10_000,
+            ts_ns: 0,
+            venue: "A".into(),
+            side: Some(Side::Buy),
+            order_qty: 4,
+            strategy: "mr".into(),
+            tags: vec![],
+            book: String::new(),
+            exch_ts_ns: 0,
+        }
+    }
+
+    #[test]
+    fn working_notional_tracks_unfilled_remainder() {
+        let open = WorkingOrders::default();
+        open.reserve("A", &child("c1", 10_000, 4));
+        open.reserve("A", &child("c2", 20_000, 1));
+        // 4 × 100 + 1 × 200 (lot 1, USDT = USD)
+        assert_eq!(open.exposure("BTCUSDT"), (2, 600.0));
+
+        open.on_report(&report("c1", ExecStatus::PartialFill, 3));
+        assert_eq!(open.exposure("BTCUSDT"), (2, 300.0));
+        open.on_report(&report("c1", ExecStatus::Filled, 4));
+        open.on_report(&report("c2", ExecStatus::Rejected("REPLACED".into()), 0));
+        assert_eq!(open.exposure("BTCUSDT"), (0, 0.0));
+    }
+
+    #[test]
+    fn no_reroute_after_portfolio_stop() {
+        let (kill, stop) = (KillSwitch::default(), PortfolioStop::default());
+        let quotes: HashMap<String, (i64, i64)> = [("BTCUSDT".to_string(), (9_990, 10_010))].into_iter().collect();
+        let er = report("c1", ExecStatus::Rejected("CANCELED".into()), 1);
+        let ord = reroute(&er, 3, &quotes, &kill, &stop, 1).unwrap();
+        assert_eq!((ord.px, ord.qty), (10_010, 3));
+
+        stop.trip();
+        assert!(reroute(&er, 3, &quotes, &kill, &stop, 1).is_none());
+    }
+
+    #[test]
+    fn partial_timeout_cancels_then_reroutes_only_oms_cancels() {
+        let cfg = OmsCfg { partial_timeout_ms: 500, reroute: true, reroute_max: 1, ..OmsCfg::default() };
+        let timeout = Duration::from_millis(cfg.partial_timeout_ms);
+        let t0 = Instant::now();
+        let mut partials: HashMap<String, Partial> = HashMap::new();
+
+        // c1 terisi sebagian; fill baru di t0+300ms me-reset timer
+        assert_eq!(track_partial(&mut partials, &Arc::new(report("c1", ExecStatus::PartialFill, 1)), &cfg, t0), None);
+        let at = |ms| t0 + Duration::from_millis(ms);
+        track_partial(&mut partials, &Arc::new(report("c1", ExecStatus::PartialFill, 2)), &cfg, at(300));
+        assert!(timed_out(&partials, timeout, at(700)).is_empty());
+        let due = timed_out(&partials, timeout, at(800));
+        assert_eq!(due.iter().map(|(c, er)| (c.as_str(), er.filled_qty)).collect::<Vec<_>>(), [("c1", 2)]);
+        partials.get_mut("c1").unwrap().cancel_sent = true;
+        // cancel sudah terkirim: tidak dibatalkan dua kali
+        assert!(timed_out(&partials, timeout, at(2_000)).is_empty());
+        let canceled = Arc::new(report("c1", ExecStatus::Rejected("CANCELED".into()), 2));
+        assert_eq!(track_partial(&mut partials, &canceled, &cfg, at(900)), Some(2));
+        assert!(partials.is_empty());
+
+        // c2 dibatalkan venue / user sebelum timeout: sisa tidak di-re-route
+        track_partial(&mut partials, &Arc::new(report("c2", ExecStatus::PartialFill, 1)), &cfg, t0);
+        let canceled = Arc::new(report("c2", ExecStatus::Rejected("CANCELED".into()), 1));
+        assert_eq!(track_partial(&mut partials, &canceled, &cfg, at(100)), None);
+
+        // PARTIAL_REROUTE=0: cancel OMS tetap jalan, tanpa re-route
+        let no_reroute = OmsCfg { reroute: false, ..cfg.clone() };
+        track_partial(&mut partials, &Arc::new(report("c3", ExecStatus::PartialFill, 1)), &no_reroute, t0);
+        partials.get_mut("c3").unwrap().cancel_sent = true;
+        let canceled = Arc::new(report("c3", ExecStatus::Rejected("CANCELED".into()), 1));
+        assert_eq!(track_partial(&mut partials, &canceled, &no_reroute, at(600)), None);
+    }
+
+    #[test]
+    fn reroute_chain_capped_by_partial_reroute_max() {
+        let (kill, stop) = (KillSwitch::default(), PortfolioStop::default());
+        let quotes: HashMap<String, (i64, i64)> = [("BTCUSDT".to_string(), (9_990, 10_010))].into_iter().collect();
+        // router memberi suffix venue ke tiap child: c1-A -> re-route c1-A-R -> child c1-A-R-A
+        let canceled = |cl_id: &str| report(&format!("{cl_id}-A"), ExecStatus::Rejected("CANCELED".into()), 1);
+        let first = reroute(&canceled("c1"), 3, &quotes, &kill, &stop, 2).unwrap();
+        assert_eq!(first.cl_id, "c1-A-R");
+        let second = reroute(&canceled(&first.cl_id), 2, &quotes, &kill, &stop, 2).unwrap();
+        assert_eq!(second.qty, 2);
+        // rantai sudah 2 re-route = PARTIAL_REROUTE_MAX: berhenti
+        assert!(reroute(&canceled(&second.cl_id), 1, &quotes, &kill, &stop, 2).is_none());
+        assert!(reroute(&canceled(&first.cl_id), 2, &quotes, &kill, &stop, 1).is_none());
+    }
+
+    fn aggressive_cfg() -> OmsCfg {
+        let schedules = [("normal".to_string(), vec![(1_000, 50), (3_000, 100), (6_000, 125)])].into_iter().collect();
+        OmsCfg { aggression: true, schedules, ..OmsCfg::default() }
+    }
+
+    #[test]
+    fn aggression_px_levels_up_to_and_through_touch() {
+        // spread 20: 0 = join, 50 = mid, 100 = at touch, >100 = menembus touch
+        let levels = [0, 50, 100, 125, 101];
+        let buy: Vec<i64> = levels.iter().map(|&l| aggression_px(Side::Buy, 9_990, 10_010, l)).collect();
+        let sell: Vec<i64> = levels.iter().map(|&l| aggression_px(Side::Sell, 9_990, 10_010, l)).collect();
+        assert_eq!(buy, [9_990, 10_000, 10_010, 10_015, 10_011]);
+        assert_eq!(sell, [10_010, 10_000, 9_990, 9_985, 9_989]);
+        // spread 1 tick: level >100 tetap minimal 1 tick di luar touch
+        assert_eq!(aggression_px(Side::Buy, 10_000, 10_001, 110), 10_002);
+        assert_eq!(aggression_px(Side::Sell, 10_000, 10_001, 110), 9_999);
+    }
+
+    #[test]
+    fn reprice_steps_follow_schedule() {
+        let cfg = aggressive_cfg();
+        let quotes: HashMap<String, (i64, i64)> = [("BTCUSDT".to_string(), (9_990, 10_010))].into_iter().collect();
+        let mut working: HashMap<String, Working> = HashMap::new();
+        let t0 = Instant::now();
+        track(&mut working, &report("c1", ExecStatus::Ack, 0), &cfg);
+        let at = |ms| t0 + Duration::from_millis(ms);
+
+        assert!(reprice(&mut working, &quotes, &cfg, at(500)).is_empty());
+        let r1 = reprice(&mut working, &quotes, &cfg, at(1_500));
+        assert!(matches!(&r1[..], [(old, o, 0)] if old == "c1" && o.cl_id == "c1-P1" && (o.px, o.qty) == (10_000, 4)));
+        // child lama menunggu REPLACED: tidak di-reprice lagi
+        assert!(working["c1"].replacing);
+        track(&mut working, &report("c1", ExecStatus::Rejected("REPLACED".into()), 0), &cfg);
+        assert!(reprice(&mut working, &quotes, &cfg, at(2_500)).is_empty());
+
+        // fill parsial di pengganti: langkah berikutnya hanya membawa sisa qty, di touch lalu menembus touch
+        track(&mut working, &report("c1-P1", ExecStatus::PartialFill, 1), &cfg);
+        let r2 = reprice(&mut working, &quotes, &cfg, at(3_500));
+        assert!(matches!(&r2[..], [(old, o, 1)] if old == "c1-P1" && o.cl_id == "c1-P2" && (o.px, o.qty) == (10_010, 3)));
+        track(&mut working, &report("c1-P1", ExecStatus::Rejected("REPLACED".into()), 1), &cfg);
+        let r3 = reprice(&mut working, &quotes, &cfg, at(6_500));
+        assert!(matches!(&r3[..], [(_, o, 2)] if o.cl_id == "c1-P3" && o.px == 10_015));
+        track(&mut working, &report("c1-P2", ExecStatus::Rejected("REPLACED".into()), 0), &cfg);
+        // jadwal habis
+        assert!(reprice(&mut working, &quotes, &cfg, at(60_000)).is_empty());
+    }
+
+    #[test]
+    fn reprice_rolled_back_when_command_channel_full() {
+        let cfg = aggressive_cfg();
+        let quotes: HashMap<String, (i64, i64)> = [("BTCUSDT".to_string(), (9_990, 10_010))].into_iter().collect();
+        let mut working: HashMap<String, Working> = HashMap::new();
+        let open = WorkingOrders::default();
+        let (tx, mut rx) = mpsc::channel(1);
+        tx.try_send(VenueCmd::CancelAll { reason: "busy".into() }).unwrap();
+        let txs: HashMap<String, mpsc::Sender<VenueCmd>> = [("A".to_string(), tx)].into_iter().collect();
+        let t0 = Instant::now();
+        track(&mut working, &report("c1", ExecStatus::Ack, 0), &cfg);
+
+        let r = reprice(&mut working, &quotes, &cfg, t0 + Duration::from_millis(1_500));
+        send_reprices(&mut working, r, &txs, &open);
+        // pengganti dibuang, child lama kembali ke langkah 0 dan tidak menunggu replace
+        assert_eq!(working.keys().collect::<Vec<_>>(), ["c1"]);
+        assert!(!working["c1"].replacing && working["c1"].step == 0);
+        assert_eq!(open.exposure("BTCUSDT").0, 0);
+
+        // channel kosong lagi: langkah yang sama diulang dan terkirim
+        rx.try_recv().unwrap();
+        let r = reprice(&mut working, &quotes, &cfg, t0 + Duration::from_millis(1_600));
+        send_reprices(&mut working, r, &txs, &open);
+        assert!(matches!(rx.try_recv(), Ok(VenueCmd::Replace { cl_id, order }) if cl_id == "c1" && order.px == 10_000));
+        assert_eq!(open.exposure("BTCUSDT").0, 1);
+    }
+}
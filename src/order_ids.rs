@@ -0,0 +1,224 @@
+// ===============================
+// src/order_ids.rs
+// ===============================
+//
+// Registry ID order: generator cl_id ringkas + peta turunan & orderId exchange.
+//
+// - cl_id parent: prefix + snowflake base62 (ms sejak 2024-01-01 | instance 10 bit | seq 12 bit),
+//   mis. "C0mK4Hq2Tz5" (<= 12 char). Tiap ID dicek ke registry; tabrakan -> seq berikutnya
+//   (order_id_collisions_total).
+// - cl_id turunan (child venue, repost `-P{n}`, re-route `-R`, hedge `-L{i}-H{n}`): tetap
+//   `{parent}-{suffix}` selama muat di batas clientOrderId Binance (36 char, [A-Za-z0-9._:/-]);
+//   jika tidak, diganti ID baru dan relasinya disimpan di registry (`parent`, `lineage`).
+// - orderId exchange: `bind_exchange(cl_id, order_id)` -> `exchange_id` / `cl_id_of`.
+// Registry dibatasi ORDER_ID_REGISTRY_CAP entri (FIFO, entri tertua dibuang).
+//
+// ENV:
+//   ORDER_ID_INSTANCE=0          (0..1023; beda per proses yang berbagi akun, default dari pid)
+//   ORDER_ID_REGISTRY_CAP=100000
+//
+use ahash::{AHashMap as HashMap, AHashSet as HashSet};
+use once_cell::sync::Lazy;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use crate::metrics::ORDER_ID_COLLISIONS;
+
+/// Batas panjang clientOrderId Binance
+pub const MAX_LEN: usize = 36;
+
+/// 2024-01-01T00:00:00Z (ms)
+const EPOCH_MS: i128 = 1_704_067_200_000;
+const SEQ_BITS: u32 = 12;
+const INSTANCE_BITS: u32 = 10;
+
+const BASE62: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+fn base62(mut n: u64) -> String {
+    let mut out = Vec::with_capacity(11);
+    loop {
+        out.push(BASE62[(n % 62) as usize]);
+        n /= 62;
+        if n == 0 {
+            break;
+        }
+    }
+    out.reverse();
+    String::from_utf8(out).unwrap_or_default()
+}
+
+fn valid(id: &str) -> bool {
+    !id.is_empty() && id.len() <= MAX_LEN && id.bytes().all(|b| b.is_ascii_alphanumeric() || b"._:/-".contains(&b))
+}
+
+struct Inner {
+    instance: u64,
+    cap: usize,
+    last_ms: u64,
+    seq: u64,
+    live: HashSet<String>,
+    order: VecDeque<String>,                 // urutan insert (eviction FIFO)
+    derived: HashMap<String, (String, String)>, // cl_id -> (parent, suffix) untuk ID pengganti
+    exch: HashMap<String, u64>,              // cl_id -> orderId
+    by_exch: HashMap<u64, String>,           // orderId -> cl_id
+}
+
+impl Inner {
+    fn from_env() -> Self {
+        let instance = std::env::var("ORDER_ID_INSTANCE")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(std::process::id() as u64)
+            & ((1 << INSTANCE_BITS) - 1);
+        let cap = std::env::var("ORDER_ID_REGISTRY_CAP").ok().and_then(|s| s.parse().ok()).unwrap_or(100_000).max(1);
+        Self {
+            instance,
+            cap,
+            last_ms: 0,
+            seq: 0,
+            live: HashSet::new(),
+            order: VecDeque::new(),
+            derived: HashMap::new(),
+            exch: HashMap::new(),
+            by_exch: HashMap::new(),
+        }
+    }
+
+    fn insert(&mut self, id: &str) {
+        self.live.insert(id.to_string());
+        self.order.push_back(id.to_string());
+        while self.order.len() > self.cap {
+            let Some(old) = self.order.pop_front() else { break };
+            self.live.remove(&old);
+            self.derived.remove(&old);
+            if let Some(oid) = self.exch.remove(&old) {
+                self.by_exch.remove(&oid);
+            }
+        }
+    }
+
+    fn next(&mut self, prefix: &str, now_ns: i128) -> String {
+        let ms = ((now_ns / 1_000_000 - EPOCH_MS).max(0) as u64) & ((1 << 41) - 1);
+        // jam mundur / ms sama: lanjutkan seq di ms terakhir
+        if ms > self.last_ms {
+            self.last_ms = ms;
+            self.seq = 0;
+        }
+        loop {
+            if self.seq >= 1 << SEQ_BITS {
+                self.last_ms += 1;
+                self.seq = 0;
+            }
+            let raw = (self.last_ms << (INSTANCE_BITS + SEQ_BITS)) | (self.instance << SEQ_BITS) | self.seq;
+            self.seq += 1;
+            let id = format!("{prefix}{}", base62(raw));
+            if !self.live.contains(&id) {
+                self.insert(&id);
+                return id;
+            }
+            ORDER_ID_COLLISIONS.inc();
+        }
+    }
+}
+
+static REGISTRY: Lazy<Mutex<Inner>> = Lazy::new(|| Mutex::new(Inner::from_env()));
+
+/// cl_id baru (prefix "C" order, "M" paket multi-leg) pada waktu `now_ns`
+pub fn next(prefix: &str, now_ns: i128) -> String {
+    REGISTRY.lock().unwrap().next(prefix, now_ns)
+}
+
+/// cl_id turunan `{parent}-{suffix}`; ID baru jika melewati batas clientOrderId
+pub fn derive(parent: &str, suffix: &str) -> String {
+    let id = format!("{parent}-{suffix}");
+    if valid(&id) {
+        return id;
+    }
+    let mut g = REGISTRY.lock().unwrap();
+    let id = g.next("C", crate::clock::now_ns());
+    g.derived.insert(id.clone(), (parent.to_string(), suffix.to_string()));
+    id
+}
+
+/// Parent langsung dari cl_id turunan `derive(parent, suffix)`
+pub fn parent(cl_id: &str, suffix: &str) -> Option<String> {
+    if let Some((p, s)) = REGISTRY.lock().unwrap().derived.get(cl_id) {
+        return (s == suffix).then(|| p.clone());
+    }
+    cl_id.strip_suffix(suffix).and_then(|p| p.strip_suffix('-')).map(str::to_string)
+}
+
+/// Suffix turunan dari cl_id sampai parent asal (terdekat dulu), termasuk yang ID-nya diganti
+pub fn lineage(cl_id: &str) -> Vec<String> {
+    let g = REGISTRY.lock().unwrap();
+    let mut out = Vec::new();
+    let mut cur = cl_id.to_string();
+    // maks 64 langkah: rantai turunan selalu pendek, hanya pengaman loop
+    for _ in 0..64 {
+        let (p, s) = match g.derived.get(&cur) {
+            Some((p, s)) => (p.clone(), s.clone()),
+            None => match cur.rsplit_once('-') {
+                Some((p, s)) if !p.is_empty() => (p.to_string(), s.to_string()),
+                _ => break,
+            },
+        };
+        out.push(s);
+        cur = p;
+    }
+    out
+}
+
+/// Catat orderId exchange untuk cl_id
+pub fn bind_exchange(cl_id: &str, order_id: u64) {
+    let mut g = REGISTRY.lock().unwrap();
+    if !g.live.contains(cl_id) {
+        g.insert(cl_id);
+    }
+    g.exch.insert(cl_id.to_string(), order_id);
+    g.by_exch.insert(order_id, cl_id.to_string());
+}
+
+/// orderId exchange untuk cl_id (jika sudah di-ACK venue)
+#[cfg(any(feature = "binance", test))]
+pub fn exchange_id(cl_id: &str) -> Option<u64> {
+    REGISTRY.lock().unwrap().exch.get(cl_id).copied()
+}
+
+/// cl_id untuk orderId exchange
+#[cfg(any(feature = "binance", test))]
+pub fn cl_id_of(order_id: u64) -> Option<String> {
+    REGISTRY.lock().unwrap().by_exch.get(&order_id).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derived_ids_fit_client_order_id_limit() {
+        let parent = next("C", 1_800_000_000_000_000_000);
+        assert!(parent.len() <= 12);
+        assert_eq!(derive(&parent, "A"), format!("{parent}-A"));
+
+        let long_venue = "binance_spot_primary_account_eu";
+        let child = derive(&parent, long_venue);
+        assert!(valid(&child));
+        assert_eq!(super::parent(&child, long_venue), Some(parent.clone()));
+
+        // rantai re-route yang memanjang tetap <= 36 char dan kedalamannya tetap terbaca
+        let mut id = child;
+        for venue in ["B", "A", "B", "A", "B", "A", "B"] {
+            id = derive(&derive(&id, "R"), venue);
+            assert!(valid(&id), "{id}");
+        }
+        assert_eq!(lineage(&id).iter().filter(|s| *s == "R").count(), 7);
+    }
+
+    #[test]
+    fn exchange_ids_map_both_ways() {
+        let cl_id = next("C", 1_800_000_000_000_000_000);
+        bind_exchange(&cl_id, 987_654_321);
+        assert_eq!(exchange_id(&cl_id), Some(987_654_321));
+        assert_eq!(cl_id_of(987_654_321), Some(cl_id));
+    }
+}
@@ -0,0 +1,164 @@
+// ===============================
+// src/pipeline.rs
+// ===============================
+//
+// Pipeline per symbol (PIPELINE=per_symbol): alih-alih setiap consumer berlangganan bus MD
+// semua symbol lalu membuang symbol lain, satu task dispatcher membaca bus sekali dan
+// meneruskan tiap tick ke task pipeline milik symbolnya lewat channel bounded:
+//
+//   bus tick -> dispatcher -> pipeline:<SYM> -> worker strategi <SYM> (mpsc bounded, per worker)
+//                                            -> positions:<SYM>       (watch: hanya mid terbaru)
+//
+// - worker strategi dijalankan per (symbol, strategi, worker) dan hanya melihat symbolnya sendiri
+//   (kunci snapshot: "<strategi>@<SYM>#<worker>")
+// - channel penuh -> tick dibuang (pipeline_dropped_total{symbol,stage}), consumer lambat
+//   tidak menahan symbol lain
+// - positions dikonflasi: mark-to-market memakai quote terbaru, gap seq dicek di task pipeline
+// Default (PIPELINE=shared) tetap bus broadcast bersama seperti sebelumnya.
+//
+// ENV:
+//   PIPELINE=shared|per_symbol
+//   PIPELINE_CHANNEL_CAP=1024
+//
+use ahash::AHashMap as HashMap;
+use std::sync::Arc;
+use tokio::sync::{broadcast, mpsc, watch};
+use tracing::warn;
+
+use crate::book_analytics::TickSrc;
+use crate::domain::{EnrichedTick, MdTick};
+use crate::md_seq::GapDetector;
+use crate::metrics::{symbol_label, PIPELINE_DROPPED};
+use crate::supervisor::{self, Relay};
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PipelineCfg {
+    pub per_symbol: bool,
+    pub capacity: usize,
+}
+
+impl PipelineCfg {
+    pub fn from_env() -> Self {
+        Self {
+            per_symbol: std::env::var("PIPELINE").is_ok_and(|v| v.eq_ignore_ascii_case("per_symbol")),
+            capacity: std::env::var("PIPELINE_CHANNEL_CAP").ok().and_then(|s| s.parse().ok()).unwrap_or(1024).max(1),
+        }
+    }
+}
+
+/// Sisi keluaran pipeline per symbol, dikumpulkan sebelum `spawn`
+pub struct Pipelines {
+    cap: usize,
+    strategies: HashMap<String, Vec<mpsc::Sender<EnrichedTick>>>,
+    positions: HashMap<String, Arc<watch::Sender<Option<MdTick>>>>,
+}
+
+impl Pipelines {
+    pub fn new(symbols: &[String], cap: usize) -> Self {
+        let positions = symbols.iter().map(|s| (s.clone(), Arc::new(watch::channel(None).0))).collect();
+        Self { cap, strategies: HashMap::new(), positions }
+    }
+
+    /// Sumber tick untuk satu worker strategi `symbol`
+    pub fn strategy_src(&mut self, symbol: &str) -> TickSrc {
+        let (tx, rx) = mpsc::channel(self.cap);
+        self.strategies.entry(symbol.to_string()).or_default().push(tx);
+        TickSrc::Symbol(Relay::new(rx, self.cap))
+    }
+
+    /// Sumber MD untuk positions `symbol` (None = symbol tanpa pipeline)
+    pub fn positions_src(&self, symbol: &str) -> Option<PosMdSrc> {
+        self.positions.get(symbol).map(|tx| PosMdSrc::Conflated(tx.subscribe()))
+    }
+
+    /// Jalankan dispatcher + task pipeline per symbol, membaca tick dari `src`
+    pub fn spawn(&self, src: TickSrc) {
+        let mut routes: HashMap<String, mpsc::Sender<EnrichedTick>> = HashMap::new();
+        for (symbol, pos_tx) in &self.positions {
+            let (tx, rx) = mpsc::channel::<EnrichedTick>(self.cap);
+            routes.insert(symbol.clone(), tx);
+            let strategies = self.strategies.get(symbol).cloned().unwrap_or_default();
+            let (rx, pos_tx, sym) = (Relay::new(rx, self.cap), pos_tx.clone(), symbol.clone());
+            supervisor::spawn(format!("pipeline:{symbol}"), move || {
+                run_symbol(sym.clone(), rx.attach(), strategies.clone(), pos_tx.clone())
+            });
+        }
+        supervisor::spawn("pipeline:dispatch", move || dispatch(src.clone(), routes.clone()));
+    }
+}
+
+/// Bus tick -> channel pipeline per symbol
+async fn dispatch(src: TickSrc, routes: HashMap<String, mpsc::Sender<EnrichedTick>>) {
+    let mut rx = src.subscribe();
+    loop {
+        match rx.recv().await {
+            Ok(t) => {
+                let Some(tx) = routes.get(&t.tick.symbol) else { continue };
+                if let Err(e) = tx.try_send(t) {
+                    let t = e.into_inner();
+                    PIPELINE_DROPPED.with_label_values(&[symbol_label(&t.tick.symbol), "dispatch"]).inc();
+                }
+            }
+            Err(broadcast::error::RecvError::Lagged(n)) => warn!(n, "pipeline dispatcher lagged"),
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
+
+/// Pipeline satu symbol: fan-out ke worker strategi symbol ini + quote terbaru ke positions
+async fn run_symbol(
+    symbol: String,
+    mut rx: mpsc::Receiver<EnrichedTick>,
+    strategies: Vec<mpsc::Sender<EnrichedTick>>,
+    pos_tx: Arc<watch::Sender<Option<MdTick>>>,
+) {
+    let mut gaps = GapDetector::new(format!("pipeline:{symbol}"));
+    while let Some(t) = rx.recv().await {
+        gaps.observe(&t.tick);
+        for tx in &strategies {
+            if tx.try_send(t.clone()).is_err() {
+                PIPELINE_DROPPED.with_label_values(&[symbol_label(&symbol), "strategy"]).inc();
+            }
+        }
+        pos_tx.send_replace(Some(t.tick));
+    }
+}
+
+/// Sumber MD untuk task positions
+#[derive(Clone)]
+pub enum PosMdSrc {
+    Shared(broadcast::Sender<MdTick>),
+    Conflated(watch::Receiver<Option<MdTick>>),
+}
+
+impl PosMdSrc {
+    pub fn subscribe(&self) -> PosMdRx {
+        match self {
+            PosMdSrc::Shared(tx) => PosMdRx::Shared(tx.subscribe()),
+            PosMdSrc::Conflated(rx) => PosMdRx::Conflated(rx.clone()),
+        }
+    }
+}
+
+pub enum PosMdRx {
+    Shared(broadcast::Receiver<MdTick>),
+    Conflated(watch::Receiver<Option<MdTick>>),
+}
+
+impl PosMdRx {
+    /// Tick berikutnya (None = lagged / sumber tertutup)
+    pub async fn recv(&mut self) -> Option<MdTick> {
+        match self {
+            PosMdRx::Shared(rx) => rx.recv().await.ok(),
+            PosMdRx::Conflated(rx) => {
+                rx.changed().await.ok()?;
+                rx.borrow_and_update().clone()
+            }
+        }
+    }
+
+    /// Bus bersama: semua tick (gap seq dicek consumer); konflasi: gap dicek di pipeline
+    pub fn is_shared(&self) -> bool {
+        matches!(self, PosMdRx::Shared(_))
+    }
+}
@@ -0,0 +1,288 @@
+// ===============================
+// src/portfolio_stop.rs
+// ===============================
+//
+// Stop level portofolio (di atas kill switch / auto-disable per strategi):
+// - equity = PORTFOLIO_EQUITY_USD + realized + unrealized semua symbol (USD, lihat fx::portfolio_pnl)
+// - drawdown = puncak equity sesi - equity sekarang; puncak dimulai saat start / re-arm / hari trading baru
+// - drawdown >= ambang (PORTFOLIO_STOP_DD_USD, atau PORTFOLIO_STOP_DD_PCT dari puncak; yang lebih ketat):
+//     1) cancel-all semua venue (kill_switch::cancel_all, alasan `portfolio_stop`)
+//     2) flatten semua posisi lewat jalur force-exit (Signal bertag `force_exit` + `portfolio_stop`,
+//        per (strategi, symbol), harga best bid/ask), diulang tiap PORTFOLIO_STOP_RETRY_SECS
+//        selama masih ada posisi
+//     3) trading terkunci: risk menolak semua Signal selain force-exit (RiskError::PortfolioStop)
+//        sampai di-re-arm manual (POST /admin/portfolio_stop/rearm). Jalur yang melewati risk ikut
+//        berhenti: re-route & reprice OMS (oms.rs) dan remediasi legging (multileg.rs)
+// - alert email `portfolio_stop`; gauge `portfolio_stop_tripped` & `portfolio_drawdown_usd`
+//
+// Catatan: kill switch yang di-engage ikut memblok Signal flatten (kill switch = tidak ada order sama sekali).
+//
+// ENV:
+//   PORTFOLIO_STOP_DD_USD=0          (0 = nonaktif)
+//   PORTFOLIO_STOP_DD_PCT=0          (% dari puncak equity; butuh PORTFOLIO_EQUITY_USD)
+//   PORTFOLIO_EQUITY_USD=0           (modal awal sesi)
+//   PORTFOLIO_STOP_CHECK_SECS=1
+//   PORTFOLIO_STOP_RETRY_SECS=10
+//
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio::time::{interval, Duration, MissedTickBehavior};
+use tracing::warn;
+
+use crate::alerts::{AlertKind, Alerts};
+use crate::books::BookCfg;
+use crate::clock;
+use crate::domain::{Side, Signal};
+use crate::feed_health::FeedHealth;
+use crate::holding::FORCE_EXIT_TAG;
+use crate::kill_switch::KillSwitch;
+use crate::metrics::{PORTFOLIO_DRAWDOWN_USD, PORTFOLIO_STOP};
+use crate::positions::PositionFeed;
+
+/// Tag tambahan Signal flatten portofolio (di samping FORCE_EXIT_TAG)
+pub const PORTFOLIO_STOP_TAG: &str = "portfolio_stop";
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PortfolioStopCfg {
+    pub dd_usd: f64,
+    pub dd_pct: f64,
+    pub equity_usd: f64,
+    pub check_secs: u64,
+    pub retry_secs: u64,
+}
+
+impl PortfolioStopCfg {
+    pub fn from_env() -> Self {
+        let num = |k: &str| std::env::var(k).ok().and_then(|s| s.trim().parse::<f64>().ok()).unwrap_or(0.0).max(0.0);
+        let secs = |k: &str, d: u64| std::env::var(k).ok().and_then(|s| s.trim().parse().ok()).unwrap_or(d);
+        Self {
+            dd_usd: num("PORTFOLIO_STOP_DD_USD"),
+            dd_pct: num("PORTFOLIO_STOP_DD_PCT"),
+            equity_usd: num("PORTFOLIO_EQUITY_USD"),
+            check_secs: secs("PORTFOLIO_STOP_CHECK_SECS", 1),
+            retry_secs: secs("PORTFOLIO_STOP_RETRY_SECS", 10),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.dd_usd > 0.0 || self.dd_pct > 0.0
+    }
+
+    /// Ambang drawdown USD untuk puncak `high` (None = tanpa ambang)
+    fn limit(&self, high: f64) -> Option<f64> {
+        let usd = (self.dd_usd > 0.0).then_some(self.dd_usd);
+        let pct = (self.dd_pct > 0.0 && high > 0.0).then(|| high * self.dd_pct / 100.0);
+        match (usd, pct) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StopStatus {
+    pub tripped: bool,
+    pub equity: f64,
+    pub high: f64,
+    pub drawdown: f64,
+    pub limit: Option<f64>,
+    pub tripped_ns: i128,
+}
+
+#[derive(Default)]
+struct Inner {
+    status: StopStatus,
+    started: bool, // puncak sudah diinisialisasi sejak start / re-arm
+    last_flatten_ns: Option<i128>,
+}
+
+/// Handle portfolio stop (clone murah; diisi task `run`, dibaca risk & admin)
+#[derive(Clone, Default)]
+pub struct PortfolioStop {
+    cfg: Arc<PortfolioStopCfg>,
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl PortfolioStop {
+    pub fn new(cfg: PortfolioStopCfg) -> Self {
+        Self { cfg: Arc::new(cfg), inner: Arc::default() }
+    }
+
+    /// true = trading terkunci (hanya force-exit yang lolos risk)
+    pub fn tripped(&self) -> bool {
+        self.inner.lock().unwrap().status.tripped
+    }
+
+    #[cfg(test)]
+    pub(crate) fn trip(&self) {
+        self.inner.lock().unwrap().status.tripped = true;
+    }
+
+    #[cfg(any(feature = "admin-api", test))]
+    pub fn status(&self) -> StopStatus {
+        self.inner.lock().unwrap().status.clone()
+    }
+
+    /// Update equity; Some(status) jika stop baru saja terpicu
+    fn observe(&self, equity: f64, now: i128) -> Option<StopStatus> {
+        let mut g = self.inner.lock().unwrap();
+        if !g.started {
+            g.started = true;
+            g.status.high = equity;
+        }
+        let st = &mut g.status;
+        st.equity = equity;
+        if !st.tripped {
+            st.high = st.high.max(equity);
+        }
+        st.drawdown = (st.high - equity).max(0.0);
+        st.limit = self.cfg.limit(st.high);
+        PORTFOLIO_DRAWDOWN_USD.set(st.drawdown);
+        if st.tripped || st.limit.is_none_or(|l| st.drawdown < l) {
+            return None;
+        }
+        st.tripped = true;
+        st.tripped_ns = now;
+        PORTFOLIO_STOP.set(1);
+        Some(st.clone())
+    }
+
+    /// Re-arm manual (admin): buka kunci trading, puncak baru = equity berikutnya
+    #[cfg(any(feature = "admin-api", test))]
+    pub fn rearm(&self) -> StopStatus {
+        let mut g = self.inner.lock().unwrap();
+        if g.status.tripped {
+            warn!(equity = g.status.equity, "portfolio stop re-armed");
+        }
+        g.started = false;
+        g.status.tripped = false;
+        g.status.tripped_ns = 0;
+        g.status.drawdown = 0.0;
+        g.last_flatten_ns = None;
+        PORTFOLIO_STOP.set(0);
+        PORTFOLIO_DRAWDOWN_USD.set(0.0);
+        g.status.clone()
+    }
+
+    /// Hari trading baru: puncak equity mulai lagi dari equity berikutnya (stop yang terpicu tetap terkunci)
+    pub fn new_day(&self) {
+        let mut g = self.inner.lock().unwrap();
+        if !g.status.tripped {
+            g.started = false;
+        }
+    }
+
+    /// true jika flatten perlu (di-)kirim sekarang
+    fn flatten_due(&self, now: i128) -> bool {
+        let mut g = self.inner.lock().unwrap();
+        let retry_ns = self.cfg.retry_secs as i128 * 1_000_000_000;
+        if !g.status.tripped || g.last_flatten_ns.is_some_and(|t| now - t < retry_ns) {
+            return false;
+        }
+        g.last_flatten_ns = Some(now);
+        true
+    }
+}
+
+/// Signal force-exit untuk semua posisi terbuka (per strategi; posisi tanpa atribusi strategi sebagai `portfolio_stop`)
+fn flatten_signals(positions: &PositionFeed, feed: &FeedHealth, books: &BookCfg, now: i128) -> Vec<Signal> {
+    let mut out = Vec::new();
+    for snap in positions.all() {
+        let mut open: Vec<(String, i64)> =
+            snap.state.by_strategy.iter().filter(|(_, p)| p.qty != 0).map(|(s, p)| (s.clone(), p.qty)).collect();
+        if open.is_empty() && snap.state.total_qty != 0 {
+            open.push((PORTFOLIO_STOP_TAG.to_string(), snap.state.total_qty));
+        }
+        if open.is_empty() {
+            continue;
+        }
+        let Some((bid, ask)) = feed.quote(&snap.symbol) else {
+            warn!(symbol = %snap.symbol, "portfolio stop: no quote for flatten, retrying later");
+            continue;
+        };
+        for (strategy, qty) in open {
+            out.push(Signal {
+                ts_ns: now,
+                symbol: snap.symbol.clone(),
+                side: if qty > 0 { Side::Sell } else { Side::Buy },
+                px: if qty > 0 { bid } else { ask },
+                qty: qty.abs(),
+                book: books.book_for(&strategy),
+                strategy,
+                tags: vec![FORCE_EXIT_TAG.to_string(), PORTFOLIO_STOP_TAG.to_string()],
+                quote_notional: None,
+            });
+        }
+    }
+    out
+}
+
+/// Task: pantau equity portofolio; saat terpicu cancel-all + flatten sampai posisi rata
+pub async fn run(
+    stop: PortfolioStop,
+    kill: KillSwitch,
+    positions: PositionFeed,
+    feed: FeedHealth,
+    books: BookCfg,
+    sig_tx: mpsc::Sender<Signal>,
+    alerts: Alerts,
+) {
+    let mut tick = interval(Duration::from_secs(stop.cfg.check_secs.max(1)));
+    tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        tick.tick().await;
+        let now = clock::now_ns();
+        let (realized, unrealized) = crate::fx::portfolio_pnl();
+        if let Some(st) = stop.observe(stop.cfg.equity_usd + realized + unrealized, now) {
+            let limit = st.limit.unwrap_or_default();
+            warn!(equity = st.equity, high = st.high, drawdown = st.drawdown, limit, "PORTFOLIO STOP: drawdown limit hit, flattening");
+            let venues = kill.cancel_all(PORTFOLIO_STOP_TAG);
+            alerts.notify(
+                AlertKind::PortfolioStop,
+                &[
+                    ("equity", format!("{:.2}", st.equity)),
+                    ("high", format!("{:.2}", st.high)),
+                    ("drawdown", format!("{:.2}", st.drawdown)),
+                    ("limit", format!("{limit:.2}")),
+                    ("venues", venues.to_string()),
+                ],
+            );
+        }
+        if !stop.flatten_due(now) {
+            continue;
+        }
+        for sig in flatten_signals(&positions, &feed, &books, now) {
+            warn!(strategy = %sig.strategy, symbol = %sig.symbol, side = ?sig.side, qty = sig.qty, "portfolio stop: flatten");
+            if sig_tx.send(sig).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trips_on_drawdown_from_session_high_and_locks_until_rearm() {
+        let stop = PortfolioStop::new(PortfolioStopCfg { dd_usd: 500.0, dd_pct: 4.0, equity_usd: 10_000.0, check_secs: 1, retry_secs: 10 });
+        assert!(stop.observe(10_000.0, 1).is_none());
+        assert!(stop.observe(10_200.0, 2).is_none());
+        // ambang = min(500, 4% x 10_200 = 408)
+        assert!(stop.observe(9_800.0, 3).is_none());
+        let st = stop.observe(9_790.0, 4).unwrap();
+        assert!(stop.tripped());
+        assert_eq!((st.high, st.limit), (10_200.0, Some(408.0)));
+        // tetap terkunci walau equity pulih; puncak tidak bergerak
+        assert!(stop.observe(10_500.0, 5).is_none() && stop.tripped());
+        assert!(stop.flatten_due(5) && !stop.flatten_due(6));
+
+        stop.rearm();
+        assert!(!stop.tripped() && !stop.flatten_due(100 * 1_000_000_000));
+        // puncak baru dari equity setelah re-arm
+        assert!(stop.observe(9_000.0, 7).is_none());
+        assert_eq!(stop.status().high, 9_000.0);
+    }
+}
@@ -0,0 +1,188 @@
+// ===============================
+// src/pos_diff.rs
+// ===============================
+//
+// Diff posisi antar sumber untuk mendeteksi drift akuntansi lebih awal:
+// live (SNAPSHOT_FILE) vs exchange-reported vs replay (rebuild-state).
+//
+// Format input (dideteksi otomatis):
+// - snapshot engine (bincode, SNAPSHOT_FILE)            -> posisi per symbol & venue (lot)
+// - JSON output `rebuild-state` ({"positions": {...}})  -> posisi per symbol & venue (lot)
+// - JSON exchange-reported, qty dalam unit BASE (dikonversi ke lot via LOT_SIZES):
+//     {"BTCUSDT": 0.0123}                       (total per symbol)
+//     {"BTCUSDT": {"binance": 0.0123}}          (per venue)
+//
+// File pertama = referensi; tiap file lain dibandingkan terhadapnya. Per symbol: baris per venue
+// (jika kedua sisi punya rincian venue) + baris total. Drift = |selisih| lot; baris dengan drift
+// di atas ambang (POS_DRIFT_MAX per symbol, `*` = default, default 0 = drift apa pun) adalah breach.
+// Breach -> exit code 1 + alert email `position_drift` (alerts.rs, jika SMTP dikonfigurasi).
+//
+// ENV:
+//   POS_DRIFT_MAX=*:0,BTCUSDT:2   (lot)
+//
+// Subcommand: dma_bot diff-positions state.snap exchange.json replay.json
+//
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+use crate::alerts::{AlertKind, Alerts};
+use crate::domain::SymbolState;
+
+/// Kunci venue untuk sumber yang hanya punya total per symbol
+const TOTAL: &str = "*";
+
+/// symbol -> venue -> qty (lot); venue TOTAL = total symbol
+pub type Positions = BTreeMap<String, BTreeMap<String, i64>>;
+
+fn from_states(states: &BTreeMap<String, SymbolState>) -> Positions {
+    states
+        .iter()
+        .map(|(sym, st)| {
+            let mut v: BTreeMap<String, i64> = st.by_venue.iter().map(|(k, p)| (k.clone(), p.qty)).collect();
+            v.insert(TOTAL.to_string(), st.total_qty);
+            (sym.clone(), v)
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Reported {
+    Total(f64),
+    ByVenue(BTreeMap<String, f64>),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonInput {
+    Rebuilt { positions: BTreeMap<String, SymbolState> },
+    Reported(BTreeMap<String, Reported>),
+}
+
+/// Baca file posisi (snapshot bincode / JSON rebuild-state / JSON exchange-reported)
+pub fn load(path: &str) -> Result<Positions, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("{path}: {e}"))?;
+    if let Ok(input) = serde_json::from_slice::<JsonInput>(&bytes) {
+        return Ok(match input {
+            JsonInput::Rebuilt { positions } => from_states(&positions),
+            JsonInput::Reported(map) => map
+                .into_iter()
+                .map(|(sym, r)| {
+                    let lots = |base: f64| crate::sizing::lots_from_base(&sym, base);
+                    let mut v: BTreeMap<String, i64> = match &r {
+                        Reported::Total(q) => [(TOTAL.to_string(), lots(*q))].into(),
+                        Reported::ByVenue(m) => m.iter().map(|(k, q)| (k.clone(), lots(*q))).collect(),
+                    };
+                    let total = v.values().sum();
+                    v.entry(TOTAL.to_string()).or_insert(total);
+                    (sym, v)
+                })
+                .collect(),
+        });
+    }
+    crate::snapshot::load(path)
+        .map(|s| from_states(&s.positions))
+        .ok_or_else(|| format!("{path}: not a snapshot, rebuild-state or exchange position file"))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DriftRow {
+    pub symbol: String,
+    pub venue: String,
+    pub left: i64,
+    pub right: i64,
+    pub drift: i64,
+    pub breach: bool,
+}
+
+/// Bandingkan dua sumber; `max_drift(symbol)` = ambang lot
+pub fn diff(left: &Positions, right: &Positions, max_drift: impl Fn(&str) -> i64) -> Vec<DriftRow> {
+    let empty = BTreeMap::new();
+    let symbols: std::collections::BTreeSet<&String> = left.keys().chain(right.keys()).collect();
+    let mut rows = Vec::new();
+    for sym in symbols {
+        let (l, r) = (left.get(sym).unwrap_or(&empty), right.get(sym).unwrap_or(&empty));
+        // rincian venue hanya jika kedua sisi punya (symbol tanpa posisi di satu sisi = semua venue 0)
+        let detailed = |m: &BTreeMap<String, i64>| m.is_empty() || m.keys().any(|k| k != TOTAL);
+        let venues: std::collections::BTreeSet<&String> = if detailed(l) && detailed(r) {
+            l.keys().chain(r.keys()).collect()
+        } else {
+            [l.keys().chain(r.keys()).find(|k| *k == TOTAL)].into_iter().flatten().collect()
+        };
+        for venue in venues {
+            let (a, b) = (l.get(venue).copied().unwrap_or(0), r.get(venue).copied().unwrap_or(0));
+            let drift = (a - b).abs();
+            rows.push(DriftRow {
+                symbol: sym.clone(),
+                venue: venue.clone(),
+                left: a,
+                right: b,
+                drift,
+                breach: drift > max_drift(sym),
+            });
+        }
+    }
+    rows
+}
+
+/// Subcommand `diff-positions`; true jika ada breach
+pub async fn run(files: &[String], max_drift: &ahash::AHashMap<String, f64>, alerts: Alerts) -> Result<bool, String> {
+    let [reference, others @ ..] = files else {
+        return Err("need at least two files".into());
+    };
+    if others.is_empty() {
+        return Err("need at least two files".into());
+    }
+    let base = load(reference)?;
+    let default = max_drift.get(TOTAL).copied().unwrap_or(0.0) as i64;
+    let limit = |sym: &str| max_drift.get(sym).map(|v| *v as i64).unwrap_or(default);
+
+    let mut breaches = Vec::new();
+    for other in others {
+        let rows = diff(&base, &load(other)?, limit);
+        println!("== {reference} vs {other}");
+        println!("{:<12} {:<16} {:>14} {:>14} {:>10}  status", "symbol", "venue", "left", "right", "drift");
+        for r in &rows {
+            let status = if r.breach { "BREACH" } else if r.drift > 0 { "drift" } else { "ok" };
+            println!("{:<12} {:<16} {:>14} {:>14} {:>10}  {status}", r.symbol, r.venue, r.left, r.right, r.drift);
+            if r.breach {
+                breaches.push(format!("{} {} {}={} {}={} drift={}", r.symbol, r.venue, reference, r.left, other, r.right, r.drift));
+            }
+        }
+    }
+    if !breaches.is_empty() {
+        alerts.notify(AlertKind::PositionDrift, &[("breaches", breaches.len().to_string()), ("details", breaches.join("\n"))]);
+    }
+    Ok(!breaches.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(rows: &[(&str, &str, i64)]) -> Positions {
+        let mut p = Positions::new();
+        for (s, v, q) in rows {
+            p.entry(s.to_string()).or_default().insert(v.to_string(), *q);
+        }
+        p
+    }
+
+    #[test]
+    fn reports_drift_per_venue_and_total_with_thresholds() {
+        let live = pos(&[("BTCUSDT", "A", 3), ("BTCUSDT", "B", 2), ("BTCUSDT", "*", 5), ("ETHUSDT", "A", 1), ("ETHUSDT", "*", 1)]);
+        let replay = pos(&[("BTCUSDT", "A", 3), ("BTCUSDT", "B", 1), ("BTCUSDT", "*", 4)]);
+        let rows = diff(&live, &replay, |s| if s == "BTCUSDT" { 1 } else { 0 });
+        let get = |s: &str, v: &str| rows.iter().find(|r| r.symbol == s && r.venue == v).unwrap().clone();
+        assert_eq!(get("BTCUSDT", "B").drift, 1);
+        assert!(!get("BTCUSDT", "B").breach);
+        // symbol hilang di sisi replay = 0
+        assert!(get("ETHUSDT", "A").breach);
+
+        // exchange hanya total: bandingkan total saja
+        let exchange = pos(&[("BTCUSDT", "*", 7)]);
+        let rows = diff(&live, &exchange, |_| 0);
+        assert_eq!(rows.iter().filter(|r| r.symbol == "BTCUSDT").count(), 1);
+        assert_eq!(rows[0].drift, 2);
+    }
+}
@@ -2,87 +2,198 @@
 // src/positions.rs (PnL & Inventory tracker)
 // ===============================
 
-use tokio::sync::{broadcast, watch};
-use crate::domain::{ExecReport, InvSnapshot, MdTick, Side, SymbolState, VenuePosition};
-use crate::metrics::{INV_QTY, INV_TOTAL_QTY, PNL_REALIZED, PNL_UNREALIZED};
+use ahash::AHashMap as HashMap;
+use std::sync::{Arc, RwLock};
+use tokio::sync::watch;
+use crate::domain::{ExecReport, FillDeltas, InvSnapshot, Side, SymbolState, VenuePosition};
+use crate::books;
+use crate::margin;
+use crate::md_seq::GapDetector;
+use crate::pipeline::PosMdRx;
+use crate::metrics::{INV_QTY, INV_QTY_BY_BOOK, INV_TOTAL_QTY, PNL_REALIZED, PNL_REALIZED_BY_BOOK, PNL_REALIZED_BY_STRATEGY, PNL_UNREALIZED};
+use crate::snapshot::Snapshots;
 
+/// Handle posisi terbaru per symbol (diisi task positions saat fill, dibaca worker strategi).
+/// Kosong di proses `PROCESS_ROLE=feed` (posisi hidup di proses exec).
+#[derive(Clone, Default)]
+pub struct PositionFeed {
+    inner: Arc<RwLock<HashMap<String, Arc<InvSnapshot>>>>,
+}
+
+impl PositionFeed {
+    pub fn publish(&self, snap: InvSnapshot) {
+        self.inner.write().unwrap().insert(snap.symbol.clone(), Arc::new(snap));
+    }
+
+    pub fn get(&self, symbol: &str) -> Option<Arc<InvSnapshot>> {
+        self.inner.read().unwrap().get(symbol).cloned()
+    }
+
+    /// Snapshot terbaru semua symbol
+    pub fn all(&self) -> Vec<Arc<InvSnapshot>> {
+        self.inner.read().unwrap().values().cloned().collect()
+    }
+}
+
+/// Serializable untuk checkpoint `rebuild-state` (state + fill kumulatif per cl_id)
+#[derive(serde::Serialize, serde::Deserialize)]
 pub struct PositionsTask {
     symbol: String,
     state: SymbolState,
+    // ExecReport bersifat kumulatif -> fill inkremental
+    deltas: FillDeltas,
 }
 
 impl PositionsTask {
-    pub fn new(symbol: String) -> Self { Self { symbol, state: SymbolState::default() } }
-
-    fn on_fill(&mut self, er: &ExecReport, side: Side) {
-        // venue diambil dari suffix cl_id: ...-A / ...-B
-        let venue = er.cl_id.split('-').last().unwrap_or("?").to_string();
-        let entry = self.state.by_venue.entry(venue.clone()).or_insert(VenuePosition::default());
-        let signed_qty = side.sign() * er.filled_qty;
+    pub fn new(symbol: String) -> Self { Self { symbol, state: SymbolState::default(), deltas: FillDeltas::default() } }
 
+    /// Terapkan fill bertanda (signed_qty) pada satu posisi
+    fn apply_fill(entry: &mut VenuePosition, signed_qty: i64, px: i64) {
         let prev_qty = entry.qty;
         let new_qty = prev_qty + signed_qty;
         if prev_qty == 0 || (prev_qty.signum() == signed_qty.signum()) {
             // arah sama -> update avg cost
             entry.avg_cost_px = if entry.qty == 0 {
-                er.avg_px
+                px
             } else {
-                ((entry.avg_cost_px * entry.qty) + (er.avg_px * signed_qty.abs())) / (entry.qty + signed_qty.abs())
+                ((entry.avg_cost_px * prev_qty.abs()) + (px * signed_qty.abs())) / (prev_qty.abs() + signed_qty.abs())
             };
             entry.qty = new_qty;
         } else {
             // arah berlawanan -> realize PnL
             let qty_closed = signed_qty.abs().min(prev_qty.abs());
-            let pnl = (er.avg_px - entry.avg_cost_px) as i64 * (if prev_qty > 0 { qty_closed } else { -qty_closed });
+            let pnl = (px - entry.avg_cost_px) * (if prev_qty > 0 { qty_closed } else { -qty_closed });
             entry.realized_pnl += pnl;
             entry.qty = new_qty;
             if entry.qty == 0 { entry.avg_cost_px = 0; }
         }
+    }
+
+    /// Reducer deterministik: ExecReport -> state (tanpa metrics / efek samping).
+    /// Dipakai task live dan `rebuild-state` (replay rekaman); Some(qty, px) jika ada fill baru.
+    pub fn apply_exec(&mut self, er: &ExecReport) -> Option<(i64, i64)> {
+        let (qty, px) = self.deltas.on_report(er)?;
+        // side dari gateway; fallback infer dari harga relatif mid (rekaman lama)
+        let side = er.side.unwrap_or(if self.state.last_mid <= px { Side::Buy } else { Side::Sell });
+        let venue = if er.venue.is_empty() { "?".to_string() } else { er.venue.clone() };
+        let signed_qty = side.sign() * qty;
+        Self::apply_fill(self.state.by_venue.entry(venue).or_default(), signed_qty, px);
+        if !er.strategy.is_empty() {
+            Self::apply_fill(self.state.by_strategy.entry(er.strategy.clone()).or_default(), signed_qty, px);
+        }
+        Self::apply_fill(self.state.by_book.entry(books::name(&er.book).to_string()).or_default(), signed_qty, px);
 
         // agregat
         self.state.total_qty = self.state.by_venue.values().map(|v| v.qty).sum();
         self.state.realized_pnl = self.state.by_venue.values().map(|v| v.realized_pnl).sum();
+        Some((qty, px))
+    }
 
-        // metrics
+    /// Reducer deterministik: mid terbaru -> unrealized PnL
+    pub fn apply_mid(&mut self, mid: i64) {
+        self.state.last_mid = mid;
+        self.state.unrealized_pnl = self
+            .state
+            .by_venue
+            .values()
+            .filter(|p| p.qty != 0 && p.avg_cost_px != 0)
+            .map(|p| (mid - p.avg_cost_px) * p.qty)
+            .sum();
+    }
+
+    pub fn state(&self) -> &SymbolState {
+        &self.state
+    }
+
+    /// PnL (tick × lot) -> quote asset
+    fn pnl_quote(&self, pnl: i64) -> f64 {
+        pnl as f64 / 100.0 * crate::sizing::lot_size(&self.symbol)
+    }
+
+    /// PnL symbol ke agregat portofolio USD (fx)
+    fn publish_usd(&self) {
+        crate::fx::set_pnl(&self.symbol, self.pnl_quote(self.state.realized_pnl), self.pnl_quote(self.state.unrealized_pnl));
+    }
+
+    fn publish_metrics(&self) {
         INV_TOTAL_QTY.set(self.state.total_qty);
+        PNL_REALIZED.set(self.state.realized_pnl);
+        self.publish_usd();
+        // symbol di luar METRICS_MAX_SYMBOLS: gauge per-symbol tidak diekspor
+        if !crate::metrics::symbol_admitted(&self.symbol) {
+            return;
+        }
         for (v, pos) in self.state.by_venue.iter() {
             INV_QTY.with_label_values(&[&self.symbol, v]).set(pos.qty);
         }
-        PNL_REALIZED.set(self.state.realized_pnl);
+        for (s, pos) in self.state.by_strategy.iter().filter(|(s, _)| crate::metrics::strategy_admitted(s)) {
+            PNL_REALIZED_BY_STRATEGY.with_label_values(&[&self.symbol, s]).set(pos.realized_pnl);
+        }
+        for (b, pos) in self.state.by_book.iter() {
+            PNL_REALIZED_BY_BOOK.with_label_values(&[&self.symbol, b]).set(pos.realized_pnl);
+            INV_QTY_BY_BOOK.with_label_values(&[&self.symbol, b]).set(pos.qty);
+        }
     }
 
     fn mark_to_market(&mut self, mid: i64) {
-        self.state.last_mid = mid;
-        let mut u = 0_i64;
-        for pos in self.state.by_venue.values() {
-            if pos.qty != 0 && pos.avg_cost_px != 0 {
-                u += (mid - pos.avg_cost_px) * pos.qty;
-            }
-        }
-        self.state.unrealized_pnl = u;
-        PNL_UNREALIZED.set(u);
+        self.apply_mid(mid);
+        PNL_UNREALIZED.set(self.state.unrealized_pnl);
+        self.publish_usd();
+        self.publish_margin();
+    }
+
+    /// Margin & harga likuidasi posisi agregat (no-op di luar TRADING_MODE=margin)
+    fn publish_margin(&self) {
+        let qty = self.state.total_qty;
+        let entry = if qty != 0 {
+            self.state.by_venue.values().map(|v| v.avg_cost_px * v.qty).sum::<i64>() / qty
+        } else {
+            0
+        };
+        let mark = if self.state.last_mid > 0 { self.state.last_mid } else { entry };
+        margin::publish(&self.symbol, qty, entry, mark);
     }
 }
 
 pub async fn run(
     symbol: String,
-    mut md_rx: broadcast::Receiver<MdTick>,
-    mut exec_rx: tokio::sync::mpsc::Receiver<ExecReport>,
+    mut md_rx: PosMdRx,
+    mut exec_rx: tokio::sync::mpsc::Receiver<Arc<ExecReport>>,
     snap_tx: watch::Sender<InvSnapshot>,
+    snaps: Snapshots,
+    feed: PositionFeed,
 ) {
     let mut task = PositionsTask::new(symbol.clone());
+    let mut gaps = GapDetector::new("positions");
+    if let Some(state) = snaps.take_position(&symbol) {
+        task.state = state;
+        task.publish_metrics();
+        feed.publish(InvSnapshot { ts_ns: crate::clock::now_ns(), symbol: symbol.clone(), state: task.state.clone() });
+    }
     loop {
         tokio::select! {
-            Ok(md) = md_rx.recv() => {
+            Some(md) = md_rx.recv() => {
+                // bus MD bersama berisi semua symbol
+                if md.symbol != symbol {
+                    continue;
+                }
+                if md_rx.is_shared() {
+                    gaps.observe(&md);
+                }
                 let mid = (md.best_bid + md.best_ask)/2;
                 task.mark_to_market(mid);
                 let _ = snap_tx.send(InvSnapshot { ts_ns: md.ts_ns, symbol: symbol.clone(), state: task.state.clone() });
             }
             Some(er) = exec_rx.recv() => {
-                // Sementara infer side dari harga relatif mid
-                let side = if task.state.last_mid <= er.avg_px { Side::Buy } else { Side::Sell };
-                task.on_fill(&er, side);
-                let _ = snap_tx.send(InvSnapshot { ts_ns: er.ts_ns, symbol: symbol.clone(), state: task.state.clone() });
+                if task.apply_exec(&er).is_none() {
+                    continue;
+                }
+                task.publish_metrics();
+                task.publish_margin();
+                snaps.put_position(&symbol, &task.state);
+                let snap = InvSnapshot { ts_ns: er.ts_ns, symbol: symbol.clone(), state: task.state.clone() };
+                feed.publish(snap.clone());
+                let _ = snap_tx.send(snap);
             }
         }
     }
@@ -1,18 +1,110 @@
 // ===============================
 // src/posttrade.rs
 // ===============================
-use tokio::sync::mpsc;
+//
+// Post-trade: log ExecReport + analitik markout per fill.
+//
+// Markout (ticks per unit, positif = fill menguntungkan):
+//   Buy : mid(t_fill + h) - px_fill
+//   Sell: px_fill - mid(t_fill + h)
+// untuk horizon h = 1s / 5s / 30s, memakai mid terakhir dari stream MD.
+//
+// Metrics:
+//   markout_ticks{horizon,strategy}            histogram per fill
+//   markout_avg_ticks_by_venue{horizon,venue}  rata-rata tertimbang qty sejak start
+//
+use std::sync::Arc;
+use ahash::AHashMap as HashMap;
+use std::collections::VecDeque;
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::{interval, Duration, Instant, MissedTickBehavior};
 use tracing::{info, warn};
-use crate::domain::{ExecReport, ExecStatus};
+use crate::domain::{ExecReport, ExecStatus, FillDeltas, MdTick};
+use crate::md_seq::GapDetector;
+use crate::metrics::{MARKOUT_AVG_BY_VENUE, MARKOUT_TICKS};
 
+const HORIZONS: [(&str, Duration); 3] = [
+    ("1s", Duration::from_secs(1)),
+    ("5s", Duration::from_secs(5)),
+    ("30s", Duration::from_secs(30)),
+];
 
-pub async fn run(mut exec_rx: mpsc::Receiver<ExecReport>) {
-while let Some(er) = exec_rx.recv().await {
-match &er.status {
-ExecStatus::Ack => info!(cl_id=?er.cl_id, symbol=?er.symbol, "ACK"),
-ExecStatus::Filled => info!(cl_id=?er.cl_id, qty=?er.filled_qty, px=?er.avg_px, "FILLED"),
-ExecStatus::PartialFill => info!(cl_id=?er.cl_id, qty=?er.filled_qty, px=?er.avg_px, "PARTIAL"),
-ExecStatus::Rejected(r) => warn!(cl_id=?er.cl_id, reason=%r, "REJECT"),
+struct PendingMarkout {
+    due: Instant,
+    symbol: String,
+    strategy: String,
+    venue: String,
+    sign: i64,
+    px: i64,
+    qty: i64,
 }
+
+fn log_report(er: &ExecReport) {
+    match &er.status {
+        ExecStatus::Ack => info!(cl_id=?er.cl_id, symbol=?er.symbol, "ACK"),
+        ExecStatus::Filled => info!(cl_id=?er.cl_id, qty=?er.filled_qty, px=?er.avg_px, "FILLED"),
+        ExecStatus::PartialFill => info!(cl_id=?er.cl_id, qty=?er.filled_qty, px=?er.avg_px, "PARTIAL"),
+        ExecStatus::Rejected(r) => warn!(cl_id=?er.cl_id, reason=%r, "REJECT"),
+        ExecStatus::Expired => info!(cl_id=?er.cl_id, qty=?er.filled_qty, "EXPIRED"),
+    }
+}
+
+pub async fn run(mut exec_rx: mpsc::Receiver<Arc<ExecReport>>, mut md_rx: broadcast::Receiver<MdTick>) {
+    let mut mids: HashMap<String, i64> = HashMap::new();
+    let mut deltas = FillDeltas::default();
+    let mut gaps = GapDetector::new("posttrade");
+    // satu antrian per horizon: due time naik sesuai urutan fill
+    let mut pending: Vec<VecDeque<PendingMarkout>> = HORIZONS.iter().map(|_| VecDeque::new()).collect();
+    // (horizon, venue) -> (sum markout*qty, sum qty)
+    let mut by_venue: HashMap<(usize, String), (i64, i64)> = HashMap::new();
+
+    let mut tick = interval(Duration::from_millis(100));
+    tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            Some(er) = exec_rx.recv() => {
+                log_report(&er);
+                let (Some((qty, px)), Some(side)) = (deltas.on_report(&er), er.side) else { continue };
+                let now = Instant::now();
+                for (i, (_, h)) in HORIZONS.iter().enumerate() {
+                    pending[i].push_back(PendingMarkout {
+                        due: now + *h,
+                        symbol: er.symbol.clone(),
+                        strategy: if er.strategy.is_empty() { "unattributed".to_string() } else { er.strategy.clone() },
+                        venue: er.venue.clone(),
+                        sign: side.sign(),
+                        px,
+                        qty,
+                    });
+                }
+            }
+            md = md_rx.recv() => match md {
+                Ok(md) => {
+                    gaps.observe(&md);
+                    mids.insert(md.symbol.clone(), (md.best_bid + md.best_ask) / 2);
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            _ = tick.tick() => {
+                let now = Instant::now();
+                for (i, (label, _)) in HORIZONS.iter().enumerate() {
+                    while pending[i].front().is_some_and(|p| p.due <= now) {
+                        let p = pending[i].pop_front().unwrap();
+                        let Some(mid) = mids.get(&p.symbol) else { continue };
+                        let markout = p.sign * (mid - p.px);
+                        MARKOUT_TICKS.with_label_values(&[label, crate::metrics::strategy_label(&p.strategy)]).observe(markout as f64);
+
+                        let agg = by_venue.entry((i, p.venue.clone())).or_default();
+                        agg.0 += markout * p.qty;
+                        agg.1 += p.qty;
+                        if agg.1 > 0 {
+                            MARKOUT_AVG_BY_VENUE.with_label_values(&[label, &p.venue]).set(agg.0 as f64 / agg.1 as f64);
+                        }
+                    }
+                }
+            }
+        }
+    }
 }
-}
\ No newline at end of file
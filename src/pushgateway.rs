@@ -0,0 +1,93 @@
+// ===============================
+// src/pushgateway.rs
+// ===============================
+//
+// Push metrics ke Prometheus Pushgateway untuk run pendek / batch yang tidak sempat di-scrape.
+// Isi registry yang sama dengan /metrics dikirim (text format) ke:
+//   PUT {PUSHGATEWAY_URL}/metrics/job/{job}/instance/{instance}
+// - periodik tiap PUSHGATEWAY_INTERVAL_SECS (0 = hanya push final)
+// - push final saat shutdown (Ctrl-C / SIGTERM), setelah cancel-all
+// PUT mengganti seluruh grup (job, instance), jadi metrik run sebelumnya dengan instance sama tertimpa.
+//
+// ENV:
+//   PUSHGATEWAY_URL=http://pushgateway:9091   (kosong = nonaktif)
+//   PUSHGATEWAY_JOB=dma_bot_rust
+//   PUSHGATEWAY_INSTANCE=<HOSTNAME>
+//   PUSHGATEWAY_INTERVAL_SECS=15
+//   PUSHGATEWAY_USER= / PUSHGATEWAY_PASS=     (basic auth, opsional)
+//
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::metrics::{encode_metrics, PUSHGATEWAY_FAILURES};
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PushCfg {
+    #[serde(serialize_with = "crate::effective_config::url")]
+    pub url: String,
+    pub job: String,
+    pub instance: String,
+    pub interval_secs: u64,
+    pub user: String,
+    #[serde(serialize_with = "crate::effective_config::secret")]
+    pub pass: String,
+}
+
+impl PushCfg {
+    pub fn from_env() -> Self {
+        let var = |k: &str| std::env::var(k).unwrap_or_default();
+        let or = |v: String, d: String| if v.is_empty() { d } else { v };
+        Self {
+            url: var("PUSHGATEWAY_URL").trim_end_matches('/').to_string(),
+            job: or(var("PUSHGATEWAY_JOB"), "dma_bot_rust".to_string()),
+            instance: or(var("PUSHGATEWAY_INSTANCE"), or(var("HOSTNAME"), "local".to_string())),
+            interval_secs: var("PUSHGATEWAY_INTERVAL_SECS").parse().unwrap_or(15),
+            user: var("PUSHGATEWAY_USER"),
+            pass: var("PUSHGATEWAY_PASS"),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        !self.url.is_empty()
+    }
+
+    fn endpoint(&self) -> String {
+        let enc = |s: &str| urlencoding::encode(s).into_owned();
+        format!("{}/metrics/job/{}/instance/{}", self.url, enc(&self.job), enc(&self.instance))
+    }
+}
+
+/// Satu kali push (dipakai periodik & final)
+pub async fn push(cfg: &PushCfg) {
+    let client = reqwest::Client::new();
+    let mut req = client.put(cfg.endpoint()).timeout(Duration::from_secs(10)).body(encode_metrics());
+    req = req.header("Content-Type", "text/plain; version=0.0.4");
+    if !cfg.user.is_empty() {
+        req = req.basic_auth(&cfg.user, Some(&cfg.pass));
+    }
+    match req.send().await {
+        Ok(r) if r.status().is_success() => {}
+        Ok(r) => {
+            PUSHGATEWAY_FAILURES.inc();
+            warn!(status = %r.status(), url = %cfg.url, "pushgateway push rejected");
+        }
+        Err(e) => {
+            PUSHGATEWAY_FAILURES.inc();
+            warn!(?e, url = %cfg.url, "pushgateway push failed");
+        }
+    }
+}
+
+/// Task push periodik
+pub async fn run(cfg: PushCfg) {
+    info!(url = %cfg.url, job = %cfg.job, instance = %cfg.instance, interval_secs = cfg.interval_secs, "pushgateway enabled");
+    if cfg.interval_secs == 0 {
+        return std::future::pending().await;
+    }
+    let mut tick = tokio::time::interval(Duration::from_secs(cfg.interval_secs));
+    tick.tick().await;
+    loop {
+        tick.tick().await;
+        push(&cfg).await;
+    }
+}
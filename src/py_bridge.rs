@@ -0,0 +1,193 @@
+// ===============================
+// src/py_bridge.rs
+// ===============================
+//
+// Bridge strategi Python (STRATEGIES=python): engine menjalankan script sebagai subprocess dan
+// bicara lewat pipe dengan frame JSON satu baris (newline-delimited).
+//
+//   engine -> stdin  : {"seq":1,"tick":{"ts_ns":..,"symbol":"BTCUSDT","best_bid":10010,"best_ask":10012,..},
+//                       "book":{"microprice":10011.2,"imbalance":0.3,"imbalance_k":0.1,"levels":5}}
+//   script -> stdout : {"seq":1,"signal":{"side":"buy","px":10012,"qty":10}}   atau  {"seq":1,"signal":null}
+//
+// Harga dalam tick (×100), qty dalam lot (sama dengan Signal). stderr script diteruskan ke log.
+//
+// Script lambat tidak boleh menahan engine:
+// - hanya satu tick in-flight; tick yang datang selama menunggu jawaban di-drop (py_strategy_dropped_total)
+// - jawaban harus datang dalam PY_STRATEGY_TIMEOUT_MS; lewat dari itu tick dianggap tanpa signal
+//   (py_strategy_timeouts_total) dan jawaban terlambat dibuang (seq tidak cocok)
+// - script mati / stdout tertutup -> task keluar, supervisor me-restart script dengan backoff
+//
+// ENV:
+//   PY_STRATEGY_CMD="python3 -u python/example_strategy.py"
+//   PY_STRATEGY_TIMEOUT_MS=50
+//
+use serde::{Deserialize, Serialize};
+use std::process::Stdio;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::{sleep_until, Duration, Instant};
+use tracing::{error, info, warn};
+
+use crate::book_analytics::TickRx;
+use crate::conflate::Conflated;
+use crate::domain::{BookStats, MdTick, Side, Signal};
+use crate::md_seq::GapDetector;
+use crate::metrics::{PY_STRATEGY_DROPPED, PY_STRATEGY_TIMEOUTS, SIGNALS};
+use crate::strategy::WorkerCtx;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PyBridgeCfg {
+    pub cmd: String,
+    pub timeout: Duration,
+}
+
+impl PyBridgeCfg {
+    pub fn from_env() -> Self {
+        let timeout_ms = std::env::var("PY_STRATEGY_TIMEOUT_MS").ok().and_then(|s| s.parse().ok()).unwrap_or(50);
+        Self {
+            cmd: std::env::var("PY_STRATEGY_CMD").unwrap_or_else(|_| "python3 -u python/example_strategy.py".to_string()),
+            timeout: Duration::from_millis(timeout_ms),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Request<'a> {
+    seq: u64,
+    tick: &'a MdTick,
+    book: &'a BookStats,
+}
+
+#[derive(Deserialize)]
+struct Reply {
+    seq: u64,
+    #[serde(default)]
+    signal: Option<ReplySignal>,
+}
+
+#[derive(Deserialize)]
+struct ReplySignal {
+    side: String,
+    px: i64,
+    #[serde(default = "default_qty")]
+    qty: i64,
+}
+
+fn default_qty() -> i64 {
+    10
+}
+
+impl ReplySignal {
+    fn into_signal(self, md: &MdTick, ctx: &WorkerCtx) -> Option<Signal> {
+        let side = match self.side.to_ascii_lowercase().as_str() {
+            "buy" | "b" => Side::Buy,
+            "sell" | "s" => Side::Sell,
+            _ => return None,
+        };
+        Some(Signal {
+            ts_ns: md.ts_ns,
+            symbol: md.symbol.clone(),
+            side,
+            px: self.px,
+            qty: self.qty,
+            strategy: ctx.strategy.to_string(),
+            tags: ctx.tags.clone(),
+            book: ctx.book.clone(),
+            quote_notional: ctx.quote_notional,
+        })
+    }
+}
+
+/// Worker strategi Python: satu subprocess per worker
+pub async fn run(md_rx: TickRx, sig_tx: mpsc::Sender<Signal>, ctx: WorkerCtx, cfg: PyBridgeCfg) {
+    let key = ctx.key();
+    let mut parts = cfg.cmd.split_whitespace();
+    let Some(program) = parts.next() else {
+        error!("PY_STRATEGY_CMD is empty");
+        return;
+    };
+    let child = Command::new(program)
+        .args(parts)
+        .env("PY_STRATEGY_WORKER", ctx.worker.to_string())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn();
+    let mut child = match child {
+        Ok(c) => c,
+        Err(e) => {
+            error!(cmd = %cfg.cmd, ?e, "python strategy spawn failed");
+            return;
+        }
+    };
+    info!(worker = %key, cmd = %cfg.cmd, "python strategy started");
+    let (Some(mut stdin), Some(stdout), Some(stderr)) = (child.stdin.take(), child.stdout.take(), child.stderr.take()) else {
+        return;
+    };
+    let worker = key.clone();
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(stderr).lines();
+        while let Ok(Some(l)) = lines.next_line().await {
+            info!(worker = %worker, "python: {l}");
+        }
+    });
+    let mut lines = BufReader::new(stdout).lines();
+    let gaps = GapDetector::new(format!("strategy:{key}"));
+    let mut md_rx = Conflated::new(md_rx, gaps, ctx.strategy, ctx.max_tps);
+
+    let mut seq = 0u64;
+    // tick yang sedang menunggu jawaban: (seq, tick, deadline)
+    let mut pending: Option<(u64, MdTick, Instant)> = None;
+    let far = Instant::now() + Duration::from_secs(86_400 * 365);
+    loop {
+        let deadline = pending.as_ref().map_or(far, |p| p.2);
+        tokio::select! {
+            md = md_rx.recv() => match md {
+                Ok(t) => {
+                    let md = t.tick;
+                    if pending.is_some() {
+                        PY_STRATEGY_DROPPED.inc();
+                        continue;
+                    }
+                    seq += 1;
+                    let mut frame = match serde_json::to_string(&Request { seq, tick: &md, book: &t.stats }) {
+                        Ok(f) => f,
+                        Err(e) => { error!(?e, "python frame encode failed"); continue; }
+                    };
+                    frame.push('\n');
+                    if let Err(e) = stdin.write_all(frame.as_bytes()).await {
+                        error!(worker = %key, ?e, "python strategy stdin closed");
+                        break;
+                    }
+                    pending = Some((seq, md, Instant::now() + cfg.timeout));
+                },
+                Err(broadcast::error::RecvError::Lagged(n)) => warn!(worker = %key, n, "python strategy lagged"),
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            line = lines.next_line() => {
+                let line = match line {
+                    Ok(Some(l)) => l,
+                    _ => { error!(worker = %key, "python strategy stdout closed"); break; }
+                };
+                let reply: Reply = match serde_json::from_str(&line) {
+                    Ok(r) => r,
+                    Err(e) => { warn!(worker = %key, ?e, %line, "python reply invalid"); continue; }
+                };
+                // jawaban terlambat untuk tick yang sudah timeout -> buang
+                let Some((_, md, _)) = pending.take_if(|p| p.0 == reply.seq) else { continue };
+                let sig = reply.signal.and_then(|s| s.into_signal(&md, &ctx)).filter(|_| ctx.symbols.tradable(&md.symbol));
+                if let Some(sig) = sig {
+                    if let Err(e) = sig_tx.send(sig).await { error!(?e, "signal send failed"); }
+                    else { SIGNALS.inc(); }
+                }
+            },
+            _ = sleep_until(deadline), if pending.is_some() => {
+                PY_STRATEGY_TIMEOUTS.inc();
+                pending = None;
+            },
+        }
+    }
+    let _ = child.kill().await;
+}
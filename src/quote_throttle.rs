@@ -0,0 +1,175 @@
+// ===============================
+// src/quote_throttle.rs
+// ===============================
+//
+// Limiter churn order per (venue, symbol) di antara router dan gateway, supaya laju
+// order / cancel / re-quote tidak melewati order-rate limit exchange (hindari rate ban).
+//
+// - Token bucket per (venue, symbol): `rate` order/detik, burst `burst`.
+// - Order di atas laju -> antre per symbol. Quote baru dengan kunci sama
+//   (symbol, side, strategy, book) menggantikan quote yang masih antre (coalescing);
+//   quote lama dilaporkan Rejected("COALESCED") agar risk melepas eksposur pending-nya.
+// - Cancel GTT gateway Binance ikut memakai budget yang sama (`note`), tapi tidak pernah diblok.
+//
+// ENV:
+//   QUOTE_RATE_PER_SEC=10            default per (venue, symbol); 0 = nonaktif
+//   QUOTE_RATE_VENUES=binance:5,A:50 override per venue
+//   QUOTE_BURST=5
+//
+use ahash::AHashMap as HashMap;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio::time::{interval, Duration, Instant, MissedTickBehavior};
+use tracing::debug;
+
+use crate::domain::{ExecReport, ExecStatus, Order, VenueOrder};
+use crate::metrics::{EXECS, QUOTES_COALESCED, QUOTES_QUEUED};
+
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct ThrottleCfg {
+    pub default_rate: f64,           // order/detik per (venue, symbol); 0 = nonaktif
+    pub venue_rates: HashMap<String, f64>,
+    pub burst: f64,
+}
+
+impl ThrottleCfg {
+    pub fn from_env() -> Self {
+        let num = |k: &str, d: f64| std::env::var(k).ok().and_then(|s| s.parse().ok()).unwrap_or(d);
+        Self {
+            default_rate: num("QUOTE_RATE_PER_SEC", 0.0),
+            venue_rates: crate::sizing::parse_map(&std::env::var("QUOTE_RATE_VENUES").unwrap_or_default()),
+            burst: num("QUOTE_BURST", 5.0).max(1.0),
+        }
+    }
+
+    /// Laju untuk venue (None = tanpa throttle)
+    pub fn rate(&self, venue: &str) -> Option<f64> {
+        let r = self.venue_rates.get(venue).copied().unwrap_or(self.default_rate);
+        (r > 0.0).then_some(r)
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    last: Instant,
+}
+
+/// Token bucket bersama per (venue, symbol)
+#[derive(Clone, Default)]
+pub struct ChurnLimiter {
+    cfg: ThrottleCfg,
+    buckets: Arc<Mutex<HashMap<(String, String), Bucket>>>,
+}
+
+impl ChurnLimiter {
+    pub fn new(cfg: ThrottleCfg) -> Self {
+        Self { cfg, buckets: Arc::default() }
+    }
+
+    pub fn enabled(&self, venue: &str) -> bool {
+        self.cfg.rate(venue).is_some()
+    }
+
+    fn with_bucket<R>(&self, venue: &str, symbol: &str, f: impl FnOnce(&mut Bucket) -> R) -> Option<R> {
+        let rate = self.cfg.rate(venue)?;
+        let burst = self.cfg.burst;
+        let mut g = self.buckets.lock().unwrap();
+        let b = g
+            .entry((venue.to_string(), symbol.to_string()))
+            .or_insert_with(|| Bucket { tokens: burst, last: Instant::now() });
+        let now = Instant::now();
+        b.tokens = (b.tokens + now.duration_since(b.last).as_secs_f64() * rate).min(burst);
+        b.last = now;
+        Some(f(b))
+    }
+
+    /// Ambil satu token jika ada (true = boleh kirim sekarang)
+    pub fn try_take(&self, venue: &str, symbol: &str) -> bool {
+        self.with_bucket(venue, symbol, |b| {
+            if b.tokens >= 1.0 {
+                b.tokens -= 1.0;
+                true
+            } else {
+                false
+            }
+        })
+        .unwrap_or(true)
+    }
+
+    /// Catat request yang tidak boleh ditahan (cancel): token dipakai bila ada
+    #[cfg(feature = "binance")]
+    pub fn note(&self, venue: &str, symbol: &str) {
+        self.with_bucket(venue, symbol, |b| b.tokens = (b.tokens - 1.0).max(0.0));
+    }
+}
+
+/// Kunci coalescing: quote baru menggantikan quote antre dengan kunci sama
+fn same_quote(a: &Order, b: &Order) -> bool {
+    a.symbol == b.symbol
+        && a.side.sign() == b.side.sign()
+        && a.strategy == b.strategy
+        && a.book == b.book
+}
+
+/// Task throttle satu venue: router -> (throttle) -> gateway
+pub async fn run(
+    venue: String,
+    mut rx: mpsc::Receiver<VenueOrder>,
+    gw_tx: mpsc::Sender<VenueOrder>,
+    exec_tx: mpsc::Sender<ExecReport>,
+    limiter: ChurnLimiter,
+) {
+    let mut queued: HashMap<String, VecDeque<VenueOrder>> = HashMap::new();
+    let mut drain = interval(Duration::from_millis(20));
+    drain.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            maybe = rx.recv() => {
+                let Some(vord) = maybe else { break };
+                let q = queued.entry(vord.order.symbol.clone()).or_default();
+                if q.is_empty() && limiter.try_take(&venue, &vord.order.symbol) {
+                    if gw_tx.send(vord).await.is_err() { break; }
+                    continue;
+                }
+                QUOTES_QUEUED.with_label_values(&[&venue]).inc();
+                if let Some(old) = q.iter_mut().find(|v| same_quote(&v.order, &vord.order)) {
+                    let old = std::mem::replace(old, vord);
+                    debug!(%venue, cl_id = %old.order.cl_id, "quote coalesced");
+                    QUOTES_COALESCED.with_label_values(&[&venue]).inc();
+                    let _ = exec_tx.send(coalesced(&old.order, &venue)).await;
+                    EXECS.with_label_values(&["rejected", &venue]).inc();
+                } else {
+                    q.push_back(vord);
+                }
+            }
+            _ = drain.tick(), if queued.values().any(|q| !q.is_empty()) => {
+                for (symbol, q) in queued.iter_mut() {
+                    while !q.is_empty() && limiter.try_take(&venue, symbol) {
+                        let vord = q.pop_front().unwrap();
+                        if gw_tx.send(vord).await.is_err() { return; }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn coalesced(o: &Order, venue: &str) -> ExecReport {
+    ExecReport {
+        cl_id: o.cl_id.clone(),
+        symbol: o.symbol.clone(),
+        status: ExecStatus::Rejected("COALESCED".to_string()),
+        filled_qty: 0,
+        avg_px: 0,
+        ts_ns: crate::clock::now_ns(),
+        venue: venue.to_string(),
+        side: Some(o.side),
+        order_qty: o.qty,
+        strategy: o.strategy.clone(),
+        tags: o.tags.clone(),
+        book: o.book.clone(),
+        exch_ts_ns: 0,
+    }
+}
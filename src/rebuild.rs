@@ -0,0 +1,347 @@
+// ===============================
+// src/rebuild.rs
+// ===============================
+//
+// Event sourcing: bangun ulang state posisi & order terbuka (OMS) murni dari rekaman event
+// (RECORD_FILE), untuk diverifikasi terhadap state live (/admin, snapshot, exchange).
+//
+// Reducer yang dipakai SAMA dengan task live (deterministik, tanpa jam / IO):
+// - exec -> `PositionsTask::apply_exec` (posisi per venue/strategi/book, realized PnL) +
+//           `WorkingOrders::on_report` (child terbuka sampai report final)
+// - md   -> `PositionsTask::apply_mid` (mark & unrealized PnL)
+// ExecReport di rekaman sudah lolos guard duplikat / out-of-order (exec_guard.rs), jadi urutan
+// replay = urutan yang dilihat task live.
+//
+// Catatan: proses yang di-start dengan --warm-start memulai dari snapshot, bukan dari nol;
+// hasil replay hanya sama dengan live jika rekaman mencakup seluruh sesi sejak state kosong.
+//
+// Rekaman panjang (berminggu-minggu):
+// - checkpoint (--checkpoint): tiap --checkpoint-every baris, posisi baca (jumlah baris mentah lintas segmen)
+//   + state reducer ditulis atomik ke file; run berikutnya dengan argumen sama melanjutkan dari situ,
+//   file checkpoint dihapus setelah output selesai ditulis
+// - shard (--shard i/n): worker paralel masing-masing memegang subset symbol (hash nama symbol);
+//   posisi & order terbuka per symbol independen, jadi hasilnya digabung dengan `merge-state`.
+//   Setiap worker tetap membaca (dekompresi + parse) seluruh rekaman; yang dibagi adalah kerja reducer.
+//
+// Subcommand: dma_bot rebuild-state --from events.jsonl [--until <ts_ns>] [--out state.json]
+//                                   [--checkpoint ck.json] [--checkpoint-every N] [--shard 0/4]
+//             dma_bot merge-state part0.json part1.json ... [--out state.json]
+//
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::domain::{Event, SymbolState};
+use crate::oms::{OpenChild, WorkingOrders};
+use crate::positions::PositionsTask;
+use crate::recorder::reader::{Filter, Reader};
+
+/// Bagian `index` dari `count` worker (`--shard 1/4`); symbol dibagi dengan hash FNV-1a (stabil antar proses)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Shard {
+    pub index: u32,
+    pub count: u32,
+}
+
+impl Shard {
+    pub fn owns(&self, symbol: &str) -> bool {
+        let h = symbol.bytes().fold(0xcbf2_9ce4_8422_2325u64, |h, b| (h ^ b as u64).wrapping_mul(0x0100_0000_01b3));
+        h % self.count as u64 == self.index as u64
+    }
+}
+
+impl std::str::FromStr for Shard {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let (i, n) = s.split_once('/').ok_or("expected i/n, e.g. 0/4")?;
+        let (index, count): (u32, u32) = (i.trim().parse().map_err(|_| "bad shard index")?, n.trim().parse().map_err(|_| "bad shard count")?);
+        if index >= count {
+            return Err(format!("shard index {index} must be < count {count}"));
+        }
+        Ok(Self { index, count })
+    }
+}
+
+/// State hasil replay
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RebuiltState {
+    pub events: u64,
+    pub last_ts_ns: i128,
+    pub positions: BTreeMap<String, SymbolState>,
+    pub open_orders: BTreeMap<String, OpenChild>,
+}
+
+/// Akumulator replay: satu reducer posisi per symbol + order terbuka
+#[derive(Default, Serialize, Deserialize)]
+pub struct Rebuild {
+    positions: BTreeMap<String, PositionsTask>,
+    #[serde(with = "open_orders")]
+    open: WorkingOrders,
+    events: u64,
+    last_ts_ns: i128,
+}
+
+impl Rebuild {
+    pub fn apply(&mut self, ev: &Event) {
+        match ev {
+            Event::Md(md) => {
+                // mark hanya untuk symbol yang sudah punya fill (state lain tetap kosong)
+                if let Some(task) = self.positions.get_mut(&md.symbol) {
+                    task.apply_mid((md.best_bid + md.best_ask) / 2);
+                }
+            }
+            Event::Exec(er) => {
+                let task = self.positions.entry(er.symbol.clone()).or_insert_with(|| PositionsTask::new(er.symbol.clone()));
+                task.apply_exec(er);
+                self.open.on_report(er);
+            }
+            _ => return,
+        }
+        self.events += 1;
+        self.last_ts_ns = self.last_ts_ns.max(ev.ts_ns().unwrap_or(0));
+    }
+
+    pub fn finish(self) -> RebuiltState {
+        RebuiltState {
+            events: self.events,
+            last_ts_ns: self.last_ts_ns,
+            positions: self.positions.into_iter().map(|(s, t)| (s, t.state().clone())).collect(),
+            open_orders: self.open.snapshot(),
+        }
+    }
+}
+
+mod open_orders {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(open: &WorkingOrders, s: S) -> Result<S::Ok, S::Error> {
+        open.snapshot().serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<WorkingOrders, D::Error> {
+        let open = WorkingOrders::default();
+        open.restore(BTreeMap::deserialize(d)?);
+        Ok(open)
+    }
+}
+
+/// Isi file checkpoint; argumen run harus sama persis untuk bisa resume
+#[derive(Serialize, Deserialize)]
+struct Checkpoint<R> {
+    inputs: Vec<String>,
+    until: Option<i128>,
+    shard: Option<Shard>,
+    /// baris mentah yang sudah diproses
+    lines: u64,
+    skipped: u64,
+    rebuild: R,
+}
+
+/// Opsi run panjang (checkpoint / shard)
+#[derive(Debug, Clone, Default)]
+pub struct RunOpts {
+    pub shard: Option<Shard>,
+    pub checkpoint: Option<String>,
+    pub checkpoint_every: u64,
+}
+
+fn write_checkpoint(path: &str, ck: &Checkpoint<&Rebuild>) -> std::io::Result<()> {
+    let tmp = format!("{path}.tmp");
+    std::fs::write(&tmp, serde_json::to_vec(ck)?)?;
+    std::fs::rename(&tmp, path)
+}
+
+fn fail(msg: String) -> ! {
+    eprintln!("{msg}");
+    std::process::exit(1);
+}
+
+fn write_out(cmd: &str, state: &RebuiltState, out: Option<&str>) {
+    let json = serde_json::to_string_pretty(state).unwrap_or_default();
+    let res = match out {
+        Some(p) => std::fs::write(p, json + "\n"),
+        None => {
+            println!("{json}");
+            Ok(())
+        }
+    };
+    if let Err(e) = res {
+        fail(format!("{cmd}: write failed: {e}"));
+    }
+}
+
+/// Subcommand `rebuild-state`
+pub fn run(path: &str, until: Option<i128>, out: Option<&str>, opts: &RunOpts) {
+    let filter = Filter { kinds: vec!["md".to_string(), "exec".to_string()], until_ns: until, ..Default::default() };
+    let mut rd = match Reader::open(path) {
+        Ok(r) => r.with_filter(filter),
+        Err(e) => fail(format!("rebuild-state: open {path} failed: {e}")),
+    };
+    let inputs = vec![path.to_string()];
+    let (mut rebuild, mut skipped) = (Rebuild::default(), 0);
+    if let Some(ck_path) = opts.checkpoint.as_deref().filter(|p| std::path::Path::new(p).exists()) {
+        let ck: Checkpoint<Rebuild> = std::fs::read(ck_path)
+            .map_err(|e| e.to_string())
+            .and_then(|b| serde_json::from_slice(&b).map_err(|e| e.to_string()))
+            .unwrap_or_else(|e| fail(format!("rebuild-state: checkpoint {ck_path}: {e}")));
+        if (&ck.inputs, ck.until, ck.shard) != (&inputs, until, opts.shard) {
+            fail(format!("rebuild-state: checkpoint {ck_path} was written for other arguments (--from / --until / --shard); delete it to start over"));
+        }
+        if !rd.skip_lines(ck.lines) {
+            fail(format!("rebuild-state: recording is shorter than checkpoint {ck_path} ({} lines)", ck.lines));
+        }
+        eprintln!("rebuild-state: resumed from {ck_path} at line {} ({} events)", ck.lines, ck.rebuild.events);
+        (rebuild, skipped) = (ck.rebuild, ck.skipped);
+    }
+    let every = opts.checkpoint_every.max(1);
+    let mut next_ck = rd.position() + every;
+    while let Some(ev) = rd.next() {
+        if opts.shard.is_none_or(|s| ev.symbol().is_some_and(|sym| s.owns(sym))) {
+            rebuild.apply(&ev);
+        }
+        if let Some(ck_path) = opts.checkpoint.as_deref().filter(|_| rd.position() >= next_ck) {
+            let ck = Checkpoint { inputs: inputs.clone(), until, shard: opts.shard, lines: rd.position(), skipped: skipped + rd.skipped(), rebuild: &rebuild };
+            if let Err(e) = write_checkpoint(ck_path, &ck) {
+                eprintln!("rebuild-state: checkpoint write failed: {e}");
+            }
+            next_ck = rd.position() + every;
+        }
+    }
+    skipped += rd.skipped();
+    let state = rebuild.finish();
+    eprintln!("rebuild-state: events={} symbols={} open_orders={} skipped={skipped}", state.events, state.positions.len(), state.open_orders.len());
+    write_out("rebuild-state", &state, out);
+    if let Some(ck_path) = &opts.checkpoint {
+        let _ = std::fs::remove_file(ck_path);
+    }
+}
+
+/// Gabungkan hasil shard; symbol / order yang muncul di dua shard = error (shard tumpang tindih)
+pub fn merge(parts: Vec<RebuiltState>) -> Result<RebuiltState, String> {
+    let mut merged = RebuiltState { events: 0, last_ts_ns: 0, positions: BTreeMap::new(), open_orders: BTreeMap::new() };
+    for part in parts {
+        merged.events += part.events;
+        merged.last_ts_ns = merged.last_ts_ns.max(part.last_ts_ns);
+        for (symbol, pos) in part.positions {
+            if merged.positions.insert(symbol.clone(), pos).is_some() {
+                return Err(format!("symbol {symbol} is in more than one part"));
+            }
+        }
+        for (cl_id, o) in part.open_orders {
+            if merged.open_orders.insert(cl_id.clone(), o).is_some() {
+                return Err(format!("open order {cl_id} is in more than one part"));
+            }
+        }
+    }
+    Ok(merged)
+}
+
+/// Subcommand `merge-state`
+pub fn run_merge(files: &[String], out: Option<&str>) {
+    let parts = files
+        .iter()
+        .map(|f| {
+            std::fs::read(f)
+                .map_err(|e| e.to_string())
+                .and_then(|b| serde_json::from_slice::<RebuiltState>(&b).map_err(|e| e.to_string()))
+                .unwrap_or_else(|e| fail(format!("merge-state: {f}: {e}")))
+        })
+        .collect();
+    let state = merge(parts).unwrap_or_else(|e| fail(format!("merge-state: {e}")));
+    eprintln!("merge-state: parts={} events={} symbols={} open_orders={}", files.len(), state.events, state.positions.len(), state.open_orders.len());
+    write_out("merge-state", &state, out);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ExecReport, ExecStatus, MdTick, Side};
+
+    fn exec(cl_id: &str, side: Side, status: ExecStatus, filled: i64, px: i64) -> Event {
+        exec_on("BTCUSDT", cl_id, side, status, filled, px)
+    }
+
+    fn exec_on(symbol: &str, cl_id: &str, side: Side, status: ExecStatus, filled: i64, px: i64) -> Event {
+        Event::Exec(ExecReport {
+            cl_id: cl_id.into(),
+            symbol: symbol.into(),
+            status,
+            filled_qty: filled,
+            avg_px: px,
+            ts_ns: 1,
+            venue: "A".into(),
+            side: Some(side),
+            order_qty: 2,
+            strategy: "mr".into(),
+            tags: vec![],
+            book: String::new(),
+            exch_ts_ns: 0,
+        })
+    }
+
+    #[test]
+    fn replay_rebuilds_positions_and_open_orders() {
+        let events = [
+            exec("b1", Side::Buy, ExecStatus::Ack, 0, 0),
+            exec("b1", Side::Buy, ExecStatus::Filled, 2, 100),
+            exec("s1", Side::Sell, ExecStatus::Ack, 0, 0),
+            exec("s1", Side::Sell, ExecStatus::PartialFill, 1, 110),
+            Event::Md(MdTick { ts_ns: 5, symbol: "BTCUSDT".into(), best_bid: 119, best_ask: 121, bid_qty: 0, ask_qty: 0, seq: 0, exch_ts_ns: 0 }),
+        ];
+        // dua kali replay -> hasil identik (deterministik)
+        let run = || {
+            let mut r = Rebuild::default();
+            events.iter().for_each(|e| r.apply(e));
+            r.finish()
+        };
+        let (a, b) = (run(), run());
+        assert_eq!(serde_json::to_string(&a).unwrap(), serde_json::to_string(&b).unwrap());
+
+        let pos = &a.positions["BTCUSDT"];
+        assert_eq!(pos.total_qty, 1);
+        assert_eq!(pos.realized_pnl, 10);
+        assert_eq!(pos.unrealized_pnl, 20);
+        assert_eq!(pos.by_strategy["mr"].qty, 1);
+        assert_eq!(a.open_orders.keys().collect::<Vec<_>>(), ["s1"]);
+        assert_eq!(a.open_orders["s1"].filled, 1);
+        assert_eq!((a.events, a.last_ts_ns), (5, 5));
+    }
+
+    #[test]
+    fn checkpoint_resume_and_shard_merge_match_a_single_run() {
+        let events = [
+            exec("b1", Side::Buy, ExecStatus::Ack, 0, 0),
+            exec_on("ETHUSDT", "e1", Side::Sell, ExecStatus::PartialFill, 1, 50),
+            exec("b1", Side::Buy, ExecStatus::PartialFill, 1, 100),
+            exec("b1", Side::Buy, ExecStatus::Filled, 2, 101),
+            exec_on("ETHUSDT", "e1", Side::Sell, ExecStatus::Filled, 3, 49),
+            Event::Md(MdTick { ts_ns: 9, symbol: "ETHUSDT".into(), best_bid: 44, best_ask: 46, bid_qty: 0, ask_qty: 0, seq: 0, exch_ts_ns: 0 }),
+        ];
+        let json = |st: &RebuiltState| serde_json::to_string(st).unwrap();
+        let mut full = Rebuild::default();
+        events.iter().for_each(|e| full.apply(e));
+        let full = full.finish();
+
+        // berhenti di tengah partial fill, lanjut dari checkpoint
+        let mut first = Rebuild::default();
+        events[..3].iter().for_each(|e| first.apply(e));
+        let ck = serde_json::to_string(&Checkpoint { inputs: vec![], until: None, shard: None, lines: 3, skipped: 0, rebuild: &first }).unwrap();
+        let mut resumed: Checkpoint<Rebuild> = serde_json::from_str(&ck).unwrap();
+        events[3..].iter().for_each(|e| resumed.rebuild.apply(e));
+        assert_eq!(json(&resumed.rebuild.finish()), json(&full));
+
+        let parts = (0..3)
+            .map(|index| {
+                let shard = Shard { index, count: 3 };
+                let mut r = Rebuild::default();
+                events.iter().filter(|e| shard.owns(e.symbol().unwrap())).for_each(|e| r.apply(e));
+                r.finish()
+            })
+            .collect();
+        assert_eq!(json(&merge(parts).unwrap()), json(&full));
+
+        assert_eq!("1/4".parse::<Shard>(), Ok(Shard { index: 1, count: 4 }));
+        assert!("4/4".parse::<Shard>().is_err() && "x".parse::<Shard>().is_err());
+    }
+}
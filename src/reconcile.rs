@@ -0,0 +1,259 @@
+// ===============================
+// src/reconcile.rs
+// ===============================
+//
+// Rekonsiliasi periodik blotter lokal vs histori trade Binance.
+//
+// - GET /api/v3/allOrders  -> peta orderId -> clientOrderId
+// - GET /api/v3/myTrades   -> fill di exchange (per orderId)
+// - Dicocokkan ke blotter per cl_id (hanya venue binance/binance_testnet).
+//
+// Jenis break (label `kind` di `reconciliation_breaks_total`):
+// - missing_local    : exchange punya fill, blotter tidak
+// - missing_exchange : blotter punya fill (lebih tua dari grace), exchange tidak
+// - qty_mismatch     : total qty berbeda
+// - px_mismatch      : avg px berbeda > 1 tick
+//
+// Setiap break hanya dihitung sekali per (cl_id, kind); catatan break yang sudah keluar dari
+// lookback dibuang tiap siklus.
+//
+// Histori diambil per halaman (limit 1000): window lookback dipecah per 24 jam (batas startTime/endTime
+// allOrders & myTrades), halaman penuh dilanjutkan lewat `orderId` (allOrders) / `fromId` (myTrades).
+//
+use ahash::AHashMap as HashMap;
+use chrono::Utc;
+use tokio::time::{interval, Duration, MissedTickBehavior};
+use tracing::{error, info, warn};
+
+use crate::binance::timestamp_ms;
+use crate::blotter::Blotter;
+use crate::exchange_http::{endpoints, Endpoint, ExchangeHttp, HttpError};
+use crate::alerts::{AlertKind, Alerts};
+use crate::metrics::RECON_BREAKS;
+
+/// Fill lokal tidak dianggap hilang di exchange sebelum umurnya melewati ini
+const MISSING_GRACE_NS: i128 = 30_000_000_000;
+/// Baris per halaman allOrders / myTrades (maks Binance)
+const PAGE_LIMIT: usize = 1000;
+/// Window startTime..endTime terpanjang yang diterima allOrders / myTrades
+const MAX_WINDOW_MS: u64 = 24 * 3600 * 1000;
+
+#[derive(Clone)]
+pub struct ReconCfg {
+    pub http: ExchangeHttp,
+    pub symbols: Vec<String>,
+    pub venues: Vec<String>,
+    pub interval_secs: u64,
+    pub lookback_secs: u64,
+    pub alerts: Alerts,
+}
+
+/// Agregat fill per cl_id (qty & notional dalam skala domain)
+#[derive(Debug, Default, Clone, Copy)]
+struct Agg {
+    qty: f64,
+    notional: f64,
+    last_ts_ns: i128,
+}
+
+impl Agg {
+    fn avg_px(&self) -> f64 {
+        if self.qty > 0.0 { self.notional / self.qty } else { 0.0 }
+    }
+}
+
+fn str_f64(v: &serde_json::Value, key: &str) -> f64 {
+    v.get(key).and_then(|x| x.as_str()).and_then(|s| s.parse::<f64>().ok()).unwrap_or(0.0)
+}
+
+/// Potong `start_ms..=end_ms` menjadi window berurutan yang masing-masing <= 24 jam
+fn windows(start_ms: u64, end_ms: u64) -> Vec<(u64, u64)> {
+    let mut out = Vec::new();
+    let mut from = start_ms;
+    while from <= end_ms {
+        let to = (from + MAX_WINDOW_MS - 1).min(end_ms);
+        out.push((from, to));
+        from = to + 1;
+    }
+    out
+}
+
+/// Semua baris allOrders / myTrades sejak `start_ms`. `id_param` = param lanjutan (`orderId` / `fromId`),
+/// `id_field` = field id di baris respon (`orderId` / `id`)
+async fn fetch_paged(
+    http: &ExchangeHttp,
+    ep: &Endpoint,
+    symbol: &str,
+    (start_ms, end_ms): (u64, u64),
+    (id_param, id_field): (&str, &str),
+) -> Result<Vec<serde_json::Value>, HttpError> {
+    let page = |v: serde_json::Value| match v {
+        serde_json::Value::Array(rows) => rows,
+        _ => Vec::new(),
+    };
+    let mut out = Vec::new();
+    for (from, to) in windows(start_ms, end_ms) {
+        let p = [
+            ("symbol", symbol.to_string()),
+            ("startTime", from.to_string()),
+            ("endTime", to.to_string()),
+            ("limit", PAGE_LIMIT.to_string()),
+        ];
+        let mut rows = page(http.send(ep, &p).await?);
+        if rows.len() < PAGE_LIMIT {
+            out.extend(rows);
+            continue;
+        }
+        // halaman penuh: lanjut per id (urut naik, tanpa batas waktu) sampai halaman tidak penuh;
+        // itu sudah mencakup semua baris sampai sekarang, window berikutnya tidak perlu diambil
+        loop {
+            let next = rows.last().and_then(|r| r.get(id_field)).and_then(|x| x.as_u64()).map(|id| id + 1);
+            let full = rows.len() >= PAGE_LIMIT;
+            out.extend(rows);
+            let (true, Some(next)) = (full, next) else { break };
+            let p = [("symbol", symbol.to_string()), (id_param, next.to_string()), ("limit", PAGE_LIMIT.to_string())];
+            rows = page(http.send(ep, &p).await?);
+        }
+        break;
+    }
+    Ok(out)
+}
+
+async fn exchange_fills(
+    http: &ExchangeHttp,
+    symbol: &str,
+    window: (u64, u64),
+) -> Result<HashMap<String, Agg>, Box<dyn std::error::Error + Send + Sync>> {
+    let orders = fetch_paged(http, &endpoints::all_orders(), symbol, window, ("orderId", "orderId")).await?;
+    let mut cl_by_order: HashMap<u64, String> = HashMap::new();
+    for o in &orders {
+        if let (Some(id), Some(cl)) = (
+            o.get("orderId").and_then(|x| x.as_u64()),
+            o.get("clientOrderId").and_then(|x| x.as_str()),
+        ) {
+            cl_by_order.insert(id, cl.to_string());
+        }
+    }
+
+    let trades = fetch_paged(http, &endpoints::my_trades(), symbol, window, ("fromId", "id")).await?;
+    let mut out: HashMap<String, Agg> = HashMap::new();
+    for t in &trades {
+        let order_id = t.get("orderId").and_then(|x| x.as_u64()).unwrap_or(0);
+        // order di luar window allOrders: pakai orderId sebagai kunci
+        let key = cl_by_order
+            .get(&order_id)
+            .cloned()
+            .or_else(|| crate::order_ids::cl_id_of(order_id))
+            .unwrap_or_else(|| format!("orderId:{order_id}"));
+        let qty = str_f64(t, "qty");
+        // skala domain 2 desimal (PoC, sama dengan gateway_binance)
+        let px = str_f64(t, "price") * 100.0;
+        let ts_ns = t.get("time").and_then(|x| x.as_i64()).unwrap_or(0) as i128 * 1_000_000;
+        let a = out.entry(key).or_default();
+        a.qty += qty;
+        a.notional += px * qty;
+        a.last_ts_ns = a.last_ts_ns.max(ts_ns);
+    }
+    Ok(out)
+}
+
+fn local_fills(blotter: &Blotter, cfg: &ReconCfg, symbol: &str, since_ns: i128) -> HashMap<String, Agg> {
+    let mut out: HashMap<String, Agg> = HashMap::new();
+    for f in blotter.query(Some(symbol), None, Some(since_ns)) {
+        if !cfg.venues.iter().any(|v| v.eq_ignore_ascii_case(&f.venue)) {
+            continue;
+        }
+        let a = out.entry(f.cl_id).or_default();
+        // lot -> unit base (sama dengan qty myTrades)
+        let qty = crate::sizing::base_qty(&f.symbol, f.qty);
+        a.qty += qty;
+        a.notional += f.px as f64 * qty;
+        a.last_ts_ns = a.last_ts_ns.max(f.ts_ns);
+    }
+    out
+}
+
+/// Bandingkan dua sisi; kembalikan daftar (cl_id, kind)
+fn diff(local: &HashMap<String, Agg>, exch: &HashMap<String, Agg>, now_ns: i128) -> Vec<(String, &'static str)> {
+    let mut breaks = Vec::new();
+    for (cl_id, e) in exch {
+        match local.get(cl_id) {
+            None => breaks.push((cl_id.clone(), "missing_local")),
+            Some(l) => {
+                if (l.qty - e.qty).abs() > 1e-9 {
+                    breaks.push((cl_id.clone(), "qty_mismatch"));
+                } else if (l.avg_px() - e.avg_px()).abs() > 1.0 {
+                    breaks.push((cl_id.clone(), "px_mismatch"));
+                }
+            }
+        }
+    }
+    for (cl_id, l) in local {
+        if !exch.contains_key(cl_id) && now_ns - l.last_ts_ns > MISSING_GRACE_NS {
+            breaks.push((cl_id.clone(), "missing_exchange"));
+        }
+    }
+    breaks
+}
+
+/// Break yang sudah dilaporkan -> ts_ns terakhir terlihat; yang tidak terlihat lagi selama lookback dibuang
+/// (fill-nya sudah keluar dari window, jadi tidak mungkin muncul lagi)
+fn prune_seen(seen: &mut HashMap<(String, &'static str), i128>, now_ns: i128, lookback_ns: i128) {
+    seen.retain(|_, last| now_ns - *last <= lookback_ns);
+}
+
+/// Task rekonsiliasi: jalan tiap `interval_secs`
+pub async fn run(cfg: ReconCfg, blotter: Blotter) {
+    info!(interval_secs = cfg.interval_secs, lookback_secs = cfg.lookback_secs, "reconciliation: started");
+    let mut seen: HashMap<(String, &'static str), i128> = HashMap::new();
+    let lookback_ns = cfg.lookback_secs as i128 * 1_000_000_000;
+
+    let mut tick = interval(Duration::from_secs(cfg.interval_secs.max(1)));
+    tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        tick.tick().await;
+        let now_ns = Utc::now().timestamp_nanos_opt().unwrap_or(0) as i128;
+        let now_ms = timestamp_ms();
+        let start_ms = now_ms.saturating_sub(cfg.lookback_secs * 1000);
+        let since_ns = start_ms as i128 * 1_000_000;
+
+        for symbol in &cfg.symbols {
+            let exch = match exchange_fills(&cfg.http, symbol, (start_ms, now_ms)).await {
+                Ok(x) => x,
+                Err(e) => {
+                    warn!(?e, %symbol, "reconciliation: fetch exchange trades failed");
+                    continue;
+                }
+            };
+            let local = local_fills(&blotter, &cfg, symbol, since_ns);
+            for (cl_id, kind) in diff(&local, &exch, now_ns) {
+                if seen.insert((cl_id.clone(), kind), now_ns).is_none() {
+                    RECON_BREAKS.with_label_values(&[kind, crate::metrics::symbol_label(symbol)]).inc();
+                    error!(%symbol, %cl_id, %kind, "ALERT reconciliation break");
+                    let vars = [("symbol", symbol.clone()), ("cl_id", cl_id.clone()), ("kind", kind.to_string())];
+                    cfg.alerts.notify(AlertKind::ReconBreak, &vars);
+                }
+            }
+        }
+        prune_seen(&mut seen, now_ns, lookback_ns);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookback_split_into_24h_windows_and_seen_pruned() {
+        let day = MAX_WINDOW_MS;
+        assert_eq!(windows(0, 1_000), vec![(0, 1_000)]);
+        assert_eq!(windows(0, 2 * day + 5), vec![(0, day - 1), (day, 2 * day - 1), (2 * day, 2 * day + 5)]);
+        assert!(windows(10, 5).is_empty());
+
+        let mut seen: HashMap<(String, &'static str), i128> = HashMap::new();
+        seen.insert(("old".into(), "missing_local"), 0);
+        seen.insert(("new".into(), "qty_mismatch"), 900);
+        prune_seen(&mut seen, 1_000, 500);
+        assert_eq!(seen.keys().map(|(c, _)| c.as_str()).collect::<Vec<_>>(), ["new"]);
+    }
+}
@@ -0,0 +1,153 @@
+// ===============================
+// src/reconnect.rs
+// ===============================
+//
+// Kebijakan reconnect bersama untuk semua loop jaringan (feed bookTicker, user data stream,
+// link IPC, adapter venue berikutnya):
+// - backoff eksponensial base..max dengan strategi jitter (none | full | equal | fixed:<ms>)
+// - reset ke attempt 0 hanya jika koneksi sempat stabil >= reset window (anti flapping)
+// - batas attempt opsional: setelah habis loop menyerah (return) dan supervisor yang me-restart
+//
+// ENV:
+//   RECONNECT_BASE_MS=500  RECONNECT_MAX_MS=32000
+//   RECONNECT_MAX_ATTEMPTS=0        (0 = tanpa batas)
+//   RECONNECT_RESET_SECS=30
+//   RECONNECT_JITTER=fixed:250      none | full | equal | fixed:<ms>
+//
+// Metrik per koneksi: `connection_up{conn}`, `reconnect_attempts_total{conn}`,
+// `reconnect_backoff_ms{conn}`.
+//
+use once_cell::sync::OnceCell;
+use rand::Rng;
+use tokio::time::{sleep, Duration, Instant};
+use tracing::{error, info, warn};
+
+use crate::metrics::{CONN_UP, RECONNECT_ATTEMPTS, RECONNECT_BACKOFF_MS};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Jitter {
+    None,
+    /// acak 0..=delay
+    Full,
+    /// delay/2 + acak 0..=delay/2
+    Equal,
+    /// delay + acak 0..=ms
+    Fixed(u64),
+}
+
+impl Jitter {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "none" | "off" => Some(Jitter::None),
+            "full" => Some(Jitter::Full),
+            "equal" => Some(Jitter::Equal),
+            other => other.strip_prefix("fixed:").and_then(|ms| ms.parse().ok()).map(Jitter::Fixed),
+        }
+    }
+
+    fn apply(&self, ms: u64) -> u64 {
+        let mut rng = rand::thread_rng();
+        match *self {
+            Jitter::None => ms,
+            Jitter::Full => rng.gen_range(0..=ms),
+            Jitter::Equal => ms / 2 + rng.gen_range(0..=ms / 2),
+            Jitter::Fixed(j) => ms + rng.gen_range(0..=j),
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Policy {
+    pub base_ms: u64,
+    pub max_ms: u64,
+    pub max_attempts: u32, // 0 = tanpa batas
+    pub reset_after: Duration,
+    pub jitter: Jitter,
+}
+
+impl Default for Policy {
+    /// Sama dengan backoff lama feed.rs: 0.5s..32s + jitter 0..250ms
+    fn default() -> Self {
+        Self {
+            base_ms: 500,
+            max_ms: 32_000,
+            max_attempts: 0,
+            reset_after: Duration::from_secs(30),
+            jitter: Jitter::Fixed(250),
+        }
+    }
+}
+
+impl Policy {
+    /// Delay sebelum attempt ke-`attempt` (1-based), sebelum jitter
+    pub fn delay_ms(&self, attempt: u32) -> u64 {
+        let shift = attempt.saturating_sub(1).min(20);
+        self.base_ms.saturating_mul(1u64 << shift).min(self.max_ms)
+    }
+}
+
+static POLICY: OnceCell<Policy> = OnceCell::new();
+
+/// Set policy global (dipanggil sekali di startup)
+pub fn init(policy: Policy) {
+    let _ = POLICY.set(policy);
+}
+
+pub fn policy() -> &'static Policy {
+    POLICY.get_or_init(Policy::default)
+}
+
+/// State backoff satu koneksi (mis. "bookTicker:BTCUSDT", "user_stream:binance")
+pub struct Backoff {
+    policy: Policy,
+    conn: String,
+    attempt: u32,
+    connected_at: Option<Instant>,
+}
+
+impl Backoff {
+    /// Backoff dengan policy global
+    #[cfg(feature = "binance")]
+    pub fn new(conn: impl Into<String>) -> Self {
+        Self::with_policy(conn, policy().clone())
+    }
+
+    pub fn with_policy(conn: impl Into<String>, policy: Policy) -> Self {
+        let conn = conn.into();
+        CONN_UP.with_label_values(&[&conn]).set(0);
+        Self { policy, conn, attempt: 0, connected_at: None }
+    }
+
+    /// Koneksi berhasil dibuka
+    pub fn connected(&mut self) {
+        self.connected_at = Some(Instant::now());
+        CONN_UP.with_label_values(&[&self.conn]).set(1);
+    }
+
+    /// Koneksi putus / connect gagal: tunggu sesuai policy.
+    /// `false` = batas attempt habis, caller sebaiknya berhenti.
+    pub async fn wait(&mut self) -> bool {
+        CONN_UP.with_label_values(&[&self.conn]).set(0);
+        if let Some(t) = self.connected_at.take() {
+            if t.elapsed() >= self.policy.reset_after {
+                self.attempt = 0;
+            }
+        }
+        self.attempt = self.attempt.saturating_add(1);
+        if self.policy.max_attempts > 0 && self.attempt > self.policy.max_attempts {
+            error!(conn = %self.conn, attempts = self.policy.max_attempts, "reconnect: giving up");
+            return false;
+        }
+        let ms = self.policy.jitter.apply(self.policy.delay_ms(self.attempt));
+        RECONNECT_ATTEMPTS.with_label_values(&[&self.conn]).inc();
+        RECONNECT_BACKOFF_MS.with_label_values(&[&self.conn]).set(ms as i64);
+        if self.attempt == 1 {
+            info!(conn = %self.conn, backoff_ms = ms, "reconnect");
+        } else {
+            warn!(conn = %self.conn, attempt = self.attempt, backoff_ms = ms, "reconnect");
+        }
+        sleep(Duration::from_millis(ms)).await;
+        true
+    }
+}
@@ -0,0 +1,271 @@
+// ===============================
+// src/recorder/mod.rs
+// ===============================
+//
+// JSONL recorder yang ringan & tahan banting:
+// - Baris pertama file baru = header skema (`{"schema":"dma_bot_rust/events","version":N,...}`).
+// - Tulis setiap Event ke file .jsonl (append).
+// - Bufer dengan BufWriter agar hemat syscall.
+// - Flush periodik tiap 1s dan/atau tiap 1000 event.
+// - Otomatis membuat parent directory jika belum ada.
+// - Jika tulis gagal, coba reopen file dan lanjut.
+//
+// - Filter tipe event (RECORD_EVENTS) + sampling MD per symbol (RECORD_MD_SAMPLE) untuk menghemat disk
+//   pada run panjang: histori order/exec tetap lengkap, firehose MD diperkecil.
+//
+// ENV:
+//   RECORD_FILE=/path/to/events.jsonl   (aktifkan recorder, lihat main.rs)
+//   RECORD_EVENTS=                      (kosong = semua; md,sig,ord,exec,compliance,riskreject,note)
+//   RECORD_MD_SAMPLE=1                  (tulis setiap tick ke-N per symbol; 1 = semua)
+// Baca kembali rekaman: `reader` (typed, filter tipe/symbol/waktu, toleran versi lama).
+//
+pub mod reader;
+
+use std::path::Path;
+use tokio::{
+    fs::{self, OpenOptions},
+    io::{AsyncWriteExt, BufWriter},
+    sync::mpsc,
+    time::{interval, Duration, MissedTickBehavior},
+};
+use ahash::AHashMap as HashMap;
+use tracing::{error, info};
+
+use crate::domain::Event;
+
+/// Tipe event yang dikenal recorder (`Event::kind`)
+pub const KINDS: &[&str] = &["md", "sig", "ord", "exec", "compliance", "riskreject", "note"];
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RecordCfg {
+    /// tipe event yang ditulis (kosong = semua)
+    pub events: Vec<String>,
+    /// tulis setiap tick MD ke-N per symbol
+    pub md_sample: u64,
+}
+
+impl Default for RecordCfg {
+    fn default() -> Self {
+        Self { events: Vec::new(), md_sample: 1 }
+    }
+}
+
+impl RecordCfg {
+    pub fn from_env() -> Self {
+        let events = std::env::var("RECORD_EVENTS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|s| s.trim().to_ascii_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect();
+        let md_sample = std::env::var("RECORD_MD_SAMPLE").ok().and_then(|s| s.trim().parse().ok()).unwrap_or(1u64).max(1);
+        Self { events, md_sample }
+    }
+
+    pub fn records(&self, kind: &str) -> bool {
+        self.events.is_empty() || self.events.iter().any(|k| k == kind)
+    }
+}
+
+/// Filter tipe + sampling MD (counter per symbol; tick pertama tiap symbol selalu ditulis)
+struct Sampler {
+    cfg: RecordCfg,
+    md_seen: HashMap<String, u64>,
+}
+
+impl Sampler {
+    fn new(cfg: RecordCfg) -> Self {
+        Self { cfg, md_seen: HashMap::new() }
+    }
+
+    fn admit(&mut self, ev: &Event) -> bool {
+        if !self.cfg.records(ev.kind()) {
+            return false;
+        }
+        let Event::Md(md) = ev else { return true };
+        let n = self.md_seen.entry(md.symbol.clone()).or_default();
+        let keep = n.is_multiple_of(self.cfg.md_sample);
+        *n += 1;
+        keep
+    }
+}
+
+/// Identitas skema rekaman (baris header)
+pub const SCHEMA: &str = "dma_bot_rust/events";
+/// Naikkan saat bentuk Event berubah tidak kompatibel (field baru ber-`serde(default)` tidak perlu)
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Header baris pertama file rekaman
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Header {
+    pub schema: String,
+    pub version: u32,
+    #[serde(default)]
+    pub app_version: String,
+    #[serde(default)]
+    pub created_ns: i128,
+}
+
+impl Header {
+    fn current() -> Self {
+        Self {
+            schema: SCHEMA.to_string(),
+            version: SCHEMA_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            created_ns: crate::clock::now_ns(),
+        }
+    }
+}
+
+async fn open_writer(path: &str) -> BufWriter<tokio::fs::File> {
+    // Pastikan parent directory ada (kalau ada)
+    if let Some(parent) = Path::new(path).parent() {
+        if !parent.as_os_str().is_empty() {
+            if let Err(e) = fs::create_dir_all(parent).await {
+                error!(?e, %path, "recorder: create_dir_all failed");
+            }
+        }
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await
+        .unwrap_or_else(|e| panic!("recorder: open {} failed: {}", path, e));
+
+    // File baru: tulis header skema dulu (file lama tanpa header tetap dibaca reader sebagai v0)
+    let empty = file.metadata().await.map(|m| m.len() == 0).unwrap_or(false);
+    let mut writer = BufWriter::new(file);
+    if empty {
+        if let Ok(h) = serde_json::to_string(&Header::current()) {
+            let _ = writer.write_all(format!("{h}\n").as_bytes()).await;
+        }
+    }
+    writer
+}
+
+pub async fn run(mut rx: mpsc::Receiver<Event>, path: String, cfg: RecordCfg) {
+    info!(%path, events = ?cfg.events, md_sample = cfg.md_sample, "recorder: started");
+    let mut writer = open_writer(&path).await;
+    let mut sampler = Sampler::new(cfg);
+
+    // Flush periodik (tiap 1 detik) + flush berbasis jumlah event
+    let mut tick = interval(Duration::from_secs(1));
+    tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    let mut since_last_flush: u32 = 0;
+    const FLUSH_EVERY_N_EVENTS: u32 = 1000;
+
+    loop {
+        tokio::select! {
+            maybe_ev = rx.recv() => {
+                match maybe_ev {
+                    Some(ev) => {
+                        if !sampler.admit(&ev) {
+                            continue;
+                        }
+                        // Serialize event
+                        let line = match serde_json::to_string(&ev) {
+                            Ok(s) => s,
+                            Err(e) => {
+                                error!(?e, "recorder: serialize error, skip event");
+                                continue;
+                            }
+                        };
+
+                        // Tulis + newline
+                        if let Err(e) = writer.write_all(line.as_bytes()).await {
+                            error!(?e, "recorder: write_all failed, attempting reopen");
+                            writer = open_writer(&path).await;
+                            // coba lagi sekali setelah reopen
+                            if let Err(e2) = writer.write_all(line.as_bytes()).await {
+                                error!(?e2, "recorder: write_all failed again after reopen, drop event");
+                                continue;
+                            }
+                        }
+                        if let Err(e) = writer.write_all(b"\n").await {
+                            error!(?e, "recorder: write newline failed, attempting reopen");
+                            writer = open_writer(&path).await;
+                            // newline setelah reopen (opsional)
+                            let _ = writer.write_all(b"\n").await;
+                        }
+
+                        since_last_flush += 1;
+                        if since_last_flush >= FLUSH_EVERY_N_EVENTS {
+                            let _ = writer.flush().await;
+                            since_last_flush = 0;
+                        }
+                    }
+                    None => {
+                        // Channel closed: flush dan keluar
+                        let _ = writer.flush().await;
+                        info!("recorder: channel closed, stopped");
+                        break;
+                    }
+                }
+            }
+
+            _ = tick.tick() => {
+                // Flush periodik
+                let _ = writer.flush().await;
+                since_last_flush = 0;
+            }
+        }
+    }
+}
+
+/// Subcommand `read-recording`: ringkasan (jumlah per tipe & symbol, rentang waktu) atau dump JSONL
+pub fn read_recording(paths: &[String], filter: reader::Filter, print: bool) {
+    let mut rd = match reader::Reader::open_all(paths) {
+        Ok(r) => r.with_filter(filter),
+        Err(e) => {
+            eprintln!("read-recording: open {} failed: {e}", paths.join(" "));
+            std::process::exit(1);
+        }
+    };
+    let h = rd.header().clone();
+    eprintln!("schema={} version={} app_version={} created_ns={}", h.schema, h.version, h.app_version, h.created_ns);
+
+    let mut counts: std::collections::BTreeMap<(String, String), u64> = Default::default();
+    let (mut first, mut last) = (None::<i128>, None::<i128>);
+    for ev in rd.by_ref() {
+        if print {
+            if let Ok(s) = serde_json::to_string(&ev) {
+                println!("{s}");
+            }
+            continue;
+        }
+        *counts.entry((ev.kind().to_string(), ev.symbol().unwrap_or("-").to_string())).or_default() += 1;
+        if let Some(ts) = ev.ts_ns() {
+            first = Some(first.map_or(ts, |f| f.min(ts)));
+            last = Some(last.map_or(ts, |l| l.max(ts)));
+        }
+    }
+    if !print {
+        for ((kind, symbol), n) in &counts {
+            println!("{kind:<11} {symbol:<10} {n}");
+        }
+        println!("first_ts_ns={} last_ts_ns={}", first.unwrap_or(0), last.unwrap_or(0));
+    }
+    eprintln!("skipped={}", rd.skipped());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::MdTick;
+
+    #[test]
+    fn filters_kinds_and_samples_md_per_symbol() {
+        let md = |symbol: &str| Event::Md(MdTick { ts_ns: 1, symbol: symbol.into(), best_bid: 1, best_ask: 2, bid_qty: 0, ask_qty: 0, seq: 0, exch_ts_ns: 0 });
+        let mut s = Sampler::new(RecordCfg { events: vec!["md".into(), "exec".into()], md_sample: 3 });
+        let kept: Vec<bool> = (0..6).map(|_| s.admit(&md("BTCUSDT"))).collect();
+        assert_eq!(kept, [true, false, false, true, false, false]);
+        // counter per symbol
+        assert!(s.admit(&md("ETHUSDT")));
+        assert!(!s.admit(&Event::Note("{}".into())));
+
+        let mut all = Sampler::new(RecordCfg::default());
+        assert!((0..3).all(|_| all.admit(&md("BTCUSDT"))) && all.admit(&Event::Note("{}".into())));
+    }
+}
@@ -0,0 +1,263 @@
+// ===============================
+// src/recorder/reader.rs
+// ===============================
+//
+// Pembaca rekaman JSONL bertipe:
+// - header skema dibaca dari baris pertama (file lama tanpa header = versi 0)
+// - iterasi `Event` dengan filter tipe / symbol / rentang waktu
+// - forward-compatible: field tak dikenal diabaikan, field baru ber-`serde(default)`,
+//   baris rusak / varian Event yang belum dikenal dilewati dan dihitung (`skipped`)
+// - file terkompresi dibaca transparan (gzip / zstd, dideteksi dari magic byte, bukan ekstensi)
+// - beberapa segmen (file rotasi / arsip, atau direktori berisi *.jsonl[.gz|.zst]) dibaca
+//   berurutan sesuai waktu: `created_ns` header, atau ts event pertama untuk file tanpa header
+// - posisi = jumlah baris mentah yang sudah dibaca lintas segmen (offset byte tidak bisa di-seek pada
+//   gzip/zstd); `skip_lines` melompati baris tanpa parse JSON untuk resume checkpoint
+//
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use super::{Header, SCHEMA, SCHEMA_VERSION};
+use crate::domain::Event;
+
+/// Filter event; field kosong/None = tidak difilter
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    pub kinds: Vec<String>, // md | sig | ord | exec | note | compliance | riskreject
+    pub symbol: Option<String>,
+    pub since_ns: Option<i128>,
+    pub until_ns: Option<i128>,
+}
+
+impl Filter {
+    pub fn matches(&self, ev: &Event) -> bool {
+        if !self.kinds.is_empty() && !self.kinds.iter().any(|k| k.eq_ignore_ascii_case(ev.kind())) {
+            return false;
+        }
+        if let Some(sym) = &self.symbol {
+            if !ev.symbol().is_some_and(|s| s.eq_ignore_ascii_case(sym)) {
+                return false;
+            }
+        }
+        // event tanpa waktu (Note) tidak lolos filter waktu
+        match ev.ts_ns() {
+            Some(ts) => self.since_ns.is_none_or(|t| ts >= t) && self.until_ns.is_none_or(|t| ts < t),
+            None => self.since_ns.is_none() && self.until_ns.is_none(),
+        }
+    }
+}
+
+type Lines = Box<dyn BufRead + Send>;
+
+/// Buka file rekaman, dekompresi sesuai magic byte (gzip 1f8b, zstd 28b52ffd)
+fn open_lines(path: &str) -> io::Result<Lines> {
+    let mut file = BufReader::new(File::open(path)?);
+    let magic = file.fill_buf()?;
+    Ok(if magic.starts_with(&[0x1f, 0x8b]) {
+        Box::new(BufReader::new(flate2::bufread::MultiGzDecoder::new(file)))
+    } else if magic.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+        Box::new(BufReader::new(zstd::stream::read::Decoder::with_buffer(file)?))
+    } else {
+        Box::new(file)
+    })
+}
+
+/// Baris berikutnya; Ok(None) = akhir segmen. UTF-8 tidak valid = baris rusak (dilewati), bukan error IO.
+fn read_line(lines: &mut Lines) -> io::Result<Option<Result<String, ()>>> {
+    let mut buf = Vec::new();
+    if lines.read_until(b'\n', &mut buf)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(String::from_utf8(buf).map_err(|_| ())))
+}
+
+/// Satu file rekaman yang sudah dibuka (header sudah dibaca)
+struct Segment {
+    path: String,
+    header: Header,
+    pending: Option<String>, // baris pertama jika bukan header (file lama)
+    lines: Lines,
+}
+
+impl Segment {
+    fn open(path: &str) -> io::Result<Self> {
+        let mut lines = open_lines(path)?;
+        let first = read_line(&mut lines)?.and_then(Result::ok);
+        let (header, pending) = match first.as_deref().map(serde_json::from_str::<Header>) {
+            Some(Ok(h)) if h.schema == SCHEMA => (h, None),
+            _ => (Header { schema: SCHEMA.to_string(), version: 0, app_version: String::new(), created_ns: 0 }, first),
+        };
+        if header.version > SCHEMA_VERSION {
+            tracing::warn!(path, version = header.version, supported = SCHEMA_VERSION, "recording is newer than this build; unknown events will be skipped");
+        }
+        Ok(Self { path: path.to_string(), header, pending, lines })
+    }
+
+    /// Kunci urutan segmen: created_ns header, atau ts event pertama (file tanpa header)
+    fn start_ns(&mut self) -> i128 {
+        if self.header.created_ns > 0 {
+            return self.header.created_ns;
+        }
+        if self.pending.is_none() {
+            self.pending = read_line(&mut self.lines).ok().flatten().and_then(Result::ok);
+        }
+        self.pending.as_deref().and_then(|l| serde_json::from_str::<Event>(l).ok()).and_then(|e| e.ts_ns()).unwrap_or(0)
+    }
+}
+
+/// File segmen rekaman di direktori (*.jsonl, *.jsonl.gz, *.jsonl.zst)
+fn segment_files(dir: &Path) -> io::Result<Vec<String>> {
+    let mut files: Vec<String> = std::fs::read_dir(dir)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .map(|p| p.to_string_lossy().into_owned())
+        .filter(|p| [".jsonl", ".jsonl.gz", ".jsonl.zst"].iter().any(|ext| p.ends_with(ext)))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+pub struct Reader {
+    header: Header,
+    current: Segment,
+    rest: VecDeque<Segment>,
+    filter: Filter,
+    skipped: u64,
+    lines: u64,
+}
+
+impl Reader {
+    /// Buka satu file (polos / .gz / .zst) atau direktori berisi segmen rekaman
+    pub fn open(path: &str) -> io::Result<Self> {
+        Self::open_all(&[path.to_string()])
+    }
+
+    /// Buka beberapa file / direktori sebagai satu rekaman; segmen dibaca berurutan sesuai waktu mulainya
+    pub fn open_all(paths: &[String]) -> io::Result<Self> {
+        let mut files = Vec::new();
+        for p in paths {
+            match Path::new(p).is_dir() {
+                true => files.extend(segment_files(Path::new(p))?),
+                false => files.push(p.clone()),
+            }
+        }
+        let paths = files;
+        let mut segments = paths
+            .iter()
+            .map(|p| Segment::open(p).map(|mut s| (s.start_ns(), s)))
+            .collect::<io::Result<Vec<_>>>()?;
+        segments.sort_by_key(|(start, _)| *start);
+        let mut rest: VecDeque<Segment> = segments.into_iter().map(|(_, s)| s).collect();
+        let current = rest.pop_front().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no recording segments"))?;
+        Ok(Self { header: current.header.clone(), current, rest, filter: Filter::default(), skipped: 0, lines: 0 })
+    }
+
+    pub fn with_filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Header segmen pertama
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Jumlah baris yang dilewati (rusak / tipe tidak dikenal)
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+
+    /// Jumlah baris mentah yang sudah dibaca (posisi resume)
+    pub fn position(&self) -> u64 {
+        self.lines
+    }
+
+    /// Lompati `n` baris mentah tanpa parse; false jika rekaman habis lebih dulu
+    pub fn skip_lines(&mut self, n: u64) -> bool {
+        (0..n).all(|_| self.next_line().is_some())
+    }
+
+    /// Baris mentah berikutnya lintas segmen
+    fn next_line(&mut self) -> Option<Result<String, ()>> {
+        loop {
+            if let Some(l) = self.current.pending.take() {
+                self.lines += 1;
+                return Some(Ok(l));
+            }
+            match read_line(&mut self.current.lines) {
+                Ok(Some(l)) => {
+                    self.lines += 1;
+                    return Some(l);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    // arsip terpotong / rusak: sisa segmen tidak terbaca, lanjut ke segmen berikutnya
+                    tracing::warn!(path = %self.current.path, ?e, "recording segment read error, skipping rest of segment");
+                    self.skipped += 1;
+                }
+            }
+            self.current = self.rest.pop_front()?;
+        }
+    }
+}
+
+impl Iterator for Reader {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        loop {
+            let Ok(line) = self.next_line()? else {
+                self.skipped += 1;
+                continue;
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<Event>(&line) {
+                Ok(ev) if self.filter.matches(&ev) => return Some(ev),
+                Ok(_) => {}
+                Err(_) => self.skipped += 1,
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn segment(created_ns: i128, ts: &[i128]) -> String {
+        let h = Header { schema: SCHEMA.to_string(), version: SCHEMA_VERSION, app_version: String::new(), created_ns };
+        let mut out = serde_json::to_string(&h).unwrap() + "\n";
+        for t in ts {
+            out += &format!("{{\"Note\":\"{t}\"}}\n");
+        }
+        out
+    }
+
+    #[test]
+    fn reads_gzip_zstd_and_plain_segments_in_time_order() {
+        let dir = std::env::temp_dir().join(format!("dma_reader_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        // nama file sengaja tidak urut waktu
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        gz.write_all(segment(200, &[3, 4]).as_bytes()).unwrap();
+        std::fs::write(dir.join("a.jsonl.gz"), gz.finish().unwrap()).unwrap();
+        std::fs::write(dir.join("b.jsonl.zst"), zstd::encode_all(segment(100, &[1, 2]).as_bytes(), 0).unwrap()).unwrap();
+        std::fs::write(dir.join("c.jsonl"), segment(300, &[5])).unwrap();
+        std::fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let rd = Reader::open(dir.to_str().unwrap()).unwrap();
+        assert_eq!(rd.header().created_ns, 100);
+        let notes: Vec<String> = rd.map(|ev| match ev {
+            Event::Note(n) => n,
+            _ => String::new(),
+        }).collect();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(notes, ["1", "2", "3", "4", "5"]);
+    }
+}
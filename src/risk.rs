@@ -1,15 +1,33 @@
 // ===============================
 // src/risk.rs
 // ===============================
-use chrono::Utc;
-use rand::Rng;
+use std::sync::Arc;
+use ahash::AHashMap as HashMap;
 use thiserror::Error;
 use tokio::sync::mpsc;
-use tracing::warn;
+use tracing::{info, warn};
 
-use crate::config::Limits;
-use crate::domain::{Order, Signal};
-use crate::metrics::ORDERS;
+use crate::account::Balances;
+use crate::books::{self, BookCfg};
+use crate::clock;
+use crate::cluster_guard::ClusterGuard;
+use crate::holding::{is_force_exit, HoldingGuard};
+use crate::md_anomaly::MdAnomaly;
+use crate::kill_switch::KillSwitch;
+use crate::portfolio_stop::PortfolioStop;
+use crate::chaos::{self, Point};
+use crate::margin;
+use crate::compliance::ComplianceRules;
+use crate::config::{Limits, TradingMode};
+use crate::domain::{ComplianceReject, Event, ExecReport, ExecStatus, FillDeltas, LegGroup, MultiLegOrder, Order, OrderType, RiskReject, Side, Signal};
+use crate::metrics::{strategy_label, symbol_label, BOOK_EXPOSURE, BOOK_ORDERS, COMPLIANCE_REJECTS, MULTILEG_ORDERS, ORDERS, RISK_REJECTS};
+use crate::multileg::LegBook;
+use crate::order_ids;
+use crate::sizing;
+use crate::symbol_registry::SymbolRegistry;
+use crate::scoreboard::Scoreboard;
+use crate::script_filter::{ScriptFilter, ScriptInput};
+use crate::venue_limits::VenuePositions;
 
 /// State throttle sederhana: batasi QPS berbasis interval waktu
 #[derive(Debug, Default)]
@@ -18,10 +36,179 @@ pub struct ThrottleState {
     pub counter: u32,
 }
 
-/// Placeholder posisi (bisa dikembangkan)
+/// Reservasi order yang sudah lolos risk tapi belum ada ACK child dari gateway
+/// (order yang di-drop router tidak pernah ACK -> reservasi kedaluwarsa)
+const INFLIGHT_TTL_NS: i128 = 2_000_000_000;
+
+/// Order pending (working di venue / in-flight ke router), qty bertanda dalam lot
+#[derive(Debug, Clone)]
+struct Pending {
+    symbol: String,
+    book: String,
+    qty: i64,
+    deadline: i128, // hanya untuk in-flight
+}
+
+/// Eksposur per symbol (akun) & per book untuk cek spot/margin/budget, dibangun dari ExecReport
 #[derive(Debug, Default)]
-pub struct Positions {
-    pub qty: i64,
+pub struct Exposure {
+    net: HashMap<String, i64>,                   // posisi bersih akun dari fill
+    book_net: HashMap<(String, String), i64>,    // (book, symbol) -> posisi bersih book
+    last_px: HashMap<String, i64>,               // harga terakhir (valuasi)
+    working: HashMap<String, Pending>,           // child cl_id -> sisa qty
+    inflight: HashMap<String, Pending>,          // parent cl_id -> qty belum ter-ACK
+    deltas: FillDeltas,
+}
+
+impl Exposure {
+    pub fn new(initial_net: HashMap<String, i64>) -> Self {
+        // posisi warm start belum diatribusikan ke book mana pun
+        Self { net: initial_net, ..Default::default() }
+    }
+
+    fn net(&self, symbol: &str) -> i64 {
+        self.net.get(symbol).copied().unwrap_or(0)
+    }
+
+    /// Buang reservasi in-flight yang kedaluwarsa
+    fn purge(&mut self, now: i128) {
+        self.inflight.retain(|_, p| p.deadline > now);
+    }
+
+    /// (buys, sells) pending yang memenuhi `pred`
+    fn pending(&self, pred: impl Fn(&Pending) -> bool) -> (i64, i64) {
+        self.working
+            .values()
+            .chain(self.inflight.values())
+            .filter(|p| pred(p))
+            .fold((0, 0), |(b, s), p| if p.qty > 0 { (b + p.qty, s) } else { (b, s - p.qty) })
+    }
+
+    /// Posisi akun terburuk (long, short) jika semua order pending terisi
+    fn worst(&self, symbol: &str) -> (i64, i64) {
+        let (buys, sells) = self.pending(|p| p.symbol == symbol);
+        let net = self.net(symbol);
+        (net + buys, net - sells)
+    }
+
+    /// Nilai posisi terburuk: `short_only` = hanya sisi short, selain itu max(|long|, |short|)
+    fn worst_qty(long: i64, short: i64, short_only: bool) -> i64 {
+        if short_only { (-short).max(0) } else { long.abs().max(short.abs()) }
+    }
+
+    /// Notional (quote) seluruh symbol setelah order bertanda `signed_qty` di `symbol` @ `px`
+    fn notional(&self, symbol: &str, signed_qty: i64, px: i64, short_only: bool) -> f64 {
+        let mut symbols: Vec<&String> = self.net.keys().chain(self.last_px.keys()).collect();
+        symbols.sort();
+        symbols.dedup();
+        let others: f64 = symbols
+            .into_iter()
+            .filter(|s| s.as_str() != symbol)
+            .map(|s| {
+                let (long, short) = self.worst(s);
+                margin::notional(s, Self::worst_qty(long, short, short_only), self.last_px.get(s).copied().unwrap_or(0))
+            })
+            .sum();
+        let (mut long, mut short) = self.worst(symbol);
+        if signed_qty > 0 { long += signed_qty } else { short += signed_qty }
+        others + margin::notional(symbol, Self::worst_qty(long, short, short_only), px)
+    }
+
+    /// Gross notional book (termasuk pending) setelah order bertanda `signed_qty` di `symbol` @ `px`
+    fn book_gross(&self, book: &str, symbol: &str, signed_qty: i64, px: i64) -> f64 {
+        let mut symbols: Vec<&String> = self
+            .book_net
+            .keys()
+            .filter(|(b, _)| b == book)
+            .map(|(_, s)| s)
+            .chain(self.working.values().chain(self.inflight.values()).filter(|p| p.book == book).map(|p| &p.symbol))
+            .collect();
+        symbols.sort();
+        symbols.dedup();
+        let value = |s: &str, extra: i64, px: i64| {
+            let net = self.book_net.get(&(book.to_string(), s.to_string())).copied().unwrap_or(0);
+            let (buys, sells) = self.pending(|p| p.book == book && p.symbol == s);
+            let (mut long, mut short) = (net + buys, net - sells);
+            if extra > 0 { long += extra } else { short += extra }
+            margin::notional(s, Self::worst_qty(long, short, false), px)
+        };
+        let others: f64 = symbols
+            .into_iter()
+            .filter(|s| s.as_str() != symbol)
+            .map(|s| value(s, 0, self.last_px.get(s).copied().unwrap_or(0)))
+            .sum();
+        others + value(symbol, signed_qty, px)
+    }
+
+    /// Notional searah (quote, bertanda) gabungan `symbols` jika semua order pending searah `signed_qty`
+    /// terisi, setelah order ini di `symbol` @ `px`. Posisi berlawanan di grup saling mengimbangi.
+    fn group_directional(&self, symbols: &[String], symbol: &str, signed_qty: i64, px: i64) -> f64 {
+        symbols
+            .iter()
+            .map(|s| {
+                let (long, short) = self.worst(s);
+                let mut q = if signed_qty > 0 { long } else { short };
+                let px = if s == symbol {
+                    q += signed_qty;
+                    px
+                } else {
+                    self.last_px.get(s).copied().unwrap_or(0)
+                };
+                q.signum() as f64 * margin::notional(s, q, px)
+            })
+            .sum()
+    }
+
+    fn reserve(&mut self, ord: &Order, now: i128) {
+        self.last_px.insert(ord.symbol.clone(), ord.px);
+        let p = Pending {
+            symbol: ord.symbol.clone(),
+            book: books::name(&ord.book).to_string(),
+            qty: ord.side.sign() * ord.qty,
+            deadline: now + INFLIGHT_TTL_NS,
+        };
+        self.inflight.insert(ord.cl_id.clone(), p);
+    }
+
+    /// Batalkan reservasi order yang tidak jadi dikirim (leg paket yang ditolak)
+    fn release(&mut self, cl_id: &str) {
+        self.inflight.remove(cl_id);
+    }
+
+    pub fn on_report(&mut self, er: &ExecReport) {
+        let terminal = !matches!(er.status, ExecStatus::Ack | ExecStatus::PartialFill);
+        // child pertama kali terlihat: pindahkan qty dari in-flight (parent) ke working (child)
+        if let (Some(side), true) = (er.side, er.order_qty > 0 && !self.working.contains_key(&er.cl_id)) {
+            let parent = order_ids::parent(&er.cl_id, &er.venue).unwrap_or_else(|| er.cl_id.clone());
+            if let Some(r) = self.inflight.get_mut(&parent) {
+                r.qty -= side.sign() * er.order_qty;
+                if r.qty * side.sign() <= 0 {
+                    self.inflight.remove(&parent);
+                }
+            }
+            if !terminal {
+                let p = Pending {
+                    symbol: er.symbol.clone(),
+                    book: books::name(&er.book).to_string(),
+                    qty: side.sign() * er.order_qty,
+                    deadline: i128::MAX,
+                };
+                self.working.insert(er.cl_id.clone(), p);
+            }
+        }
+        if let Some((qty, px)) = self.deltas.on_report(er) {
+            let sign = er.side.unwrap_or(Side::Buy).sign();
+            *self.net.entry(er.symbol.clone()).or_default() += sign * qty;
+            *self.book_net.entry((books::name(&er.book).to_string(), er.symbol.clone())).or_default() += sign * qty;
+            self.last_px.insert(er.symbol.clone(), px);
+            if let Some(w) = self.working.get_mut(&er.cl_id) {
+                w.qty -= sign * qty;
+            }
+        }
+        if terminal {
+            self.working.remove(&er.cl_id);
+        }
+    }
 }
 
 #[derive(Debug, Error)]
@@ -32,32 +219,208 @@ pub enum RiskError {
     PriceBand,
     #[error("Throttle exceeded")]
     Throttle,
+    #[error("Kill switch engaged")]
+    KillSwitch,
+    #[error("Portfolio stop tripped: only exits allowed until re-armed")]
+    PortfolioStop,
+    #[error("Symbol not trading (status {0})")]
+    Halted(String),
+    #[error("No cross rate for quote asset {0} (add its USD pair to SYMBOLS or FX_RATES)")]
+    NoCrossRate(String),
+    #[error("Order size rounds to zero lots")]
+    Size,
+    #[error("Insufficient {asset} balance: need {need}, free {free}")]
+    Balance { asset: String, need: f64, free: f64 },
+    #[error("Spot mode: sell {qty} exceeds holdings {available}")]
+    ShortNotAllowed { available: i64, qty: i64 },
+    #[error("Margin mode: short notional {short:.2} exceeds leverage cap {cap:.2}")]
+    Leverage { short: f64, cap: f64 },
+    #[error("Margin usage {usage:.1}% exceeds {max:.1}%")]
+    MarginUsage { usage: f64, max: f64 },
+    #[error("Book {book}: gross notional {used:.2} exceeds budget {cap:.2}")]
+    BookBudget { book: String, used: f64, cap: f64 },
+    #[error("Venue position cap: {qty} lots exceeds headroom {headroom} across venues ({symbol})")]
+    VenuePosition { symbol: String, qty: i64, headroom: i64 },
+    #[error("Strategy {0} disabled by scoreboard (re-enable via admin)")]
+    StrategyDisabled(String),
+    #[error("Correlation group {group}: directional notional {used:.2} exceeds cap {cap:.2}")]
+    Correlation { group: String, used: f64, cap: f64 },
+    #[error("Inventory band {symbol}: position {after} would leave hard band {hard} around target {target}")]
+    InventoryBand { symbol: String, after: i64, target: i64, hard: i64 },
+    #[error("Overtrading: strategy {strategy} muted on {symbol} for {remaining_ms} ms")]
+    Overtrading { strategy: String, symbol: String, remaining_ms: i64 },
+    #[error("Market data anomaly on {symbol} ({kind}): trading paused")]
+    MdAnomaly { symbol: String, kind: String },
+    #[error("Holding period: {strategy} has held {symbol} for {held_secs:.0}s (max {max_secs:.0}s), only exits allowed")]
+    HoldingPeriod { strategy: String, symbol: String, held_secs: f64, max_secs: f64 },
+    #[error("Rejected by risk script: {0}")]
+    Script(String),
+    #[error("Compliance: {} ({})", .0.rule, .0.symbol)]
+    Compliance(Box<ComplianceReject>),
+}
+
+impl RiskError {
+    /// Label `reason` untuk risk_rejects_total / Event::RiskReject
+    pub fn reason(&self) -> &'static str {
+        match self {
+            RiskError::Notional => "notional",
+            RiskError::PriceBand => "price_band",
+            RiskError::Throttle => "throttle",
+            RiskError::KillSwitch => "kill_switch",
+            RiskError::PortfolioStop => "portfolio_stop",
+            RiskError::Halted(_) => "halted",
+            RiskError::NoCrossRate(_) => "no_cross_rate",
+            RiskError::Size => "size",
+            RiskError::Balance { .. } => "balance",
+            RiskError::ShortNotAllowed { .. } => "short_not_allowed",
+            RiskError::Leverage { .. } => "leverage",
+            RiskError::MarginUsage { .. } => "margin_usage",
+            RiskError::BookBudget { .. } => "book_budget",
+            RiskError::VenuePosition { .. } => "venue_position",
+            RiskError::StrategyDisabled(_) => "strategy_disabled",
+            RiskError::Correlation { .. } => "correlation",
+            RiskError::InventoryBand { .. } => "inventory_band",
+            RiskError::Overtrading { .. } => "overtrading",
+            RiskError::MdAnomaly { .. } => "md_anomaly",
+            RiskError::HoldingPeriod { .. } => "holding_period",
+            RiskError::Script(_) => "script",
+            RiskError::Compliance(_) => "compliance",
+        }
+    }
+}
+
+/// Hitung penolakan (metric) dan catat ke recorder; compliance punya event sendiri (Event::Compliance)
+fn record_reject(ctx: &RiskCtx, sig: &Signal, e: &RiskError) {
+    let reason = e.reason();
+    RISK_REJECTS.with_label_values(&[reason, symbol_label(&sig.symbol), strategy_label(&sig.strategy)]).inc();
+    if matches!(e, RiskError::Compliance(_)) {
+        return;
+    }
+    if let Some(tx) = &ctx.audit_tx {
+        audit(tx, Event::RiskReject(RiskReject {
+            ts_ns: ctx.clock.now_ns(),
+            reason: reason.to_string(),
+            symbol: sig.symbol.clone(),
+            strategy: sig.strategy.clone(),
+            book: books::name(&sig.book).to_string(),
+            side: sig.side,
+            px: sig.px,
+            qty: sig.qty,
+            detail: e.to_string(),
+        }));
+    }
+}
+
+/// Kirim event audit ke recorder tanpa menunggu; antrean penuh -> dead-letter
+fn audit(tx: &mpsc::Sender<Event>, ev: Event) {
+    if let Err(e) = tx.try_send(ev) {
+        let reason = if matches!(e, mpsc::error::TrySendError::Full(_)) { "full" } else { "closed" };
+        let ev = e.into_inner();
+        crate::dead_letter::record(ev.kind(), "recorder", reason, &ev);
+    }
+}
+
+/// Dependensi task risk
+#[derive(Clone)]
+pub struct RiskCtx {
+    pub limits: Limits,
+    pub balances: Option<Balances>,         // saldo akun (polling Binance)
+    pub initial_net: HashMap<String, i64>,  // posisi awal (warm start)
+    pub symbols: SymbolRegistry,            // status exchangeInfo
+    pub compliance: Option<ComplianceRules>,
+    pub books: BookCfg,                     // budget & throttle per book
+    pub kill: KillSwitch,                   // panic button: tolak semua Signal saat aktif
+    pub portfolio_stop: PortfolioStop,      // drawdown portofolio: hanya force-exit sampai re-arm
+    pub venue_pos: VenuePositions,          // posisi per venue (VENUE_MAX_POSITION)
+    pub scoreboard: Scoreboard,             // strategi yang di-auto-disable
+    pub cluster: ClusterGuard,              // mute strategi yang burst order searah
+    pub md_anomaly: MdAnomaly,              // pola quote mencurigakan (MD_ANOMALY=pause)
+    pub holding: HoldingGuard,              // umur posisi per strategi (HOLD_MAX_SECS)
+    pub script: Option<ScriptFilter>,       // filter pre-trade rhai (RISK_SCRIPT_FILE)
+    pub audit_tx: Option<mpsc::Sender<Event>>, // recorder (signal, order diterima, penolakan risk & compliance)
+    pub legs: LegBook,                      // view paket multi-leg
+    pub leg_tx: mpsc::Sender<LegGroup>,     // paket multi-leg yang lolos -> router
+    pub clock: clock::SharedClock,          // sumber waktu throttle / GTT / cl_id (MockClock di test)
 }
 
 /// Pre-trade checks -> jika lolos, konversi Signal menjadi Order
-fn check(
-    sig: &Signal,
-    lim: &Limits,
-    _pos: &Positions,
-    thr: &mut ThrottleState,
-) -> Result<Order, RiskError> {
-    // 1) Notional limit (px * qty)
-    let notional = sig.px.saturating_mul(sig.qty);
-    if notional > lim.max_notional {
-        return Err(RiskError::Notional);
-    }
-
-    // 2) Price band
+fn check(sig: &Signal, ctx: &RiskCtx, exp: &mut Exposure, thr: &mut HashMap<String, ThrottleState>) -> Result<Order, RiskError> {
+    let lim = &ctx.limits;
+    let book = books::name(&sig.book);
+    let now: i128 = ctx.clock.now_ns();
+    // force-exit posisi kedaluwarsa (holding.rs): qty apa adanya, tidak diblok scoreboard / overtrading
+    let force_exit = is_force_exit(sig);
+
+    // 0) Kill switch, portfolio stop (force-exit tetap lolos), strategi yang di-disable scoreboard, status symbol dari exchangeInfo (BREAK / HALT / ...),
+    //    anomali quote (MD_ANOMALY=pause), lalu umur posisi strategi (HOLD_MAX_SECS)
+    if ctx.kill.engaged() {
+        return Err(RiskError::KillSwitch);
+    }
+    if !force_exit && ctx.portfolio_stop.tripped() {
+        return Err(RiskError::PortfolioStop);
+    }
+    if !force_exit && ctx.scoreboard.is_disabled(&sig.strategy) {
+        return Err(RiskError::StrategyDisabled(sig.strategy.clone()));
+    }
+    if !ctx.symbols.tradable(&sig.symbol) {
+        return Err(RiskError::Halted(ctx.symbols.status(&sig.symbol).unwrap_or_default()));
+    }
+    if let Some(kind) = ctx.md_anomaly.paused(&sig.symbol, now) {
+        return Err(RiskError::MdAnomaly { symbol: sig.symbol.clone(), kind: kind.to_string() });
+    }
+    if let Some((held_secs, max_secs)) = ctx.holding.blocks(&sig.strategy, &sig.symbol, sig.side, now) {
+        return Err(RiskError::HoldingPeriod { strategy: sig.strategy.clone(), symbol: sig.symbol.clone(), held_secs, max_secs });
+    }
+
+    // notional risk dihitung dalam USD: quote tanpa kurs tidak bisa dinilai
+    if let Some(asset) = crate::fx::missing_quote(&sig.symbol) {
+        return Err(RiskError::NoCrossRate(asset));
+    }
+
+    // 1) Sizing: notional quote (Signal / ORDER_QUOTE_NOTIONAL) -> lot, diskalakan alokasi strategi
+    let quote_notional = sig.quote_notional.or((lim.order_quote_notional > 0.0).then_some(lim.order_quote_notional));
+    let scale = sizing::strategy_scale(&sig.strategy);
+    let qty = match quote_notional {
+        _ if force_exit => sig.qty,
+        Some(q) => sizing::lots_for_notional(&sig.symbol, sig.px, q * scale),
+        None => (sig.qty as f64 * scale + 1e-9).floor() as i64,
+    };
+    if qty <= 0 {
+        return Err(RiskError::Size);
+    }
+
+    // 1b) Filter script (boleh menolak atau mengubah qty)
+    let qty = match &ctx.script {
+        Some(script) => {
+            let input = ScriptInput { qty, position: exp.net(&sig.symbol), unrealized_pnl: ctx.scoreboard.unrealized(&sig.strategy) };
+            script.check(sig, input).map_err(RiskError::Script)?
+        }
+        None => qty,
+    };
+
+    // 2) Compliance (deny-list / hard cap organisasi) sebelum limit risk
+    if let Some(rules) = &ctx.compliance {
+        rules.check(now, &sig.symbol, &sig.strategy, sig.side, sig.px, qty).map_err(RiskError::Compliance)?;
+    }
+
+    // 3) Notional limit (px * qty); overflow i64 selalu ditolak (juga saat MAX_NOTIONAL = i64::MAX)
+    match sig.px.checked_mul(qty) {
+        Some(notional) if notional <= lim.max_notional => {}
+        _ => return Err(RiskError::Notional),
+    }
+
+    // 4) Price band
     if sig.px < lim.px_min || sig.px > lim.px_max {
         return Err(RiskError::PriceBand);
     }
 
-    // 3) Throttle (contoh: jika <20ms dari last_ns, hitung counter; jika >max_qps, reject)
-    let now: i128 = Utc::now().timestamp_nanos_opt().unwrap_or(0) as i128;
+    // 5) Throttle (contoh: jika <20ms dari last_ns, hitung counter; jika >max_qps, reject)
+    //    bucket per book (allocator): burst satu book tidak memakan kuota book lain
+    let thr = thr.entry(book.to_string()).or_default();
     if now - thr.last_ns < 20_000_000i128 {
         // 20 ms
         thr.counter += 1;
-        if thr.counter > lim.max_qps {
+        if thr.counter > ctx.books.qps(book, lim.max_qps) {
             return Err(RiskError::Throttle);
         }
     } else {
@@ -65,34 +428,393 @@ fn check(
         thr.last_ns = now;
     }
 
-    // 4) Build order (cl_id unik)
-    let cl_id = format!("CL-{}-{}", now, rand::thread_rng().gen::<u32>());
+    // 6) Saldo akun (jika polling saldo aktif)
+    if let Some(b) = &ctx.balances {
+        b.check(&sig.symbol, sig.side, sig.px, qty).map_err(|(asset, need, free)| RiskError::Balance { asset, need, free })?;
+    }
+
+    // 7) Spot vs margin: jual yang membawa posisi (termasuk jual yang masih pending) ke bawah nol
+    exp.purge(now);
+    if matches!(sig.side, Side::Sell) {
+        let available = exp.worst(&sig.symbol).1;
+        if available - qty < 0 {
+            match lim.trading_mode {
+                TradingMode::Spot => return Err(RiskError::ShortNotAllowed { available: available.max(0), qty }),
+                TradingMode::Margin if lim.margin_equity > 0.0 => {
+                    let short = exp.notional(&sig.symbol, -qty, sig.px, true);
+                    let cap = lim.max_leverage * lim.margin_equity;
+                    if short > cap {
+                        return Err(RiskError::Leverage { short, cap });
+                    }
+                }
+                TradingMode::Margin => {}
+            }
+        }
+    }
+
+    // 8) Margin usage: initial margin seluruh posisi (termasuk order pending) setelah order ini
+    if lim.trading_mode == TradingMode::Margin && lim.max_margin_usage_pct > 0.0 {
+        let signed = sig.side.sign() * qty;
+        let (long, short) = exp.worst(&sig.symbol);
+        // order yang mengurangi posisi selalu boleh
+        let increases = if signed > 0 { long >= 0 } else { short <= 0 };
+        let usage = margin::usage_pct(margin::initial_margin(exp.notional(&sig.symbol, signed, sig.px, false)));
+        if increases && usage > lim.max_margin_usage_pct {
+            return Err(RiskError::MarginUsage { usage, max: lim.max_margin_usage_pct });
+        }
+    }
+
+    // 9) Budget book: gross notional book (termasuk pending) setelah order ini
+    if let Some(cap) = ctx.books.budget(book) {
+        let used = exp.book_gross(book, &sig.symbol, sig.side.sign() * qty, sig.px);
+        if used > cap {
+            return Err(RiskError::BookBudget { book: book.to_string(), used, cap });
+        }
+    }
+
+    // 10) Batas posisi per venue: qty harus muat di headroom gabungan semua venue
+    if let Some(headroom) = ctx.venue_pos.total_headroom(&sig.symbol, sig.side) {
+        if qty > headroom {
+            return Err(RiskError::VenuePosition { symbol: sig.symbol.clone(), qty, headroom });
+        }
+    }
+
+    // 11) Grup korelasi: eksposur searah gabungan (BTC+ETH long bersama) tidak boleh melewati cap grup
+    for g in lim.correlation_groups.iter().filter(|g| g.symbols.contains(&sig.symbol)) {
+        let signed = sig.side.sign() * qty;
+        let used = exp.group_directional(&g.symbols, &sig.symbol, signed, sig.px);
+        if used * signed.signum() as f64 > g.max_notional {
+            return Err(RiskError::Correlation { group: g.name.clone(), used: used.abs(), cap: g.max_notional });
+        }
+    }
+
+    // 12) Band inventory: di luar band hard hanya order yang mengurangi eksposur yang lolos
+    if let Some(band) = lim.inv_bands.get(&sig.symbol) {
+        let (long, short) = exp.worst(&sig.symbol);
+        let before = if matches!(sig.side, Side::Buy) { long } else { short };
+        let after = before + sig.side.sign() * qty;
+        if band.blocks(before, after) {
+            return Err(RiskError::InventoryBand { symbol: sig.symbol.clone(), after, target: band.target, hard: band.hard });
+        }
+    }
+
+    // 13) Burst order searah (strategi lepas kendali): dicatat paling akhir, setelah semua cek lain lolos
+    if let (false, Err(until)) = (force_exit, ctx.cluster.admit(&sig.strategy, &sig.symbol, sig.side, now)) {
+        let remaining_ms = ((until - now) / 1_000_000) as i64;
+        return Err(RiskError::Overtrading { strategy: sig.strategy.clone(), symbol: sig.symbol.clone(), remaining_ms });
+    }
+
+    // 14) Build order (cl_id unik); stop-limit (tag) = order protektif di exchange, tanpa GTT
+    let cl_id = order_ids::next("C", now);
+    let order_type = OrderType::from_tags(&sig.tags);
     Ok(Order {
         cl_id,
         ts_ns: sig.ts_ns,
         symbol: sig.symbol.clone(),
         side: sig.side,
         px: sig.px,
-        qty: sig.qty,
+        qty,
+        strategy: sig.strategy.clone(),
+        tags: sig.tags.clone(),
+        book: book.to_string(),
+        expire_at_ns: (lim.order_ttl_ms > 0 && order_type == OrderType::Limit).then(|| now + lim.order_ttl_ms as i128 * 1_000_000),
+        order_type,
     })
 }
 
-/// Task risk: menerima Signal, menjalankan check(), lalu mengirim Order valid
+/// Paket multi-leg: leg dicek berurutan dan langsung di-reserve (leg berikutnya melihat eksposur leg
+/// sebelumnya); satu leg gagal -> reservasi leg sebelumnya dibatalkan dan seluruh paket ditolak
+fn check_multileg(
+    m: &MultiLegOrder,
+    ctx: &RiskCtx,
+    exp: &mut Exposure,
+    thr: &mut HashMap<String, ThrottleState>,
+) -> Result<LegGroup, (usize, RiskError)> {
+    let now = ctx.clock.now_ns();
+    let mut orders: Vec<Order> = Vec::with_capacity(m.legs.len());
+    for i in 0..m.legs.len() {
+        match check(&m.leg_signal(i), ctx, exp, thr) {
+            Ok(ord) => {
+                exp.reserve(&ord, now);
+                orders.push(ord);
+            }
+            Err(e) => {
+                for o in &orders {
+                    exp.release(&o.cl_id);
+                }
+                return Err((i, e));
+            }
+        }
+    }
+    Ok(LegGroup { id: m.id.clone(), orders })
+}
+
+/// Task risk: menerima Signal, menjalankan check(), lalu mengirim Order valid.
+/// ExecReport (fan-out) dipakai untuk melacak eksposur spot/margin.
 pub async fn run(
     mut sig_rx: mpsc::Receiver<Signal>,
+    mut mleg_rx: mpsc::Receiver<MultiLegOrder>,
+    mut exec_rx: mpsc::Receiver<Arc<ExecReport>>,
     ord_tx: mpsc::Sender<Order>,
-    lim: Limits,
+    mut ctx: RiskCtx,
 ) {
-    let pos = Positions::default();
-    let mut thr = ThrottleState::default();
+    let lim = &ctx.limits;
+    info!(
+        trading_mode = lim.trading_mode.as_str(),
+        max_leverage = lim.max_leverage,
+        margin_equity = lim.margin_equity,
+        compliance = ctx.compliance.is_some(),
+        "risk: trading mode"
+    );
+    let mut exp = Exposure::new(std::mem::take(&mut ctx.initial_net));
+    let mut thr: HashMap<String, ThrottleState> = HashMap::new();
 
-    while let Some(sig) = sig_rx.recv().await {
-        match check(&sig, &lim, &pos, &mut thr) {
-            Ok(ord) => {
-                let _ = ord_tx.send(ord).await;
-                ORDERS.inc();
+    loop {
+        tokio::select! {
+            // ExecReport dulu agar eksposur selalu terbaru sebelum cek Signal
+            biased;
+            Some(er) = exec_rx.recv() => exp.on_report(&er),
+            Some(mut m) = mleg_rx.recv() => {
+                if m.id.is_empty() {
+                    m.id = order_ids::next("M", ctx.clock.now_ns());
+                }
+                if m.ts_ns == 0 {
+                    m.ts_ns = ctx.clock.now_ns();
+                }
+                if m.legs.len() < 2 || m.legs.iter().any(|l| l.qty <= 0) {
+                    warn!(id = %m.id, legs = m.legs.len(), "multi-leg rejected: need >= 2 legs with qty > 0");
+                    MULTILEG_ORDERS.with_label_values(&["rejected"]).inc();
+                    continue;
+                }
+                match check_multileg(&m, &ctx, &mut exp, &mut thr) {
+                    Ok(group) => {
+                        info!(id = %m.id, legs = group.orders.len(), strategy = %m.strategy, "multi-leg accepted");
+                        ctx.legs.register(&m);
+                        if let Some(tx) = &ctx.audit_tx {
+                            group.orders.iter().for_each(|o| audit(tx, Event::Ord(o.clone())));
+                        }
+                        let _ = ctx.leg_tx.send(group).await;
+                        ORDERS.inc_by(m.legs.len() as u64);
+                        MULTILEG_ORDERS.with_label_values(&["accepted"]).inc();
+                    }
+                    Err((leg, e)) => {
+                        warn!(id = %m.id, leg, ?e, "multi-leg rejected by risk");
+                        record_reject(&ctx, &m.leg_signal(leg), &e);
+                        MULTILEG_ORDERS.with_label_values(&["rejected"]).inc();
+                    }
+                }
+            }
+            sig = sig_rx.recv() => {
+                let Some(sig) = sig else { break };
+                chaos::delay(Point::Signal).await;
+                if chaos::drop(Point::Signal) {
+                    continue;
+                }
+                if let Some(tx) = &ctx.audit_tx {
+                    audit(tx, Event::Sig(sig.clone()));
+                }
+                let book = books::name(&sig.book).to_string();
+                let res = check(&sig, &ctx, &mut exp, &mut thr);
+                if let Err(e) = &res {
+                    record_reject(&ctx, &sig, e);
+                }
+                match res {
+                    Ok(ord) => {
+                        exp.reserve(&ord, ctx.clock.now_ns());
+                        BOOK_EXPOSURE.with_label_values(&[&book]).set(exp.book_gross(&book, &ord.symbol, 0, ord.px));
+                        if let Some(tx) = &ctx.audit_tx {
+                            audit(tx, Event::Ord(ord.clone()));
+                        }
+                        let _ = ord_tx.send(ord).await;
+                        ORDERS.inc();
+                        BOOK_ORDERS.with_label_values(&[&book, "accepted"]).inc();
+                        continue;
+                    }
+                    Err(RiskError::Compliance(rej)) => {
+                        warn!(rule = %rej.rule, symbol = %rej.symbol, strategy = %rej.strategy, limit = rej.limit, actual = rej.actual, "compliance rejected");
+                        COMPLIANCE_REJECTS.with_label_values(&[&rej.rule]).inc();
+                        if let Some(tx) = &ctx.audit_tx {
+                            audit(tx, Event::Compliance(*rej));
+                        }
+                    }
+                    Err(e) => warn!(?e, "risk rejected"),
+                }
+                BOOK_ORDERS.with_label_values(&[&book, "rejected"]).inc();
             }
-            Err(e) => warn!(?e, "risk rejected"),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+
+    const T0: i128 = 1_700_000_000_000_000_000;
+
+    fn limits() -> Limits {
+        Limits {
+            max_notional: 1_000_000,
+            px_min: 100,
+            px_max: 100_000,
+            max_qps: 2,
+            order_ttl_ms: 0,
+            order_quote_notional: 0.0,
+            trading_mode: TradingMode::Spot,
+            max_leverage: 0.0,
+            margin_equity: 0.0,
+            maint_margin_rate: 0.0,
+            max_margin_usage_pct: 0.0,
+            venue_max_position: HashMap::new(),
+            correlation_groups: Vec::new(),
+            inv_bands: HashMap::new(),
+        }
+    }
+
+    fn ctx(limits: Limits, clock: Arc<MockClock>) -> RiskCtx {
+        RiskCtx {
+            limits,
+            balances: None,
+            initial_net: HashMap::new(),
+            symbols: SymbolRegistry::default(),
+            compliance: None,
+            books: BookCfg::default(),
+            kill: KillSwitch::default(),
+            portfolio_stop: PortfolioStop::default(),
+            venue_pos: VenuePositions::new(HashMap::new(), vec!["A".to_string()]),
+            scoreboard: Scoreboard::default(),
+            cluster: ClusterGuard::default(),
+            md_anomaly: MdAnomaly::default(),
+            holding: HoldingGuard::default(),
+            script: None,
+            audit_tx: None,
+            legs: LegBook::default(),
+            leg_tx: mpsc::channel(1).0,
+            clock,
+        }
+    }
+
+    fn buy(px: i64, qty: i64) -> Signal {
+        Signal {
+            ts_ns: T0,
+            symbol: "BTCUSDT".to_string(),
+            side: Side::Buy,
+            px,
+            qty,
+            strategy: "test".to_string(),
+            tags: Vec::new(),
+            book: String::new(),
+            quote_notional: None,
+        }
+    }
+
+    struct Harness {
+        clock: Arc<MockClock>,
+        ctx: RiskCtx,
+        exp: Exposure,
+        thr: HashMap<String, ThrottleState>,
+    }
+
+    impl Harness {
+        fn new(limits: Limits) -> Self {
+            let clock = MockClock::at_ns(T0);
+            Self { ctx: ctx(limits, clock.clone()), clock, exp: Exposure::default(), thr: HashMap::new() }
+        }
+
+        fn check(&mut self, sig: &Signal) -> Result<Order, RiskError> {
+            check(sig, &self.ctx, &mut self.exp, &mut self.thr)
+        }
+    }
+
+    #[test]
+    fn throttle_rejects_burst_above_qps_and_resets_after_window() {
+        let mut h = Harness::new(limits());
+        // order pertama membuka window 20 ms, lalu max_qps (2) order tambahan di dalam window
+        for _ in 0..3 {
+            assert!(h.check(&buy(10_000, 1)).is_ok());
+            h.clock.advance_ms(1);
+        }
+        assert!(matches!(h.check(&buy(10_000, 1)), Err(RiskError::Throttle)));
+        h.clock.advance_ms(20);
+        assert!(h.check(&buy(10_000, 1)).is_ok());
+    }
+
+    #[test]
+    fn throttle_is_per_book() {
+        let mut h = Harness::new(Limits { max_qps: 0, ..limits() });
+        assert!(h.check(&buy(10_000, 1)).is_ok());
+        assert!(matches!(h.check(&buy(10_000, 1)), Err(RiskError::Throttle)));
+        let other = Signal { book: "beta".to_string(), ..buy(10_000, 1) };
+        assert!(h.check(&other).is_ok());
+    }
+
+    #[test]
+    fn price_band_is_inclusive() {
+        let mut h = Harness::new(Limits { max_qps: 100, ..limits() });
+        assert!(h.check(&buy(100, 1)).is_ok());
+        assert!(h.check(&buy(100_000, 1)).is_ok());
+        assert!(matches!(h.check(&buy(99, 1)), Err(RiskError::PriceBand)));
+        assert!(matches!(h.check(&buy(100_001, 1)), Err(RiskError::PriceBand)));
+    }
+
+    #[test]
+    fn notional_limit_is_px_times_qty() {
+        let mut h = Harness::new(Limits { max_qps: 100, ..limits() });
+        assert!(h.check(&buy(10_000, 100)).is_ok());
+        assert!(matches!(h.check(&buy(10_001, 100)), Err(RiskError::Notional)));
+        assert!(matches!(h.check(&buy(10_000, 0)), Err(RiskError::Size)));
+    }
+
+    #[test]
+    fn notional_overflow_is_rejected() {
+        let unlimited = Limits { max_notional: i64::MAX, px_max: i64::MAX, max_qps: 100, ..limits() };
+        let mut h = Harness::new(unlimited);
+        assert!(matches!(h.check(&buy(i64::MAX / 2, 3)), Err(RiskError::Notional)));
+        assert!(matches!(h.check(&buy(i64::MAX, i64::MAX)), Err(RiskError::Notional)));
+        assert!(h.check(&buy(i64::MAX / 2, 2)).is_ok());
+    }
+
+    #[test]
+    fn gtt_expiry_follows_injected_clock() {
+        let mut h = Harness::new(Limits { order_ttl_ms: 500, ..limits() });
+        h.clock.set_ns(T0 + 42);
+        let ord = h.check(&buy(10_000, 1)).unwrap();
+        assert_eq!(ord.expire_at_ns, Some(T0 + 42 + 500_000_000));
+        assert!(ord.cl_id.starts_with('C') && ord.cl_id.len() <= order_ids::MAX_LEN);
+    }
+
+    #[test]
+    fn cl_ids_are_unique_within_the_same_ms() {
+        let mut h = Harness::new(Limits { max_qps: 10_000, ..limits() });
+        let ids: std::collections::HashSet<String> = (0..5_000).map(|_| h.check(&buy(10_000, 1)).unwrap().cl_id).collect();
+        assert_eq!(ids.len(), 5_000);
+    }
+
+    #[test]
+    fn inventory_band_blocks_only_exposure_increasing_orders() {
+        let band = crate::config::InvBand { target: 0, soft: 50, hard: 100 };
+        let mut h = Harness::new(Limits { max_qps: 100, inv_bands: [("BTCUSDT".to_string(), band)].into_iter().collect(), ..limits() });
+        h.exp = Exposure::new([("BTCUSDT".to_string(), 100)].into_iter().collect());
+        assert!(matches!(h.check(&buy(10_000, 1)), Err(RiskError::InventoryBand { after: 101, .. })));
+        assert!(h.check(&Signal { side: Side::Sell, ..buy(10_000, 1) }).is_ok());
+        assert_eq!(band.pressure(40), 1.0);
+        assert_eq!(band.pressure(100), 3.0);
+    }
+
+    #[test]
+    fn unrouted_remainder_releases_parent_reservation() {
+        let mut h = Harness::new(limits());
+        let ord = h.check(&buy(10_000, 3)).unwrap();
+        h.exp.reserve(&ord, T0);
+        let report = |cl_id: String, venue: &str, status: ExecStatus, order_qty: i64| ExecReport {
+            cl_id, symbol: ord.symbol.clone(), status, filled_qty: 0, avg_px: 0, ts_ns: T0, venue: venue.to_string(),
+            side: Some(Side::Buy), order_qty, strategy: ord.strategy.clone(), tags: vec![], book: String::new(), exch_ts_ns: 0,
+        };
+        h.exp.on_report(&report(format!("{}-A", ord.cl_id), "A", ExecStatus::Ack, 2));
+        assert_eq!(h.exp.inflight[&ord.cl_id].qty, 1);
+        // router: sisa 1 lot tidak dirutekan -> Rejected("UNROUTED") untuk parent
+        h.exp.on_report(&report(ord.cl_id.clone(), "router", ExecStatus::Rejected("UNROUTED".into()), 1));
+        assert!(h.exp.inflight.is_empty());
+        assert_eq!(h.exp.pending(|_| true), (2, 0));
+    }
+}
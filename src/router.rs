@@ -1,33 +1,134 @@
 // ===============================
 // src/router.rs (SOR + inventory bias)
 // ===============================
-use ahash::AHashMap as HashMap;
+use ahash::{AHashMap as HashMap, AHashSet as HashSet};
 use tokio::sync::{mpsc, watch};
-use crate::domain::{InvSnapshot, Order, VenueOrder};
-use crate::metrics::VENUE_SCORE;
+use crate::config::InvBand;
+use crate::domain::{ExecReport, ExecStatus, InvSnapshot, LegGroup, Order, OrderType, Side, VenueOrder};
+use crate::feed_health::FeedHealth;
+use crate::metrics::{EXECS, MULTILEG_ORDERS, ORDERS_PEGGED, ROUTER_REJECTS, VENUE_BACKPRESSURE, VENUE_POSITION_CAPPED, VENUE_SCORE};
+use crate::venue_health::VenueHealth;
+use crate::oms::WorkingOrders;
+use crate::venue_limits::VenuePositions;
 
 #[derive(Debug, Clone)]
-pub struct VenueCfg { pub fee_bps: i32, pub est_latency_ms: u32, pub liq_score: u32 }
+pub struct VenueCfg {
+    pub fee_bps: i32,
+    pub est_latency_ms: u32,
+    pub liq_score: u32,
+    /// symbol yang diperdagangkan venue (None = semua)
+    pub symbols: Option<HashSet<String>>,
+}
+
+impl VenueCfg {
+    pub fn trades(&self, symbol: &str) -> bool {
+        self.symbols.as_ref().is_none_or(|s| s.contains(symbol))
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct RouterCfg {
     pub venues: HashMap<String, VenueCfg>,
     pub top_n: usize,
     pub min_child_qty: i64,
-    pub inv_target: i64,
+    pub inv_bands: HashMap<String, InvBand>, // band inventory per symbol (INV_BANDS), tanpa band = target 0
     pub inv_bias_weight: i64,
 }
 
 impl Default for RouterCfg {
     fn default() -> Self {
         let mut venues = HashMap::new();
-        venues.insert("A".into(), VenueCfg { fee_bps: 5, est_latency_ms: 3, liq_score: 70 });
-        venues.insert("B".into(), VenueCfg { fee_bps: 7, est_latency_ms: 2, liq_score: 50 });
-        venues.insert("C".into(), VenueCfg { fee_bps: 2, est_latency_ms: 6, liq_score: 90 });
-        Self { venues, top_n: 2, min_child_qty: 2, inv_target: 0, inv_bias_weight: 5 }
+        venues.insert("A".into(), VenueCfg { fee_bps: 5, est_latency_ms: 3, liq_score: 70, symbols: None });
+        venues.insert("B".into(), VenueCfg { fee_bps: 7, est_latency_ms: 2, liq_score: 50, symbols: None });
+        venues.insert("C".into(), VenueCfg { fee_bps: 2, est_latency_ms: 6, liq_score: 90, symbols: None });
+        Self { venues, top_n: 2, min_child_qty: 2, inv_bands: HashMap::new(), inv_bias_weight: 5 }
+    }
+}
+
+impl RouterCfg {
+    /// Terapkan whitelist VENUE_SYMBOLS (venue tak dikenal dilog & diabaikan)
+    pub fn set_symbols(&mut self, whitelists: &HashMap<String, HashSet<String>>) {
+        for (venue, syms) in whitelists {
+            match self.venues.get_mut(venue) {
+                Some(v) => v.symbols = Some(syms.clone()),
+                None => tracing::warn!(%venue, "VENUE_SYMBOLS: unknown venue, ignored"),
+            }
+        }
+    }
+
+    /// Validasi startup: batasi `venue` ke symbol yang terdaftar & TRADING di exchangeInfo-nya
+    #[cfg(feature = "binance")]
+    pub fn restrict_to_listed(&mut self, venue: &str, listed: &HashSet<String>) {
+        let Some(v) = self.venues.get_mut(venue) else { return };
+        let wanted: Vec<String> = match &v.symbols {
+            Some(s) => s.iter().cloned().collect(),
+            None => listed.iter().cloned().collect(),
+        };
+        for sym in wanted.iter().filter(|s| !listed.contains(*s)) {
+            tracing::warn!(%venue, symbol = %sym, "symbol not tradable on venue, removed from its whitelist");
+        }
+        v.symbols = Some(wanted.into_iter().filter(|s| listed.contains(s)).collect());
+    }
+
+    /// Ada venue yang memperdagangkan `symbol`
+    pub fn lists(&self, symbol: &str) -> bool {
+        self.venues.values().any(|v| v.trades(symbol))
     }
 }
 
+/// Pegging harga child ke quote terkini saat routing (pasar bisa bergerak sejak Signal dibuat)
+///   PEG_MODE=off|passive|aggressive  passive: buy@bid, sell@ask; aggressive: buy@ask, sell@bid
+///   PEG_OFFSET_TICKS=0               + = lebih agresif (buy naik / sell turun), dalam unit harga ×100
+///   PEG_MAX_SLIP_TICKS=0             batas harga lebih buruk dari harga Signal (0 = hanya improvement, -1 = tanpa batas)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PegMode { #[default] Off, Passive, Aggressive }
+
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct PegCfg { pub mode: PegMode, pub offset_ticks: i64, pub max_slip_ticks: i64 }
+
+impl PegCfg {
+    pub fn from_env() -> Self {
+        let mode = match std::env::var("PEG_MODE").unwrap_or_default().to_ascii_lowercase().as_str() {
+            "passive" => PegMode::Passive,
+            "aggressive" => PegMode::Aggressive,
+            _ => PegMode::Off,
+        };
+        let num = |k: &str, d: i64| std::env::var(k).ok().and_then(|s| s.parse().ok()).unwrap_or(d);
+        Self { mode, offset_ticks: num("PEG_OFFSET_TICKS", 0), max_slip_ticks: num("PEG_MAX_SLIP_TICKS", 0) }
+    }
+
+    /// Harga child setelah peg ke (bid, ask); None = peg nonaktif
+    pub fn price(&self, side: Side, px: i64, bid: i64, ask: i64) -> Option<i64> {
+        let (near, far) = match side { Side::Buy => (bid, ask), Side::Sell => (ask, bid) };
+        let base = match self.mode {
+            PegMode::Off => return None,
+            PegMode::Passive => near,
+            PegMode::Aggressive => far,
+        };
+        let pegged = base + side.sign() * self.offset_ticks;
+        if self.max_slip_ticks < 0 {
+            return Some(pegged);
+        }
+        // harga tidak boleh lebih buruk dari Signal + slip
+        Some(match side {
+            Side::Buy => pegged.min(px + self.max_slip_ticks),
+            Side::Sell => pegged.max(px - self.max_slip_ticks),
+        })
+    }
+}
+
+/// Dependensi task router
+#[derive(Clone)]
+pub struct RouterCtx {
+    pub health: VenueHealth,           // safe mode per venue
+    pub venue_pos: VenuePositions,     // batas posisi per venue
+    pub open: WorkingOrders,           // child terbuka (gauge open_orders / working_notional)
+    pub feed: FeedHealth,              // MD terakhir per symbol
+    pub exec_tx: mpsc::Sender<ExecReport>, // reject order yang tidak bisa dirutekan
+    pub peg: PegCfg,                   // pegging harga child ke quote terkini
+}
+
 fn score_base(v: &VenueCfg, px: i64) -> i64 {
     let fee_ticks = (v.fee_bps as i64) * px / 10_000;
     let lat_penalty = v.est_latency_ms as i64;
@@ -36,55 +137,270 @@ fn score_base(v: &VenueCfg, px: i64) -> i64 {
 
 pub async fn run(
     mut ord_rx: mpsc::Receiver<Order>,
+    mut leg_rx: mpsc::Receiver<LegGroup>,
     gw_txs: HashMap<String, mpsc::Sender<VenueOrder>>,
     cfg: RouterCfg,
     mut inv_snap_rx: watch::Receiver<InvSnapshot>,
+    ctx: RouterCtx,
 ) {
-    let mut last_inv: Option<InvSnapshot> = inv_snap_rx.borrow().clone().into();
+    // snapshot inventory terakhir per symbol
+    let mut last_inv: HashMap<String, InvSnapshot> = HashMap::new();
+    let first = inv_snap_rx.borrow().clone();
+    if !first.symbol.is_empty() {
+        last_inv.insert(first.symbol.clone(), first);
+    }
 
     loop {
         tokio::select! {
-            _ = inv_snap_rx.changed() => { last_inv = Some(inv_snap_rx.borrow().clone()); }
-            Some(o) = ord_rx.recv() => {
-                let px = o.px;
-                // 1) skor dasar
-                let mut ranked: Vec<(String, i64)> =
-                    cfg.venues.iter().map(|(k,v)| (k.clone(), score_base(v, px))).collect();
-
-                // 2) bias inventory (mendekati target)
-                if let Some(inv) = &last_inv {
-                    for (venue, s) in ranked.iter_mut() {
-                        let cur_qty = inv.state.by_venue.get(venue).map(|vp| vp.qty).unwrap_or(0);
-                        let bias = -cur_qty.signum() as i64 * cfg.inv_bias_weight;
-                        *s += bias;
-                        VENUE_SCORE.with_label_values(&[venue]).set(*s);
+            _ = inv_snap_rx.changed() => {
+                let snap = inv_snap_rx.borrow().clone();
+                last_inv.insert(snap.symbol.clone(), snap);
+            }
+            Some(o) = ord_rx.recv() => route(o, &gw_txs, &cfg, &last_inv, &ctx).await,
+            Some(group) = leg_rx.recv() => {
+                // multi-leg: semua leg harus bisa dirutekan sebelum leg pertama dikirim
+                if let Some((leg, why)) = group.orders.iter().enumerate().find_map(|(i, o)| preflight(o, &cfg, &ctx).map(|w| (i, w))) {
+                    tracing::warn!(id = %group.id, leg, why, "multi-leg pre-flight failed, all legs rejected");
+                    ROUTER_REJECTS.with_label_values(&["multileg_preflight"]).inc();
+                    MULTILEG_ORDERS.with_label_values(&["preflight_rejected"]).inc();
+                    for o in &group.orders {
+                        EXECS.with_label_values(&["rejected", "router"]).inc();
+                        let _ = ctx.exec_tx.send(reject(o, "MLEG_PREFLIGHT")).await;
                     }
+                    continue;
                 }
-
-                // 3) top-N
-                ranked.sort_by_key(|(_,s)| -s);
-                let top = ranked.into_iter().take(cfg.top_n).collect::<Vec<_>>();
-
-                // 4) bagi qty berdasar likuiditas
-                let total_liq: u32 = top.iter().map(|(k,_)| cfg.venues.get(k).unwrap().liq_score).sum();
-                let mut remaining = o.qty;
-
-                for (i,(k,_)) in top.iter().enumerate() {
-                    let liq = cfg.venues.get(k).unwrap().liq_score as i64;
-                    let share = if i == top.len()-1 {
-                        remaining
-                    } else {
-                        (o.qty as i64 * liq / total_liq as i64).max(cfg.min_child_qty)
-                    };
-                    remaining -= share;
-                    if share <= 0 { continue; }
-
-                    if let Some(tx) = gw_txs.get(k) {
-                        let child = Order { qty: share, cl_id: format!("{}-{}", o.cl_id, k), ..o.clone() };
-                        let _ = tx.send(VenueOrder { venue: k.clone(), order: child }).await;
-                    }
+                for o in group.orders {
+                    route(o, &gw_txs, &cfg, &last_inv, &ctx).await;
                 }
             }
         }
     }
 }
+
+/// Cek leg multi-leg tanpa mengirim apa pun: MD ada, dan venue yang bisa dirutekan punya headroom cukup
+fn preflight(o: &Order, cfg: &RouterCfg, ctx: &RouterCtx) -> Option<&'static str> {
+    if ctx.feed.check(&o.symbol).is_some() {
+        return Some("no_market_data");
+    }
+    if !cfg.lists(&o.symbol) {
+        return Some("symbol_not_listed");
+    }
+    let rooms: Vec<Option<i64>> = cfg.venues.iter()
+        .filter(|(k, v)| v.trades(&o.symbol) && ctx.health.is_routable(k))
+        .map(|(k, _)| k)
+        .map(|k| ctx.venue_pos.headroom(k, &o.symbol, o.side))
+        .collect();
+    if rooms.is_empty() {
+        return Some("no_routable_venue");
+    }
+    // None = tanpa cap posisi
+    if rooms.iter().all(Option::is_some) && rooms.iter().flatten().sum::<i64>() < o.qty {
+        return Some("venue_capacity");
+    }
+    None
+}
+
+/// Rutekan satu order ke venue (peg, skor + bias inventory, split top-N, cadangan)
+async fn route(
+    mut o: Order,
+    gw_txs: &HashMap<String, mpsc::Sender<VenueOrder>>,
+    cfg: &RouterCfg,
+    last_inv: &HashMap<String, InvSnapshot>,
+    ctx: &RouterCtx,
+) {
+    let RouterCtx { health, venue_pos, open, feed, exec_tx, peg } = ctx;
+    // 0) tanpa MD (feed mati / symbol tidak dikonfigurasi) -> tolak, jangan rutekan buta
+    if let Some(why) = feed.check(&o.symbol) {
+        tracing::warn!(cl_id = %o.cl_id, symbol = %o.symbol, why, "no market data, order rejected");
+        ROUTER_REJECTS.with_label_values(&["no_market_data"]).inc();
+        EXECS.with_label_values(&["rejected", "router"]).inc();
+        let _ = exec_tx.send(reject(&o, "NO_MARKET_DATA")).await;
+        return;
+    }
+    // 0a) tidak ada venue yang memperdagangkan symbol (VENUE_SYMBOLS / exchangeInfo)
+    if !cfg.lists(&o.symbol) {
+        tracing::warn!(cl_id = %o.cl_id, symbol = %o.symbol, "symbol not listed on any venue, order rejected");
+        ROUTER_REJECTS.with_label_values(&["symbol_not_listed"]).inc();
+        EXECS.with_label_values(&["rejected", "router"]).inc();
+        let _ = exec_tx.send(reject(&o, "SYMBOL_NOT_LISTED")).await;
+        return;
+    }
+    // 0b) peg ke best bid/ask terkini (stop-limit: harga limit ditentukan pemberi sinyal, tidak di-peg)
+    let pegable = o.order_type == OrderType::Limit;
+    if let Some(px) = feed.quote(&o.symbol).filter(|_| pegable).and_then(|(b, a)| peg.price(o.side, o.px, b, a)).filter(|&p| p > 0) {
+        let label = match (px - o.px) * o.side.sign() {
+            d if d < 0 => "improved",
+            0 => "unchanged",
+            _ => "worse",
+        };
+        ORDERS_PEGGED.with_label_values(&[label]).inc();
+        o.px = px;
+    }
+    let px = o.px;
+    // 1) skor dasar (venue yang tidak memperdagangkan symbol / safe mode / sudah di cap posisi dilewati)
+    let mut ranked: Vec<(String, i64)> = cfg.venues.iter()
+        .filter(|(_,v)| v.trades(&o.symbol))
+        .filter(|(k,_)| health.is_routable(k))
+        .filter(|(k,_)| {
+            let full = venue_pos.headroom(k, &o.symbol, o.side) == Some(0);
+            if full { VENUE_POSITION_CAPPED.with_label_values(&[k.as_str()]).inc(); }
+            !full
+        })
+        .map(|(k,v)| (k.clone(), score_base(v, px)))
+        .collect();
+    if ranked.is_empty() {
+        tracing::warn!(cl_id = %o.cl_id, "no routable venue (safe mode / position cap), order rejected");
+        ROUTER_REJECTS.with_label_values(&["no_route"]).inc();
+        EXECS.with_label_values(&["rejected", "router"]).inc();
+        crate::dead_letter::record("ord", "router", "no_route", &o);
+        let _ = exec_tx.send(reject(&o, "NO_ROUTE")).await;
+        return;
+    }
+
+    // 2) bias inventory: venue tempat child ini mengurangi posisi diutamakan; bobotnya naik
+    //    saat inventory symbol keluar dari band soft (INV_BANDS)
+    if let Some(inv) = last_inv.get(&o.symbol) {
+        let pressure = cfg.inv_bands.get(&o.symbol).map_or(1.0, |b| b.pressure(inv.state.total_qty));
+        let weight = (cfg.inv_bias_weight as f64 * pressure).round() as i64;
+        for (venue, s) in ranked.iter_mut() {
+            let cur_qty = inv.state.by_venue.get(venue).map(|vp| vp.qty).unwrap_or(0);
+            let bias = -(cur_qty.signum() * o.side.sign()) * weight;
+            *s += bias;
+            VENUE_SCORE.with_label_values(&[venue]).set(*s);
+        }
+    }
+
+    // 3) top-N (sisanya cadangan untuk qty yang tidak muat di headroom)
+    ranked.sort_by_key(|(_,s)| -s);
+    let spare = ranked.split_off(cfg.top_n.min(ranked.len()));
+    let top = ranked;
+
+    // 4) bagi qty berdasar likuiditas
+    let total_liq: u32 = top.iter().map(|(k,_)| cfg.venues.get(k).unwrap().liq_score).sum();
+    let mut remaining = o.qty;
+
+    // venue yang sudah menerima child / antreannya penuh (satu child per venue: cl_id turunan per venue)
+    let mut tried: HashSet<&str> = HashSet::new();
+
+    for (i,(k,_)) in top.iter().enumerate() {
+        let liq = cfg.venues.get(k).unwrap().liq_score as i64;
+        let share = if i == top.len()-1 {
+            remaining
+        } else {
+            (o.qty * liq / total_liq as i64).max(cfg.min_child_qty).min(remaining)
+        };
+        let share = cap_share(venue_pos, k, &o, share);
+        if share <= 0 { continue; }
+        tried.insert(k);
+        // antrean gateway penuh: share tetap di `remaining` (venue berikutnya / cadangan)
+        if send_child(gw_txs, venue_pos, open, k, &o, share) {
+            remaining -= share;
+        }
+    }
+
+    // 5) sisa yang terpotong cap / backpressure -> venue yang belum dicoba sesuai skor
+    for (k, _) in top.iter().chain(spare.iter()).filter(|(k, _)| !tried.contains(k.as_str())) {
+        if remaining <= 0 { break; }
+        let share = cap_share(venue_pos, k, &o, remaining);
+        if share <= 0 { continue; }
+        if send_child(gw_txs, venue_pos, open, k, &o, share) {
+            remaining -= share;
+        }
+    }
+    if remaining > 0 {
+        tracing::warn!(cl_id = %o.cl_id, unrouted = remaining, "venue position caps / backpressure reached, remainder rejected");
+        // reject final atas sisa qty saja: reservasi in-flight parent berkurang sebesar `remaining`
+        let rest = Order { qty: remaining, ..o };
+        ROUTER_REJECTS.with_label_values(&["unrouted"]).inc();
+        EXECS.with_label_values(&["rejected", "router"]).inc();
+        crate::dead_letter::record("ord", "router", "unrouted", &rest);
+        let _ = exec_tx.send(reject(&rest, "UNROUTED")).await;
+    }
+}
+
+/// Potong share ke headroom posisi venue
+fn cap_share(venue_pos: &VenuePositions, venue: &str, o: &Order, share: i64) -> i64 {
+    match venue_pos.headroom(venue, &o.symbol, o.side) {
+        Some(room) if room < share => {
+            VENUE_POSITION_CAPPED.with_label_values(&[venue]).inc();
+            room
+        }
+        _ => share,
+    }
+}
+
+/// Kirim child tanpa menunggu: antrean gateway penuh (venue macet) / tertutup -> false,
+/// supaya satu gateway yang macet tidak menahan seluruh aliran order
+fn send_child(
+    gw_txs: &HashMap<String, mpsc::Sender<VenueOrder>>,
+    venue_pos: &VenuePositions,
+    open: &WorkingOrders,
+    venue: &str,
+    o: &Order,
+    qty: i64,
+) -> bool {
+    let Some(tx) = gw_txs.get(venue) else { return false };
+    // slot antrean diambil dulu: reservasi posisi / order terbuka hanya untuk child yang benar-benar terkirim
+    let permit = match tx.try_reserve() {
+        Ok(p) => p,
+        Err(mpsc::error::TrySendError::Full(())) => {
+            VENUE_BACKPRESSURE.with_label_values(&[venue]).inc();
+            tracing::warn!(%venue, cl_id = %o.cl_id, qty, "venue order queue full, trying next venue");
+            return false;
+        }
+        Err(mpsc::error::TrySendError::Closed(())) => {
+            tracing::warn!(%venue, cl_id = %o.cl_id, qty, "venue gateway closed, trying next venue");
+            return false;
+        }
+    };
+    let child = Order { qty, cl_id: crate::order_ids::derive(&o.cl_id, venue), ..o.clone() };
+    venue_pos.reserve(venue, &child);
+    open.reserve(venue, &child);
+    permit.send(VenueOrder { venue: venue.to_string(), order: child });
+    true
+}
+
+/// ExecReport penolakan di level router (cl_id parent, venue "router")
+fn reject(o: &Order, reason: &str) -> ExecReport {
+    ExecReport {
+        cl_id: o.cl_id.clone(),
+        symbol: o.symbol.clone(),
+        status: ExecStatus::Rejected(reason.to_string()),
+        filled_qty: 0,
+        avg_px: 0,
+        ts_ns: crate::clock::now_ns(),
+        venue: "router".to_string(),
+        side: Some(o.side),
+        order_qty: o.qty,
+        strategy: o.strategy.clone(),
+        tags: o.tags.clone(),
+        book: o.book.clone(),
+        exch_ts_ns: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_venue_queue_is_skipped_without_reserving() {
+        // label venue unik + delta: counter global, test lain boleh jalan paralel
+        let venue = "bp-test-A";
+        let (tx, mut rx) = mpsc::channel::<VenueOrder>(1);
+        let gw_txs: HashMap<String, mpsc::Sender<VenueOrder>> = [(venue.to_string(), tx)].into_iter().collect();
+        let before = VENUE_BACKPRESSURE.with_label_values(&[venue]).get();
+        let (venue_pos, open) = (VenuePositions::default(), WorkingOrders::default());
+        let o = Order {
+            cl_id: "p1".into(), ts_ns: 0, symbol: "BTCUSDT".into(), side: Side::Buy, px: 100, qty: 3,
+            strategy: String::new(), tags: vec![], book: String::new(), expire_at_ns: None, order_type: OrderType::Limit,
+        };
+        assert!(send_child(&gw_txs, &venue_pos, &open, venue, &o, 2));
+        // antrean penuh: tidak menunggu, tidak tercatat sebagai order terbuka
+        assert!(!send_child(&gw_txs, &venue_pos, &open, venue, &o, 1));
+        assert!(!send_child(&gw_txs, &venue_pos, &open, "bp-test-B", &o, 1));
+        assert_eq!(VENUE_BACKPRESSURE.with_label_values(&[venue]).get() - before, 1);
+        assert_eq!(open.snapshot().len(), 1);
+        assert_eq!(rx.try_recv().unwrap().order.qty, 2);
+    }
+}
@@ -0,0 +1,235 @@
+// ===============================
+// src/scoreboard.rs
+// ===============================
+//
+// Scoreboard performa per strategi + auto-disable.
+//
+// Dari fill (fan-out ExecReport) dan MD (mark-to-market) dihitung per strategi, dalam quote:
+// - equity = realized + unrealized (posisi per (strategi, symbol), avg cost)
+// - PnL window = equity sekarang - equity di awal window evaluasi
+// - hit rate = trade penutup (mengurangi posisi) yang untung / semua trade penutup dalam window
+// - drawdown = penurunan terbesar dari puncak equity dalam window
+//
+// Jika PnL window <= -STRATEGY_MAX_LOSS, strategi di-disable: risk menolak Signal-nya
+// (RiskError::StrategyDisabled). Aktif lagi hanya lewat admin:
+//   GET  /admin/strategies                       (scoreboard JSON)
+//   POST /admin/strategies/enable?name=<strategy>
+//
+// ENV:
+//   STRATEGY_EVAL_WINDOW_SECS=3600
+//   STRATEGY_MAX_LOSS=0            (quote; 0 = auto-disable nonaktif)
+//
+use ahash::AHashMap as HashMap;
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::{interval, Duration, Instant};
+use tracing::error;
+
+use crate::domain::{ExecReport, FillDeltas, MdTick, Side};
+use crate::metrics::{STRATEGY_DISABLED, STRATEGY_DRAWDOWN, STRATEGY_HIT_RATE, STRATEGY_PNL_WINDOW};
+use crate::sizing;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoreCfg {
+    pub window_secs: u64,
+    pub max_loss: f64,
+}
+
+impl ScoreCfg {
+    pub fn from_env() -> Self {
+        let num = |k: &str, d: f64| std::env::var(k).ok().and_then(|s| s.parse().ok()).unwrap_or(d);
+        Self { window_secs: num("STRATEGY_EVAL_WINDOW_SECS", 3600.0) as u64, max_loss: num("STRATEGY_MAX_LOSS", 0.0) }
+    }
+}
+
+impl Default for ScoreCfg {
+    fn default() -> Self {
+        Self { window_secs: 3600, max_loss: 0.0 }
+    }
+}
+
+/// Ringkasan performa satu strategi (admin / metrics)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StrategyScore {
+    pub equity: f64,
+    pub unrealized: f64,
+    pub window_pnl: f64,
+    pub hit_rate: f64,
+    pub closing_trades: usize,
+    pub drawdown: f64,
+    pub disabled: bool,
+    pub disabled_reason: Option<String>,
+}
+
+#[derive(Default)]
+struct Pos {
+    qty: i64,
+    avg_px: i64,
+}
+
+#[derive(Default)]
+struct StrategyState {
+    realized: f64,
+    positions: HashMap<String, Pos>,   // symbol -> posisi strategi
+    equity: VecDeque<(Instant, f64)>,  // sampel equity per detik dalam window
+    closes: VecDeque<(Instant, bool)>, // trade penutup (untung?)
+    score: StrategyScore,
+}
+
+#[derive(Default)]
+struct Inner {
+    strategies: HashMap<String, StrategyState>,
+    mids: HashMap<String, i64>,
+    deltas: FillDeltas,
+}
+
+/// Handle scoreboard (clone murah; diisi task `run`, dibaca risk & admin)
+#[derive(Clone, Default)]
+pub struct Scoreboard {
+    cfg: Arc<ScoreCfg>,
+    inner: Arc<Mutex<Inner>>,
+}
+
+/// Nilai quote untuk `ticks` (px ×100) × `lots`
+fn quote(symbol: &str, ticks: i64, lots: i64) -> f64 {
+    ticks as f64 / 100.0 * sizing::base_qty(symbol, lots)
+}
+
+impl Scoreboard {
+    pub fn new(cfg: ScoreCfg) -> Self {
+        Self { cfg: Arc::new(cfg), inner: Arc::default() }
+    }
+
+    /// true = Signal strategi ini harus ditolak
+    pub fn is_disabled(&self, strategy: &str) -> bool {
+        self.inner.lock().unwrap().strategies.get(strategy).is_some_and(|s| s.score.disabled)
+    }
+
+    /// Aktifkan lagi strategi (admin); window evaluasi dimulai ulang. false = strategi tidak dikenal
+    #[cfg(feature = "admin-api")]
+    pub fn enable(&self, strategy: &str) -> bool {
+        let mut g = self.inner.lock().unwrap();
+        let Some(s) = g.strategies.get_mut(strategy) else { return false };
+        if s.score.disabled {
+            tracing::warn!(%strategy, "strategy re-enabled by admin");
+        }
+        s.score.disabled = false;
+        s.score.disabled_reason = None;
+        s.equity.clear();
+        s.closes.clear();
+        if crate::metrics::strategy_admitted(strategy) {
+            STRATEGY_DISABLED.with_label_values(&[strategy]).set(0);
+        }
+        true
+    }
+
+    /// PnL unrealized strategi (quote, dievaluasi tiap detik)
+    pub fn unrealized(&self, strategy: &str) -> f64 {
+        self.inner.lock().unwrap().strategies.get(strategy).map_or(0.0, |s| s.score.unrealized)
+    }
+
+    pub fn snapshot(&self) -> BTreeMap<String, StrategyScore> {
+        let g = self.inner.lock().unwrap();
+        g.strategies.iter().map(|(k, s)| (k.clone(), s.score.clone())).collect()
+    }
+
+    fn on_exec(&self, er: &ExecReport) {
+        if er.strategy.is_empty() {
+            return;
+        }
+        let mut g = self.inner.lock().unwrap();
+        let Some((qty, px)) = g.deltas.on_report(er) else { return };
+        let signed = er.side.unwrap_or(Side::Buy).sign() * qty;
+        let s = g.strategies.entry(er.strategy.clone()).or_default();
+        let pos = s.positions.entry(er.symbol.clone()).or_default();
+        if pos.qty == 0 || pos.qty.signum() == signed.signum() {
+            pos.avg_px = (pos.avg_px * pos.qty.abs() + px * qty) / (pos.qty.abs() + qty);
+            pos.qty += signed;
+        } else {
+            // mengurangi / membalik posisi -> realize PnL untuk bagian yang ditutup
+            let closed = qty.min(pos.qty.abs());
+            let pnl = quote(&er.symbol, (px - pos.avg_px) * pos.qty.signum(), closed);
+            s.realized += pnl;
+            s.closes.push_back((Instant::now(), pnl > 0.0));
+            pos.qty += signed;
+            if pos.qty == 0 {
+                pos.avg_px = 0;
+            } else if pos.qty.signum() == signed.signum() {
+                pos.avg_px = px; // posisi berbalik arah
+            }
+        }
+    }
+
+    /// Evaluasi tiap detik: sampel equity, statistik window, auto-disable
+    fn evaluate(&self) {
+        let window = Duration::from_secs(self.cfg.window_secs.max(1));
+        let now = Instant::now();
+        let mut g = self.inner.lock().unwrap();
+        let Inner { strategies, mids, .. } = &mut *g;
+        for (name, s) in strategies.iter_mut() {
+            let unrealized: f64 = s
+                .positions
+                .iter()
+                .filter(|(_, p)| p.qty != 0)
+                .filter_map(|(sym, p)| mids.get(sym).map(|m| quote(sym, (m - p.avg_px) * p.qty.signum(), p.qty.abs())))
+                .sum();
+            let equity = s.realized + unrealized;
+            s.equity.push_back((now, equity));
+            while s.equity.front().is_some_and(|(t, _)| now.duration_since(*t) > window) {
+                s.equity.pop_front();
+            }
+            while s.closes.front().is_some_and(|(t, _)| now.duration_since(*t) > window) {
+                s.closes.pop_front();
+            }
+
+            let start = s.equity.front().map_or(equity, |(_, e)| *e);
+            let (mut peak, mut dd) = (f64::MIN, 0.0_f64);
+            for (_, e) in &s.equity {
+                peak = peak.max(*e);
+                dd = dd.max(peak - e);
+            }
+            let wins = s.closes.iter().filter(|(_, w)| *w).count();
+            let sc = &mut s.score;
+            sc.equity = equity;
+            sc.unrealized = unrealized;
+            sc.window_pnl = equity - start;
+            sc.closing_trades = s.closes.len();
+            sc.hit_rate = if s.closes.is_empty() { 0.0 } else { wins as f64 / s.closes.len() as f64 };
+            sc.drawdown = dd;
+
+            if self.cfg.max_loss > 0.0 && !sc.disabled && sc.window_pnl <= -self.cfg.max_loss {
+                let reason = format!("window pnl {:.2} <= -{:.2}", sc.window_pnl, self.cfg.max_loss);
+                error!(strategy = %name, %reason, "STRATEGY DISABLED (re-enable via POST /admin/strategies/enable)");
+                sc.disabled = true;
+                sc.disabled_reason = Some(reason);
+            }
+            if crate::metrics::strategy_admitted(name) {
+                STRATEGY_PNL_WINDOW.with_label_values(&[name]).set(sc.window_pnl);
+                STRATEGY_HIT_RATE.with_label_values(&[name]).set(sc.hit_rate);
+                STRATEGY_DRAWDOWN.with_label_values(&[name]).set(sc.drawdown);
+                STRATEGY_DISABLED.with_label_values(&[name]).set(sc.disabled as i64);
+            }
+        }
+    }
+}
+
+/// Task scoreboard: konsumsi fill (fan-out) + MD (mark-to-market)
+pub async fn run(mut exec_rx: mpsc::Receiver<Arc<ExecReport>>, mut md_rx: broadcast::Receiver<MdTick>, board: Scoreboard) {
+    let mut tick = interval(Duration::from_secs(1));
+    loop {
+        tokio::select! {
+            er = exec_rx.recv() => match er {
+                Some(er) => board.on_exec(&er),
+                None => break,
+            },
+            md = md_rx.recv() => match md {
+                Ok(md) => { board.inner.lock().unwrap().mids.insert(md.symbol.clone(), (md.best_bid + md.best_ask) / 2); }
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            _ = tick.tick() => board.evaluate(),
+        }
+    }
+}
@@ -0,0 +1,192 @@
+// ===============================
+// src/script_filter.rs
+// ===============================
+//
+// Filter pre-trade berbasis script (rhai), dievaluasi di pipeline risk setelah sizing.
+// Script di-load dari RISK_SCRIPT_FILE dan di-hot-reload saat file berubah (cek mtime);
+// script yang gagal compile tidak menggantikan versi yang sedang aktif.
+//
+// Variabel yang tersedia di script:
+//   symbol, side ("buy"/"sell"), px (quote), qty (lot), strategy, book, tags (array),
+//   position (posisi bersih akun, lot), unrealized_pnl (strategi, quote),
+//   hour_utc, minute_utc, weekday (0 = Senin)
+//
+// Hasil script (ekspresi terakhir):
+//   true / ()  -> lolos          false -> tolak ("script")          "alasan" -> tolak dengan alasan
+// Override: script boleh mengubah `qty` (mis. `qty = qty / 2;`), qty <= 0 = tolak.
+// Error runtime / batas operasi terlampaui -> tolak (fail-closed).
+//
+// Contoh:
+//   if side == "sell" && unrealized_pnl < -50.0 && hour_utc < 4 { return "no sells in drawdown overnight"; }
+//   if symbol == "SOLUSDT" { qty = qty / 2; }
+//   true
+//
+// Butuh fitur cargo `risk-script` (rhai, default aktif). Tanpa fitur ini RISK_SCRIPT_FILE ditolak
+// config_check saat startup; stub `ScriptFilter` menolak semua signal (fail-closed) bila tetap dipakai.
+//
+// ENV:
+//   RISK_SCRIPT_FILE=risk_filter.rhai   (kosong = nonaktif)
+//   RISK_SCRIPT_RELOAD_SECS=2
+//
+#[cfg(feature = "risk-script")]
+use chrono::{Datelike, Timelike, Utc};
+#[cfg(feature = "risk-script")]
+use rhai::{Dynamic, Engine, Scope, AST};
+#[cfg(feature = "risk-script")]
+use std::sync::{Arc, RwLock};
+#[cfg(feature = "risk-script")]
+use std::time::SystemTime;
+use std::time::Duration;
+#[cfg(feature = "risk-script")]
+use tracing::{error, info};
+
+use crate::domain::Signal;
+#[cfg(feature = "risk-script")]
+use crate::metrics::SCRIPT_FILTER_ERRORS;
+use crate::metrics::SCRIPT_FILTER_REJECTS;
+
+/// Batas operasi per evaluasi: script dengan loop tak berujung tidak boleh menahan risk
+#[cfg(feature = "risk-script")]
+const MAX_OPERATIONS: u64 = 100_000;
+
+/// Konteks akun untuk script (diisi risk)
+#[cfg_attr(not(feature = "risk-script"), allow(dead_code))]
+pub struct ScriptInput {
+    pub qty: i64,
+    pub position: i64,
+    pub unrealized_pnl: f64,
+}
+
+#[cfg(feature = "risk-script")]
+struct Loaded {
+    ast: AST,
+    modified: Option<SystemTime>,
+}
+
+/// Handle filter (clone murah; di-reload task `run_reloader`, dievaluasi risk)
+#[cfg(feature = "risk-script")]
+#[derive(Clone)]
+pub struct ScriptFilter {
+    path: String,
+    engine: Arc<Engine>,
+    loaded: Arc<RwLock<Option<Loaded>>>,
+}
+
+#[cfg(feature = "risk-script")]
+impl ScriptFilter {
+    pub fn new(path: String) -> Self {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        let f = Self { path, engine: Arc::new(engine), loaded: Arc::default() };
+        f.reload();
+        f
+    }
+
+    fn modified(&self) -> Option<SystemTime> {
+        std::fs::metadata(&self.path).and_then(|m| m.modified()).ok()
+    }
+
+    /// Compile ulang jika file berubah; true jika versi baru aktif
+    pub fn reload(&self) -> bool {
+        let modified = self.modified();
+        if let Some(l) = self.loaded.read().unwrap().as_ref() {
+            if l.modified == modified {
+                return false;
+            }
+        }
+        let src = match std::fs::read_to_string(&self.path) {
+            Ok(s) => s,
+            Err(e) => {
+                error!(path = %self.path, ?e, "risk script read failed");
+                return false;
+            }
+        };
+        match self.engine.compile(&src) {
+            Ok(ast) => {
+                info!(path = %self.path, "risk script loaded");
+                *self.loaded.write().unwrap() = Some(Loaded { ast, modified });
+                true
+            }
+            Err(e) => {
+                SCRIPT_FILTER_ERRORS.inc();
+                error!(path = %self.path, err = %e, "risk script compile failed (keeping previous version)");
+                // jangan coba compile file yang sama berulang kali
+                if let Some(l) = self.loaded.write().unwrap().as_mut() {
+                    l.modified = modified;
+                }
+                false
+            }
+        }
+    }
+
+    /// Evaluasi script: Ok(qty final) atau Err(alasan tolak)
+    pub fn check(&self, sig: &Signal, input: ScriptInput) -> Result<i64, String> {
+        let guard = self.loaded.read().unwrap();
+        let Some(l) = guard.as_ref() else { return Ok(input.qty) };
+        let now = Utc::now();
+        let mut scope = Scope::new();
+        scope
+            .push_constant("symbol", sig.symbol.clone())
+            .push_constant("side", sig.side.as_str())
+            .push_constant("px", sig.px as f64 / 100.0)
+            .push("qty", input.qty)
+            .push_constant("strategy", sig.strategy.clone())
+            .push_constant("book", sig.book.clone())
+            .push_constant("tags", sig.tags.iter().cloned().map(Dynamic::from).collect::<rhai::Array>())
+            .push_constant("position", input.position)
+            .push_constant("unrealized_pnl", input.unrealized_pnl)
+            .push_constant("hour_utc", now.hour() as i64)
+            .push_constant("minute_utc", now.minute() as i64)
+            .push_constant("weekday", now.weekday().num_days_from_monday() as i64);
+        let out = self.engine.eval_ast_with_scope::<Dynamic>(&mut scope, &l.ast);
+        let verdict = match out {
+            Ok(v) if v.is_unit() => Ok(()),
+            Ok(v) if v.is_bool() => if v.as_bool().unwrap_or(false) { Ok(()) } else { Err("script".to_string()) },
+            Ok(v) if v.is_string() => Err(v.into_string().unwrap_or_default()),
+            Ok(v) => Err(format!("script returned {}", v.type_name())),
+            Err(e) => {
+                SCRIPT_FILTER_ERRORS.inc();
+                Err(format!("script error: {e}"))
+            }
+        };
+        let qty = scope.get_value::<i64>("qty").unwrap_or(0);
+        let res = verdict.and_then(|_| if qty > 0 { Ok(qty) } else { Err("script: qty <= 0".to_string()) });
+        if res.is_err() {
+            SCRIPT_FILTER_REJECTS.inc();
+        }
+        res
+    }
+}
+
+/// Build tanpa fitur `risk-script` (tanpa rhai): tidak ada engine, setiap signal ditolak
+#[cfg(not(feature = "risk-script"))]
+#[derive(Clone)]
+pub struct ScriptFilter {
+    path: String,
+}
+
+#[cfg(not(feature = "risk-script"))]
+impl ScriptFilter {
+    pub fn new(path: String) -> Self {
+        tracing::error!(%path, "RISK_SCRIPT_FILE set but binary built without `--features risk-script`; all signals rejected");
+        Self { path }
+    }
+
+    pub fn reload(&self) -> bool {
+        false
+    }
+
+    pub fn check(&self, _sig: &Signal, _input: ScriptInput) -> Result<i64, String> {
+        SCRIPT_FILTER_REJECTS.inc();
+        Err(format!("risk script {} unavailable (built without risk-script)", self.path))
+    }
+}
+
+/// Task: cek perubahan file script tiap `secs` detik
+pub async fn run_reloader(filter: ScriptFilter, secs: u64) {
+    let mut tick = tokio::time::interval(Duration::from_secs(secs.max(1)));
+    loop {
+        tick.tick().await;
+        filter.reload();
+    }
+}
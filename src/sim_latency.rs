@@ -0,0 +1,162 @@
+// ===============================
+// src/sim_latency.rs
+// ===============================
+//
+// Model latensi per venue untuk gateway mock (simulasi SOR), supaya pilihan venue di router diuji
+// terhadap venue yang lambat / berjitter, bukan fill instan:
+// - latensi order : order diterima gateway -> ACK
+// - latensi fill  : ACK -> order di-match ke book (depth-aware) / terisi penuh
+// - skew clock    : jam venue lebih cepat/lambat dari clock engine; report mock membawa
+//                   `exch_ts_ns` = clock engine + skew (terlihat di exec_network_delay_ms)
+// Tiap order disampel sendiri (order bisa saling menyalip), semua dalam ms dan tidak pernah negatif.
+//
+// Distribusi:
+//   fixed:<ms> | uniform:<lo>-<hi> | normal:<mean>,<sd> | lognormal:<median>,<sigma>
+//
+// ENV:
+//   MOCK_ORDER_LATENCY=A=normal:3,1;B=fixed:1          (default 0)
+//   MOCK_FILL_LATENCY=A=lognormal:5,0.6;C=uniform:4-10  (default fixed:<est_latency_ms> venue)
+//   MOCK_CLOCK_SKEW_MS=A:5,B:-3                         (default 0 = report tanpa exch_ts_ns)
+//   MOCK_LATENCY_SEED=42                                (kosong = acak; diisi = run bisa diulang)
+//
+use ahash::AHashMap as HashMap;
+use once_cell::sync::OnceCell;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::time::Duration;
+
+use crate::clock;
+use crate::metrics::SIM_LATENCY;
+
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LatencyDist {
+    Fixed(f64),
+    Uniform(f64, f64),
+    Normal(f64, f64),
+    LogNormal(f64, f64),
+}
+
+impl LatencyDist {
+    /// Parse "normal:3,1" dst.; None jika format salah
+    pub fn parse(s: &str) -> Option<Self> {
+        let (kind, args) = s.trim().split_once(':')?;
+        let nums = |sep: char| -> Option<(f64, f64)> {
+            let (a, b) = args.split_once(sep)?;
+            Some((a.trim().parse().ok()?, b.trim().parse().ok()?))
+        };
+        match kind.trim().to_ascii_lowercase().as_str() {
+            "fixed" => args.trim().parse().ok().map(LatencyDist::Fixed),
+            "uniform" => nums('-').map(|(lo, hi)| LatencyDist::Uniform(lo, hi.max(lo))),
+            "normal" => nums(',').map(|(m, sd)| LatencyDist::Normal(m, sd)),
+            "lognormal" => nums(',').map(|(med, sigma)| LatencyDist::LogNormal(med, sigma)),
+            _ => None,
+        }
+    }
+
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> Duration {
+        let ms = match *self {
+            LatencyDist::Fixed(ms) => ms,
+            LatencyDist::Uniform(lo, hi) if hi > lo => rng.gen_range(lo..hi),
+            LatencyDist::Uniform(lo, _) => lo,
+            LatencyDist::Normal(mean, sd) => mean + sd * std_normal(rng),
+            LatencyDist::LogNormal(median, sigma) => median * (sigma * std_normal(rng)).exp(),
+        };
+        Duration::from_secs_f64(ms.max(0.0) / 1000.0)
+    }
+}
+
+/// Normal standar (Box-Muller)
+fn std_normal<R: Rng>(rng: &mut R) -> f64 {
+    let u1: f64 = rng.gen::<f64>().max(f64::MIN_POSITIVE);
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct SimLatencyCfg {
+    pub order: HashMap<String, LatencyDist>,
+    pub fill: HashMap<String, LatencyDist>,
+    pub skew_ms: HashMap<String, i64>,
+    pub seed: Option<u64>,
+}
+
+/// "A=normal:3,1;B=fixed:1" -> venue -> distribusi
+fn parse_dists(key: &str) -> HashMap<String, LatencyDist> {
+    std::env::var(key)
+        .unwrap_or_default()
+        .split(';')
+        .filter_map(|kv| kv.split_once('='))
+        .filter_map(|(venue, spec)| match LatencyDist::parse(spec) {
+            Some(d) => Some((venue.trim().to_string(), d)),
+            None => {
+                tracing::warn!(key, venue, spec, "invalid latency distribution, ignored");
+                None
+            }
+        })
+        .collect()
+}
+
+impl SimLatencyCfg {
+    pub fn from_env() -> Self {
+        let skew_ms = std::env::var("MOCK_CLOCK_SKEW_MS")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|kv| kv.split_once(':'))
+            .filter_map(|(v, ms)| Some((v.trim().to_string(), ms.trim().parse().ok()?)))
+            .collect();
+        Self {
+            order: parse_dists("MOCK_ORDER_LATENCY"),
+            fill: parse_dists("MOCK_FILL_LATENCY"),
+            skew_ms,
+            seed: std::env::var("MOCK_LATENCY_SEED").ok().and_then(|s| s.parse().ok()),
+        }
+    }
+
+    /// Model untuk satu venue; `est_latency_ms` (RouterCfg) jadi default latensi fill
+    pub fn venue(&self, venue: &str, est_latency_ms: u64) -> VenueLatency {
+        // seed per venue agar tiap venue punya urutan sampel sendiri yang tetap
+        let seed = self.seed.map(|s| venue.bytes().fold(s, |h, b| h.wrapping_mul(31).wrapping_add(b as u64)));
+        VenueLatency {
+            venue: venue.to_string(),
+            order: self.order.get(venue).copied().unwrap_or(LatencyDist::Fixed(0.0)),
+            fill: self.fill.get(venue).copied().unwrap_or(LatencyDist::Fixed(est_latency_ms as f64)),
+            rng: seed.map_or_else(StdRng::from_entropy, StdRng::seed_from_u64),
+        }
+    }
+}
+
+/// Sampler latensi satu venue (dipegang task gateway mock)
+pub struct VenueLatency {
+    venue: String,
+    order: LatencyDist,
+    fill: LatencyDist,
+    rng: StdRng,
+}
+
+impl VenueLatency {
+    pub fn order(&mut self) -> Duration {
+        let d = self.order.sample(&mut self.rng);
+        SIM_LATENCY.with_label_values(&[&self.venue, "order"]).observe(d.as_secs_f64() * 1000.0);
+        d
+    }
+
+    pub fn fill(&mut self) -> Duration {
+        let d = self.fill.sample(&mut self.rng);
+        SIM_LATENCY.with_label_values(&[&self.venue, "fill"]).observe(d.as_secs_f64() * 1000.0);
+        d
+    }
+}
+
+static SKEW: OnceCell<HashMap<String, i128>> = OnceCell::new();
+
+/// Set skew clock per venue (sekali di startup)
+pub fn init(cfg: &SimLatencyCfg) {
+    let skew = cfg.skew_ms.iter().map(|(v, ms)| (v.clone(), *ms as i128 * 1_000_000)).collect();
+    let _ = SKEW.set(skew);
+}
+
+/// `exch_ts_ns` untuk report mock venue: clock engine + skew (0 jika venue tanpa skew)
+pub fn venue_ts(venue: &str) -> i128 {
+    SKEW.get().and_then(|m| m.get(venue)).map_or(0, |skew| clock::now_ns() + skew)
+}
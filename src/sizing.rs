@@ -0,0 +1,103 @@
+// ===============================
+// src/sizing.rs
+// ===============================
+//
+// Ukuran order: lot per symbol + konversi notional quote -> qty.
+//
+// `qty` di Signal/Order/ExecReport adalah jumlah LOT (integer). Ukuran lot per symbol
+// mengikuti filter LOT_SIZE (stepSize) exchange:
+//   LOT_SIZES=BTCUSDT:0.00001,ETHUSDT:0.0001   (default 1 = qty dalam unit base utuh)
+// Gateway Binance mengirim `quantity = qty * stepSize` (format string: exch_format.rs).
+//
+// Order bisa dinyatakan dalam notional quote (mis. $50 per trade):
+// - per strategi: STRATEGY_QUOTE_NOTIONAL=mean_reversion:50,ma_crossover:100 (dibawa Signal)
+// - global:       ORDER_QUOTE_NOTIONAL=50 (fallback di risk; 0 = pakai qty dari strategi)
+// Risk mengonversi ke lot memakai harga limit Signal, dibulatkan ke bawah ke stepSize.
+//
+// Ukuran per strategi dikalikan scale dari capital allocator (allocator.rs; default 1.0)
+// dan scale global volatility targeting (vol_target.rs; default 1.0).
+//
+use ahash::AHashMap as HashMap;
+use once_cell::sync::{Lazy, OnceCell};
+use std::sync::RwLock;
+
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct SizingCfg {
+    pub lot_sizes: HashMap<String, f64>,
+    /// tickSize PRICE_FILTER per symbol (TICK_SIZES, fallback sebelum exchangeInfo)
+    pub tick_sizes: HashMap<String, f64>,
+}
+
+static SIZING: OnceCell<SizingCfg> = OnceCell::new();
+
+/// Set konfigurasi sizing global (dipanggil sekali di startup)
+pub fn init(cfg: SizingCfg) {
+    if !cfg.lot_sizes.is_empty() {
+        tracing::info!(lot_sizes = ?cfg.lot_sizes, "order sizing");
+    }
+    let _ = SIZING.set(cfg);
+}
+
+fn cfg() -> &'static SizingCfg {
+    SIZING.get_or_init(SizingCfg::default)
+}
+
+/// Scale ukuran order per strategi (diisi allocator)
+static STRATEGY_SCALES: Lazy<RwLock<HashMap<String, f64>>> = Lazy::new(Default::default);
+
+pub fn set_strategy_scales(scales: HashMap<String, f64>) {
+    *STRATEGY_SCALES.write().unwrap() = scales;
+}
+
+/// Scale global semua strategi (diisi vol targeting)
+static VOL_SCALE: Lazy<RwLock<f64>> = Lazy::new(|| RwLock::new(1.0));
+
+pub fn set_vol_scale(scale: f64) {
+    *VOL_SCALE.write().unwrap() = scale;
+}
+
+/// Pengali ukuran order strategi: allocator × vol targeting (1.0 jika keduanya nonaktif)
+pub fn strategy_scale(strategy: &str) -> f64 {
+    let alloc = STRATEGY_SCALES.read().unwrap().get(strategy).copied().unwrap_or(1.0);
+    alloc * *VOL_SCALE.read().unwrap()
+}
+
+/// Parse "KEY:val,KEY:val" (kunci apa adanya, nilai f64 > 0)
+pub fn parse_map(s: &str) -> HashMap<String, f64> {
+    s.split(',')
+        .filter_map(|kv| kv.split_once(':'))
+        .filter_map(|(k, v)| Some((k.trim().to_string(), v.trim().parse::<f64>().ok()?)))
+        .filter(|(k, v)| !k.is_empty() && *v > 0.0)
+        .collect()
+}
+
+/// stepSize LOT_SIZE untuk symbol (unit base per lot)
+pub fn lot_size(symbol: &str) -> f64 {
+    cfg().lot_sizes.get(symbol).copied().unwrap_or(1.0)
+}
+
+/// tickSize harga untuk symbol (default 0.01 = skala harga internal)
+#[cfg(feature = "binance")]
+pub fn tick_size(symbol: &str) -> f64 {
+    cfg().tick_sizes.get(symbol).copied().unwrap_or(0.01)
+}
+
+/// Jumlah lot untuk notional quote `quote_notional` di harga `px` (tick ×100), dibulatkan ke bawah
+pub fn lots_for_notional(symbol: &str, px: i64, quote_notional: f64) -> i64 {
+    if px <= 0 {
+        return 0;
+    }
+    let base = quote_notional / (px as f64 / 100.0);
+    // epsilon: 0.3 / 0.1 = 2.9999999 harus jadi 3 lot
+    (base / lot_size(symbol) + 1e-9).floor() as i64
+}
+
+/// Qty dalam unit base untuk `lots`
+pub fn base_qty(symbol: &str, lots: i64) -> f64 {
+    lots as f64 * lot_size(symbol)
+}
+
+/// Lot (dibulatkan) dari qty base yang dilaporkan exchange
+pub fn lots_from_base(symbol: &str, base: f64) -> i64 {
+    (base / lot_size(symbol)).round() as i64
+}
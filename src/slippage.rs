@@ -0,0 +1,110 @@
+// ===============================
+// src/slippage.rs
+// ===============================
+//
+// Model slippage untuk gateway mock, supaya strategi dengan order besar tidak dievaluasi
+// dengan market impact nol. Slippage selalu merugikan: buy terisi lebih mahal, sell lebih murah.
+// Harga tetap x100 (1 tick = 1 unit harga engine).
+//
+// Model:
+//   none                 : tanpa slippage (default)
+//   ticks:<n>            : n tick tetap per fill
+//   spread:<frac>        : frac x spread book saat fill (butuh MOCK_BOOK_LEVELS > 0; tanpa book = 0)
+//   sqrt:<bps>,<ref_qty> : impact akar kuadrat, bps x sqrt(qty order / ref_qty) dari harga fill
+//
+// Impact dihitung dari qty order induk (bukan potongan per level), jadi semua partial fill
+// satu order kena slippage per unit yang sama. Mode depth-aware tetap "makan" level book;
+// slippage ditambahkan di atas harga level.
+//
+// ENV:
+//   MOCK_SLIPPAGE=BTCUSDT=sqrt:10,50;ETHUSDT=ticks:2;*=spread:0.5   (* = default symbol lain)
+//
+use ahash::AHashMap as HashMap;
+
+use crate::domain::{BookUpdate, Side};
+use crate::metrics::{symbol_label, SIM_SLIPPAGE_BPS};
+
+#[derive(Debug, Clone, Copy, Default, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlippageModel {
+    #[default]
+    None,
+    FixedTicks(i64),
+    SpreadFrac(f64),
+    SqrtImpact { bps: f64, ref_qty: f64 },
+}
+
+impl SlippageModel {
+    /// Parse "ticks:2" dst.; None jika format salah
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("none") {
+            return Some(SlippageModel::None);
+        }
+        let (kind, args) = s.split_once(':')?;
+        match kind.trim().to_ascii_lowercase().as_str() {
+            "ticks" => args.trim().parse().ok().map(SlippageModel::FixedTicks),
+            "spread" => args.trim().parse().ok().map(SlippageModel::SpreadFrac),
+            "sqrt" => {
+                let (bps, ref_qty) = args.split_once(',')?;
+                let (bps, ref_qty): (f64, f64) = (bps.trim().parse().ok()?, ref_qty.trim().parse().ok()?);
+                (ref_qty > 0.0).then_some(SlippageModel::SqrtImpact { bps, ref_qty })
+            }
+            _ => None,
+        }
+    }
+
+    /// Slippage per unit (unit harga, >= 0) untuk order `qty` lot yang terisi di sekitar `px`
+    pub fn slip(&self, qty: i64, px: i64, spread: Option<i64>) -> i64 {
+        let s = match *self {
+            SlippageModel::None => 0.0,
+            SlippageModel::FixedTicks(n) => n as f64,
+            SlippageModel::SpreadFrac(f) => spread.map_or(0.0, |sp| f * sp as f64),
+            SlippageModel::SqrtImpact { bps, ref_qty } => px as f64 * bps / 1e4 * (qty.max(0) as f64 / ref_qty).sqrt(),
+        };
+        s.round().max(0.0) as i64
+    }
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct SlippageCfg {
+    pub per_symbol: HashMap<String, SlippageModel>,
+    pub default: SlippageModel,
+}
+
+impl SlippageCfg {
+    pub fn from_env() -> Self {
+        let mut cfg = Self::default();
+        for (sym, spec) in std::env::var("MOCK_SLIPPAGE").unwrap_or_default().split(';').filter_map(|kv| kv.split_once('=')) {
+            let Some(m) = SlippageModel::parse(spec) else {
+                tracing::warn!(symbol = sym, spec, "invalid slippage model, ignored");
+                continue;
+            };
+            match sym.trim() {
+                "*" => cfg.default = m,
+                s => {
+                    cfg.per_symbol.insert(s.to_ascii_uppercase(), m);
+                }
+            }
+        }
+        cfg
+    }
+
+    pub fn model(&self, symbol: &str) -> SlippageModel {
+        self.per_symbol.get(symbol).copied().unwrap_or(self.default)
+    }
+
+    /// Harga fill setelah slippage untuk order (`side`, `qty` induk) yang terisi di `px`
+    pub fn apply(&self, symbol: &str, side: Side, qty: i64, px: i64, book: Option<&BookUpdate>) -> i64 {
+        let spread = book.and_then(|b| Some(b.asks.first()?.0 - b.bids.first()?.0)).filter(|s| *s > 0);
+        let model = self.model(symbol);
+        let slip = model.slip(qty, px, spread);
+        if px > 0 && !matches!(model, SlippageModel::None) {
+            SIM_SLIPPAGE_BPS.with_label_values(&[symbol_label(symbol)]).observe(slip as f64 * 1e4 / px as f64);
+        }
+        match side {
+            Side::Buy => px + slip,
+            Side::Sell => (px - slip).max(1),
+        }
+    }
+}
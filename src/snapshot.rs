@@ -0,0 +1,217 @@
+// ===============================
+// src/snapshot.rs
+// ===============================
+//
+// Snapshot state engine ke disk (bincode) + warm restart.
+//
+// Yang disimpan:
+// - rolling window tiap worker strategi (kunci: "<strategi>#<worker>")
+// - posisi per symbol (SymbolState)
+// - progres order yang masih working di blotter (cum qty/avg_px per cl_id)
+//
+// ENV:
+//   SNAPSHOT_FILE=state.snap          (kosong = nonaktif)
+//   SNAPSHOT_INTERVAL_SECS=10
+//   SNAPSHOT_MAX_AGE_SECS=300         (window strategi lebih tua dari ini tidak dipulihkan)
+// CLI:
+//   --warm-start                      (pulihkan dari SNAPSHOT_FILE saat startup)
+//
+// File ditulis atomik (tmp + rename) supaya crash saat menulis tidak merusak snapshot lama.
+//
+use chrono::Utc;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use tokio::time::{interval, Duration, MissedTickBehavior};
+use tracing::{error, info, warn};
+
+use crate::blotter::{Blotter, CumState};
+use crate::domain::SymbolState;
+
+/// Naikkan jika layout `EngineSnapshot` berubah (snapshot lama diabaikan)
+const SNAPSHOT_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EngineSnapshot {
+    pub version: u32,
+    pub ts_ns: i128,
+    /// state strategi ter-encode bincode (tipe berbeda per strategi)
+    pub strategies: BTreeMap<String, Vec<u8>>,
+    pub positions: BTreeMap<String, SymbolState>,
+    pub working_orders: BTreeMap<String, CumState>,
+}
+
+struct Inner {
+    current: EngineSnapshot,
+    restored: Option<EngineSnapshot>,
+}
+
+/// Handle snapshot yang bisa di-clone (dipakai strategi, positions, writer)
+#[derive(Clone)]
+pub struct Snapshots {
+    enabled: bool,
+    max_age_ns: i128,
+    inner: Arc<Mutex<Inner>>,
+}
+
+fn now_ns() -> i128 {
+    Utc::now().timestamp_nanos_opt().unwrap_or(0) as i128
+}
+
+impl Snapshots {
+    /// `path` = None -> snapshot nonaktif (put/take jadi no-op).
+    /// `warm_start` -> baca snapshot yang ada untuk dipulihkan task saat start.
+    pub fn new(path: Option<&str>, warm_start: bool, max_age_secs: u64) -> Self {
+        let restored = match (path, warm_start) {
+            (Some(p), true) => load(p),
+            (None, true) => {
+                warn!("--warm-start requested but SNAPSHOT_FILE is not set, cold start");
+                None
+            }
+            _ => None,
+        };
+        Self {
+            enabled: path.is_some(),
+            max_age_ns: max_age_secs as i128 * 1_000_000_000,
+            inner: Arc::new(Mutex::new(Inner { current: EngineSnapshot::default(), restored })),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Ambil state strategi hasil restore (sekali pakai); None jika tidak ada / terlalu tua
+    pub fn take_strategy<S: DeserializeOwned>(&self, key: &str) -> Option<S> {
+        let mut g = self.inner.lock().unwrap();
+        let snap = g.restored.as_mut()?;
+        let age_ns = now_ns() - snap.ts_ns;
+        let bytes = snap.strategies.remove(key)?;
+        if age_ns > self.max_age_ns {
+            info!(%key, age_secs = age_ns / 1_000_000_000, "snapshot: strategy window too old, cold start");
+            return None;
+        }
+        match bincode::deserialize(&bytes) {
+            Ok(s) => {
+                info!(%key, "snapshot: strategy state restored");
+                Some(s)
+            }
+            Err(e) => {
+                warn!(?e, %key, "snapshot: strategy state incompatible, cold start");
+                None
+            }
+        }
+    }
+
+    pub fn take_position(&self, symbol: &str) -> Option<SymbolState> {
+        let mut g = self.inner.lock().unwrap();
+        let st = g.restored.as_mut()?.positions.remove(symbol)?;
+        info!(%symbol, qty = st.total_qty, realized = st.realized_pnl, "snapshot: position restored");
+        Some(st)
+    }
+
+    /// Qty posisi hasil restore tanpa mengonsumsi (dibaca risk sebelum positions mengambilnya)
+    pub fn peek_position_qty(&self, symbol: &str) -> Option<i64> {
+        let g = self.inner.lock().unwrap();
+        g.restored.as_ref()?.positions.get(symbol).map(|st| st.total_qty)
+    }
+
+    /// Posisi per venue hasil restore tanpa mengonsumsi (untuk batas posisi venue)
+    pub fn peek_venue_positions(&self, symbol: &str) -> Vec<(String, i64)> {
+        let g = self.inner.lock().unwrap();
+        g.restored
+            .as_ref()
+            .and_then(|r| r.positions.get(symbol))
+            .map(|st| st.by_venue.iter().map(|(v, p)| (v.clone(), p.qty)).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn take_working_orders(&self) -> BTreeMap<String, CumState> {
+        let mut g = self.inner.lock().unwrap();
+        g.restored.as_mut().map(|s| std::mem::take(&mut s.working_orders)).unwrap_or_default()
+    }
+
+    pub fn put_strategy<S: Serialize>(&self, key: &str, state: &S) {
+        if !self.enabled {
+            return;
+        }
+        match bincode::serialize(state) {
+            Ok(bytes) => {
+                self.inner.lock().unwrap().current.strategies.insert(key.to_string(), bytes);
+            }
+            Err(e) => warn!(?e, %key, "snapshot: encode strategy failed"),
+        }
+    }
+
+    pub fn put_position(&self, symbol: &str, state: &SymbolState) {
+        if !self.enabled {
+            return;
+        }
+        self.inner.lock().unwrap().current.positions.insert(symbol.to_string(), state.clone());
+    }
+}
+
+/// Baca file snapshot (dipakai warm start & tool offline)
+pub fn load(path: &str) -> Option<EngineSnapshot> {
+    let bytes = match std::fs::read(path) {
+        Ok(b) => b,
+        Err(e) => {
+            warn!(?e, %path, "snapshot: read failed, cold start");
+            return None;
+        }
+    };
+    match bincode::deserialize::<EngineSnapshot>(&bytes) {
+        Ok(s) if s.version == SNAPSHOT_VERSION => {
+            info!(
+                %path,
+                age_secs = (now_ns() - s.ts_ns) / 1_000_000_000,
+                strategies = s.strategies.len(),
+                positions = s.positions.len(),
+                working_orders = s.working_orders.len(),
+                "snapshot: loaded for warm start"
+            );
+            Some(s)
+        }
+        Ok(s) => {
+            warn!(%path, version = s.version, expected = SNAPSHOT_VERSION, "snapshot: version mismatch, cold start");
+            None
+        }
+        Err(e) => {
+            warn!(?e, %path, "snapshot: decode failed, cold start");
+            None
+        }
+    }
+}
+
+fn write_atomic(path: &str, snap: &EngineSnapshot) -> std::io::Result<()> {
+    let bytes = bincode::serialize(snap).map_err(std::io::Error::other)?;
+    let tmp = format!("{path}.tmp");
+    std::fs::write(&tmp, bytes)?;
+    std::fs::rename(&tmp, path)
+}
+
+/// Task writer: tulis snapshot tiap `interval_secs`
+pub async fn run_writer(snaps: Snapshots, blotter: Blotter, path: String, interval_secs: u64) {
+    info!(%path, interval_secs, "snapshot: writer started");
+    let mut tick = interval(Duration::from_secs(interval_secs.max(1)));
+    tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    // lewati tick pertama (langsung jalan) agar snapshot lama tidak tertimpa
+    // state kosong sebelum task sempat restore/publish
+    tick.tick().await;
+    loop {
+        tick.tick().await;
+        let snap = {
+            let mut g = snaps.inner.lock().unwrap();
+            g.current.version = SNAPSHOT_VERSION;
+            g.current.ts_ns = now_ns();
+            g.current.working_orders = blotter.working_orders();
+            g.current.clone()
+        };
+        let p = path.clone();
+        match tokio::task::spawn_blocking(move || write_atomic(&p, &snap)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => error!(?e, %path, "snapshot: write failed"),
+            Err(e) => error!(?e, "snapshot: writer join failed"),
+        }
+    }
+}
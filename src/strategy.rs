@@ -2,10 +2,12 @@
 // src/strategy.rs
 // ===============================
 //
-// Disediakan 3 strategi:
+// Disediakan 5 strategi:
 // 1) Mean-Reversion (default)          -> function: run (alias run_mean_reversion)
 // 2) MA Crossover (Trend-Following)    -> function: run_ma_crossover
 // 3) Volatility Breakout (Range Break) -> function: run_vol_breakout
+// 4) Bollinger Band (Mean-Reversion)   -> function: run_bollinger
+// 5) Donchian Channel + ATR trailing   -> function: run_donchian
 //
 // Cara pakai cepat (tanpa ubah main.rs):
 // - Strategi default yang dipanggil main.rs adalah `run()` = mean-reversion.
@@ -18,17 +20,168 @@
 //
 // Remarks ringkas setiap strategi ada di komentar di atas state struct masing-masing.
 //
+// Warm restart: state tiap worker dipublikasikan ke `Snapshots` (kunci "<strategi>#<worker>")
+// dan dipulihkan saat start dengan `--warm-start` (lihat snapshot.rs).
+//
+// Input worker = EnrichedTick (MdTick + microprice/imbalance dari book_analytics.rs); strategi
+// yang hanya butuh quote cukup implement `on_tick`, yang butuh analitik book override `on_enriched`.
+//
+// Evaluasi bar-close (STRATEGY_BAR_SECS): worker mengagregasi tick jadi candle (candles.rs) dan
+// memanggil strategi sekali per candle dengan tick terakhir candle itu, jadi window strategi dihitung
+// dalam bar, bukan tick. Signal diberi ts_ns tick yang menutup candle (latensi tidak ikut durasi bar).
+//
+// Umpan balik posisi: worker membaca InvSnapshot terbaru symbol dari `PositionFeed` (positions.rs) dan
+// memanggil `on_position` setiap kali snapshot berubah (fill), dengan posisi milik strategi itu sendiri
+// (`by_strategy[<strategi>]`). Strategi yang mengelola posisi sendiri (Donchian) memakai ini untuk exit.
+//
+// Anomali MD (MD_ANOMALY=widen, md_anomaly.rs): sebelum tiap tick worker memanggil `set_threshold_mult`
+// dengan pengali untuk symbol tick itu (1.0 = normal); strategi bawaan memperlebar edge / k-nya.
+//
+// Atribusi: setiap Signal diberi `strategy` (nama strategi) dan `tags` (STRATEGY_TAGS)
+// oleh loop worker, lalu dibawa sampai Order/ExecReport/blotter.
+//
 
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::collections::VecDeque;
-use tokio::sync::{broadcast, mpsc};
-use tracing::{error, warn};
-use crate::domain::{MdTick, Signal, Side};
+use tokio::sync::mpsc;
+use crate::book_analytics::TickRx;
+use crate::conflate::Conflated;
+use tokio::time::{interval, Duration, MissedTickBehavior};
+use tracing::{debug, error, warn};
+use crate::candles::Aggregator;
+use ahash::AHashMap as HashMap;
+use crate::domain::{EnrichedTick, InvSnapshot, MdTick, Signal, Side, VenuePosition};
+use crate::indicators::{Atr, Channel, RollingStats};
+use crate::md_anomaly::MdAnomaly;
+use crate::positions::PositionFeed;
+use crate::md_seq::GapDetector;
 use crate::metrics::SIGNALS;
+use crate::snapshot::Snapshots;
+use crate::symbol_registry::SymbolRegistry;
 
 fn mid_price(md: &MdTick) -> i64 {
     (md.best_bid + md.best_ask) / 2
 }
 
+/// Signal default (qty 10 lot); `strategy`/`tags`/`book`/`quote_notional` diisi oleh loop worker
+fn signal(md: &MdTick, side: Side, px: i64) -> Signal {
+    Signal {
+        ts_ns: md.ts_ns,
+        symbol: md.symbol.clone(),
+        side,
+        px,
+        qty: 10,
+        strategy: String::new(),
+        tags: Vec::new(),
+        book: String::new(),
+        quote_notional: None,
+    }
+}
+
+/// Identitas worker strategi: atribusi Signal + kunci snapshot
+#[derive(Clone)]
+pub struct WorkerCtx {
+    pub strategy: &'static str,
+    pub worker: u32,
+    pub tags: Vec<String>,
+    pub book: String,                // book (tenant) pemilik strategi (BOOKS)
+    pub quote_notional: Option<f64>, // ukuran per trade dalam quote (STRATEGY_QUOTE_NOTIONAL)
+    pub max_tps: Option<f64>,        // konflasi MD: tick/s maks per symbol (STRATEGY_MAX_TPS)
+    pub bar_secs: Option<u64>,       // evaluasi hanya saat candle close (STRATEGY_BAR_SECS)
+    pub symbol: Option<String>,      // PIPELINE=per_symbol: worker khusus satu symbol
+    pub snaps: Snapshots,
+    pub symbols: SymbolRegistry, // status exchange: symbol BREAK/HALT -> tidak ada Signal
+    pub positions: PositionFeed, // posisi terbaru per symbol (on_position)
+    pub anomaly: MdAnomaly,      // pengali threshold saat quote mencurigakan (MD_ANOMALY=widen)
+}
+
+impl WorkerCtx {
+    pub fn key(&self) -> String {
+        match &self.symbol {
+            Some(sym) => format!("{}@{}#{}", self.strategy, sym, self.worker),
+            None => format!("{}#{}", self.strategy, self.worker),
+        }
+    }
+}
+
+/// State strategi berbasis tick (bisa di-snapshot)
+pub trait TickStrategy: Serialize + DeserializeOwned + Send + 'static {
+    fn on_tick(&mut self, md: &MdTick) -> Option<Signal>;
+
+    /// Tick + analitik book (microprice, imbalance); default: abaikan analitik
+    fn on_enriched(&mut self, t: &EnrichedTick) -> Option<Signal> {
+        self.on_tick(&t.tick)
+    }
+
+    /// Snapshot posisi symbol berubah (fill); `own` = posisi strategi ini. Default: abaikan
+    fn on_position(&mut self, _snap: &InvSnapshot, _own: &VenuePosition) {}
+
+    /// Pengali threshold entry untuk tick berikutnya (>= 1.0; MD_ANOMALY=widen). Default: abaikan
+    fn set_threshold_mult(&mut self, _mult: f64) {}
+}
+
+/// Threshold integer (tick) dikali pengali anomali; pengali 0 (state lama tanpa field) = 1
+fn widened(v: i64, mult: f64) -> i64 {
+    (v as f64 * mult.max(1.0)).round() as i64
+}
+
+/// Loop umum worker strategi: MdTick -> Signal, publish state ke snapshot tiap detik
+async fn drive<S: TickStrategy>(
+    mut st: S,
+    ctx: WorkerCtx,
+    md_rx: TickRx,
+    sig_tx: mpsc::Sender<Signal>,
+) {
+    let key = ctx.key();
+    let gaps = GapDetector::new(format!("strategy:{key}"));
+    let mut md_rx = Conflated::new(md_rx, gaps, ctx.strategy, ctx.max_tps);
+    let mut bars = ctx.bar_secs.map(Aggregator::new);
+    // ts_ns snapshot posisi terakhir yang sudah diteruskan ke strategi, per symbol
+    let mut seen_pos: HashMap<String, i128> = HashMap::new();
+    let mut publish = interval(Duration::from_secs(1));
+    publish.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            md = md_rx.recv() => match md {
+                Ok(t) => {
+                    // bar-close: strategi hanya melihat tick terakhir tiap candle yang sudah close
+                    let (eval, ts_ns) = match &mut bars {
+                        Some(agg) => match agg.on_tick(&t) {
+                            Some((c, last)) => {
+                                debug!(symbol = %c.symbol, start_ns = %c.start_ns, o = c.open, h = c.high, l = c.low, c = c.close, ticks = c.ticks, "bar close");
+                                (last, t.tick.ts_ns)
+                            }
+                            None => continue,
+                        },
+                        None => (t, 0),
+                    };
+                    if let Some(snap) = ctx.positions.get(&eval.tick.symbol) {
+                        if seen_pos.insert(snap.symbol.clone(), snap.ts_ns) != Some(snap.ts_ns) {
+                            let own = snap.state.by_strategy.get(ctx.strategy).cloned().unwrap_or_default();
+                            st.on_position(&snap, &own);
+                        }
+                    }
+                    st.set_threshold_mult(ctx.anomaly.threshold_mult(&eval.tick.symbol, eval.tick.ts_ns));
+                    // state strategi tetap di-update walau symbol sedang tidak bisa ditradingkan
+                    if let Some(mut sig) = st.on_enriched(&eval).filter(|_| ctx.symbols.tradable(&eval.tick.symbol)) {
+                        if ts_ns > 0 {
+                            sig.ts_ns = ts_ns;
+                        }
+                        sig.strategy = ctx.strategy.to_string();
+                        sig.tags = ctx.tags.clone();
+                        sig.book = ctx.book.clone();
+                        sig.quote_notional = ctx.quote_notional;
+                        if let Err(e) = sig_tx.send(sig).await { error!(?e, "signal send failed"); }
+                        else { SIGNALS.inc(); }
+                    }
+                },
+                Err(e) => warn!(?e, "md channel closed"),
+            },
+            _ = publish.tick(), if ctx.snaps.enabled() => ctx.snaps.put_strategy(&key, &st),
+        }
+    }
+}
+
 // -----------------------------------------------------------------------------
 // 1) MEAN-REVERSION (default)
 //    Ide: jika harga saat ini (ask) < rata-rata N-bar - edge  -> Buy
@@ -38,20 +191,25 @@ fn mid_price(md: &MdTick) -> i64 {
 //    Risiko:
 //      - Saat trending kuat, bisa melawan arus (perlu risk guard di modul risk).
 // -----------------------------------------------------------------------------
+#[derive(Serialize, Deserialize)]
 pub struct StratState {
     window: VecDeque<i64>,
     sum: i64,
     edge: i64,
     w: usize,
+    #[serde(skip)]
+    thr_mult: f64,
 }
 impl StratState {
     pub fn new(w: usize, edge: i64) -> Self {
-        Self { window: VecDeque::with_capacity(w), sum: 0, edge, w }
+        Self { window: VecDeque::with_capacity(w), sum: 0, edge, w, thr_mult: 1.0 }
     }
     fn fair(&self) -> Option<i64> {
         if self.window.len() >= self.w { Some(self.sum / self.w as i64) } else { None }
     }
-    pub fn on_tick(&mut self, md: &MdTick) -> Option<Signal> {
+}
+impl TickStrategy for StratState {
+    fn on_tick(&mut self, md: &MdTick) -> Option<Signal> {
         if self.window.len() == self.w {
             if let Some(x) = self.window.pop_front() { self.sum -= x; }
         }
@@ -60,31 +218,26 @@ impl StratState {
         self.sum += mid;
 
         if let Some(fair) = self.fair() {
-            if md.best_ask < fair - self.edge {
-                return Some(Signal { ts_ns: md.ts_ns, symbol: md.symbol.clone(), side: Side::Buy,  px: md.best_ask, qty: 10 });
+            let edge = widened(self.edge, self.thr_mult);
+            if md.best_ask < fair - edge {
+                return Some(signal(md, Side::Buy, md.best_ask));
             }
-            if md.best_bid > fair + self.edge {
-                return Some(Signal { ts_ns: md.ts_ns, symbol: md.symbol.clone(), side: Side::Sell, px: md.best_bid, qty: 10 });
+            if md.best_bid > fair + edge {
+                return Some(signal(md, Side::Sell, md.best_bid));
             }
         }
         None
     }
+
+    fn set_threshold_mult(&mut self, mult: f64) {
+        self.thr_mult = mult;
+    }
 }
 
-pub async fn run(mut md_rx: broadcast::Receiver<MdTick>, sig_tx: mpsc::Sender<Signal>) {
+pub async fn run(md_rx: TickRx, sig_tx: mpsc::Sender<Signal>, ctx: WorkerCtx) {
     // Parameter default: MA window 64, edge 3 tick
-    let mut st = StratState::new(64, 3);
-    loop {
-        match md_rx.recv().await {
-            Ok(md) => {
-                if let Some(sig) = st.on_tick(&md) {
-                    if let Err(e) = sig_tx.send(sig).await { error!(?e, "signal send failed"); }
-                    else { SIGNALS.inc(); }
-                }
-            },
-            Err(e) => warn!(?e, "md channel closed"),
-        }
-    }
+    let st = ctx.snaps.take_strategy(&ctx.key()).unwrap_or_else(|| StratState::new(64, 3));
+    drive(st, ctx, md_rx, sig_tx).await;
 }
 
 // -----------------------------------------------------------------------------
@@ -101,6 +254,7 @@ pub async fn run(mut md_rx: broadcast::Receiver<MdTick>, sig_tx: mpsc::Sender<Si
 //    Risiko:
 //      - Choppy/ranging market bisa menghasilkan whipsaw (perlu risk/cooldown).
 // -----------------------------------------------------------------------------
+#[derive(Serialize, Deserialize)]
 pub struct MACrossState {
     fast_w: usize,
     slow_w: usize,
@@ -112,6 +266,8 @@ pub struct MACrossState {
     min_edge: i64,      // threshold selisih min agar dianggap valid cross
     cooldown_ticks: u32,
     since_last: u32,
+    #[serde(skip)]
+    thr_mult: f64,
 }
 impl MACrossState {
     pub fn new(fast_w: usize, slow_w: usize, min_edge: i64, cooldown_ticks: u32) -> Self {
@@ -126,6 +282,7 @@ impl MACrossState {
             min_edge,
             cooldown_ticks,
             since_last: cooldown_ticks, // mulai bisa sinyal
+            thr_mult: 1.0,
         }
     }
     fn push_window(win: &mut VecDeque<i64>, sum: &mut i64, cap: usize, v: i64) {
@@ -138,7 +295,9 @@ impl MACrossState {
     fn sma(sum: i64, len: usize) -> Option<i64> {
         if len > 0 { Some(sum / len as i64) } else { None }
     }
-    pub fn on_tick(&mut self, md: &MdTick) -> Option<Signal> {
+}
+impl TickStrategy for MACrossState {
+    fn on_tick(&mut self, md: &MdTick) -> Option<Signal> {
         let m = mid_price(md);
         Self::push_window(&mut self.fast_win, &mut self.fast_sum, self.fast_w, m);
         Self::push_window(&mut self.slow_win, &mut self.slow_sum, self.slow_w, m);
@@ -153,7 +312,7 @@ impl MACrossState {
         let diff = fast - slow;
 
         // Edge filter: abaikan diff terlalu kecil (noise)
-        if diff.abs() < self.min_edge { return None; }
+        if diff.abs() < widened(self.min_edge, self.thr_mult) { return None; }
 
         // Hitung sign sekarang
         let cur_sign: i8 = if diff > 0 { 1 } else { -1 };
@@ -165,10 +324,10 @@ impl MACrossState {
 
             if cur_sign > 0 {
                 // Golden cross -> Buy di best_ask
-                return Some(Signal { ts_ns: md.ts_ns, symbol: md.symbol.clone(), side: Side::Buy,  px: md.best_ask, qty: 10 });
+                return Some(signal(md, Side::Buy, md.best_ask));
             } else {
                 // Dead cross -> Sell di best_bid
-                return Some(Signal { ts_ns: md.ts_ns, symbol: md.symbol.clone(), side: Side::Sell, px: md.best_bid, qty: 10 });
+                return Some(signal(md, Side::Sell, md.best_bid));
             }
         }
 
@@ -178,22 +337,16 @@ impl MACrossState {
         }
         None
     }
+
+    fn set_threshold_mult(&mut self, mult: f64) {
+        self.thr_mult = mult;
+    }
 }
 
-pub async fn run_ma_crossover(mut md_rx: broadcast::Receiver<MdTick>, sig_tx: mpsc::Sender<Signal>) {
+pub async fn run_ma_crossover(md_rx: TickRx, sig_tx: mpsc::Sender<Signal>, ctx: WorkerCtx) {
     // Parameter default: fast=16, slow=64, min_edge=2 tick, cooldown=16 ticks
-    let mut st = MACrossState::new(16, 64, 2, 16);
-    loop {
-        match md_rx.recv().await {
-            Ok(md) => {
-                if let Some(sig) = st.on_tick(&md) {
-                    if let Err(e) = sig_tx.send(sig).await { error!(?e, "signal send failed"); }
-                    else { SIGNALS.inc(); }
-                }
-            },
-            Err(e) => warn!(?e, "md channel closed"),
-        }
-    }
+    let st = ctx.snaps.take_strategy(&ctx.key()).unwrap_or_else(|| MACrossState::new(16, 64, 2, 16));
+    drive(st, ctx, md_rx, sig_tx).await;
 }
 
 // -----------------------------------------------------------------------------
@@ -208,6 +361,7 @@ pub async fn run_ma_crossover(mut md_rx: broadcast::Receiver<MdTick>, sig_tx: mp
 //    Risiko:
 //      - False breakout ketika market cepat kembali ke dalam range.
 // -----------------------------------------------------------------------------
+#[derive(Serialize, Deserialize)]
 pub struct VolBreakoutState {
     w: usize,
     edge: i64,
@@ -217,6 +371,8 @@ pub struct VolBreakoutState {
     // Optional cooldown supaya tak spam sinyal
     cooldown_ticks: u32,
     since_last: u32,
+    #[serde(skip)]
+    thr_mult: f64,
 }
 impl VolBreakoutState {
     pub fn new(w: usize, edge: i64, cooldown_ticks: u32) -> Self {
@@ -228,6 +384,7 @@ impl VolBreakoutState {
             rolling_low: i64::MAX / 4,
             cooldown_ticks,
             since_last: cooldown_ticks,
+            thr_mult: 1.0,
         }
     }
     fn recompute_hilo(win: &VecDeque<i64>) -> (i64, i64) {
@@ -239,7 +396,9 @@ impl VolBreakoutState {
         }
         (hi, lo)
     }
-    pub fn on_tick(&mut self, md: &MdTick) -> Option<Signal> {
+}
+impl TickStrategy for VolBreakoutState {
+    fn on_tick(&mut self, md: &MdTick) -> Option<Signal> {
         self.since_last = self.since_last.saturating_add(1);
 
         let m = mid_price(md);
@@ -260,33 +419,283 @@ impl VolBreakoutState {
 
         // Sinyal breakout + buffer edge + cooldown
         if self.since_last >= self.cooldown_ticks {
-            if m > self.rolling_high + self.edge {
+            let edge = widened(self.edge, self.thr_mult);
+            if m > self.rolling_high + edge {
                 self.since_last = 0;
                 // Buy pada momentum break di best_ask
-                return Some(Signal { ts_ns: md.ts_ns, symbol: md.symbol.clone(), side: Side::Buy,  px: md.best_ask, qty: 10 });
+                return Some(signal(md, Side::Buy, md.best_ask));
             }
-            if m < self.rolling_low - self.edge {
+            if m < self.rolling_low - edge {
                 self.since_last = 0;
                 // Sell pada momentum break di best_bid
-                return Some(Signal { ts_ns: md.ts_ns, symbol: md.symbol.clone(), side: Side::Sell, px: md.best_bid, qty: 10 });
+                return Some(signal(md, Side::Sell, md.best_bid));
             }
         }
         None
     }
+
+    fn set_threshold_mult(&mut self, mult: f64) {
+        self.thr_mult = mult;
+    }
 }
 
-pub async fn run_vol_breakout(mut md_rx: broadcast::Receiver<MdTick>, sig_tx: mpsc::Sender<Signal>) {
+pub async fn run_vol_breakout(md_rx: TickRx, sig_tx: mpsc::Sender<Signal>, ctx: WorkerCtx) {
     // Parameter default: window=100, edge=5 tick, cooldown=20 ticks
-    let mut st = VolBreakoutState::new(100, 5, 20);
-    loop {
-        match md_rx.recv().await {
-            Ok(md) => {
-                if let Some(sig) = st.on_tick(&md) {
-                    if let Err(e) = sig_tx.send(sig).await { error!(?e, "signal send failed"); }
-                    else { SIGNALS.inc(); }
+    let st = ctx.snaps.take_strategy(&ctx.key()).unwrap_or_else(|| VolBreakoutState::new(100, 5, 20));
+    drive(st, ctx, md_rx, sig_tx).await;
+}
+
+// -----------------------------------------------------------------------------
+// 4) BOLLINGER BAND (Mean-Reversion)
+//    Ide: band = SMA(w) ± k × stdev(w) (RollingStats di indicators.rs)
+//         mid menyentuh band bawah -> Buy (entry long), band atas -> Sell (entry short)
+//         posisi ditutup saat mid kembali ke mean (long: mid >= mean, short: mid <= mean).
+//    Kapan cocok:
+//      - Pasar ranging dengan volatilitas stabil; band melebar otomatis saat volatil.
+//    Parameter (ENV):
+//      - BOLLINGER_WINDOW=20, BOLLINGER_K=2.0, BOLLINGER_COOLDOWN=10 (tick/bar antar entry)
+//    Risiko:
+//      - Breakout/trend kuat: harga "berjalan" di sepanjang band (perlu stop di modul risk).
+// -----------------------------------------------------------------------------
+#[derive(Debug, Clone, Serialize)]
+pub struct BollingerCfg {
+    pub window: usize,
+    pub k: f64,
+    pub cooldown: u32,
+}
+
+impl BollingerCfg {
+    pub fn from_env() -> Self {
+        let var = |k: &str| std::env::var(k).ok();
+        Self {
+            window: var("BOLLINGER_WINDOW").and_then(|s| s.parse().ok()).unwrap_or(20),
+            k: var("BOLLINGER_K").and_then(|s| s.parse().ok()).unwrap_or(2.0),
+            cooldown: var("BOLLINGER_COOLDOWN").and_then(|s| s.parse().ok()).unwrap_or(10),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BollingerState {
+    stats: RollingStats,
+    k: f64,
+    pos: i8, // -1 short, 0 flat, +1 long (posisi versi strategi, bukan posisi venue)
+    cooldown_ticks: u32,
+    since_last: u32,
+    #[serde(skip)]
+    thr_mult: f64,
+}
+impl BollingerState {
+    pub fn new(cfg: &BollingerCfg) -> Self {
+        Self {
+            stats: RollingStats::new(cfg.window),
+            k: cfg.k,
+            pos: 0,
+            cooldown_ticks: cfg.cooldown,
+            since_last: cfg.cooldown,
+            thr_mult: 1.0,
+        }
+    }
+}
+impl TickStrategy for BollingerState {
+    fn on_tick(&mut self, md: &MdTick) -> Option<Signal> {
+        self.since_last = self.since_last.saturating_add(1);
+        let m = mid_price(md);
+        self.stats.push(m);
+        if !self.stats.full() {
+            return None;
+        }
+        let (mean, sd) = (self.stats.mean()?, self.stats.std()?);
+        let k = self.k * self.thr_mult.max(1.0);
+        let (upper, lower) = (mean + k * sd, mean - k * sd);
+        let mid = m as f64;
+
+        match self.pos {
+            // exit di mean
+            1 if mid >= mean => {
+                self.pos = 0;
+                Some(signal(md, Side::Sell, md.best_bid))
+            }
+            -1 if mid <= mean => {
+                self.pos = 0;
+                Some(signal(md, Side::Buy, md.best_ask))
+            }
+            // entry saat band tersentuh (band nol saat harga diam -> tidak ada entry)
+            0 if sd > 0.0 && self.since_last >= self.cooldown_ticks => {
+                if mid <= lower {
+                    self.pos = 1;
+                    self.since_last = 0;
+                    Some(signal(md, Side::Buy, md.best_ask))
+                } else if mid >= upper {
+                    self.pos = -1;
+                    self.since_last = 0;
+                    Some(signal(md, Side::Sell, md.best_bid))
+                } else {
+                    None
                 }
-            },
-            Err(e) => warn!(?e, "md channel closed"),
+            }
+            _ => None,
+        }
+    }
+
+    fn set_threshold_mult(&mut self, mult: f64) {
+        self.thr_mult = mult;
+    }
+}
+
+pub async fn run_bollinger(md_rx: TickRx, sig_tx: mpsc::Sender<Signal>, ctx: WorkerCtx, cfg: BollingerCfg) {
+    let st = ctx.snaps.take_strategy(&ctx.key()).unwrap_or_else(|| BollingerState::new(&cfg));
+    drive(st, ctx, md_rx, sig_tx).await;
+}
+
+// -----------------------------------------------------------------------------
+// 5) DONCHIAN CHANNEL + ATR TRAILING STOP (Trend-Following)
+//    Ide: mid menembus high N-bar sebelumnya -> Buy (entry long), low N-bar -> Sell (entry short).
+//         Exit: trailing stop = harga ekstrem sejak entry ∓ atr_mult × ATR; tersentuh -> tutup posisi.
+//    Posisi diambil dari umpan balik posisi (on_position, by_strategy), bukan ditebak dari Signal:
+//      - entry hanya saat posisi strategi flat, exit dengan qty = posisi terbuka
+//      - setelah Signal, strategi menunggu fill (snapshot posisi baru) sebelum Signal berikutnya;
+//        jika tidak ada fill dalam `retry_ticks` tick/bar (order ditolak / expired) boleh mencoba lagi.
+//    Parameter (ENV):
+//      - DONCHIAN_WINDOW=55, DONCHIAN_ATR_WINDOW=14, DONCHIAN_ATR_MULT=3.0, DONCHIAN_RETRY_TICKS=50
+//    Risiko:
+//      - Whipsaw di pasar ranging; qty exit tetap melewati sizing risk (STRATEGY_QUOTE_NOTIONAL /
+//        alokasi) sehingga bisa tidak menutup penuh -> strategi mengirim exit lagi setelah fill.
+// -----------------------------------------------------------------------------
+#[derive(Debug, Clone, Serialize)]
+pub struct DonchianCfg {
+    pub window: usize,
+    pub atr_window: usize,
+    pub atr_mult: f64,
+    pub retry_ticks: u32,
+}
+
+impl DonchianCfg {
+    pub fn from_env() -> Self {
+        let var = |k: &str| std::env::var(k).ok();
+        Self {
+            window: var("DONCHIAN_WINDOW").and_then(|s| s.parse().ok()).unwrap_or(55),
+            atr_window: var("DONCHIAN_ATR_WINDOW").and_then(|s| s.parse().ok()).unwrap_or(14),
+            atr_mult: var("DONCHIAN_ATR_MULT").and_then(|s| s.parse().ok()).unwrap_or(3.0),
+            retry_ticks: var("DONCHIAN_RETRY_TICKS").and_then(|s| s.parse().ok()).unwrap_or(50),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DonchianState {
+    channel: Channel,
+    atr: Atr,
+    atr_mult: f64,
+    pos: i64,     // posisi strategi (lot) dari on_position
+    extreme: i64, // mid tertinggi (long) / terendah (short) sejak entry
+    retry_ticks: u32,
+    pending: u32, // >0: menunggu fill Signal terakhir (hitung mundur per tick)
+}
+impl DonchianState {
+    pub fn new(cfg: &DonchianCfg) -> Self {
+        Self {
+            channel: Channel::new(cfg.window),
+            atr: Atr::new(cfg.atr_window),
+            atr_mult: cfg.atr_mult,
+            pos: 0,
+            extreme: 0,
+            retry_ticks: cfg.retry_ticks.max(1),
+            pending: 0,
         }
     }
+
+    fn send(&mut self, md: &MdTick, side: Side, qty: i64) -> Option<Signal> {
+        self.pending = self.retry_ticks;
+        let px = if matches!(side, Side::Buy) { md.best_ask } else { md.best_bid };
+        let mut sig = signal(md, side, px);
+        if qty > 0 {
+            sig.qty = qty;
+        }
+        Some(sig)
+    }
+}
+impl TickStrategy for DonchianState {
+    fn on_tick(&mut self, md: &MdTick) -> Option<Signal> {
+        let m = mid_price(md);
+        // channel dari N bar SEBELUMNYA (bar sekarang belum masuk)
+        let bounds = self.channel.full().then(|| self.channel.bounds()).flatten();
+        self.channel.push(m);
+        self.atr.push(m, m, m);
+        self.pending = self.pending.saturating_sub(1);
+        if self.pending > 0 {
+            return None;
+        }
+
+        if self.pos != 0 {
+            let atr = self.atr.value()?;
+            let trail = self.atr_mult * atr;
+            if self.pos > 0 {
+                self.extreme = self.extreme.max(m);
+                if (m as f64) <= self.extreme as f64 - trail {
+                    return self.send(md, Side::Sell, self.pos);
+                }
+            } else {
+                self.extreme = self.extreme.min(m);
+                if (m as f64) >= self.extreme as f64 + trail {
+                    return self.send(md, Side::Buy, -self.pos);
+                }
+            }
+            return None;
+        }
+
+        let (hi, lo) = bounds?;
+        if m > hi {
+            self.extreme = m;
+            return self.send(md, Side::Buy, 0);
+        }
+        if m < lo {
+            self.extreme = m;
+            return self.send(md, Side::Sell, 0);
+        }
+        None
+    }
+
+    fn on_position(&mut self, _snap: &InvSnapshot, own: &VenuePosition) {
+        if own.qty.signum() != self.pos.signum() && own.qty != 0 {
+            // posisi baru / berbalik arah: trailing mulai dari harga rata-rata entry
+            self.extreme = own.avg_cost_px;
+        }
+        self.pos = own.qty;
+        self.pending = 0;
+    }
+}
+
+pub async fn run_donchian(md_rx: TickRx, sig_tx: mpsc::Sender<Signal>, ctx: WorkerCtx, cfg: DonchianCfg) {
+    let st = ctx.snaps.take_strategy(&ctx.key()).unwrap_or_else(|| DonchianState::new(&cfg));
+    drive(st, ctx, md_rx, sig_tx).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn md(mid: i64) -> MdTick {
+        MdTick { ts_ns: 0, symbol: "BTCUSDT".into(), best_bid: mid - 1, best_ask: mid + 1, bid_qty: 0, ask_qty: 0, seq: 0, exch_ts_ns: 0 }
+    }
+
+    #[test]
+    fn donchian_exits_open_position_on_atr_trailing_stop() {
+        let cfg = DonchianCfg { window: 3, atr_window: 3, atr_mult: 1.0, retry_ticks: 5 };
+        let mut st = DonchianState::new(&cfg);
+        for m in [100, 102, 100, 102] {
+            assert!(st.on_tick(&md(m)).is_none());
+        }
+        let entry = st.on_tick(&md(110)).expect("breakout above channel high");
+        assert!(matches!(entry.side, Side::Buy));
+        // menunggu fill: tidak ada Signal baru
+        assert!(st.on_tick(&md(120)).is_none());
+
+        // fill 4 lot @110 -> trailing dari harga entry, ekstrem 121; ATR ~7 -> stop ~114
+        st.on_position(&InvSnapshot::default(), &VenuePosition { qty: 4, avg_cost_px: 110, realized_pnl: 0 });
+        assert!(st.on_tick(&md(121)).is_none());
+        let exit = st.on_tick(&md(108)).expect("trailing stop hit");
+        assert!(matches!(exit.side, Side::Sell));
+        assert_eq!(exit.qty, 4);
+    }
 }
@@ -0,0 +1,102 @@
+// ===============================
+// src/supervisor.rs
+// ===============================
+//
+// Watchdog untuk task yang di-spawn main:
+// - `spawn(name, factory)` menjalankan `factory()` sebagai task anak dan memegang JoinHandle-nya
+// - task anak selesai (return / panic) -> dilog, `task_restarts_total{task}` +1, lalu dibuat ulang
+//   dengan backoff eksponensial 0.5s..30s (reset jika task sempat jalan >= 30s)
+// - `Relay` membuat mpsc::Receiver bisa "dipakai ulang": receiver asli dipegang forwarder,
+//   setiap restart memanggil `attach()` untuk mendapat receiver baru. Pesan yang sudah masuk
+//   buffer task yang mati ikut hilang.
+//
+// Catatan: state in-memory task (mis. eksposur risk, posisi) mulai dari nol setelah restart.
+//
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, Duration, Instant};
+use tracing::{error, warn};
+
+use crate::metrics::TASK_RESTARTS;
+
+const BACKOFF_MIN: Duration = Duration::from_millis(500);
+const BACKOFF_MAX: Duration = Duration::from_secs(30);
+
+/// Jalankan task `name` di bawah supervisi (restart otomatis selamanya)
+pub fn spawn<F, Fut>(name: impl Into<String>, mut factory: F) -> JoinHandle<()>
+where
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let name = name.into();
+    tokio::spawn(async move {
+        let mut backoff = BACKOFF_MIN;
+        loop {
+            let started = Instant::now();
+            match tokio::spawn(factory()).await {
+                Ok(()) => warn!(task = %name, "supervisor: task exited"),
+                Err(e) if e.is_panic() => error!(task = %name, "supervisor: task panicked"),
+                Err(e) => error!(task = %name, err = %e, "supervisor: task cancelled"),
+            }
+            if started.elapsed() >= BACKOFF_MAX {
+                backoff = BACKOFF_MIN;
+            }
+            TASK_RESTARTS.with_label_values(&[&name]).inc();
+            warn!(task = %name, backoff_ms = backoff.as_millis() as u64, "supervisor: restarting task");
+            sleep(backoff).await;
+            backoff = (backoff * 2).min(BACKOFF_MAX);
+        }
+    })
+}
+
+/// Receiver yang bisa di-attach ulang oleh task yang di-restart
+pub struct Relay<T> {
+    cur: Arc<Mutex<mpsc::Sender<T>>>,
+    first: Mutex<Option<mpsc::Receiver<T>>>,
+    cap: usize,
+}
+
+impl<T: Send + 'static> Relay<T> {
+    pub fn new(mut rx: mpsc::Receiver<T>, cap: usize) -> Arc<Self> {
+        let (tx, first) = mpsc::channel(cap);
+        let cur = Arc::new(Mutex::new(tx));
+        let fwd = cur.clone();
+        tokio::spawn(async move {
+            while let Some(m) = rx.recv().await {
+                let tx = fwd.lock().unwrap().clone();
+                // gagal = task tujuan sedang mati; pesan dibuang
+                let _ = tx.send(m).await;
+            }
+        });
+        Arc::new(Self { cur, first: Mutex::new(Some(first)), cap })
+    }
+
+    /// Pesan yang antre di channel internal (belum dibaca task saat ini)
+    pub fn depth(&self) -> usize {
+        let tx = self.cur.lock().unwrap();
+        tx.max_capacity() - tx.capacity()
+    }
+
+    /// Receiver untuk instance task berikutnya (pertama kali: receiver awal)
+    pub fn attach(&self) -> mpsc::Receiver<T> {
+        if let Some(rx) = self.first.lock().unwrap().take() {
+            return rx;
+        }
+        let (tx, rx) = mpsc::channel(self.cap);
+        *self.cur.lock().unwrap() = tx;
+        rx
+    }
+}
+
+/// Batalkan task saat handle di-drop (task anak ikut mati bersama induknya)
+#[cfg(feature = "binance")]
+pub struct AbortOnDrop(pub JoinHandle<()>);
+
+#[cfg(feature = "binance")]
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
@@ -0,0 +1,155 @@
+// ===============================
+// src/symbol_registry.rs
+// ===============================
+//
+// Cache metadata symbol dari GET /api/v3/exchangeInfo (public), di-refresh berkala:
+// - status (TRADING / BREAK / HALT / ...), izin spot trading
+// - filter: tickSize (PRICE_FILTER), stepSize (LOT_SIZE), minNotional (NOTIONAL/MIN_NOTIONAL)
+//
+// Perubahan status dilog + dipublish ke `symbol_tradable{symbol}`. Strategi berhenti
+// mengirim Signal dan risk menolak order untuk symbol yang tidak TRADING.
+// Symbol yang belum ada di cache (mode mock / sebelum refresh pertama) dianggap tradable.
+//
+// ENV: SYMBOL_REFRESH_SECS=300 (0 = off). Hanya aktif jika feed/venue Binance.
+//
+use ahash::AHashMap as HashMap;
+use serde::Serialize;
+use std::sync::{Arc, RwLock};
+#[cfg(feature = "binance")]
+use tokio::time::{interval, Duration, MissedTickBehavior};
+#[cfg(feature = "binance")]
+use tracing::{info, warn};
+
+#[cfg(feature = "binance")]
+use crate::exchange_http::{endpoints, ExchangeHttp, HttpError};
+#[cfg(feature = "binance")]
+use crate::metrics::SYMBOL_TRADABLE;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SymbolInfo {
+    pub status: String,
+    pub spot_allowed: bool,
+    pub tick_size: f64,
+    pub step_size: f64,
+    pub min_notional: f64,
+}
+
+impl SymbolInfo {
+    pub fn tradable(&self) -> bool {
+        self.status == "TRADING" && self.spot_allowed
+    }
+
+    #[cfg(feature = "binance")]
+    pub fn from_json(v: &serde_json::Value) -> Self {
+        let filter = |kind: &str, key: &str| {
+            v.get("filters")
+                .and_then(|f| f.as_array())
+                .and_then(|fs| fs.iter().find(|f| f.get("filterType").and_then(|t| t.as_str()) == Some(kind)))
+                .and_then(|f| f.get(key))
+                .and_then(|x| x.as_str())
+                .and_then(|s| s.parse::<f64>().ok())
+        };
+        Self {
+            status: v.get("status").and_then(|x| x.as_str()).unwrap_or("UNKNOWN").to_string(),
+            spot_allowed: v.get("isSpotTradingAllowed").and_then(|x| x.as_bool()).unwrap_or(true),
+            tick_size: filter("PRICE_FILTER", "tickSize").unwrap_or(0.0),
+            step_size: filter("LOT_SIZE", "stepSize").unwrap_or(0.0),
+            min_notional: filter("NOTIONAL", "minNotional").or_else(|| filter("MIN_NOTIONAL", "minNotional")).unwrap_or(0.0),
+        }
+    }
+}
+
+/// Handle registry (diisi task refresh, dibaca strategi / risk / admin)
+#[derive(Clone, Default)]
+pub struct SymbolRegistry {
+    inner: Arc<RwLock<HashMap<String, SymbolInfo>>>,
+}
+
+impl SymbolRegistry {
+    /// false hanya jika exchange melaporkan symbol tidak bisa ditradingkan
+    pub fn tradable(&self, symbol: &str) -> bool {
+        self.inner.read().unwrap().get(symbol).is_none_or(|i| i.tradable())
+    }
+
+    /// Status symbol jika diketahui
+    pub fn status(&self, symbol: &str) -> Option<String> {
+        self.inner.read().unwrap().get(symbol).map(|i| i.status.clone())
+    }
+
+    /// Salinan seluruh cache (admin API)
+    #[cfg(feature = "admin-api")]
+    pub fn snapshot(&self) -> std::collections::BTreeMap<String, SymbolInfo> {
+        self.inner.read().unwrap().iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+
+    /// Terapkan hasil refresh; log & publish perubahan
+    #[cfg(feature = "binance")]
+    fn update(&self, fresh: HashMap<String, SymbolInfo>) {
+        let mut g = self.inner.write().unwrap();
+        for (symbol, info) in fresh {
+            let prev = g.get(&symbol);
+            if prev.map(|p| p.status.as_str()) != Some(info.status.as_str()) || prev.map(|p| p.spot_allowed) != Some(info.spot_allowed) {
+                let from = prev.map(|p| p.status.clone()).unwrap_or_else(|| "-".into());
+                if info.tradable() {
+                    info!(%symbol, %from, to = %info.status, "symbol registry: tradable");
+                } else {
+                    warn!(%symbol, %from, to = %info.status, spot_allowed = info.spot_allowed, "symbol registry: trading stopped");
+                }
+            }
+            if crate::metrics::symbol_admitted(&symbol) {
+                SYMBOL_TRADABLE.with_label_values(&[&symbol]).set(info.tradable() as i64);
+            }
+            crate::exch_format::set_filters(&symbol, info.tick_size, info.step_size);
+            g.insert(symbol, info);
+        }
+    }
+}
+
+/// GET exchangeInfo untuk `symbols` (symbol -> info)
+#[cfg(feature = "binance")]
+pub async fn fetch(http: &ExchangeHttp, symbols: &[String]) -> Result<HashMap<String, SymbolInfo>, HttpError> {
+    let list = serde_json::to_string(symbols).unwrap_or_default();
+    let v = http.send(&endpoints::exchange_info(), &[("symbols", list)]).await?;
+    Ok(v
+        .get("symbols")
+        .and_then(|x| x.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|s| Some((s.get("symbol")?.as_str()?.to_string(), SymbolInfo::from_json(s))))
+        .collect())
+}
+
+/// Symbol dari `symbols` yang terdaftar & TRADING, dicek satu per satu
+/// (exchangeInfo menolak seluruh daftar dengan -1121 jika satu symbol tidak dikenal)
+#[cfg(feature = "binance")]
+pub async fn tradable_symbols(http: &ExchangeHttp, symbols: &[String]) -> Result<ahash::AHashSet<String>, HttpError> {
+    let all = match fetch(http, symbols).await {
+        Ok(m) => m,
+        Err(e) if e.binance().map(|b| b.code()) == Some(-1121) => {
+            let mut m = HashMap::new();
+            for s in symbols {
+                if let Ok(one) = fetch(http, std::slice::from_ref(s)).await {
+                    m.extend(one);
+                }
+            }
+            m
+        }
+        Err(e) => return Err(e),
+    };
+    Ok(all.into_iter().filter(|(_, i)| i.tradable()).map(|(s, _)| s).collect())
+}
+
+/// Task refresh exchangeInfo untuk `symbols`
+#[cfg(feature = "binance")]
+pub async fn run(http: ExchangeHttp, symbols: Vec<String>, interval_secs: u64, registry: SymbolRegistry) {
+    info!(interval_secs, ?symbols, "symbol registry: started");
+    let mut tick = interval(Duration::from_secs(interval_secs.max(1)));
+    tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        tick.tick().await;
+        match fetch(&http, &symbols).await {
+            Ok(fresh) => registry.update(fresh),
+            Err(e) => warn!(err = %e, "symbol registry: GET /api/v3/exchangeInfo failed"),
+        }
+    }
+}
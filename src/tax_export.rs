@@ -0,0 +1,183 @@
+// ===============================
+// src/tax_export.rs
+// ===============================
+//
+// Export fill ke CSV ternormalisasi untuk pajak / akuntansi (subcommand `export-fills`).
+//
+// Sumber:
+// - blotter   : file BLOTTER_FILE (JSONL Fill)
+// - recording : file RECORD_FILE; ExecReport diputar ulang lewat Blotter (delta fill + fee estimasi)
+//
+// Format:
+// - normalized : timestamp,symbol,base,quote,side,qty,price,fee,fee_currency,venue,order_id
+// - koinly     : "universal" CSV (Sent/Received/Fee), juga diterima CoinTracking/Accointing sebagai custom import
+//
+// Qty dalam unit base (lot × LOT_SIZES), harga & fee dalam quote. Fee = estimasi fee_bps venue
+// (sama dengan blotter), mata uang fee = quote. Waktu dalam UTC.
+//
+// Contoh (bisa dijadwalkan lewat cron):
+//   dma_bot_rust export-fills --source recording --file events.jsonl --out fills_2025.csv
+//
+use chrono::{TimeZone, Utc};
+use std::io::{BufRead, BufReader, Write};
+
+use crate::blotter::{Blotter, Fill};
+use crate::domain::{Event, Side};
+use crate::recorder::reader::{Filter, Reader};
+use crate::sizing;
+
+/// Suffix quote yang dikenali (yang lebih panjang dicek dulu: USDT sebelum USD)
+const QUOTES: &[&str] = &["FDUSD", "USDT", "USDC", "BUSD", "TUSD", "EUR", "TRY", "BTC", "ETH", "BNB", "USD"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Source {
+    Blotter,
+    Recording,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    Normalized,
+    Koinly,
+}
+
+/// "BTCUSDT" -> ("BTC", "USDT"); quote tidak dikenal -> (symbol, "")
+pub fn split_symbol(symbol: &str) -> (&str, &str) {
+    QUOTES
+        .iter()
+        .find(|q| symbol.len() > q.len() && symbol.ends_with(*q))
+        .map(|q| symbol.split_at(symbol.len() - q.len()))
+        .unwrap_or((symbol, ""))
+}
+
+/// Desimal tanpa notasi ilmiah & tanpa nol di belakang (maks 8 digit)
+fn dec(x: f64) -> String {
+    let s = format!("{x:.8}");
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+fn load_blotter(path: &str) -> std::io::Result<Vec<Fill>> {
+    let file = std::fs::File::open(path)?;
+    Ok(BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|l| serde_json::from_str::<Fill>(&l).ok())
+        .collect())
+}
+
+fn load_recording(path: &str, fee_bps: ahash::AHashMap<String, i32>) -> std::io::Result<Vec<Fill>> {
+    let filter = Filter { kinds: vec!["exec".to_string()], ..Default::default() };
+    let reader = Reader::open(path)?.with_filter(filter);
+    let blotter = Blotter::new(fee_bps, usize::MAX, None);
+    Ok(reader
+        .filter_map(|ev| match ev {
+            Event::Exec(er) => blotter.on_exec(&er),
+            _ => None,
+        })
+        .collect())
+}
+
+fn row(f: &Fill, side: Side, format: Format) -> String {
+    let (base, quote) = split_symbol(&f.symbol);
+    let qty = sizing::base_qty(&f.symbol, f.qty);
+    let px = f.px as f64 / 100.0;
+    let fee = f.fee as f64 / 100.0 * sizing::lot_size(&f.symbol);
+    let ts = Utc.timestamp_nanos(f.ts_ns as i64);
+    match format {
+        Format::Normalized => format!(
+            "{},{},{},{},{},{},{},{},{},{},{}",
+            ts.format("%Y-%m-%dT%H:%M:%S%.3fZ"),
+            f.symbol,
+            base,
+            quote,
+            side.as_str(),
+            dec(qty),
+            dec(px),
+            dec(fee),
+            quote,
+            f.venue,
+            f.cl_id
+        ),
+        Format::Koinly => {
+            let (sent, sent_cur, recv, recv_cur) = match side {
+                Side::Buy => (qty * px, quote, qty, base),
+                Side::Sell => (qty, base, qty * px, quote),
+            };
+            format!(
+                "{},{},{},{},{},{},{},,{} {},{}",
+                ts.format("%Y-%m-%d %H:%M:%S UTC"),
+                dec(sent),
+                sent_cur,
+                dec(recv),
+                recv_cur,
+                dec(fee),
+                quote,
+                f.venue,
+                f.symbol,
+                f.cl_id
+            )
+        }
+    }
+}
+
+fn header(format: Format) -> &'static str {
+    match format {
+        Format::Normalized => "timestamp,symbol,base,quote,side,qty,price,fee,fee_currency,venue,order_id",
+        Format::Koinly => {
+            "Date,Sent Amount,Sent Currency,Received Amount,Received Currency,Fee Amount,Fee Currency,Label,Description,TxHash"
+        }
+    }
+}
+
+/// Subcommand `export-fills`
+pub fn run(
+    source: Source,
+    path: &str,
+    out: Option<&str>,
+    format: Format,
+    range: (Option<i128>, Option<i128>),
+    fee_bps: ahash::AHashMap<String, i32>,
+) {
+    let fills = match source {
+        Source::Blotter => load_blotter(path),
+        Source::Recording => load_recording(path, fee_bps),
+    };
+    let mut fills = match fills {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("export-fills: open {path} failed: {e}");
+            std::process::exit(1);
+        }
+    };
+    let (since, until) = range;
+    fills.retain(|f| since.is_none_or(|t| f.ts_ns >= t) && until.is_none_or(|t| f.ts_ns < t));
+    fills.sort_by_key(|f| f.ts_ns);
+
+    let mut w: Box<dyn Write> = match out {
+        Some(p) => match std::fs::File::create(p) {
+            Ok(f) => Box::new(std::io::BufWriter::new(f)),
+            Err(e) => {
+                eprintln!("export-fills: create {p} failed: {e}");
+                std::process::exit(1);
+            }
+        },
+        None => Box::new(std::io::stdout().lock()),
+    };
+
+    let (mut written, mut no_side) = (0u64, 0u64);
+    let mut res = writeln!(w, "{}", header(format));
+    for f in &fills {
+        // fill lama tanpa side tidak bisa dibukukan (buy/sell)
+        let Some(side) = f.side else {
+            no_side += 1;
+            continue;
+        };
+        res = res.and_then(|_| writeln!(w, "{}", row(f, side, format)));
+        written += 1;
+    }
+    if let Err(e) = res.and_then(|_| w.flush()) {
+        eprintln!("export-fills: write failed: {e}");
+        std::process::exit(1);
+    }
+    eprintln!("export-fills: {written} fills written, {no_side} skipped (no side)");
+}
@@ -0,0 +1,250 @@
+// ===============================
+// src/trading_day.rs
+// ===============================
+//
+// Pergantian hari trading (kalender bursa) untuk run multi-hari:
+// - batas hari = TRADING_DAY_START pada zona TRADING_DAY_TZ (UTC atau offset lokal bursa, mis. +07:00)
+// - saat pergantian hari:
+//     1) snapshot akhir hari: PnL hari itu (USD, realized delta + unrealized), jumlah fill / volume / fee,
+//        posisi per symbol -> satu baris JSONL di TRADING_DAY_FILE + log info
+//     2) reset counter harian: gauge `daily_pnl_usd` / `daily_fills`, dan puncak equity portfolio stop
+//        (drawdown dihitung dari awal hari; stop yang sudah terpicu tetap terkunci sampai re-arm)
+// - tanggal hari trading = tanggal lokal saat sesi DIBUKA (start 17:00 -> sesi Senin 17:00..Selasa 17:00 = Senin)
+//
+// Catatan: fill dihitung dari blotter (dibatasi BLOTTER_MAX_FILLS di memori).
+//
+// ENV:
+//   TRADING_DAY_TZ=UTC               (UTC | +07:00 | -05:00)
+//   TRADING_DAY_START=00:00          (HH:MM waktu lokal)
+//   TRADING_DAY_FILE=daily.jsonl     (kosong = hanya log)
+//
+use chrono::{DateTime, Duration as ChronoDuration, FixedOffset, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::Serialize;
+use std::io::Write;
+use std::path::Path;
+use tokio::time::{interval, sleep, Duration, MissedTickBehavior};
+use tracing::{error, info};
+
+use crate::blotter::Blotter;
+use crate::metrics::{DAILY_FILLS, DAILY_PNL_USD, TRADING_DAY_ROLLOVERS};
+use crate::portfolio_stop::PortfolioStop;
+use crate::positions::PositionFeed;
+use crate::sizing;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TradingDayCfg {
+    #[serde(serialize_with = "crate::effective_config::display")]
+    pub tz: FixedOffset,
+    #[serde(serialize_with = "crate::effective_config::display")]
+    pub start: NaiveTime,
+    pub file: Option<String>,
+}
+
+impl Default for TradingDayCfg {
+    fn default() -> Self {
+        Self { tz: FixedOffset::east_opt(0).unwrap(), start: NaiveTime::MIN, file: None }
+    }
+}
+
+impl TradingDayCfg {
+    pub fn from_env() -> Self {
+        let var = |k: &str| std::env::var(k).unwrap_or_default().trim().to_string();
+        let d = Self::default();
+        let tz = var("TRADING_DAY_TZ");
+        let start = var("TRADING_DAY_START");
+        Self {
+            tz: if tz.is_empty() { d.tz } else { parse_offset(&tz).unwrap_or(d.tz) },
+            start: NaiveTime::parse_from_str(&start, "%H:%M").unwrap_or(d.start),
+            file: Some(var("TRADING_DAY_FILE")).filter(|s| !s.is_empty()),
+        }
+    }
+
+    /// (tanggal hari trading yang sedang berjalan, awal hari itu, awal hari berikutnya) untuk `now`
+    pub fn day_at(&self, now: DateTime<Utc>) -> (NaiveDate, DateTime<Utc>, DateTime<Utc>) {
+        let local = now.with_timezone(&self.tz).naive_local();
+        let date = (local - (self.start - NaiveTime::MIN)).date();
+        let open = |d: NaiveDate| self.tz.from_local_datetime(&d.and_time(self.start)).unwrap().with_timezone(&Utc);
+        (date, open(date), open(date + ChronoDuration::days(1)))
+    }
+}
+
+/// `UTC` / `Z` / `+07:00` / `-0500` / `+7`
+pub fn parse_offset(s: &str) -> Option<FixedOffset> {
+    let s = s.trim();
+    if s.eq_ignore_ascii_case("utc") || s.eq_ignore_ascii_case("z") {
+        return FixedOffset::east_opt(0);
+    }
+    let (sign, rest) = match s.as_bytes().first()? {
+        b'+' => (1, &s[1..]),
+        b'-' => (-1, &s[1..]),
+        _ => return None,
+    };
+    let (h, m) = match rest.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if rest.len() == 4 => rest.split_at(2),
+        None => (rest, "0"),
+    };
+    let (h, m): (i32, i32) = (h.parse().ok()?, m.parse().ok()?);
+    if h > 14 || m > 59 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (h * 3600 + m * 60))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DayPosition {
+    pub symbol: String,
+    pub qty: i64,
+    pub last_mid: i64,
+    pub realized_pnl: i64,
+    pub unrealized_pnl: i64,
+}
+
+/// Satu baris TRADING_DAY_FILE
+#[derive(Debug, Clone, Serialize)]
+pub struct DailyRecord {
+    pub date: String,
+    pub tz: String,
+    pub start_ns: i128,
+    pub end_ns: i128,
+    /// realized hari ini (delta) + perubahan unrealized, USD
+    pub pnl_usd: f64,
+    pub realized_usd: f64,
+    /// unrealized saat tutup hari (posisi yang dibawa ke hari berikutnya)
+    pub unrealized_usd: f64,
+    pub fills: usize,
+    pub volume: f64,
+    pub fees: f64,
+    pub positions: Vec<DayPosition>,
+}
+
+/// Baseline awal hari (PnL portofolio USD saat hari dibuka)
+#[derive(Debug, Clone, Copy, Default)]
+struct DayOpen {
+    realized: f64,
+    unrealized: f64,
+}
+
+impl DayOpen {
+    fn now() -> Self {
+        let (realized, unrealized) = crate::fx::portfolio_pnl();
+        Self { realized, unrealized }
+    }
+
+    /// (pnl hari ini, realized hari ini) relatif terhadap baseline
+    fn pnl(&self, realized: f64, unrealized: f64) -> (f64, f64) {
+        (realized + unrealized - self.realized - self.unrealized, realized - self.realized)
+    }
+}
+
+fn ns(t: DateTime<Utc>) -> i128 {
+    t.timestamp_nanos_opt().unwrap_or(0) as i128
+}
+
+fn close_day(cfg: &TradingDayCfg, date: NaiveDate, (start, end): (DateTime<Utc>, DateTime<Utc>), open: DayOpen, blotter: &Blotter, positions: &PositionFeed) -> DailyRecord {
+    let (start_ns, end_ns) = (ns(start), ns(end));
+    let fills: Vec<_> = blotter.query(None, None, Some(start_ns)).into_iter().filter(|f| f.ts_ns < end_ns).collect();
+    let (realized, unrealized) = crate::fx::portfolio_pnl();
+    let (pnl_usd, realized_usd) = open.pnl(realized, unrealized);
+    let mut pos: Vec<DayPosition> = positions
+        .all()
+        .iter()
+        .map(|s| DayPosition {
+            symbol: s.symbol.clone(),
+            qty: s.state.total_qty,
+            last_mid: s.state.last_mid,
+            realized_pnl: s.state.realized_pnl,
+            unrealized_pnl: s.state.unrealized_pnl,
+        })
+        .collect();
+    pos.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+    DailyRecord {
+        date: date.to_string(),
+        tz: cfg.tz.to_string(),
+        start_ns,
+        end_ns,
+        pnl_usd,
+        realized_usd,
+        unrealized_usd: unrealized,
+        volume: fills.iter().map(|f| sizing::base_qty(&f.symbol, f.qty) * f.px as f64 / 100.0).sum(),
+        fees: fills.iter().map(|f| f.fee as f64 / 100.0 * sizing::lot_size(&f.symbol)).sum(),
+        fills: fills.len(),
+        positions: pos,
+    }
+}
+
+fn persist(path: &str, rec: &DailyRecord) {
+    if let Some(parent) = Path::new(path).parent() {
+        if !parent.as_os_str().is_empty() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+    }
+    let res = serde_json::to_string(rec).map_err(std::io::Error::other).and_then(|line| {
+        let mut f = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(f, "{line}")
+    });
+    if let Err(e) = res {
+        error!(?e, %path, date = %rec.date, "trading day: write daily record failed");
+    }
+}
+
+/// Task: update gauge harian tiap 5s; di batas hari tulis rekap + reset counter
+pub async fn run(cfg: TradingDayCfg, blotter: Blotter, positions: PositionFeed, stop: PortfolioStop) {
+    let (mut date, mut start, mut end) = cfg.day_at(Utc::now());
+    let mut open = DayOpen::now();
+    info!(date = %date, tz = %cfg.tz, next = %end, "trading day: started");
+    let mut tick = interval(Duration::from_secs(5));
+    tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        let wait = (end - Utc::now()).to_std().unwrap_or_default();
+        tokio::select! {
+            _ = tick.tick() => {
+                let (r, u) = crate::fx::portfolio_pnl();
+                DAILY_PNL_USD.set(open.pnl(r, u).0);
+                DAILY_FILLS.set(blotter.query(None, None, Some(ns(start))).len() as i64);
+                continue;
+            }
+            _ = sleep(wait) => {}
+        }
+        let rec = close_day(&cfg, date, (start, end), open, &blotter, &positions);
+        info!(date = %rec.date, pnl_usd = rec.pnl_usd, realized_usd = rec.realized_usd, fills = rec.fills, "trading day closed");
+        if let Some(path) = &cfg.file {
+            persist(path, &rec);
+        }
+        TRADING_DAY_ROLLOVERS.inc();
+
+        (date, start, end) = cfg.day_at(end);
+        open = DayOpen::now();
+        DAILY_PNL_USD.set(0.0);
+        DAILY_FILLS.set(0);
+        stop.new_day();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn day_bounds_follow_exchange_local_session_start() {
+        let at = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        let jkt = TradingDayCfg { tz: parse_offset("+07:00").unwrap(), ..Default::default() };
+        // 18:30Z = 01:30 WIB tanggal 17
+        let (date, open, next) = jkt.day_at(at("2026-10-16T18:30:00Z"));
+        assert_eq!((date.to_string(), open, next), ("2026-10-17".to_string(), at("2026-10-16T17:00:00Z"), at("2026-10-17T17:00:00Z")));
+
+        // sesi FX New York 17:00 -05:00: tanggal = hari sesi dibuka
+        let ny = TradingDayCfg { tz: parse_offset("-0500").unwrap(), start: NaiveTime::from_hms_opt(17, 0, 0).unwrap(), file: None };
+        let (date, open, next) = ny.day_at(at("2026-10-16T23:00:00Z"));
+        assert_eq!((date.to_string(), open, next), ("2026-10-16".to_string(), at("2026-10-16T22:00:00Z"), at("2026-10-17T22:00:00Z")));
+        let (date, ..) = ny.day_at(at("2026-10-16T21:59:00Z"));
+        assert_eq!(date.to_string(), "2026-10-15");
+
+        assert_eq!(parse_offset("UTC"), FixedOffset::east_opt(0));
+        assert_eq!(parse_offset("+7"), FixedOffset::east_opt(7 * 3600));
+        assert!(parse_offset("07:00").is_none() && parse_offset("+25:00").is_none());
+
+        let open = DayOpen { realized: 100.0, unrealized: -20.0 };
+        assert_eq!(open.pnl(150.0, 10.0), (80.0, 50.0));
+    }
+}
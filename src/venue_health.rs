@@ -0,0 +1,159 @@
+// ===============================
+// src/venue_health.rs
+// ===============================
+//
+// Deteksi gangguan venue & safe-mode otomatis.
+//
+// Gateway melaporkan kejadian (reject, REST 5xx, error clock -1021, aktivitas WS)
+// ke registry bersama. Task monitor mengevaluasi tiap detik:
+// - Jumlah kejadian buruk dalam window > ambang -> SAFE MODE:
+//     * kirim VenueCmd::CancelAll ke gateway venue tsb
+//     * router berhenti merutekan ke venue tsb (is_routable = false)
+//     * log ALERT + metrics
+// - WS pernah hidup lalu diam > ws_silence_secs -> SAFE MODE
+// - Resume otomatis setelah `resume_secs` tanpa kejadian buruk.
+//
+use ahash::AHashMap as HashMap;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tracing::{error, info};
+
+use crate::domain::VenueCmd;
+use crate::metrics::{VENUE_SAFE_MODE, VENUE_SAFE_MODE_ENTRIES};
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct HealthCfg {
+    pub window_secs: u64,
+    pub max_rejects: usize,
+    pub max_http_5xx: usize,
+    pub max_clock_errors: usize,
+    pub ws_silence_secs: u64,
+    pub resume_secs: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bad {
+    Reject,
+    Http5xx,
+    Clock,
+}
+
+#[derive(Debug, Default)]
+struct VenueState {
+    bad: VecDeque<(Instant, Bad)>,
+    last_bad: Option<Instant>,
+    ws_last_seen: Option<Instant>,
+    safe_since: Option<Instant>,
+}
+
+/// Registry kesehatan venue (clone murah, dibagi gateway/router/monitor)
+#[derive(Clone, Default)]
+pub struct VenueHealth {
+    inner: Arc<Mutex<HashMap<String, VenueState>>>,
+}
+
+impl VenueHealth {
+    #[cfg(feature = "binance")]
+    fn push(&self, venue: &str, kind: Bad) {
+        let now = Instant::now();
+        let mut g = self.inner.lock().unwrap();
+        let st = g.entry(venue.to_string()).or_default();
+        st.bad.push_back((now, kind));
+        st.last_bad = Some(now);
+    }
+
+    #[cfg(feature = "binance")]
+    pub fn report_reject(&self, venue: &str) { self.push(venue, Bad::Reject); }
+    #[cfg(feature = "binance")]
+    pub fn report_http_5xx(&self, venue: &str) { self.push(venue, Bad::Http5xx); }
+    #[cfg(feature = "binance")]
+    pub fn report_clock_error(&self, venue: &str) { self.push(venue, Bad::Clock); }
+
+    /// Tandai WS venue masih hidup (event/ping diterima)
+    #[cfg(feature = "binance")]
+    pub fn report_ws_alive(&self, venue: &str) {
+        let mut g = self.inner.lock().unwrap();
+        g.entry(venue.to_string()).or_default().ws_last_seen = Some(Instant::now());
+    }
+
+    /// false jika venue sedang safe mode
+    pub fn is_routable(&self, venue: &str) -> bool {
+        let g = self.inner.lock().unwrap();
+        g.get(venue).is_none_or(|st| st.safe_since.is_none())
+    }
+
+    /// Evaluasi semua venue; kembalikan transisi (venue, Some(reason)=masuk / None=resume)
+    fn evaluate(&self, cfg: &HealthCfg) -> Vec<(String, Option<&'static str>)> {
+        let now = Instant::now();
+        let window = Duration::from_secs(cfg.window_secs);
+        let mut out = Vec::new();
+        let mut g = self.inner.lock().unwrap();
+        for (venue, st) in g.iter_mut() {
+            while st.bad.front().is_some_and(|(t, _)| now.duration_since(*t) > window) {
+                st.bad.pop_front();
+            }
+            let count = |k: Bad| st.bad.iter().filter(|(_, b)| *b == k).count();
+            let ws_silent = st
+                .ws_last_seen
+                .is_some_and(|t| now.duration_since(t) > Duration::from_secs(cfg.ws_silence_secs));
+
+            let reason = if count(Bad::Reject) > cfg.max_rejects {
+                Some("rejects")
+            } else if count(Bad::Http5xx) > cfg.max_http_5xx {
+                Some("http_5xx")
+            } else if count(Bad::Clock) > cfg.max_clock_errors {
+                Some("clock")
+            } else if ws_silent {
+                Some("ws_silence")
+            } else {
+                None
+            };
+
+            match (st.safe_since, reason) {
+                (None, Some(r)) => {
+                    st.safe_since = Some(now);
+                    out.push((venue.clone(), Some(r)));
+                }
+                (Some(_), None) => {
+                    let healthy_for = st.last_bad.map(|t| now.duration_since(t)).unwrap_or(Duration::MAX);
+                    if healthy_for >= Duration::from_secs(cfg.resume_secs) {
+                        st.safe_since = None;
+                        out.push((venue.clone(), None));
+                    }
+                }
+                _ => {}
+            }
+        }
+        out
+    }
+}
+
+/// Task monitor: evaluasi tiap detik, kirim CancelAll saat masuk safe mode
+pub async fn run(
+    health: VenueHealth,
+    cfg: HealthCfg,
+    venue_cmd_txs: HashMap<String, mpsc::Sender<VenueCmd>>,
+) {
+    let mut tick = tokio::time::interval(Duration::from_secs(1));
+    loop {
+        tick.tick().await;
+        for (venue, transition) in health.evaluate(&cfg) {
+            match transition {
+                Some(reason) => {
+                    error!(%venue, %reason, "ALERT venue entering SAFE MODE: cancel orders, stop routing");
+                    VENUE_SAFE_MODE.with_label_values(&[&venue]).set(1);
+                    VENUE_SAFE_MODE_ENTRIES.with_label_values(&[&venue, reason]).inc();
+                    if let Some(tx) = venue_cmd_txs.get(&venue) {
+                        let _ = tx.send(VenueCmd::CancelAll { reason: format!("safe_mode:{reason}") }).await;
+                    }
+                }
+                None => {
+                    info!(%venue, resume_secs = cfg.resume_secs, "venue healthy again, leaving safe mode");
+                    VENUE_SAFE_MODE.with_label_values(&[&venue]).set(0);
+                }
+            }
+        }
+    }
+}
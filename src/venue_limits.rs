@@ -0,0 +1,101 @@
+// ===============================
+// src/venue_limits.rs
+// ===============================
+//
+// Batas posisi per venue (per symbol): satu venue tidak boleh menumpuk inventory besar
+// walau eksposur total masih di bawah limit.
+//
+// Posisi venue = posisi bersih dari fill + order child yang masih working di venue tsb
+// (sisi terburuk: long jika semua buy terisi, short jika semua sell terisi).
+// - risk   : tolak Signal jika qty melebihi total headroom semua venue
+// - router : venue yang sudah di cap dikeluarkan dari scoring, share child dipotong ke headroom
+//            (sisa dialihkan ke venue lain yang masih punya ruang)
+//
+// ENV: VENUE_MAX_POSITION=A:100,binance:20   (lot, |posisi| per symbol; venue tanpa entri = tanpa batas)
+//
+use ahash::AHashMap as HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::domain::{ExecReport, ExecStatus, FillDeltas, Order, Side};
+
+#[derive(Default)]
+struct Inner {
+    net: HashMap<(String, String), i64>, // (venue, symbol) -> posisi bersih
+    working: HashMap<String, (String, String, i64)>, // child cl_id -> (venue, symbol, sisa qty bertanda)
+    deltas: FillDeltas,
+}
+
+/// Posisi per venue (clone murah; diupdate fan-out ExecReport, dibaca risk & router)
+#[derive(Clone, Default)]
+pub struct VenuePositions {
+    caps: Arc<HashMap<String, i64>>,
+    venues: Arc<Vec<String>>,
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl VenuePositions {
+    pub fn new(caps: HashMap<String, i64>, venues: Vec<String>) -> Self {
+        if !caps.is_empty() {
+            tracing::info!(?caps, "venue position limits");
+        }
+        Self { caps: Arc::new(caps), venues: Arc::new(venues), inner: Arc::default() }
+    }
+
+    /// Posisi awal (warm start), per (venue, symbol)
+    pub fn seed(&self, venue: &str, symbol: &str, qty: i64) {
+        self.inner.lock().unwrap().net.insert((venue.to_string(), symbol.to_string()), qty);
+    }
+
+    /// Sisa ruang (lot) untuk order `side` di venue; None = venue tanpa batas
+    pub fn headroom(&self, venue: &str, symbol: &str, side: Side) -> Option<i64> {
+        let cap = *self.caps.get(venue)?;
+        let g = self.inner.lock().unwrap();
+        let net = g.net.get(&(venue.to_string(), symbol.to_string())).copied().unwrap_or(0);
+        let (buys, sells) = g
+            .working
+            .values()
+            .filter(|(v, s, _)| v == venue && s == symbol)
+            .fold((0, 0), |(b, s), (_, _, q)| if *q > 0 { (b + q, s) } else { (b, s - q) });
+        let room = match side {
+            Side::Buy => cap - (net + buys),
+            Side::Sell => cap + (net - sells),
+        };
+        Some(room.max(0))
+    }
+
+    /// Total headroom semua venue; None = ada venue tanpa batas
+    pub fn total_headroom(&self, symbol: &str, side: Side) -> Option<i64> {
+        self.venues.iter().map(|v| self.headroom(v, symbol, side)).sum()
+    }
+
+    /// Catat child yang dikirim router (working sampai terminal)
+    pub fn reserve(&self, venue: &str, child: &Order) {
+        if !self.caps.contains_key(venue) {
+            return;
+        }
+        let entry = (venue.to_string(), child.symbol.clone(), child.side.sign() * child.qty);
+        self.inner.lock().unwrap().working.insert(child.cl_id.clone(), entry);
+    }
+
+    pub fn on_report(&self, er: &ExecReport) {
+        let mut g = self.inner.lock().unwrap();
+        // order yang tidak lewat router (cancel/replace OMS): catat saat ACK
+        if let (ExecStatus::Ack, Some(side)) = (&er.status, er.side) {
+            if self.caps.contains_key(&er.venue) && !g.working.contains_key(&er.cl_id) {
+                let entry = (er.venue.clone(), er.symbol.clone(), side.sign() * er.order_qty);
+                g.working.insert(er.cl_id.clone(), entry);
+            }
+        }
+        if let Some((qty, _)) = g.deltas.on_report(er) {
+            let signed = er.side.unwrap_or(Side::Buy).sign() * qty;
+            let venue = if er.venue.is_empty() { "?" } else { er.venue.as_str() };
+            *g.net.entry((venue.to_string(), er.symbol.clone())).or_default() += signed;
+            if let Some(w) = g.working.get_mut(&er.cl_id) {
+                w.2 -= signed;
+            }
+        }
+        if !matches!(er.status, ExecStatus::Ack | ExecStatus::PartialFill) {
+            g.working.remove(&er.cl_id);
+        }
+    }
+}
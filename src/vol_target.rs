@@ -0,0 +1,198 @@
+// ===============================
+// src/vol_target.rs
+// ===============================
+//
+// Volatility targeting: skalakan ukuran order SEMUA strategi agar estimasi volatilitas portofolio
+// tetap dekat VOL_TARGET_PCT (tahunan, relatif terhadap VOL_TARGET_EQUITY).
+//
+// Estimator:
+// - mid tiap symbol di-sample tiap VOL_SAMPLE_SECS; return log antar sample masuk kovarians EWMA
+//   (RiskMetrics, lambda VOL_EWMA_LAMBDA), jadi korelasi antar symbol ikut dihitung.
+// - eksposur = posisi total per symbol (PositionFeed) × mid, dalam USD (fx.rs).
+// - vol portofolio = sqrt(wᵀ Σ w) / equity × sqrt(sample per tahun).
+//
+// Tiap VOL_RECOMPUTE_SECS (setelah VOL_WARMUP_SAMPLES sample):
+//   scale = clamp(VOL_TARGET_PCT / vol portofolio, VOL_MIN_SCALE, VOL_MAX_SCALE)
+// dipasang ke `sizing::set_vol_scale` dan dikalikan ke scale allocator di risk. Portofolio flat
+// (vol 0) -> scale terakhir dipertahankan.
+//
+// ENV:
+//   VOL_TARGET_PCT=0            (mis. 10 = 10% tahunan; 0 = nonaktif)
+//   VOL_TARGET_EQUITY=10000     (USD)
+//   VOL_SAMPLE_SECS=60
+//   VOL_EWMA_LAMBDA=0.94
+//   VOL_RECOMPUTE_SECS=300
+//   VOL_WARMUP_SAMPLES=30
+//   VOL_MIN_SCALE=0.1
+//   VOL_MAX_SCALE=2.0
+//
+use ahash::AHashMap as HashMap;
+use tokio::sync::broadcast;
+use tokio::time::{interval, Duration, Instant, MissedTickBehavior};
+use tracing::info;
+
+use crate::domain::MdTick;
+use crate::metrics::{PORTFOLIO_VOL, VOL_TARGET_SCALE};
+use crate::positions::PositionFeed;
+use crate::sizing;
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct VolTargetCfg {
+    pub target_pct: f64,
+    pub equity: f64,
+    pub sample_secs: u64,
+    pub lambda: f64,
+    pub recompute_secs: u64,
+    pub warmup_samples: u32,
+    pub min_scale: f64,
+    pub max_scale: f64,
+}
+
+impl VolTargetCfg {
+    pub fn from_env() -> Self {
+        let f = |k: &str, d: f64| std::env::var(k).ok().and_then(|s| s.parse().ok()).unwrap_or(d);
+        Self {
+            target_pct: f("VOL_TARGET_PCT", 0.0),
+            equity: f("VOL_TARGET_EQUITY", 10_000.0),
+            sample_secs: f("VOL_SAMPLE_SECS", 60.0) as u64,
+            lambda: f("VOL_EWMA_LAMBDA", 0.94),
+            recompute_secs: f("VOL_RECOMPUTE_SECS", 300.0) as u64,
+            warmup_samples: f("VOL_WARMUP_SAMPLES", 30.0) as u32,
+            min_scale: f("VOL_MIN_SCALE", 0.1),
+            max_scale: f("VOL_MAX_SCALE", 2.0),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.target_pct > 0.0 && self.equity > 0.0
+    }
+}
+
+/// Kovarians EWMA return log antar symbol
+#[derive(Debug, Default)]
+struct CovEstimator {
+    lambda: f64,
+    symbols: Vec<String>,
+    last_mid: HashMap<String, f64>,
+    cov: HashMap<(usize, usize), f64>,
+    samples: u32,
+}
+
+impl CovEstimator {
+    fn new(lambda: f64) -> Self {
+        Self { lambda, ..Default::default() }
+    }
+
+    fn idx(&mut self, symbol: &str) -> usize {
+        match self.symbols.iter().position(|s| s == symbol) {
+            Some(i) => i,
+            None => {
+                self.symbols.push(symbol.to_string());
+                self.symbols.len() - 1
+            }
+        }
+    }
+
+    /// Satu sample mid semua symbol
+    fn sample(&mut self, mids: &HashMap<String, f64>) {
+        let mut rets: Vec<(usize, f64)> = Vec::new();
+        for (sym, &mid) in mids.iter().filter(|(_, m)| **m > 0.0) {
+            if let Some(prev) = self.last_mid.insert(sym.clone(), mid) {
+                let i = self.idx(sym);
+                rets.push((i, (mid / prev).ln()));
+            }
+        }
+        if rets.is_empty() {
+            return;
+        }
+        for &(i, ri) in &rets {
+            for &(j, rj) in &rets {
+                let c = self.cov.entry((i, j)).or_insert(ri * rj);
+                *c = self.lambda * *c + (1.0 - self.lambda) * ri * rj;
+            }
+        }
+        self.samples += 1;
+    }
+
+    /// Deviasi standar PnL portofolio per sample (USD) untuk eksposur `w` (symbol -> USD)
+    fn portfolio_std(&self, w: &HashMap<String, f64>) -> f64 {
+        let mut var = 0.0;
+        for (i, si) in self.symbols.iter().enumerate() {
+            for (j, sj) in self.symbols.iter().enumerate() {
+                let (wi, wj) = (w.get(si).copied().unwrap_or(0.0), w.get(sj).copied().unwrap_or(0.0));
+                var += wi * wj * self.cov.get(&(i, j)).copied().unwrap_or(0.0);
+            }
+        }
+        var.max(0.0).sqrt()
+    }
+}
+
+/// Scale dari vol portofolio tahunan (fraksi); None jika portofolio flat
+fn scale_for(cfg: &VolTargetCfg, vol: f64) -> Option<f64> {
+    (vol > 0.0).then(|| (cfg.target_pct / 100.0 / vol).clamp(cfg.min_scale, cfg.max_scale))
+}
+
+/// Task: sample mid, estimasi vol portofolio, set `sizing::set_vol_scale` berkala
+pub async fn run(cfg: VolTargetCfg, mut md_rx: broadcast::Receiver<MdTick>, positions: PositionFeed) {
+    let mut est = CovEstimator::new(cfg.lambda);
+    let mut mids: HashMap<String, f64> = HashMap::new();
+    let mut sample = interval(Duration::from_secs(cfg.sample_secs.max(1)));
+    sample.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let annualize = (365.0 * 86_400.0 / cfg.sample_secs.max(1) as f64).sqrt();
+    let mut last_recompute = Instant::now();
+    loop {
+        tokio::select! {
+            r = md_rx.recv() => match r {
+                Ok(md) => {
+                    mids.insert(md.symbol.clone(), (md.best_bid + md.best_ask) as f64 / 200.0);
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => return,
+            },
+            _ = sample.tick() => {
+                est.sample(&mids);
+                if est.samples < cfg.warmup_samples || last_recompute.elapsed() < Duration::from_secs(cfg.recompute_secs) {
+                    continue;
+                }
+                last_recompute = Instant::now();
+                let exposure: HashMap<String, f64> = positions
+                    .all()
+                    .iter()
+                    .filter_map(|s| {
+                        let mid = *mids.get(&s.symbol)?;
+                        Some((s.symbol.clone(), crate::fx::to_usd(&s.symbol, sizing::base_qty(&s.symbol, s.state.total_qty) * mid)))
+                    })
+                    .collect();
+                let vol = est.portfolio_std(&exposure) / cfg.equity * annualize;
+                PORTFOLIO_VOL.set(vol);
+                if let Some(scale) = scale_for(&cfg, vol) {
+                    info!(vol_pct = format!("{:.2}", vol * 100.0), target_pct = cfg.target_pct, scale = format!("{scale:.3}"), "vol target: size scale");
+                    sizing::set_vol_scale(scale);
+                    VOL_TARGET_SCALE.set(scale);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scales_down_when_portfolio_vol_above_target() {
+        let mut est = CovEstimator::new(0.0);
+        // BTC +1% / -1% bergantian; lambda 0 -> kovarians = sample terakhir
+        for mid in [100.0, 101.0, 100.0] {
+            est.sample(&[("BTCUSDT".to_string(), mid)].into_iter().collect());
+        }
+        let r = (100.0f64 / 101.0).ln();
+        let w: HashMap<String, f64> = [("BTCUSDT".to_string(), 5_000.0)].into_iter().collect();
+        assert!((est.portfolio_std(&w) - 5_000.0 * r.abs()).abs() < 1e-9);
+
+        let cfg = VolTargetCfg { target_pct: 10.0, min_scale: 0.1, max_scale: 2.0, ..VolTargetCfg::from_env() };
+        assert_eq!(scale_for(&cfg, 0.2), Some(0.5));
+        assert_eq!(scale_for(&cfg, 0.01), Some(2.0));
+        assert_eq!(scale_for(&cfg, 0.0), None);
+    }
+}
@@ -0,0 +1,191 @@
+// ===============================
+// src/webhook.rs
+// ===============================
+//
+// Signal manual dari webhook (alert TradingView & sejenisnya), di-serve server metrics:
+//   POST /webhook/tradingview     body JSON (pesan alert)
+//
+// Payload (field selain secret/symbol/side/price opsional):
+//   {"secret":"<WEBHOOK_SECRET>","symbol":"{{ticker}}","side":"{{strategy.order.action}}","price":{{close}},
+//    "qty":10,"notional":50,"strategy":"tv_breakout","book":"alpha","tags":["tv"]}
+//
+// - secret wajib cocok dengan WEBHOOK_SECRET (TradingView tidak bisa set header -> secret di body,
+//   atau `?secret=` di URL); tanpa WEBHOOK_SECRET endpoint nonaktif (404)
+// - symbol "BINANCE:BTCUSDT" -> "BTCUSDT"; side buy/sell/long/short
+// - price dalam quote (desimal), dikonversi ke tick ×100
+// - qty (lot) atau notional (quote, dihitung risk); tanpa keduanya -> WEBHOOK_DEFAULT_QTY
+// - tag internal engine (`force_exit`, `portfolio_stop`) ditolak: Signal bertag itu melewati gate risk
+// Signal masuk ke bus Signal yang sama dengan strategi -> lewat risk + router seperti biasa.
+//
+// ENV:
+//   WEBHOOK_SECRET=change-me
+//   WEBHOOK_DEFAULT_QTY=10
+//   WEBHOOK_STRATEGY=webhook   (nama strategi default untuk atribusi)
+//
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::admin::{parse_query, Response};
+use crate::clock;
+use crate::domain::{Side, Signal};
+use crate::holding::is_reserved_tag;
+use crate::metrics::WEBHOOK_SIGNALS;
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct WebhookCfg {
+    #[serde(serialize_with = "crate::effective_config::secret")]
+    pub secret: String,
+    pub default_qty: i64,
+    pub strategy: String,
+}
+
+impl WebhookCfg {
+    pub fn from_env() -> Self {
+        Self {
+            secret: std::env::var("WEBHOOK_SECRET").unwrap_or_default(),
+            default_qty: std::env::var("WEBHOOK_DEFAULT_QTY").ok().and_then(|s| s.parse().ok()).unwrap_or(10),
+            strategy: std::env::var("WEBHOOK_STRATEGY").unwrap_or_else(|_| "webhook".to_string()),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        !self.secret.is_empty()
+    }
+}
+
+#[derive(Deserialize)]
+struct Alert {
+    #[serde(default)]
+    secret: String,
+    symbol: String,
+    side: String,
+    price: f64,
+    #[serde(default)]
+    qty: Option<i64>,
+    #[serde(default)]
+    notional: Option<f64>,
+    #[serde(default)]
+    strategy: Option<String>,
+    #[serde(default)]
+    book: String,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// Handle webhook (dipegang AdminState; kirim Signal ke bus risk)
+#[derive(Clone)]
+pub struct Webhook {
+    cfg: WebhookCfg,
+    sig_tx: mpsc::Sender<Signal>,
+}
+
+/// Bandingkan secret tanpa short-circuit (waktu tidak bocor posisi byte yang beda)
+fn secret_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn reject(status: &'static str, result: &str, msg: &str) -> Response {
+    WEBHOOK_SIGNALS.with_label_values(&[result]).inc();
+    Response::text(status, &format!("{msg}\n"))
+}
+
+impl Webhook {
+    pub fn new(cfg: WebhookCfg, sig_tx: mpsc::Sender<Signal>) -> Self {
+        info!(strategy = %cfg.strategy, "webhook signals enabled (POST /webhook/tradingview)");
+        Self { cfg, sig_tx }
+    }
+
+    fn to_signal(&self, a: Alert) -> Result<Signal, String> {
+        let side = match a.side.trim().to_ascii_lowercase().as_str() {
+            "buy" | "long" => Side::Buy,
+            "sell" | "short" => Side::Sell,
+            other => return Err(format!("unknown side '{other}'")),
+        };
+        // "BINANCE:BTCUSDT" / "BTCUSDT.P" -> "BTCUSDT"
+        let symbol = a.symbol.rsplit(':').next().unwrap_or_default();
+        let symbol = symbol.split('.').next().unwrap_or_default().trim().to_ascii_uppercase();
+        if symbol.is_empty() {
+            return Err("missing symbol".to_string());
+        }
+        if !(a.price.is_finite() && a.price > 0.0) {
+            return Err("invalid price".to_string());
+        }
+        let qty = a.qty.unwrap_or(self.cfg.default_qty);
+        if qty <= 0 && a.notional.is_none() {
+            return Err("invalid qty".to_string());
+        }
+        if let Some(t) = a.tags.iter().find(|t| is_reserved_tag(t)) {
+            return Err(format!("reserved tag '{t}'"));
+        }
+        Ok(Signal {
+            ts_ns: clock::now_ns(),
+            symbol,
+            side,
+            px: (a.price * 100.0).round() as i64,
+            qty,
+            strategy: a.strategy.filter(|s| !s.is_empty()).unwrap_or_else(|| self.cfg.strategy.clone()),
+            tags: a.tags,
+            book: a.book,
+            quote_notional: a.notional.filter(|n| *n > 0.0),
+        })
+    }
+
+    /// POST /webhook/tradingview (dipanggil thread server metrics)
+    pub fn handle(&self, method: &str, query: &str, body: &[u8]) -> Response {
+        if method != "POST" {
+            return Response::text("405 Method Not Allowed", "POST only\n");
+        }
+        let alert: Alert = match serde_json::from_slice(body) {
+            Ok(a) => a,
+            Err(e) => return reject("400 Bad Request", "invalid", &format!("invalid payload: {e}")),
+        };
+        let params = parse_query(query);
+        let secret = if alert.secret.is_empty() { params.get("secret").map(String::as_str).unwrap_or("") } else { &alert.secret };
+        if !secret_eq(secret, &self.cfg.secret) {
+            warn!("webhook: bad secret");
+            return reject("401 Unauthorized", "unauthorized", "bad secret");
+        }
+        let sig = match self.to_signal(alert) {
+            Ok(s) => s,
+            Err(e) => return reject("400 Bad Request", "invalid", &e),
+        };
+        info!(symbol = %sig.symbol, side = sig.side.as_str(), px = sig.px, qty = sig.qty, strategy = %sig.strategy, "webhook signal");
+        match self.sig_tx.try_send(sig) {
+            Ok(()) => {
+                WEBHOOK_SIGNALS.with_label_values(&["accepted"]).inc();
+                Response::json("{\"accepted\":true}".to_string())
+            }
+            Err(_) => reject("503 Service Unavailable", "queue_full", "signal queue full"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reserved_tags_rejected() {
+        let cfg = WebhookCfg { secret: "s".into(), default_qty: 10, strategy: "webhook".into() };
+        let hook = Webhook { cfg, sig_tx: mpsc::channel(1).0 };
+        let alert = |tags: &[&str]| Alert {
+            secret: "s".into(),
+            symbol: "BINANCE:BTCUSDT".into(),
+            side: "buy".into(),
+            price: 100.5,
+            qty: None,
+            notional: None,
+            strategy: None,
+            book: String::new(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+        };
+        let sig = hook.to_signal(alert(&["tv"])).unwrap();
+        assert_eq!((sig.symbol.as_str(), sig.px, sig.qty, sig.tags), ("BTCUSDT", 10_050, 10, vec!["tv".to_string()]));
+        // force_exit / portfolio_stop akan melewati portfolio stop, sizing & cluster guard di risk
+        for tag in ["force_exit", "portfolio_stop", " FORCE_EXIT "] {
+            let err = hook.to_signal(alert(&["tv", tag])).unwrap_err();
+            assert!(err.starts_with("reserved tag"), "{tag}: {err}");
+        }
+    }
+}